@@ -0,0 +1,328 @@
+//! Tune body scanning for ABC notation.
+//!
+//! The scanner turns each body line into a flat list of [`BodyItem`]s
+//! (notes, rests, bar lines, and inline fields). Lengths are kept as
+//! multiples of the unit note length; the caller resolves them against
+//! `L:` and the key signature.
+
+use super::error::{AbcError, AbcResult};
+use crate::lang::ast::{PitchAlter, PitchStep};
+
+/// A note length as a fraction `(numerator, denominator)` of the unit length.
+pub(super) type Length = (u32, u32);
+
+/// Bar line kinds recognized in the tune body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum BarKind {
+    /// `|`
+    Single,
+    /// `||` or `[|`
+    Double,
+    /// `|]`
+    Final,
+    /// `|:`
+    RepeatStart,
+    /// `:|`
+    RepeatEnd,
+    /// `::` or `:|:`
+    RepeatBoth,
+}
+
+/// A single element of the tune body.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum BodyItem {
+    /// A pitched note.
+    Note {
+        /// Letter name
+        step: PitchStep,
+        /// Explicit accidental written before the note
+        accidental: Option<PitchAlter>,
+        /// Octave (uppercase letters are octave 4)
+        octave: u8,
+        /// Length in unit note lengths
+        length: Length,
+        /// Whether a `-` tie follows the note
+        tie: bool,
+        /// Source line (1-indexed)
+        line: usize,
+    },
+    /// A rest (`z` or invisible `x`).
+    Rest {
+        /// Length in unit note lengths
+        length: Length,
+        /// Source line (1-indexed)
+        line: usize,
+    },
+    /// A bar line.
+    Bar(BarKind),
+    /// A field line or inline field such as `[K:D]`.
+    Field(char, String),
+}
+
+/// Scan one body line (without comments) and append its items.
+pub(super) fn scan_line(text: &str, line: usize, items: &mut Vec<BodyItem>) -> AbcResult<()> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+    // Multiplier for the next note or rest after a broken rhythm
+    let mut pending_broken: Option<Length> = None;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            ' ' | '\t' | '`' | '\\' | '(' | ')' => i += 1,
+            '"' | '!' | '+' | '{' => {
+                // Annotations, decorations, and grace notes are skipped
+                let close = if c == '{' { '}' } else { c };
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == close)
+                    .ok_or_else(|| syntax(line, format!("unclosed '{}'", c)))?;
+                i += end + 2;
+            }
+            '~' | '.' | 'H' | 'L' | 'M' | 'O' | 'P' | 'S' | 'T' | 'u' | 'v' => i += 1,
+            '|' | ':' => {
+                let (kind, next) = scan_bar(&chars, i, line)?;
+                items.push(BodyItem::Bar(kind));
+                i = skip_ending_number(&chars, next);
+            }
+            '[' => match chars.get(i + 1) {
+                Some('|') => {
+                    items.push(BodyItem::Bar(BarKind::Double));
+                    i += 2;
+                }
+                Some(d) if d.is_ascii_digit() => i = skip_ending_number(&chars, i + 1),
+                Some(f) if f.is_ascii_alphabetic() && chars.get(i + 2) == Some(&':') => {
+                    let end = chars[i..]
+                        .iter()
+                        .position(|&ch| ch == ']')
+                        .ok_or_else(|| syntax(line, "unclosed inline field".to_string()))?;
+                    let value: String = chars[i + 3..i + end].iter().collect();
+                    items.push(BodyItem::Field(*f, value.trim().to_string()));
+                    i += end + 1;
+                }
+                _ => {
+                    return Err(AbcError::Unsupported {
+                        line,
+                        feature: "chords",
+                    });
+                }
+            },
+            '>' | '<' => {
+                let level = chars[i..].iter().take_while(|&&ch| ch == c).count();
+                let (longer, shorter) = broken_factors(level);
+                let (first, second) = if c == '>' {
+                    (longer, shorter)
+                } else {
+                    (shorter, longer)
+                };
+                match items.last_mut() {
+                    Some(BodyItem::Note { length, .. }) | Some(BodyItem::Rest { length, .. }) => {
+                        *length = multiply(*length, first);
+                    }
+                    _ => return Err(syntax(line, format!("'{}' must follow a note", c))),
+                }
+                pending_broken = Some(second);
+                i += level;
+            }
+            '^' | '_' | '=' | 'A'..='G' | 'a'..='g' => {
+                let (mut item, next) = scan_note(&chars, i, line)?;
+                if let (Some(factor), BodyItem::Note { length, .. }) =
+                    (pending_broken.take(), &mut item)
+                {
+                    *length = multiply(*length, factor);
+                }
+                items.push(item);
+                i = next;
+            }
+            'z' | 'x' => {
+                let (mut length, next) = scan_length(&chars, i + 1, line)?;
+                if let Some(factor) = pending_broken.take() {
+                    length = multiply(length, factor);
+                }
+                items.push(BodyItem::Rest { length, line });
+                i = next;
+            }
+            '&' => {
+                return Err(AbcError::Unsupported {
+                    line,
+                    feature: "voice overlays",
+                });
+            }
+            _ => return Err(syntax(line, format!("unexpected character '{}'", c))),
+        }
+
+        // A tuplet marker is `(` immediately followed by a digit
+        if c == '(' && chars.get(i).is_some_and(|ch| ch.is_ascii_digit()) {
+            return Err(AbcError::Unsupported {
+                line,
+                feature: "tuplets",
+            });
+        }
+    }
+
+    if pending_broken.is_some() {
+        return Err(syntax(line, "broken rhythm at end of line".to_string()));
+    }
+    Ok(())
+}
+
+/// Scan a bar line starting at `start`, returning its kind and the next index.
+fn scan_bar(chars: &[char], start: usize, line: usize) -> AbcResult<(BarKind, usize)> {
+    let end = start
+        + chars[start..]
+            .iter()
+            .take_while(|&&ch| matches!(ch, '|' | ':' | ']'))
+            .count();
+    let token: String = chars[start..end].iter().collect();
+
+    let kind = match token.as_str() {
+        "|" => BarKind::Single,
+        "||" => BarKind::Double,
+        "|]" | "||]" => BarKind::Final,
+        "|:" | "||:" => BarKind::RepeatStart,
+        ":|" | ":||" | ":|]" => BarKind::RepeatEnd,
+        "::" | ":|:" | ":||:" => BarKind::RepeatBoth,
+        _ => return Err(syntax(line, format!("unrecognized bar line '{}'", token))),
+    };
+    Ok((kind, end))
+}
+
+/// Skip a volta number such as `1`, `1,3`, or `1-2` after a bar line.
+fn skip_ending_number(chars: &[char], start: usize) -> usize {
+    start
+        + chars[start..]
+            .iter()
+            .take_while(|ch| ch.is_ascii_digit() || matches!(ch, ',' | '-'))
+            .count()
+}
+
+/// Scan a note (accidental, letter, octave marks, length, tie).
+fn scan_note(chars: &[char], start: usize, line: usize) -> AbcResult<(BodyItem, usize)> {
+    let mut i = start;
+
+    let accidental = match (chars[i], chars.get(i + 1)) {
+        ('^', Some('^')) => Some(PitchAlter::DoubleSharp),
+        ('_', Some('_')) => Some(PitchAlter::DoubleFlat),
+        ('^', _) => Some(PitchAlter::Sharp),
+        ('_', _) => Some(PitchAlter::Flat),
+        ('=', _) => Some(PitchAlter::Natural),
+        _ => None,
+    };
+    i += match accidental {
+        Some(PitchAlter::DoubleSharp | PitchAlter::DoubleFlat) => 2,
+        Some(_) => 1,
+        None => 0,
+    };
+
+    let letter = *chars
+        .get(i)
+        .ok_or_else(|| syntax(line, "accidental without a note".to_string()))?;
+    let step = match letter.to_ascii_uppercase() {
+        'C' => PitchStep::C,
+        'D' => PitchStep::D,
+        'E' => PitchStep::E,
+        'F' => PitchStep::F,
+        'G' => PitchStep::G,
+        'A' => PitchStep::A,
+        'B' => PitchStep::B,
+        _ => {
+            return Err(syntax(
+                line,
+                format!("expected note letter, got '{}'", letter),
+            ));
+        }
+    };
+    let mut octave: i32 = if letter.is_ascii_lowercase() { 5 } else { 4 };
+    i += 1;
+
+    while let Some(&mark) = chars.get(i) {
+        match mark {
+            ',' => octave -= 1,
+            '\'' => octave += 1,
+            _ => break,
+        }
+        i += 1;
+    }
+    if !(0..=9).contains(&octave) {
+        return Err(syntax(line, format!("octave {} out of range", octave)));
+    }
+
+    let (length, next) = scan_length(chars, i, line)?;
+    i = next;
+
+    let tie = chars.get(i) == Some(&'-');
+    if tie {
+        i += 1;
+    }
+
+    Ok((
+        BodyItem::Note {
+            step,
+            accidental,
+            octave: octave as u8,
+            length,
+            tie,
+            line,
+        },
+        i,
+    ))
+}
+
+/// Scan a length suffix such as `2`, `/2`, `/`, `//`, or `3/2`.
+fn scan_length(chars: &[char], start: usize, line: usize) -> AbcResult<(Length, usize)> {
+    let mut i = start;
+    let (numerator, next) = scan_number(chars, i);
+    i = next;
+    let mut denominator = 1u32;
+
+    while chars.get(i) == Some(&'/') {
+        i += 1;
+        let (value, next) = scan_number(chars, i);
+        i = next;
+        denominator = denominator.saturating_mul(value.unwrap_or(2));
+    }
+
+    let numerator = numerator.unwrap_or(1);
+    if numerator == 0 || denominator == 0 {
+        return Err(syntax(line, "note length must be positive".to_string()));
+    }
+    Ok(((numerator, denominator), i))
+}
+
+/// Scan an optional unsigned decimal number.
+fn scan_number(chars: &[char], start: usize) -> (Option<u32>, usize) {
+    let digits = chars[start..]
+        .iter()
+        .take_while(|ch| ch.is_ascii_digit())
+        .count();
+    if digits == 0 {
+        return (None, start);
+    }
+    let text: String = chars[start..start + digits].iter().collect();
+    (text.parse().ok(), start + digits)
+}
+
+/// Length multipliers for a broken rhythm of the given level (`>` = 1).
+///
+/// Returns `(longer, shorter)`: level 1 is 3/2 and 1/2, level 2 is 7/4 and
+/// 1/4, and so on.
+fn broken_factors(level: usize) -> (Length, Length) {
+    let den = 1u32 << level.min(8);
+    ((2 * den - 1, den), (1, den))
+}
+
+/// Multiply two fractions, reducing the result.
+pub(super) fn multiply(a: Length, b: Length) -> Length {
+    let num = a.0 * b.0;
+    let den = a.1 * b.1;
+    let g = gcd(num, den);
+    (num / g, den / g)
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a.max(1) } else { gcd(b, a % b) }
+}
+
+fn syntax(line: usize, message: String) -> AbcError {
+    AbcError::Syntax { line, message }
+}
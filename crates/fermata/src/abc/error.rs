@@ -0,0 +1,58 @@
+//! Error types for ABC import.
+
+use thiserror::Error;
+
+use crate::lang::error::CompileError;
+
+/// Errors that can occur while importing ABC notation.
+#[derive(Debug, Error)]
+pub enum AbcError {
+    /// A required header field is missing.
+    #[error("Missing header field {0}:")]
+    MissingField(char),
+
+    /// A header field has a value that could not be understood.
+    #[error("Invalid {field}: field '{value}'")]
+    InvalidField {
+        /// The field letter (e.g. 'M', 'L', 'K')
+        field: char,
+        /// The field value as written
+        value: String,
+    },
+
+    /// Malformed tune body syntax.
+    #[error("Line {line}: {message}")]
+    Syntax {
+        /// Line number in the source (1-indexed)
+        line: usize,
+        /// Description of the problem
+        message: String,
+    },
+
+    /// Valid ABC that the importer does not handle yet.
+    #[error("Line {line}: {feature} are not supported")]
+    Unsupported {
+        /// Line number in the source (1-indexed)
+        line: usize,
+        /// The unsupported feature (plural, e.g. "chords")
+        feature: &'static str,
+    },
+
+    /// A note length that has no single notated duration (e.g. 5/8).
+    #[error("Line {line}: length {numerator}/{denominator} of a whole note cannot be notated")]
+    UnsupportedDuration {
+        /// Line number in the source (1-indexed)
+        line: usize,
+        /// Numerator of the length as a fraction of a whole note
+        numerator: u32,
+        /// Denominator of the length as a fraction of a whole note
+        denominator: u32,
+    },
+
+    /// The imported tune failed to compile to IR.
+    #[error(transparent)]
+    Compile(#[from] CompileError),
+}
+
+/// Result type for ABC import.
+pub type AbcResult<T> = Result<T, AbcError>;
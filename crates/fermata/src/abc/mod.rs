@@ -0,0 +1,676 @@
+//! ABC notation import.
+//!
+//! This module reads a single tune in [ABC notation](https://abcnotation.com/)
+//! and builds a Fermata score from it, which is then compiled to IR like any
+//! other Fermata source.
+//!
+//! Supported:
+//! - Header fields `X:` (ignored), `T:`, `C:`, `M:`, `L:`, and `K:` (which
+//!   ends the header); `M:`, `L:`, and `K:` may also change mid-tune
+//! - Notes `A`–`G`/`a`–`g` with `,`/`'` octave marks and `^`, `^^`, `_`,
+//!   `__`, `=` accidentals, applied per bar and on top of the key signature
+//! - Lengths as multipliers and fractions (`A2`, `A/2`, `A/`, `A3/2`),
+//!   relative to `L:` (or the meter-based default when `L:` is absent)
+//! - Broken rhythm (`A>B`, `A<B`, `A>>B`), rests (`z`, `x`), and ties (`A-A`)
+//! - Bar lines, including double, final, and repeat bars
+//!
+//! Annotations, decorations, slurs, grace notes, and volta numbers are
+//! skipped. Chords, tuplets, and voice overlays return
+//! [`AbcError::Unsupported`]. Only the first tune in the source is read.
+//!
+//! # Examples
+//!
+//! ```
+//! let score = fermata::abc::parse("X:1\nT:Scale\nM:4/4\nL:1/4\nK:C\nCDEF|\n").unwrap();
+//! assert_eq!(score.parts[0].measures[0].content.len(), 5);
+//! ```
+
+mod body;
+mod error;
+
+pub use error::{AbcError, AbcResult};
+
+use std::collections::HashMap;
+
+use body::{BarKind, BodyItem, Length, multiply, scan_line};
+
+use crate::ir::ScorePartwise;
+use crate::ir::common::StartStop;
+use crate::lang::ast::{
+    BarlineSpec, ClefSpec, DurationBase, FermataDuration, FermataMeasure, FermataNote, FermataPart,
+    FermataPitch, FermataRest, FermataScore, KeySpec, MeasureElement, Mode, PitchAlter, PitchStep,
+    TimeSpec,
+};
+use crate::lang::attributes::compute_fifths;
+use crate::lang::score::compile_fermata_score;
+
+/// Parse an ABC tune and compile it to IR.
+///
+/// # Errors
+///
+/// Returns [`AbcError`] if the tune is malformed, uses unsupported features,
+/// or contains a length that cannot be notated as a single note.
+pub fn parse(src: &str) -> AbcResult<ScorePartwise> {
+    let score = parse_to_ast(src)?;
+    Ok(compile_fermata_score(&score)?)
+}
+
+/// Parse an ABC tune into a Fermata AST without compiling it.
+///
+/// The tune becomes a single part named "Melody" in treble clef.
+///
+/// # Errors
+///
+/// See [`parse`].
+pub fn parse_to_ast(src: &str) -> AbcResult<FermataScore> {
+    let mut lines = src.lines().enumerate().map(|(i, l)| (i + 1, l));
+
+    // Header: everything up to and including K:
+    let mut title = None;
+    let mut composer = None;
+    let mut meter = None;
+    let mut unit = None;
+    let key = loop {
+        let Some((_, raw)) = lines.next() else {
+            return Err(AbcError::MissingField('K'));
+        };
+        let text = strip_comment(raw);
+        if text.is_empty() {
+            continue;
+        }
+        let Some((field, value)) = field_line(text) else {
+            return Err(AbcError::MissingField('K'));
+        };
+        match field {
+            'T' if title.is_none() => title = Some(value.to_string()),
+            'C' if composer.is_none() => composer = Some(value.to_string()),
+            'M' => meter = parse_meter(value)?,
+            'L' => unit = Some(parse_unit(value)?),
+            'K' => break parse_key(value)?,
+            _ => {}
+        }
+    };
+
+    let mut builder = TuneBuilder::new(key, meter, unit);
+
+    // Body: until a blank line ends the tune
+    for (line, raw) in lines {
+        if raw.trim().is_empty() {
+            break;
+        }
+        let text = strip_comment(raw);
+        if text.is_empty() {
+            continue;
+        }
+        let mut items = Vec::new();
+        match field_line(text) {
+            Some((field, value)) => items.push(BodyItem::Field(field, value.to_string())),
+            None => scan_line(text, line, &mut items)?,
+        }
+        for item in items {
+            builder.push(item)?;
+        }
+    }
+
+    Ok(FermataScore {
+        title,
+        composer,
+        tempo: None,
+        parts: vec![FermataPart {
+            name: "Melody".to_string(),
+            id: None,
+            abbreviation: None,
+            transpose: None,
+            measures: builder.finish(),
+        }],
+    })
+}
+
+/// Accumulates measures while walking the tune body.
+struct TuneBuilder {
+    /// Unit note length as a fraction of a whole note
+    unit: Length,
+    /// Key signature alteration for each step (indexed by [`step_index`])
+    key_alters: [Option<PitchAlter>; 7],
+    /// Accidentals written earlier in the current bar
+    bar_alters: HashMap<(usize, u8), PitchAlter>,
+    /// Whether the previous note started a tie
+    tie_pending: bool,
+    measures: Vec<FermataMeasure>,
+    current: Vec<MeasureElement>,
+}
+
+impl TuneBuilder {
+    fn new(key: KeySpec, meter: Option<TimeSpec>, unit: Option<Length>) -> Self {
+        let unit = unit.unwrap_or_else(|| default_unit(meter.as_ref()));
+        let mut current = vec![MeasureElement::Key(key.clone())];
+        if let Some(time) = meter {
+            current.push(MeasureElement::Time(time));
+        }
+        current.push(MeasureElement::Clef(ClefSpec::Treble));
+
+        Self {
+            unit,
+            key_alters: key_alters(&key),
+            bar_alters: HashMap::new(),
+            tie_pending: false,
+            measures: Vec::new(),
+            current,
+        }
+    }
+
+    fn push(&mut self, item: BodyItem) -> AbcResult<()> {
+        match item {
+            BodyItem::Note {
+                step,
+                accidental,
+                octave,
+                length,
+                tie,
+                line,
+            } => {
+                let index = step_index(step);
+                if let Some(alter) = accidental {
+                    self.bar_alters.insert((index, octave), alter);
+                }
+                let alter = self
+                    .bar_alters
+                    .get(&(index, octave))
+                    .copied()
+                    .or(self.key_alters[index]);
+
+                // A note can carry only one tie; a new start wins over a stop
+                let tie_mark = if tie {
+                    Some(StartStop::Start)
+                } else if self.tie_pending {
+                    Some(StartStop::Stop)
+                } else {
+                    None
+                };
+                self.tie_pending = tie;
+
+                self.current.push(MeasureElement::Note(FermataNote {
+                    pitch: FermataPitch {
+                        step,
+                        alter,
+                        octave,
+                    },
+                    duration: self.duration(length, line)?,
+                    voice: None,
+                    staff: None,
+                    stem: None,
+                    articulations: vec![],
+                    dynamic: None,
+                    ornaments: vec![],
+                    technical: vec![],
+                    tie: tie_mark,
+                    slur: None,
+                    lyrics: vec![],
+                    invisible: false,
+                }));
+            }
+            BodyItem::Rest { length, line } => {
+                self.tie_pending = false;
+                self.current.push(MeasureElement::Rest(FermataRest {
+                    duration: self.duration(length, line)?,
+                    voice: None,
+                    staff: None,
+                    measure_rest: false,
+                    invisible: false,
+                }));
+            }
+            BodyItem::Bar(kind) => match kind {
+                BarKind::Single => self.end_measure(),
+                BarKind::Double => self.end_measure_with(BarlineSpec::Double),
+                BarKind::Final => self.end_measure_with(BarlineSpec::Final),
+                BarKind::RepeatEnd => {
+                    self.end_measure_with(BarlineSpec::RepeatBackward { times: None })
+                }
+                BarKind::RepeatStart => {
+                    self.end_measure();
+                    self.current
+                        .push(MeasureElement::Barline(BarlineSpec::RepeatForward));
+                }
+                BarKind::RepeatBoth => {
+                    self.end_measure_with(BarlineSpec::RepeatBackward { times: None });
+                    self.current
+                        .push(MeasureElement::Barline(BarlineSpec::RepeatForward));
+                }
+            },
+            BodyItem::Field(field, value) => match field {
+                'L' => self.unit = parse_unit(&value)?,
+                'M' => {
+                    if let Some(time) = parse_meter(&value)? {
+                        self.current.push(MeasureElement::Time(time));
+                    }
+                }
+                'K' => {
+                    let key = parse_key(&value)?;
+                    self.key_alters = key_alters(&key);
+                    self.current.push(MeasureElement::Key(key));
+                }
+                _ => {}
+            },
+        }
+        Ok(())
+    }
+
+    /// Convert a length in unit note lengths to a notated duration.
+    fn duration(&self, length: Length, line: usize) -> AbcResult<FermataDuration> {
+        let (numerator, denominator) = multiply(length, self.unit);
+        whole_fraction_to_duration(numerator, denominator).ok_or(AbcError::UnsupportedDuration {
+            line,
+            numerator,
+            denominator,
+        })
+    }
+
+    fn end_measure_with(&mut self, barline: BarlineSpec) {
+        self.current.push(MeasureElement::Barline(barline));
+        self.end_measure();
+    }
+
+    /// Close the current measure if it contains any notes or rests.
+    fn end_measure(&mut self) {
+        let has_music = self
+            .current
+            .iter()
+            .any(|e| matches!(e, MeasureElement::Note(_) | MeasureElement::Rest(_)));
+        if has_music {
+            self.measures.push(FermataMeasure {
+                number: Some(self.measures.len() as u32 + 1),
+                implicit: false,
+                content: std::mem::take(&mut self.current),
+            });
+        }
+        self.bar_alters.clear();
+    }
+
+    fn finish(mut self) -> Vec<FermataMeasure> {
+        self.end_measure();
+        self.measures
+    }
+}
+
+/// Remove a `%` comment from a line and trim it.
+fn strip_comment(line: &str) -> &str {
+    line.split('%').next().unwrap_or("").trim()
+}
+
+/// Split a `X:value` field line into its letter and trimmed value.
+fn field_line(text: &str) -> Option<(char, &str)> {
+    let mut chars = text.chars();
+    let field = chars.next()?;
+    (field.is_ascii_alphabetic() && chars.next() == Some(':')).then(|| (field, text[2..].trim()))
+}
+
+/// Parse an `M:` value. `M:none` (free meter) yields `None`.
+fn parse_meter(value: &str) -> AbcResult<Option<TimeSpec>> {
+    let invalid = || AbcError::InvalidField {
+        field: 'M',
+        value: value.to_string(),
+    };
+    match value {
+        "none" | "" => Ok(None),
+        "C" => Ok(Some(TimeSpec::Common)),
+        "C|" => Ok(Some(TimeSpec::Cut)),
+        _ => {
+            let (beats, beat_type) = value.split_once('/').ok_or_else(invalid)?;
+            Ok(Some(TimeSpec::Simple {
+                beats: beats.trim().parse().map_err(|_| invalid())?,
+                beat_type: beat_type.trim().parse().map_err(|_| invalid())?,
+            }))
+        }
+    }
+}
+
+/// Parse an `L:` value such as `1/8`.
+fn parse_unit(value: &str) -> AbcResult<Length> {
+    let invalid = || AbcError::InvalidField {
+        field: 'L',
+        value: value.to_string(),
+    };
+    let (num, den) = value.split_once('/').ok_or_else(invalid)?;
+    let num: u32 = num.trim().parse().map_err(|_| invalid())?;
+    let den: u32 = den.trim().parse().map_err(|_| invalid())?;
+    if num == 0 || den == 0 {
+        return Err(invalid());
+    }
+    Ok(multiply((num, den), (1, 1)))
+}
+
+/// The default unit length: 1/16 for meters below 3/4, otherwise 1/8.
+fn default_unit(meter: Option<&TimeSpec>) -> Length {
+    match meter {
+        Some(TimeSpec::Simple { beats, beat_type })
+            if (*beats as u32) * 4 < 3 * (*beat_type as u32) =>
+        {
+            (1, 16)
+        }
+        _ => (1, 8),
+    }
+}
+
+/// Parse a `K:` value such as `G`, `Bbm`, `D mix`, or `Ador clef=treble`.
+fn parse_key(value: &str) -> AbcResult<KeySpec> {
+    let invalid = || AbcError::InvalidField {
+        field: 'K',
+        value: value.to_string(),
+    };
+    if value.is_empty() || value.eq_ignore_ascii_case("none") {
+        return Ok(KeySpec {
+            root: PitchStep::C,
+            root_alter: None,
+            mode: Mode::Major,
+        });
+    }
+
+    let mut chars = value.chars();
+    let root = match chars.next().map(|c| c.to_ascii_uppercase()) {
+        Some('C') => PitchStep::C,
+        Some('D') => PitchStep::D,
+        Some('E') => PitchStep::E,
+        Some('F') => PitchStep::F,
+        Some('G') => PitchStep::G,
+        Some('A') => PitchStep::A,
+        Some('B') => PitchStep::B,
+        _ => return Err(invalid()),
+    };
+    let mut rest = chars.as_str();
+    let root_alter = if let Some(r) = rest.strip_prefix('#') {
+        rest = r;
+        Some(PitchAlter::Sharp)
+    } else if let Some(r) = rest.strip_prefix('b') {
+        rest = r;
+        Some(PitchAlter::Flat)
+    } else {
+        None
+    };
+
+    // The mode is the first word unless it is a `name=value` modifier
+    let mode_word = rest
+        .split_whitespace()
+        .next()
+        .filter(|w| !w.contains('='))
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let mode = match mode_word.get(..3).unwrap_or(&mode_word) {
+        "" | "maj" => Mode::Major,
+        "m" | "min" => Mode::Minor,
+        "ion" => Mode::Ionian,
+        "aeo" => Mode::Aeolian,
+        "dor" => Mode::Dorian,
+        "phr" => Mode::Phrygian,
+        "lyd" => Mode::Lydian,
+        "mix" => Mode::Mixolydian,
+        "loc" => Mode::Locrian,
+        _ => return Err(invalid()),
+    };
+
+    Ok(KeySpec {
+        root,
+        root_alter,
+        mode,
+    })
+}
+
+/// Alterations implied by a key signature, indexed by [`step_index`].
+fn key_alters(key: &KeySpec) -> [Option<PitchAlter>; 7] {
+    const SHARPS: [PitchStep; 7] = [
+        PitchStep::F,
+        PitchStep::C,
+        PitchStep::G,
+        PitchStep::D,
+        PitchStep::A,
+        PitchStep::E,
+        PitchStep::B,
+    ];
+
+    let fifths = compute_fifths(key.root, key.root_alter.as_ref(), &key.mode);
+    let mut alters = [None; 7];
+    if fifths > 0 {
+        for step in SHARPS.iter().take(fifths.min(7) as usize) {
+            alters[step_index(*step)] = Some(PitchAlter::Sharp);
+        }
+    } else {
+        for step in SHARPS.iter().rev().take((-fifths).min(7) as usize) {
+            alters[step_index(*step)] = Some(PitchAlter::Flat);
+        }
+    }
+    alters
+}
+
+fn step_index(step: PitchStep) -> usize {
+    match step {
+        PitchStep::C => 0,
+        PitchStep::D => 1,
+        PitchStep::E => 2,
+        PitchStep::F => 3,
+        PitchStep::G => 4,
+        PitchStep::A => 5,
+        PitchStep::B => 6,
+    }
+}
+
+/// Convert a length in whole notes to a base duration with up to two dots.
+fn whole_fraction_to_duration(numerator: u32, denominator: u32) -> Option<FermataDuration> {
+    for dots in 0..=2u8 {
+        // n dots multiply the base by (2^(n+1) - 1) / 2^n
+        let (base_num, base_den) =
+            multiply((numerator, denominator), (1 << dots, (1 << (dots + 1)) - 1));
+        let base = match (base_num, base_den) {
+            (8, 1) => Some(DurationBase::Maxima),
+            (4, 1) => Some(DurationBase::Long),
+            (2, 1) => Some(DurationBase::Breve),
+            (1, 1) => Some(DurationBase::Whole),
+            (1, 2) => Some(DurationBase::Half),
+            (1, 4) => Some(DurationBase::Quarter),
+            (1, 8) => Some(DurationBase::Eighth),
+            (1, 16) => Some(DurationBase::Sixteenth),
+            (1, 32) => Some(DurationBase::ThirtySecond),
+            (1, 64) => Some(DurationBase::SixtyFourth),
+            (1, 128) => Some(DurationBase::OneTwentyEighth),
+            (1, 256) => Some(DurationBase::TwoFiftySixth),
+            (1, 512) => Some(DurationBase::FiveTwelfth),
+            (1, 1024) => Some(DurationBase::OneThousandTwentyFourth),
+            _ => None,
+        };
+        if let Some(base) = base {
+            return Some(FermataDuration { base, dots });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes(score: &FermataScore) -> Vec<&FermataNote> {
+        score.parts[0]
+            .measures
+            .iter()
+            .flat_map(|m| &m.content)
+            .filter_map(|e| match e {
+                MeasureElement::Note(n) => Some(n),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn pitch(step: PitchStep, alter: Option<PitchAlter>, octave: u8) -> FermataPitch {
+        FermataPitch {
+            step,
+            alter,
+            octave,
+        }
+    }
+
+    const REEL: &str = "X:1
+T:The Test Reel
+C:Trad.
+M:4/4
+L:1/8
+K:G
+|:GABc dBGB|
+% second bar
+AFDF ABcA:|
+";
+
+    #[test]
+    fn test_parse_to_ast_reel_pitches_and_durations() {
+        let score = parse_to_ast(REEL).unwrap();
+        assert_eq!(score.title.as_deref(), Some("The Test Reel"));
+        assert_eq!(score.composer.as_deref(), Some("Trad."));
+        assert_eq!(score.parts[0].measures.len(), 2);
+
+        let notes = notes(&score);
+        assert_eq!(notes.len(), 16);
+        assert!(
+            notes
+                .iter()
+                .all(|n| n.duration.base == DurationBase::Eighth && n.duration.dots == 0)
+        );
+
+        use PitchStep::*;
+        let sharp = Some(PitchAlter::Sharp);
+        let expected = [
+            pitch(G, None, 4),
+            pitch(A, None, 4),
+            pitch(B, None, 4),
+            pitch(C, None, 5),
+            pitch(D, None, 5),
+            pitch(B, None, 4),
+            pitch(G, None, 4),
+            pitch(B, None, 4),
+            pitch(A, None, 4),
+            pitch(F, sharp, 4),
+            pitch(D, None, 4),
+            pitch(F, sharp, 4),
+            pitch(A, None, 4),
+            pitch(B, None, 4),
+            pitch(C, None, 5),
+            pitch(A, None, 4),
+        ];
+        let actual: Vec<FermataPitch> = notes.iter().map(|n| n.pitch.clone()).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_to_ast_reel_repeat_barlines() {
+        let score = parse_to_ast(REEL).unwrap();
+        let measures = &score.parts[0].measures;
+        assert!(
+            measures[0]
+                .content
+                .contains(&MeasureElement::Barline(BarlineSpec::RepeatForward))
+        );
+        assert_eq!(
+            measures[1].content.last(),
+            Some(&MeasureElement::Barline(BarlineSpec::RepeatBackward {
+                times: None
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_to_ast_octaves_accidentals_and_bar_scope() {
+        let score = parse_to_ast("L:1/4\nK:C\nC, c' ^F F | F =B _B2 |").unwrap();
+        let pitches: Vec<FermataPitch> = notes(&score).iter().map(|n| n.pitch.clone()).collect();
+        use PitchStep::*;
+        assert_eq!(
+            pitches,
+            vec![
+                pitch(C, None, 3),
+                pitch(C, None, 6),
+                pitch(F, Some(PitchAlter::Sharp), 4),
+                pitch(F, Some(PitchAlter::Sharp), 4),
+                pitch(F, None, 4),
+                pitch(B, Some(PitchAlter::Natural), 4),
+                pitch(B, Some(PitchAlter::Flat), 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_to_ast_broken_rhythm_and_default_length() {
+        // No L: with 4/4 means eighth-note units
+        let score = parse_to_ast("M:4/4\nK:D\nA>B c<d A/B/ z2|").unwrap();
+        let durations: Vec<(DurationBase, u8)> = notes(&score)
+            .iter()
+            .map(|n| (n.duration.base, n.duration.dots))
+            .collect();
+        use DurationBase::*;
+        assert_eq!(
+            durations,
+            vec![
+                (Eighth, 1),
+                (Sixteenth, 0),
+                (Sixteenth, 0),
+                (Eighth, 1),
+                (Sixteenth, 0),
+                (Sixteenth, 0),
+            ]
+        );
+
+        // Meters below 3/4 default to sixteenth-note units
+        let score = parse_to_ast("M:2/4\nK:C\nC4|").unwrap();
+        assert_eq!(notes(&score)[0].duration.base, Quarter);
+    }
+
+    #[test]
+    fn test_parse_to_ast_ties_across_bars() {
+        let score = parse_to_ast("L:1/2\nK:C\nc2-|c2|").unwrap();
+        let ties: Vec<Option<StartStop>> = notes(&score).iter().map(|n| n.tie).collect();
+        assert_eq!(ties, vec![Some(StartStop::Start), Some(StartStop::Stop)]);
+    }
+
+    #[test]
+    fn test_parse_key_modes() {
+        let key = parse_key("Bbm").unwrap();
+        assert_eq!(key.root, PitchStep::B);
+        assert_eq!(key.root_alter, Some(PitchAlter::Flat));
+        assert_eq!(key.mode, Mode::Minor);
+        assert_eq!(parse_key("D mix").unwrap().mode, Mode::Mixolydian);
+        assert_eq!(parse_key("Ador clef=treble").unwrap().mode, Mode::Dorian);
+        assert_eq!(parse_key("F# clef=bass").unwrap().mode, Mode::Major);
+        assert!(parse_key("Hp").is_err());
+    }
+
+    #[test]
+    fn test_parse_to_ast_errors() {
+        assert!(matches!(
+            parse_to_ast("T:No key\nCDEF|"),
+            Err(AbcError::MissingField('K'))
+        ));
+        assert!(matches!(
+            parse_to_ast("K:C\n[CEG]|"),
+            Err(AbcError::Unsupported {
+                feature: "chords",
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse_to_ast("K:C\n(3CDE|"),
+            Err(AbcError::Unsupported {
+                feature: "tuplets",
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse_to_ast("L:1/8\nK:C\nC5|"),
+            Err(AbcError::UnsupportedDuration {
+                line: 3,
+                numerator: 5,
+                denominator: 8
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_compiles_to_ir() {
+        let score = parse(REEL).unwrap();
+        assert_eq!(score.parts.len(), 1);
+        assert_eq!(score.parts[0].measures.len(), 2);
+    }
+}
@@ -0,0 +1,295 @@
+//! Score statistics.
+//!
+//! [`analyze`] summarizes a compiled or imported [`ScorePartwise`]: how many
+//! notes and measures it has, the range it covers, its most common note
+//! value, its time signatures, and roughly how long it plays. It is meant
+//! for sizing up an unfamiliar file, not for detailed analysis.
+
+use std::time::Duration;
+
+use crate::ir::attributes::{TimeContent, Transpose};
+use crate::ir::duration::NoteTypeValue;
+use crate::ir::measure::MusicDataElement;
+use crate::ir::part::PartListElement;
+use crate::ir::pitch::Pitch;
+use crate::ir::score::ScorePartwise;
+
+/// Summary statistics for a score, produced by [`analyze`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreStats {
+    /// Pitched and unpitched notes in every part, counting each chord tone
+    /// and grace note
+    pub notes: usize,
+    /// Per-part statistics, in score order
+    pub parts: Vec<PartStats>,
+    /// Lowest sounding pitch, if the score has any pitched notes
+    pub lowest: Option<Pitch>,
+    /// Highest sounding pitch, if the score has any pitched notes
+    pub highest: Option<Pitch>,
+    /// The most common written note value and its number of dots
+    pub common_duration: Option<(NoteTypeValue, usize)>,
+    /// Time signatures of the first part with the measure each starts in
+    pub time_signatures: Vec<TimeChange>,
+    /// Estimated playing time, with repeats played once
+    pub duration: Duration,
+}
+
+/// Statistics for one part of a score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartStats {
+    /// Part id
+    pub id: String,
+    /// Part name from the part list, or empty if the part is not listed
+    pub name: String,
+    /// Number of measures
+    pub measures: usize,
+}
+
+/// A time signature and the measure it takes effect in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeChange {
+    /// Number of the measure
+    pub measure: String,
+    /// The signature as written, such as `3/4`, `3+2/8`, or `senza misura`
+    pub signature: String,
+}
+
+/// Collect summary statistics for a score.
+///
+/// Pitches are compared as they sound, applying each part's most recent
+/// `<transpose>`; a pitch that would sound outside octaves 0-9 is left out
+/// of the range. Note values are counted once per chord and grace notes
+/// are left out. Time signatures are read from the first part only, since
+/// parts normally share them. The playing time comes from
+/// [`ScorePartwise::playback_duration`].
+pub fn analyze(score: &ScorePartwise) -> ScoreStats {
+    let mut notes = 0;
+    let mut lowest: Option<(f64, Pitch)> = None;
+    let mut highest: Option<(f64, Pitch)> = None;
+    // Count of each (note value, dots), in first-seen order so that ties
+    // resolve predictably
+    let mut durations: Vec<((NoteTypeValue, usize), usize)> = Vec::new();
+
+    for part in &score.parts {
+        let mut transpose: Option<&Transpose> = None;
+        for element in part.measures.iter().flat_map(|m| &m.content) {
+            let note = match element {
+                MusicDataElement::Attributes(attributes) => {
+                    if let Some(t) = attributes.transpose.first() {
+                        transpose = Some(t);
+                    }
+                    continue;
+                }
+                MusicDataElement::Note(note) => note,
+                _ => continue,
+            };
+            if note.is_rest() {
+                continue;
+            }
+            notes += 1;
+            if let Some((height, sounding)) = note
+                .pitch()
+                .and_then(|pitch| sounding_pitch(pitch, transpose))
+            {
+                if lowest.as_ref().is_none_or(|(low, _)| height < *low) {
+                    lowest = Some((height, sounding.clone()));
+                }
+                if highest.as_ref().is_none_or(|(high, _)| height > *high) {
+                    highest = Some((height, sounding));
+                }
+            }
+
+            // Grace notes have no duration of their own
+            if let (Some(note_type), Some(_)) = (&note.r#type, note.duration())
+                && !note.is_chord_member()
+            {
+                let key = (note_type.value, note.dots.len());
+                match durations.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, count)) => *count += 1,
+                    None => durations.push((key, 1)),
+                }
+            }
+        }
+    }
+
+    let common_duration = durations
+        .iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(key, _)| *key);
+
+    ScoreStats {
+        notes,
+        parts: part_stats(score),
+        lowest: lowest.map(|(_, pitch)| pitch),
+        highest: highest.map(|(_, pitch)| pitch),
+        common_duration,
+        time_signatures: time_signatures(score),
+        duration: score.playback_duration(false),
+    }
+}
+
+fn part_stats(score: &ScorePartwise) -> Vec<PartStats> {
+    score
+        .parts
+        .iter()
+        .map(|part| {
+            let name = score
+                .part_list
+                .content
+                .iter()
+                .find_map(|element| match element {
+                    PartListElement::ScorePart(sp) if sp.id == part.id => {
+                        Some(sp.part_name.value.clone())
+                    }
+                    _ => None,
+                })
+                .unwrap_or_default();
+            PartStats {
+                id: part.id.clone(),
+                name,
+                measures: part.measures.len(),
+            }
+        })
+        .collect()
+}
+
+/// Every time signature in the first part, skipping restatements of the
+/// signature already in effect.
+fn time_signatures(score: &ScorePartwise) -> Vec<TimeChange> {
+    let mut changes: Vec<TimeChange> = Vec::new();
+    let Some(part) = score.parts.first() else {
+        return changes;
+    };
+    for measure in &part.measures {
+        for element in &measure.content {
+            let MusicDataElement::Attributes(attributes) = element else {
+                continue;
+            };
+            let Some(time) = attributes.times.first() else {
+                continue;
+            };
+            let signature = match &time.content {
+                TimeContent::Measured { signatures } => signatures
+                    .iter()
+                    .map(|s| format!("{}/{}", s.beats, s.beat_type))
+                    .collect::<Vec<_>>()
+                    .join("+"),
+                TimeContent::SenzaMisura(_) => "senza misura".to_string(),
+            };
+            if changes
+                .last()
+                .is_none_or(|last| last.signature != signature)
+            {
+                changes.push(TimeChange {
+                    measure: measure.number.clone(),
+                    signature,
+                });
+            }
+        }
+    }
+    changes
+}
+
+/// The pitch that sounds for a written pitch under `transpose`, with its
+/// height in semitones above C0, or `None` if it is outside octaves 0-9.
+///
+/// Without a diatonic transposition the sounding pitch keeps the written
+/// letter name shifted by the nearest number of steps.
+fn sounding_pitch(pitch: &Pitch, transpose: Option<&Transpose>) -> Option<(f64, Pitch)> {
+    let Some(transpose) = transpose else {
+        return Some((pitch.semitone_position(), pitch.clone()));
+    };
+
+    let octaves = transpose.octave_change.unwrap_or(0);
+    let chromatic = transpose.chromatic + 12 * octaves;
+    let diatonic = transpose
+        .diatonic
+        .unwrap_or_else(|| (f64::from(transpose.chromatic) * 7.0 / 12.0).round() as i32)
+        + 7 * octaves;
+
+    let sounding = pitch.shift(diatonic, f64::from(chromatic))?;
+    Some((sounding.semitone_position(), sounding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang::compile;
+
+    #[test]
+    fn test_analyze_eight_measure_scale() {
+        let measures: String = ["c4", "d4", "e4", "f4", "g4", "a4", "b4", "c5"]
+            .iter()
+            .map(|p| format!("(measure (note {} :q) (note {} :q) (note {} :h))", p, p, p))
+            .collect::<String>()
+            .replacen("(measure", "(measure (time 4 4) (tempo :q 60)", 1);
+        let score = compile(&format!("(score (part :name \"Flute\" {}))", measures)).unwrap();
+
+        let stats = analyze(&score);
+
+        assert_eq!(stats.notes, 24);
+        assert_eq!(stats.lowest.unwrap().to_string(), "C4");
+        assert_eq!(stats.highest.unwrap().to_string(), "C5");
+        assert_eq!(stats.common_duration, Some((NoteTypeValue::Quarter, 0)));
+        assert_eq!(stats.parts[0].name, "Flute");
+        assert_eq!(stats.parts[0].measures, 8);
+        assert_eq!(stats.duration, Duration::from_secs(32));
+        assert_eq!(
+            stats.time_signatures,
+            vec![TimeChange {
+                measure: "1".to_string(),
+                signature: "4/4".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_analyze_range_uses_sounding_pitch() {
+        // A B-flat clarinet sounds a major second below written pitch
+        let mut score =
+            compile("(score (part :name \"Clarinet\" (measure (note d4 :h) (note e5 :h))))")
+                .unwrap();
+        let MusicDataElement::Attributes(attributes) = &mut score.parts[0].measures[0].content[0]
+        else {
+            panic!("expected attributes first");
+        };
+        attributes.transpose.push(Transpose {
+            number: None,
+            diatonic: Some(-1),
+            chromatic: -2,
+            octave_change: None,
+            double: None,
+        });
+
+        let stats = analyze(&score);
+
+        assert_eq!(stats.lowest.unwrap().to_string(), "C4");
+        assert_eq!(stats.highest.unwrap().to_string(), "D5");
+    }
+
+    #[test]
+    fn test_analyze_range_skips_pitches_sounding_above_octave_nine() {
+        let mut score =
+            compile("(score (part :name \"Piccolo\" (measure (note c4 :h) (note c9 :h))))")
+                .unwrap();
+        let MusicDataElement::Attributes(attributes) = &mut score.parts[0].measures[0].content[0]
+        else {
+            panic!("expected attributes first");
+        };
+        attributes.transpose.push(Transpose {
+            number: None,
+            diatonic: None,
+            chromatic: 0,
+            octave_change: Some(1),
+            double: None,
+        });
+
+        let stats = analyze(&score);
+
+        // C9 would sound as C10, which cannot be written
+        assert_eq!(stats.notes, 2);
+        assert_eq!(stats.lowest.unwrap().to_string(), "C5");
+        assert_eq!(stats.highest.unwrap().to_string(), "C5");
+    }
+}
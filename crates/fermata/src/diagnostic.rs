@@ -0,0 +1,197 @@
+//! Diagnostics shared by every checking phase.
+//!
+//! Parsing, compilation, and the score lints in [`crate::validate`] each
+//! report problems their own way. A [`Diagnostic`] gives them one shape, a
+//! severity, a message, and an optional source span, so that tools such as
+//! `fermata check` (and eventually a language server) can collect and
+//! render them uniformly.
+
+use std::fmt;
+
+use crate::lang::error::{CompileError, SourceSpan};
+use crate::lang::{compile_sexpr, parse_recover};
+use crate::validate::{ValidationWarning, validate_score};
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The source cannot be compiled
+    Error,
+    /// The source compiles but likely produces broken notation
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => f.write_str("error"),
+            Severity::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+/// A problem found in Fermata source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How serious the problem is
+    pub severity: Severity,
+    /// What is wrong
+    pub message: String,
+    /// Where in the source, if known
+    pub span: Option<SourceSpan>,
+}
+
+impl Diagnostic {
+    /// Create an error with no source location.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            span: None,
+        }
+    }
+
+    /// Create a warning with no source location.
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            span: None,
+        }
+    }
+
+    /// Attach the source location.
+    pub fn with_span(mut self, span: SourceSpan) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Whether this diagnostic is an error.
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Format the message alongside the source it came from, followed by a
+    /// snippet of the offending line when the span is known (see
+    /// [`SourceSpan::snippet`]).
+    pub fn render(&self, source: &str) -> String {
+        match self.span.as_ref().and_then(|span| span.snippet(source)) {
+            Some(snippet) => format!("{}\n{}", self.message, snippet),
+            None => self.message.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+impl From<CompileError> for Diagnostic {
+    fn from(error: CompileError) -> Self {
+        match error {
+            CompileError::WithSpan { message, span, .. } => {
+                Diagnostic::error(message).with_span(span)
+            }
+            error => Diagnostic::error(error.to_string()),
+        }
+    }
+}
+
+impl From<ValidationWarning> for Diagnostic {
+    fn from(warning: ValidationWarning) -> Self {
+        Diagnostic::warning(warning.to_string())
+    }
+}
+
+/// Accumulates diagnostics from several phases, in the order reported.
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticCollector {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl DiagnosticCollector {
+    /// Create an empty collector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a diagnostic, or anything that converts into one.
+    pub fn push(&mut self, diagnostic: impl Into<Diagnostic>) {
+        self.diagnostics.push(diagnostic.into());
+    }
+
+    /// Add every diagnostic from an iterator.
+    pub fn extend<D: Into<Diagnostic>>(&mut self, diagnostics: impl IntoIterator<Item = D>) {
+        self.diagnostics
+            .extend(diagnostics.into_iter().map(Into::into));
+    }
+
+    /// Whether any error-severity diagnostic has been collected.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    /// The diagnostics collected so far.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Consume the collector, returning its diagnostics.
+    pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+}
+
+/// Check Fermata source, collecting diagnostics from every phase.
+///
+/// Syntax errors are reported all at once; whatever could be parsed is
+/// then compiled, and if that succeeds the score lints run on the result.
+/// Source with no error-severity diagnostics compiles.
+pub fn check(source: &str) -> Vec<Diagnostic> {
+    let mut collector = DiagnosticCollector::new();
+
+    let (sexpr, errors) = parse_recover(source);
+    collector.extend(errors);
+    if let Some(sexpr) = sexpr {
+        match compile_sexpr(&sexpr) {
+            Ok(score) => collector.extend(validate_score(&score)),
+            Err(error) => collector.push(error),
+        }
+    }
+
+    collector.into_diagnostics()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_collects_parse_error_and_validation_warning() {
+        let source = "(score (part :piano (measure (time 2 4) (note c4 :h) (note d4 :q)) ]))";
+
+        let diagnostics = check(source);
+
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        let span = diagnostics[0].span.as_ref().unwrap();
+        assert_eq!((span.line, span.column), (1, 68));
+        assert_eq!(
+            diagnostics[1],
+            Diagnostic::warning(
+                "overfull measure in part P1, measure 1: 2880 divisions where the time signature allows 1920"
+            )
+        );
+    }
+
+    #[test]
+    fn test_check_semantic_error_is_error_severity() {
+        let diagnostics = check("(score (part :piano (measure (note c4 :q :stacato))))");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].is_error());
+        assert!(diagnostics[0].message.contains(":stacato"));
+    }
+}
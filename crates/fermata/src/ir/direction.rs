@@ -6,6 +6,7 @@ use super::common::{
     YesNo,
 };
 use super::duration::NoteTypeValue;
+use super::part::MidiInstrument;
 
 /// A musical direction.
 #[derive(Debug, Clone, PartialEq)]
@@ -456,6 +457,10 @@ pub struct Sound {
     pub time_only: Option<String>,
     /// Pizzicato
     pub pizzicato: Option<YesNo>,
+    /// MIDI program, channel, or volume changes for instruments in the part
+    pub midi_instruments: Vec<MidiInstrument>,
+    /// Offset of the sound change from the current position
+    pub offset: Option<Offset>,
 }
 
 // Placeholder types for less common direction types
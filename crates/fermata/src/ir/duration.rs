@@ -66,6 +66,78 @@ pub struct TimeModification {
     pub normal_dots: u32,
 }
 
+/// Compute a note's `<duration>` in divisions.
+///
+/// Combines the note type, augmentation dots (each adding half of the
+/// previous value: 1.5×, 1.75×, ...), and an optional tuplet ratio
+/// (`normal_notes / actual_notes`), given `divisions` per quarter note.
+///
+/// The calculation is exact; when the result is not a whole number of
+/// divisions it is rounded to the nearest division, with halves rounding up.
+/// A result that would round to zero is clamped to 1, since MusicXML
+/// durations must be positive. Pick `divisions` as a multiple of every
+/// tuplet and subdivision in the piece (e.g. 960) to avoid rounding.
+/// A time modification with zero actual or normal notes is ignored.
+///
+/// # Examples
+///
+/// ```
+/// use fermata::ir::duration::{NoteTypeValue, TimeModification, divisions_for};
+///
+/// assert_eq!(divisions_for(NoteTypeValue::Quarter, 0, None, 960), 960);
+/// assert_eq!(divisions_for(NoteTypeValue::Half, 1, None, 960), 2880);
+///
+/// let triplet = TimeModification {
+///     actual_notes: 3,
+///     normal_notes: 2,
+///     normal_type: None,
+///     normal_dots: 0,
+/// };
+/// assert_eq!(divisions_for(NoteTypeValue::Eighth, 0, Some(&triplet), 960), 320);
+/// ```
+pub fn divisions_for(
+    note_type: NoteTypeValue,
+    dots: u32,
+    time_mod: Option<&TimeModification>,
+    divisions: u32,
+) -> u64 {
+    // Length in quarter notes as a fraction
+    let (base_num, base_den): (u128, u128) = match note_type {
+        NoteTypeValue::Maxima => (32, 1),
+        NoteTypeValue::Long => (16, 1),
+        NoteTypeValue::Breve => (8, 1),
+        NoteTypeValue::Whole => (4, 1),
+        NoteTypeValue::Half => (2, 1),
+        NoteTypeValue::Quarter => (1, 1),
+        NoteTypeValue::Eighth => (1, 2),
+        NoteTypeValue::N16th => (1, 4),
+        NoteTypeValue::N32nd => (1, 8),
+        NoteTypeValue::N64th => (1, 16),
+        NoteTypeValue::N128th => (1, 32),
+        NoteTypeValue::N256th => (1, 64),
+        NoteTypeValue::N512th => (1, 128),
+        NoteTypeValue::N1024th => (1, 256),
+    };
+
+    // n dots multiply by (2^(n+1) - 1) / 2^n; beyond 16 dots the extra
+    // value is far below one division, so cap to keep the arithmetic small
+    let dots = dots.min(16);
+    let dot_num = (1u128 << (dots + 1)) - 1;
+    let dot_den = 1u128 << dots;
+
+    let (tm_num, tm_den) = match time_mod {
+        Some(tm) if tm.actual_notes > 0 && tm.normal_notes > 0 => {
+            (tm.normal_notes as u128, tm.actual_notes as u128)
+        }
+        _ => (1, 1),
+    };
+
+    let num = divisions as u128 * base_num * dot_num * tm_num;
+    let den = base_den * dot_den * tm_den;
+    let rounded = (2 * num + den) / (2 * den);
+    rounded.max(1) as u64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -451,4 +523,52 @@ mod tests {
         assert!(debug_str.contains("actual_notes"));
         assert!(debug_str.contains("normal_notes"));
     }
+
+    // === divisions_for Tests ===
+
+    fn triplet() -> TimeModification {
+        TimeModification {
+            actual_notes: 3,
+            normal_notes: 2,
+            normal_type: None,
+            normal_dots: 0,
+        }
+    }
+
+    #[test]
+    fn test_divisions_for_quarter_equals_divisions() {
+        assert_eq!(divisions_for(NoteTypeValue::Quarter, 0, None, 960), 960);
+        assert_eq!(divisions_for(NoteTypeValue::Quarter, 0, None, 1), 1);
+    }
+
+    #[test]
+    fn test_divisions_for_dotted_notes() {
+        assert_eq!(divisions_for(NoteTypeValue::Half, 1, None, 960), 2880);
+        assert_eq!(divisions_for(NoteTypeValue::Quarter, 2, None, 960), 1680);
+    }
+
+    #[test]
+    fn test_divisions_for_eighth_triplet() {
+        let tm = triplet();
+        assert_eq!(divisions_for(NoteTypeValue::Eighth, 0, Some(&tm), 960), 320);
+        assert_eq!(divisions_for(NoteTypeValue::Eighth, 0, Some(&tm), 3), 1);
+    }
+
+    #[test]
+    fn test_divisions_for_dotted_eighth_triplet() {
+        let tm = triplet();
+        // 0.5 * 1.5 * 2/3 = 0.5 quarters
+        assert_eq!(divisions_for(NoteTypeValue::Eighth, 1, Some(&tm), 960), 480);
+    }
+
+    #[test]
+    fn test_divisions_for_rounds_uneven_results() {
+        let tm = triplet();
+        // 4 * 2/3 = 2.67 -> 3
+        assert_eq!(divisions_for(NoteTypeValue::Quarter, 0, Some(&tm), 4), 3);
+        // 0.5 -> 1 (halves round up)
+        assert_eq!(divisions_for(NoteTypeValue::Eighth, 0, None, 1), 1);
+        // Never zero
+        assert_eq!(divisions_for(NoteTypeValue::N1024th, 0, None, 1), 1);
+    }
 }
@@ -2,8 +2,9 @@
 
 use super::attributes::{Attributes, Barline};
 use super::common::{Tenths, YesNo};
-use super::direction::Direction;
+use super::direction::{Direction, Offset};
 use super::note::Note;
+use super::score::{PageLayout, StaffLayout, SystemLayout};
 use super::voice::{Backup, Forward};
 
 /// A measure within a part.
@@ -21,6 +22,18 @@ pub struct Measure {
     pub content: Vec<MusicDataElement>,
 }
 
+/// Number measures from 1 in order, leaving implicit measures such as a
+/// pickup with the number they already have.
+pub(crate) fn renumber_measures(measures: &mut [Measure]) {
+    let mut number = 0;
+    for measure in measures {
+        if measure.implicit != Some(YesNo::Yes) {
+            number += 1;
+            measure.number = number.to_string();
+        }
+    }
+}
+
 /// Elements that can appear within a measure.
 #[derive(Debug, Clone, PartialEq)]
 pub enum MusicDataElement {
@@ -36,6 +49,76 @@ pub enum MusicDataElement {
     Attributes(Box<Attributes>),
     /// Barline
     Barline(Box<Barline>),
+    /// Layout and break instructions (system and page breaks)
+    Print(Box<Print>),
+    /// Interactive performance hints (score following)
+    Listening(Box<Listening>),
+}
+
+/// Layout changes and explicit breaks that apply from this measure on.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Print {
+    /// Start a new system at this measure
+    pub new_system: Option<YesNo>,
+    /// Start a new page at this measure
+    pub new_page: Option<YesNo>,
+    /// Page number to display on the new page
+    pub page_number: Option<String>,
+    /// Page layout override
+    pub page_layout: Option<PageLayout>,
+    /// System layout override
+    pub system_layout: Option<SystemLayout>,
+    /// Staff layout overrides
+    pub staff_layouts: Vec<StaffLayout>,
+    /// Measure layout override
+    pub measure_layout: Option<MeasureLayout>,
+}
+
+/// Measure layout.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeasureLayout {
+    /// Horizontal distance from the previous measure
+    pub measure_distance: Option<Tenths>,
+}
+
+/// How a performance listening application should follow the score from
+/// this point on.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Listening {
+    /// Synchronization hints
+    pub syncs: Vec<ListeningSync>,
+    /// Application-specific listening hints
+    pub other_listening: Vec<OtherListening>,
+    /// Where the hints take effect relative to the current position
+    pub offset: Option<Offset>,
+}
+
+/// A `<sync>` hint describing how closely an application should follow a
+/// performer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListeningSync {
+    /// Synchronization type, such as `none`, `tempo`, or `event`; unknown
+    /// values are kept as written
+    pub r#type: String,
+    /// Expected delay of the performer in milliseconds
+    pub latency: Option<u32>,
+    /// Player the hint applies to
+    pub player: Option<String>,
+    /// Repeat passes the hint applies to, such as `1,2`
+    pub time_only: Option<String>,
+}
+
+/// An `<other-listening>` hint for a specific application.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtherListening {
+    /// Hint value
+    pub value: String,
+    /// Kind of hint
+    pub r#type: String,
+    /// Player the hint applies to
+    pub player: Option<String>,
+    /// Repeat passes the hint applies to, such as `1,2`
+    pub time_only: Option<String>,
 }
 
 #[cfg(test)]
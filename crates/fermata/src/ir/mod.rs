@@ -13,7 +13,7 @@
 //! - [`attributes`] - Measure attributes (Key, Time, Clef, Barline)
 //! - [`direction`] - Directions (Dynamics, Wedge, Metronome, etc.)
 //! - [`notation`] - Notations (Articulations, Ornaments, Slurs, etc.)
-//! - [`voice`] - Voice-related types (Backup, Forward)
+//! - [`voice`] - Voice-related types (Backup, Forward, MeasureCursor)
 //! - [`lyric`] - Lyric types
 //! - [`measure`] - Measure and music data types
 //! - [`part`] - Part and part-list types
@@ -42,6 +42,7 @@ pub mod notation;
 pub mod note;
 pub mod part;
 pub mod pitch;
+mod playback;
 pub mod score;
 pub mod voice;
 
@@ -51,13 +52,16 @@ pub use beam::{Beam, Notehead, Stem};
 pub use direction::{Direction, DirectionType, Dynamics, Metronome, Wedge};
 pub use duration::{Dot, NoteType, NoteTypeValue, TimeModification};
 pub use lyric::{Lyric, Syllabic};
-pub use measure::{Measure, MusicDataElement};
+pub use measure::{
+    Listening, ListeningSync, Measure, MeasureLayout, MusicDataElement, OtherListening, Print,
+};
 pub use notation::{Articulations, Fermata, Notations, Ornaments, Slur, Technical, Tied, Tuplet};
 pub use note::{Accidental, FullNote, Grace, Note, NoteContent, Rest};
-pub use part::{Part, PartGroup, PartList, PartListElement, PartName, ScorePart};
+pub use part::{GroupSpan, Part, PartGroup, PartList, PartListElement, PartName, ScorePart};
 pub use pitch::{Pitch, Step, Unpitched};
-pub use score::ScorePartwise;
-pub use voice::{Backup, Forward};
+pub use playback::expand_repeats;
+pub use score::{ReorderError, ScorePartwise};
+pub use voice::{Backup, Forward, MeasureCursor};
 
 // Re-export common types
 pub use common::*;
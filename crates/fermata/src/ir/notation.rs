@@ -821,6 +821,8 @@ pub struct Bend {
 /// Bend release timing.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BendRelease {
+    /// Release at the default time, with no offset
+    Default,
     /// Early release
     Early,
     /// Late release
@@ -65,6 +65,46 @@ pub struct Note {
     pub lyrics: Vec<Lyric>,
 }
 
+impl Note {
+    /// The chord flag and pitch, rest, or unpitched content, whichever
+    /// variant the note is.
+    pub fn full_note(&self) -> &FullNote {
+        match &self.content {
+            NoteContent::Regular { full_note, .. }
+            | NoteContent::Grace { full_note, .. }
+            | NoteContent::Cue { full_note, .. } => full_note,
+        }
+    }
+
+    /// The pitch, or `None` for rests and unpitched notes.
+    pub fn pitch(&self) -> Option<&Pitch> {
+        match &self.full_note().content {
+            PitchRestUnpitched::Pitch(pitch) => Some(pitch),
+            PitchRestUnpitched::Rest(_) | PitchRestUnpitched::Unpitched(_) => None,
+        }
+    }
+
+    /// Duration in divisions, or `None` for grace notes, which have none.
+    pub fn duration(&self) -> Option<PositiveDivisions> {
+        match &self.content {
+            NoteContent::Regular { duration, .. } | NoteContent::Cue { duration, .. } => {
+                Some(*duration)
+            }
+            NoteContent::Grace { .. } => None,
+        }
+    }
+
+    /// Whether the note is a rest.
+    pub fn is_rest(&self) -> bool {
+        matches!(self.full_note().content, PitchRestUnpitched::Rest(_))
+    }
+
+    /// Whether the note sounds with the note before it (`<chord/>`).
+    pub fn is_chord_member(&self) -> bool {
+        self.full_note().chord
+    }
+}
+
 /// The three content variants for a note.
 #[derive(Debug, Clone, PartialEq)]
 pub enum NoteContent {
@@ -696,6 +736,98 @@ mod tests {
         }
     }
 
+    // === Note Accessor Tests ===
+
+    fn note_with(content: NoteContent) -> Note {
+        Note {
+            position: Position::default(),
+            dynamics: None,
+            end_dynamics: None,
+            attack: None,
+            release: None,
+            pizzicato: None,
+            print_object: None,
+            content,
+            instrument: vec![],
+            voice: None,
+            r#type: None,
+            dots: vec![],
+            accidental: None,
+            time_modification: None,
+            stem: None,
+            notehead: None,
+            staff: None,
+            beams: vec![],
+            notations: vec![],
+            lyrics: vec![],
+        }
+    }
+
+    fn pitched(chord: bool, step: Step) -> FullNote {
+        FullNote {
+            chord,
+            content: PitchRestUnpitched::Pitch(Pitch {
+                step,
+                alter: None,
+                octave: 4,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_note_accessors_regular_note() {
+        let note = note_with(NoteContent::Regular {
+            full_note: pitched(false, Step::C),
+            duration: 960,
+            ties: vec![],
+        });
+        assert_eq!(note.pitch().map(|p| p.step), Some(Step::C));
+        assert_eq!(note.duration(), Some(960));
+        assert!(!note.is_rest());
+        assert!(!note.is_chord_member());
+    }
+
+    #[test]
+    fn test_note_accessors_grace_note() {
+        let note = note_with(NoteContent::Grace {
+            grace: Grace::default(),
+            full_note: pitched(false, Step::D),
+            ties: vec![],
+        });
+        assert_eq!(note.pitch().map(|p| p.step), Some(Step::D));
+        assert_eq!(note.duration(), None);
+        assert!(!note.is_rest());
+        assert!(!note.is_chord_member());
+    }
+
+    #[test]
+    fn test_note_accessors_rest() {
+        let note = note_with(NoteContent::Regular {
+            full_note: FullNote {
+                chord: false,
+                content: PitchRestUnpitched::Rest(Rest::default()),
+            },
+            duration: 480,
+            ties: vec![],
+        });
+        assert_eq!(note.pitch(), None);
+        assert_eq!(note.duration(), Some(480));
+        assert!(note.is_rest());
+        assert!(!note.is_chord_member());
+    }
+
+    #[test]
+    fn test_note_accessors_chord_member() {
+        let note = note_with(NoteContent::Cue {
+            full_note: pitched(true, Step::E),
+            duration: 960,
+        });
+        assert_eq!(note.pitch().map(|p| p.step), Some(Step::E));
+        assert_eq!(note.duration(), Some(960));
+        assert!(!note.is_rest());
+        assert!(note.is_chord_member());
+    }
+
     // === Note Tests ===
 
     #[test]
@@ -190,6 +190,20 @@ pub struct PartGroup {
     pub editorial: Editorial,
 }
 
+/// A part group resolved to the parts it spans.
+///
+/// Built by [`ScorePartwise::part_groups`](super::score::ScorePartwise::part_groups)
+/// from the flat start/stop [`PartGroup`] entries of the part list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupSpan {
+    /// The group's start entry, carrying its name, symbol, and barline
+    pub group: PartGroup,
+    /// Ids of the spanned parts, in score order
+    pub part_ids: Vec<String>,
+    /// Groups lying wholly within this one, in score order
+    pub children: Vec<GroupSpan>,
+}
+
 /// Group name.
 #[derive(Debug, Clone, PartialEq)]
 pub struct GroupName {
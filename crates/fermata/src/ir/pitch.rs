@@ -1,5 +1,7 @@
 //! Pitch representation types.
 
+use std::fmt;
+
 use super::common::{Octave, Semitones};
 
 /// A musical pitch with step, optional alteration, and octave.
@@ -32,6 +34,21 @@ pub enum Step {
     G,
 }
 
+impl fmt::Display for Pitch {
+    /// Scientific pitch notation with `#` and `b` for whole-semitone
+    /// alterations (`C#4`, `Bbb3`); microtonal alterations are written as
+    /// a signed number (`E-0.5 4`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.step)?;
+        match self.alter.unwrap_or(0.0) {
+            alter if alter.fract() != 0.0 => write!(f, "{:+} ", alter)?,
+            alter if alter > 0.0 => f.write_str(&"#".repeat(alter as usize))?,
+            alter => f.write_str(&"b".repeat(-alter as usize))?,
+        }
+        write!(f, "{}", self.octave)
+    }
+}
+
 /// Unpitched note (percussion) with optional display position.
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct Unpitched {
@@ -45,6 +62,19 @@ pub struct Unpitched {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_pitch_display() {
+        let pitch = |step, alter, octave| Pitch {
+            step,
+            alter,
+            octave,
+        };
+        assert_eq!(pitch(Step::C, None, 4).to_string(), "C4");
+        assert_eq!(pitch(Step::F, Some(1.0), 5).to_string(), "F#5");
+        assert_eq!(pitch(Step::B, Some(-2.0), 3).to_string(), "Bbb3");
+        assert_eq!(pitch(Step::E, Some(-0.5), 4).to_string(), "E-0.5 4");
+    }
+
     // === Step Tests ===
 
     #[test]
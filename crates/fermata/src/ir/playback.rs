@@ -0,0 +1,792 @@
+//! Playback duration estimation and repeat expansion.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use super::attributes::{Barline, TimeContent};
+use super::common::{BackwardForward, StartStopDiscontinue, YesNo};
+use super::direction::{Direction, DirectionTypeContent, MetronomeContent, Sound};
+use super::duration::NoteTypeValue;
+use super::measure::{Measure, MusicDataElement, renumber_measures};
+use super::part::Part;
+use super::score::ScorePartwise;
+
+/// Tempo used until the score sets one, in quarter notes per minute.
+const DEFAULT_TEMPO: f64 = 120.0;
+
+/// Tempo changes keyed by measure index, then offset in quarter notes.
+type TempoMap = BTreeMap<usize, Vec<(f64, f64)>>;
+
+impl ScorePartwise {
+    /// Estimate how long the score takes to play.
+    ///
+    /// Each part is walked measure by measure, resolving `divisions` from its
+    /// attributes and summing the time covered by its notes, backups, and
+    /// forwards. Tempo comes from `<sound tempo>` or a metronome mark in any
+    /// part (taking effect at its position in the measure) and defaults to
+    /// quarter = 120; an empty measure lasts as long as the current time
+    /// signature. The result is the length of the longest part.
+    ///
+    /// When `expand_repeats` is true, measures are played in the order used
+    /// by [`expand_repeats`]; otherwise every measure is played once in
+    /// written order.
+    pub fn playback_duration(&self, expand_repeats: bool) -> Duration {
+        let tempos = self.tempo_map();
+        let order = expand_repeats.then(|| playback_order(&self.parts));
+        self.parts
+            .iter()
+            .map(|part| part_seconds(part, &tempos, order.as_deref()))
+            .fold(Duration::ZERO, |longest, secs| {
+                longest.max(Duration::from_secs_f64(secs))
+            })
+    }
+
+    /// Collect tempo changes from every part.
+    fn tempo_map(&self) -> TempoMap {
+        let mut map = TempoMap::new();
+        for part in &self.parts {
+            let mut divisions = 1.0;
+            for (index, measure) in part.measures.iter().enumerate() {
+                let mut position = 0.0;
+                walk_measure(measure, &mut divisions, &mut position, |element, at| {
+                    if let MusicDataElement::Direction(direction) = element {
+                        if let Some(tempo) = direction_tempo(direction) {
+                            map.entry(index).or_default().push((at, tempo));
+                        }
+                    }
+                });
+            }
+        }
+        for changes in map.values_mut() {
+            changes.sort_by(|a, b| a.0.total_cmp(&b.0));
+        }
+        map
+    }
+}
+
+/// Total playing time of one part in seconds.
+fn part_seconds(part: &Part, tempos: &TempoMap, order: Option<&[usize]>) -> f64 {
+    // Length of each measure in quarter notes, in written order
+    let mut divisions = 1.0;
+    let mut meter = 4.0;
+    let lengths: Vec<f64> = part
+        .measures
+        .iter()
+        .map(|measure| {
+            let mut position = 0.0;
+            let mut end = 0.0f64;
+            walk_measure(measure, &mut divisions, &mut position, |element, at| {
+                end = end.max(at);
+                if let MusicDataElement::Attributes(attributes) = element {
+                    if let Some(quarters) = attributes.times.first().and_then(time_quarters) {
+                        meter = quarters;
+                    }
+                }
+            });
+            end = end.max(position);
+            if end > 0.0 { end } else { meter }
+        })
+        .collect();
+
+    let order: Vec<usize> = match order {
+        Some(order) => order.to_vec(),
+        None => (0..part.measures.len()).collect(),
+    };
+
+    // Tempo in effect at the start of each written measure
+    let mut tempo_at_start = Vec::with_capacity(lengths.len());
+    let mut tempo = DEFAULT_TEMPO;
+    for index in 0..lengths.len() {
+        tempo_at_start.push(tempo);
+        if let Some(&(_, last)) = tempos.get(&index).and_then(|c| c.last()) {
+            tempo = last;
+        }
+    }
+
+    order
+        .into_iter()
+        .map(|index| {
+            let Some(&length) = lengths.get(index) else {
+                return 0.0;
+            };
+            let changes = tempos.get(&index).map(Vec::as_slice).unwrap_or(&[]);
+            measure_seconds(length, tempo_at_start[index], changes)
+        })
+        .sum()
+}
+
+/// Seconds taken by a measure of `length` quarters with mid-measure changes.
+fn measure_seconds(length: f64, mut tempo: f64, changes: &[(f64, f64)]) -> f64 {
+    let mut seconds = 0.0;
+    let mut from = 0.0;
+    for &(at, new_tempo) in changes {
+        let at = at.min(length);
+        seconds += (at - from) * 60.0 / tempo;
+        from = at;
+        tempo = new_tempo;
+    }
+    seconds + (length - from) * 60.0 / tempo
+}
+
+/// Walk a measure's content, tracking the position in quarter notes.
+///
+/// `visit` is called with each element and the position at which it ends.
+fn walk_measure(
+    measure: &Measure,
+    divisions: &mut f64,
+    position: &mut f64,
+    mut visit: impl FnMut(&MusicDataElement, f64),
+) {
+    for element in &measure.content {
+        match element {
+            MusicDataElement::Attributes(attributes) => {
+                if let Some(d) = attributes.divisions.filter(|&d| d > 0) {
+                    *divisions = d as f64;
+                }
+            }
+            MusicDataElement::Note(note) if !note.is_chord_member() => {
+                if let Some(duration) = note.duration() {
+                    *position += duration as f64 / *divisions;
+                }
+            }
+            MusicDataElement::Backup(backup) => {
+                *position = (*position - backup.duration as f64 / *divisions).max(0.0);
+            }
+            MusicDataElement::Forward(forward) => {
+                *position += forward.duration as f64 / *divisions;
+            }
+            _ => {}
+        }
+        visit(element, *position);
+    }
+}
+
+/// The tempo set by a direction, in quarter notes per minute.
+///
+/// `<sound tempo>` is already in quarters per minute and takes precedence
+/// over a metronome mark, whose beat unit is converted to quarters.
+fn direction_tempo(direction: &Direction) -> Option<f64> {
+    if let Some(tempo) = direction.sound.as_ref().and_then(|s| s.tempo) {
+        return (tempo > 0.0).then_some(tempo);
+    }
+    direction.direction_types.iter().find_map(|dt| {
+        let DirectionTypeContent::Metronome(metronome) = &dt.content else {
+            return None;
+        };
+        let MetronomeContent::PerMinute {
+            beat_unit,
+            beat_unit_dots,
+            per_minute,
+        } = &metronome.content
+        else {
+            return None;
+        };
+        // Ranges such as "120-132" use their lower bound
+        let digits: String = per_minute
+            .value
+            .trim()
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect();
+        let bpm: f64 = digits.parse().ok().filter(|&b: &f64| b > 0.0)?;
+        let dots = *beat_unit_dots as i32;
+        let quarters = note_type_quarters(*beat_unit) * (2.0 - 0.5f64.powi(dots));
+        Some(bpm * quarters)
+    })
+}
+
+/// Nominal length of a time signature in quarter notes.
+fn time_quarters(time: &super::attributes::Time) -> Option<f64> {
+    let TimeContent::Measured { signatures } = &time.content else {
+        return None;
+    };
+    let total: f64 = signatures
+        .iter()
+        .map(|sig| {
+            let beats: f64 = sig
+                .beats
+                .split('+')
+                .filter_map(|b| b.trim().parse::<f64>().ok())
+                .sum();
+            let beat_type: f64 = sig.beat_type.trim().parse().unwrap_or(4.0);
+            beats * 4.0 / beat_type
+        })
+        .sum();
+    (total > 0.0).then_some(total)
+}
+
+/// Length of a note type in quarter notes.
+fn note_type_quarters(value: NoteTypeValue) -> f64 {
+    match value {
+        NoteTypeValue::Maxima => 32.0,
+        NoteTypeValue::Long => 16.0,
+        NoteTypeValue::Breve => 8.0,
+        NoteTypeValue::Whole => 4.0,
+        NoteTypeValue::Half => 2.0,
+        NoteTypeValue::Quarter => 1.0,
+        NoteTypeValue::Eighth => 0.5,
+        NoteTypeValue::N16th => 0.25,
+        NoteTypeValue::N32nd => 0.125,
+        NoteTypeValue::N64th => 0.0625,
+        NoteTypeValue::N128th => 0.03125,
+        NoteTypeValue::N256th => 0.015625,
+        NoteTypeValue::N512th => 0.0078125,
+        NoteTypeValue::N1024th => 0.00390625,
+    }
+}
+
+/// Unfold repeats, voltas, and D.C./D.S./coda jumps into a linear score.
+///
+/// Every part is rewritten to follow the same playing order (see
+/// [`ScorePartwise::playback_duration`]): repeated measures are duplicated,
+/// repeat and ending marks are removed from barlines, navigation attributes
+/// are cleared from `<sound>`, and measures are renumbered from 1, except
+/// that implicit measures such as a pickup keep their number. After a
+/// D.C. or D.S. jump, repeats are not taken again and only the final ending
+/// is played; `fine` stops playback and `tocoda` jumps to the matching coda.
+pub fn expand_repeats(score: &ScorePartwise) -> ScorePartwise {
+    let order = playback_order(&score.parts);
+    let mut expanded = score.clone();
+    for (part, source) in expanded.parts.iter_mut().zip(&score.parts) {
+        part.measures = order
+            .iter()
+            .filter_map(|&index| source.measures.get(index))
+            .map(|measure| {
+                let mut measure = measure.clone();
+                strip_navigation(&mut measure);
+                measure
+            })
+            .collect();
+        renumber_measures(&mut part.measures);
+    }
+    expanded
+}
+
+/// Remove repeat, ending, and jump markings from a measure.
+fn strip_navigation(measure: &mut Measure) {
+    measure.content.retain_mut(|element| match element {
+        MusicDataElement::Barline(barline) => {
+            let had_repeat = barline.repeat.take().is_some();
+            let had_ending = barline.ending.take().is_some();
+            // Drop barlines that only existed to carry the repeat or ending
+            !((had_repeat || had_ending)
+                && barline.segno.is_none()
+                && barline.coda.is_none()
+                && barline.fermatas.is_empty()
+                && barline.wavy_line.is_none())
+        }
+        MusicDataElement::Direction(direction) => {
+            if let Some(sound) = direction.sound.as_mut() {
+                sound.dacapo = None;
+                sound.segno = None;
+                sound.dalsegno = None;
+                sound.coda = None;
+                sound.tocoda = None;
+                sound.fine = None;
+                sound.forward_repeat = None;
+                if *sound == Sound::default() {
+                    direction.sound = None;
+                }
+            }
+            true
+        }
+        _ => true,
+    });
+}
+
+/// Jump markings found in `<sound>` elements of one measure.
+#[derive(Debug, Default)]
+struct Navigation {
+    segno: Vec<String>,
+    coda: Vec<String>,
+    dacapo: bool,
+    dalsegno: Option<String>,
+    tocoda: Option<String>,
+    fine: bool,
+}
+
+/// Collect jump markings per measure index from every part.
+fn navigation(parts: &[Part]) -> Vec<Navigation> {
+    let count = parts.iter().map(|p| p.measures.len()).max().unwrap_or(0);
+    let mut marks: Vec<Navigation> = (0..count).map(|_| Navigation::default()).collect();
+    for part in parts {
+        for (index, measure) in part.measures.iter().enumerate() {
+            let sounds = measure.content.iter().filter_map(|e| match e {
+                MusicDataElement::Direction(d) => d.sound.as_ref(),
+                _ => None,
+            });
+            let mark = &mut marks[index];
+            for sound in sounds {
+                mark.segno.extend(sound.segno.clone());
+                mark.coda.extend(sound.coda.clone());
+                mark.dacapo |= sound.dacapo == Some(YesNo::Yes);
+                mark.fine |= sound.fine.is_some();
+                if sound.dalsegno.is_some() {
+                    mark.dalsegno.clone_from(&sound.dalsegno);
+                }
+                if sound.tocoda.is_some() {
+                    mark.tocoda.clone_from(&sound.tocoda);
+                }
+            }
+        }
+    }
+    marks
+}
+
+/// Index of the measure carrying the target `name`, preferring an exact
+/// match and falling back to the first target of any name.
+fn jump_target(
+    marks: &[Navigation],
+    name: &str,
+    targets: impl Fn(&Navigation) -> &[String],
+) -> Option<usize> {
+    marks
+        .iter()
+        .position(|m| targets(m).iter().any(|t| t == name))
+        .or_else(|| marks.iter().position(|m| !targets(m).is_empty()))
+}
+
+/// The order in which measures are played with repeats and jumps expanded.
+///
+/// Repeat barlines and endings are read from the first part; jump markings
+/// may come from any part.
+fn playback_order(parts: &[Part]) -> Vec<usize> {
+    let Some(measures) = parts.first().map(|p| p.measures.as_slice()) else {
+        return Vec::new();
+    };
+    let marks = navigation(parts);
+    let mut order = Vec::new();
+    let mut section_start = 0;
+    let mut pass = 1;
+    let mut repeats_taken = vec![0u32; measures.len()];
+    let mut jumps_taken = vec![false; measures.len()];
+    // Set once a D.C. or D.S. has been followed
+    let mut jumped = false;
+    // Guard against malformed repeat structures
+    let limit = measures.len() * 16;
+
+    let mut index = 0;
+    while index < measures.len() && order.len() < limit {
+        let barlines: Vec<_> = barlines(&measures[index]).collect();
+
+        let forward = barlines.iter().any(|b| {
+            b.repeat
+                .as_ref()
+                .is_some_and(|r| r.direction == BackwardForward::Forward)
+        });
+        if forward && index != section_start {
+            section_start = index;
+            pass = 1;
+        }
+
+        // Skip a volta ending that isn't for this pass; after a jump only
+        // the final ending (the one not closed by a repeat) is played
+        let skipped_ending = barlines.iter().any(|b| {
+            b.ending
+                .as_ref()
+                .filter(|e| e.r#type == StartStopDiscontinue::Start)
+                .is_some_and(|e| {
+                    if jumped {
+                        ends_with_repeat(measures, ending_end(measures, index))
+                    } else {
+                        !ending_numbers(&e.number).contains(&pass)
+                    }
+                })
+        });
+        if skipped_ending {
+            index = ending_end(measures, index) + 1;
+            continue;
+        }
+
+        order.push(index);
+
+        let backward = barlines.iter().find_map(|b| {
+            b.repeat
+                .as_ref()
+                .filter(|r| r.direction == BackwardForward::Backward)
+        });
+        if let Some(repeat) = backward.filter(|_| !jumped) {
+            let times = repeat.times.unwrap_or(2).max(1);
+            if repeats_taken[index] + 1 < times {
+                repeats_taken[index] += 1;
+                pass += 1;
+                index = section_start;
+                continue;
+            }
+            repeats_taken[index] = 0;
+            section_start = index + 1;
+            pass = 1;
+        }
+
+        let mark = &marks[index];
+        if jumped && mark.fine {
+            break;
+        }
+        if let Some(name) = mark.tocoda.as_deref().filter(|_| jumped) {
+            if let Some(target) = jump_target(&marks, name, |m| &m.coda).filter(|&t| t > index) {
+                index = target;
+                continue;
+            }
+        }
+        if !jumps_taken[index] {
+            let target = if mark.dacapo {
+                Some(0)
+            } else {
+                mark.dalsegno
+                    .as_deref()
+                    .and_then(|name| jump_target(&marks, name, |m| &m.segno))
+            };
+            if let Some(target) = target {
+                jumps_taken[index] = true;
+                jumped = true;
+                section_start = target;
+                pass = 1;
+                index = target;
+                continue;
+            }
+        }
+        index += 1;
+    }
+    order
+}
+
+/// Barlines in a measure.
+fn barlines(measure: &Measure) -> impl Iterator<Item = &Barline> {
+    measure.content.iter().filter_map(|e| match e {
+        MusicDataElement::Barline(b) => Some(b.as_ref()),
+        _ => None,
+    })
+}
+
+/// Whether a measure closes with a backward repeat.
+fn ends_with_repeat(measures: &[Measure], index: usize) -> bool {
+    barlines(&measures[index]).any(|b| {
+        b.repeat
+            .as_ref()
+            .is_some_and(|r| r.direction == BackwardForward::Backward)
+    })
+}
+
+/// Parse an ending number list such as "1", "1, 2", or "1 2".
+fn ending_numbers(numbers: &str) -> Vec<u32> {
+    numbers
+        .split([',', ' '])
+        .filter_map(|n| n.trim().parse().ok())
+        .collect()
+}
+
+/// Index of the measure where the ending starting at `start` stops.
+fn ending_end(measures: &[Measure], start: usize) -> usize {
+    (start..measures.len())
+        .find(|&i| {
+            measures[i].content.iter().any(|e| {
+                matches!(e, MusicDataElement::Barline(b) if b.ending.as_ref().is_some_and(|e| {
+                    e.r#type != StartStopDiscontinue::Start
+                }))
+            })
+        })
+        .unwrap_or(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ir::attributes::{BarStyle, Barline, Ending, Repeat};
+    use crate::ir::common::{Editorial, RightLeftMiddle};
+
+    use super::*;
+
+    fn compile(source: &str) -> ScorePartwise {
+        crate::lang::compile(source).unwrap()
+    }
+
+    fn whole_note_measures(count: usize) -> String {
+        "(measure (note c4 :w))".repeat(count)
+    }
+
+    fn barline(
+        repeat: Option<BackwardForward>,
+        ending: Option<(&str, StartStopDiscontinue)>,
+    ) -> MusicDataElement {
+        MusicDataElement::Barline(Box::new(Barline {
+            location: Some(RightLeftMiddle::Right),
+            bar_style: Some(BarStyle::Regular),
+            editorial: Editorial::default(),
+            wavy_line: None,
+            segno: None,
+            coda: None,
+            fermatas: vec![],
+            ending: ending.map(|(number, r#type)| Ending {
+                r#type,
+                number: number.to_string(),
+                text: None,
+                print_object: None,
+                end_length: None,
+                text_x: None,
+                text_y: None,
+            }),
+            repeat: repeat.map(|direction| Repeat {
+                direction,
+                times: None,
+                winged: None,
+            }),
+        }))
+    }
+
+    fn sound_direction(sound: Sound) -> MusicDataElement {
+        MusicDataElement::Direction(Box::new(Direction {
+            placement: None,
+            directive: None,
+            direction_types: vec![],
+            offset: None,
+            voice: None,
+            staff: None,
+            sound: Some(sound),
+        }))
+    }
+
+    fn has_repeat_marks(score: &ScorePartwise) -> bool {
+        score
+            .parts
+            .iter()
+            .flat_map(|p| &p.measures)
+            .any(|m| barlines(m).any(|b| b.repeat.is_some() || b.ending.is_some()))
+    }
+
+    #[test]
+    fn test_playback_duration_eight_measures_at_120() {
+        let score = compile(&format!(
+            "(score (part :piano (measure (time 4 4) (tempo :q 120) (note c4 :w)) {}))",
+            whole_note_measures(7)
+        ));
+        let secs = score.playback_duration(false).as_secs_f64();
+        assert!((secs - 16.0).abs() < 1e-6, "got {}", secs);
+    }
+
+    #[test]
+    fn test_playback_duration_mid_piece_tempo_change() {
+        // Two measures at q=60 (8s), then a half at q=60 and a half at q=120 (2s + 1s)
+        let score = compile(
+            "(score (part :piano
+               (measure (time 4 4) (tempo :q 60) (note c4 :w))
+               (measure (note c4 :w))
+               (measure (note c4 :h) (tempo :h 60) (note c4 :h))))",
+        );
+        let secs = score.playback_duration(false).as_secs_f64();
+        assert!((secs - 11.0).abs() < 1e-6, "got {}", secs);
+    }
+
+    #[test]
+    fn test_playback_duration_takes_longest_part_and_shared_tempo() {
+        let score = compile(&format!(
+            "(score (part :flute (measure (time 4 4) (tempo :q 60) (note c4 :w))) (part :piano {}))",
+            whole_note_measures(2)
+        ));
+        // The tempo set in the flute part applies to the piano too
+        let secs = score.playback_duration(false).as_secs_f64();
+        assert!((secs - 8.0).abs() < 1e-6, "got {}", secs);
+    }
+
+    #[test]
+    fn test_playback_duration_expands_repeats_and_endings() {
+        let mut score = compile(&format!(
+            "(score (part :piano (measure (time 4 4) (note c4 :w)) {}))",
+            whole_note_measures(3)
+        ));
+        // | m1 | m2 [1. m3 :| [2. m4 ||
+        let measures = &mut score.parts[0].measures;
+        measures[2]
+            .content
+            .insert(0, barline(None, Some(("1", StartStopDiscontinue::Start))));
+        measures[2].content.push(barline(
+            Some(BackwardForward::Backward),
+            Some(("1", StartStopDiscontinue::Stop)),
+        ));
+        measures[3]
+            .content
+            .insert(0, barline(None, Some(("2", StartStopDiscontinue::Start))));
+        measures[3].content.push(barline(
+            None,
+            Some(("2", StartStopDiscontinue::Discontinue)),
+        ));
+
+        assert_eq!(playback_order(&score.parts), vec![0, 1, 2, 0, 1, 3]);
+        assert_eq!(score.playback_duration(false), Duration::from_secs(8));
+        assert_eq!(score.playback_duration(true), Duration::from_secs(12));
+    }
+
+    #[test]
+    fn test_direction_tempo_prefers_sound() {
+        let score = compile("(score (part :piano (measure (tempo :q. 60) (note c4 :q))))");
+        let direction = score.parts[0].measures[0]
+            .content
+            .iter()
+            .find_map(|e| match e {
+                MusicDataElement::Direction(d) => Some(d),
+                _ => None,
+            })
+            .unwrap();
+        // Dotted quarter = 60 is 90 quarters per minute
+        assert_eq!(direction_tempo(direction), Some(90.0));
+
+        let mut direction = direction.clone();
+        direction.sound = Some(Sound {
+            tempo: Some(72.0),
+            ..Default::default()
+        });
+        assert_eq!(direction_tempo(&direction), Some(72.0));
+    }
+
+    #[test]
+    fn test_expand_repeats_simple_repeat() {
+        let mut score = compile(&format!(
+            "(score (part :piano (measure (time 4 4) (note c4 :w)) {}))",
+            whole_note_measures(2)
+        ));
+        // |: m1 m2 :| m3
+        let measures = &mut score.parts[0].measures;
+        measures[0]
+            .content
+            .insert(0, barline(Some(BackwardForward::Forward), None));
+        measures[1]
+            .content
+            .push(barline(Some(BackwardForward::Backward), None));
+
+        let expanded = expand_repeats(&score);
+        let numbers: Vec<_> = expanded.parts[0]
+            .measures
+            .iter()
+            .map(|m| m.number.as_str())
+            .collect();
+        assert_eq!(numbers, vec!["1", "2", "3", "4", "5"]);
+        assert!(!has_repeat_marks(&expanded));
+        assert_eq!(expanded.parts[0].measures[2], {
+            let mut first = score.parts[0].measures[0].clone();
+            first.number = "3".to_string();
+            strip_navigation(&mut first);
+            first
+        });
+    }
+
+    #[test]
+    fn test_expand_repeats_keeps_pickup_number() {
+        let mut score = compile(&format!(
+            "(score (part :piano (pickup (time 4 4) (note g3 :q)) {}))",
+            whole_note_measures(2)
+        ));
+        // pickup |: m1 :| m2
+        let measures = &mut score.parts[0].measures;
+        measures[1]
+            .content
+            .insert(0, barline(Some(BackwardForward::Forward), None));
+        measures[1]
+            .content
+            .push(barline(Some(BackwardForward::Backward), None));
+
+        let expanded = expand_repeats(&score);
+        let numbers: Vec<_> = expanded.parts[0]
+            .measures
+            .iter()
+            .map(|m| m.number.as_str())
+            .collect();
+        assert_eq!(numbers, vec!["0", "1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_expand_repeats_two_endings() {
+        let mut score = compile(&format!(
+            "(score (part :piano (measure (time 4 4) (note c4 :w)) {}))",
+            whole_note_measures(3)
+        ));
+        let measures = &mut score.parts[0].measures;
+        measures[2]
+            .content
+            .insert(0, barline(None, Some(("1", StartStopDiscontinue::Start))));
+        measures[2].content.push(barline(
+            Some(BackwardForward::Backward),
+            Some(("1", StartStopDiscontinue::Stop)),
+        ));
+        measures[3]
+            .content
+            .insert(0, barline(None, Some(("2", StartStopDiscontinue::Start))));
+        measures[3].content.push(barline(
+            None,
+            Some(("2", StartStopDiscontinue::Discontinue)),
+        ));
+
+        let expanded = expand_repeats(&score);
+        assert_eq!(expanded.parts[0].measures.len(), 6);
+        assert!(!has_repeat_marks(&expanded));
+    }
+
+    #[test]
+    fn test_expand_repeats_da_capo_al_fine() {
+        let mut score = compile(&format!(
+            "(score (part :piano (measure (time 4 4) (note c4 :w)) {}))",
+            whole_note_measures(3)
+        ));
+        // m1 m2 (Fine) m3 m4 (D.C. al Fine) plays 1 2 3 4 1 2
+        let measures = &mut score.parts[0].measures;
+        measures[1].content.push(sound_direction(Sound {
+            fine: Some("yes".to_string()),
+            ..Default::default()
+        }));
+        measures[3].content.push(sound_direction(Sound {
+            dacapo: Some(YesNo::Yes),
+            ..Default::default()
+        }));
+
+        assert_eq!(playback_order(&score.parts), vec![0, 1, 2, 3, 0, 1]);
+        let expanded = expand_repeats(&score);
+        assert_eq!(expanded.parts[0].measures.len(), 6);
+        assert_eq!(expanded.playback_duration(true), Duration::from_secs(12));
+    }
+
+    #[test]
+    fn test_expand_repeats_dal_segno_al_coda() {
+        let mut score = compile(&format!(
+            "(score (part :piano (measure (time 4 4) (note c4 :w)) {}))",
+            whole_note_measures(4)
+        ));
+        // m1 m2 (segno) m3 (to coda) m4 (D.S.) m5 (coda) plays 1 2 3 4 2 3 5
+        let measures = &mut score.parts[0].measures;
+        let marks = [
+            (
+                1,
+                Sound {
+                    segno: Some("s".to_string()),
+                    ..Default::default()
+                },
+            ),
+            (
+                2,
+                Sound {
+                    tocoda: Some("c".to_string()),
+                    ..Default::default()
+                },
+            ),
+            (
+                3,
+                Sound {
+                    dalsegno: Some("s".to_string()),
+                    ..Default::default()
+                },
+            ),
+            (
+                4,
+                Sound {
+                    coda: Some("c".to_string()),
+                    ..Default::default()
+                },
+            ),
+        ];
+        for (index, sound) in marks {
+            measures[index].content.push(sound_direction(sound));
+        }
+
+        assert_eq!(playback_order(&score.parts), vec![0, 1, 2, 3, 1, 2, 4]);
+        let expanded = expand_repeats(&score);
+        assert_eq!(expanded.parts[0].measures.len(), 7);
+        // Jump markings are cleared so the expanded score plays straight through
+        assert_eq!(playback_order(&expanded.parts), (0..7).collect::<Vec<_>>());
+    }
+}
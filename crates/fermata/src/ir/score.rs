@@ -1,10 +1,16 @@
 //! Score-level types.
 
+use std::collections::HashSet;
+use std::ops::Range;
+
+use thiserror::Error;
+
 use super::common::{
-    Font, Identification, LeftCenterRight, Position, PrintStyle, StaffNumber, Tenths,
-    TopMiddleBottom, YesNo,
+    Editorial, Font, Identification, LeftCenterRight, Position, PrintStyle, StaffNumber, StartStop,
+    Tenths, TopMiddleBottom, YesNo,
 };
-use super::part::{Part, PartList};
+use super::measure::{Measure, MusicDataElement};
+use super::part::{GroupSpan, Part, PartGroup, PartList, PartListElement};
 
 /// The root score-partwise element.
 #[derive(Debug, Clone, PartialEq)]
@@ -29,6 +35,311 @@ pub struct ScorePartwise {
     pub parts: Vec<Part>,
 }
 
+impl ScorePartwise {
+    /// Insert a measure after measure number `after` in every part.
+    ///
+    /// The inserted measure is numbered `after + 1` and all later measures
+    /// with numeric numbers are shifted up by one. When `blank_or` is `None`
+    /// an empty measure is inserted; otherwise a copy of the given measure is
+    /// inserted into each part. An `after` of `0` with no measure `0` (no
+    /// pickup) inserts at the start of each part.
+    ///
+    /// Returns `false`, leaving the score unchanged, if some part has no
+    /// measure numbered `after`.
+    pub fn insert_measure(&mut self, after: u32, blank_or: Option<Measure>) -> bool {
+        let positions: Option<Vec<usize>> = self
+            .parts
+            .iter()
+            .map(|part| {
+                match part
+                    .measures
+                    .iter()
+                    .position(|m| measure_number(m) == Some(after))
+                {
+                    Some(index) => Some(index + 1),
+                    None if after == 0 => Some(0),
+                    None => None,
+                }
+            })
+            .collect();
+        let Some(positions) = positions else {
+            return false;
+        };
+
+        let template = blank_or.unwrap_or_else(|| Measure {
+            number: String::new(),
+            implicit: None,
+            non_controlling: None,
+            width: None,
+            content: vec![],
+        });
+
+        for (part, index) in self.parts.iter_mut().zip(positions) {
+            renumber_from(&mut part.measures[index..], 1);
+            let mut measure = template.clone();
+            measure.number = (after + 1).to_string();
+            measure.implicit = None;
+            part.measures.insert(index, measure);
+        }
+        true
+    }
+
+    /// Delete measure number `number` from every part.
+    ///
+    /// Later measures with numeric numbers are shifted down by one, unless the
+    /// deleted measure was an implicit (pickup) measure, which does not count
+    /// towards the numbering. A warning is logged if the deleted measure
+    /// carries a repeat or volta ending, since the repeat structure of the
+    /// score changes.
+    ///
+    /// Returns `false`, leaving the score unchanged, if some part has no
+    /// measure numbered `number`.
+    pub fn delete_measure(&mut self, number: u32) -> bool {
+        let positions: Option<Vec<usize>> = self
+            .parts
+            .iter()
+            .map(|part| {
+                part.measures
+                    .iter()
+                    .position(|m| measure_number(m) == Some(number))
+            })
+            .collect();
+        let Some(positions) = positions else {
+            return false;
+        };
+
+        for (part, index) in self.parts.iter_mut().zip(positions) {
+            let removed = part.measures.remove(index);
+            if has_repeat_structure(&removed) {
+                log::warn!(
+                    "Deleted measure {} of part {} contained a repeat or ending",
+                    number,
+                    part.id
+                );
+            }
+            if removed.implicit != Some(YesNo::Yes) {
+                renumber_from(&mut part.measures[index..], -1);
+            }
+        }
+        true
+    }
+
+    /// Pair the part list's part-group starts and stops into the groups of
+    /// parts they bracket.
+    ///
+    /// Starts and stops are matched by group number (`"1"` when absent).
+    /// A group left open runs to the last part, stops without a start are
+    /// ignored, and groups containing no parts are dropped. A group lying
+    /// wholly within another is returned among its children; groups that
+    /// merely overlap are both returned at the outermost level that
+    /// contains them.
+    pub fn part_groups(&self) -> Vec<GroupSpan> {
+        let mut part_ids: Vec<&str> = Vec::new();
+        // Open groups: number, start entry, and index of their first part
+        let mut open: Vec<(&str, &PartGroup, usize)> = Vec::new();
+        let mut spans: Vec<(&PartGroup, Range<usize>)> = Vec::new();
+
+        for element in &self.part_list.content {
+            match element {
+                PartListElement::ScorePart(part) => part_ids.push(&part.id),
+                PartListElement::PartGroup(group) => {
+                    let number = group.number.as_deref().unwrap_or("1");
+                    match group.r#type {
+                        StartStop::Start => open.push((number, group, part_ids.len())),
+                        StartStop::Stop => {
+                            if let Some(i) = open.iter().rposition(|&(n, _, _)| n == number) {
+                                let (_, start, first) = open.remove(i);
+                                spans.push((start, first..part_ids.len()));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        spans.extend(
+            open.into_iter()
+                .map(|(_, start, first)| (start, first..part_ids.len())),
+        );
+        spans.retain(|(_, range)| !range.is_empty());
+        // Outer groups before the groups they contain
+        spans.sort_by_key(|(_, range)| (range.start, std::cmp::Reverse(range.end)));
+
+        let mut roots = Vec::new();
+        // Groups still collecting children, outermost first
+        let mut stack: Vec<(Range<usize>, GroupSpan)> = Vec::new();
+        for (group, range) in spans {
+            while stack.last().is_some_and(|(outer, _)| range.end > outer.end) {
+                close_group_span(&mut stack, &mut roots);
+            }
+            let span = GroupSpan {
+                group: group.clone(),
+                part_ids: part_ids[range.clone()]
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect(),
+                children: Vec::new(),
+            };
+            stack.push((range, span));
+        }
+        while !stack.is_empty() {
+            close_group_span(&mut stack, &mut roots);
+        }
+        roots
+    }
+
+    /// Rearrange the parts to follow the part ids in `order`.
+    ///
+    /// Both the part list and the parts are reordered. Parts not named in
+    /// `order` keep their relative order after the named ones. A part group
+    /// (see [`part_groups`](Self::part_groups)) whose parts are still
+    /// adjacent is rewritten around them; one whose parts are split apart is
+    /// dropped with a warning.
+    ///
+    /// Returns an error, leaving the score unchanged, if `order` names a
+    /// part that is not in the part list or names a part twice.
+    pub fn reorder_parts(&mut self, order: &[&str]) -> Result<(), ReorderError> {
+        let part_list_ids: Vec<String> = self
+            .part_list
+            .content
+            .iter()
+            .filter_map(|element| match element {
+                PartListElement::ScorePart(part) => Some(part.id.clone()),
+                PartListElement::PartGroup(_) => None,
+            })
+            .collect();
+
+        let mut named = HashSet::new();
+        for &id in order {
+            if !part_list_ids.iter().any(|p| p == id) {
+                return Err(ReorderError::UnknownPart(id.to_string()));
+            }
+            if !named.insert(id) {
+                return Err(ReorderError::DuplicatePart(id.to_string()));
+            }
+        }
+        let new_order: Vec<String> = order
+            .iter()
+            .map(|id| id.to_string())
+            .chain(
+                part_list_ids
+                    .iter()
+                    .filter(|id| !named.contains(id.as_str()))
+                    .cloned(),
+            )
+            .collect();
+        let position = |id: &str| new_order.iter().position(|p| p == id);
+
+        // Keep the groups whose parts stay together, as (range, group)
+        let mut groups: Vec<(Range<usize>, PartGroup)> = Vec::new();
+        let mut pending = self.part_groups();
+        while let Some(span) = pending.pop() {
+            pending.extend(span.children);
+            let positions: Vec<usize> =
+                span.part_ids.iter().filter_map(|id| position(id)).collect();
+            let first = positions.iter().copied().min().unwrap_or(0);
+            let last = positions.iter().copied().max().unwrap_or(0);
+            if last - first + 1 == positions.len() {
+                groups.push((first..last + 1, span.group));
+            } else {
+                log::warn!(
+                    "Dropped part group {} because reordering separates its parts",
+                    span.group.number.as_deref().unwrap_or("1")
+                );
+            }
+        }
+        // Outer groups start first and stop last
+        groups.sort_by_key(|(range, _)| (range.start, std::cmp::Reverse(range.end)));
+
+        let mut score_parts: Vec<Option<PartListElement>> =
+            std::mem::take(&mut self.part_list.content)
+                .into_iter()
+                .filter(|element| matches!(element, PartListElement::ScorePart(_)))
+                .map(Some)
+                .collect();
+        let mut content = Vec::with_capacity(score_parts.len() + 2 * groups.len());
+        for (index, id) in new_order.iter().enumerate() {
+            for (_, group) in groups.iter().filter(|(range, _)| range.start == index) {
+                content.push(PartListElement::PartGroup(group.clone()));
+            }
+            let from = part_list_ids.iter().position(|p| p == id).unwrap_or(0);
+            content.extend(score_parts[from].take());
+            for (_, group) in groups
+                .iter()
+                .rev()
+                .filter(|(range, _)| range.end == index + 1)
+            {
+                content.push(PartListElement::PartGroup(group_stop(group)));
+            }
+        }
+        self.part_list.content = content;
+
+        // Parts missing from the part list stay at the end
+        self.parts
+            .sort_by_key(|part| position(&part.id).unwrap_or(new_order.len()));
+        Ok(())
+    }
+}
+
+/// An error from [`ScorePartwise::reorder_parts`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ReorderError {
+    /// The order names a part id that is not in the part list
+    #[error("unknown part id '{0}'")]
+    UnknownPart(String),
+    /// The order names a part id more than once
+    #[error("part id '{0}' is listed more than once")]
+    DuplicatePart(String),
+}
+
+/// The stop entry closing the part group that `start` opens.
+fn group_stop(start: &PartGroup) -> PartGroup {
+    PartGroup {
+        r#type: StartStop::Stop,
+        number: start.number.clone(),
+        group_name: None,
+        group_name_display: None,
+        group_abbreviation: None,
+        group_abbreviation_display: None,
+        group_symbol: None,
+        group_barline: None,
+        group_time: None,
+        editorial: Editorial::default(),
+    }
+}
+
+/// Pop the innermost open group span into its parent, or into `roots` if
+/// it is outermost.
+fn close_group_span(stack: &mut Vec<(Range<usize>, GroupSpan)>, roots: &mut Vec<GroupSpan>) {
+    let Some((_, span)) = stack.pop() else {
+        return;
+    };
+    match stack.last_mut() {
+        Some((_, parent)) => parent.children.push(span),
+        None => roots.push(span),
+    }
+}
+
+/// Parse a measure's number, if it is purely numeric.
+fn measure_number(measure: &Measure) -> Option<u32> {
+    measure.number.parse().ok()
+}
+
+/// Shift the numbers of numeric measures by `delta`.
+fn renumber_from(measures: &mut [Measure], delta: i64) {
+    for measure in measures {
+        if let Some(n) = measure_number(measure) {
+            measure.number = (n as i64 + delta).max(0).to_string();
+        }
+    }
+}
+
+/// Whether a measure contains a repeat barline or a volta ending.
+fn has_repeat_structure(measure: &Measure) -> bool {
+    measure.content.iter().any(|element| {
+        matches!(element, MusicDataElement::Barline(b) if b.repeat.is_some() || b.ending.is_some())
+    })
+}
+
 /// Work information.
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct Work {
@@ -54,6 +365,9 @@ pub struct Opus {
 pub struct Defaults {
     /// Scaling
     pub scaling: Option<Scaling>,
+    /// Whether the notes are written at concert pitch, so that each part's
+    /// `<transpose>` describes the instrument rather than the notes
+    pub concert_score: bool,
     /// Page layout
     pub page_layout: Option<PageLayout>,
     /// System layout
@@ -963,4 +1277,246 @@ mod tests {
         };
         assert_eq!(cs.value, "coda");
     }
+
+    // === Measure Editing Tests ===
+
+    fn numbered_measure(number: &str) -> Measure {
+        Measure {
+            number: number.to_string(),
+            implicit: None,
+            non_controlling: None,
+            width: None,
+            content: vec![],
+        }
+    }
+
+    fn two_part_score(numbers: &[&str]) -> ScorePartwise {
+        let part = |id: &str| Part {
+            id: id.to_string(),
+            measures: numbers.iter().map(|n| numbered_measure(n)).collect(),
+        };
+        ScorePartwise {
+            version: Some("4.0".to_string()),
+            work: None,
+            movement_number: None,
+            movement_title: None,
+            identification: None,
+            defaults: None,
+            credits: vec![],
+            part_list: PartList { content: vec![] },
+            parts: vec![part("P1"), part("P2")],
+        }
+    }
+
+    fn numbers(part: &Part) -> Vec<&str> {
+        part.measures.iter().map(|m| m.number.as_str()).collect()
+    }
+
+    #[test]
+    fn test_insert_measure_blank_renumbers_all_parts() {
+        let mut score = two_part_score(&["1", "2", "3"]);
+        assert!(score.insert_measure(1, None));
+
+        for part in &score.parts {
+            assert_eq!(numbers(part), vec!["1", "2", "3", "4"]);
+            assert!(part.measures[1].content.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_insert_measure_with_template() {
+        let mut score = two_part_score(&["1", "2"]);
+        let mut template = numbered_measure("99");
+        template.width = Some(120.0);
+        assert!(score.insert_measure(2, Some(template)));
+
+        for part in &score.parts {
+            assert_eq!(numbers(part), vec!["1", "2", "3"]);
+            assert_eq!(part.measures[2].width, Some(120.0));
+        }
+    }
+
+    #[test]
+    fn test_insert_measure_after_pickup() {
+        let mut score = two_part_score(&["0", "1", "2"]);
+        for part in &mut score.parts {
+            part.measures[0].implicit = Some(YesNo::Yes);
+        }
+        assert!(score.insert_measure(0, None));
+
+        for part in &score.parts {
+            assert_eq!(numbers(part), vec!["0", "1", "2", "3"]);
+            assert_eq!(part.measures[0].implicit, Some(YesNo::Yes));
+            assert_eq!(part.measures[1].implicit, None);
+        }
+    }
+
+    #[test]
+    fn test_insert_measure_at_start_without_pickup() {
+        let mut score = two_part_score(&["1", "2"]);
+        assert!(score.insert_measure(0, None));
+        assert_eq!(numbers(&score.parts[0]), vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_insert_measure_missing_leaves_score_unchanged() {
+        let mut score = two_part_score(&["1", "2"]);
+        assert!(!score.insert_measure(5, None));
+        assert_eq!(score, two_part_score(&["1", "2"]));
+    }
+
+    #[test]
+    fn test_delete_measure_interior_renumbers_all_parts() {
+        let mut score = two_part_score(&["1", "2", "3", "4"]);
+        score.parts[0].measures[2].width = Some(42.0);
+        assert!(score.delete_measure(2));
+
+        for part in &score.parts {
+            assert_eq!(numbers(part), vec!["1", "2", "3"]);
+        }
+        // The old measure 3 is now measure 2
+        assert_eq!(score.parts[0].measures[1].width, Some(42.0));
+    }
+
+    #[test]
+    fn test_delete_measure_pickup_keeps_numbering() {
+        let mut score = two_part_score(&["0", "1", "2"]);
+        score.parts[0].measures[0].implicit = Some(YesNo::Yes);
+        score.parts[1].measures[0].implicit = Some(YesNo::Yes);
+        assert!(score.delete_measure(0));
+        assert_eq!(numbers(&score.parts[1]), vec!["1", "2"]);
+    }
+
+    #[test]
+    fn test_delete_measure_missing_returns_false() {
+        let mut score = two_part_score(&["1", "2"]);
+        assert!(!score.delete_measure(3));
+        assert_eq!(score.parts[0].measures.len(), 2);
+    }
+
+    // === part_groups Tests ===
+
+    /// A score whose part list is `entries`: `"+n"` starts group n, `"-n"`
+    /// stops it, and anything else is a part id.
+    fn grouped_score(entries: &[&str]) -> ScorePartwise {
+        let xml_entries: String = entries
+            .iter()
+            .map(|entry| match entry.split_at(1) {
+                ("+", n) => format!(
+                    r#"<part-group type="start" number="{}"><group-symbol>bracket</group-symbol></part-group>"#,
+                    n
+                ),
+                ("-", n) => format!(r#"<part-group type="stop" number="{}"/>"#, n),
+                _ => format!(
+                    r#"<score-part id="{}"><part-name>{}</part-name></score-part>"#,
+                    entry, entry
+                ),
+            })
+            .collect();
+        let parts: String = entries
+            .iter()
+            .filter(|e| !e.starts_with(['+', '-']))
+            .map(|id| format!(r#"<part id="{}"><measure number="1"/></part>"#, id))
+            .collect();
+        crate::musicxml::parse(&format!(
+            r#"<score-partwise version="4.0"><part-list>{}</part-list>{}</score-partwise>"#,
+            xml_entries, parts
+        ))
+        .unwrap()
+    }
+
+    fn ids(span: &GroupSpan) -> Vec<&str> {
+        span.part_ids.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn test_part_groups_bracket_spans_three_parts() {
+        let score = grouped_score(&["+1", "P1", "P2", "P3", "-1", "P4"]);
+        let groups = score.part_groups();
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(ids(&groups[0]), ["P1", "P2", "P3"]);
+        assert!(groups[0].children.is_empty());
+        assert_eq!(
+            groups[0].group.group_symbol.as_ref().unwrap().value,
+            crate::ir::attributes::GroupSymbolValue::Bracket
+        );
+    }
+
+    #[test]
+    fn test_part_groups_nested_and_unclosed() {
+        let score = grouped_score(&["+1", "+2", "P1", "P2", "-2", "P3", "-1", "+3", "P4", "P5"]);
+        let groups = score.part_groups();
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(ids(&groups[0]), ["P1", "P2", "P3"]);
+        assert_eq!(groups[0].children.len(), 1);
+        assert_eq!(ids(&groups[0].children[0]), ["P1", "P2"]);
+        // Group 3 is never stopped and runs to the last part
+        assert_eq!(ids(&groups[1]), ["P4", "P5"]);
+    }
+
+    // === reorder_parts Tests ===
+
+    fn part_list_ids(score: &ScorePartwise) -> Vec<&str> {
+        score
+            .part_list
+            .content
+            .iter()
+            .filter_map(|element| match element {
+                PartListElement::ScorePart(part) => Some(part.id.as_str()),
+                PartListElement::PartGroup(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reorder_parts_three_parts() {
+        let mut score = grouped_score(&["P1", "P2", "P3"]);
+        score.reorder_parts(&["P3", "P1"]).unwrap();
+
+        assert_eq!(part_list_ids(&score), ["P3", "P1", "P2"]);
+        let part_ids: Vec<&str> = score.parts.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(part_ids, ["P3", "P1", "P2"]);
+    }
+
+    #[test]
+    fn test_reorder_parts_keeps_adjacent_groups_and_drops_split_ones() {
+        let mut score = grouped_score(&["+1", "P1", "P2", "-1", "+2", "P3", "P4", "-2", "P5"]);
+        score.reorder_parts(&["P3", "P4", "P1", "P5"]).unwrap();
+
+        assert_eq!(part_list_ids(&score), ["P3", "P4", "P1", "P5", "P2"]);
+        let groups = score.part_groups();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(ids(&groups[0]), ["P3", "P4"]);
+        assert!(groups[0].group.group_symbol.is_some());
+    }
+
+    #[test]
+    fn test_reorder_parts_keeps_nested_groups() {
+        let mut score = grouped_score(&["P1", "+1", "+2", "P2", "-2", "P3", "-1"]);
+        score.reorder_parts(&["P2", "P3"]).unwrap();
+
+        assert_eq!(part_list_ids(&score), ["P2", "P3", "P1"]);
+        let groups = score.part_groups();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(ids(&groups[0]), ["P2", "P3"]);
+        assert_eq!(ids(&groups[0].children[0]), ["P2"]);
+    }
+
+    #[test]
+    fn test_reorder_parts_invalid_order_leaves_score_unchanged() {
+        let mut score = grouped_score(&["+1", "P1", "P2", "-1"]);
+        let original = score.clone();
+
+        assert_eq!(
+            score.reorder_parts(&["P2", "P9"]),
+            Err(ReorderError::UnknownPart("P9".to_string()))
+        );
+        assert_eq!(
+            score.reorder_parts(&["P2", "P2"]),
+            Err(ReorderError::DuplicatePart("P2".to_string()))
+        );
+        assert_eq!(score, original);
+    }
 }
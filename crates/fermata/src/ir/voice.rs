@@ -1,6 +1,9 @@
-//! Voice-related types: backup, forward.
+//! Voice-related types: backup, forward, and the measure cursor.
+
+use std::collections::BTreeMap;
 
 use super::common::{Editorial, PositiveDivisions, StaffNumber, Voice};
+use super::measure::MusicDataElement;
 
 /// Move backward in time within a measure (for multiple voices).
 #[derive(Debug, Clone, PartialEq)]
@@ -24,6 +27,86 @@ pub struct Forward {
     pub editorial: Editorial,
 }
 
+/// Tracks the time position within a measure as its content is read in order.
+///
+/// Notes and forwards move the cursor ahead and backups move it back, as in
+/// MusicXML. Chord tails and grace notes take no time. Along the way the
+/// cursor remembers where each voice ended, so callers can find the length
+/// of the measure or how far to back up before the next voice.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeasureCursor {
+    /// Current position, in divisions from the start of the measure
+    offset: PositiveDivisions,
+    /// Start of the most recent timed note, shared by its chord tail
+    note_start: PositiveDivisions,
+    /// Furthest position reached by each voice
+    voice_ends: BTreeMap<Option<Voice>, PositiveDivisions>,
+    /// Furthest position reached by any element
+    end: PositiveDivisions,
+}
+
+impl MeasureCursor {
+    /// Create a cursor at the start of a measure.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the next element of the measure, returning the position it starts at.
+    pub fn advance(&mut self, element: &MusicDataElement) -> PositiveDivisions {
+        let start = self.offset;
+        match element {
+            MusicDataElement::Note(note) => {
+                if let Some(duration) = note.duration() {
+                    if note.is_chord_member() {
+                        return self.note_start;
+                    }
+                    self.note_start = start;
+                    self.move_to(start + duration, &note.voice);
+                }
+            }
+            MusicDataElement::Backup(backup) => {
+                self.offset = self.offset.saturating_sub(backup.duration);
+            }
+            MusicDataElement::Forward(forward) => {
+                self.move_to(start + forward.duration, &forward.voice);
+            }
+            _ => {}
+        }
+        start
+    }
+
+    /// Current position, in divisions from the start of the measure.
+    pub fn offset(&self) -> PositiveDivisions {
+        self.offset
+    }
+
+    /// Furthest position reached so far: the length of the longest voice.
+    pub fn end(&self) -> PositiveDivisions {
+        self.end
+    }
+
+    /// Furthest position reached by a voice, or `None` if it has not appeared.
+    ///
+    /// Notes and forwards without a voice are tracked under `None`.
+    pub fn voice_end(&self, voice: Option<&str>) -> Option<PositiveDivisions> {
+        self.voice_ends.get(&voice.map(str::to_string)).copied()
+    }
+
+    /// Voices seen so far with the position each one reached.
+    pub fn voice_ends(&self) -> impl Iterator<Item = (Option<&str>, PositiveDivisions)> {
+        self.voice_ends
+            .iter()
+            .map(|(voice, &end)| (voice.as_deref(), end))
+    }
+
+    fn move_to(&mut self, position: PositiveDivisions, voice: &Option<Voice>) {
+        self.offset = position;
+        self.end = self.end.max(position);
+        let voice_end = self.voice_ends.entry(voice.clone()).or_default();
+        *voice_end = (*voice_end).max(position);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,4 +304,91 @@ mod tests {
         assert!(debug_str.contains("voice"));
         assert!(debug_str.contains("staff"));
     }
+
+    // === MeasureCursor Tests ===
+
+    fn note(duration: PositiveDivisions, voice: &str, chord: bool) -> MusicDataElement {
+        use crate::ir::common::Position;
+        use crate::ir::note::{FullNote, Note, NoteContent, PitchRestUnpitched};
+        use crate::ir::pitch::{Pitch, Step};
+
+        MusicDataElement::Note(Box::new(Note {
+            position: Position::default(),
+            dynamics: None,
+            end_dynamics: None,
+            attack: None,
+            release: None,
+            pizzicato: None,
+            print_object: None,
+            content: NoteContent::Regular {
+                full_note: FullNote {
+                    chord,
+                    content: PitchRestUnpitched::Pitch(Pitch {
+                        step: Step::C,
+                        alter: None,
+                        octave: 4,
+                    }),
+                },
+                duration,
+                ties: vec![],
+            },
+            instrument: vec![],
+            voice: Some(voice.to_string()),
+            r#type: None,
+            dots: vec![],
+            accidental: None,
+            time_modification: None,
+            stem: None,
+            notehead: None,
+            staff: None,
+            beams: vec![],
+            notations: vec![],
+            lyrics: vec![],
+        }))
+    }
+
+    #[test]
+    fn test_measure_cursor_note_backup_forward() {
+        let mut cursor = MeasureCursor::new();
+        assert_eq!(cursor.offset(), 0);
+
+        assert_eq!(cursor.advance(&note(4, "1", false)), 0);
+        assert_eq!(cursor.offset(), 4);
+
+        let backup = Backup {
+            duration: 4,
+            editorial: Editorial::default(),
+        };
+        assert_eq!(cursor.advance(&MusicDataElement::Backup(backup)), 4);
+        assert_eq!(cursor.offset(), 0);
+
+        let forward = Forward {
+            duration: 2,
+            voice: Some("2".to_string()),
+            staff: None,
+            editorial: Editorial::default(),
+        };
+        assert_eq!(cursor.advance(&MusicDataElement::Forward(forward)), 0);
+        assert_eq!(cursor.offset(), 2);
+
+        assert_eq!(cursor.end(), 4);
+        assert_eq!(cursor.voice_end(Some("1")), Some(4));
+        assert_eq!(cursor.voice_end(Some("2")), Some(2));
+        assert_eq!(cursor.voice_end(None), None);
+    }
+
+    #[test]
+    fn test_measure_cursor_chord_tail_does_not_advance() {
+        let mut cursor = MeasureCursor::new();
+        cursor.advance(&note(2, "1", false));
+
+        assert_eq!(cursor.advance(&note(4, "1", false)), 2);
+        assert_eq!(cursor.advance(&note(4, "1", true)), 2);
+        assert_eq!(cursor.advance(&note(4, "1", true)), 2);
+        assert_eq!(cursor.offset(), 6);
+        assert_eq!(
+            cursor.voice_ends().collect::<Vec<_>>(),
+            vec![(Some("1"), 6)]
+        );
+    }
 }
@@ -2,7 +2,8 @@
 //!
 //! This AST captures the ergonomic forms before compilation to IR.
 
-use crate::ir::common::StartStop;
+use crate::ir::common::{AboveBelow, StartStop};
+use crate::ir::notation::TremoloType;
 
 /// A complete Fermata score
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -11,6 +12,8 @@ pub struct FermataScore {
     pub title: Option<String>,
     /// Optional composer name
     pub composer: Option<String>,
+    /// Optional opening tempo, shown above the first measure
+    pub tempo: Option<TempoMark>,
     /// Parts in the score
     pub parts: Vec<FermataPart>,
 }
@@ -24,6 +27,9 @@ pub struct FermataPart {
     pub id: Option<String>,
     /// Optional part abbreviation (e.g., "Pno.", "Vln. I")
     pub abbreviation: Option<String>,
+    /// Sounding pitch relative to written pitch, in semitones, for
+    /// transposing instruments (e.g. -2 for a B-flat clarinet)
+    pub transpose: Option<i32>,
     /// Measures in this part
     pub measures: Vec<FermataMeasure>,
 }
@@ -33,6 +39,9 @@ pub struct FermataPart {
 pub struct FermataMeasure {
     /// Optional measure number
     pub number: Option<u32>,
+    /// Whether this is a pickup (anacrusis): intentionally shorter than its
+    /// time signature and numbered before the first full measure
+    pub implicit: bool,
     /// Content elements in this measure
     pub content: Vec<MeasureElement>,
 }
@@ -44,10 +53,26 @@ pub enum MeasureElement {
     Note(FermataNote),
     /// A rest
     Rest(FermataRest),
+    /// An unpitched (percussion) note
+    Unpitched(FermataUnpitched),
     /// A chord (multiple simultaneous pitches)
     Chord(FermataChord),
     /// A tuplet wrapper
     Tuplet(FermataTuplet),
+    /// A slur spanning a group of notes
+    SlurGroup(FermataSlur),
+    /// A tie connecting notes of the same pitch
+    TieGroup(FermataTie),
+    /// A glissando or slide between consecutive notes
+    Glissando(FermataGlissando),
+    /// An octave shift (8va, 8vb, 15ma, ...) spanning a group of notes
+    Ottava(FermataOttava),
+    /// A crescendo or diminuendo hairpin spanning a group of notes
+    Hairpin(FermataHairpin),
+    /// A two-note tremolo alternating between a pair of notes
+    TremoloPair(FermataTremoloPair),
+    /// One voice of a multi-voice measure
+    Voice(FermataVoice),
     /// A grace note
     GraceNote(FermataGraceNote),
     /// A dynamic marking
@@ -90,15 +115,61 @@ pub struct FermataNote {
     /// Optional stem direction
     pub stem: Option<StemDirection>,
     /// Articulations on this note
-    pub articulations: Vec<Articulation>,
+    pub articulations: Vec<ArticulationMark>,
+    /// Dynamic marking placed before this note
+    pub dynamic: Option<DynamicMark>,
     /// Ornaments on this note
     pub ornaments: Vec<Ornament>,
+    /// Technical indications on this note (harmonics, etc.)
+    pub technical: Vec<TechnicalMark>,
     /// Tie start/stop
     pub tie: Option<StartStop>,
     /// Slur start/stop
     pub slur: Option<StartStop>,
-    /// Optional lyric
-    pub lyric: Option<LyricSpec>,
+    /// Lyrics sung on this note, one per verse
+    pub lyrics: Vec<LyricSpec>,
+    /// Whether the note is hidden (`print-object="no"`) while still taking up
+    /// time
+    pub invisible: bool,
+}
+
+impl FermataNote {
+    /// Create a plain note with no voice, marks, ties, or lyric.
+    pub fn new(pitch: FermataPitch, duration: FermataDuration) -> Self {
+        Self {
+            pitch,
+            duration,
+            voice: None,
+            staff: None,
+            stem: None,
+            articulations: Vec::new(),
+            dynamic: None,
+            ornaments: Vec::new(),
+            technical: Vec::new(),
+            tie: None,
+            slur: None,
+            lyrics: Vec::new(),
+            invisible: false,
+        }
+    }
+
+    /// Add an articulation, leaving its placement to the reader.
+    pub fn with_articulation(mut self, articulation: Articulation) -> Self {
+        self.articulations.push(articulation.into());
+        self
+    }
+
+    /// Set the dynamic marking placed before the note.
+    pub fn with_dynamic(mut self, dynamic: DynamicMark) -> Self {
+        self.dynamic = Some(dynamic);
+        self
+    }
+
+    /// Start or stop a tie on the note.
+    pub fn tied(mut self, tie: StartStop) -> Self {
+        self.tie = Some(tie);
+        self
+    }
 }
 
 /// A rest
@@ -112,6 +183,23 @@ pub struct FermataRest {
     pub staff: Option<u32>,
     /// Whether this is a whole-measure rest
     pub measure_rest: bool,
+    /// Whether the rest is hidden (`print-object="no"`), as for a spacer
+    pub invisible: bool,
+}
+
+/// An unpitched (percussion) note
+#[derive(Debug, Clone, PartialEq)]
+pub struct FermataUnpitched {
+    /// Staff position the note is drawn at (never has an accidental)
+    pub display: FermataPitch,
+    /// The duration of the note
+    pub duration: FermataDuration,
+    /// Optional score-instrument id (e.g. "P1-I39")
+    pub instrument: Option<String>,
+    /// Optional voice number
+    pub voice: Option<u32>,
+    /// Optional staff number
+    pub staff: Option<u32>,
 }
 
 /// A chord (multiple simultaneous pitches)
@@ -128,11 +216,13 @@ pub struct FermataChord {
     /// Optional stem direction
     pub stem: Option<StemDirection>,
     /// Articulations on this chord
-    pub articulations: Vec<Articulation>,
+    pub articulations: Vec<ArticulationMark>,
     /// Ornaments on this chord
     pub ornaments: Vec<Ornament>,
     /// Optional arpeggiate direction
     pub arpeggiate: Option<ArpeggiateDirection>,
+    /// Whether a bracket marks the chord as not to be rolled
+    pub non_arpeggiate: bool,
 }
 
 /// A grace note
@@ -157,6 +247,105 @@ pub struct FermataTuplet {
     pub notes: Vec<MeasureElement>,
 }
 
+/// A slur wrapper spanning its notes
+#[derive(Debug, Clone, PartialEq)]
+pub struct FermataSlur {
+    /// Notes under the slur
+    pub notes: Vec<MeasureElement>,
+}
+
+/// A tie wrapper connecting same-pitch notes
+#[derive(Debug, Clone, PartialEq)]
+pub struct FermataTie {
+    /// Tied notes and chords, in order
+    pub notes: Vec<MeasureElement>,
+}
+
+/// A glissando or slide wrapper connecting consecutive notes
+#[derive(Debug, Clone, PartialEq)]
+pub struct FermataGlissando {
+    /// Wavy glissando or straight slide
+    pub kind: GlissandoKind,
+    /// Optional text along the line (e.g. "gliss.")
+    pub text: Option<String>,
+    /// Connected notes, in order
+    pub notes: Vec<FermataNote>,
+}
+
+/// The line drawn between glissando notes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GlissandoKind {
+    /// Glissando (`<glissando>`), usually a wavy line
+    #[default]
+    Glissando,
+    /// Slide or portamento (`<slide>`), a straight line
+    Slide,
+}
+
+/// An octave shift wrapper spanning a group of notes
+#[derive(Debug, Clone, PartialEq)]
+pub struct FermataOttava {
+    /// Direction of the shift
+    pub shift: OttavaShift,
+    /// Size of the shift in scale steps (8 = one octave, 15 = two)
+    pub size: u8,
+    /// Elements (notes, rests, chords, ...) under the shift
+    pub notes: Vec<MeasureElement>,
+}
+
+/// A hairpin wrapper spanning a group of notes
+#[derive(Debug, Clone, PartialEq)]
+pub struct FermataHairpin {
+    /// Whether the hairpin opens or closes
+    pub kind: HairpinKind,
+    /// Whether the point of the hairpin is circled (dal niente / al niente)
+    pub niente: bool,
+    /// Elements (notes, rests, chords, ...) under the hairpin
+    pub notes: Vec<MeasureElement>,
+}
+
+/// A two-note tremolo alternating between a pair of notes
+#[derive(Debug, Clone, PartialEq)]
+pub struct FermataTremoloPair {
+    /// Number of tremolo beams between the notes (1-8)
+    pub marks: u8,
+    /// The note the tremolo starts on
+    pub first: FermataNote,
+    /// The note the tremolo alternates with
+    pub second: FermataNote,
+}
+
+/// Direction of a hairpin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HairpinKind {
+    /// Crescendo (opening hairpin)
+    Crescendo,
+    /// Diminuendo (closing hairpin)
+    Diminuendo,
+}
+
+/// One voice within a measure
+///
+/// Each voice starts at the beginning of the measure; the compiler inserts
+/// a backup between consecutive voices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FermataVoice {
+    /// Voice number (1 = top voice)
+    pub number: u32,
+    /// Elements (notes, rests, chords, ...) in this voice
+    pub content: Vec<MeasureElement>,
+}
+
+/// Direction of an octave shift
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OttavaShift {
+    /// Ottava alta (8va, 15ma)
+    #[default]
+    Up,
+    /// Ottava bassa (8vb, 15mb)
+    Down,
+}
+
 /// A pitch (parsed from "c4", "f#5", etc.)
 #[derive(Debug, Clone, PartialEq)]
 pub struct FermataPitch {
@@ -338,6 +527,24 @@ pub enum Articulation {
     Caesura,
 }
 
+/// An articulation and where it is drawn relative to the staff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArticulationMark {
+    /// The articulation
+    pub articulation: Articulation,
+    /// Above or below the staff; `None` leaves the choice to the reader
+    pub placement: Option<AboveBelow>,
+}
+
+impl From<Articulation> for ArticulationMark {
+    fn from(articulation: Articulation) -> Self {
+        Self {
+            articulation,
+            placement: None,
+        }
+    }
+}
+
 /// Fermata mark (separate from articulations per MusicXML/IR structure)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct FermataMark {
@@ -387,7 +594,55 @@ pub enum Ornament {
     /// Shake
     Shake,
     /// Tremolo with given number of beams
-    Tremolo(u8),
+    Tremolo {
+        /// Number of tremolo beams (1-8)
+        marks: u8,
+        /// Single-note tremolo, or one end of a two-note tremolo
+        r#type: TremoloType,
+    },
+}
+
+/// Technical indications (string, wind, and keyboard techniques)
+#[derive(Debug, Clone, PartialEq)]
+pub enum TechnicalMark {
+    /// String harmonic
+    Harmonic(HarmonicSpec),
+    /// Guitar bend
+    Bend(BendSpec),
+    /// String to play the note on, counted from the highest (1)
+    String(u8),
+    /// Fret to stop the string at (0 = open string)
+    Fret(u8),
+}
+
+/// A guitar bend
+#[derive(Debug, Clone, PartialEq)]
+pub struct BendSpec {
+    /// How far the string is bent, in semitones (2 = a whole-step bend)
+    pub alter: f64,
+    /// Whether the string is bent before it is struck
+    pub pre_bend: bool,
+    /// Whether the bend is released back to the original pitch
+    pub release: bool,
+}
+
+/// A string harmonic
+#[derive(Debug, Clone, PartialEq)]
+pub struct HarmonicSpec {
+    /// Natural or artificial harmonic
+    pub kind: HarmonicKind,
+    /// Touching pitch for artificial harmonics (shown as a diamond notehead)
+    pub touching: Option<FermataPitch>,
+}
+
+/// Harmonic kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HarmonicKind {
+    /// Natural harmonic (open string)
+    #[default]
+    Natural,
+    /// Artificial harmonic (stopped string)
+    Artificial,
 }
 
 /// Arpeggio direction
@@ -483,18 +738,41 @@ pub struct TempoMark {
 pub enum FermataDirection {
     /// Text direction (performance instructions)
     Words(String),
+    /// Expressive text with placement and font, e.g. `(text "dolce" :italic)`
+    Text(TextMark),
     /// Rehearsal mark
     Rehearsal(String),
     /// Segno sign
     Segno,
     /// Coda sign
     Coda,
+    /// "To Coda": jump to the coda sign
+    ToCoda,
+    /// Dal segno: jump back to the segno sign
+    DalSegno,
+    /// Da capo: jump back to the beginning
+    DaCapo,
+    /// Fine: where the music ends after a jump
+    Fine,
     /// Pedal start
     PedalStart,
     /// Pedal stop
     PedalStop,
 }
 
+/// Expressive text such as "dolce" or "rit."
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextMark {
+    /// The text to print
+    pub text: String,
+    /// Above or below the staff; `None` leaves the choice to the reader
+    pub placement: Option<AboveBelow>,
+    /// Set in italics
+    pub italic: bool,
+    /// Set in bold
+    pub bold: bool,
+}
+
 /// Key signature specification
 #[derive(Debug, Clone, PartialEq)]
 pub struct KeySpec {
@@ -586,9 +864,11 @@ pub enum ClefSpec {
     Percussion,
     /// Tab clef
     Tab,
+    /// No clef displayed
+    None,
     /// Custom clef with explicit sign, line, and octave change
     Custom {
-        /// Clef sign (G, F, C, etc.)
+        /// Clef sign: G, F, C, P (percussion), or T (tablature)
         sign: char,
         /// Staff line (1-5)
         line: u8,
@@ -621,7 +901,10 @@ pub enum BarlineSpec {
     /// Repeat forward (start repeat)
     RepeatForward,
     /// Repeat backward (end repeat)
-    RepeatBackward,
+    RepeatBackward {
+        /// Total number of times the passage is played, if not twice
+        times: Option<u32>,
+    },
     /// Repeat both directions
     RepeatBoth,
     /// Ending bracket
@@ -667,6 +950,8 @@ pub struct LyricSpec {
     pub syllabic: Syllabic,
     /// Optional verse number
     pub verse: Option<u8>,
+    /// Whether a melisma line extends from the syllable
+    pub extend: bool,
 }
 
 /// Syllabic type for lyrics
@@ -735,6 +1020,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fermata_note_builder_staccato_forte_quarter() {
+        let pitch = FermataPitch {
+            step: PitchStep::C,
+            alter: None,
+            octave: 4,
+        };
+        let note = FermataNote::new(pitch.clone(), FermataDuration::default())
+            .with_articulation(Articulation::Staccato)
+            .with_dynamic(DynamicMark::F)
+            .tied(StartStop::Start);
+
+        assert_eq!(note.pitch, pitch);
+        assert_eq!(note.duration.base, DurationBase::Quarter);
+        assert_eq!(note.duration.dots, 0);
+        assert_eq!(note.articulations, vec![Articulation::Staccato.into()]);
+        assert_eq!(note.dynamic, Some(DynamicMark::F));
+        assert_eq!(note.tie, Some(StartStop::Start));
+        assert!(note.slur.is_none() && note.lyrics.is_empty());
+    }
+
     #[test]
     fn test_pitch_alter_sharp_semitones() {
         assert_eq!(PitchAlter::Sharp.to_semitones(), 1.0);
@@ -901,6 +1207,7 @@ mod tests {
             name: "Piano".to_string(),
             id: Some("P1".to_string()),
             abbreviation: Some("Pno.".to_string()),
+            transpose: None,
             measures: vec![],
         };
         let cloned = part.clone();
@@ -948,10 +1255,13 @@ mod tests {
             staff: None,
             stem: None,
             articulations: vec![],
+            dynamic: None,
             ornaments: vec![],
+            technical: vec![],
             tie: None,
             slur: None,
-            lyric: None,
+            lyrics: vec![],
+            invisible: false,
         };
         let elem = MeasureElement::Note(note);
         if let MeasureElement::Note(n) = elem {
@@ -7,7 +7,9 @@ use crate::ir::attributes::Mode as IrMode;
 use crate::ir::attributes::{
     Clef, ClefSign, Key, KeyContent, Time, TimeContent, TimeSignature, TimeSymbol, TraditionalKey,
 };
+use crate::ir::pitch::Step as IrStep;
 use crate::sexpr::Sexpr;
+use crate::theory::fifths_for;
 
 use super::ast::{ClefSpec, KeySpec, Mode as FermataMode, PitchAlter, PitchStep, TimeSpec};
 use super::error::{CompileError, CompileResult};
@@ -51,8 +53,9 @@ pub fn compile_key(sexpr: &Sexpr) -> CompileResult<Key> {
 
 /// Parse key arguments into a KeySpec.
 ///
-/// Expected form: `<root> <mode>` where root is like "c", "f#", "bb"
-/// and mode is a keyword like `:major`, `:minor`, `:dorian`.
+/// Expected form: `<root> <mode>` where root is like "c", "f#", "bb" (or
+/// the same as a keyword, `:d`) and mode is a keyword like `:major`,
+/// `:minor`, `:dorian`.
 pub fn parse_key_form(args: &[Sexpr]) -> CompileResult<KeySpec> {
     if args.len() < 2 {
         return Err(CompileError::InvalidKey(
@@ -63,6 +66,7 @@ pub fn parse_key_form(args: &[Sexpr]) -> CompileResult<KeySpec> {
     // First argument: root (e.g., "c", "f#", "bb")
     let root_str = args[0]
         .as_symbol()
+        .or_else(|| args[0].as_keyword())
         .ok_or_else(|| CompileError::InvalidKey("expected root symbol".to_string()))?;
 
     let (root, root_alter) = parse_key_root(root_str)?;
@@ -154,18 +158,7 @@ pub fn parse_mode(s: &str) -> CompileResult<FermataMode> {
 /// Compile a KeySpec into an IR Key.
 pub fn compile_key_spec(spec: &KeySpec) -> CompileResult<Key> {
     let fifths = compute_fifths(spec.root, spec.root_alter.as_ref(), &spec.mode);
-
-    let ir_mode = match spec.mode {
-        FermataMode::Major => IrMode::Major,
-        FermataMode::Minor => IrMode::Minor,
-        FermataMode::Dorian => IrMode::Dorian,
-        FermataMode::Phrygian => IrMode::Phrygian,
-        FermataMode::Lydian => IrMode::Lydian,
-        FermataMode::Mixolydian => IrMode::Mixolydian,
-        FermataMode::Aeolian => IrMode::Aeolian,
-        FermataMode::Ionian => IrMode::Ionian,
-        FermataMode::Locrian => IrMode::Locrian,
-    };
+    let ir_mode = compile_mode(&spec.mode);
 
     Ok(Key {
         content: KeyContent::Traditional(TraditionalKey {
@@ -180,57 +173,42 @@ pub fn compile_key_spec(spec: &KeySpec) -> CompileResult<Key> {
 
 /// Compute the circle of fifths position for a key.
 ///
-/// The calculation:
-/// 1. Start with the base fifths for the root in major mode
-/// 2. Add 7 for sharp, subtract 7 for flat
-/// 3. Adjust for mode
-///
-/// Base fifths (major mode):
-/// - C = 0, G = 1, D = 2, A = 3, E = 4, B = 5, F# = 6
-/// - F = -1, Bb = -2, Eb = -3, Ab = -4, Db = -5, Gb = -6, Cb = -7
-///
-/// Mode adjustments (relative to major):
-/// - Ionian = 0 (same as major)
-/// - Dorian = -2
-/// - Phrygian = -4
-/// - Lydian = +1
-/// - Mixolydian = -1
-/// - Aeolian = -3 (same as minor)
-/// - Locrian = -5
-/// - Minor = -3
+/// Delegates to [`fifths_for`], which covers every mode and respells keys
+/// beyond seven sharps or flats. Quarter-tone and natural alterations on
+/// the root are ignored.
 pub fn compute_fifths(root: PitchStep, root_alter: Option<&PitchAlter>, mode: &FermataMode) -> i8 {
-    // Base fifths for natural roots in major mode
-    let base = match root {
-        PitchStep::C => 0,
-        PitchStep::G => 1,
-        PitchStep::D => 2,
-        PitchStep::A => 3,
-        PitchStep::E => 4,
-        PitchStep::B => 5,
-        PitchStep::F => -1,
+    let step = match root {
+        PitchStep::C => IrStep::C,
+        PitchStep::D => IrStep::D,
+        PitchStep::E => IrStep::E,
+        PitchStep::F => IrStep::F,
+        PitchStep::G => IrStep::G,
+        PitchStep::A => IrStep::A,
+        PitchStep::B => IrStep::B,
     };
-
-    // Adjust for alteration (sharp adds 7, flat subtracts 7)
-    let alter_adjustment = match root_alter {
-        Some(PitchAlter::Sharp) => 7,
-        Some(PitchAlter::Flat) => -7,
-        Some(PitchAlter::DoubleSharp) => 14,
-        Some(PitchAlter::DoubleFlat) => -14,
+    let alter = match root_alter {
+        Some(PitchAlter::Sharp) => 1,
+        Some(PitchAlter::Flat) => -1,
+        Some(PitchAlter::DoubleSharp) => 2,
+        Some(PitchAlter::DoubleFlat) => -2,
         _ => 0,
     };
+    fifths_for(step, alter, compile_mode(mode))
+}
 
-    // Mode adjustment
-    let mode_adjustment = match mode {
-        FermataMode::Major | FermataMode::Ionian => 0,
-        FermataMode::Minor | FermataMode::Aeolian => -3,
-        FermataMode::Dorian => -2,
-        FermataMode::Phrygian => -4,
-        FermataMode::Lydian => 1,
-        FermataMode::Mixolydian => -1,
-        FermataMode::Locrian => -5,
-    };
-
-    base + alter_adjustment + mode_adjustment
+/// Convert a Fermata mode to its IR equivalent.
+fn compile_mode(mode: &FermataMode) -> IrMode {
+    match mode {
+        FermataMode::Major => IrMode::Major,
+        FermataMode::Minor => IrMode::Minor,
+        FermataMode::Dorian => IrMode::Dorian,
+        FermataMode::Phrygian => IrMode::Phrygian,
+        FermataMode::Lydian => IrMode::Lydian,
+        FermataMode::Mixolydian => IrMode::Mixolydian,
+        FermataMode::Aeolian => IrMode::Aeolian,
+        FermataMode::Ionian => IrMode::Ionian,
+        FermataMode::Locrian => IrMode::Locrian,
+    }
 }
 
 // =============================================================================
@@ -397,6 +375,7 @@ pub fn compile_time_spec(spec: &TimeSpec) -> CompileResult<Time> {
 /// - `(clef :bass)`
 /// - `(clef :alto)`
 /// - `(clef :treble-8vb)`
+/// - `(clef :c 1)` (a clef moved to another line)
 pub fn compile_clef(sexpr: &Sexpr) -> CompileResult<Clef> {
     let args = sexpr
         .as_list()
@@ -419,19 +398,54 @@ pub fn compile_clef(sexpr: &Sexpr) -> CompileResult<Clef> {
         )));
     }
 
-    if args.len() < 2 {
-        return Err(CompileError::InvalidClef(
-            "clef requires a type".to_string(),
-        ));
-    }
+    let spec = parse_clef_args(&args[1..])?;
+    compile_clef_spec(&spec)
+}
 
-    // Second argument: clef type keyword
-    let clef_name = args[1]
+/// Parse the arguments of a clef form: a clef name and an optional line.
+///
+/// A line moves the named clef's sign to that staff line, so `(clef :c 1)`
+/// is a soprano clef and `(clef :tab 6)` a tablature clef on line 6. The
+/// result is a [`ClefSpec::Custom`] unless no line is given.
+pub fn parse_clef_args(args: &[Sexpr]) -> CompileResult<ClefSpec> {
+    let clef_name = args
+        .first()
+        .ok_or_else(|| CompileError::InvalidClef("clef requires a type".to_string()))?
         .as_keyword()
         .ok_or_else(|| CompileError::InvalidClef("expected clef type keyword".to_string()))?;
-
     let spec = parse_clef_name(clef_name)?;
-    compile_clef_spec(&spec)
+
+    match args.get(1) {
+        None => Ok(spec),
+        Some(line) => {
+            let line = line
+                .as_integer()
+                .and_then(|n| u8::try_from(n).ok())
+                .filter(|&n| n >= 1)
+                .ok_or_else(|| {
+                    CompileError::InvalidClef(format!("expected a staff line, got {:?}", line))
+                })?;
+            let clef = compile_clef_spec(&spec)?;
+            let sign = match clef.sign {
+                ClefSign::G => 'G',
+                ClefSign::F => 'F',
+                ClefSign::C => 'C',
+                ClefSign::Percussion => 'P',
+                ClefSign::Tab => 'T',
+                ClefSign::Jianpu | ClefSign::None => {
+                    return Err(CompileError::InvalidClef(format!(
+                        "{} clef takes no line",
+                        clef_name
+                    )));
+                }
+            };
+            Ok(ClefSpec::Custom {
+                sign,
+                line,
+                octave_change: clef.octave_change,
+            })
+        }
+    }
 }
 
 /// Parse a clef name keyword into a ClefSpec.
@@ -441,12 +455,16 @@ pub fn parse_clef_name(name: &str) -> CompileResult<ClefSpec> {
         "bass" | "f" => Ok(ClefSpec::Bass),
         "alto" | "c" => Ok(ClefSpec::Alto),
         "tenor" => Ok(ClefSpec::Tenor),
+        "soprano" => Ok(c_clef(1)),
+        "mezzo-soprano" | "mezzo" => Ok(c_clef(2)),
+        "baritone" => Ok(c_clef(5)),
         "treble-8vb" | "treble8vb" | "g-8vb" => Ok(ClefSpec::Treble8vb),
         "treble-8va" | "treble8va" | "g-8va" => Ok(ClefSpec::Treble8va),
         "bass-8vb" | "bass8vb" | "f-8vb" => Ok(ClefSpec::Bass8vb),
         "bass-8va" | "bass8va" | "f-8va" => Ok(ClefSpec::Bass8va),
         "percussion" | "perc" => Ok(ClefSpec::Percussion),
         "tab" => Ok(ClefSpec::Tab),
+        "none" => Ok(ClefSpec::None),
         _ => Err(CompileError::InvalidClef(format!(
             "unknown clef type: {}",
             name
@@ -454,6 +472,15 @@ pub fn parse_clef_name(name: &str) -> CompileResult<ClefSpec> {
     }
 }
 
+/// A C clef on `line`, for the C clefs without a named variant.
+fn c_clef(line: u8) -> ClefSpec {
+    ClefSpec::Custom {
+        sign: 'C',
+        line,
+        octave_change: None,
+    }
+}
+
 /// Compile a ClefSpec into an IR Clef.
 pub fn compile_clef_spec(spec: &ClefSpec) -> CompileResult<Clef> {
     let (sign, line, octave_change) = match spec {
@@ -467,6 +494,7 @@ pub fn compile_clef_spec(spec: &ClefSpec) -> CompileResult<Clef> {
         ClefSpec::Bass8va => (ClefSign::F, Some(4), Some(1)),
         ClefSpec::Percussion => (ClefSign::Percussion, None, None),
         ClefSpec::Tab => (ClefSign::Tab, Some(5), None),
+        ClefSpec::None => (ClefSign::None, None, None),
         ClefSpec::Custom {
             sign,
             line,
@@ -476,6 +504,8 @@ pub fn compile_clef_spec(spec: &ClefSpec) -> CompileResult<Clef> {
                 'G' | 'g' => ClefSign::G,
                 'F' | 'f' => ClefSign::F,
                 'C' | 'c' => ClefSign::C,
+                'P' | 'p' => ClefSign::Percussion,
+                'T' | 't' => ClefSign::Tab,
                 _ => {
                     return Err(CompileError::InvalidClef(format!(
                         "unknown clef sign: {}",
@@ -497,6 +527,174 @@ pub fn compile_clef_spec(spec: &ClefSpec) -> CompileResult<Clef> {
     })
 }
 
+impl TryFrom<&ClefSpec> for Clef {
+    type Error = CompileError;
+
+    /// Convert a clef specification into an IR clef (see [`compile_clef_spec`]).
+    fn try_from(spec: &ClefSpec) -> CompileResult<Self> {
+        compile_clef_spec(spec)
+    }
+}
+
+impl TryFrom<&Clef> for ClefSpec {
+    type Error = CompileError;
+
+    /// Recover a clef specification from an IR clef.
+    ///
+    /// Standard sign/line/octave combinations map to their named variants;
+    /// other G, F, C, percussion, and tablature clefs become
+    /// [`ClefSpec::Custom`]. A missing line takes the sign's usual line.
+    /// Jianpu clefs have no equivalent and are rejected.
+    fn try_from(clef: &Clef) -> CompileResult<Self> {
+        let octave_change = clef.octave_change.filter(|&o| o != 0);
+        let spec = match (clef.sign, clef.line, octave_change) {
+            (ClefSign::Percussion, None, None) => ClefSpec::Percussion,
+            (ClefSign::Tab, None | Some(5), None) => ClefSpec::Tab,
+            (ClefSign::None, _, _) => ClefSpec::None,
+            (ClefSign::G, None | Some(2), None) => ClefSpec::Treble,
+            (ClefSign::G, None | Some(2), Some(-1)) => ClefSpec::Treble8vb,
+            (ClefSign::G, None | Some(2), Some(1)) => ClefSpec::Treble8va,
+            (ClefSign::F, None | Some(4), None) => ClefSpec::Bass,
+            (ClefSign::F, None | Some(4), Some(-1)) => ClefSpec::Bass8vb,
+            (ClefSign::F, None | Some(4), Some(1)) => ClefSpec::Bass8va,
+            (ClefSign::C, None | Some(3), None) => ClefSpec::Alto,
+            (ClefSign::C, Some(4), None) => ClefSpec::Tenor,
+            (
+                sign @ (ClefSign::G
+                | ClefSign::F
+                | ClefSign::C
+                | ClefSign::Percussion
+                | ClefSign::Tab),
+                line,
+                octave_change,
+            ) => {
+                let (sign, default_line) = match sign {
+                    ClefSign::G => ('G', 2),
+                    ClefSign::F => ('F', 4),
+                    ClefSign::Percussion => ('P', 3),
+                    ClefSign::Tab => ('T', 5),
+                    _ => ('C', 3),
+                };
+                ClefSpec::Custom {
+                    sign,
+                    line: line.unwrap_or(default_line),
+                    octave_change,
+                }
+            }
+            (sign, _, _) => {
+                return Err(CompileError::InvalidClef(format!(
+                    "no clef specification for sign {:?}",
+                    sign
+                )));
+            }
+        };
+        Ok(spec)
+    }
+}
+
+impl TryFrom<&Key> for KeySpec {
+    type Error = CompileError;
+
+    /// Recover a key specification from an IR key.
+    ///
+    /// The root is found by undoing the mode adjustment of
+    /// [`compute_fifths`]; a missing mode (or `none`) reads as major.
+    /// Non-traditional keys and roots beyond seven sharps or flats are
+    /// rejected.
+    fn try_from(key: &Key) -> CompileResult<Self> {
+        let KeyContent::Traditional(traditional) = &key.content else {
+            return Err(CompileError::InvalidKey(
+                "no key specification for a non-traditional key".to_string(),
+            ));
+        };
+
+        let mode = match traditional.mode {
+            None | Some(IrMode::Major) | Some(IrMode::None) => FermataMode::Major,
+            Some(IrMode::Minor) => FermataMode::Minor,
+            Some(IrMode::Dorian) => FermataMode::Dorian,
+            Some(IrMode::Phrygian) => FermataMode::Phrygian,
+            Some(IrMode::Lydian) => FermataMode::Lydian,
+            Some(IrMode::Mixolydian) => FermataMode::Mixolydian,
+            Some(IrMode::Aeolian) => FermataMode::Aeolian,
+            Some(IrMode::Ionian) => FermataMode::Ionian,
+            Some(IrMode::Locrian) => FermataMode::Locrian,
+        };
+
+        // The major key with the same signature, shifted back by the mode
+        let major_fifths = traditional.fifths - compute_fifths(PitchStep::C, None, &mode);
+        let (root, root_alter) = match major_fifths {
+            -7 => (PitchStep::C, Some(PitchAlter::Flat)),
+            -6 => (PitchStep::G, Some(PitchAlter::Flat)),
+            -5 => (PitchStep::D, Some(PitchAlter::Flat)),
+            -4 => (PitchStep::A, Some(PitchAlter::Flat)),
+            -3 => (PitchStep::E, Some(PitchAlter::Flat)),
+            -2 => (PitchStep::B, Some(PitchAlter::Flat)),
+            -1 => (PitchStep::F, None),
+            0 => (PitchStep::C, None),
+            1 => (PitchStep::G, None),
+            2 => (PitchStep::D, None),
+            3 => (PitchStep::A, None),
+            4 => (PitchStep::E, None),
+            5 => (PitchStep::B, None),
+            6 => (PitchStep::F, Some(PitchAlter::Sharp)),
+            7 => (PitchStep::C, Some(PitchAlter::Sharp)),
+            _ => {
+                return Err(CompileError::InvalidKey(format!(
+                    "no key specification for {} fifths in {:?}",
+                    traditional.fifths, mode
+                )));
+            }
+        };
+
+        Ok(KeySpec {
+            root,
+            root_alter,
+            mode,
+        })
+    }
+}
+
+impl TryFrom<&Time> for TimeSpec {
+    type Error = CompileError;
+
+    /// Recover a time specification from an IR time signature.
+    ///
+    /// Common and cut symbols map to their named variants, several
+    /// signatures to [`TimeSpec::Compound`]. Additive beats such as `3+2`
+    /// are rejected.
+    fn try_from(time: &Time) -> CompileResult<Self> {
+        let signatures = match &time.content {
+            TimeContent::SenzaMisura(_) => return Ok(TimeSpec::SenzaMisura),
+            TimeContent::Measured { signatures } => signatures,
+        };
+        let number = |text: &str| {
+            text.trim().parse::<u8>().map_err(|_| {
+                CompileError::InvalidTime(format!("no time specification for '{}'", text))
+            })
+        };
+        let signatures = signatures
+            .iter()
+            .map(|s| Ok((number(&s.beats)?, number(&s.beat_type)?)))
+            .collect::<CompileResult<Vec<_>>>()?;
+
+        let spec = match (time.symbol, signatures.as_slice()) {
+            (Some(TimeSymbol::Common), [(4, 4)]) => TimeSpec::Common,
+            (Some(TimeSymbol::Cut), [(2, 2)]) => TimeSpec::Cut,
+            (_, [(beats, beat_type)]) => TimeSpec::Simple {
+                beats: *beats,
+                beat_type: *beat_type,
+            },
+            (_, []) => {
+                return Err(CompileError::InvalidTime(
+                    "time signature has no beats".to_string(),
+                ));
+            }
+            _ => TimeSpec::Compound { signatures },
+        };
+        Ok(spec)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -946,6 +1144,31 @@ mod tests {
             assert_eq!(compute_fifths(PitchStep::E, None, &FermataMode::Minor), 1);
             assert_eq!(compute_fifths(PitchStep::D, None, &FermataMode::Minor), -1);
         }
+
+        #[test]
+        fn test_parse_key_form_keyword_root() {
+            let sexpr = parse("(key :bb :minor)").unwrap();
+            let spec = parse_key_form(&sexpr.as_list().unwrap()[1..]).unwrap();
+            assert_eq!(spec.root, PitchStep::B);
+            assert_eq!(spec.root_alter, Some(PitchAlter::Flat));
+            assert_eq!(spec.mode, FermataMode::Minor);
+        }
+
+        #[test]
+        fn test_compile_key_change_mid_piece_emits_fifths() {
+            let score = crate::lang::compile(
+                "(score (part :piano
+                   (measure (key :d :major) (time 3 4) (note d4 :h.))
+                   (measure (key :a :major) (note a4 :h.))))",
+            )
+            .unwrap();
+
+            let xml = crate::musicxml::emit(&score).unwrap();
+
+            let second = &xml[xml.find("<measure number=\"2\"").unwrap()..];
+            assert!(second.contains("<fifths>3</fifths>"), "{}", second);
+            assert!(second.contains("<mode>major</mode>"), "{}", second);
+        }
     }
 
     // =============================================================================
@@ -1241,5 +1464,100 @@ mod tests {
             };
             assert!(compile_clef_spec(&spec).is_err());
         }
+
+        #[test]
+        fn test_clef_spec_conversions_round_trip() {
+            let cases = [
+                (ClefSpec::Treble, ClefSign::G, Some(2)),
+                (ClefSpec::Bass, ClefSign::F, Some(4)),
+                (ClefSpec::Alto, ClefSign::C, Some(3)),
+                (ClefSpec::Tenor, ClefSign::C, Some(4)),
+                (ClefSpec::Percussion, ClefSign::Percussion, None),
+            ];
+            for (spec, sign, line) in cases {
+                let clef = Clef::try_from(&spec).unwrap();
+                assert_eq!((clef.sign, clef.line), (sign, line), "{:?}", spec);
+                assert_eq!(ClefSpec::try_from(&clef).unwrap(), spec);
+            }
+        }
+
+        #[test]
+        fn test_clef_spec_try_from_clef_unusual() {
+            let mut clef = compile_clef_spec(&ClefSpec::Bass8vb).unwrap();
+            assert_eq!(ClefSpec::try_from(&clef).unwrap(), ClefSpec::Bass8vb);
+
+            // Baritone C clef has no named variant
+            clef.sign = ClefSign::C;
+            clef.line = Some(5);
+            clef.octave_change = Some(0);
+            assert_eq!(
+                ClefSpec::try_from(&clef).unwrap(),
+                ClefSpec::Custom {
+                    sign: 'C',
+                    line: 5,
+                    octave_change: None,
+                }
+            );
+
+            clef.sign = ClefSign::Jianpu;
+            assert!(ClefSpec::try_from(&clef).is_err());
+        }
+
+        #[test]
+        fn test_compile_clef_with_line_moves_sign() {
+            let cases = [
+                ("(clef :c 1)", ClefSign::C, Some(1), None),
+                ("(clef :soprano)", ClefSign::C, Some(1), None),
+                ("(clef :baritone)", ClefSign::C, Some(5), None),
+                ("(clef :percussion 2)", ClefSign::Percussion, Some(2), None),
+                ("(clef :tab 6)", ClefSign::Tab, Some(6), None),
+                ("(clef :treble-8vb 1)", ClefSign::G, Some(1), Some(-1)),
+                ("(clef :none)", ClefSign::None, None, None),
+            ];
+            for (source, sign, line, octave_change) in cases {
+                let clef = compile_clef(&parse(source).unwrap()).unwrap();
+                assert_eq!(
+                    (clef.sign, clef.line, clef.octave_change),
+                    (sign, line, octave_change),
+                    "{}",
+                    source
+                );
+            }
+
+            for source in ["(clef :none 2)", "(clef :c 0)", "(clef :c :high)"] {
+                assert!(compile_clef(&parse(source).unwrap()).is_err(), "{}", source);
+            }
+        }
+
+        #[test]
+        fn test_clef_spec_try_from_tab_percussion_and_none() {
+            let tab = |line| Clef {
+                sign: ClefSign::Tab,
+                line,
+                octave_change: None,
+                number: None,
+                size: None,
+                print_object: None,
+            };
+            assert_eq!(ClefSpec::try_from(&tab(Some(5))).unwrap(), ClefSpec::Tab);
+            let moved = ClefSpec::try_from(&tab(Some(4))).unwrap();
+            assert_eq!(
+                moved,
+                ClefSpec::Custom {
+                    sign: 'T',
+                    line: 4,
+                    octave_change: None,
+                }
+            );
+            assert_eq!(Clef::try_from(&moved).unwrap(), tab(Some(4)));
+
+            let mut percussion = compile_clef_spec(&ClefSpec::Percussion).unwrap();
+            percussion.line = Some(2);
+            let spec = ClefSpec::try_from(&percussion).unwrap();
+            assert_eq!(Clef::try_from(&spec).unwrap(), percussion);
+
+            let none = compile_clef_spec(&ClefSpec::None).unwrap();
+            assert_eq!(ClefSpec::try_from(&none).unwrap(), ClefSpec::None);
+        }
     }
 }
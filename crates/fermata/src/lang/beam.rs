@@ -0,0 +1,230 @@
+//! Automatic beaming for compiled measures.
+//!
+//! Fermata source has no beam syntax; instead, consecutive eighth-or-shorter
+//! notes are beamed within each beat group of the time signature. Simple
+//! meters group by beat, compound meters (6/8, 9/8, 12/8, 3/8) by dotted
+//! beat, and additive meters such as 3+2/8 by their addends. Rests and
+//! longer notes break beams, and secondary beams follow the sixteenths,
+//! thirty-seconds, and so on inside each group.
+
+use std::collections::HashMap;
+
+use crate::ir::attributes::{TimeContent, TimeSignature};
+use crate::ir::beam::{Beam, BeamValue};
+use crate::ir::duration::NoteTypeValue;
+use crate::ir::measure::{Measure, MusicDataElement};
+use crate::ir::voice::MeasureCursor;
+use crate::lang::defaults::DEFAULT_DIVISIONS;
+
+/// Assign `<beam>` elements to the notes of a measure.
+///
+/// Durations are expected in [`DEFAULT_DIVISIONS`], as produced by
+/// measure compilation. Each voice is beamed separately; chord members
+/// share their first note's beams, and grace notes are left alone.
+/// Measures without a meter (senza misura) are not beamed.
+pub fn auto_beam(measure: &mut Measure, time: &TimeContent) {
+    let TimeContent::Measured { signatures } = time else {
+        return;
+    };
+    let Some(boundaries) = beat_group_starts(signatures) else {
+        return;
+    };
+    let group_of = |position: u64| boundaries.iter().filter(|&&b| b <= position).count();
+
+    // Runs of beamable notes per voice: (content index, beam levels)
+    let mut runs: HashMap<Option<String>, Vec<(usize, u8)>> = HashMap::new();
+    let mut groups: Vec<Vec<(usize, u8)>> = Vec::new();
+    // Beat group of the run currently open in each voice
+    let mut run_group: HashMap<Option<String>, usize> = HashMap::new();
+    let mut cursor = MeasureCursor::new();
+
+    for (index, element) in measure.content.iter().enumerate() {
+        let start = cursor.advance(element);
+        let MusicDataElement::Note(note) = element else {
+            continue;
+        };
+        let Some(duration) = note.duration() else {
+            continue;
+        };
+        if note.is_chord_member() {
+            continue;
+        }
+        let position = start + duration;
+
+        let voice = note.voice.clone();
+        let levels = match &note.r#type {
+            Some(note_type) if !note.is_rest() => beam_levels(note_type.value),
+            _ => 0,
+        };
+        let group = group_of(start);
+        let fits = duration > 0 && group_of(position - 1) == group;
+
+        let run = runs.entry(voice.clone()).or_default();
+        if levels == 0 || !fits || run_group.get(&voice) != Some(&group) {
+            groups.push(std::mem::take(run));
+        }
+        if levels > 0 && fits {
+            run.push((index, levels));
+            run_group.insert(voice, group);
+        } else {
+            run_group.remove(&voice);
+        }
+    }
+    groups.extend(runs.into_values());
+
+    for group in groups.into_iter().filter(|g| g.len() > 1) {
+        for (i, &(index, levels)) in group.iter().enumerate() {
+            let before = i.checked_sub(1).map_or(0, |j| group[j].1);
+            let after = group.get(i + 1).map_or(0, |next| next.1);
+            let beams = (1..=levels)
+                .map(|number| {
+                    let value = match (before >= number, after >= number) {
+                        (true, true) => BeamValue::Continue,
+                        (true, false) => BeamValue::End,
+                        (false, true) => BeamValue::Begin,
+                        (false, false) if i == 0 => BeamValue::ForwardHook,
+                        (false, false) => BeamValue::BackwardHook,
+                    };
+                    Beam {
+                        value,
+                        number,
+                        fan: None,
+                        color: None,
+                    }
+                })
+                .collect();
+            if let MusicDataElement::Note(note) = &mut measure.content[index] {
+                note.beams = beams;
+            }
+        }
+    }
+}
+
+/// Start positions of every beat group after the first, in divisions.
+///
+/// Returns `None` if a signature cannot be read as numbers.
+fn beat_group_starts(signatures: &[TimeSignature]) -> Option<Vec<u64>> {
+    let mut starts = Vec::new();
+    let mut position = 0;
+    for signature in signatures {
+        let addends = signature
+            .beats
+            .split('+')
+            .map(|b| b.trim().parse::<u64>().ok().filter(|&n| n > 0))
+            .collect::<Option<Vec<u64>>>()?;
+        let beat_type: u64 = signature.beat_type.trim().parse().ok().filter(|&t| t > 0)?;
+        let unit = 4 * DEFAULT_DIVISIONS as u64 / beat_type;
+
+        let group_sizes: Vec<u64> = match addends[..] {
+            [beats] if beat_type >= 8 && beats % 3 == 0 => vec![3; (beats / 3) as usize],
+            [beats] => vec![1; beats as usize],
+            _ => addends,
+        };
+        for size in group_sizes {
+            starts.push(position);
+            position += size * unit;
+        }
+    }
+    // The first group starts at zero and needs no boundary
+    starts.retain(|&start| start > 0);
+    Some(starts)
+}
+
+/// Number of beams a note type carries (0 for a quarter or longer).
+fn beam_levels(value: NoteTypeValue) -> u8 {
+    match value {
+        NoteTypeValue::Eighth => 1,
+        NoteTypeValue::N16th => 2,
+        NoteTypeValue::N32nd => 3,
+        NoteTypeValue::N64th => 4,
+        NoteTypeValue::N128th => 5,
+        NoteTypeValue::N256th => 6,
+        NoteTypeValue::N512th => 7,
+        NoteTypeValue::N1024th => 8,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::score::ScorePartwise;
+
+    /// Beam values (number, value) of each note in the first measure.
+    fn beams(score: &ScorePartwise) -> Vec<Vec<(u8, BeamValue)>> {
+        score.parts[0].measures[0]
+            .content
+            .iter()
+            .filter_map(|element| match element {
+                MusicDataElement::Note(note) => {
+                    Some(note.beams.iter().map(|b| (b.number, b.value)).collect())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_auto_beam_eighths_in_four_four_pair_by_beat() {
+        let score = crate::lang::compile(
+            "(score (part :piano (measure (time 4 4)
+               (note c4 :8) (note d4 :8) (note e4 :8) (note f4 :8) (note g4 :h))))",
+        )
+        .unwrap();
+
+        use BeamValue::*;
+        assert_eq!(
+            beams(&score),
+            vec![
+                vec![(1, Begin)],
+                vec![(1, End)],
+                vec![(1, Begin)],
+                vec![(1, End)],
+                vec![],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_auto_beam_compound_meter_and_rests() {
+        let score = crate::lang::compile(
+            "(score (part :piano (measure (time 6 8)
+               (note c4 :8) (note d4 :8) (note e4 :8) (note f4 :8) (rest :8) (note a4 :8))))",
+        )
+        .unwrap();
+
+        use BeamValue::*;
+        assert_eq!(
+            beams(&score),
+            vec![
+                vec![(1, Begin)],
+                vec![(1, Continue)],
+                vec![(1, End)],
+                vec![],
+                vec![],
+                vec![],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_auto_beam_secondary_beams_for_sixteenths() {
+        let score = crate::lang::compile(
+            "(score (part :piano (measure (time 2 4)
+               (note c4 :8) (note d4 :16) (note e4 :16) (note f4 :8.) (note g4 :16))))",
+        )
+        .unwrap();
+
+        use BeamValue::*;
+        assert_eq!(
+            beams(&score),
+            vec![
+                vec![(1, Begin)],
+                vec![(1, Continue), (2, Begin)],
+                vec![(1, End), (2, End)],
+                vec![(1, Begin)],
+                vec![(1, End), (2, BackwardHook)],
+            ]
+        );
+    }
+}
@@ -7,17 +7,19 @@
 
 use crate::ir::beam::Stem;
 use crate::ir::common::{Position, UpDown};
-use crate::ir::notation::{
-    Arpeggiate, ArticulationElement, Articulations, NotationContent, Notations,
-};
+use crate::ir::notation::{Arpeggiate, NonArpeggiate, NotationContent, Notations, TopBottom};
 use crate::ir::note::{FullNote, Note, NoteContent, PitchRestUnpitched};
+use crate::ir::pitch::Pitch;
 use crate::lang::ast::{
-    ArpeggiateDirection, Articulation, FermataChord, FermataDuration, StemDirection,
+    ArpeggiateDirection, ArticulationMark, FermataChord, FermataDuration, StemDirection,
 };
 use crate::lang::defaults::DEFAULT_DIVISIONS;
 use crate::lang::duration::{compile_dots, compile_duration_divisions_with, compile_duration_type};
 use crate::lang::error::{CompileError, CompileResult};
-use crate::lang::note::{compile_stem_ir, parse_stem, parse_u32};
+use crate::lang::note::{
+    compile_articulations, compile_stem_ir, parse_articulation_at, parse_articulation_list,
+    parse_stem, parse_u32,
+};
 use crate::lang::pitch::{compile_pitch, parse_pitch_str};
 use crate::sexpr::Sexpr;
 
@@ -66,7 +68,8 @@ pub fn compile_chord(sexpr: &Sexpr) -> CompileResult<Vec<Note>> {
 /// Expected format: `(pitches...) duration [keywords...]`
 /// - pitches: a list of pitch symbols like (c4 e4 g4)
 /// - duration: :q, :h, :w, :8, etc.
-/// - keywords: :voice N, :staff N, :stem up/down, :arpeggiate up/down/none, etc.
+/// - keywords: :voice N, :staff N, :stem up/down, :arpeggiate [up/down/none],
+///   :arpeggiate-up, :arpeggiate-down, :non-arpeggiate, etc.
 pub fn parse_chord_form(items: &[Sexpr]) -> CompileResult<FermataChord> {
     if items.is_empty() {
         return Err(CompileError::InvalidChord {
@@ -121,7 +124,8 @@ pub fn parse_chord_form(items: &[Sexpr]) -> CompileResult<FermataChord> {
     let mut staff: Option<u32> = None;
     let mut stem: Option<StemDirection> = None;
     let mut arpeggiate: Option<ArpeggiateDirection> = None;
-    let mut articulations: Vec<Articulation> = Vec::new();
+    let mut non_arpeggiate = false;
+    let mut articulations: Vec<ArticulationMark> = Vec::new();
 
     let mut i = remaining_start;
     while i < items.len() {
@@ -155,57 +159,60 @@ pub fn parse_chord_form(items: &[Sexpr]) -> CompileResult<FermataChord> {
                     i += 2;
                 }
                 "arpeggiate" => {
-                    if i + 1 >= items.len() {
-                        // Just :arpeggiate without a direction means default (None direction)
-                        arpeggiate = Some(ArpeggiateDirection::None);
-                        i += 1;
-                    } else if let Some(dir_str) = items[i + 1]
-                        .as_symbol()
-                        .or_else(|| items[i + 1].as_keyword())
-                    {
+                    // A direction may follow; another keyword such as :voice
+                    // starts the next option instead
+                    let direction = items.get(i + 1).and_then(|item| {
+                        item.as_symbol().or_else(|| {
+                            item.as_keyword()
+                                .filter(|k| matches!(*k, "up" | "down" | "none"))
+                        })
+                    });
+                    if let Some(dir_str) = direction {
                         arpeggiate = Some(parse_arpeggiate_direction(dir_str)?);
                         i += 2;
                     } else {
+                        // Just :arpeggiate without a direction means default (None direction)
                         arpeggiate = Some(ArpeggiateDirection::None);
                         i += 1;
                     }
                 }
-                // Articulations as flags
-                "staccato" => {
-                    articulations.push(Articulation::Staccato);
+                "arpeggiate-up" => {
+                    arpeggiate = Some(ArpeggiateDirection::Up);
                     i += 1;
                 }
-                "accent" => {
-                    articulations.push(Articulation::Accent);
+                "arpeggiate-down" => {
+                    arpeggiate = Some(ArpeggiateDirection::Down);
                     i += 1;
                 }
-                "tenuto" => {
-                    articulations.push(Articulation::Tenuto);
-                    i += 1;
-                }
-                "marcato" => {
-                    articulations.push(Articulation::StrongAccent);
-                    i += 1;
-                }
-                "staccatissimo" => {
-                    articulations.push(Articulation::Staccatissimo);
-                    i += 1;
-                }
-                "spiccato" => {
-                    articulations.push(Articulation::Spiccato);
+                "non-arpeggiate" => {
+                    non_arpeggiate = true;
                     i += 1;
                 }
                 _ => {
-                    // Unknown keyword - skip it
-                    i += 1;
+                    // Articulations as flags; unknown keywords are skipped
+                    if let Some((mark, next)) = parse_articulation_at(items, i) {
+                        articulations.push(mark);
+                        i = next;
+                    } else {
+                        i += 1;
+                    }
                 }
             }
+        } else if let Some(list) = items[i].as_list() {
+            articulations.push(parse_articulation_list(list)?);
+            i += 1;
         } else {
             // Skip non-keyword items
             i += 1;
         }
     }
 
+    if arpeggiate.is_some() && non_arpeggiate {
+        return Err(CompileError::InvalidChord {
+            reason: "a chord cannot be both arpeggiated and non-arpeggiated".to_string(),
+        });
+    }
+
     Ok(FermataChord {
         pitches,
         duration,
@@ -215,6 +222,7 @@ pub fn parse_chord_form(items: &[Sexpr]) -> CompileResult<FermataChord> {
         articulations,
         ornaments: vec![],
         arpeggiate,
+        non_arpeggiate,
     })
 }
 
@@ -268,7 +276,7 @@ fn is_duration_keyword(s: &str) -> bool {
             | "breve"
             | "long"
             | "maxima"
-    )
+    ) || crate::lang::duration::is_fraction_duration(s)
 }
 
 /// Compile a FermataChord to a `Vec<Note>`.
@@ -294,11 +302,27 @@ pub fn compile_fermata_chord(chord: &FermataChord) -> CompileResult<Vec<Note>> {
     // Build arpeggiate notation (only for first note typically, but we put on all)
     let arpeggiate_notation = chord.arpeggiate.map(compile_arpeggiate);
 
+    let ir_pitches = chord
+        .pitches
+        .iter()
+        .map(compile_pitch)
+        .collect::<CompileResult<Vec<_>>>()?;
+
+    // A non-arpeggiate bracket runs from the lowest note to the highest
+    let bracket_ends = if chord.non_arpeggiate && ir_pitches.len() > 1 {
+        let heights: Vec<f64> = ir_pitches.iter().map(Pitch::semitone_position).collect();
+        let by_height = |a: &usize, b: &usize| heights[*a].total_cmp(&heights[*b]);
+        let lowest = (0..heights.len()).min_by(by_height).unwrap_or(0);
+        let highest = (0..heights.len()).max_by(by_height).unwrap_or(0);
+        Some((lowest, highest))
+    } else {
+        None
+    };
+
     let mut notes = Vec::with_capacity(chord.pitches.len());
 
-    for (idx, fermata_pitch) in chord.pitches.iter().enumerate() {
+    for (idx, ir_pitch) in ir_pitches.iter().enumerate() {
         let is_chord_note = idx > 0; // First note is NOT a chord note
-        let ir_pitch = compile_pitch(fermata_pitch)?;
 
         // Build notations for this note
         let mut notations_content: Vec<NotationContent> = Vec::new();
@@ -313,6 +337,16 @@ pub fn compile_fermata_chord(chord: &FermataChord) -> CompileResult<Vec<Note>> {
             notations_content.push(NotationContent::Arpeggiate(arp.clone()));
         }
 
+        // Add the bracket's ends to the lowest and highest notes
+        if let Some((lowest, highest)) = bracket_ends {
+            if idx == lowest {
+                notations_content.push(compile_non_arpeggiate(TopBottom::Bottom));
+            }
+            if idx == highest {
+                notations_content.push(compile_non_arpeggiate(TopBottom::Top));
+            }
+        }
+
         let notations = if notations_content.is_empty() {
             vec![]
         } else {
@@ -334,7 +368,7 @@ pub fn compile_fermata_chord(chord: &FermataChord) -> CompileResult<Vec<Note>> {
             content: NoteContent::Regular {
                 full_note: FullNote {
                     chord: is_chord_note,
-                    content: PitchRestUnpitched::Pitch(ir_pitch),
+                    content: PitchRestUnpitched::Pitch(ir_pitch.clone()),
                 },
                 duration: duration_divisions,
                 ties: vec![],
@@ -359,49 +393,6 @@ pub fn compile_fermata_chord(chord: &FermataChord) -> CompileResult<Vec<Note>> {
     Ok(notes)
 }
 
-/// Compile articulations to IR Articulations.
-fn compile_articulations(articulations: &[Articulation]) -> Option<Articulations> {
-    if articulations.is_empty() {
-        return None;
-    }
-
-    use crate::ir::common::EmptyPlacement;
-    use crate::ir::notation::StrongAccent;
-
-    let content: Vec<ArticulationElement> = articulations
-        .iter()
-        .map(|a| match a {
-            Articulation::Staccato => ArticulationElement::Staccato(EmptyPlacement::default()),
-            Articulation::Staccatissimo => {
-                ArticulationElement::Staccatissimo(EmptyPlacement::default())
-            }
-            Articulation::Spiccato => ArticulationElement::Spiccato(EmptyPlacement::default()),
-            Articulation::Accent => ArticulationElement::Accent(EmptyPlacement::default()),
-            Articulation::StrongAccent => {
-                ArticulationElement::StrongAccent(StrongAccent::default())
-            }
-            Articulation::Tenuto => ArticulationElement::Tenuto(EmptyPlacement::default()),
-            Articulation::DetachedLegato => {
-                ArticulationElement::DetachedLegato(EmptyPlacement::default())
-            }
-            Articulation::BreathMark => {
-                ArticulationElement::BreathMark(crate::ir::notation::BreathMark {
-                    value: crate::ir::notation::BreathMarkValue::Comma,
-                    placement: None,
-                    position: Position::default(),
-                })
-            }
-            Articulation::Caesura => ArticulationElement::Caesura(crate::ir::notation::Caesura {
-                value: crate::ir::notation::CaesuraValue::Normal,
-                placement: None,
-                position: Position::default(),
-            }),
-        })
-        .collect();
-
-    Some(Articulations { content })
-}
-
 /// Compile arpeggiate direction to IR Arpeggiate.
 fn compile_arpeggiate(direction: ArpeggiateDirection) -> Arpeggiate {
     let ir_direction = match direction {
@@ -411,18 +402,28 @@ fn compile_arpeggiate(direction: ArpeggiateDirection) -> Arpeggiate {
     };
 
     Arpeggiate {
-        number: None,
+        number: Some(1),
         direction: ir_direction,
         position: Position::default(),
         color: None,
     }
 }
 
+/// Compile one end of a non-arpeggiate bracket.
+fn compile_non_arpeggiate(end: TopBottom) -> NotationContent {
+    NotationContent::NonArpeggiate(NonArpeggiate {
+        r#type: end,
+        number: Some(1),
+        position: Position::default(),
+        color: None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::ir::pitch::Step as IrStep;
-    use crate::lang::ast::{FermataPitch, PitchStep};
+    use crate::lang::ast::{Articulation, FermataPitch, PitchStep};
 
     // === parse_arpeggiate_direction tests ===
 
@@ -579,7 +580,7 @@ mod tests {
             Sexpr::keyword("staccato"),
         ];
         let chord = parse_chord_form(&items).unwrap();
-        assert!(chord.articulations.contains(&Articulation::Staccato));
+        assert!(chord.articulations.contains(&Articulation::Staccato.into()));
     }
 
     #[test]
@@ -590,7 +591,7 @@ mod tests {
             Sexpr::keyword("accent"),
         ];
         let chord = parse_chord_form(&items).unwrap();
-        assert!(chord.articulations.contains(&Articulation::Accent));
+        assert!(chord.articulations.contains(&Articulation::Accent.into()));
     }
 
     #[test]
@@ -723,6 +724,76 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_compile_arpeggiated_triad_marks_every_note() {
+        let score = crate::lang::compile(
+            "(score (part :piano (measure (chord (c4 e4 g4) :q :arpeggiate) (chord (d4 f4 a4) :q :arpeggiate-down))))",
+        )
+        .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        assert_eq!(xml.matches("<note>").count(), 6, "{}", xml);
+        assert_eq!(
+            xml.matches("<arpeggiate number=\"1\"/>").count(),
+            3,
+            "{}",
+            xml
+        );
+        assert_eq!(
+            xml.matches("<arpeggiate number=\"1\" direction=\"down\"/>")
+                .count(),
+            3,
+            "{}",
+            xml
+        );
+    }
+
+    #[test]
+    fn test_compile_non_arpeggiate_brackets_lowest_and_highest() {
+        let notes =
+            compile_chord(&crate::sexpr::parse("(chord (e4 g4 c4) :h :non-arpeggiate)").unwrap())
+                .unwrap();
+
+        let brackets: Vec<Vec<TopBottom>> = notes
+            .iter()
+            .map(|note| {
+                note.notations
+                    .iter()
+                    .flat_map(|n| &n.content)
+                    .filter_map(|c| match c {
+                        NotationContent::NonArpeggiate(na) => {
+                            assert_eq!(na.number, Some(1));
+                            Some(na.r#type)
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            brackets,
+            [vec![], vec![TopBottom::Top], vec![TopBottom::Bottom]]
+        );
+    }
+
+    #[test]
+    fn test_parse_chord_form_arpeggiate_before_option_keeps_option() {
+        let sexpr = crate::sexpr::parse("(chord (c4 e4) :q :arpeggiate :voice 2)").unwrap();
+        let chord = parse_chord_form(&sexpr.as_list().unwrap()[1..]).unwrap();
+        assert_eq!(chord.arpeggiate, Some(ArpeggiateDirection::None));
+        assert_eq!(chord.voice, Some(2));
+    }
+
+    #[test]
+    fn test_parse_chord_form_arpeggiate_and_non_arpeggiate_is_error() {
+        let sexpr =
+            crate::sexpr::parse("(chord (c4 e4) :q :arpeggiate-up :non-arpeggiate)").unwrap();
+        assert!(matches!(
+            parse_chord_form(&sexpr.as_list().unwrap()[1..]),
+            Err(CompileError::InvalidChord { .. })
+        ));
+    }
+
     #[test]
     fn test_compile_chord_with_articulations() {
         let sexpr = Sexpr::list(vec![
@@ -787,6 +858,7 @@ mod tests {
             articulations: vec![],
             ornaments: vec![],
             arpeggiate: None,
+            non_arpeggiate: false,
         };
 
         let notes = compile_fermata_chord(&chord).unwrap();
@@ -806,6 +878,7 @@ mod tests {
             articulations: vec![],
             ornaments: vec![],
             arpeggiate: None,
+            non_arpeggiate: false,
         };
 
         assert!(compile_fermata_chord(&chord).is_err());
@@ -821,7 +894,7 @@ mod tests {
 
     #[test]
     fn test_compile_articulations_staccato() {
-        let result = compile_articulations(&[Articulation::Staccato]);
+        let result = compile_articulations(&[Articulation::Staccato.into()]);
         assert!(result.is_some());
         let arts = result.unwrap();
         assert_eq!(arts.content.len(), 1);
@@ -829,7 +902,8 @@ mod tests {
 
     #[test]
     fn test_compile_articulations_multiple() {
-        let result = compile_articulations(&[Articulation::Staccato, Articulation::Accent]);
+        let result =
+            compile_articulations(&[Articulation::Staccato.into(), Articulation::Accent.into()]);
         assert!(result.is_some());
         let arts = result.unwrap();
         assert_eq!(arts.content.len(), 2);
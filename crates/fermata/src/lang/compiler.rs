@@ -3,12 +3,14 @@
 //! This module orchestrates the compilation of Fermata syntax to Music IR.
 
 use crate::ir::score::ScorePartwise;
-use crate::sexpr::Sexpr;
-use crate::sexpr::parser::parse as parse_sexpr;
+use crate::sexpr::parser::{parse as parse_sexpr, parse_spanned};
+use crate::sexpr::{FromSexpr, Sexpr};
 
 use super::ast::FermataScore;
-use super::error::{CompileError, CompileResult};
+use super::define::expand_definitions;
+use super::error::{CompileError, CompileResult, SourceSpan};
 use super::score::{compile_fermata_score, parse_score_from_sexpr};
+use super::structure::check_structure;
 
 /// Compile Fermata source text to Music IR.
 ///
@@ -25,17 +27,65 @@ use super::score::{compile_fermata_score, parse_score_from_sexpr};
 /// "#;
 /// let score = compile(source)?;
 /// ```
+///
+/// Source written by `fermata import` (a `score` holding a `part-list`) is
+/// already IR and is read back as-is, so its `divisions` and durations are
+/// kept rather than recomputed.
+///
+/// Structural mistakes, such as a score with no parts, are reported as
+/// [`CompileError::Semantic`] with the span of the offending form.
 pub fn compile(source: &str) -> CompileResult<ScorePartwise> {
     // Step 1: Parse S-expression
-    let sexpr = parse_sexpr(source)?;
+    let (sexpr, spans) = parse_spanned(source)?;
+
+    if is_imported_score(&sexpr) {
+        return Ok(ScorePartwise::from_sexpr(&sexpr)?);
+    }
+    check_structure(&sexpr).map_err(|e| e.locate(&spans, source))?;
+
+    compile_checked(&sexpr)
+}
+
+/// Compile an already-parsed S-expression to Music IR.
+///
+/// This is [`compile`] without the parsing step, for callers that parse
+/// once and reuse the expression (for example through a
+/// [`ParseCache`](crate::sexpr::ParseCache)).
+pub fn compile_sexpr(sexpr: &Sexpr) -> CompileResult<ScorePartwise> {
+    if is_imported_score(sexpr) {
+        return Ok(ScorePartwise::from_sexpr(sexpr)?);
+    }
+    check_structure(sexpr)?;
+
+    compile_checked(sexpr)
+}
+
+/// Compile Fermata source whose structure has been checked.
+fn compile_checked(sexpr: &Sexpr) -> CompileResult<ScorePartwise> {
+    // Step 2: Expand definitions
+    let sexpr = expand_definitions(sexpr)?;
 
-    // Step 2: Interpret as Fermata AST
+    // Step 3: Interpret as Fermata AST
     let fermata_ast = interpret_sexpr(&sexpr)?;
 
-    // Step 3: Compile to IR
+    // Step 4: Compile to IR
     compile_to_ir(&fermata_ast)
 }
 
+/// Whether an S-expression is the IR form written by `fermata import`.
+///
+/// Fermata source never has a `part-list`; the import form always does.
+fn is_imported_score(sexpr: &Sexpr) -> bool {
+    sexpr.as_list().is_some_and(|list| {
+        list.first().is_some_and(|head| head.is_symbol("score"))
+            && list[1..].iter().any(|item| {
+                item.as_list()
+                    .and_then(|l| l.first())
+                    .is_some_and(|head| head.is_symbol("part-list"))
+            })
+    })
+}
+
 /// Interpret an S-expression as Fermata AST
 fn interpret_sexpr(sexpr: &Sexpr) -> CompileResult<FermataScore> {
     parse_score_from_sexpr(sexpr)
@@ -81,11 +131,32 @@ pub fn compile_part_str(source: &str) -> CompileResult<super::part::CompiledPart
 /// Returns Ok(()) if the source can be parsed and validated,
 /// or an error describing what's wrong.
 pub fn check(source: &str) -> CompileResult<()> {
-    let sexpr = parse_sexpr(source)?;
+    let (sexpr, spans) = parse_spanned(source)?;
+    if !is_imported_score(&sexpr) {
+        check_structure(&sexpr).map_err(|e| e.locate(&spans, source))?;
+    }
+    let sexpr = expand_definitions(&sexpr)?;
     let _ast = interpret_sexpr(&sexpr)?;
     Ok(())
 }
 
+/// Parse Fermata source, collecting every syntax error instead of stopping
+/// at the first.
+///
+/// Each error is a [`CompileError::Parse`] with its [`SourceSpan`] (including
+/// line and column) attached. The expression holds whatever could be parsed.
+pub fn parse_recover(source: &str) -> (Option<Sexpr>, Vec<CompileError>) {
+    let (sexpr, errors) = crate::sexpr::parser::parse_recover(source);
+    let errors = errors
+        .into_iter()
+        .map(|(error, span)| {
+            CompileError::Parse(error)
+                .with_span(SourceSpan::new(span.start, span.end).with_source(source))
+        })
+        .collect();
+    (sexpr, errors)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,15 +168,37 @@ mod tests {
     // === compile tests ===
 
     #[test]
-    fn test_compile_empty_score() {
-        let source = "(score)";
-        let score = compile(source).unwrap();
-        assert!(score.parts.is_empty());
+    fn test_compile_empty_score_has_no_parts_error() {
+        let err = compile("(score)").unwrap_err();
+        assert_eq!(err.to_string(), "Semantic error: score has no parts");
+        assert!(matches!(
+            &err,
+            CompileError::WithSpan { source, .. } if matches!(**source, CompileError::Semantic(_))
+        ));
+    }
+
+    #[test]
+    fn test_compile_structural_errors_point_at_form() {
+        let source = "(score\n  (part :piano (measure (note c4 :q)))\n  (measure (note d4 :q)))";
+        let err = compile(source).unwrap_err();
+        let CompileError::WithSpan { span, .. } = &err else {
+            panic!("expected span, got {:?}", err);
+        };
+        assert_eq!((span.line, span.column), (3, 3));
+        assert!(err.to_string().contains("measure must be inside a part"));
+
+        let err = compile("(score (part :piano (measure (note c4))))").unwrap_err();
+        assert!(
+            err.to_string().contains("note c4 has no duration"),
+            "{}",
+            err
+        );
+        assert!(check("(score (part :piano (measure (note c4))))").is_err());
     }
 
     #[test]
     fn test_compile_score_with_title() {
-        let source = r#"(score :title "Test Score")"#;
+        let source = r#"(score :title "Test Score" (part :piano))"#;
         let score = compile(source).unwrap();
         assert!(score.work.is_some());
         assert_eq!(
@@ -116,7 +209,7 @@ mod tests {
 
     #[test]
     fn test_compile_score_with_composer() {
-        let source = r#"(score :composer "Test Composer")"#;
+        let source = r#"(score :composer "Test Composer" (part :piano))"#;
         let score = compile(source).unwrap();
         assert!(score.identification.is_some());
     }
@@ -358,6 +451,24 @@ mod tests {
         assert!(matches!(measure.content[1], MusicDataElement::Direction(_)));
     }
 
+    #[test]
+    fn test_compile_note_accent_dynamics_emit_their_elements() {
+        for (flag, element) in [
+            (":sfz", "<sfz/>"),
+            (":sf", "<sf/>"),
+            (":fp", "<fp/>"),
+            (":rfz", "<rfz/>"),
+            (":n", "<n/>"),
+        ] {
+            let source = format!("(score (part :piano (measure (note c4 :q {}))))", flag);
+            let xml = crate::musicxml::emit(&compile(&source).unwrap()).unwrap();
+            let dynamics = xml.find("<dynamics").expect("dynamics");
+            assert!(xml[dynamics..].contains(element), "{}: {}", flag, xml);
+            // The dynamic comes before the note it marks
+            assert!(dynamics < xml.find("<note>").unwrap(), "{}", flag);
+        }
+    }
+
     #[test]
     fn test_compile_score_with_chord() {
         let source = r#"
@@ -376,4 +487,129 @@ mod tests {
             MusicDataElement::Attributes(_)
         ));
     }
+
+    // === parse_recover tests ===
+
+    #[test]
+    fn test_parse_recover_reports_every_paren_error() {
+        let source =
+            "(score\n  (part :piano\n    (measure (note c4 :q]))\n    (measure (note d4 :q)))\n";
+        let (sexpr, errors) = parse_recover(source);
+
+        assert!(sexpr.is_some());
+        assert_eq!(errors.len(), 2);
+
+        // The unclosed score list, reported at its opening paren
+        let spans: Vec<(usize, usize, usize)> = errors
+            .iter()
+            .map(|e| match e {
+                CompileError::WithSpan { span, .. } => (span.start, span.line, span.column),
+                other => panic!("expected span, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(spans[0], (0, 1, 1));
+        assert!(errors[0].to_string().contains("Unclosed list"));
+
+        // The bracket in place of a closing paren on line 3
+        let bracket = source.find(']').unwrap();
+        assert_eq!(spans[1], (bracket, 3, 25));
+        assert!(errors[1].to_string().contains("']'"));
+    }
+
+    #[test]
+    fn test_parse_recover_valid_source_matches_parse() {
+        let source = "(score :title \"Test\" (part :piano (measure (note c4 :q))))";
+        let (sexpr, errors) = parse_recover(source);
+        assert!(errors.is_empty());
+        assert_eq!(sexpr, Some(parse_sexpr(source).unwrap()));
+    }
+
+    #[test]
+    fn test_compile_imported_score_keeps_divisions() {
+        use crate::sexpr::{ToSexpr, print_sexpr};
+
+        let xml = r#"<?xml version="1.0"?>
+            <score-partwise version="4.0">
+              <part-list><score-part id="P1"><part-name>Piano</part-name></score-part></part-list>
+              <part id="P1"><measure number="1">
+                <attributes><divisions>480</divisions></attributes>
+                <note><pitch><step>C</step><octave>4</octave></pitch><duration>480</duration><type>quarter</type></note>
+                <note><pitch><step>D</step><octave>4</octave></pitch><duration>1440</duration><type>half</type><dot/></note>
+              </measure></part>
+            </score-partwise>"#;
+        let imported = crate::musicxml::parse(xml).unwrap();
+        let source = print_sexpr(&imported.to_sexpr());
+
+        let score = compile(&source).unwrap();
+        assert_eq!(score, imported);
+
+        let emitted = crate::musicxml::emit(&score).unwrap();
+        assert!(emitted.contains("<divisions>480</divisions>"));
+        assert!(emitted.contains("<duration>1440</duration>"));
+    }
+
+    #[test]
+    fn test_compile_imported_score_keeps_part_symbol() {
+        use crate::sexpr::{ToSexpr, print_sexpr};
+
+        let xml = r#"<?xml version="1.0"?>
+            <score-partwise version="4.0">
+              <part-list><score-part id="P1"><part-name>Piano</part-name></score-part></part-list>
+              <part id="P1"><measure number="1">
+                <attributes><divisions>1</divisions><staves>2</staves>
+                  <part-symbol top-staff="1" bottom-staff="2">brace</part-symbol>
+                  <clef number="1"><sign>G</sign><line>2</line></clef>
+                  <clef number="2"><sign>F</sign><line>4</line></clef></attributes>
+                <note><pitch><step>C</step><octave>4</octave></pitch><duration>4</duration><type>whole</type><staff>1</staff></note>
+              </measure></part>
+            </score-partwise>"#;
+        let imported = crate::musicxml::parse(xml).unwrap();
+        let source = print_sexpr(&imported.to_sexpr());
+
+        let score = compile(&source).unwrap();
+        assert_eq!(score, imported);
+
+        let emitted = crate::musicxml::emit(&score).unwrap();
+        assert!(
+            emitted.contains(r#"<part-symbol top-staff="1" bottom-staff="2">brace</part-symbol>"#)
+        );
+    }
+
+    #[test]
+    fn test_compile_imported_score_keeps_print_layout() {
+        use crate::sexpr::{ToSexpr, print_sexpr};
+
+        let xml = r#"<?xml version="1.0"?>
+            <score-partwise version="4.0">
+              <part-list><score-part id="P1"><part-name>Piano</part-name></score-part></part-list>
+              <part id="P1"><measure number="1">
+                <print new-page="yes">
+                  <page-layout><page-height>1553</page-height><page-width>1200</page-width>
+                    <page-margins type="both"><left-margin>70</left-margin><right-margin>70</right-margin>
+                      <top-margin>88</top-margin><bottom-margin>88</bottom-margin></page-margins>
+                  </page-layout>
+                  <system-layout><system-margins><left-margin>21</left-margin><right-margin>0</right-margin></system-margins>
+                    <top-system-distance>170</top-system-distance>
+                    <system-dividers><left-divider print-object="yes"/></system-dividers></system-layout>
+                  <staff-layout number="2"><staff-distance>65</staff-distance></staff-layout>
+                  <measure-layout><measure-distance>12.5</measure-distance></measure-layout>
+                </print>
+                <attributes><divisions>1</divisions></attributes>
+                <note><pitch><step>C</step><octave>4</octave></pitch><duration>4</duration><type>whole</type></note>
+              </measure></part>
+            </score-partwise>"#;
+        let imported = crate::musicxml::parse(xml).unwrap();
+        let source = print_sexpr(&imported.to_sexpr());
+
+        let score = compile(&source).unwrap();
+        assert_eq!(score, imported);
+
+        let emitted = crate::musicxml::emit(&score).unwrap();
+        assert!(emitted.contains("<page-height>1553</page-height>"));
+        assert!(emitted.contains(r#"<page-margins type="both">"#));
+        assert!(emitted.contains("<top-system-distance>170</top-system-distance>"));
+        assert!(emitted.contains(r#"<left-divider print-object="yes"/>"#));
+        assert!(emitted.contains(r#"<staff-layout number="2">"#));
+        assert!(emitted.contains("<measure-distance>12.5</measure-distance>"));
+    }
 }
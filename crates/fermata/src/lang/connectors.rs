@@ -4,8 +4,13 @@
 //! that connect notes together.
 
 use crate::ir::common::{Position, StartStop, StartStopContinue};
-use crate::ir::notation::{NotationContent, Notations, Slur, Tied};
-use crate::lang::ast::SlurMark;
+use crate::ir::notation::{Glissando, NotationContent, Notations, Slide, Slur, Tied};
+use crate::ir::note::Tie;
+use crate::ir::note::{Note, NoteContent};
+use crate::lang::ast::{
+    FermataGlissando, FermataSlur, FermataTie, GlissandoKind, MeasureElement, SlurMark,
+};
+use crate::lang::direction::{OpenSpans, compile_spanned_element};
 use crate::lang::error::{CompileError, CompileResult};
 use crate::sexpr::Sexpr;
 
@@ -159,6 +164,297 @@ pub fn create_tied_notations(action: StartStop) -> Notations {
     }
 }
 
+/// Parse a spanning slur form into a FermataSlur AST.
+///
+/// Expected format: `elements...`, where each element is any form a
+/// spanning form accepts (a note, rest, chord, tuplet, slur, tie, or
+/// tremolo), e.g. `(slur (note c4 :q) (note d4 :q) (note e4 :q))`.
+pub fn parse_slur_group_form(items: &[Sexpr]) -> CompileResult<FermataSlur> {
+    let notes = items
+        .iter()
+        .map(|item| crate::lang::direction::parse_spanned_element(item, "slur"))
+        .collect::<CompileResult<Vec<MeasureElement>>>()?;
+
+    if notes.len() < 2 {
+        return Err(CompileError::InvalidNote(
+            "slur requires at least two notes".to_string(),
+        ));
+    }
+
+    Ok(FermataSlur { notes })
+}
+
+/// Compile a FermataSlur to a `Vec<Note>`.
+///
+/// The first note gets a slur start and the last note (the first note of a
+/// final chord) gets a slur stop; notes in between are left untouched.
+/// Nested slurs are numbered by nesting level so that each slur that is open
+/// at the same time carries a distinct number.
+pub fn compile_fermata_slur(slur: &FermataSlur) -> CompileResult<Vec<Note>> {
+    compile_slur_level(slur, OpenSpans::default())
+}
+
+/// Compile a slur inside the slurs and tuplets already `open`, numbering it
+/// one past the enclosing slurs.
+pub(crate) fn compile_slur_level(slur: &FermataSlur, open: OpenSpans) -> CompileResult<Vec<Note>> {
+    let level = open.slurs + 1;
+    let inner = OpenSpans {
+        slurs: level,
+        ..open
+    };
+
+    let mut all_notes: Vec<Note> = Vec::new();
+    for element in &slur.notes {
+        all_notes.extend(compile_spanned_element(element, "slur", inner)?);
+    }
+
+    let last = all_notes
+        .iter()
+        .rposition(|note| !note.is_chord_member())
+        .unwrap_or(0);
+    if let Some(first) = all_notes.first_mut() {
+        push_notation_content(
+            first,
+            slur_to_notation_content(create_slur(StartStop::Start, level)),
+        );
+    }
+    if last > 0 {
+        push_notation_content(
+            &mut all_notes[last],
+            slur_to_notation_content(create_slur(StartStop::Stop, level)),
+        );
+    }
+
+    Ok(all_notes)
+}
+
+/// Parse a tie form into a FermataTie AST.
+///
+/// Expected format: two or more note or chord forms, e.g.
+/// `(tie (note c4 :h) (note c4 :h))` or `(tie (note c4 :h) (chord (c4 e4) :h))`.
+pub fn parse_tie_group_form(items: &[Sexpr]) -> CompileResult<FermataTie> {
+    let mut notes = Vec::new();
+
+    for item in items {
+        let sub_items = item.as_list().unwrap_or_default();
+        match sub_items.first().and_then(Sexpr::as_symbol) {
+            Some("note") => notes.push(MeasureElement::Note(crate::lang::note::parse_note_form(
+                &sub_items[1..],
+            )?)),
+            Some("chord") => notes.push(MeasureElement::Chord(
+                crate::lang::chord::parse_chord_form(&sub_items[1..])?,
+            )),
+            _ => {
+                return Err(CompileError::InvalidNote(format!(
+                    "expected note or chord form in tie, got {:?}",
+                    item
+                )));
+            }
+        }
+    }
+
+    if notes.len() < 2 {
+        return Err(CompileError::InvalidNote(
+            "tie requires at least two notes".to_string(),
+        ));
+    }
+
+    Ok(FermataTie { notes })
+}
+
+/// Compile a FermataTie to a `Vec<Note>`.
+///
+/// Each pitch that a note or chord shares with the next one is tied to it:
+/// both notes get the sounding `<tie>` and the `<tied>` notation, a start
+/// on the earlier note and a stop on the later, so a note in the middle of
+/// a chain gets stop+start. Chord pitches with no match are left untied.
+///
+/// # Errors
+///
+/// Returns [`CompileError::Semantic`] if two neighbouring notes or chords
+/// share no pitch.
+pub fn compile_fermata_tie(tie: &FermataTie) -> CompileResult<Vec<Note>> {
+    // The tied notes of each element, and any touching-pitch notes that
+    // follow them
+    let mut groups: Vec<(Vec<Note>, Vec<Note>)> = Vec::with_capacity(tie.notes.len());
+    for element in &tie.notes {
+        groups.push(match element {
+            MeasureElement::Note(fermata_note) => (
+                vec![crate::lang::note::compile_fermata_note(fermata_note)?],
+                crate::lang::note::compile_touching_notes(fermata_note)?,
+            ),
+            MeasureElement::Chord(fermata_chord) => (
+                crate::lang::chord::compile_fermata_chord(fermata_chord)?,
+                vec![],
+            ),
+            _ => {
+                return Err(CompileError::InvalidNote(format!(
+                    "unsupported element type in tie: {:?}",
+                    element
+                )));
+            }
+        });
+    }
+
+    for idx in 1..groups.len() {
+        let (before, after) = groups.split_at_mut(idx);
+        let (earlier, later) = (&mut before[idx - 1].0, &mut after[0].0);
+        let mut tied = false;
+        for start in earlier.iter_mut() {
+            let Some(stop) = later
+                .iter_mut()
+                .find(|note| note.pitch().is_some() && note.pitch() == start.pitch())
+            else {
+                continue;
+            };
+            add_tie(start, StartStop::Start);
+            add_tie(stop, StartStop::Stop);
+            tied = true;
+        }
+        if !tied {
+            return Err(CompileError::Semantic(format!(
+                "tied notes must share a pitch, got {} and {}",
+                tied_pitches(&tie.notes[idx - 1]),
+                tied_pitches(&tie.notes[idx])
+            )));
+        }
+    }
+
+    Ok(groups
+        .into_iter()
+        .flat_map(|(notes, touching)| notes.into_iter().chain(touching))
+        .collect())
+}
+
+/// The pitches of a tied note or chord, e.g. `C4` or `(C4 E4)`.
+fn tied_pitches(element: &MeasureElement) -> String {
+    match element {
+        MeasureElement::Note(note) => note.pitch.to_string(),
+        MeasureElement::Chord(chord) => {
+            let pitches: Vec<String> = chord.pitches.iter().map(ToString::to_string).collect();
+            format!("({})", pitches.join(" "))
+        }
+        _ => format!("{:?}", element),
+    }
+}
+
+/// Give a note both the sounding `<tie>` and the `<tied>` notation.
+fn add_tie(note: &mut Note, action: StartStop) {
+    if let NoteContent::Regular { ties, .. } = &mut note.content {
+        ties.push(Tie {
+            r#type: action,
+            time_only: None,
+        });
+    }
+    push_notation_content(note, tied_to_notation_content(create_tied(action)));
+}
+
+/// Parse a glissando or slide form into a FermataGlissando AST.
+///
+/// Expected format: an optional `:text "..."` followed by two or more note
+/// forms, e.g. `(gliss (note c4 :q) (note g4 :q))`.
+pub fn parse_glissando_form(
+    kind: GlissandoKind,
+    items: &[Sexpr],
+) -> CompileResult<FermataGlissando> {
+    let mut text = None;
+    let mut notes = Vec::new();
+
+    let mut i = 0;
+    while i < items.len() {
+        let item = &items[i];
+        if item.as_keyword() == Some("text") {
+            let value = items
+                .get(i + 1)
+                .and_then(|v| v.as_string())
+                .ok_or(CompileError::MissingField("glissando text"))?;
+            text = Some(value.to_string());
+            i += 2;
+            continue;
+        }
+        match item.as_list() {
+            Some(sub_items) if sub_items.first().is_some_and(|h| h.is_symbol("note")) => {
+                notes.push(crate::lang::note::parse_note_form(&sub_items[1..])?);
+            }
+            _ => {
+                return Err(CompileError::InvalidNote(format!(
+                    "expected note form in glissando, got {:?}",
+                    item
+                )));
+            }
+        }
+        i += 1;
+    }
+
+    if notes.len() < 2 {
+        return Err(CompileError::InvalidNote(
+            "glissando requires at least two notes".to_string(),
+        ));
+    }
+
+    Ok(FermataGlissando { kind, text, notes })
+}
+
+/// Compile a FermataGlissando to a `Vec<Note>`.
+///
+/// Each pair of consecutive notes is joined by a line numbered 1: a start
+/// on the first note, a stop on the last, and stop+start on any notes in
+/// between. The text, if any, is placed on each start.
+pub fn compile_fermata_glissando(glissando: &FermataGlissando) -> CompileResult<Vec<Note>> {
+    let last = glissando.notes.len() - 1;
+    let mut notes = Vec::with_capacity(glissando.notes.len());
+    for (idx, fermata_note) in glissando.notes.iter().enumerate() {
+        let mut note = crate::lang::note::compile_fermata_note(fermata_note)?;
+
+        let mut actions = Vec::new();
+        if idx > 0 {
+            actions.push(StartStop::Stop);
+        }
+        if idx < last {
+            actions.push(StartStop::Start);
+        }
+        for action in actions {
+            let text = match action {
+                StartStop::Start => glissando.text.clone(),
+                StartStop::Stop => None,
+            };
+            let content = match glissando.kind {
+                GlissandoKind::Glissando => NotationContent::Glissando(Glissando {
+                    r#type: action,
+                    number: Some(1),
+                    text,
+                    line_type: None,
+                    position: Position::default(),
+                }),
+                GlissandoKind::Slide => NotationContent::Slide(Slide {
+                    r#type: action,
+                    number: Some(1),
+                    text,
+                    line_type: None,
+                    position: Position::default(),
+                }),
+            };
+            push_notation_content(&mut note, content);
+        }
+
+        notes.push(note);
+    }
+
+    Ok(notes)
+}
+
+/// Add a notation to a note, reusing its first `<notations>` element if any.
+fn push_notation_content(note: &mut Note, content: NotationContent) {
+    match note.notations.first_mut() {
+        Some(notations) => notations.content.push(content),
+        None => note.notations.push(Notations {
+            print_object: None,
+            content: vec![content],
+            editorial: Default::default(),
+        }),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -398,4 +694,286 @@ mod tests {
         assert_eq!(notations.content.len(), 1);
         assert!(matches!(&notations.content[0], NotationContent::Tied(_)));
     }
+
+    // === slur group tests ===
+
+    fn slur_group(source: &str) -> FermataSlur {
+        let sexpr = crate::sexpr::parse(source).unwrap();
+        parse_slur_group_form(&sexpr.as_list().unwrap()[1..]).unwrap()
+    }
+
+    fn slurs(note: &Note) -> Vec<(StartStopContinue, u8)> {
+        note.notations
+            .iter()
+            .flat_map(|n| &n.content)
+            .filter_map(|c| match c {
+                NotationContent::Slur(s) => Some((s.r#type, s.number)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_compile_fermata_slur_marks_first_and_last() {
+        let slur = slur_group("(slur (note c4 :q) (note d4 :q) (note e4 :q))");
+        let notes = compile_fermata_slur(&slur).unwrap();
+        assert_eq!(notes.len(), 3);
+        assert_eq!(slurs(&notes[0]), vec![(StartStopContinue::Start, 1)]);
+        assert!(slurs(&notes[1]).is_empty());
+        assert_eq!(slurs(&notes[2]), vec![(StartStopContinue::Stop, 1)]);
+    }
+
+    #[test]
+    fn test_compile_fermata_slur_nested_gets_distinct_numbers() {
+        let slur = slur_group("(slur (slur (note c4 :q) (note d4 :q)) (note e4 :q))");
+        let notes = compile_fermata_slur(&slur).unwrap();
+        assert_eq!(
+            slurs(&notes[0]),
+            vec![(StartStopContinue::Start, 2), (StartStopContinue::Start, 1)]
+        );
+        assert_eq!(slurs(&notes[1]), vec![(StartStopContinue::Stop, 2)]);
+        assert_eq!(slurs(&notes[2]), vec![(StartStopContinue::Stop, 1)]);
+    }
+
+    #[test]
+    fn test_compile_fermata_slur_nested_in_tuplet_gets_distinct_numbers() {
+        let slur = slur_group(
+            "(slur (note c4 :q) (tuplet 3 2 (slur (note d4 :8) (note e4 :8)) (note f4 :8)) \
+             (note g4 :q) (note a4 :q))",
+        );
+        let notes = compile_fermata_slur(&slur).unwrap();
+        assert_eq!(slurs(&notes[0]), vec![(StartStopContinue::Start, 1)]);
+        assert_eq!(slurs(&notes[1]), vec![(StartStopContinue::Start, 2)]);
+        assert_eq!(slurs(&notes[2]), vec![(StartStopContinue::Stop, 2)]);
+        assert!(slurs(&notes[3]).is_empty());
+        assert_eq!(slurs(&notes[5]), vec![(StartStopContinue::Stop, 1)]);
+    }
+
+    #[test]
+    fn test_compile_fermata_slur_stop_on_chord_root() {
+        let slur = slur_group("(slur (note c4 :q) (chord (e4 g4) :q))");
+        let notes = compile_fermata_slur(&slur).unwrap();
+        assert_eq!(notes.len(), 3);
+        assert_eq!(slurs(&notes[1]), vec![(StartStopContinue::Stop, 1)]);
+        assert!(slurs(&notes[2]).is_empty());
+    }
+
+    #[test]
+    fn test_parse_slur_group_form_rejects_single_note() {
+        let sexpr = crate::sexpr::parse("(slur (note c4 :q))").unwrap();
+        assert!(parse_slur_group_form(&sexpr.as_list().unwrap()[1..]).is_err());
+        let sexpr = crate::sexpr::parse("(slur (note c4 :q) (key c :major))").unwrap();
+        assert!(parse_slur_group_form(&sexpr.as_list().unwrap()[1..]).is_err());
+    }
+
+    #[test]
+    fn test_compile_fermata_slur_over_tie_and_tremolo() {
+        let slur = slur_group("(slur (tie (note c4 :h) (note c4 :q)) (tremolo 3 (note d4 :q)))");
+        let notes = compile_fermata_slur(&slur).unwrap();
+        assert_eq!(notes.len(), 3);
+        assert_eq!(slurs(&notes[0]), vec![(StartStopContinue::Start, 1)]);
+        assert_eq!(slurs(&notes[2]), vec![(StartStopContinue::Stop, 1)]);
+    }
+
+    #[test]
+    fn test_slur_group_emits_slur_inside_notations() {
+        let score = crate::lang::compile(
+            "(score (part :piano (measure (slur (note c4 :q) (note d4 :q) (note e4 :q)))))",
+        )
+        .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        let notes: Vec<&str> = xml.split("<note>").skip(1).collect();
+        assert_eq!(notes.len(), 3);
+        assert!(notes[0].contains("<notations>\n") || notes[0].contains("<notations>"));
+        assert!(notes[0].contains(r#"<slur type="start" number="1"/>"#));
+        assert!(!notes[1].contains("<slur"));
+        assert!(notes[2].contains(r#"<slur type="stop" number="1"/>"#));
+        let start = notes[0].find("<notations>").unwrap();
+        let slur = notes[0].find("<slur").unwrap();
+        let end = notes[0].find("</notations>").unwrap();
+        assert!(start < slur && slur < end);
+    }
+
+    // === tie group tests ===
+
+    fn tie_group(source: &str) -> FermataTie {
+        let sexpr = crate::sexpr::parse(source).unwrap();
+        parse_tie_group_form(&sexpr.as_list().unwrap()[1..]).unwrap()
+    }
+
+    #[test]
+    fn test_compile_fermata_tie_marks_start_and_stop() {
+        let tie = tie_group("(tie (note c4 :h) (note c4 :h))");
+        let notes = compile_fermata_tie(&tie).unwrap();
+        assert_eq!(notes.len(), 2);
+
+        for (note, expected) in notes.iter().zip([StartStop::Start, StartStop::Stop]) {
+            let NoteContent::Regular { ties, .. } = &note.content else {
+                panic!("Expected regular note");
+            };
+            assert_eq!(ties.len(), 1);
+            assert_eq!(ties[0].r#type, expected);
+            assert!(matches!(
+                &note.notations[0].content[..],
+                [NotationContent::Tied(t)] if t.r#type == start_stop_to_continue(expected)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_compile_fermata_tie_chain_middle_stops_and_starts() {
+        let tie = tie_group("(tie (note g4 :q) (note g4 :h) (note g4 :q))");
+        let notes = compile_fermata_tie(&tie).unwrap();
+        let NoteContent::Regular { ties, .. } = &notes[1].content else {
+            panic!("Expected regular note");
+        };
+        let types: Vec<StartStop> = ties.iter().map(|t| t.r#type).collect();
+        assert_eq!(types, vec![StartStop::Stop, StartStop::Start]);
+        assert_eq!(notes[1].notations[0].content.len(), 2);
+    }
+
+    #[test]
+    fn test_compile_fermata_tie_different_pitches_is_semantic_error() {
+        let tie = tie_group("(tie (note c4 :h) (note d4 :h))");
+        let err = compile_fermata_tie(&tie).unwrap_err();
+        assert!(matches!(
+            err,
+            CompileError::Semantic(ref message)
+                if message == "tied notes must share a pitch, got C4 and D4"
+        ));
+    }
+
+    #[test]
+    fn test_compile_fermata_tie_chord_ties_matching_pitch() {
+        let tie = tie_group("(tie (note c4 :h) (chord (c4 e4) :h))");
+        let notes = compile_fermata_tie(&tie).unwrap();
+        assert_eq!(notes.len(), 3);
+
+        let ties: Vec<Vec<StartStop>> = notes
+            .iter()
+            .map(|note| match &note.content {
+                NoteContent::Regular { ties, .. } => ties.iter().map(|t| t.r#type).collect(),
+                _ => panic!("Expected regular note"),
+            })
+            .collect();
+        assert_eq!(
+            ties,
+            vec![vec![StartStop::Start], vec![StartStop::Stop], vec![]]
+        );
+    }
+
+    #[test]
+    fn test_compile_fermata_tie_chord_without_shared_pitch_is_semantic_error() {
+        let tie = tie_group("(tie (chord (c4 e4) :h) (chord (d4 f4) :h))");
+        let err = compile_fermata_tie(&tie).unwrap_err();
+        assert!(matches!(
+            err,
+            CompileError::Semantic(ref message)
+                if message == "tied notes must share a pitch, got (C4 E4) and (D4 F4)"
+        ));
+    }
+
+    #[test]
+    fn test_compile_fermata_tie_keeps_harmonic_touching_note() {
+        let tie = tie_group(
+            "(tie (note e4 :h :harmonic :artificial :touching a4) (note e4 :h :harmonic :artificial :touching a4))",
+        );
+        let notes = compile_fermata_tie(&tie).unwrap();
+        // Each tied note is followed by its touching pitch
+        assert_eq!(notes.len(), 4);
+        assert!(notes[1].is_chord_member());
+        assert!(notes[3].is_chord_member());
+        assert!(matches!(
+            &notes[0].content,
+            NoteContent::Regular { ties, .. } if ties.len() == 1
+        ));
+    }
+
+    #[test]
+    fn test_parse_tie_group_form_rejects_non_notes() {
+        let sexpr = crate::sexpr::parse("(tie (note c4 :h))").unwrap();
+        assert!(parse_tie_group_form(&sexpr.as_list().unwrap()[1..]).is_err());
+        let sexpr = crate::sexpr::parse("(tie (note c4 :h) (rest :h))").unwrap();
+        assert!(parse_tie_group_form(&sexpr.as_list().unwrap()[1..]).is_err());
+    }
+
+    #[test]
+    fn test_tie_group_emits_tie_and_tied() {
+        let score =
+            crate::lang::compile("(score (part :piano (measure (tie (note c4 :h) (note c4 :h)))))")
+                .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        let notes: Vec<&str> = xml.split("<note>").skip(1).collect();
+        assert_eq!(notes.len(), 2);
+        assert!(notes[0].contains(r#"<tie type="start"/>"#));
+        assert!(notes[0].contains(r#"<tied type="start"/>"#));
+        assert!(notes[1].contains(r#"<tie type="stop"/>"#));
+        assert!(notes[1].contains(r#"<tied type="stop"/>"#));
+    }
+
+    // === glissando tests ===
+
+    fn glissando(source: &str, kind: GlissandoKind) -> FermataGlissando {
+        let sexpr = crate::sexpr::parse(source).unwrap();
+        parse_glissando_form(kind, &sexpr.as_list().unwrap()[1..]).unwrap()
+    }
+
+    #[test]
+    fn test_compile_fermata_glissando_marks_start_and_stop() {
+        let gliss = glissando(
+            "(gliss :text \"gliss.\" (note c4 :q) (note g4 :q))",
+            GlissandoKind::Glissando,
+        );
+        let notes = compile_fermata_glissando(&gliss).unwrap();
+        assert_eq!(notes.len(), 2);
+
+        assert!(matches!(
+            &notes[0].notations[0].content[..],
+            [NotationContent::Glissando(g)]
+                if g.r#type == StartStop::Start
+                    && g.number == Some(1)
+                    && g.text.as_deref() == Some("gliss.")
+        ));
+        assert!(matches!(
+            &notes[1].notations[0].content[..],
+            [NotationContent::Glissando(g)]
+                if g.r#type == StartStop::Stop && g.number == Some(1) && g.text.is_none()
+        ));
+    }
+
+    #[test]
+    fn test_compile_fermata_glissando_slide_chain() {
+        let slide = glissando(
+            "(slide (note c3 :q) (note e3 :q) (note g3 :h))",
+            GlissandoKind::Slide,
+        );
+        let notes = compile_fermata_glissando(&slide).unwrap();
+        let types: Vec<StartStop> = notes[1].notations[0]
+            .content
+            .iter()
+            .map(|c| match c {
+                NotationContent::Slide(s) => s.r#type,
+                other => panic!("expected slide, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(types, vec![StartStop::Stop, StartStop::Start]);
+    }
+
+    #[test]
+    fn test_glissando_emits_start_and_stop_on_notes() {
+        let score = crate::lang::compile(
+            "(score (part :harp (measure (gliss (note c4 :q) (note g4 :q)) (slide (note e4 :q) (note a4 :q)))))",
+        )
+        .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        let notes: Vec<&str> = xml.split("<note>").skip(1).collect();
+        assert_eq!(notes.len(), 4);
+        assert!(notes[0].contains(r#"<glissando type="start" number="1"/>"#));
+        assert!(notes[1].contains(r#"<glissando type="stop" number="1"/>"#));
+        assert!(notes[2].contains(r#"<slide type="start" number="1"/>"#));
+        assert!(notes[3].contains(r#"<slide type="stop" number="1"/>"#));
+    }
 }
@@ -0,0 +1,416 @@
+//! Conversions between the Fermata AST and the MusicXML IR.
+//!
+//! [`FermataScore`] converts into a [`ScorePartwise`] by compiling it (see
+//! [`compile_fermata_score`]), which assigns part ids, divisions, and staff
+//! numbers. The reverse direction recovers the structure a Fermata source
+//! would need to produce the score again: parts, measures, attributes,
+//! notes, rests, chords, and tuplets, in their voices and staves.
+//!
+//! The reverse is lossy. Notations, directions, beams, grace and cue notes
+//! have no place in the recovered score and are dropped, and consecutive
+//! tuplets with the same ratio merge into one.
+
+use crate::ir::attributes::Attributes;
+use crate::ir::common::{PositiveDivisions, YesNo};
+use crate::ir::measure::{Measure, MusicDataElement};
+use crate::ir::note::{Note, NoteContent, PitchRestUnpitched};
+use crate::ir::part::{Part, PartListElement};
+use crate::ir::pitch::Pitch;
+use crate::ir::score::ScorePartwise;
+use crate::lang::ast::{
+    DurationBase, FermataChord, FermataDuration, FermataMeasure, FermataNote, FermataPart,
+    FermataPitch, FermataRest, FermataScore, FermataTuplet, FermataUnpitched, MeasureElement,
+};
+use crate::lang::error::{CompileError, CompileResult};
+use crate::lang::score::compile_fermata_score;
+
+impl TryFrom<&FermataScore> for ScorePartwise {
+    type Error = CompileError;
+
+    /// Compile a Fermata score into an IR score (see [`compile_fermata_score`]).
+    fn try_from(score: &FermataScore) -> CompileResult<Self> {
+        compile_fermata_score(score)
+    }
+}
+
+impl TryFrom<FermataScore> for ScorePartwise {
+    type Error = CompileError;
+
+    /// Compile a Fermata score into an IR score (see [`compile_fermata_score`]).
+    fn try_from(score: FermataScore) -> CompileResult<Self> {
+        compile_fermata_score(&score)
+    }
+}
+
+impl TryFrom<&ScorePartwise> for FermataScore {
+    type Error = CompileError;
+
+    /// Recover a Fermata score from an IR score.
+    ///
+    /// The title comes from the work title (or else the movement title) and
+    /// the composer from the first `composer` creator. Part names and
+    /// abbreviations come from the part list.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a note has no written `<type>`, or if a pitch,
+    /// key, time signature, or clef has no Fermata equivalent.
+    fn try_from(score: &ScorePartwise) -> CompileResult<Self> {
+        let title = score
+            .work
+            .as_ref()
+            .and_then(|work| work.work_title.clone())
+            .or_else(|| score.movement_title.clone());
+        let composer = score.identification.as_ref().and_then(|identification| {
+            identification
+                .creators
+                .iter()
+                .find(|creator| creator.r#type.as_deref() == Some("composer"))
+                .map(|creator| creator.value.clone())
+        });
+
+        Ok(FermataScore {
+            title,
+            composer,
+            tempo: None,
+            parts: score
+                .parts
+                .iter()
+                .map(|part| lift_part(score, part))
+                .collect::<CompileResult<_>>()?,
+        })
+    }
+}
+
+/// Recover a part, taking its name from the matching score-part.
+fn lift_part(score: &ScorePartwise, part: &Part) -> CompileResult<FermataPart> {
+    let score_part = score
+        .part_list
+        .content
+        .iter()
+        .find_map(|element| match element {
+            PartListElement::ScorePart(score_part) if score_part.id == part.id => Some(score_part),
+            _ => None,
+        });
+
+    // Divisions carry over from measure to measure until changed
+    let mut divisions = 1;
+    let measures = part
+        .measures
+        .iter()
+        .map(|measure| lift_measure(measure, &mut divisions))
+        .collect::<CompileResult<_>>()?;
+
+    Ok(FermataPart {
+        name: score_part
+            .map(|score_part| score_part.part_name.value.clone())
+            .unwrap_or_default(),
+        id: Some(part.id.clone()),
+        abbreviation: score_part
+            .and_then(|score_part| score_part.part_abbreviation.as_ref())
+            .map(|abbreviation| abbreviation.value.clone()),
+        transpose: part
+            .measures
+            .first()
+            .into_iter()
+            .flat_map(|measure| &measure.content)
+            .find_map(|element| match element {
+                MusicDataElement::Attributes(attributes) => attributes.transpose.first(),
+                _ => None,
+            })
+            .map(|t| t.chromatic + 12 * t.octave_change.unwrap_or(0)),
+        measures,
+    })
+}
+
+/// Recover a measure, tracking the divisions per quarter note in effect.
+fn lift_measure(
+    measure: &Measure,
+    divisions: &mut PositiveDivisions,
+) -> CompileResult<FermataMeasure> {
+    let mut content = Vec::new();
+
+    for element in &measure.content {
+        match element {
+            MusicDataElement::Attributes(attributes) => {
+                lift_attributes(attributes, divisions, &mut content)?
+            }
+            MusicDataElement::Note(note) => lift_note(note, &mut content)?,
+            MusicDataElement::Backup(backup) => content.push(MeasureElement::Backup(quarters(
+                backup.duration,
+                *divisions,
+            )?)),
+            MusicDataElement::Forward(forward) => content.push(MeasureElement::Forward(quarters(
+                forward.duration,
+                *divisions,
+            )?)),
+            _ => {}
+        }
+    }
+
+    Ok(FermataMeasure {
+        number: measure.number.parse().ok(),
+        implicit: measure.implicit == Some(YesNo::Yes),
+        content,
+    })
+}
+
+/// Recover the key, time, and clef changes from an attributes element.
+fn lift_attributes(
+    attributes: &Attributes,
+    divisions: &mut PositiveDivisions,
+    content: &mut Vec<MeasureElement>,
+) -> CompileResult<()> {
+    if let Some(value) = attributes.divisions {
+        *divisions = value;
+    }
+    for key in &attributes.keys {
+        content.push(MeasureElement::Key(key.try_into()?));
+    }
+    for time in &attributes.times {
+        content.push(MeasureElement::Time(time.try_into()?));
+    }
+    for clef in &attributes.clefs {
+        content.push(MeasureElement::Clef(clef.try_into()?));
+    }
+    Ok(())
+}
+
+/// Convert a backup or forward duration to whole quarter notes.
+fn quarters(duration: PositiveDivisions, divisions: PositiveDivisions) -> CompileResult<u32> {
+    if divisions == 0 || duration % divisions != 0 {
+        return Err(CompileError::Semantic(format!(
+            "cannot express {} divisions at {} per quarter note as whole quarter notes",
+            duration, divisions
+        )));
+    }
+    Ok((duration / divisions) as u32)
+}
+
+/// Recover a note, rest, or chord member and append it to `content`.
+///
+/// Chord members join the element before them, which becomes a chord;
+/// notes with a time modification join the tuplet before them when its
+/// ratio matches, or start a new one.
+fn lift_note(note: &Note, content: &mut Vec<MeasureElement>) -> CompileResult<()> {
+    let NoteContent::Regular { full_note, .. } = &note.content else {
+        return Ok(());
+    };
+
+    let target = match &note.time_modification {
+        Some(modification) => {
+            let (actual, normal) = (modification.actual_notes, modification.normal_notes);
+            let open = matches!(
+                content.last(),
+                Some(MeasureElement::Tuplet(tuplet))
+                    if tuplet.actual == actual && tuplet.normal == normal
+            );
+            if !open {
+                content.push(MeasureElement::Tuplet(FermataTuplet {
+                    actual,
+                    normal,
+                    notes: Vec::new(),
+                }));
+            }
+            match content.last_mut() {
+                Some(MeasureElement::Tuplet(tuplet)) => &mut tuplet.notes,
+                _ => unreachable!("a tuplet was just pushed"),
+            }
+        }
+        None => content,
+    };
+
+    let voice = note.voice.as_deref().and_then(|v| v.parse().ok());
+    let staff = note.staff.map(u32::from);
+    let invisible = note.print_object == Some(YesNo::No);
+
+    let element = match &full_note.content {
+        PitchRestUnpitched::Pitch(pitch) => {
+            let pitch = FermataPitch::try_from(pitch)?;
+            if full_note.chord {
+                return join_chord(target, pitch);
+            }
+            MeasureElement::Note(FermataNote {
+                voice,
+                staff,
+                invisible,
+                ..FermataNote::new(pitch, lift_duration(note)?)
+            })
+        }
+        PitchRestUnpitched::Rest(rest) => {
+            let measure_rest = rest.measure == Some(YesNo::Yes);
+            let duration = match note.r#type {
+                None if measure_rest => FermataDuration {
+                    base: DurationBase::Whole,
+                    dots: 0,
+                },
+                _ => lift_duration(note)?,
+            };
+            MeasureElement::Rest(FermataRest {
+                duration,
+                voice,
+                staff,
+                measure_rest,
+                invisible,
+            })
+        }
+        PitchRestUnpitched::Unpitched(unpitched) => {
+            let (Some(step), Some(octave)) = (unpitched.display_step, unpitched.display_octave)
+            else {
+                return Err(CompileError::InvalidNote(
+                    "unpitched note has no display position".to_string(),
+                ));
+            };
+            let display = FermataPitch::try_from(&Pitch {
+                step,
+                alter: None,
+                octave,
+            })?;
+            MeasureElement::Unpitched(FermataUnpitched {
+                display,
+                duration: lift_duration(note)?,
+                instrument: note.instrument.first().map(|i| i.id.clone()),
+                voice,
+                staff,
+            })
+        }
+    };
+    target.push(element);
+    Ok(())
+}
+
+/// Add a chord member to the note or chord before it.
+fn join_chord(target: &mut [MeasureElement], pitch: FermataPitch) -> CompileResult<()> {
+    match target.last_mut() {
+        Some(MeasureElement::Chord(chord)) => chord.pitches.push(pitch),
+        Some(last @ MeasureElement::Note(_)) => {
+            let MeasureElement::Note(first) = &*last else {
+                unreachable!("matched a note");
+            };
+            *last = MeasureElement::Chord(FermataChord {
+                pitches: vec![first.pitch.clone(), pitch],
+                duration: first.duration.clone(),
+                voice: first.voice,
+                staff: first.staff,
+                stem: None,
+                articulations: Vec::new(),
+                ornaments: Vec::new(),
+                arpeggiate: None,
+                non_arpeggiate: false,
+            });
+        }
+        _ => {
+            return Err(CompileError::Semantic(
+                "chord note does not follow a note".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The written value of a note: its `<type>` and dots.
+fn lift_duration(note: &Note) -> CompileResult<FermataDuration> {
+    let note_type = note.r#type.as_ref().ok_or_else(|| {
+        CompileError::InvalidDuration("note has no <type>, so its written value is unknown".into())
+    })?;
+    Ok(FermataDuration {
+        base: note_type.value.into(),
+        dots: note.dots.len() as u8,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang::ast::{KeySpec, Mode, PitchStep, TimeSpec};
+    use crate::lang::score::parse_score_to_ast;
+    use crate::sexpr::parse;
+
+    fn ast(source: &str) -> FermataScore {
+        parse_score_to_ast(&parse(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_try_from_fermata_score_builds_one_part_one_measure() {
+        let score = ast("(score (part :piano (measure (note c4 :q))))");
+
+        let ir = ScorePartwise::try_from(score).unwrap();
+
+        assert_eq!(ir.parts.len(), 1);
+        assert_eq!(ir.parts[0].id, "P1");
+        assert_eq!(ir.parts[0].measures.len(), 1);
+        let notes: Vec<&Note> = ir.parts[0].measures[0]
+            .content
+            .iter()
+            .filter_map(|element| match element {
+                MusicDataElement::Note(note) => Some(note.as_ref()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(notes.len(), 1);
+        let NoteContent::Regular { full_note, .. } = &notes[0].content else {
+            panic!("expected a regular note");
+        };
+        let PitchRestUnpitched::Pitch(pitch) = &full_note.content else {
+            panic!("expected a pitched note");
+        };
+        assert_eq!(pitch.to_string(), "C4");
+    }
+
+    #[test]
+    fn test_try_from_score_partwise_recovers_structure() {
+        let source = r#"(score :title "Etude" :composer "Czerny"
+            (part :piano
+              (measure (key d :major) (time 3 4) (clef :treble)
+                (note f#4 :h) (chord (c4 e4 g4) :q))
+              (measure (tuplet 3 2 (note c5 :8) (note d5 :8) (note e5 :8)) (rest :h))))"#;
+        let ir = ScorePartwise::try_from(&ast(source)).unwrap();
+
+        let lifted = FermataScore::try_from(&ir).unwrap();
+
+        assert_eq!(lifted.title.as_deref(), Some("Etude"));
+        assert_eq!(lifted.composer.as_deref(), Some("Czerny"));
+        let part = &lifted.parts[0];
+        assert_eq!(part.name, "Piano");
+        assert_eq!(part.measures.len(), 2);
+
+        let first = &part.measures[0].content;
+        assert!(first.contains(&MeasureElement::Key(KeySpec {
+            root: PitchStep::D,
+            root_alter: None,
+            mode: Mode::Major,
+        })));
+        assert!(first.contains(&MeasureElement::Time(TimeSpec::Simple {
+            beats: 3,
+            beat_type: 4
+        })));
+        let chord = first
+            .iter()
+            .find_map(|element| match element {
+                MeasureElement::Chord(chord) => Some(chord),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(chord.pitches.len(), 3);
+        assert_eq!(chord.duration.base, DurationBase::Quarter);
+
+        let second = &part.measures[1].content;
+        assert!(matches!(
+            &second[0],
+            MeasureElement::Tuplet(tuplet) if (tuplet.actual, tuplet.normal, tuplet.notes.len()) == (3, 2, 3)
+        ));
+        assert!(
+            matches!(&second[1], MeasureElement::Rest(rest) if rest.duration.base == DurationBase::Half)
+        );
+    }
+
+    #[test]
+    fn test_try_from_score_partwise_round_trips_through_compile() {
+        let source = "(score (part :violin (measure (time 2 4) (note g4 :q.) (note a4 :8))))";
+        let ir = ScorePartwise::try_from(&ast(source)).unwrap();
+
+        let recompiled = ScorePartwise::try_from(FermataScore::try_from(&ir).unwrap()).unwrap();
+
+        assert_eq!(recompiled.parts, ir.parts);
+    }
+}
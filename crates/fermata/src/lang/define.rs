@@ -0,0 +1,640 @@
+//! Definitions for Fermata syntax.
+//!
+//! `(define motif (note c4 :8) (note e4 :8) (note g4 :8))` names a sequence
+//! of forms. A later `motif` or `(motif)` is replaced by that sequence, so
+//! the three notes appear wherever the motif is used.
+//!
+//! A definition can also take parameters, which are replaced by the
+//! arguments of each call: `(define (bass p) (note p :h) (note p :h))`
+//! makes `(bass c3)` two half notes on C3. Inside a quasiquote template
+//! only unquoted (`,`) parts are evaluated, and they may transpose a pitch
+//! with `(up pitch :interval)` or `(down pitch :interval)`:
+//!
+//! ```text
+//! (define (arp root) `(,root ,(up root :M3) ,(up root :P5)))
+//! (chord (arp c4) :q)   ; (chord (c4 e4 g4) :q)
+//! ```
+//!
+//! Definitions are expanded on the S-expression tree before it is
+//! interpreted, with these rules:
+//!
+//! - A definition applies to the whole score, wherever it is written, so a
+//!   motif may be used before the `define` that introduces it.
+//! - Each name can be defined once; there is no shadowing between
+//!   definitions. Defining a name twice, or naming a definition after a
+//!   pitch such as `c4`, is an error. Inside a body, parameters hide
+//!   definitions of the same name.
+//! - Definitions may use other definitions, but not themselves, directly or
+//!   through others.
+//! - A call must pass exactly as many arguments as the definition has
+//!   parameters.
+//! - A definition replaces every bare symbol with its name outside the head
+//!   of a list, so names should not clash with symbols the language uses as
+//!   values.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::lang::error::{CompileError, CompileResult};
+use crate::lang::pitch::{parse_pitch, parse_pitch_str};
+use crate::sexpr::{Folder, Sexpr, Visitor, walk_list};
+use crate::theory::Interval;
+
+/// Replace every reference to a definition with the forms it defines,
+/// evaluate quasiquote templates, and drop the `define` forms themselves.
+///
+/// Returns the expression unchanged, without copying it, if it has no
+/// definitions or templates.
+///
+/// # Errors
+///
+/// Returns [`CompileError::InvalidDefinition`] for a malformed or
+/// duplicate definition, a cycle between definitions, a call with the
+/// wrong number of arguments, or a template that cannot be evaluated.
+pub fn expand_definitions(sexpr: &Sexpr) -> CompileResult<Cow<'_, Sexpr>> {
+    if !contains_form(sexpr, &["define", "quasiquote", "unquote"]) {
+        return Ok(Cow::Borrowed(sexpr));
+    }
+
+    let mut collector = DefinitionCollector::default();
+    let stripped = collector.fold(sexpr.clone());
+    if let Some(error) = collector.error {
+        return Err(error);
+    }
+    check_cycles(&collector.definitions)?;
+
+    let mut expander = Expander {
+        definitions: &collector.definitions,
+        error: None,
+    };
+    let expanded = expander.fold(stripped);
+    match expander.error {
+        Some(error) => Err(error),
+        None => Ok(Cow::Owned(expanded)),
+    }
+}
+
+/// A named sequence of forms, with its parameters.
+#[derive(Debug)]
+struct Definition {
+    params: Vec<String>,
+    body: Vec<Sexpr>,
+}
+
+/// Whether `sexpr` is a list headed by `head`.
+fn is_form(sexpr: &Sexpr, head: &str) -> bool {
+    sexpr
+        .as_list()
+        .is_some_and(|items| is_form_items(items, head))
+}
+
+/// Whether a list's items start with the symbol `head`.
+fn is_form_items(items: &[Sexpr], head: &str) -> bool {
+    items.first().is_some_and(|first| first.is_symbol(head))
+}
+
+/// Whether `sexpr` contains a list headed by any of `heads`.
+fn contains_form(sexpr: &Sexpr, heads: &[&str]) -> bool {
+    let mut finder = FormFinder {
+        heads,
+        found: false,
+    };
+    finder.visit(sexpr);
+    finder.found
+}
+
+/// Looks for a list headed by one of `heads`.
+struct FormFinder<'a> {
+    heads: &'a [&'a str],
+    found: bool,
+}
+
+impl Visitor for FormFinder<'_> {
+    fn visit_list(&mut self, items: &[Sexpr]) {
+        if items
+            .first()
+            .and_then(Sexpr::as_symbol)
+            .is_some_and(|head| self.heads.contains(&head))
+        {
+            self.found = true;
+        } else if !self.found {
+            walk_list(self, items);
+        }
+    }
+}
+
+/// Removes `define` forms from the tree, recording each definition.
+#[derive(Default)]
+struct DefinitionCollector {
+    definitions: HashMap<String, Definition>,
+    error: Option<CompileError>,
+}
+
+impl DefinitionCollector {
+    fn record(&mut self, items: Vec<Sexpr>) -> CompileResult<()> {
+        let mut items = items.into_iter().skip(1);
+        let (name, params) = match items.next() {
+            Some(Sexpr::Symbol(name)) => (name, Vec::new()),
+            Some(Sexpr::List(signature)) => parse_signature(signature)?,
+            other => {
+                return Err(CompileError::InvalidDefinition(format!(
+                    "expected a name or (name parameters...) after define, got {:?}",
+                    other
+                )));
+            }
+        };
+        let body: Vec<Sexpr> = items.collect();
+
+        if body.is_empty() {
+            return Err(CompileError::InvalidDefinition(format!(
+                "'{}' defines nothing",
+                name
+            )));
+        }
+        if parse_pitch_str(&name).is_ok() {
+            return Err(CompileError::InvalidDefinition(format!(
+                "'{}' is a pitch and cannot be defined",
+                name
+            )));
+        }
+        if body.iter().any(|item| contains_form(item, &["define"])) {
+            return Err(CompileError::InvalidDefinition(format!(
+                "'{}' contains a nested define",
+                name
+            )));
+        }
+        if self.definitions.contains_key(&name) {
+            return Err(CompileError::InvalidDefinition(format!(
+                "'{}' is already defined",
+                name
+            )));
+        }
+
+        self.definitions.insert(name, Definition { params, body });
+        Ok(())
+    }
+}
+
+/// Parse `(name parameters...)`, requiring distinct symbol parameters.
+fn parse_signature(signature: Vec<Sexpr>) -> CompileResult<(String, Vec<String>)> {
+    let mut symbols = Vec::with_capacity(signature.len());
+    for item in signature {
+        match item {
+            Sexpr::Symbol(symbol) => symbols.push(symbol),
+            other => {
+                return Err(CompileError::InvalidDefinition(format!(
+                    "expected a parameter name, got {:?}",
+                    other
+                )));
+            }
+        }
+    }
+
+    let Some((name, params)) = symbols.split_first() else {
+        return Err(CompileError::InvalidDefinition(
+            "expected a name in (name parameters...)".to_string(),
+        ));
+    };
+    for (i, param) in params.iter().enumerate() {
+        if params[..i].contains(param) {
+            return Err(CompileError::InvalidDefinition(format!(
+                "'{}' has parameter '{}' more than once",
+                name, param
+            )));
+        }
+    }
+    Ok((name.clone(), params.to_vec()))
+}
+
+impl Folder for DefinitionCollector {
+    fn fold_list(&mut self, items: Vec<Sexpr>) -> Sexpr {
+        let mut kept = Vec::with_capacity(items.len());
+        for item in items {
+            if !is_form(&item, "define") {
+                kept.push(self.fold(item));
+                continue;
+            }
+            let Sexpr::List(definition) = item else {
+                unreachable!("is_form only matches lists");
+            };
+            if let Err(error) = self.record(definition)
+                && self.error.is_none()
+            {
+                self.error = Some(error);
+            }
+        }
+        Sexpr::List(kept)
+    }
+}
+
+/// Report the first definition that uses itself, checking names in order so
+/// that the reported cycle is stable.
+fn check_cycles(definitions: &HashMap<String, Definition>) -> CompileResult<()> {
+    fn uses<'a>(
+        sexpr: &Sexpr,
+        definition: &Definition,
+        definitions: &'a HashMap<String, Definition>,
+        found: &mut Vec<&'a String>,
+    ) {
+        match sexpr {
+            Sexpr::Symbol(name) if !definition.params.contains(name) => {
+                if let Some((key, _)) = definitions.get_key_value(name) {
+                    found.push(key);
+                }
+            }
+            Sexpr::List(items) => {
+                for item in items {
+                    uses(item, definition, definitions, found);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit<'a>(
+        name: &'a String,
+        definitions: &'a HashMap<String, Definition>,
+        path: &mut Vec<&'a String>,
+        done: &mut Vec<&'a String>,
+    ) -> CompileResult<()> {
+        if done.contains(&name) {
+            return Ok(());
+        }
+        if path.contains(&name) {
+            let cycle: Vec<&str> = path
+                .iter()
+                .map(|n| n.as_str())
+                .chain([name.as_str()])
+                .collect();
+            return Err(CompileError::InvalidDefinition(format!(
+                "cyclic definition: {}",
+                cycle.join(" -> ")
+            )));
+        }
+
+        let definition = &definitions[name];
+        let mut found = Vec::new();
+        for item in &definition.body {
+            uses(item, definition, definitions, &mut found);
+        }
+        path.push(name);
+        for used in found {
+            visit(used, definitions, path, done)?;
+        }
+        path.pop();
+        done.push(name);
+        Ok(())
+    }
+
+    let mut names: Vec<&String> = definitions.keys().collect();
+    names.sort();
+    let mut done = Vec::new();
+    for name in names {
+        visit(name, definitions, &mut Vec::new(), &mut done)?;
+    }
+    Ok(())
+}
+
+/// Splices definitions into the lists that reference them.
+struct Expander<'a> {
+    definitions: &'a HashMap<String, Definition>,
+    error: Option<CompileError>,
+}
+
+impl Expander<'_> {
+    fn fail(&mut self, error: CompileError) {
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+    }
+
+    /// The definition `item` calls and its arguments, if any: a bare defined
+    /// symbol or a list headed by one.
+    fn call(&self, item: &Sexpr) -> Option<(String, Vec<Sexpr>)> {
+        match item {
+            Sexpr::Symbol(name) if self.definitions.contains_key(name) => {
+                Some((name.clone(), Vec::new()))
+            }
+            Sexpr::List(items) => match items.split_first() {
+                Some((Sexpr::Symbol(name), args)) if self.definitions.contains_key(name) => {
+                    Some((name.clone(), args.to_vec()))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Fold `items`, replacing each call with its definition's forms and
+    /// each quasiquote with its value. The head of a list is never a call
+    /// itself.
+    fn fold_items(&mut self, items: Vec<Sexpr>, has_head: bool) -> Vec<Sexpr> {
+        let mut folded = Vec::with_capacity(items.len());
+        for (i, item) in items.into_iter().enumerate() {
+            if has_head && i == 0 {
+                folded.push(self.fold(item));
+                continue;
+            }
+            let item = self.instantiate(item, &HashMap::new());
+            match self.call(&item) {
+                Some((name, args)) => folded.extend(self.expand(&name, args)),
+                None => folded.push(self.fold(item)),
+            }
+        }
+        folded
+    }
+
+    fn expand(&mut self, name: &str, args: Vec<Sexpr>) -> Vec<Sexpr> {
+        let definition = &self.definitions[name];
+        if args.len() != definition.params.len() {
+            let expected = definition.params.len();
+            self.fail(CompileError::InvalidDefinition(format!(
+                "'{}' expects {} argument{}, got {}",
+                name,
+                expected,
+                if expected == 1 { "" } else { "s" },
+                args.len()
+            )));
+            return Vec::new();
+        }
+
+        let args: Vec<Sexpr> = args.into_iter().map(|arg| self.fold(arg)).collect();
+        let bindings: HashMap<&str, Sexpr> = definition
+            .params
+            .iter()
+            .map(String::as_str)
+            .zip(args)
+            .collect();
+        let body: Vec<Sexpr> = definition
+            .body
+            .iter()
+            .map(|item| self.instantiate(item.clone(), &bindings))
+            .collect();
+        // Cycles were rejected up front, so this recursion ends
+        self.fold_items(body, false)
+    }
+
+    /// Substitute parameters in `sexpr` and evaluate its quasiquotes.
+    fn instantiate(&mut self, sexpr: Sexpr, bindings: &HashMap<&str, Sexpr>) -> Sexpr {
+        match sexpr {
+            Sexpr::Symbol(name) => match bindings.get(name.as_str()) {
+                Some(value) => value.clone(),
+                None => Sexpr::Symbol(name),
+            },
+            Sexpr::List(mut items) if is_form_items(&items, "quasiquote") => {
+                if items.len() != 2 {
+                    self.fail(CompileError::InvalidDefinition(
+                        "quasiquote takes exactly one form".to_string(),
+                    ));
+                    return Sexpr::Nil;
+                }
+                self.quasiquote(items.pop().unwrap(), bindings)
+            }
+            Sexpr::List(items) if is_form_items(&items, "unquote") => {
+                self.fail(CompileError::InvalidDefinition(
+                    "unquote (,) outside a quasiquote template".to_string(),
+                ));
+                Sexpr::Nil
+            }
+            Sexpr::List(items) => Sexpr::List(
+                items
+                    .into_iter()
+                    .map(|item| self.instantiate(item, bindings))
+                    .collect(),
+            ),
+            atom => atom,
+        }
+    }
+
+    /// Copy a template, replacing each unquoted form with its value.
+    fn quasiquote(&mut self, template: Sexpr, bindings: &HashMap<&str, Sexpr>) -> Sexpr {
+        match template {
+            Sexpr::List(mut items) if is_form_items(&items, "unquote") => {
+                if items.len() != 2 {
+                    self.fail(CompileError::InvalidDefinition(
+                        "unquote takes exactly one form".to_string(),
+                    ));
+                    return Sexpr::Nil;
+                }
+                match evaluate(&items.pop().unwrap(), bindings) {
+                    Ok(value) => value,
+                    Err(error) => {
+                        self.fail(error);
+                        Sexpr::Nil
+                    }
+                }
+            }
+            Sexpr::List(items) => Sexpr::List(
+                items
+                    .into_iter()
+                    .map(|item| self.quasiquote(item, bindings))
+                    .collect(),
+            ),
+            literal => literal,
+        }
+    }
+}
+
+impl Folder for Expander<'_> {
+    fn fold_list(&mut self, items: Vec<Sexpr>) -> Sexpr {
+        Sexpr::List(self.fold_items(items, true))
+    }
+}
+
+/// Evaluate an unquoted expression: a parameter, a literal, or an
+/// `(up pitch :interval)` or `(down pitch :interval)` transposition.
+fn evaluate(expr: &Sexpr, bindings: &HashMap<&str, Sexpr>) -> CompileResult<Sexpr> {
+    match expr {
+        Sexpr::Symbol(name) => Ok(bindings
+            .get(name.as_str())
+            .cloned()
+            .unwrap_or_else(|| expr.clone())),
+        Sexpr::List(items) => match items.as_slice() {
+            [head, pitch, interval] if head.is_symbol("up") || head.is_symbol("down") => transpose(
+                &evaluate(pitch, bindings)?,
+                &evaluate(interval, bindings)?,
+                head.is_symbol("up"),
+            ),
+            _ => Err(CompileError::InvalidDefinition(format!(
+                "cannot evaluate {:?}; expected a parameter, a literal, \
+                 or (up pitch :interval) / (down pitch :interval)",
+                expr
+            ))),
+        },
+        literal => Ok(literal.clone()),
+    }
+}
+
+/// Move a pitch symbol up or down by an interval keyword such as `:M3`.
+fn transpose(pitch: &Sexpr, interval: &Sexpr, up: bool) -> CompileResult<Sexpr> {
+    let Some(name) = pitch.as_symbol() else {
+        return Err(CompileError::type_mismatch(
+            "pitch symbol",
+            format!("{:?}", pitch),
+        ));
+    };
+    let pitch = parse_pitch(name)?;
+    let (interval_name, interval) = interval
+        .as_keyword()
+        .and_then(|k| Some((k, Interval::from_name(k)?)))
+        .ok_or_else(|| {
+            CompileError::InvalidDefinition(format!(
+                "expected an interval such as :M3 or :P5, got {:?}",
+                interval
+            ))
+        })?;
+
+    let moved = if up {
+        pitch.add_interval(interval)
+    } else {
+        pitch.subtract_interval(interval)
+    };
+    let spelled = moved.map(|p| p.to_string().to_lowercase());
+    let Some(spelled) = spelled.filter(|s| parse_pitch_str(s).is_ok()) else {
+        return Err(CompileError::InvalidDefinition(format!(
+            "({} {} :{}) is out of range or has no Fermata spelling",
+            if up { "up" } else { "down" },
+            name,
+            interval_name
+        )));
+    };
+    Ok(Sexpr::symbol(spelled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::measure::MusicDataElement;
+    use crate::lang::compile;
+    use crate::sexpr::parse;
+
+    fn expand(source: &str) -> CompileResult<Sexpr> {
+        expand_definitions(&parse(source).unwrap()).map(Cow::into_owned)
+    }
+
+    #[test]
+    fn test_expand_definitions_motif_used_twice_gives_six_notes() {
+        let score = compile(
+            "(score
+               (define motif (note c4 :8) (note e4 :8) (note g4 :8))
+               (part :piano (measure (time 3 4) motif (motif))))",
+        )
+        .unwrap();
+
+        let notes = score.parts[0].measures[0]
+            .content
+            .iter()
+            .filter(|e| matches!(e, MusicDataElement::Note(_)))
+            .count();
+        assert_eq!(notes, 6);
+    }
+
+    #[test]
+    fn test_expand_definitions_allows_forward_references() {
+        let expanded = expand(
+            "(score (part :piano (measure phrase))
+                    (define phrase motif (rest :q))
+                    (define motif (note c4 :q)))",
+        )
+        .unwrap();
+
+        assert_eq!(
+            expanded,
+            parse("(score (part :piano (measure (note c4 :q) (rest :q))))").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_expand_definitions_without_define_borrows() {
+        let sexpr = parse("(score (part :piano (measure (note c4 :q))))").unwrap();
+
+        assert!(matches!(
+            expand_definitions(&sexpr).unwrap(),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_expand_definitions_cycle_fails() {
+        let error =
+            expand("(score (define a (note c4 :q) b) (define b a) (part :piano (measure a)))")
+                .unwrap_err();
+
+        assert!(
+            error.to_string().contains("cyclic definition: a -> b -> a"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_expand_definitions_invalid_definitions_fail() {
+        for source in [
+            "(score (define m (note c4 :q)) (define m (note d4 :q)))",
+            "(score (define c4 (note d4 :q)))",
+            "(score (define m))",
+            "(score (define m (note c4 :q)) (part :piano (measure (m 2))))",
+            "(score (define (m p p) (note p :q)))",
+        ] {
+            assert!(
+                matches!(expand(source), Err(CompileError::InvalidDefinition(_))),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn test_expand_definitions_arp_template_gives_major_triad() {
+        let expanded = expand(
+            "(score
+               (define (arp root) `(,root ,(up root :M3) ,(up root :P5)))
+               (part :piano (measure (chord (arp c4) :q))))",
+        )
+        .unwrap();
+
+        assert_eq!(
+            expanded,
+            parse("(score (part :piano (measure (chord (c4 e4 g4) :q))))").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_expand_definitions_substitutes_parameters_outside_templates() {
+        let expanded = expand(
+            "(score
+               (define (bass p) (note p :h) (note ,(down p :P8) :h))
+               (part :piano (measure (bass eb3))))",
+        );
+
+        // Unquote only works inside a template
+        assert!(matches!(expanded, Err(CompileError::InvalidDefinition(_))));
+
+        let expanded = expand(
+            "(score
+               (define (bass p) (note p :h) `(note ,(down p :P8) :h))
+               (part :piano (measure (bass eb3))))",
+        )
+        .unwrap();
+
+        assert_eq!(
+            expanded,
+            parse("(score (part :piano (measure (note eb3 :h) (note eb2 :h))))").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_expand_definitions_wrong_arity_fails() {
+        let error = expand(
+            "(score
+               (define (arp root) `(,root ,(up root :M3)))
+               (part :piano (measure (chord (arp c4 e4) :q))))",
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Invalid definition: 'arp' expects 1 argument, got 2"
+        );
+    }
+}
@@ -3,15 +3,25 @@
 //! This module handles compiling direction S-expressions (dynamics, tempo,
 //! rehearsal marks, etc.) into IR Direction types.
 
-use crate::ir::common::{AboveBelow, Font, FormattedText, PrintStyle, StartStop};
+use crate::ir::common::{
+    AboveBelow, Font, FontStyle, FontWeight, FormattedText, Position, PrintStyle, StartStop, YesNo,
+};
 use crate::ir::direction::{
     Coda, Direction, DirectionType, DirectionTypeContent, DynamicElement, Dynamics, Metronome,
-    MetronomeContent, Pedal, PedalType, PerMinute, Segno, Wedge, WedgeType, Words,
+    MetronomeContent, OctaveShift, Pedal, PedalType, PerMinute, Segno, Sound, UpDownStopContinue,
+    Wedge, WedgeType, Words,
 };
 use crate::ir::duration::NoteTypeValue;
+use crate::ir::measure::MusicDataElement;
+use crate::ir::note::Note;
 use crate::sexpr::Sexpr;
 
-use super::ast::{DurationBase, DynamicMark, FermataDirection, TempoMark};
+use super::ast::{
+    DurationBase, DynamicMark, FermataDirection, FermataDuration, FermataHairpin, FermataOttava,
+    HairpinKind, MeasureElement, OttavaShift, TempoMark, TextMark,
+};
+use super::defaults::DEFAULT_DIVISIONS;
+use super::duration::compile_duration_divisions;
 use super::error::{CompileError, CompileResult};
 
 // =============================================================================
@@ -108,50 +118,14 @@ pub fn compile_dynamic_mark(mark: &DynamicMark) -> CompileResult<Direction> {
                 StartStop::Start => WedgeType::Crescendo,
                 StartStop::Stop => WedgeType::Stop,
             };
-            Ok(Direction {
-                placement: Some(AboveBelow::Below),
-                directive: None,
-                direction_types: vec![DirectionType {
-                    content: DirectionTypeContent::Wedge(Wedge {
-                        r#type: wedge_type,
-                        number: Some(1),
-                        spread: None,
-                        niente: None,
-                        line_type: None,
-                        position: Default::default(),
-                        color: None,
-                    }),
-                }],
-                offset: None,
-                voice: None,
-                staff: None,
-                sound: None,
-            })
+            Ok(wedge_direction(wedge_type, None))
         }
         DynamicMark::Diminuendo(action) => {
             let wedge_type = match action {
                 StartStop::Start => WedgeType::Diminuendo,
                 StartStop::Stop => WedgeType::Stop,
             };
-            Ok(Direction {
-                placement: Some(AboveBelow::Below),
-                directive: None,
-                direction_types: vec![DirectionType {
-                    content: DirectionTypeContent::Wedge(Wedge {
-                        r#type: wedge_type,
-                        number: Some(1),
-                        spread: None,
-                        niente: None,
-                        line_type: None,
-                        position: Default::default(),
-                        color: None,
-                    }),
-                }],
-                offset: None,
-                voice: None,
-                staff: None,
-                sound: None,
-            })
+            Ok(wedge_direction(wedge_type, None))
         }
         // All other dynamics
         _ => {
@@ -401,7 +375,23 @@ pub fn compile_tempo_mark(mark: &TempoMark) -> CompileResult<Direction> {
         offset: None,
         voice: None,
         staff: None,
-        sound: None,
+        sound: tempo_sound(mark),
+    })
+}
+
+/// Playback tempo for a metronome marking.
+///
+/// `<sound tempo>` is always in quarter notes per minute, so other beat
+/// units are scaled: half = 60 plays at 120, dotted quarter = 60 at 90.
+fn tempo_sound(mark: &TempoMark) -> Option<Sound> {
+    let (base, per_minute) = (mark.beat_unit?, mark.per_minute?);
+    let beat = compile_duration_divisions(&FermataDuration {
+        base,
+        dots: mark.beat_unit_dots,
+    });
+    Some(Sound {
+        tempo: Some(per_minute as f64 * beat as f64 / DEFAULT_DIVISIONS as f64),
+        ..Default::default()
     })
 }
 
@@ -434,8 +424,11 @@ fn duration_base_to_note_type(base: &DurationBase) -> NoteTypeValue {
 /// Supports forms like:
 /// - `(rehearsal "A")` - rehearsal mark
 /// - `(words "dolce")` - text direction
+/// - `(text "rit." :above :italic)` - text direction with placement and font
 /// - `(segno)` - segno sign
 /// - `(coda)` - coda sign
+/// - `(direction :to-coda)` - jump instruction (also `:dal-segno`,
+///   `:da-capo`, `:fine`)
 /// - `(pedal :start)` - pedal start
 /// - `(pedal :stop)` - pedal stop
 pub fn compile_direction(sexpr: &Sexpr) -> CompileResult<Direction> {
@@ -457,8 +450,10 @@ pub fn compile_direction(sexpr: &Sexpr) -> CompileResult<Direction> {
     match head {
         "rehearsal" => compile_rehearsal(&args[1..]),
         "words" => compile_words(&args[1..]),
+        "text" => compile_text_mark(&parse_text_form(&args[1..])?),
         "segno" => compile_segno(),
         "coda" => compile_coda(),
+        "direction" => compile_fermata_direction(&parse_navigation_form(&args[1..])?),
         "pedal" => compile_pedal(&args[1..]),
         _ => Err(CompileError::UnknownForm(format!(
             "unknown direction: {}",
@@ -471,9 +466,38 @@ pub fn compile_direction(sexpr: &Sexpr) -> CompileResult<Direction> {
 pub fn compile_fermata_direction(dir: &FermataDirection) -> CompileResult<Direction> {
     match dir {
         FermataDirection::Words(text) => compile_words_text(text),
+        FermataDirection::Text(mark) => compile_text_mark(mark),
         FermataDirection::Rehearsal(mark) => compile_rehearsal_text(mark),
         FermataDirection::Segno => compile_segno(),
         FermataDirection::Coda => compile_coda(),
+        FermataDirection::ToCoda => compile_navigation(
+            "To Coda",
+            Sound {
+                tocoda: Some("coda".to_string()),
+                ..Default::default()
+            },
+        ),
+        FermataDirection::DalSegno => compile_navigation(
+            "D.S.",
+            Sound {
+                dalsegno: Some("segno".to_string()),
+                ..Default::default()
+            },
+        ),
+        FermataDirection::DaCapo => compile_navigation(
+            "D.C.",
+            Sound {
+                dacapo: Some(YesNo::Yes),
+                ..Default::default()
+            },
+        ),
+        FermataDirection::Fine => compile_navigation(
+            "Fine",
+            Sound {
+                fine: Some("yes".to_string()),
+                ..Default::default()
+            },
+        ),
         FermataDirection::PedalStart => compile_pedal_action(PedalType::Start),
         FermataDirection::PedalStop => compile_pedal_action(PedalType::Stop),
     }
@@ -544,7 +568,81 @@ fn compile_words_text(text: &str) -> CompileResult<Direction> {
     })
 }
 
-/// Compile a segno sign.
+/// Parse the arguments of a `(text "dolce" [:above|:below] [:italic] [:bold])`
+/// form.
+pub fn parse_text_form(args: &[Sexpr]) -> CompileResult<TextMark> {
+    let text = args
+        .first()
+        .ok_or(CompileError::MissingField("text"))?
+        .as_string()
+        .ok_or_else(|| CompileError::type_mismatch("string", format!("{:?}", args[0])))?;
+
+    let mut mark = TextMark {
+        text: text.to_string(),
+        placement: None,
+        italic: false,
+        bold: false,
+    };
+    for arg in &args[1..] {
+        match arg.as_keyword() {
+            Some("above") => mark.placement = Some(AboveBelow::Above),
+            Some("below") => mark.placement = Some(AboveBelow::Below),
+            Some("italic") => mark.italic = true,
+            Some("bold") => mark.bold = true,
+            _ => {
+                return Err(CompileError::UnknownForm(format!(
+                    "unknown text option {:?} (expected :above, :below, :italic, or :bold)",
+                    arg
+                )));
+            }
+        }
+    }
+    Ok(mark)
+}
+
+/// Compile expressive text to a words direction.
+fn compile_text_mark(mark: &TextMark) -> CompileResult<Direction> {
+    let mut direction = compile_words_text(&mark.text)?;
+    direction.placement = mark.placement;
+    if let DirectionTypeContent::Words(words) = &mut direction.direction_types[0].content {
+        words[0].print_style.font = Font {
+            font_style: mark.italic.then_some(FontStyle::Italic),
+            font_weight: mark.bold.then_some(FontWeight::Bold),
+            ..Font::default()
+        };
+    }
+    Ok(direction)
+}
+
+/// Parse the arguments of a `(direction :to-coda)` form: one of
+/// `:to-coda`, `:dal-segno`, `:da-capo`, or `:fine`.
+pub fn parse_navigation_form(args: &[Sexpr]) -> CompileResult<FermataDirection> {
+    let kind = args
+        .first()
+        .ok_or(CompileError::MissingField("direction type"))?
+        .as_keyword()
+        .ok_or_else(|| CompileError::type_mismatch("keyword", format!("{:?}", args[0])))?;
+    match kind {
+        "to-coda" => Ok(FermataDirection::ToCoda),
+        "dal-segno" | "ds" => Ok(FermataDirection::DalSegno),
+        "da-capo" | "dc" => Ok(FermataDirection::DaCapo),
+        "fine" => Ok(FermataDirection::Fine),
+        _ => Err(CompileError::UnknownForm(format!(
+            "unknown direction: :{} (expected :to-coda, :dal-segno, :da-capo, or :fine)",
+            kind
+        ))),
+    }
+}
+
+/// Compile a jump instruction: its printed text plus the `<sound>` that
+/// tells playback where to go.
+fn compile_navigation(text: &str, sound: Sound) -> CompileResult<Direction> {
+    let mut direction = compile_words_text(text)?;
+    direction.sound = Some(sound);
+    Ok(direction)
+}
+
+/// Compile a segno sign, marking it as the target of a dal segno.
 fn compile_segno() -> CompileResult<Direction> {
     Ok(Direction {
         placement: Some(AboveBelow::Above),
@@ -558,11 +656,14 @@ fn compile_segno() -> CompileResult<Direction> {
         offset: None,
         voice: None,
         staff: None,
-        sound: None,
+        sound: Some(Sound {
+            segno: Some("segno".to_string()),
+            ..Default::default()
+        }),
     })
 }
 
-/// Compile a coda sign.
+/// Compile a coda sign, marking it as the target of a "To Coda".
 fn compile_coda() -> CompileResult<Direction> {
     Ok(Direction {
         placement: Some(AboveBelow::Above),
@@ -576,7 +677,10 @@ fn compile_coda() -> CompileResult<Direction> {
         offset: None,
         voice: None,
         staff: None,
-        sound: None,
+        sound: Some(Sound {
+            coda: Some("coda".to_string()),
+            ..Default::default()
+        }),
     })
 }
 
@@ -629,6 +733,282 @@ fn compile_pedal_action(pedal_type: PedalType) -> CompileResult<Direction> {
     })
 }
 
+// =============================================================================
+// Octave Shift Compilation
+// =============================================================================
+
+/// Parse an ottava form into a FermataOttava AST.
+///
+/// Expected format: `[:up | :down] [:8 | :15 | :22] elements...`, where each
+/// element is a note, rest, chord, tuplet, slur, or tie form, e.g.
+/// `(ottava :up (note c6 :q) (note d6 :q))`. The shift defaults to up by
+/// an octave (8va).
+pub fn parse_ottava_form(items: &[Sexpr]) -> CompileResult<FermataOttava> {
+    let mut shift = OttavaShift::default();
+    let mut size = 8;
+    let mut notes = Vec::new();
+
+    for item in items {
+        if let Some(kw) = item.as_keyword() {
+            match kw {
+                "up" => shift = OttavaShift::Up,
+                "down" => shift = OttavaShift::Down,
+                "8" | "15" | "22" => size = kw.parse().unwrap_or(8),
+                _ => {
+                    return Err(CompileError::UnknownForm(format!(
+                        ":{} in ottava (expected :up, :down, :8, :15, or :22)",
+                        kw
+                    )));
+                }
+            }
+            continue;
+        }
+
+        notes.push(parse_spanned_element(item, "ottava")?);
+    }
+
+    if notes.is_empty() {
+        return Err(CompileError::InvalidNote(
+            "ottava requires at least one note".to_string(),
+        ));
+    }
+
+    Ok(FermataOttava { shift, size, notes })
+}
+
+/// Compile an ottava into its notes bracketed by octave-shift directions.
+///
+/// A start direction (`type="up"` or `"down"`) precedes the first element
+/// and a matching `stop` direction follows the last; both carry the size.
+pub fn compile_fermata_ottava(ottava: &FermataOttava) -> CompileResult<Vec<MusicDataElement>> {
+    let (start, placement) = match ottava.shift {
+        OttavaShift::Up => (UpDownStopContinue::Up, AboveBelow::Above),
+        OttavaShift::Down => (UpDownStopContinue::Down, AboveBelow::Below),
+    };
+    let size = Some(ottava.size);
+
+    let mut content = vec![octave_shift_direction(start, size, placement)];
+    content.extend(compile_spanned_elements(&ottava.notes, "ottava")?);
+    content.push(octave_shift_direction(
+        UpDownStopContinue::Stop,
+        size,
+        placement,
+    ));
+
+    Ok(content)
+}
+
+/// Parse one element of a spanning form such as `ottava`, `cresc`, or
+/// `slur`: a note, rest, chord, tuplet, slur, tie, or tremolo form.
+pub(crate) fn parse_spanned_element(item: &Sexpr, form: &str) -> CompileResult<MeasureElement> {
+    let sub_items = item.as_list().filter(|l| !l.is_empty()).ok_or_else(|| {
+        CompileError::UnknownForm(format!("expected note form in {}, got {:?}", form, item))
+    })?;
+    let args = &sub_items[1..];
+    let element = match sub_items[0].as_symbol() {
+        Some("note") => MeasureElement::Note(crate::lang::note::parse_note_form(args)?),
+        Some("rest") => MeasureElement::Rest(crate::lang::note::parse_rest_form(args)?),
+        Some("chord") => MeasureElement::Chord(crate::lang::chord::parse_chord_form(args)?),
+        Some("tuplet") => MeasureElement::Tuplet(crate::lang::tuplet::parse_tuplet_form(args)?),
+        Some("slur") => {
+            MeasureElement::SlurGroup(crate::lang::connectors::parse_slur_group_form(args)?)
+        }
+        Some("tie") => {
+            MeasureElement::TieGroup(crate::lang::connectors::parse_tie_group_form(args)?)
+        }
+        Some("tremolo") => MeasureElement::Note(crate::lang::tremolo::parse_tremolo_form(args)?),
+        Some("tremolo-pair") => {
+            MeasureElement::TremoloPair(crate::lang::tremolo::parse_tremolo_pair_form(args)?)
+        }
+        _ => {
+            return Err(CompileError::UnknownForm(format!(
+                "unexpected element {:?} in {}, expected note, rest, chord, tuplet, slur, tie, or tremolo",
+                sub_items[0], form
+            )));
+        }
+    };
+    Ok(element)
+}
+
+/// Compile the elements of a spanning form to notes, in order.
+fn compile_spanned_elements(
+    elements: &[MeasureElement],
+    form: &str,
+) -> CompileResult<Vec<MusicDataElement>> {
+    let mut content = Vec::new();
+    for element in elements {
+        content.extend(
+            compile_spanned_element(element, form, OpenSpans::default())?
+                .into_iter()
+                .map(|note| MusicDataElement::Note(Box::new(note))),
+        );
+    }
+    Ok(content)
+}
+
+/// The slurs and tuplets already open around a spanned element, so that
+/// nested ones are given distinct numbers.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct OpenSpans {
+    /// Enclosing slurs
+    pub slurs: u8,
+    /// Enclosing tuplets
+    pub tuplets: u8,
+}
+
+/// Compile one element of a spanning form, as parsed by
+/// [`parse_spanned_element`], to its notes.
+pub(crate) fn compile_spanned_element(
+    element: &MeasureElement,
+    form: &str,
+    open: OpenSpans,
+) -> CompileResult<Vec<Note>> {
+    let notes = match element {
+        MeasureElement::Note(fermata_note) => {
+            let mut notes = vec![crate::lang::note::compile_fermata_note(fermata_note)?];
+            notes.extend(crate::lang::note::compile_touching_notes(fermata_note)?);
+            notes
+        }
+        MeasureElement::Rest(fermata_rest) => {
+            vec![crate::lang::note::compile_fermata_rest(fermata_rest)?]
+        }
+        MeasureElement::Chord(fermata_chord) => {
+            crate::lang::chord::compile_fermata_chord(fermata_chord)?
+        }
+        MeasureElement::Tuplet(fermata_tuplet) => {
+            crate::lang::tuplet::compile_tuplet_level(fermata_tuplet, open)?
+        }
+        MeasureElement::SlurGroup(slur) => crate::lang::connectors::compile_slur_level(slur, open)?,
+        MeasureElement::TieGroup(tie) => crate::lang::connectors::compile_fermata_tie(tie)?,
+        MeasureElement::TremoloPair(pair) => {
+            crate::lang::tremolo::compile_fermata_tremolo_pair(pair)?
+        }
+        _ => {
+            return Err(CompileError::UnknownForm(format!(
+                "unsupported element type in {}: {:?}",
+                form, element
+            )));
+        }
+    };
+    Ok(notes)
+}
+
+/// Build a direction holding a single octave shift.
+fn octave_shift_direction(
+    r#type: UpDownStopContinue,
+    size: Option<u8>,
+    placement: AboveBelow,
+) -> MusicDataElement {
+    MusicDataElement::Direction(Box::new(Direction {
+        placement: Some(placement),
+        directive: None,
+        direction_types: vec![DirectionType {
+            content: DirectionTypeContent::OctaveShift(OctaveShift {
+                r#type,
+                number: None,
+                size,
+                position: Position::default(),
+            }),
+        }],
+        offset: None,
+        voice: None,
+        staff: None,
+        sound: None,
+    }))
+}
+
+// =============================================================================
+// Hairpin Compilation
+// =============================================================================
+
+/// Parse a hairpin form into a FermataHairpin AST.
+///
+/// Expected format: `[:niente] elements...` after the `cresc` or `dim`
+/// head, where each element is a note, rest, chord, tuplet, slur, or tie
+/// form, e.g. `(cresc (note c4 :q) (note d4 :q))`.
+pub fn parse_hairpin_form(kind: HairpinKind, items: &[Sexpr]) -> CompileResult<FermataHairpin> {
+    let mut niente = false;
+    let mut notes = Vec::new();
+
+    for item in items {
+        if let Some(kw) = item.as_keyword() {
+            match kw {
+                "niente" => niente = true,
+                _ => {
+                    return Err(CompileError::UnknownForm(format!(
+                        ":{} in hairpin (expected :niente)",
+                        kw
+                    )));
+                }
+            }
+            continue;
+        }
+        notes.push(parse_spanned_element(item, "hairpin")?);
+    }
+
+    if notes.is_empty() {
+        return Err(CompileError::InvalidNote(
+            "hairpin requires at least one note".to_string(),
+        ));
+    }
+
+    Ok(FermataHairpin {
+        kind,
+        niente,
+        notes,
+    })
+}
+
+/// Compile a hairpin into its notes bracketed by wedge directions.
+///
+/// A `crescendo` or `diminuendo` wedge precedes the first element and a
+/// `stop` wedge follows the last. With `niente`, the circle goes on the
+/// point of the hairpin: the start of a crescendo or the stop of a
+/// diminuendo.
+pub fn compile_fermata_hairpin(hairpin: &FermataHairpin) -> CompileResult<Vec<MusicDataElement>> {
+    let (start, niente_at_start) = match hairpin.kind {
+        HairpinKind::Crescendo => (WedgeType::Crescendo, true),
+        HairpinKind::Diminuendo => (WedgeType::Diminuendo, false),
+    };
+    let niente =
+        |at_start: bool| (hairpin.niente && at_start == niente_at_start).then_some(YesNo::Yes);
+
+    let mut content = vec![MusicDataElement::Direction(Box::new(wedge_direction(
+        start,
+        niente(true),
+    )))];
+    content.extend(compile_spanned_elements(&hairpin.notes, "hairpin")?);
+    content.push(MusicDataElement::Direction(Box::new(wedge_direction(
+        WedgeType::Stop,
+        niente(false),
+    ))));
+
+    Ok(content)
+}
+
+/// Build a direction holding a single wedge, placed below the staff.
+fn wedge_direction(r#type: WedgeType, niente: Option<YesNo>) -> Direction {
+    Direction {
+        placement: Some(AboveBelow::Below),
+        directive: None,
+        direction_types: vec![DirectionType {
+            content: DirectionTypeContent::Wedge(Wedge {
+                r#type,
+                number: Some(1),
+                spread: None,
+                niente,
+                line_type: None,
+                position: Default::default(),
+                color: None,
+            }),
+        }],
+        offset: None,
+        voice: None,
+        staff: None,
+        sound: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1011,6 +1391,8 @@ mod tests {
             } else {
                 panic!("Expected Metronome content");
             }
+            // Playback tempo is counted in quarter notes
+            assert_eq!(dir.sound.and_then(|s| s.tempo), Some(120.0));
         }
 
         #[test]
@@ -1215,6 +1597,53 @@ mod tests {
             }
         }
 
+        #[test]
+        fn test_compile_text_dolce_emits_words() {
+            let score = crate::lang::compile(
+                r#"(score (part :piano (measure (text "dolce") (note c4 :w))))"#,
+            )
+            .unwrap();
+            let xml = crate::musicxml::emit(&score).unwrap();
+
+            assert!(xml.contains("<direction>"), "{}", xml);
+            assert!(xml.contains("<words>dolce</words>"), "{}", xml);
+        }
+
+        #[test]
+        fn test_compile_text_placement_and_font() {
+            let sexpr = parse("(text \"rit.\" :above :italic)").unwrap();
+            let dir = compile_direction(&sexpr).unwrap();
+            assert_eq!(dir.placement, Some(AboveBelow::Above));
+            let DirectionTypeContent::Words(w) = &dir.direction_types[0].content else {
+                panic!("Expected Words content");
+            };
+            assert_eq!(w[0].value, "rit.");
+            assert_eq!(w[0].print_style.font.font_style, Some(FontStyle::Italic));
+            assert_eq!(w[0].print_style.font.font_weight, None);
+
+            let xml = crate::musicxml::emit(
+                &crate::lang::compile(
+                    r#"(score (part :piano (measure (text "cresc. poco a poco" :below :bold) (note c4 :w))))"#,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            assert!(xml.contains(r#"<direction placement="below">"#), "{}", xml);
+            assert!(
+                xml.contains(r#"<words font-weight="bold">cresc. poco a poco</words>"#),
+                "{}",
+                xml
+            );
+        }
+
+        #[test]
+        fn test_parse_text_form_invalid_is_error() {
+            for source in ["(text)", "(text dolce)", "(text \"dolce\" :sideways)"] {
+                let sexpr = parse(source).unwrap();
+                assert!(compile_direction(&sexpr).is_err(), "{}", source);
+            }
+        }
+
         #[test]
         fn test_compile_segno() {
             let sexpr = parse("(segno)").unwrap();
@@ -1235,6 +1664,43 @@ mod tests {
             } else {
                 panic!("Expected Coda content");
             }
+            assert_eq!(dir.sound.unwrap().coda.as_deref(), Some("coda"));
+        }
+
+        #[test]
+        fn test_compile_navigation_directions_set_sound() {
+            let to_coda = compile_direction(&parse("(direction :to-coda)").unwrap()).unwrap();
+            assert!(matches!(
+                &to_coda.direction_types[0].content,
+                DirectionTypeContent::Words(w) if w[0].value == "To Coda"
+            ));
+            assert_eq!(to_coda.sound.unwrap().tocoda.as_deref(), Some("coda"));
+
+            let ds = compile_direction(&parse("(direction :dal-segno)").unwrap()).unwrap();
+            assert_eq!(ds.sound.unwrap().dalsegno.as_deref(), Some("segno"));
+            let dc = compile_direction(&parse("(direction :da-capo)").unwrap()).unwrap();
+            assert_eq!(dc.sound.unwrap().dacapo, Some(YesNo::Yes));
+            let fine = compile_direction(&parse("(direction :fine)").unwrap()).unwrap();
+            assert_eq!(fine.sound.unwrap().fine.as_deref(), Some("yes"));
+
+            assert!(compile_direction(&parse("(direction :sideways)").unwrap()).is_err());
+        }
+
+        #[test]
+        fn test_compile_score_rehearsal_and_segno_emit() {
+            let score = crate::lang::compile(
+                r#"(score (part :piano
+                     (measure (rehearsal "B") (segno) (note c4 :w))
+                     (measure (note d4 :h) (direction :dal-segno) (note e4 :h))))"#,
+            )
+            .unwrap();
+            let xml = crate::musicxml::emit(&score).unwrap();
+
+            let rehearsal = xml.find("<rehearsal>B</rehearsal>").unwrap();
+            let direction = xml[..rehearsal].rfind("<direction").unwrap();
+            assert!(!xml[direction..rehearsal].contains("</direction>"));
+            assert!(xml.contains(r#"<sound segno="segno"/>"#));
+            assert!(xml.contains(r#"<sound dalsegno="segno"/>"#));
         }
 
         #[test]
@@ -1356,4 +1822,141 @@ mod tests {
             }
         }
     }
+
+    mod ottava_tests {
+        use super::*;
+
+        fn ottava(source: &str) -> FermataOttava {
+            let sexpr = parse(source).unwrap();
+            parse_ottava_form(&sexpr.as_list().unwrap()[1..]).unwrap()
+        }
+
+        fn octave_shift(element: &MusicDataElement) -> &OctaveShift {
+            match element {
+                MusicDataElement::Direction(d) => match &d.direction_types[0].content {
+                    DirectionTypeContent::OctaveShift(shift) => shift,
+                    other => panic!("Expected OctaveShift, got {:?}", other),
+                },
+                other => panic!("Expected Direction, got {:?}", other),
+            }
+        }
+
+        #[test]
+        fn test_parse_ottava_form_defaults() {
+            let parsed = ottava("(ottava (note c6 :q))");
+            assert_eq!(parsed.shift, OttavaShift::Up);
+            assert_eq!(parsed.size, 8);
+            assert_eq!(parsed.notes.len(), 1);
+        }
+
+        #[test]
+        fn test_compile_fermata_ottava_up_brackets_notes() {
+            let content =
+                compile_fermata_ottava(&ottava("(ottava :up (note c6 :q) (note d6 :q))")).unwrap();
+            assert_eq!(content.len(), 4);
+
+            let start = octave_shift(&content[0]);
+            assert_eq!(start.r#type, UpDownStopContinue::Up);
+            assert_eq!(start.size, Some(8));
+            assert!(matches!(content[1], MusicDataElement::Note(_)));
+            assert!(matches!(content[2], MusicDataElement::Note(_)));
+            assert_eq!(octave_shift(&content[3]).r#type, UpDownStopContinue::Stop);
+        }
+
+        #[test]
+        fn test_compile_fermata_ottava_down_15() {
+            let content =
+                compile_fermata_ottava(&ottava("(ottava :down :15 (chord (c2 e2) :h))")).unwrap();
+            let start = octave_shift(&content[0]);
+            assert_eq!(start.r#type, UpDownStopContinue::Down);
+            assert_eq!(start.size, Some(15));
+            assert_eq!(octave_shift(content.last().unwrap()).size, Some(15));
+        }
+
+        #[test]
+        fn test_parse_ottava_form_errors() {
+            let empty = parse("(ottava :up)").unwrap();
+            assert!(parse_ottava_form(&empty.as_list().unwrap()[1..]).is_err());
+            let bad = parse("(ottava :sideways (note c6 :q))").unwrap();
+            assert!(parse_ottava_form(&bad.as_list().unwrap()[1..]).is_err());
+        }
+    }
+
+    mod hairpin_tests {
+        use super::*;
+
+        fn wedge(element: &MusicDataElement) -> &Wedge {
+            match element {
+                MusicDataElement::Direction(d) => match &d.direction_types[0].content {
+                    DirectionTypeContent::Wedge(wedge) => wedge,
+                    other => panic!("Expected Wedge, got {:?}", other),
+                },
+                other => panic!("Expected Direction, got {:?}", other),
+            }
+        }
+
+        #[test]
+        fn test_compile_crescendo_wedges_bracket_notes() {
+            let score = crate::lang::compile(
+                "(score (part :piano (measure
+                   (cresc (note c4 :q) (note d4 :q) (note e4 :q)) (note f4 :q))))",
+            )
+            .unwrap();
+            let content: Vec<&MusicDataElement> = score.parts[0].measures[0]
+                .content
+                .iter()
+                .filter(|e| !matches!(e, MusicDataElement::Attributes(_)))
+                .collect();
+
+            assert_eq!(content.len(), 6);
+            assert_eq!(wedge(content[0]).r#type, WedgeType::Crescendo);
+            assert_eq!(wedge(content[0]).niente, None);
+            assert!(
+                content[1..4]
+                    .iter()
+                    .all(|e| matches!(e, MusicDataElement::Note(_)))
+            );
+            assert_eq!(wedge(content[4]).r#type, WedgeType::Stop);
+            assert!(matches!(content[5], MusicDataElement::Note(_)));
+
+            let xml = crate::musicxml::emit(&score).unwrap();
+            let start = xml.find(r#"<wedge type="crescendo""#).unwrap();
+            let stop = xml.find(r#"<wedge type="stop""#).unwrap();
+            let notes: Vec<usize> = xml.match_indices("<note>").map(|(i, _)| i).collect();
+            assert!(start < notes[0] && notes[2] < stop && stop < notes[3]);
+        }
+
+        #[test]
+        fn test_compile_hairpin_niente_on_the_point() {
+            let parse_hairpin = |kind, source: &str| {
+                let sexpr = parse(source).unwrap();
+                parse_hairpin_form(kind, &sexpr.as_list().unwrap()[1..]).unwrap()
+            };
+
+            let cresc = compile_fermata_hairpin(&parse_hairpin(
+                HairpinKind::Crescendo,
+                "(cresc :niente (note c4 :h))",
+            ))
+            .unwrap();
+            assert_eq!(wedge(&cresc[0]).niente, Some(YesNo::Yes));
+            assert_eq!(wedge(&cresc[2]).niente, None);
+
+            let dim = compile_fermata_hairpin(&parse_hairpin(
+                HairpinKind::Diminuendo,
+                "(dim :niente (note c4 :h))",
+            ))
+            .unwrap();
+            assert_eq!(wedge(&dim[0]).r#type, WedgeType::Diminuendo);
+            assert_eq!(wedge(&dim[0]).niente, None);
+            assert_eq!(wedge(&dim[2]).niente, Some(YesNo::Yes));
+        }
+
+        #[test]
+        fn test_parse_hairpin_form_errors() {
+            let empty = parse("(cresc :niente)").unwrap();
+            assert!(
+                parse_hairpin_form(HairpinKind::Crescendo, &empty.as_list().unwrap()[1..]).is_err()
+            );
+        }
+    }
 }
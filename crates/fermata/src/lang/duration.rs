@@ -4,8 +4,10 @@
 //! (e.g., :q, :quarter, :h., :w) and compiling them to the IR representation.
 
 use crate::ir::common::PositiveDivisions;
-use crate::ir::duration::{Dot, NoteType, NoteTypeValue};
-use crate::lang::ast::{DurationBase, FermataDuration};
+use crate::ir::duration::{Dot, NoteType, NoteTypeValue, divisions_for};
+use crate::lang::ast::{
+    DurationBase, FermataDuration, FermataMeasure, FermataScore, MeasureElement, TimeSpec,
+};
 use crate::lang::defaults::DEFAULT_DIVISIONS;
 use crate::lang::error::{CompileError, CompileResult};
 use crate::sexpr::Sexpr;
@@ -17,6 +19,7 @@ use crate::sexpr::Sexpr;
 /// - Full names: `:quarter`, `:half`, `:whole`, `:eighth`
 /// - British names: `:crotchet`, `:minim`, `:semibreve`, `:quaver`
 /// - With dots: `:q.`, `:h..`, `:quarter.`
+/// - Fractions of a whole note: `1/8`, `3/16` (see [`parse_fraction_duration`])
 ///
 /// # Examples
 ///
@@ -39,6 +42,9 @@ pub fn parse_duration(s: &str) -> CompileResult<FermataDuration> {
 
     // Strip leading colon if present (keyword syntax)
     let s = s.strip_prefix(':').unwrap_or(s);
+    if is_fraction_duration(s) {
+        return parse_fraction_duration(s);
+    }
 
     // Count and strip trailing dots
     let dot_count = s.chars().rev().take_while(|&c| c == '.').count();
@@ -58,6 +64,87 @@ pub fn parse_duration(s: &str) -> CompileResult<FermataDuration> {
     })
 }
 
+/// Check if a string has the shape of a fractional duration (`N/M`).
+pub(crate) fn is_fraction_duration(s: &str) -> bool {
+    s.split_once('/').is_some_and(|(num, den)| {
+        [num, den]
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+/// Parse a duration written as a fraction of a whole note.
+///
+/// The fraction must be a note value with optional dots: `1/8` is an
+/// eighth, `3/8` a dotted quarter, and `7/16` a double-dotted quarter.
+/// Anything else, such as the `1/12` of a triplet eighth, is an error;
+/// tuplet rhythms are written with `(tuplet ...)`.
+///
+/// # Examples
+///
+/// ```
+/// use fermata::lang::ast::DurationBase;
+/// use fermata::lang::duration::parse_fraction_duration;
+///
+/// let dur = parse_fraction_duration("3/16").unwrap();
+/// assert_eq!(dur.base, DurationBase::Eighth);
+/// assert_eq!(dur.dots, 1);
+/// ```
+pub fn parse_fraction_duration(s: &str) -> CompileResult<FermataDuration> {
+    let invalid = |reason: &str| {
+        CompileError::InvalidDuration(format!("cannot use {} as a duration: {}", s, reason))
+    };
+    let (num, den) = s
+        .split_once('/')
+        .and_then(|(num, den)| Some((num.parse::<u64>().ok()?, den.parse::<u64>().ok()?)))
+        .ok_or_else(|| invalid("expected a fraction such as 1/8"))?;
+    if num == 0 || den == 0 {
+        return Err(invalid("fraction must be positive"));
+    }
+    let divisor = gcd(num, den);
+    let (num, den) = (num / divisor, den / divisor);
+    if !den.is_power_of_two() {
+        return Err(invalid(
+            "not a note value; use (tuplet ...) for tuplet rhythms",
+        ));
+    }
+
+    // n dots make the value (2^(n+1) - 1) / 2^n times the base, so the odd
+    // part of the numerator must be one less than a power of two
+    let odd = num >> num.trailing_zeros();
+    if !(odd + 1).is_power_of_two() {
+        return Err(invalid("not a note value with dots"));
+    }
+    let dots = (odd + 1).trailing_zeros() - 1;
+    // Base value as a fraction of a whole note: num / odd * 2^dots / den
+    let base_value = ((num / odd) << dots) as f64 / den as f64;
+
+    let base = [
+        DurationBase::Maxima,
+        DurationBase::Long,
+        DurationBase::Breve,
+        DurationBase::Whole,
+        DurationBase::Half,
+        DurationBase::Quarter,
+        DurationBase::Eighth,
+        DurationBase::Sixteenth,
+        DurationBase::ThirtySecond,
+        DurationBase::SixtyFourth,
+        DurationBase::OneTwentyEighth,
+        DurationBase::TwoFiftySixth,
+        DurationBase::FiveTwelfth,
+        DurationBase::OneThousandTwentyFourth,
+    ]
+    .into_iter()
+    .find(|base| base.to_fraction() == base_value)
+    .ok_or_else(|| invalid("outside the range of note values"))?;
+
+    Ok(FermataDuration {
+        base,
+        dots: dots as u8,
+    })
+}
+
 /// Parse a base duration string to a DurationBase.
 ///
 /// Supports:
@@ -119,6 +206,28 @@ pub fn compile_duration_type(base: &DurationBase) -> NoteType {
     NoteType { value, size: None }
 }
 
+impl From<NoteTypeValue> for DurationBase {
+    /// The duration base written with an IR note type.
+    fn from(value: NoteTypeValue) -> Self {
+        match value {
+            NoteTypeValue::Maxima => DurationBase::Maxima,
+            NoteTypeValue::Long => DurationBase::Long,
+            NoteTypeValue::Breve => DurationBase::Breve,
+            NoteTypeValue::Whole => DurationBase::Whole,
+            NoteTypeValue::Half => DurationBase::Half,
+            NoteTypeValue::Quarter => DurationBase::Quarter,
+            NoteTypeValue::Eighth => DurationBase::Eighth,
+            NoteTypeValue::N16th => DurationBase::Sixteenth,
+            NoteTypeValue::N32nd => DurationBase::ThirtySecond,
+            NoteTypeValue::N64th => DurationBase::SixtyFourth,
+            NoteTypeValue::N128th => DurationBase::OneTwentyEighth,
+            NoteTypeValue::N256th => DurationBase::TwoFiftySixth,
+            NoteTypeValue::N512th => DurationBase::FiveTwelfth,
+            NoteTypeValue::N1024th => DurationBase::OneThousandTwentyFourth,
+        }
+    }
+}
+
 /// Compile a dot count to a `Vec<Dot>`.
 pub fn compile_dots(count: u8) -> Vec<Dot> {
     (0..count).map(|_| Dot::default()).collect()
@@ -144,43 +253,12 @@ pub fn compile_duration_divisions_with(
     duration: &FermataDuration,
     divisions_per_quarter: PositiveDivisions,
 ) -> PositiveDivisions {
-    // Base duration relative to quarter note
-    let base_quarters = match duration.base {
-        DurationBase::Maxima => 32.0, // 8 whole notes = 32 quarters
-        DurationBase::Long => 16.0,   // 4 whole notes = 16 quarters
-        DurationBase::Breve => 8.0,   // 2 whole notes = 8 quarters
-        DurationBase::Whole => 4.0,   // 4 quarters
-        DurationBase::Half => 2.0,    // 2 quarters
-        DurationBase::Quarter => 1.0, // 1 quarter
-        DurationBase::Eighth => 0.5,
-        DurationBase::Sixteenth => 0.25,
-        DurationBase::ThirtySecond => 0.125,
-        DurationBase::SixtyFourth => 0.0625,
-        DurationBase::OneTwentyEighth => 0.03125,
-        DurationBase::TwoFiftySixth => 0.015625,
-        DurationBase::FiveTwelfth => 0.0078125,
-        DurationBase::OneThousandTwentyFourth => 0.00390625,
-    };
-
-    // Apply dot multiplier: each dot adds half of the previous value
-    // dot_multiplier = 1 + 1/2 + 1/4 + ... = 2 - (1/2)^dots
-    let dot_multiplier = if duration.dots == 0 {
-        1.0
-    } else {
-        let mut multiplier = 1.0;
-        let mut add = 0.5;
-        for _ in 0..duration.dots {
-            multiplier += add;
-            add *= 0.5;
-        }
-        multiplier
-    };
-
-    let total_quarters = base_quarters * dot_multiplier;
-    let divisions = total_quarters * divisions_per_quarter as f64;
-
-    // Round to nearest integer (should be exact for standard durations)
-    divisions.round() as PositiveDivisions
+    divisions_for(
+        compile_duration_type(&duration.base).value,
+        duration.dots as u32,
+        None,
+        divisions_per_quarter as u32,
+    )
 }
 
 /// Parse a duration from an S-expression.
@@ -316,6 +394,128 @@ fn parse_dots_sexpr(sexpr: &Sexpr) -> CompileResult<u8> {
     }
 }
 
+/// Choose a divisions-per-quarter value that expresses every duration exactly.
+///
+/// Each note, rest, and chord duration (after dots and tuplet ratios,
+/// including nested tuplets) and each time signature's measure length is
+/// measured in quarter notes; the result is the least common multiple of
+/// [`DEFAULT_DIVISIONS`] and all of their denominators. Ordinary rhythms,
+/// including triplets and sixteenths, keep the default of 960, while a
+/// septuplet raises it to 6720. If the multiple would overflow `u32`, the
+/// largest value reached so far is returned.
+pub fn compute_divisions(score: &FermataScore) -> u32 {
+    let mut divisions = DEFAULT_DIVISIONS as u64;
+    for measure in score.parts.iter().flat_map(|p| &p.measures) {
+        collect_divisions(&measure.content, (1, 1), &mut divisions);
+    }
+    divisions as u32
+}
+
+/// Choose a divisions-per-quarter value that expresses every duration in
+/// one measure exactly.
+///
+/// This is [`compute_divisions`] for a single measure, so a measure of
+/// septuplets needs 6720 even when the rest of the score is fine at 960.
+pub fn compute_measure_divisions(measure: &FermataMeasure) -> u32 {
+    let mut divisions = DEFAULT_DIVISIONS as u64;
+    collect_divisions(&measure.content, (1, 1), &mut divisions);
+    divisions as u32
+}
+
+/// Fold the denominators needed by `elements` into `divisions`.
+///
+/// `ratio` is the product of the enclosing tuplets' `normal/actual`.
+fn collect_divisions(elements: &[MeasureElement], ratio: (u64, u64), divisions: &mut u64) {
+    let scaled = |duration: &FermataDuration| {
+        let (num, den) = duration_quarters(duration);
+        (num * ratio.0, den * ratio.1)
+    };
+
+    for element in elements {
+        match element {
+            MeasureElement::Note(note) => require(divisions, scaled(&note.duration)),
+            MeasureElement::Rest(rest) => require(divisions, scaled(&rest.duration)),
+            MeasureElement::Unpitched(unpitched) => require(divisions, scaled(&unpitched.duration)),
+            MeasureElement::Chord(chord) => require(divisions, scaled(&chord.duration)),
+            MeasureElement::TieGroup(tie) => collect_divisions(&tie.notes, ratio, divisions),
+            MeasureElement::TremoloPair(pair) => {
+                let halved = (ratio.0, ratio.1 * 2);
+                for note in [&pair.first, &pair.second] {
+                    let (num, den) = duration_quarters(&note.duration);
+                    require(divisions, (num * halved.0, den * halved.1));
+                }
+            }
+            MeasureElement::Glissando(glissando) => {
+                for note in &glissando.notes {
+                    require(divisions, scaled(&note.duration));
+                }
+            }
+            MeasureElement::Tuplet(tuplet) if tuplet.actual > 0 => {
+                let inner = (
+                    ratio.0 * tuplet.normal as u64,
+                    ratio.1 * tuplet.actual as u64,
+                );
+                collect_divisions(&tuplet.notes, inner, divisions);
+            }
+            MeasureElement::SlurGroup(slur) => collect_divisions(&slur.notes, ratio, divisions),
+            MeasureElement::Ottava(ottava) => collect_divisions(&ottava.notes, ratio, divisions),
+            MeasureElement::Hairpin(hairpin) => collect_divisions(&hairpin.notes, ratio, divisions),
+            MeasureElement::Voice(voice) => collect_divisions(&voice.content, ratio, divisions),
+            MeasureElement::Time(spec) => {
+                let signatures = match spec {
+                    TimeSpec::Simple { beats, beat_type } => vec![(*beats, *beat_type)],
+                    TimeSpec::Compound { signatures } => signatures.clone(),
+                    _ => vec![],
+                };
+                for (beats, beat_type) in signatures.into_iter().filter(|&(_, t)| t > 0) {
+                    require(divisions, (beats as u64 * 4, beat_type as u64));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Raise `divisions` so that `quarters` (a fraction) is a whole number of them.
+fn require(divisions: &mut u64, quarters: (u64, u64)) {
+    let denominator = quarters.1 / gcd(quarters.0, quarters.1);
+    let next = lcm(*divisions, denominator);
+    if next <= u32::MAX as u64 {
+        *divisions = next;
+    }
+}
+
+/// Length of a duration in quarter notes, as an unreduced fraction.
+fn duration_quarters(duration: &FermataDuration) -> (u64, u64) {
+    let (num, den): (u64, u64) = match duration.base {
+        DurationBase::Maxima => (32, 1),
+        DurationBase::Long => (16, 1),
+        DurationBase::Breve => (8, 1),
+        DurationBase::Whole => (4, 1),
+        DurationBase::Half => (2, 1),
+        DurationBase::Quarter => (1, 1),
+        DurationBase::Eighth => (1, 2),
+        DurationBase::Sixteenth => (1, 4),
+        DurationBase::ThirtySecond => (1, 8),
+        DurationBase::SixtyFourth => (1, 16),
+        DurationBase::OneTwentyEighth => (1, 32),
+        DurationBase::TwoFiftySixth => (1, 64),
+        DurationBase::FiveTwelfth => (1, 128),
+        DurationBase::OneThousandTwentyFourth => (1, 256),
+    };
+    // n dots multiply the value by (2^(n+1) - 1) / 2^n
+    let dots = duration.dots.min(16) as u32;
+    (num * ((1 << (dots + 1)) - 1), den << dots)
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a.max(1) } else { gcd(b, a % b) }
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -445,6 +645,34 @@ mod tests {
         assert_eq!(dur.dots, 2);
     }
 
+    #[test]
+    fn test_parse_fraction_duration_note_values() {
+        let cases = [
+            ("1/8", DurationBase::Eighth, 0),
+            ("3/8", DurationBase::Quarter, 1),
+            ("2/8", DurationBase::Quarter, 0),
+            ("7/16", DurationBase::Quarter, 2),
+            ("3/1", DurationBase::Breve, 1),
+            ("1/1024", DurationBase::OneThousandTwentyFourth, 0),
+        ];
+        for (fraction, base, dots) in cases {
+            let dur = parse_duration(fraction).unwrap();
+            assert_eq!((dur.base, dur.dots), (base, dots), "{}", fraction);
+        }
+    }
+
+    #[test]
+    fn test_parse_fraction_duration_unrepresentable_is_error() {
+        for fraction in ["1/12", "5/16", "0/4", "1/2048", "16/1"] {
+            let err = parse_fraction_duration(fraction).unwrap_err();
+            assert!(
+                matches!(err, CompileError::InvalidDuration(_)),
+                "{}",
+                fraction
+            );
+        }
+    }
+
     #[test]
     fn test_parse_duration_triple_dotted() {
         let dur = parse_duration("w...").unwrap();
@@ -793,4 +1021,53 @@ mod tests {
         let divisions = compile_duration_divisions_with(&dur, 24);
         assert_eq!(divisions, 36);
     }
+
+    // === compute_divisions tests ===
+
+    fn score_ast(source: &str) -> FermataScore {
+        let sexpr = crate::sexpr::parse(source).unwrap();
+        crate::lang::score::parse_score_to_ast(&sexpr).unwrap()
+    }
+
+    #[test]
+    fn test_compute_divisions_triplets_and_sixteenths() {
+        let score = score_ast(
+            "(score (part :piano (measure
+               (tuplet 3 2 (note c4 :8) (note d4 :8) (note e4 :8))
+               (note f4 :16) (note g4 :16) (note a4 :8) (note b4 :h))))",
+        );
+        let divisions = compute_divisions(&score);
+        assert_eq!(divisions % 3, 0);
+        assert_eq!(divisions % 4, 0);
+        assert_eq!(divisions, DEFAULT_DIVISIONS);
+    }
+
+    #[test]
+    fn test_compute_divisions_septuplet_and_nested() {
+        let septuplet = score_ast(
+            "(score (part :piano (measure
+               (tuplet 7 4 (note c4 :16) (note c4 :16) (note c4 :16) (note c4 :16)
+                           (note c4 :16) (note c4 :16) (note c4 :16)))))",
+        );
+        assert_eq!(compute_divisions(&septuplet), DEFAULT_DIVISIONS * 7);
+
+        // A 9:8 inside a 7:4 needs 7 and another factor of 3
+        let nested = score_ast(
+            "(score (part :piano (measure
+               (tuplet 7 4 (tuplet 9 8 (note c4 :32) (note c4 :32) (note c4 :32)
+                                       (note c4 :32) (note c4 :32))))))",
+        );
+        assert_eq!(compute_divisions(&nested), DEFAULT_DIVISIONS * 21);
+    }
+
+    #[test]
+    fn test_compute_divisions_dots() {
+        let mut score = score_ast("(score (part :piano (measure (note c4 :256))))");
+        let MeasureElement::Note(note) = &mut score.parts[0].measures[0].content[0] else {
+            panic!("expected note");
+        };
+        note.duration.dots = 2;
+        // A double-dotted 256th is 7/256 of a quarter
+        assert_eq!(compute_divisions(&score), 3840);
+    }
 }
@@ -5,7 +5,7 @@ use thiserror::Error;
 use crate::sexpr::error::{ConvertError, ParseError};
 
 /// Source location for error reporting
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct SourceSpan {
     /// Starting byte offset in the source
     pub start: usize,
@@ -47,6 +47,42 @@ impl SourceSpan {
         self.column = column;
         self
     }
+
+    /// Render the spanned source line with a caret under the span, in the
+    /// style of rustc diagnostics:
+    ///
+    /// ```text
+    ///   |
+    /// 3 |     (measure (note c4 :q]))
+    ///   |                         ^
+    /// ```
+    ///
+    /// Expects a span whose line and column were filled in by
+    /// [`with_source`](Self::with_source) from the same source. Returns
+    /// `None` if the line is not in `source`.
+    pub fn snippet(&self, source: &str) -> Option<String> {
+        let text = source.lines().nth(self.line.checked_sub(1)?)?;
+        // Keep tabs so the caret lines up with the source as displayed
+        let indent: String = text
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let width = source
+            .get(self.start..self.end.max(self.start))
+            .map_or(0, |spanned| {
+                spanned.chars().take_while(|&c| c != '\n').count()
+            })
+            .max(1);
+        let gutter = " ".repeat(self.line.to_string().len());
+        Some(format!(
+            "{gutter} |\n{} | {}\n{gutter} | {}{}",
+            self.line,
+            text,
+            indent,
+            "^".repeat(width)
+        ))
+    }
 }
 
 /// Errors that can occur during Fermata compilation
@@ -102,6 +138,10 @@ pub enum CompileError {
     #[error("Invalid dynamic: {0}")]
     InvalidDynamic(String),
 
+    /// Invalid `define` form or use of a definition
+    #[error("Invalid definition: {0}")]
+    InvalidDefinition(String),
+
     /// Missing required field
     #[error("Missing required field: {0}")]
     MissingField(&'static str),
@@ -119,6 +159,10 @@ pub enum CompileError {
         found: String,
     },
 
+    /// Semantically invalid music (e.g. a tie between different pitches)
+    #[error("Semantic error: {0}")]
+    Semantic(String),
+
     /// Error during IR conversion
     #[error("IR conversion error: {0}")]
     IrConvert(#[from] ConvertError),
@@ -150,6 +194,21 @@ impl CompileError {
         }
     }
 
+    /// Format this error for display alongside the source it came from.
+    ///
+    /// Errors carrying a [`SourceSpan`] are followed by a snippet of the
+    /// offending line with a caret under the column (see
+    /// [`SourceSpan::snippet`]); other errors render as their message alone.
+    pub fn render(&self, source: &str) -> String {
+        match self {
+            CompileError::WithSpan { span, .. } => match span.snippet(source) {
+                Some(snippet) => format!("{}\n{}", self, snippet),
+                None => self.to_string(),
+            },
+            _ => self.to_string(),
+        }
+    }
+
     /// Create a type mismatch error
     pub fn type_mismatch(expected: &'static str, found: impl AsRef<str>) -> Self {
         CompileError::TypeMismatch {
@@ -171,6 +230,30 @@ pub type CompileResult<T> = Result<T, CompileError>;
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_compile_error_render_caret_under_column() {
+        let source = "(score\n  (part :piano\n    (measure (note c4 :q]))))\n";
+        let (_, errors) = crate::lang::parse_recover(source);
+        let CompileError::WithSpan { span, .. } = &errors[0] else {
+            panic!("expected span, got {:?}", errors[0]);
+        };
+
+        let rendered = errors[0].render(source);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], errors[0].to_string());
+        assert_eq!(lines[2], "3 |     (measure (note c4 :q]))))");
+        // The caret sits under the reported column, after the "3 | " gutter
+        let caret = lines[3].find('^').unwrap() - "  | ".len() + 1;
+        assert_eq!(caret, span.column);
+        assert_eq!(span.column, 25);
+    }
+
+    #[test]
+    fn test_compile_error_render_without_span_is_message() {
+        let err = CompileError::InvalidPitch("xyz".to_string());
+        assert_eq!(err.render("(note xyz :q)"), err.to_string());
+    }
+
     #[test]
     fn test_compile_error_invalid_pitch_display() {
         let err = CompileError::InvalidPitch("xyz".to_string());
@@ -212,6 +295,15 @@ mod tests {
         assert!(err.to_string().contains("invalid ratio"));
     }
 
+    #[test]
+    fn test_compile_error_semantic_display() {
+        let err = CompileError::Semantic("tie pitches differ".to_string());
+        assert!(
+            err.to_string()
+                .contains("Semantic error: tie pitches differ")
+        );
+    }
+
     #[test]
     fn test_compile_error_invalid_key_display() {
         let err = CompileError::InvalidKey("X major".to_string());
@@ -5,7 +5,7 @@
 
 use crate::ir::common::{Position, YesNo};
 use crate::ir::note::{FullNote, Grace, Note, NoteContent, PitchRestUnpitched};
-use crate::lang::ast::{FermataDuration, FermataGraceNote};
+use crate::lang::ast::{FermataDuration, FermataGraceNote, FermataNote};
 use crate::lang::duration::{compile_dots, compile_duration_type};
 use crate::lang::error::{CompileError, CompileResult};
 use crate::lang::pitch::{compile_pitch, parse_pitch_str};
@@ -51,9 +51,11 @@ pub fn compile_grace_note(sexpr: &Sexpr) -> CompileResult<Note> {
 
 /// Parse grace note arguments from S-expression items into a FermataGraceNote AST.
 ///
-/// Expected format: `pitch [keywords...]`
+/// Expected format: `pitch [keywords...]` or `[:slash] (note ...)`
 /// - pitch: "c4", "f#5", etc.
 /// - keywords: :slash, :duration :8, etc.
+/// - a wrapped `(note pitch duration)` supplies the pitch and displayed type;
+///   any other keyword on the wrapped note is an error
 pub fn parse_grace_form(items: &[Sexpr]) -> CompileResult<FermataGraceNote> {
     if items.is_empty() {
         return Err(CompileError::InvalidNote(
@@ -61,6 +63,10 @@ pub fn parse_grace_form(items: &[Sexpr]) -> CompileResult<FermataGraceNote> {
         ));
     }
 
+    if let Some(note_items) = items.iter().find_map(wrapped_note_items) {
+        return parse_wrapped_grace(items, note_items);
+    }
+
     // First item is pitch
     let pitch = parse_pitch_str(items[0].as_symbol().ok_or_else(|| {
         CompileError::InvalidNote(format!("expected pitch symbol, got {:?}", items[0]))
@@ -127,6 +133,57 @@ pub fn parse_grace_form(items: &[Sexpr]) -> CompileResult<FermataGraceNote> {
     })
 }
 
+/// The items of a `(note ...)` list, if `item` is one.
+fn wrapped_note_items(item: &Sexpr) -> Option<&[Sexpr]> {
+    match item {
+        Sexpr::List(list) if list.first().is_some_and(|h| h.is_symbol("note")) => Some(list),
+        _ => None,
+    }
+}
+
+/// Parse the `[:slash] (note ...)` grace form.
+fn parse_wrapped_grace(items: &[Sexpr], note_items: &[Sexpr]) -> CompileResult<FermataGraceNote> {
+    if items.iter().filter_map(wrapped_note_items).count() > 1 {
+        return Err(CompileError::InvalidNote(
+            "grace takes a single wrapped note".to_string(),
+        ));
+    }
+
+    let mut slash = false;
+    for item in items {
+        match item.as_keyword() {
+            Some("slash") => slash = true,
+            Some(other) => {
+                return Err(CompileError::InvalidNote(format!(
+                    "unexpected :{} in grace with a wrapped note",
+                    other
+                )));
+            }
+            None if wrapped_note_items(item).is_some() => {}
+            None => {
+                return Err(CompileError::InvalidNote(format!(
+                    "grace takes a single wrapped note, got {:?}",
+                    item
+                )));
+            }
+        }
+    }
+
+    let note = crate::lang::note::parse_note_form(&note_items[1..])?;
+    let plain = FermataNote::new(note.pitch.clone(), note.duration.clone());
+    if note != plain {
+        return Err(CompileError::InvalidNote(format!(
+            "grace with a wrapped note takes only a pitch and duration, got {}",
+            crate::sexpr::print_sexpr(&Sexpr::List(note_items.to_vec()))
+        )));
+    }
+    Ok(FermataGraceNote {
+        pitch: note.pitch,
+        slash,
+        duration: Some(note.duration),
+    })
+}
+
 /// Check if a string looks like a duration keyword.
 fn is_duration_keyword(s: &str) -> bool {
     let s = s.trim_start_matches(':');
@@ -319,6 +376,70 @@ mod tests {
 
     // === compile_grace_note tests ===
 
+    #[test]
+    fn test_parse_grace_form_wrapped_note() {
+        let sexpr = crate::sexpr::parse("(grace (note d5 :8))").unwrap();
+        let grace = parse_grace_form(&sexpr.as_list().unwrap()[1..]).unwrap();
+        assert_eq!(grace.pitch.step, PitchStep::D);
+        assert_eq!(grace.pitch.octave, 5);
+        assert!(!grace.slash);
+        assert_eq!(grace.duration.unwrap().base, DurationBase::Eighth);
+    }
+
+    #[test]
+    fn test_parse_grace_form_wrapped_note_with_slash() {
+        let sexpr = crate::sexpr::parse("(grace :slash (note d5 :8))").unwrap();
+        let grace = parse_grace_form(&sexpr.as_list().unwrap()[1..]).unwrap();
+        assert!(grace.slash);
+        assert_eq!(grace.pitch.step, PitchStep::D);
+    }
+
+    #[test]
+    fn test_parse_grace_form_wrapped_note_rejects_extra_items() {
+        let sexpr = crate::sexpr::parse("(grace (note d5 :8) (note e5 :8))").unwrap();
+        assert!(parse_grace_form(&sexpr.as_list().unwrap()[1..]).is_err());
+        let sexpr = crate::sexpr::parse("(grace :8 (note d5 :8))").unwrap();
+        assert!(parse_grace_form(&sexpr.as_list().unwrap()[1..]).is_err());
+    }
+
+    #[test]
+    fn test_parse_grace_form_wrapped_note_rejects_note_keywords() {
+        for source in [
+            "(grace (note d5 :8 :staccato))",
+            "(grace (note d5 :8 :harmonic))",
+            "(grace :slash (note d5 :8 :voice 2))",
+        ] {
+            let sexpr = crate::sexpr::parse(source).unwrap();
+            let error = parse_grace_form(&sexpr.as_list().unwrap()[1..]).unwrap_err();
+            assert!(
+                error.to_string().contains("only a pitch and duration"),
+                "{}: {}",
+                source,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_slashed_grace_before_quarter_emits_without_duration() {
+        let score = crate::lang::compile(
+            "(score (part :piano (measure (grace :slash (note d5 :8)) (note c5 :q))))",
+        )
+        .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        let grace_start = xml.find("<note>").unwrap();
+        let grace_end = xml[grace_start..].find("</note>").unwrap() + grace_start;
+        let grace_xml = &xml[grace_start..grace_end];
+        assert!(grace_xml.contains(r#"<grace slash="yes"/>"#));
+        assert!(grace_xml.contains("<type>eighth</type>"));
+        assert!(!grace_xml.contains("<duration>"));
+
+        let main_xml = &xml[grace_end..];
+        assert!(main_xml.contains("<duration>"));
+        assert!(main_xml.contains("<type>quarter</type>"));
+    }
+
     #[test]
     fn test_compile_grace_note_simple() {
         let sexpr = Sexpr::list(vec![Sexpr::symbol("grace"), Sexpr::symbol("c4")]);
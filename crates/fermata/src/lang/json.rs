@@ -0,0 +1,215 @@
+//! A compact JSON score format.
+//!
+//! Tools that generate music as data can describe a score in JSON instead
+//! of S-expressions. The format covers parts, measures, time signatures,
+//! notes, and rests:
+//!
+//! ```json
+//! {
+//!   "title": "Scale",
+//!   "composer": "Anonymous",
+//!   "parts": [
+//!     {
+//!       "name": "Piano",
+//!       "measures": [
+//!         {
+//!           "time": [4, 4],
+//!           "notes": [
+//!             { "pitch": "c4", "duration": "q" },
+//!             { "pitch": "bb4", "duration": "q." },
+//!             { "duration": "8" },
+//!             { "pitch": "f#5", "duration": "h" }
+//!           ]
+//!         }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! `title`, `composer`, and `time` are optional. Pitches and durations use
+//! the same spellings as the S-expression syntax (`c4`, `bb3`, `q`, `h.`,
+//! `3/8`); a note without a pitch is a rest. Unknown fields are rejected so
+//! that typos do not pass silently.
+
+use serde::Deserialize;
+
+use crate::ir::score::ScorePartwise;
+use crate::lang::ast::{
+    FermataMeasure, FermataNote, FermataPart, FermataRest, FermataScore, MeasureElement, TimeSpec,
+};
+use crate::lang::duration::parse_duration;
+use crate::lang::error::{CompileError, CompileResult};
+use crate::lang::pitch::parse_pitch_str;
+
+/// The top level of a JSON score.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonScore {
+    title: Option<String>,
+    composer: Option<String>,
+    parts: Vec<JsonPart>,
+}
+
+/// A part: a name and its measures.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonPart {
+    name: String,
+    measures: Vec<JsonMeasure>,
+}
+
+/// A measure: an optional `[beats, beat-type]` time signature and notes.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonMeasure {
+    time: Option<(u8, u8)>,
+    notes: Vec<JsonNote>,
+}
+
+/// A note, or a rest when `pitch` is absent.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonNote {
+    pitch: Option<String>,
+    duration: String,
+}
+
+/// Parse a JSON score into a Fermata AST.
+///
+/// Malformed JSON, or JSON that does not follow the schema, is a
+/// [`CompileError::Semantic`]; bad pitches and durations report the same
+/// errors as in S-expression source.
+pub fn parse_json_score(json: &str) -> CompileResult<FermataScore> {
+    let score: JsonScore = serde_json::from_str(json)
+        .map_err(|e| CompileError::Semantic(format!("invalid JSON score: {}", e)))?;
+
+    Ok(FermataScore {
+        title: score.title,
+        composer: score.composer,
+        tempo: None,
+        parts: score
+            .parts
+            .into_iter()
+            .map(lift_part)
+            .collect::<CompileResult<_>>()?,
+    })
+}
+
+/// Compile a JSON score to a MusicXML document.
+///
+/// # Examples
+///
+/// ```
+/// use fermata::lang::compile_json_to_musicxml;
+///
+/// let json = r#"{"parts": [{"name": "Flute",
+///     "measures": [{"notes": [{"pitch": "g4", "duration": "w"}]}]}]}"#;
+/// let xml = compile_json_to_musicxml(json).unwrap();
+/// assert!(xml.contains("<step>G</step>"));
+/// ```
+pub fn compile_json_to_musicxml(json: &str) -> CompileResult<String> {
+    let score = ScorePartwise::try_from(parse_json_score(json)?)?;
+    crate::musicxml::emit(&score).map_err(|e| CompileError::emit(e.to_string()))
+}
+
+fn lift_part(part: JsonPart) -> CompileResult<FermataPart> {
+    Ok(FermataPart {
+        name: part.name,
+        id: None,
+        abbreviation: None,
+        transpose: None,
+        measures: part
+            .measures
+            .into_iter()
+            .enumerate()
+            .map(|(index, measure)| lift_measure(measure, index as u32 + 1))
+            .collect::<CompileResult<_>>()?,
+    })
+}
+
+fn lift_measure(measure: JsonMeasure, number: u32) -> CompileResult<FermataMeasure> {
+    let mut content = Vec::with_capacity(measure.notes.len() + 1);
+    if let Some((beats, beat_type)) = measure.time {
+        content.push(MeasureElement::Time(TimeSpec::Simple { beats, beat_type }));
+    }
+    for note in measure.notes {
+        let duration = parse_duration(&note.duration)?;
+        content.push(match note.pitch {
+            Some(pitch) => {
+                MeasureElement::Note(FermataNote::new(parse_pitch_str(&pitch)?, duration))
+            }
+            None => MeasureElement::Rest(FermataRest {
+                duration,
+                voice: None,
+                staff: None,
+                measure_rest: false,
+                invisible: false,
+            }),
+        });
+    }
+
+    Ok(FermataMeasure {
+        number: Some(number),
+        content,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_json_to_musicxml_minimal_score() {
+        let json = r#"{
+            "title": "Minimal",
+            "parts": [{
+                "name": "Piano",
+                "measures": [{
+                    "time": [2, 4],
+                    "notes": [
+                        {"pitch": "eb5", "duration": "q"},
+                        {"duration": "q"}
+                    ]
+                }]
+            }]
+        }"#;
+
+        let xml = compile_json_to_musicxml(json).unwrap();
+
+        assert!(xml.contains("<work-title>Minimal</work-title>"), "{}", xml);
+        assert!(xml.contains("<part-name>Piano</part-name>"), "{}", xml);
+        assert!(xml.contains("<beats>2</beats>"), "{}", xml);
+        assert!(xml.contains("<step>E</step>"), "{}", xml);
+        assert!(xml.contains("<alter>-1</alter>"), "{}", xml);
+        assert!(xml.contains("<octave>5</octave>"), "{}", xml);
+        assert!(xml.contains("<rest/>"), "{}", xml);
+        assert_eq!(xml.matches("<type>quarter</type>").count(), 2);
+    }
+
+    #[test]
+    fn test_parse_json_score_malformed_is_semantic_error() {
+        for json in [
+            "{\"parts\": [",
+            "{\"parts\": [{\"name\": \"Piano\"}]}",
+            "{\"parts\": [], \"tempo\": 120}",
+        ] {
+            assert!(
+                matches!(parse_json_score(json), Err(CompileError::Semantic(_))),
+                "{}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_json_score_bad_pitch_reports_pitch_error() {
+        let json = r#"{"parts": [{"name": "Piano",
+            "measures": [{"notes": [{"pitch": "h4", "duration": "q"}]}]}]}"#;
+        assert!(matches!(
+            parse_json_score(json),
+            Err(CompileError::InvalidPitch(_))
+        ));
+    }
+}
@@ -0,0 +1,195 @@
+//! Lyric compilation for Fermata syntax.
+//!
+//! A lyric is attached to a note as a list: `(:lyric "Hel-")`. Hyphens at
+//! the edges of the text mark where a word is split across notes, so a
+//! two-syllable word is written `(:lyric "Hel-")` on the first note and
+//! `(:lyric "-lo")` on the second. A verse number may precede the text,
+//! and a trailing `:extend` draws a melisma line.
+
+use crate::ir::common::Font;
+use crate::ir::lyric::{Extend, Lyric, LyricContent, Syllabic as IrSyllabic, TextElementData};
+use crate::lang::ast::{LyricSpec, Syllabic};
+use crate::lang::error::{CompileError, CompileResult};
+use crate::sexpr::Sexpr;
+
+/// Parse the arguments of a `(:lyric [verse] "text" [:extend])` list.
+///
+/// The syllabic type comes from hyphens on the text: a trailing hyphen
+/// begins a word, a leading one ends it, and both mark a middle syllable.
+/// The hyphens are not part of the stored text.
+pub fn parse_lyric_list(items: &[Sexpr]) -> CompileResult<LyricSpec> {
+    let mut rest = items;
+
+    let verse = match rest.first().and_then(|s| s.as_integer()) {
+        Some(n) => {
+            rest = &rest[1..];
+            let verse = u8::try_from(n).ok().filter(|&v| v >= 1).ok_or_else(|| {
+                CompileError::InvalidNote(format!("lyric verse must be at least 1, got {}", n))
+            })?;
+            Some(verse)
+        }
+        None => None,
+    };
+
+    let raw = rest.first().and_then(|s| s.as_string()).ok_or_else(|| {
+        CompileError::InvalidNote("lyric requires text in quotes, e.g. (:lyric \"la\")".to_string())
+    })?;
+    rest = &rest[1..];
+
+    let (text, syllabic) = split_syllabic(raw);
+    if text.is_empty() {
+        return Err(CompileError::InvalidNote(format!(
+            "lyric {:?} has no text besides hyphens",
+            raw
+        )));
+    }
+
+    let mut extend = false;
+    for item in rest {
+        match item.as_keyword() {
+            Some("extend") => extend = true,
+            _ => {
+                return Err(CompileError::InvalidNote(format!(
+                    "expected :extend after lyric text, got {:?}",
+                    item
+                )));
+            }
+        }
+    }
+
+    Ok(LyricSpec {
+        text: text.to_string(),
+        syllabic,
+        verse,
+        extend,
+    })
+}
+
+/// Strip the word-splitting hyphens from `raw` and infer its syllabic type.
+fn split_syllabic(raw: &str) -> (&str, Syllabic) {
+    let continues = raw.ends_with('-');
+    let continued = raw.starts_with('-');
+    let text = raw.trim_start_matches('-').trim_end_matches('-');
+    let syllabic = match (continued, continues) {
+        (false, false) => Syllabic::Single,
+        (false, true) => Syllabic::Begin,
+        (true, true) => Syllabic::Middle,
+        (true, false) => Syllabic::End,
+    };
+    (text, syllabic)
+}
+
+/// Compile a lyric to its IR form.
+pub fn compile_lyric(lyric: &LyricSpec) -> Lyric {
+    let syllabic = match lyric.syllabic {
+        Syllabic::Single => IrSyllabic::Single,
+        Syllabic::Begin => IrSyllabic::Begin,
+        Syllabic::Middle => IrSyllabic::Middle,
+        Syllabic::End => IrSyllabic::End,
+    };
+
+    Lyric {
+        number: lyric.verse.map(|v| v.to_string()),
+        name: None,
+        justify: None,
+        placement: None,
+        print_object: None,
+        content: LyricContent::Syllable {
+            syllabic: Some(syllabic),
+            text: TextElementData {
+                value: lyric.text.clone(),
+                font: Font::default(),
+                color: None,
+                lang: None,
+            },
+            extensions: vec![],
+            extend: lyric.extend.then(|| Extend {
+                r#type: None,
+                position: Default::default(),
+                color: None,
+            }),
+        },
+        end_line: false,
+        end_paragraph: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sexpr::parse;
+
+    fn lyric(source: &str) -> CompileResult<LyricSpec> {
+        let sexpr = parse(source).unwrap();
+        parse_lyric_list(&sexpr.as_list().unwrap()[1..])
+    }
+
+    #[test]
+    fn test_parse_lyric_list_infers_syllabic_from_hyphens() {
+        let cases = [
+            ("(:lyric \"la\")", "la", Syllabic::Single),
+            ("(:lyric \"Hel-\")", "Hel", Syllabic::Begin),
+            ("(:lyric \"-le-\")", "le", Syllabic::Middle),
+            ("(:lyric \"-lo\")", "lo", Syllabic::End),
+        ];
+        for (source, text, syllabic) in cases {
+            let spec = lyric(source).unwrap();
+            assert_eq!(spec.text, text, "{}", source);
+            assert_eq!(spec.syllabic, syllabic, "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_parse_lyric_list_verse_and_extend() {
+        let spec = lyric("(:lyric 2 \"verse two\" :extend)").unwrap();
+        assert_eq!(spec.verse, Some(2));
+        assert_eq!(spec.text, "verse two");
+        assert!(spec.extend);
+    }
+
+    #[test]
+    fn test_parse_lyric_list_invalid_is_error() {
+        for source in [
+            "(:lyric)",
+            "(:lyric 0 \"la\")",
+            "(:lyric \"-\")",
+            "(:lyric \"la\" :bogus)",
+        ] {
+            assert!(
+                matches!(lyric(source), Err(CompileError::InvalidNote(_))),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn test_compile_hello_across_two_notes() {
+        let score = crate::lang::compile(
+            r#"(score (part :voice (measure (note c4 :h (:lyric "Hel-")) (note d4 :h (:lyric "-lo")))))"#,
+        )
+        .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        let begin = xml
+            .find("<syllabic>begin</syllabic>")
+            .expect("begin syllable");
+        let end = xml.find("<syllabic>end</syllabic>").expect("end syllable");
+        assert!(begin < end, "{}", xml);
+        assert!(xml[begin..end].contains("<text>Hel</text>"), "{}", xml);
+        assert!(xml[end..].contains("<text>lo</text>"), "{}", xml);
+    }
+
+    #[test]
+    fn test_compile_lyric_verses_and_melisma() {
+        let score = crate::lang::compile(
+            r#"(score (part :voice (measure (note c4 :w (:lyric 1 "one") (:lyric 2 "word" :extend)))))"#,
+        )
+        .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        assert!(xml.contains(r#"<lyric number="1">"#), "{}", xml);
+        assert!(xml.contains(r#"<lyric number="2">"#), "{}", xml);
+        assert_eq!(xml.matches("<extend/>").count(), 1, "{}", xml);
+    }
+}
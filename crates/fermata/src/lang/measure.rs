@@ -4,20 +4,32 @@
 //! It dispatches each child element to the appropriate sub-compiler and
 //! gathers attributes into a single Attributes block emitted first.
 
-use crate::ir::attributes::{Attributes, BarStyle, Barline, Clef, Key, Repeat, Time};
-use crate::ir::common::{Editorial, RightLeftMiddle};
+use crate::ir::attributes::{Attributes, BarStyle, Barline, Clef, Ending, Key, Repeat, Time};
+use crate::ir::common::{Editorial, RightLeftMiddle, StartStopDiscontinue, YesNo};
 use crate::ir::measure::{Measure, MusicDataElement};
-use crate::ir::voice::{Backup, Forward};
+use crate::ir::voice::{Backup, Forward, MeasureCursor};
 use crate::lang::ast::{
-    BarlineSpec, EndingAction, FermataDirection, FermataMeasure, MeasureElement,
+    BarlineSpec, EndingAction, FermataDirection, FermataMeasure, FermataVoice, GlissandoKind,
+    HairpinKind, MeasureElement,
 };
 use crate::lang::attributes::{compile_clef_spec, compile_key_spec, compile_time_spec};
 use crate::lang::chord::compile_fermata_chord;
+use crate::lang::connectors::{
+    compile_fermata_glissando, compile_fermata_slur, compile_fermata_tie, parse_glissando_form,
+    parse_slur_form, parse_slur_group_form, parse_tie_group_form,
+};
 use crate::lang::defaults::DEFAULT_DIVISIONS;
-use crate::lang::direction::{compile_dynamic_mark, compile_fermata_direction, compile_tempo_mark};
+use crate::lang::direction::{
+    compile_dynamic_mark, compile_fermata_direction, compile_fermata_hairpin,
+    compile_fermata_ottava, compile_tempo_mark, parse_hairpin_form, parse_ottava_form,
+};
 use crate::lang::error::{CompileError, CompileResult};
 use crate::lang::grace::compile_fermata_grace;
-use crate::lang::note::{compile_fermata_note, compile_fermata_rest};
+use crate::lang::note::{compile_fermata_note, compile_fermata_rest, compile_touching_notes};
+use crate::lang::percussion::{compile_fermata_unpitched, parse_hit_form, parse_unpitched_form};
+use crate::lang::tremolo::{
+    compile_fermata_tremolo_pair, parse_tremolo_form, parse_tremolo_pair_form,
+};
 use crate::lang::tuplet::compile_fermata_tuplet;
 use crate::sexpr::Sexpr;
 
@@ -46,6 +58,7 @@ pub fn compile_measure(sexpr: &Sexpr, number: u32) -> CompileResult<Measure> {
 ///
 /// Expected format: `(measure [content...])`
 /// where content can be notes, rests, chords, tuplets, attributes, directions, etc.
+/// A `(pickup [content...])` form is parsed the same way and marked implicit.
 pub fn parse_measure_from_sexpr(sexpr: &Sexpr, number: u32) -> CompileResult<FermataMeasure> {
     let items = sexpr.as_list().ok_or_else(|| {
         CompileError::UnknownForm(format!("expected measure list, got {:?}", sexpr))
@@ -55,20 +68,23 @@ pub fn parse_measure_from_sexpr(sexpr: &Sexpr, number: u32) -> CompileResult<Fer
         return Err(CompileError::UnknownForm("empty measure list".to_string()));
     }
 
-    // Check for 'measure' head
-    if let Some(head) = items[0].as_symbol() {
-        if head != "measure" {
+    // Check for 'measure' or 'pickup' head
+    let implicit = match items[0].as_symbol() {
+        Some("measure") => false,
+        Some("pickup") => true,
+        Some(head) => {
             return Err(CompileError::UnknownForm(format!(
                 "expected 'measure', got '{}'",
                 head
             )));
         }
-    } else {
-        return Err(CompileError::UnknownForm(format!(
-            "expected 'measure' symbol, got {:?}",
-            items[0]
-        )));
-    }
+        None => {
+            return Err(CompileError::UnknownForm(format!(
+                "expected 'measure' symbol, got {:?}",
+                items[0]
+            )));
+        }
+    };
 
     // Parse measure content
     let mut content = Vec::new();
@@ -81,6 +97,7 @@ pub fn parse_measure_from_sexpr(sexpr: &Sexpr, number: u32) -> CompileResult<Fer
 
     Ok(FermataMeasure {
         number: Some(number),
+        implicit,
         content,
     })
 }
@@ -108,6 +125,13 @@ fn parse_measure_element(sexpr: &Sexpr) -> CompileResult<Option<MeasureElement>>
             let fermata_rest = crate::lang::note::parse_rest_form(&items[1..])?;
             MeasureElement::Rest(fermata_rest)
         }
+        "unpitched" => MeasureElement::Unpitched(parse_unpitched_form(&items[1..])?),
+        "hit" => MeasureElement::Unpitched(parse_hit_form(&items[1..])?),
+        "mrest" => {
+            let mut fermata_rest = crate::lang::note::parse_rest_form(&items[1..])?;
+            fermata_rest.measure_rest = true;
+            MeasureElement::Rest(fermata_rest)
+        }
         "chord" => {
             let fermata_chord = crate::lang::chord::parse_chord_form(&items[1..])?;
             MeasureElement::Chord(fermata_chord)
@@ -116,6 +140,32 @@ fn parse_measure_element(sexpr: &Sexpr) -> CompileResult<Option<MeasureElement>>
             let fermata_tuplet = crate::lang::tuplet::parse_tuplet_form(&items[1..])?;
             MeasureElement::Tuplet(fermata_tuplet)
         }
+        "slur" => {
+            // `(slur start 2)` is a bare marker; `(slur (note ...) ...)` spans notes
+            if items.get(1).is_some_and(|item| item.as_list().is_some()) {
+                MeasureElement::SlurGroup(parse_slur_group_form(&items[1..])?)
+            } else {
+                MeasureElement::Slur(parse_slur_form(&items[1..])?)
+            }
+        }
+        "tie" => MeasureElement::TieGroup(parse_tie_group_form(&items[1..])?),
+        "gliss" | "glissando" => {
+            MeasureElement::Glissando(parse_glissando_form(GlissandoKind::Glissando, &items[1..])?)
+        }
+        "slide" => {
+            MeasureElement::Glissando(parse_glissando_form(GlissandoKind::Slide, &items[1..])?)
+        }
+        "ottava" => MeasureElement::Ottava(parse_ottava_form(&items[1..])?),
+        "tremolo" => MeasureElement::Note(parse_tremolo_form(&items[1..])?),
+        "tremolo-pair" => MeasureElement::TremoloPair(parse_tremolo_pair_form(&items[1..])?),
+        // A hairpin wrapping notes; the bare form is a start marker below
+        "cresc" | "crescendo" if items.len() > 1 => {
+            MeasureElement::Hairpin(parse_hairpin_form(HairpinKind::Crescendo, &items[1..])?)
+        }
+        "dim" | "diminuendo" | "decresc" | "decrescendo" if items.len() > 1 => {
+            MeasureElement::Hairpin(parse_hairpin_form(HairpinKind::Diminuendo, &items[1..])?)
+        }
+        "voice" => MeasureElement::Voice(parse_voice_form(&items[1..])?),
         "grace" => {
             let fermata_grace = crate::lang::grace::parse_grace_form(&items[1..])?;
             MeasureElement::GraceNote(fermata_grace)
@@ -129,15 +179,7 @@ fn parse_measure_element(sexpr: &Sexpr) -> CompileResult<Option<MeasureElement>>
             MeasureElement::Time(time_spec)
         }
         "clef" => {
-            if items.len() < 2 {
-                return Err(CompileError::InvalidClef(
-                    "clef requires a type".to_string(),
-                ));
-            }
-            let clef_name = items[1].as_keyword().ok_or_else(|| {
-                CompileError::InvalidClef("expected clef type keyword".to_string())
-            })?;
-            let clef_spec = crate::lang::attributes::parse_clef_name(clef_name)?;
+            let clef_spec = crate::lang::attributes::parse_clef_args(&items[1..])?;
             MeasureElement::Clef(clef_spec)
         }
         "barline" => {
@@ -170,7 +212,7 @@ fn parse_measure_element(sexpr: &Sexpr) -> CompileResult<Option<MeasureElement>>
             MeasureElement::Dynamic(dynamic)
         }
         // Direction elements
-        "rehearsal" | "words" | "segno" | "coda" | "pedal" => {
+        "rehearsal" | "words" | "text" | "segno" | "coda" | "direction" | "pedal" => {
             let direction = parse_direction_form(head, &items[1..])?;
             MeasureElement::Direction(direction)
         }
@@ -183,6 +225,42 @@ fn parse_measure_element(sexpr: &Sexpr) -> CompileResult<Option<MeasureElement>>
     Ok(Some(element))
 }
 
+/// Parse a voice form: `(voice N elements...)`.
+///
+/// Attributes and nested voices are not allowed inside a voice.
+fn parse_voice_form(args: &[Sexpr]) -> CompileResult<FermataVoice> {
+    let number = args
+        .first()
+        .ok_or(CompileError::MissingField("voice number"))
+        .and_then(crate::lang::note::parse_u32)?;
+    if number == 0 {
+        return Err(CompileError::Semantic(
+            "voice number must be at least 1".to_string(),
+        ));
+    }
+
+    let mut content = Vec::new();
+    for item in &args[1..] {
+        match parse_measure_element(item)? {
+            Some(
+                MeasureElement::Key(_)
+                | MeasureElement::Time(_)
+                | MeasureElement::Clef(_)
+                | MeasureElement::Voice(_),
+            ) => {
+                return Err(CompileError::UnknownForm(format!(
+                    "not allowed inside a voice: {:?}",
+                    item
+                )));
+            }
+            Some(element) => content.push(element),
+            None => {}
+        }
+    }
+
+    Ok(FermataVoice { number, content })
+}
+
 /// Parse a barline specification from S-expression arguments.
 fn parse_barline_form(args: &[Sexpr]) -> CompileResult<BarlineSpec> {
     if args.is_empty() {
@@ -201,7 +279,24 @@ fn parse_barline_form(args: &[Sexpr]) -> CompileResult<BarlineSpec> {
         "double" => Ok(BarlineSpec::Double),
         "final" | "end" => Ok(BarlineSpec::Final),
         "repeat-forward" | "repeat-start" | "start-repeat" => Ok(BarlineSpec::RepeatForward),
-        "repeat-backward" | "repeat-end" | "end-repeat" => Ok(BarlineSpec::RepeatBackward),
+        "repeat-backward" | "repeat-end" | "end-repeat" => {
+            let times = match args.get(1).and_then(|a| a.as_keyword()) {
+                Some("times") => {
+                    let value = args
+                        .get(2)
+                        .ok_or(CompileError::MissingField("repeat times"))?;
+                    Some(crate::lang::note::parse_u32(value)?)
+                }
+                Some(other) => {
+                    return Err(CompileError::UnknownForm(format!(
+                        "unknown repeat option: :{}",
+                        other
+                    )));
+                }
+                None => None,
+            };
+            Ok(BarlineSpec::RepeatBackward { times })
+        }
         "repeat-both" => Ok(BarlineSpec::RepeatBoth),
         "ending" => {
             // Parse ending number and action
@@ -254,8 +349,12 @@ fn parse_direction_form(head: &str, args: &[Sexpr]) -> CompileResult<FermataDire
                 .ok_or_else(|| CompileError::type_mismatch("string", format!("{:?}", args[0])))?;
             Ok(FermataDirection::Words(text.to_string()))
         }
+        "text" => Ok(FermataDirection::Text(
+            crate::lang::direction::parse_text_form(args)?,
+        )),
         "segno" => Ok(FermataDirection::Segno),
         "coda" => Ok(FermataDirection::Coda),
+        "direction" => crate::lang::direction::parse_navigation_form(args),
         "pedal" => {
             if args.is_empty() {
                 return Err(CompileError::MissingField("pedal type"));
@@ -340,15 +439,47 @@ pub fn compile_fermata_measure(measure: &FermataMeasure) -> CompileResult<Measur
     }
 
     // Second pass: compile non-attribute elements in order
-    for element in &measure.content {
+    compile_measure_elements(&measure.content, &mut ir_content)?;
+    place_edge_barlines(&mut ir_content);
+
+    Ok(Measure {
+        number: measure
+            .number
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "1".to_string()),
+        implicit: measure.implicit.then_some(YesNo::Yes),
+        non_controlling: None,
+        width: None,
+        content: ir_content,
+    })
+}
+
+/// Compile measure elements in source order, appending to `ir_content`.
+///
+/// Attribute elements are skipped; the caller gathers them beforehand.
+fn compile_measure_elements(
+    elements: &[MeasureElement],
+    ir_content: &mut Vec<MusicDataElement>,
+) -> CompileResult<()> {
+    // Length of the last voice, backed over before the next one
+    let mut previous_voice: Option<u64> = None;
+
+    for element in elements {
         match element {
             // Skip attributes (already handled)
             MeasureElement::Key(_) | MeasureElement::Time(_) | MeasureElement::Clef(_) => continue,
 
             // Notes
             MeasureElement::Note(fermata_note) => {
+                if let Some(dynamic_mark) = &fermata_note.dynamic {
+                    let direction = compile_dynamic_mark(dynamic_mark)?;
+                    ir_content.push(MusicDataElement::Direction(Box::new(direction)));
+                }
                 let note = compile_fermata_note(fermata_note)?;
                 ir_content.push(MusicDataElement::Note(Box::new(note)));
+                for touching in compile_touching_notes(fermata_note)? {
+                    ir_content.push(MusicDataElement::Note(Box::new(touching)));
+                }
             }
 
             // Rests
@@ -357,6 +488,12 @@ pub fn compile_fermata_measure(measure: &FermataMeasure) -> CompileResult<Measur
                 ir_content.push(MusicDataElement::Note(Box::new(note)));
             }
 
+            // Percussion
+            MeasureElement::Unpitched(fermata_unpitched) => {
+                let note = compile_fermata_unpitched(fermata_unpitched)?;
+                ir_content.push(MusicDataElement::Note(Box::new(note)));
+            }
+
             // Chords
             MeasureElement::Chord(fermata_chord) => {
                 let notes = compile_fermata_chord(fermata_chord)?;
@@ -373,6 +510,62 @@ pub fn compile_fermata_measure(measure: &FermataMeasure) -> CompileResult<Measur
                 }
             }
 
+            // Slurs spanning a group of notes
+            MeasureElement::SlurGroup(fermata_slur) => {
+                let notes = compile_fermata_slur(fermata_slur)?;
+                for note in notes {
+                    ir_content.push(MusicDataElement::Note(Box::new(note)));
+                }
+            }
+
+            // Ties between same-pitch notes
+            MeasureElement::TieGroup(fermata_tie) => {
+                let notes = compile_fermata_tie(fermata_tie)?;
+                for note in notes {
+                    ir_content.push(MusicDataElement::Note(Box::new(note)));
+                }
+            }
+
+            // Two-note tremolos
+            MeasureElement::TremoloPair(fermata_pair) => {
+                let notes = compile_fermata_tremolo_pair(fermata_pair)?;
+                for note in notes {
+                    ir_content.push(MusicDataElement::Note(Box::new(note)));
+                }
+            }
+
+            // Glissandi and slides between notes
+            MeasureElement::Glissando(fermata_glissando) => {
+                let notes = compile_fermata_glissando(fermata_glissando)?;
+                for note in notes {
+                    ir_content.push(MusicDataElement::Note(Box::new(note)));
+                }
+            }
+
+            // Octave shifts around a group of notes
+            MeasureElement::Ottava(fermata_ottava) => {
+                ir_content.extend(compile_fermata_ottava(fermata_ottava)?);
+            }
+
+            // Hairpins around a group of notes
+            MeasureElement::Hairpin(fermata_hairpin) => {
+                ir_content.extend(compile_fermata_hairpin(fermata_hairpin)?);
+            }
+
+            // Each voice starts over at the beginning of the measure
+            MeasureElement::Voice(voice) => {
+                if let Some(duration) = previous_voice {
+                    ir_content.push(MusicDataElement::Backup(Backup {
+                        duration,
+                        editorial: Editorial::default(),
+                    }));
+                }
+                let start = ir_content.len();
+                compile_measure_elements(&voice.content, ir_content)?;
+                assign_voice(&mut ir_content[start..], &voice.number.to_string());
+                previous_voice = Some(voice_duration(&ir_content[start..]));
+            }
+
             // Grace notes
             MeasureElement::GraceNote(fermata_grace) => {
                 let note = compile_fermata_grace(fermata_grace)?;
@@ -430,20 +623,84 @@ pub fn compile_fermata_measure(measure: &FermataMeasure) -> CompileResult<Measur
         }
     }
 
-    Ok(Measure {
-        number: measure
-            .number
-            .map(|n| n.to_string())
-            .unwrap_or_else(|| "1".to_string()),
-        implicit: None,
-        non_controlling: None,
-        width: None,
-        content: ir_content,
-    })
+    Ok(())
+}
+
+/// Set the voice on every note and forward in `content`.
+fn assign_voice(content: &mut [MusicDataElement], voice: &str) {
+    for element in content {
+        match element {
+            MusicDataElement::Note(note) => note.voice = Some(voice.to_string()),
+            MusicDataElement::Forward(forward) => forward.voice = Some(voice.to_string()),
+            _ => {}
+        }
+    }
+}
+
+/// Time covered by `content`: sounding durations plus forwards, less backups.
+fn voice_duration(content: &[MusicDataElement]) -> u64 {
+    let mut cursor = MeasureCursor::new();
+    for element in content {
+        cursor.advance(element);
+    }
+    cursor.offset()
+}
+
+/// Move left barlines to the start of the measure and right barlines to
+/// the end, merging the barlines on each edge into one.
+///
+/// Repeats and endings belong to a measure's edges wherever they are
+/// written, and an ending that shares an edge with a repeat must be in the
+/// same `<barline>`. Barlines without a location stay where they are.
+fn place_edge_barlines(content: &mut Vec<MusicDataElement>) {
+    let mut left: Option<Barline> = None;
+    let mut right: Option<Barline> = None;
+    let mut rest = Vec::with_capacity(content.len());
+
+    for element in content.drain(..) {
+        match element {
+            MusicDataElement::Barline(barline) => match barline.location {
+                Some(RightLeftMiddle::Left) => merge_barline(&mut left, *barline),
+                Some(RightLeftMiddle::Right) => merge_barline(&mut right, *barline),
+                _ => rest.push(MusicDataElement::Barline(barline)),
+            },
+            other => rest.push(other),
+        }
+    }
+
+    if let Some(barline) = left {
+        rest.insert(0, MusicDataElement::Barline(Box::new(barline)));
+    }
+    if let Some(barline) = right {
+        rest.push(MusicDataElement::Barline(Box::new(barline)));
+    }
+    *content = rest;
+}
+
+/// Fold `barline` into the barline already on the same edge, if any.
+fn merge_barline(edge: &mut Option<Barline>, barline: Barline) {
+    let Some(merged) = edge else {
+        *edge = Some(barline);
+        return;
+    };
+    // A styled barline wins over a regular or unstyled one
+    if matches!(merged.bar_style, None | Some(BarStyle::Regular)) && barline.bar_style.is_some() {
+        merged.bar_style = barline.bar_style;
+    }
+    merged.wavy_line = merged.wavy_line.take().or(barline.wavy_line);
+    merged.segno = merged.segno.take().or(barline.segno);
+    merged.coda = merged.coda.take().or(barline.coda);
+    merged.fermatas.extend(barline.fermatas);
+    merged.ending = merged.ending.take().or(barline.ending);
+    merged.repeat = merged.repeat.take().or(barline.repeat);
 }
 
 /// Compile a barline specification to an IR Barline.
+///
+/// Repeat starts and ending starts sit on the left barline; repeat ends,
+/// ending stops, and final barlines on the right.
 fn compile_barline_spec(spec: &BarlineSpec) -> CompileResult<Barline> {
+    let mut ending = None;
     let (bar_style, location, repeat) = match spec {
         BarlineSpec::Regular => (Some(BarStyle::Regular), None, None),
         BarlineSpec::Double => (Some(BarStyle::LightLight), None, None),
@@ -461,12 +718,12 @@ fn compile_barline_spec(spec: &BarlineSpec) -> CompileResult<Barline> {
                 winged: None,
             }),
         ),
-        BarlineSpec::RepeatBackward => (
+        BarlineSpec::RepeatBackward { times } => (
             Some(BarStyle::LightHeavy),
             Some(RightLeftMiddle::Right),
             Some(Repeat {
                 direction: crate::ir::common::BackwardForward::Backward,
-                times: None,
+                times: *times,
                 winged: None,
             }),
         ),
@@ -479,12 +736,24 @@ fn compile_barline_spec(spec: &BarlineSpec) -> CompileResult<Barline> {
                 winged: None,
             }),
         ),
-        BarlineSpec::Ending {
-            number: _,
-            action: _,
-        } => {
-            // Endings are more complex and would need additional IR support
-            (Some(BarStyle::Regular), None, None)
+        BarlineSpec::Ending { number, action } => {
+            let (r#type, location) = match action {
+                EndingAction::Start => (StartStopDiscontinue::Start, RightLeftMiddle::Left),
+                EndingAction::Stop => (StartStopDiscontinue::Stop, RightLeftMiddle::Right),
+                EndingAction::Discontinue => {
+                    (StartStopDiscontinue::Discontinue, RightLeftMiddle::Right)
+                }
+            };
+            ending = Some(Ending {
+                r#type,
+                number: number.to_string(),
+                text: None,
+                print_object: None,
+                end_length: None,
+                text_x: None,
+                text_y: None,
+            });
+            (None, Some(location), None)
         }
     };
 
@@ -496,7 +765,7 @@ fn compile_barline_spec(spec: &BarlineSpec) -> CompileResult<Barline> {
         segno: None,
         coda: None,
         fermatas: vec![],
-        ending: None,
+        ending,
         repeat,
     })
 }
@@ -549,7 +818,18 @@ mod tests {
     fn test_parse_barline_form_repeat_backward() {
         let args = vec![Sexpr::keyword("repeat-backward")];
         let result = parse_barline_form(&args).unwrap();
-        assert_eq!(result, BarlineSpec::RepeatBackward);
+        assert_eq!(result, BarlineSpec::RepeatBackward { times: None });
+    }
+
+    #[test]
+    fn test_parse_barline_form_repeat_end_times() {
+        let args = vec![
+            Sexpr::keyword("repeat-end"),
+            Sexpr::keyword("times"),
+            Sexpr::Integer(3),
+        ];
+        let result = parse_barline_form(&args).unwrap();
+        assert_eq!(result, BarlineSpec::RepeatBackward { times: Some(3) });
     }
 
     #[test]
@@ -716,6 +996,89 @@ mod tests {
         assert!(matches!(measure.content[0], MusicDataElement::Direction(_)));
     }
 
+    #[test]
+    fn test_compile_measure_note_with_articulation_and_dynamic() {
+        let score =
+            crate::lang::compile("(score (part :piano (measure (note c4 :q :staccato :mf))))")
+                .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        let dynamics = xml.find("<dynamics").expect("dynamics direction");
+        let note = xml.find("<note>").expect("note");
+        assert!(dynamics < note, "dynamic should precede the note");
+        assert!(xml.contains("<mf/>"));
+        assert!(xml.contains("<articulations>"));
+        assert!(xml.contains("<staccato/>"));
+    }
+
+    #[test]
+    fn test_compile_measure_with_voices_inserts_backup() {
+        let sexpr = parse(
+            "(measure (time 4 4)
+               (voice 1 (note e5 :h) (note d5 :h))
+               (voice 2 (note c4 :q) (note b3 :q) (chord (a3 c4) :h)))",
+        )
+        .unwrap();
+        let measure = compile_measure(&sexpr, 1).unwrap();
+
+        let backups: Vec<u64> = measure
+            .content
+            .iter()
+            .filter_map(|e| match e {
+                MusicDataElement::Backup(b) => Some(b.duration),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(backups, vec![4 * DEFAULT_DIVISIONS as u64]);
+
+        let voices: Vec<Option<&str>> = measure
+            .content
+            .iter()
+            .filter_map(|e| match e {
+                MusicDataElement::Note(n) => Some(n.voice.as_deref()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            voices,
+            vec![
+                Some("1"),
+                Some("1"),
+                Some("2"),
+                Some("2"),
+                Some("2"),
+                Some("2")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compile_measure_soprano_alto_emits_backup_and_voices() {
+        let score = crate::lang::compile(
+            "(score (part :choir (measure
+               (voice 1 (note g4 :q) (note a4 :q) (note b4 :h))
+               (voice 2 (note e4 :h) (note d4 :h)))))",
+        )
+        .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        let backup = xml.find("<backup>").expect("backup");
+        assert!(xml[backup..].contains("<duration>3840</duration>"));
+        let first_alto = xml.find("<voice>2</voice>").expect("alto voice");
+        assert!(backup < first_alto);
+        assert_eq!(xml.matches("<voice>1</voice>").count(), 3);
+        assert_eq!(xml.matches("<voice>2</voice>").count(), 2);
+    }
+
+    #[test]
+    fn test_parse_voice_form_rejects_attributes() {
+        let sexpr = parse("(measure (voice 1 (clef :bass) (note c3 :w)))").unwrap();
+        assert!(parse_measure_from_sexpr(&sexpr, 1).is_err());
+
+        let sexpr = parse("(measure (voice 0 (note c3 :w)))").unwrap();
+        assert!(parse_measure_from_sexpr(&sexpr, 1).is_err());
+    }
+
     #[test]
     fn test_compile_measure_with_barline() {
         let sexpr = parse("(measure (note c4 :q) (barline :final))").unwrap();
@@ -726,12 +1089,34 @@ mod tests {
         assert!(matches!(measure.content[1], MusicDataElement::Barline(_)));
     }
 
+    #[test]
+    fn test_compile_measure_moves_repeats_to_edges() {
+        let sexpr = parse(
+            "(measure (note c4 :h) (barline :repeat-start) (barline :ending 2 :start) (note d4 :h))",
+        )
+        .unwrap();
+        let measure = compile_measure(&sexpr, 1).unwrap();
+
+        assert_eq!(measure.content.len(), 3);
+        let MusicDataElement::Barline(barline) = &measure.content[0] else {
+            panic!(
+                "expected a left barline first, got {:?}",
+                measure.content[0]
+            );
+        };
+        assert_eq!(barline.location, Some(RightLeftMiddle::Left));
+        assert_eq!(barline.bar_style, Some(BarStyle::HeavyLight));
+        assert!(barline.repeat.is_some());
+        assert_eq!(barline.ending.as_ref().unwrap().number, "2");
+    }
+
     // === compile_fermata_measure tests ===
 
     #[test]
     fn test_compile_fermata_measure_basic() {
         let measure = FermataMeasure {
             number: Some(1),
+            implicit: false,
             content: vec![MeasureElement::Note(FermataNote {
                 pitch: FermataPitch {
                     step: PitchStep::C,
@@ -743,10 +1128,13 @@ mod tests {
                 staff: None,
                 stem: None,
                 articulations: vec![],
+                dynamic: None,
                 ornaments: vec![],
+                technical: vec![],
                 tie: None,
                 slur: None,
-                lyric: None,
+                lyrics: vec![],
+                invisible: false,
             })],
         };
 
@@ -759,11 +1147,13 @@ mod tests {
     fn test_compile_fermata_measure_with_rest() {
         let measure = FermataMeasure {
             number: Some(2),
+            implicit: false,
             content: vec![MeasureElement::Rest(FermataRest {
                 duration: FermataDuration::default(),
                 voice: None,
                 staff: None,
                 measure_rest: false,
+                invisible: false,
             })],
         };
 
@@ -786,6 +1176,7 @@ mod tests {
     fn test_compile_fermata_measure_empty() {
         let measure = FermataMeasure {
             number: Some(1),
+            implicit: false,
             content: vec![],
         };
 
@@ -798,6 +1189,7 @@ mod tests {
     fn test_compile_fermata_measure_no_number() {
         let measure = FermataMeasure {
             number: None,
+            implicit: false,
             content: vec![],
         };
 
@@ -835,7 +1227,7 @@ mod tests {
 
     #[test]
     fn test_compile_barline_spec_repeat_backward() {
-        let barline = compile_barline_spec(&BarlineSpec::RepeatBackward).unwrap();
+        let barline = compile_barline_spec(&BarlineSpec::RepeatBackward { times: None }).unwrap();
         assert_eq!(barline.bar_style, Some(BarStyle::LightHeavy));
         assert!(barline.repeat.is_some());
     }
@@ -22,18 +22,26 @@
 
 pub mod ast;
 pub mod attributes;
+pub mod beam;
 pub mod chord;
 pub mod connectors;
+pub mod convert;
 pub mod defaults;
+pub mod define;
 pub mod direction;
 pub mod duration;
 pub mod error;
 pub mod grace;
+pub mod json;
+pub mod lyric;
 pub mod measure;
 pub mod note;
 pub mod part;
+pub mod percussion;
 pub mod pitch;
 pub mod score;
+pub mod structure;
+pub mod tremolo;
 pub mod tuplet;
 
 mod compiler;
@@ -41,10 +49,15 @@ mod compiler;
 pub use ast::*;
 pub use compiler::{
     check, compile, compile_measure_str, compile_note_str, compile_part_str, compile_pitch_str,
+    compile_sexpr, parse_recover,
 };
 pub use error::{CompileError, CompileResult};
+pub use json::{compile_json_to_musicxml, parse_json_score};
 pub use part::CompiledPart;
-pub use score::{compile_fermata_score, compile_score, parse_score_to_ast};
+pub use score::{
+    compile_fermata_score, compile_score, concat_movements, parse_score_to_ast, select_measures,
+    select_part,
+};
 
 /// Compile Fermata source to Music IR
 pub fn compile_str(source: &str) -> CompileResult<crate::ir::score::ScorePartwise> {
@@ -2,19 +2,26 @@
 //!
 //! This module handles compiling note and rest S-expressions into IR Note types.
 
+use crate::ir::beam::{Notehead, NoteheadValue};
 use crate::ir::beam::{Stem, StemValue};
-use crate::ir::common::{EmptyPlacement, Position, StartStop, StartStopContinue, YesNo};
+use crate::ir::common::{
+    AboveBelow, EmptyPlacement, Font, Position, PrintStyle, StartStop, StartStopContinue, YesNo,
+};
 use crate::ir::notation::{
-    ArticulationElement, Articulations, Mordent, NotationContent, Notations, OrnamentElement,
-    OrnamentWithAccidentals, Ornaments, Slur, StrongAccent, Tied, Turn,
+    ArticulationElement, Articulations, Bend, BendRelease, Fret, Harmonic, Mordent,
+    NotationContent, Notations, OrnamentElement, OrnamentWithAccidentals, Ornaments, Slur,
+    StringNumber, StrongAccent, Technical, TechnicalElement, Tied, Turn,
 };
 use crate::ir::note::{FullNote, Note, NoteContent, PitchRestUnpitched, Rest, Tie};
 use crate::lang::ast::{
-    Articulation, FermataDuration, FermataNote, FermataRest, Ornament, StemDirection,
+    Articulation, ArticulationMark, BendSpec, DynamicMark, FermataDuration, FermataNote,
+    FermataRest, HarmonicKind, HarmonicSpec, Ornament, StemDirection, TechnicalMark,
 };
 use crate::lang::defaults::DEFAULT_DIVISIONS;
+use crate::lang::direction::parse_dynamic_name;
 use crate::lang::duration::{compile_dots, compile_duration_divisions_with, compile_duration_type};
 use crate::lang::error::{CompileError, CompileResult};
+use crate::lang::lyric::{compile_lyric, parse_lyric_list};
 use crate::lang::pitch::{compile_pitch, parse_pitch_str};
 use crate::sexpr::Sexpr;
 
@@ -60,6 +67,12 @@ pub fn compile_note(sexpr: &Sexpr) -> CompileResult<Note> {
 /// - pitch: "c4", "f#5", etc.
 /// - duration: :q, :h, :w, :8, etc.
 /// - keywords: :voice N, :staff N, :stem up/down, :tie start/stop, etc.
+/// - flags: articulations (`:staccato`, `:accent`, ...), ornaments, and a
+///   dynamic (`:mf`, `:sfz`, ...) placed before the note
+/// - an articulation may be followed by `:above` or `:below`, or written as
+///   a list such as `(:staccato :below)`, to place it relative to the staff
+///
+/// Returns [`CompileError::Semantic`] for an unrecognized keyword.
 pub fn parse_note_form(items: &[Sexpr]) -> CompileResult<FermataNote> {
     if items.is_empty() {
         return Err(CompileError::InvalidNote("note requires pitch".to_string()));
@@ -92,8 +105,12 @@ pub fn parse_note_form(items: &[Sexpr]) -> CompileResult<FermataNote> {
     let mut stem: Option<StemDirection> = None;
     let mut tie: Option<StartStop> = None;
     let mut slur: Option<StartStop> = None;
-    let mut articulations: Vec<Articulation> = Vec::new();
+    let mut articulations: Vec<ArticulationMark> = Vec::new();
+    let mut dynamic: Option<DynamicMark> = None;
     let mut ornaments: Vec<Ornament> = Vec::new();
+    let mut technical: Vec<TechnicalMark> = Vec::new();
+    let mut lyrics = Vec::new();
+    let mut invisible = false;
 
     let mut i = remaining_start;
     while i < items.len() {
@@ -138,29 +155,8 @@ pub fn parse_note_form(items: &[Sexpr]) -> CompileResult<FermataNote> {
                     slur = Some(parse_start_stop(&items[i + 1])?);
                     i += 2;
                 }
-                // Articulations as flags
-                "staccato" => {
-                    articulations.push(Articulation::Staccato);
-                    i += 1;
-                }
-                "accent" => {
-                    articulations.push(Articulation::Accent);
-                    i += 1;
-                }
-                "tenuto" => {
-                    articulations.push(Articulation::Tenuto);
-                    i += 1;
-                }
-                "marcato" => {
-                    articulations.push(Articulation::StrongAccent);
-                    i += 1;
-                }
-                "staccatissimo" => {
-                    articulations.push(Articulation::Staccatissimo);
-                    i += 1;
-                }
-                "spiccato" => {
-                    articulations.push(Articulation::Spiccato);
+                "invisible" => {
+                    invisible = true;
                     i += 1;
                 }
                 // Ornaments as flags
@@ -176,11 +172,49 @@ pub fn parse_note_form(items: &[Sexpr]) -> CompileResult<FermataNote> {
                     ornaments.push(Ornament::Turn);
                     i += 1;
                 }
+                // Technical
+                "harmonic" => {
+                    let (harmonic, next) = parse_harmonic_args(items, i + 1)?;
+                    technical.push(TechnicalMark::Harmonic(harmonic));
+                    i = next;
+                }
                 _ => {
-                    // Unknown keyword - skip it (or could error)
+                    // Articulation and dynamic flags
+                    if let Some((mark, next)) = parse_articulation_at(items, i) {
+                        articulations.push(mark);
+                        i = next;
+                        continue;
+                    } else if let Some(mark) = parse_note_dynamic(kw) {
+                        dynamic = Some(mark);
+                    } else {
+                        return Err(CompileError::Semantic(format!(
+                            "unknown note keyword :{}; valid articulations are {}",
+                            kw,
+                            ARTICULATION_KEYWORDS
+                                .iter()
+                                .map(|(name, _)| format!(":{}", name))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )));
+                    }
                     i += 1;
                 }
             }
+        } else if let Some(list) = items[i].as_list() {
+            match list.first().and_then(|s| s.as_keyword()) {
+                Some("bend") => {
+                    technical.push(TechnicalMark::Bend(parse_bend_list(&list[1..])?));
+                }
+                Some("string") => {
+                    technical.push(TechnicalMark::String(parse_tab_number(list, 1)?));
+                }
+                Some("fret") => {
+                    technical.push(TechnicalMark::Fret(parse_tab_number(list, 0)?));
+                }
+                Some("lyric") => lyrics.push(parse_lyric_list(&list[1..])?),
+                _ => articulations.push(parse_articulation_list(list)?),
+            }
+            i += 1;
         } else {
             // Skip non-keyword items
             i += 1;
@@ -194,15 +228,190 @@ pub fn parse_note_form(items: &[Sexpr]) -> CompileResult<FermataNote> {
         staff,
         stem,
         articulations,
+        dynamic,
         ornaments,
+        technical,
         tie,
         slur,
-        lyric: None,
+        lyrics,
+        invisible,
     })
 }
 
+/// Articulation keywords accepted on notes.
+const ARTICULATION_KEYWORDS: &[(&str, Articulation)] = &[
+    ("staccato", Articulation::Staccato),
+    ("staccatissimo", Articulation::Staccatissimo),
+    ("spiccato", Articulation::Spiccato),
+    ("accent", Articulation::Accent),
+    ("strong-accent", Articulation::StrongAccent),
+    ("marcato", Articulation::StrongAccent),
+    ("tenuto", Articulation::Tenuto),
+    ("detached-legato", Articulation::DetachedLegato),
+    ("breath-mark", Articulation::BreathMark),
+    ("caesura", Articulation::Caesura),
+];
+
+/// Look up an articulation keyword (without the leading colon).
+fn parse_articulation_keyword(kw: &str) -> Option<Articulation> {
+    ARTICULATION_KEYWORDS
+        .iter()
+        .find(|(name, _)| *name == kw)
+        .map(|(_, articulation)| *articulation)
+}
+
+/// Parse an articulation keyword at `items[i]` and an optional `:above` or
+/// `:below` after it, returning the mark and the index of the next item.
+pub(crate) fn parse_articulation_at(
+    items: &[Sexpr],
+    i: usize,
+) -> Option<(ArticulationMark, usize)> {
+    let articulation = parse_articulation_keyword(items.get(i)?.as_keyword()?)?;
+    let placement = items
+        .get(i + 1)
+        .and_then(|s| s.as_keyword())
+        .and_then(parse_placement_keyword);
+    let next = if placement.is_some() { i + 2 } else { i + 1 };
+    Some((
+        ArticulationMark {
+            articulation,
+            placement,
+        },
+        next,
+    ))
+}
+
+/// Parse an articulation list such as `(:staccato :below)`.
+pub(crate) fn parse_articulation_list(items: &[Sexpr]) -> CompileResult<ArticulationMark> {
+    match parse_articulation_at(items, 0) {
+        Some((mark, next)) if next == items.len() => Ok(mark),
+        _ => Err(CompileError::Semantic(format!(
+            "expected an articulation with optional :above or :below, got {:?}",
+            items
+        ))),
+    }
+}
+
+/// Look up a placement keyword (`above` or `below`).
+fn parse_placement_keyword(kw: &str) -> Option<AboveBelow> {
+    match kw {
+        "above" => Some(AboveBelow::Above),
+        "below" => Some(AboveBelow::Below),
+        _ => None,
+    }
+}
+
+/// Look up a dynamic keyword such as `mf` or `sfz`.
+///
+/// Hairpins (`cresc`, `dim`) span several notes and are not accepted here.
+fn parse_note_dynamic(kw: &str) -> Option<DynamicMark> {
+    parse_dynamic_name(kw)
+        .ok()
+        .filter(|mark| !matches!(mark, DynamicMark::Crescendo(_) | DynamicMark::Diminuendo(_)))
+}
+
+/// Parse the arguments following a `:harmonic` keyword.
+///
+/// Accepts `:natural` (the default when omitted) or `:artificial`, optionally
+/// followed by `:touching <pitch>`. Returns the harmonic and the index of the
+/// first unconsumed item.
+fn parse_harmonic_args(items: &[Sexpr], start: usize) -> CompileResult<(HarmonicSpec, usize)> {
+    let mut i = start;
+    let kind = match items.get(i).and_then(|s| s.as_keyword()) {
+        Some("natural") => {
+            i += 1;
+            HarmonicKind::Natural
+        }
+        Some("artificial") => {
+            i += 1;
+            HarmonicKind::Artificial
+        }
+        _ => HarmonicKind::Natural,
+    };
+
+    let mut touching = None;
+    if items.get(i).and_then(|s| s.as_keyword()) == Some("touching") {
+        let pitch = items
+            .get(i + 1)
+            .and_then(|s| s.as_symbol())
+            .ok_or_else(|| CompileError::InvalidNote("missing :touching pitch".to_string()))?;
+        if kind != HarmonicKind::Artificial {
+            return Err(CompileError::InvalidNote(
+                ":touching is only valid for artificial harmonics".to_string(),
+            ));
+        }
+        touching = Some(parse_pitch_str(pitch)?);
+        i += 2;
+    }
+
+    Ok((HarmonicSpec { kind, touching }, i))
+}
+
+/// Parse the arguments of a `(:bend ...)` list.
+///
+/// Expects the bend amount in semitones, as MusicXML writes it (`1` is a
+/// half-step bend, `2` a whole step), optionally followed by `:pre-bend`
+/// and/or `:release`.
+fn parse_bend_list(items: &[Sexpr]) -> CompileResult<BendSpec> {
+    let alter = items
+        .first()
+        .and_then(|s| s.as_float().or_else(|| s.as_integer().map(|n| n as f64)))
+        .ok_or_else(|| {
+            CompileError::InvalidNote(
+                "bend requires an amount in semitones, e.g. (:bend 2)".to_string(),
+            )
+        })?;
+
+    let mut bend = BendSpec {
+        alter,
+        pre_bend: false,
+        release: false,
+    };
+    for item in &items[1..] {
+        match item.as_keyword() {
+            Some("pre-bend") => bend.pre_bend = true,
+            Some("release") => bend.release = true,
+            _ => {
+                return Err(CompileError::InvalidNote(format!(
+                    "expected :pre-bend or :release in bend, got {:?}",
+                    item
+                )));
+            }
+        }
+    }
+    Ok(bend)
+}
+
+/// Parse a `(:string n)` or `(:fret n)` list, requiring `n >= min`.
+fn parse_tab_number(list: &[Sexpr], min: u8) -> CompileResult<u8> {
+    let name = list[0].as_keyword().unwrap_or_default();
+    let value = match list {
+        [_, value] => value.as_integer(),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        CompileError::InvalidNote(format!(
+            "{} requires a single number, e.g. (:{} {})",
+            name, name, min
+        ))
+    })?;
+
+    u8::try_from(value)
+        .ok()
+        .filter(|&n| n >= min)
+        .ok_or_else(|| {
+            CompileError::InvalidNote(format!(
+                "{} must be between {} and {}, got {}",
+                name,
+                min,
+                u8::MAX,
+                value
+            ))
+        })
+}
+
 /// Check if a string looks like a duration keyword.
-fn is_duration_keyword(s: &str) -> bool {
+pub(crate) fn is_duration_keyword(s: &str) -> bool {
     let s = s.trim_start_matches(':');
     let s = s.trim_end_matches('.');
     matches!(
@@ -230,7 +439,7 @@ fn is_duration_keyword(s: &str) -> bool {
             | "breve"
             | "long"
             | "maxima"
-    )
+    ) || crate::lang::duration::is_fraction_duration(s)
 }
 
 /// Compile a FermataNote to an IR Note.
@@ -251,7 +460,7 @@ pub fn compile_fermata_note(note: &FermataNote) -> CompileResult<Note> {
         attack: None,
         release: None,
         pizzicato: None,
-        print_object: None,
+        print_object: note.invisible.then_some(YesNo::No),
         content: NoteContent::Regular {
             full_note: FullNote {
                 chord: false,
@@ -271,10 +480,72 @@ pub fn compile_fermata_note(note: &FermataNote) -> CompileResult<Note> {
         staff: note.staff.map(|s| s as u16),
         beams: vec![],
         notations,
-        lyrics: vec![],
+        lyrics: note.lyrics.iter().map(compile_lyric).collect(),
     })
 }
 
+/// Compile the touching-pitch notes for any artificial harmonics on a note.
+///
+/// MusicXML writes an artificial harmonic as a chord: the stopped (base)
+/// pitch followed by a diamond-headed touching pitch. The returned notes
+/// must be emitted directly after the note compiled by
+/// [`compile_fermata_note`].
+pub fn compile_touching_notes(note: &FermataNote) -> CompileResult<Vec<Note>> {
+    let mut notes = Vec::new();
+
+    for mark in &note.technical {
+        let TechnicalMark::Harmonic(HarmonicSpec {
+            touching: Some(touching),
+            ..
+        }) = mark
+        else {
+            continue;
+        };
+
+        let mut touching_note = compile_fermata_note(&FermataNote {
+            pitch: touching.clone(),
+            duration: note.duration.clone(),
+            voice: note.voice,
+            staff: note.staff,
+            stem: note.stem,
+            articulations: vec![],
+            dynamic: None,
+            ornaments: vec![],
+            technical: vec![],
+            tie: None,
+            slur: None,
+            lyrics: vec![],
+            invisible: note.invisible,
+        })?;
+
+        if let NoteContent::Regular { full_note, .. } = &mut touching_note.content {
+            full_note.chord = true;
+        }
+        touching_note.notehead = Some(Notehead {
+            value: NoteheadValue::Diamond,
+            filled: None,
+            parentheses: None,
+            font: Default::default(),
+            color: None,
+        });
+        touching_note.notations = vec![Notations {
+            print_object: None,
+            content: vec![NotationContent::Technical(Box::new(Technical {
+                content: vec![TechnicalElement::Harmonic(Harmonic {
+                    artificial: true,
+                    touching_pitch: true,
+                    ..Default::default()
+                })],
+            }))],
+            editorial: Default::default(),
+        }];
+
+        notes.push(touching_note);
+    }
+
+    Ok(notes)
+}
+
 /// Parse a u32 from an S-expression (Integer or Symbol).
 pub fn parse_u32(sexpr: &Sexpr) -> CompileResult<u32> {
     match sexpr {
@@ -403,45 +674,8 @@ pub fn compile_notations(note: &FermataNote) -> CompileResult<Vec<Notations>> {
     }
 
     // Add articulations
-    if !note.articulations.is_empty() {
-        let articulation_elements: Vec<ArticulationElement> = note
-            .articulations
-            .iter()
-            .map(|a| match a {
-                Articulation::Staccato => ArticulationElement::Staccato(EmptyPlacement::default()),
-                Articulation::Staccatissimo => {
-                    ArticulationElement::Staccatissimo(EmptyPlacement::default())
-                }
-                Articulation::Spiccato => ArticulationElement::Spiccato(EmptyPlacement::default()),
-                Articulation::Accent => ArticulationElement::Accent(EmptyPlacement::default()),
-                Articulation::StrongAccent => {
-                    ArticulationElement::StrongAccent(StrongAccent::default())
-                }
-                Articulation::Tenuto => ArticulationElement::Tenuto(EmptyPlacement::default()),
-                Articulation::DetachedLegato => {
-                    ArticulationElement::DetachedLegato(EmptyPlacement::default())
-                }
-                Articulation::BreathMark => {
-                    // BreathMark requires a value, using default
-                    ArticulationElement::BreathMark(crate::ir::notation::BreathMark {
-                        value: crate::ir::notation::BreathMarkValue::Comma,
-                        placement: None,
-                        position: Position::default(),
-                    })
-                }
-                Articulation::Caesura => {
-                    ArticulationElement::Caesura(crate::ir::notation::Caesura {
-                        value: crate::ir::notation::CaesuraValue::Normal,
-                        placement: None,
-                        position: Position::default(),
-                    })
-                }
-            })
-            .collect();
-
-        content.push(NotationContent::Articulations(Box::new(Articulations {
-            content: articulation_elements,
-        })));
+    if let Some(articulations) = compile_articulations(&note.articulations) {
+        content.push(NotationContent::Articulations(Box::new(articulations)));
     }
 
     // Add ornaments
@@ -464,10 +698,10 @@ pub fn compile_notations(note: &FermataNote) -> CompileResult<Vec<Notations>> {
                     Ornament::Shake => {
                         OrnamentElement::Shake(crate::ir::notation::EmptyTrillSound::default())
                     }
-                    Ornament::Tremolo(marks) => {
+                    Ornament::Tremolo { marks, r#type } => {
                         OrnamentElement::Tremolo(crate::ir::notation::Tremolo {
                             value: *marks,
-                            r#type: None,
+                            r#type: Some(*r#type),
                             placement: None,
                             position: Position::default(),
                         })
@@ -485,6 +719,42 @@ pub fn compile_notations(note: &FermataNote) -> CompileResult<Vec<Notations>> {
         })));
     }
 
+    // Add technical indications
+    if !note.technical.is_empty() {
+        let technical_elements: Vec<TechnicalElement> = note
+            .technical
+            .iter()
+            .map(|t| match t {
+                TechnicalMark::Harmonic(h) => TechnicalElement::Harmonic(Harmonic {
+                    natural: h.kind == HarmonicKind::Natural,
+                    artificial: h.kind == HarmonicKind::Artificial,
+                    base_pitch: h.touching.is_some(),
+                    ..Default::default()
+                }),
+                TechnicalMark::Bend(b) => TechnicalElement::Bend(Bend {
+                    bend_alter: b.alter,
+                    pre_bend: b.pre_bend,
+                    release: b.release.then_some(BendRelease::Default),
+                    with_bar: None,
+                }),
+                TechnicalMark::String(n) => TechnicalElement::String(StringNumber {
+                    value: *n,
+                    placement: None,
+                    print_style: PrintStyle::default(),
+                }),
+                TechnicalMark::Fret(n) => TechnicalElement::Fret(Fret {
+                    value: *n,
+                    font: Font::default(),
+                    color: None,
+                }),
+            })
+            .collect();
+
+        content.push(NotationContent::Technical(Box::new(Technical {
+            content: technical_elements,
+        })));
+    }
+
     // Return notations if we have any content
     if content.is_empty() {
         Ok(vec![])
@@ -545,7 +815,7 @@ pub fn parse_note_form_to_ast(items: &[Sexpr]) -> CompileResult<FermataNote> {
 ///
 /// Expected format: `duration [keywords...]`
 /// - duration: :q, :h, :w, :8, etc.
-/// - keywords: :voice N, :staff N, :measure
+/// - keywords: :voice N, :staff N, :measure (or :whole-measure)
 pub fn parse_rest_form(items: &[Sexpr]) -> CompileResult<FermataRest> {
     // First item is duration (if present and is a keyword/symbol)
     let (duration, remaining_start) = if !items.is_empty() {
@@ -566,6 +836,7 @@ pub fn parse_rest_form(items: &[Sexpr]) -> CompileResult<FermataRest> {
     let mut voice: Option<u32> = None;
     let mut staff: Option<u32> = None;
     let mut measure_rest = false;
+    let mut invisible = false;
 
     let mut i = remaining_start;
     while i < items.len() {
@@ -589,10 +860,14 @@ pub fn parse_rest_form(items: &[Sexpr]) -> CompileResult<FermataRest> {
                     staff = Some(parse_u32(&items[i + 1])?);
                     i += 2;
                 }
-                "measure" => {
+                "measure" | "whole-measure" => {
                     measure_rest = true;
                     i += 1;
                 }
+                "invisible" => {
+                    invisible = true;
+                    i += 1;
+                }
                 _ => {
                     // Unknown keyword - skip it
                     i += 1;
@@ -609,6 +884,7 @@ pub fn parse_rest_form(items: &[Sexpr]) -> CompileResult<FermataRest> {
         voice,
         staff,
         measure_rest,
+        invisible,
     })
 }
 
@@ -619,6 +895,10 @@ pub fn parse_rest_form_to_ast(items: &[Sexpr]) -> CompileResult<FermataRest> {
 }
 
 /// Compile a FermataRest to an IR Note (with Rest content).
+///
+/// A whole-measure rest has no note type and keeps its written duration
+/// as a placeholder; the part compiler replaces it with the length of the
+/// measure under the active time signature.
 pub fn compile_fermata_rest(rest: &FermataRest) -> CompileResult<Note> {
     let divisions = DEFAULT_DIVISIONS as u64;
 
@@ -639,7 +919,7 @@ pub fn compile_fermata_rest(rest: &FermataRest) -> CompileResult<Note> {
         attack: None,
         release: None,
         pizzicato: None,
-        print_object: None,
+        print_object: rest.invisible.then_some(YesNo::No),
         content: NoteContent::Regular {
             full_note: FullNote {
                 chord: false,
@@ -650,8 +930,12 @@ pub fn compile_fermata_rest(rest: &FermataRest) -> CompileResult<Note> {
         },
         instrument: vec![],
         voice: rest.voice.map(|v| v.to_string()),
-        r#type: Some(compile_duration_type(&rest.duration.base)),
-        dots: compile_dots(rest.duration.dots),
+        r#type: (!rest.measure_rest).then(|| compile_duration_type(&rest.duration.base)),
+        dots: if rest.measure_rest {
+            vec![]
+        } else {
+            compile_dots(rest.duration.dots)
+        },
         accidental: None,
         time_modification: None,
         stem: None,
@@ -663,6 +947,80 @@ pub fn compile_fermata_rest(rest: &FermataRest) -> CompileResult<Note> {
     })
 }
 
+/// Compile articulation keywords into a single IR `<articulations>` group.
+///
+/// Articulations are emitted in the order the MusicXML schema lists them
+/// (accent, strong-accent, staccato, tenuto, detached-legato, ...) no matter
+/// how they were written, and repeats are dropped, so `:staccato :accent`
+/// and `:accent :staccato` produce the same output.
+pub(crate) fn compile_articulations(articulations: &[ArticulationMark]) -> Option<Articulations> {
+    let mut articulations = articulations.to_vec();
+    articulations.sort_by_key(|mark| schema_order(mark.articulation));
+    articulations.dedup_by_key(|mark| mark.articulation);
+    if articulations.is_empty() {
+        return None;
+    }
+
+    let content = articulations
+        .into_iter()
+        .map(
+            |ArticulationMark {
+                 articulation,
+                 placement,
+             }| {
+                let empty = EmptyPlacement {
+                    placement,
+                    position: Position::default(),
+                };
+                match articulation {
+                    Articulation::Staccato => ArticulationElement::Staccato(empty),
+                    Articulation::Staccatissimo => ArticulationElement::Staccatissimo(empty),
+                    Articulation::Spiccato => ArticulationElement::Spiccato(empty),
+                    Articulation::Accent => ArticulationElement::Accent(empty),
+                    Articulation::StrongAccent => ArticulationElement::StrongAccent(StrongAccent {
+                        placement,
+                        ..StrongAccent::default()
+                    }),
+                    Articulation::Tenuto => ArticulationElement::Tenuto(empty),
+                    Articulation::DetachedLegato => ArticulationElement::DetachedLegato(empty),
+                    Articulation::BreathMark => {
+                        // BreathMark requires a value, using default
+                        ArticulationElement::BreathMark(crate::ir::notation::BreathMark {
+                            value: crate::ir::notation::BreathMarkValue::Comma,
+                            placement,
+                            position: Position::default(),
+                        })
+                    }
+                    Articulation::Caesura => {
+                        ArticulationElement::Caesura(crate::ir::notation::Caesura {
+                            value: crate::ir::notation::CaesuraValue::Normal,
+                            placement,
+                            position: Position::default(),
+                        })
+                    }
+                }
+            },
+        )
+        .collect();
+
+    Some(Articulations { content })
+}
+
+/// Position of an articulation in the MusicXML `<articulations>` choice.
+fn schema_order(articulation: Articulation) -> u8 {
+    match articulation {
+        Articulation::Accent => 0,
+        Articulation::StrongAccent => 1,
+        Articulation::Staccato => 2,
+        Articulation::Tenuto => 3,
+        Articulation::DetachedLegato => 4,
+        Articulation::Staccatissimo => 5,
+        Articulation::Spiccato => 6,
+        Articulation::BreathMark => 7,
+        Articulation::Caesura => 8,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -935,7 +1293,7 @@ mod tests {
             Sexpr::keyword("staccato"),
         ];
         let note = parse_note_form(&items).unwrap();
-        assert!(note.articulations.contains(&Articulation::Staccato));
+        assert!(note.articulations.contains(&Articulation::Staccato.into()));
     }
 
     #[test]
@@ -946,7 +1304,83 @@ mod tests {
             Sexpr::keyword("accent"),
         ];
         let note = parse_note_form(&items).unwrap();
-        assert!(note.articulations.contains(&Articulation::Accent));
+        assert!(note.articulations.contains(&Articulation::Accent.into()));
+    }
+
+    #[test]
+    fn test_parse_note_form_with_dynamic() {
+        let items = vec![
+            Sexpr::symbol("c4"),
+            Sexpr::keyword("q"),
+            Sexpr::keyword("staccato"),
+            Sexpr::keyword("breath-mark"),
+            Sexpr::keyword("mf"),
+        ];
+        let note = parse_note_form(&items).unwrap();
+        assert_eq!(
+            note.articulations,
+            vec![
+                Articulation::Staccato.into(),
+                Articulation::BreathMark.into()
+            ]
+        );
+        assert_eq!(note.dynamic, Some(DynamicMark::MF));
+    }
+
+    #[test]
+    fn test_parse_note_form_articulation_placement() {
+        let note = parse_note_form(&[
+            Sexpr::symbol("c4"),
+            Sexpr::keyword("q"),
+            Sexpr::list(vec![Sexpr::keyword("staccato"), Sexpr::keyword("below")]),
+            Sexpr::keyword("accent"),
+            Sexpr::keyword("above"),
+            Sexpr::keyword("tenuto"),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            note.articulations,
+            vec![
+                ArticulationMark {
+                    articulation: Articulation::Staccato,
+                    placement: Some(AboveBelow::Below),
+                },
+                ArticulationMark {
+                    articulation: Articulation::Accent,
+                    placement: Some(AboveBelow::Above),
+                },
+                Articulation::Tenuto.into(),
+            ]
+        );
+
+        let err = parse_note_form(&[
+            Sexpr::symbol("c4"),
+            Sexpr::list(vec![Sexpr::keyword("staccato"), Sexpr::keyword("left")]),
+        ])
+        .unwrap_err();
+        assert!(matches!(err, CompileError::Semantic(_)));
+    }
+
+    #[test]
+    fn test_parse_note_form_unknown_keyword_lists_articulations() {
+        let items = vec![
+            Sexpr::symbol("c4"),
+            Sexpr::keyword("q"),
+            Sexpr::keyword("stacato"),
+        ];
+        let err = parse_note_form(&items).unwrap_err();
+        assert!(matches!(err, CompileError::Semantic(_)));
+        let message = err.to_string();
+        assert!(message.contains(":stacato"));
+        assert!(message.contains(":staccato"));
+        assert!(message.contains(":detached-legato"));
+    }
+
+    #[test]
+    fn test_parse_note_form_rejects_hairpin_keyword() {
+        let items = vec![Sexpr::symbol("c4"), Sexpr::keyword("cresc")];
+        assert!(parse_note_form(&items).is_err());
     }
 
     #[test]
@@ -1096,6 +1530,122 @@ mod tests {
         assert!(has_articulations);
     }
 
+    #[test]
+    fn test_compile_note_articulations_in_schema_order() {
+        for source in [
+            "(note c4 :w :accent :staccato :tenuto)",
+            "(note c4 :w :tenuto :staccato :accent :staccato)",
+        ] {
+            let score =
+                crate::lang::compile(&format!("(score (part :piano (measure {})))", source))
+                    .unwrap();
+            let xml = crate::musicxml::emit(&score).unwrap();
+            assert_eq!(xml.matches("<articulations>").count(), 1);
+            assert_eq!(xml.matches("<staccato/>").count(), 1);
+            let accent = xml.find("<accent/>").unwrap();
+            let staccato = xml.find("<staccato/>").unwrap();
+            let tenuto = xml.find("<tenuto/>").unwrap();
+            assert!(accent < staccato && staccato < tenuto, "{}", xml);
+        }
+    }
+
+    #[test]
+    fn test_compile_note_accent_below_sets_placement() {
+        let score = crate::lang::compile(
+            "(score (part :piano (measure (note c4 :h :accent :below) (note d4 :h :accent))))",
+        )
+        .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        assert!(xml.contains(r#"<accent placement="below"/>"#), "{}", xml);
+        assert_eq!(xml.matches("<accent/>").count(), 1);
+    }
+
+    #[test]
+    fn test_compile_note_bend_emits_bend_alter() {
+        let score =
+            crate::lang::compile("(score (part :guitar (measure (note e4 :q (:bend 1.0)))))")
+                .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        assert!(xml.contains("<technical>"), "{}", xml);
+        assert!(xml.contains("<bend-alter>1</bend-alter>"), "{}", xml);
+        assert!(!xml.contains("<pre-bend/>"), "{}", xml);
+        assert!(!xml.contains("<release"), "{}", xml);
+    }
+
+    #[test]
+    fn test_compile_note_bend_pre_bend_and_release() {
+        let score = crate::lang::compile(
+            "(score (part :guitar (measure (note g3 :h (:bend 0.5 :pre-bend)) (note g3 :h (:bend 2 :release)))))",
+        )
+        .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        assert!(xml.contains("<bend-alter>0.5</bend-alter>"), "{}", xml);
+        assert_eq!(xml.matches("<pre-bend/>").count(), 1);
+        assert!(xml.contains("<bend-alter>2</bend-alter>"), "{}", xml);
+        assert_eq!(xml.matches("<release/>").count(), 1);
+    }
+
+    #[test]
+    fn test_parse_note_form_bend_without_amount_is_error() {
+        let result = crate::lang::compile("(score (part :guitar (measure (note e4 :q (:bend)))))");
+        assert!(matches!(result, Err(CompileError::InvalidNote(_))));
+    }
+
+    #[test]
+    fn test_compile_note_open_first_string_emits_string_and_fret() {
+        let score = crate::lang::compile(
+            "(score (part :guitar (measure (note e4 :q (:string 1) (:fret 0)))))",
+        )
+        .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        let technical = xml.find("<technical>").expect("technical element");
+        let end = xml.find("</technical>").unwrap();
+        let content = &xml[technical..end];
+        assert!(content.contains("<string>1</string>"), "{}", xml);
+        assert!(content.contains("<fret>0</fret>"), "{}", xml);
+    }
+
+    #[test]
+    fn test_parse_note_form_rejects_string_zero_and_negative_fret() {
+        for source in [
+            "(note e4 :q (:string 0))",
+            "(note e4 :q (:fret -1))",
+            "(note e4 :q (:fret))",
+        ] {
+            let result =
+                crate::lang::compile(&format!("(score (part :guitar (measure {})))", source));
+            assert!(
+                matches!(result, Err(CompileError::InvalidNote(_))),
+                "{}: {:?}",
+                source,
+                result
+            );
+        }
+    }
+
+    #[test]
+    fn test_compile_note_fraction_duration() {
+        let score =
+            crate::lang::compile("(score (part :piano (measure (note c4 3/8) (note d4 1/8))))")
+                .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        let dotted = xml.find("<type>quarter</type>").unwrap();
+        assert!(
+            xml[dotted..]
+                .trim_start_matches("<type>quarter</type>")
+                .trim_start()
+                .starts_with("<dot/>"),
+            "{}",
+            xml
+        );
+        assert!(xml.contains("<type>eighth</type>"));
+    }
+
     #[test]
     fn test_compile_note_empty_list() {
         let sexpr = Sexpr::list(vec![]);
@@ -1240,6 +1790,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_compile_rest_invisible_emits_print_object_no() {
+        let sexpr = Sexpr::list(vec![
+            Sexpr::symbol("rest"),
+            Sexpr::keyword("q"),
+            Sexpr::keyword("invisible"),
+        ]);
+        let note = compile_rest(&sexpr).unwrap();
+        assert_eq!(note.print_object, Some(YesNo::No));
+
+        let score = crate::lang::compile(
+            "(score (part :piano (measure (note c4 :h) (rest :q :invisible))))",
+        )
+        .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+        assert!(xml.contains("<note print-object=\"no\">"), "{}", xml);
+    }
+
+    #[test]
+    fn test_compile_note_invisible_sets_print_object() {
+        let visible = compile_note(&crate::sexpr::parse("(note c4 :q)").unwrap()).unwrap();
+        let hidden =
+            compile_note(&crate::sexpr::parse("(note c4 :q :invisible)").unwrap()).unwrap();
+
+        assert_eq!(visible.print_object, None);
+        assert_eq!(hidden.print_object, Some(YesNo::No));
+    }
+
     #[test]
     fn test_compile_rest_empty_list() {
         let sexpr = Sexpr::list(vec![]);
@@ -1273,10 +1851,13 @@ mod tests {
             staff: Some(1),
             stem: None,
             articulations: vec![],
+            dynamic: None,
             ornaments: vec![],
+            technical: vec![],
             tie: None,
             slur: None,
-            lyric: None,
+            lyrics: vec![],
+            invisible: false,
         };
 
         let note = compile_fermata_note(&fermata_note).unwrap();
@@ -1296,11 +1877,14 @@ mod tests {
             voice: None,
             staff: None,
             stem: None,
-            articulations: vec![Articulation::Staccato, Articulation::Accent],
+            articulations: vec![Articulation::Staccato.into(), Articulation::Accent.into()],
+            dynamic: None,
             ornaments: vec![],
+            technical: vec![],
             tie: None,
             slur: None,
-            lyric: None,
+            lyrics: vec![],
+            invisible: false,
         };
 
         let note = compile_fermata_note(&fermata_note).unwrap();
@@ -1320,10 +1904,13 @@ mod tests {
             staff: None,
             stem: None,
             articulations: vec![],
+            dynamic: None,
             ornaments: vec![Ornament::Trill],
+            technical: vec![],
             tie: None,
             slur: None,
-            lyric: None,
+            lyrics: vec![],
+            invisible: false,
         };
 
         let note = compile_fermata_note(&fermata_note).unwrap();
@@ -1346,6 +1933,7 @@ mod tests {
             voice: Some(1),
             staff: Some(1),
             measure_rest: false,
+            invisible: false,
         };
 
         let note = compile_fermata_rest(&fermata_rest).unwrap();
@@ -1366,6 +1954,7 @@ mod tests {
             voice: None,
             staff: None,
             measure_rest: true,
+            invisible: false,
         };
 
         let note = compile_fermata_rest(&fermata_rest).unwrap();
@@ -1396,10 +1985,13 @@ mod tests {
             staff: None,
             stem: None,
             articulations: vec![],
+            dynamic: None,
             ornaments: vec![],
+            technical: vec![],
             tie: None,
             slur: None,
-            lyric: None,
+            lyrics: vec![],
+            invisible: false,
         };
 
         let notations = compile_notations(&fermata_note).unwrap();
@@ -1419,10 +2011,13 @@ mod tests {
             staff: None,
             stem: None,
             articulations: vec![],
+            dynamic: None,
             ornaments: vec![],
+            technical: vec![],
             tie: Some(StartStop::Start),
             slur: None,
-            lyric: None,
+            lyrics: vec![],
+            invisible: false,
         };
 
         let notations = compile_notations(&fermata_note).unwrap();
@@ -1448,10 +2043,13 @@ mod tests {
             staff: None,
             stem: None,
             articulations: vec![],
+            dynamic: None,
             ornaments: vec![],
+            technical: vec![],
             tie: None,
             slur: Some(StartStop::Start),
-            lyric: None,
+            lyrics: vec![],
+            invisible: false,
         };
 
         let notations = compile_notations(&fermata_note).unwrap();
@@ -1476,11 +2074,14 @@ mod tests {
             voice: None,
             staff: None,
             stem: None,
-            articulations: vec![Articulation::Staccato],
+            articulations: vec![Articulation::Staccato.into()],
+            dynamic: None,
             ornaments: vec![Ornament::Trill],
+            technical: vec![],
             tie: Some(StartStop::Start),
             slur: Some(StartStop::Start),
-            lyric: None,
+            lyrics: vec![],
+            invisible: false,
         };
 
         let notations = compile_notations(&fermata_note).unwrap();
@@ -1509,4 +2110,129 @@ mod tests {
                 .any(|c| matches!(c, NotationContent::Ornaments(_)))
         );
     }
+
+    // === Harmonic tests ===
+
+    /// Compile a single-measure score, emit it as MusicXML, and parse it back.
+    fn round_trip_measure(measure: &str) -> Vec<Note> {
+        let source = format!("(score (part :violin (measure {})))", measure);
+        let score = crate::lang::compile(&source).unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+        let parsed = crate::musicxml::parse(&xml).unwrap();
+        parsed.parts[0].measures[0]
+            .content
+            .iter()
+            .filter_map(|e| match e {
+                crate::ir::measure::MusicDataElement::Note(n) => Some((**n).clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn first_harmonic(note: &Note) -> Option<Harmonic> {
+        note.notations
+            .iter()
+            .flat_map(|n| &n.content)
+            .find_map(|c| match c {
+                NotationContent::Technical(t) => t.content.iter().find_map(|e| match e {
+                    TechnicalElement::Harmonic(h) => Some(h.clone()),
+                    _ => None,
+                }),
+                _ => None,
+            })
+    }
+
+    #[test]
+    fn test_parse_note_form_with_natural_harmonic() {
+        let items = vec![
+            Sexpr::symbol("c4"),
+            Sexpr::keyword("q"),
+            Sexpr::keyword("harmonic"),
+            Sexpr::keyword("natural"),
+        ];
+        let note = parse_note_form(&items).unwrap();
+        assert_eq!(
+            note.technical,
+            vec![TechnicalMark::Harmonic(HarmonicSpec {
+                kind: HarmonicKind::Natural,
+                touching: None,
+            })]
+        );
+    }
+
+    #[test]
+    fn test_parse_note_form_with_artificial_harmonic_touching() {
+        let items = vec![
+            Sexpr::symbol("c4"),
+            Sexpr::keyword("q"),
+            Sexpr::keyword("harmonic"),
+            Sexpr::keyword("artificial"),
+            Sexpr::keyword("touching"),
+            Sexpr::symbol("f4"),
+            Sexpr::keyword("staccato"),
+        ];
+        let note = parse_note_form(&items).unwrap();
+        match &note.technical[0] {
+            TechnicalMark::Harmonic(h) => {
+                assert_eq!(h.kind, HarmonicKind::Artificial);
+                assert_eq!(h.touching.as_ref().unwrap().step, PitchStep::F);
+            }
+            other => panic!("expected a harmonic, got {:?}", other),
+        }
+        assert_eq!(note.articulations, vec![Articulation::Staccato.into()]);
+    }
+
+    #[test]
+    fn test_parse_note_form_touching_on_natural_harmonic_is_error() {
+        let items = vec![
+            Sexpr::symbol("c4"),
+            Sexpr::keyword("harmonic"),
+            Sexpr::keyword("natural"),
+            Sexpr::keyword("touching"),
+            Sexpr::symbol("f4"),
+        ];
+        assert!(parse_note_form(&items).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_natural_harmonic() {
+        let notes = round_trip_measure("(note c4 :q :harmonic :natural)");
+        assert_eq!(notes.len(), 1);
+
+        let harmonic = first_harmonic(&notes[0]).expect("harmonic notation");
+        assert!(harmonic.natural);
+        assert!(!harmonic.artificial);
+    }
+
+    #[test]
+    fn test_round_trip_artificial_harmonic_with_touching_pitch() {
+        let notes = round_trip_measure("(note c4 :q :harmonic :artificial :touching f4)");
+        assert_eq!(notes.len(), 2);
+
+        let base = first_harmonic(&notes[0]).expect("base harmonic");
+        assert!(base.artificial);
+        assert!(base.base_pitch);
+
+        let touching = &notes[1];
+        let touching_harmonic = first_harmonic(touching).expect("touching harmonic");
+        assert!(touching_harmonic.artificial);
+        assert!(touching_harmonic.touching_pitch);
+        assert_eq!(
+            touching.notehead.as_ref().map(|n| n.value),
+            Some(NoteheadValue::Diamond)
+        );
+        match &touching.content {
+            NoteContent::Regular { full_note, .. } => {
+                assert!(full_note.chord);
+                match &full_note.content {
+                    PitchRestUnpitched::Pitch(p) => {
+                        assert_eq!(p.step, IrStep::F);
+                        assert_eq!(p.octave, 4);
+                    }
+                    _ => panic!("Expected pitched touching note"),
+                }
+            }
+            _ => panic!("Expected regular note"),
+        }
+    }
 }
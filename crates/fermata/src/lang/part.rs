@@ -6,12 +6,14 @@
 
 use crate::ir::attributes::{
     Attributes, Clef, ClefSign, Key, KeyContent, Mode, Time, TimeContent, TimeSignature,
-    TraditionalKey,
+    TraditionalKey, Transpose,
 };
-use crate::ir::common::{Editorial, PrintStyle};
+use crate::ir::common::{Editorial, PrintStyle, YesNo};
 use crate::ir::measure::{Measure, MusicDataElement};
-use crate::ir::part::{Part, PartListElement, PartName, ScorePart};
-use crate::lang::ast::{FermataMeasure, FermataPart};
+use crate::ir::note::{FullNote, NoteContent, PitchRestUnpitched};
+use crate::ir::part::{Part, PartListElement, PartName, ScoreInstrument, ScorePart};
+use crate::lang::ast::{BarlineSpec, EndingAction, FermataMeasure, FermataPart, MeasureElement};
+use crate::lang::beam::auto_beam;
 use crate::lang::defaults::{DEFAULT_DIVISIONS, generate_part_id};
 use crate::lang::error::{CompileError, CompileResult};
 use crate::lang::measure::{compile_fermata_measure, parse_measure_from_sexpr};
@@ -49,7 +51,14 @@ pub fn compile_part(sexpr: &Sexpr, index: usize) -> CompileResult<CompiledPart>
 
 /// Parse a part S-expression into a FermataPart AST.
 ///
-/// Expected format: `(part :name "Name" [:id "P1"] [:abbreviation "Abbr."] content...)`
+/// Expected format: `(part :name "Name" [:id "P1"] [:abbreviation "Abbr."] [:transpose N] content...)`
+///
+/// `:transpose` gives the sounding pitch relative to the written pitch in
+/// semitones, following MusicXML's `<transpose>`: `-2` for a B-flat
+/// clarinet, which sounds a major second below written, or `-7` for a horn
+/// in F. The notes are written at concert pitch and stay there, so the score
+/// is marked `<concert-score/>`; `--transposing-parts` moves them to
+/// written pitch.
 pub fn parse_part_from_sexpr(sexpr: &Sexpr, index: usize) -> CompileResult<FermataPart> {
     let items = sexpr
         .as_list()
@@ -78,6 +87,7 @@ pub fn parse_part_from_sexpr(sexpr: &Sexpr, index: usize) -> CompileResult<Ferma
     let mut name: Option<String> = None;
     let mut id: Option<String> = None;
     let mut abbreviation: Option<String> = None;
+    let mut transpose: Option<i32> = None;
     let mut measures: Vec<FermataMeasure> = Vec::new();
     let mut measure_number = 1u32;
 
@@ -129,6 +139,18 @@ pub fn parse_part_from_sexpr(sexpr: &Sexpr, index: usize) -> CompileResult<Ferma
                     );
                     i += 2;
                 }
+                "transpose" => {
+                    if i + 1 >= items.len() {
+                        return Err(CompileError::MissingField("part transpose value"));
+                    }
+                    let semitones = items[i + 1].as_integer().ok_or_else(|| {
+                        CompileError::type_mismatch("integer", format!("{:?}", items[i + 1]))
+                    })?;
+                    transpose = Some(i32::try_from(semitones).map_err(|_| {
+                        CompileError::Semantic(format!("transpose {} is out of range", semitones))
+                    })?);
+                    i += 2;
+                }
                 // Check for instrument shortcuts (e.g., :piano)
                 _ => {
                     // Instrument shortcuts set name
@@ -149,6 +171,24 @@ pub fn parse_part_from_sexpr(sexpr: &Sexpr, index: usize) -> CompileResult<Ferma
                         i += 1;
                         continue;
                     }
+                    // A pickup is numbered 0 so the first full measure is 1
+                    if head == "pickup" {
+                        if !measures.is_empty() {
+                            return Err(CompileError::Semantic(
+                                "pickup must come before the first measure".to_string(),
+                            ));
+                        }
+                        measures.push(parse_measure_from_sexpr(&items[i], 0)?);
+                        i += 1;
+                        continue;
+                    }
+                    if head == "ending" {
+                        let ending = parse_ending_form(&list[1..], measure_number)?;
+                        measure_number += ending.len() as u32;
+                        measures.extend(ending);
+                        i += 1;
+                        continue;
+                    }
                 }
             }
             // Not a measure - skip unknown list
@@ -166,10 +206,60 @@ pub fn parse_part_from_sexpr(sexpr: &Sexpr, index: usize) -> CompileResult<Ferma
         name: part_name,
         id,
         abbreviation,
+        transpose,
         measures,
     })
 }
 
+/// Parse the arguments of an `(ending N [:discontinue] (measure ...) ...)`
+/// form into its measures, numbered from `first_number`.
+///
+/// The bracket starts on the first measure and stops on the last, or is
+/// left open with `:discontinue` for a final ending the music runs on from.
+fn parse_ending_form(args: &[Sexpr], first_number: u32) -> CompileResult<Vec<FermataMeasure>> {
+    let number = args
+        .first()
+        .ok_or(CompileError::MissingField("ending number"))?;
+    let number = u8::try_from(crate::lang::note::parse_u32(number)?).map_err(|_| {
+        CompileError::UnknownForm(format!("ending number too large: {:?}", args[0]))
+    })?;
+
+    let mut action = EndingAction::Stop;
+    let mut measures = Vec::new();
+    for arg in &args[1..] {
+        match arg.as_keyword() {
+            Some("discontinue") => action = EndingAction::Discontinue,
+            Some(other) => {
+                return Err(CompileError::UnknownForm(format!(
+                    "unknown ending option: :{}",
+                    other
+                )));
+            }
+            None => {
+                let number = first_number + measures.len() as u32;
+                measures.push(parse_measure_from_sexpr(arg, number)?);
+            }
+        }
+    }
+
+    let Some(last) = measures.last_mut() else {
+        return Err(CompileError::MissingField("ending measures"));
+    };
+    last.content
+        .push(MeasureElement::Barline(BarlineSpec::Ending {
+            number,
+            action,
+        }));
+    measures[0].content.insert(
+        0,
+        MeasureElement::Barline(BarlineSpec::Ending {
+            number,
+            action: EndingAction::Start,
+        }),
+    );
+    Ok(measures)
+}
+
 /// Convert an instrument keyword to a display name.
 fn instrument_from_keyword(kw: &str) -> String {
     match kw.to_lowercase().as_str() {
@@ -221,6 +311,19 @@ pub fn compile_fermata_part(part: &FermataPart, index: usize) -> CompileResult<C
     // Ensure the first measure has attributes (required by Verovio)
     ensure_first_measure_has_attributes(&mut ir_measures);
 
+    if let Some(chromatic) = part.transpose {
+        set_transpose(&mut ir_measures, chromatic);
+    }
+
+    // Whole-measure rests last as long as the active time signature
+    fill_measure_rest_durations(&mut ir_measures);
+
+    // Beam eighths and shorter by the active time signature's beat groups
+    beam_measures(&mut ir_measures);
+
+    // Notes name their instrument by id, which the part must declare
+    let score_instruments = declare_instruments(&ir_measures);
+
     // Build Part
     let ir_part = Part {
         id: part_id.clone(),
@@ -246,7 +349,7 @@ pub fn compile_fermata_part(part: &FermataPart, index: usize) -> CompileResult<C
         }),
         part_abbreviation_display: None,
         group: vec![],
-        score_instruments: vec![],
+        score_instruments,
         midi_devices: vec![],
         midi_instruments: vec![],
     };
@@ -257,6 +360,33 @@ pub fn compile_fermata_part(part: &FermataPart, index: usize) -> CompileResult<C
     })
 }
 
+/// A score-instrument for each distinct instrument id the notes refer to,
+/// in order of first use, named after its id.
+fn declare_instruments(measures: &[Measure]) -> Vec<ScoreInstrument> {
+    let mut instruments: Vec<ScoreInstrument> = Vec::new();
+    let ids = measures
+        .iter()
+        .flat_map(|m| &m.content)
+        .filter_map(|element| match element {
+            MusicDataElement::Note(note) => Some(&note.instrument),
+            _ => None,
+        })
+        .flatten();
+    for instrument in ids {
+        if !instruments.iter().any(|si| si.id == instrument.id) {
+            instruments.push(ScoreInstrument {
+                id: instrument.id.clone(),
+                instrument_name: instrument.id.clone(),
+                instrument_abbreviation: None,
+                instrument_sound: None,
+                solo_or_ensemble: None,
+                virtual_instrument: None,
+            });
+        }
+    }
+    instruments
+}
+
 /// Create a PartListElement from a ScorePart.
 pub fn score_part_to_list_element(score_part: ScorePart) -> PartListElement {
     PartListElement::ScorePart(score_part)
@@ -331,9 +461,117 @@ fn ensure_first_measure_has_attributes(measures: &mut [Measure]) {
     );
 }
 
+/// Record the part's transposition in the first measure's attributes.
+///
+/// The diatonic steps are the chromatic ones rounded to the nearest step,
+/// which spells every standard transposing instrument: -2 is a major
+/// second down, -9 a major sixth down.
+fn set_transpose(measures: &mut [Measure], chromatic: i32) {
+    let Some(MusicDataElement::Attributes(attributes)) = measures.first_mut().and_then(|m| {
+        m.content
+            .iter_mut()
+            .find(|e| matches!(e, MusicDataElement::Attributes(_)))
+    }) else {
+        return;
+    };
+    attributes.transpose = vec![Transpose {
+        number: None,
+        diatonic: Some((f64::from(chromatic) * 7.0 / 12.0).round() as i32),
+        chromatic,
+        octave_change: None,
+        double: None,
+    }];
+}
+
+/// Set the duration of every whole-measure rest to its measure's length.
+///
+/// The time signature carries over from earlier measures, starting from
+/// 4/4. Rests under senza misura keep their written duration.
+fn fill_measure_rest_durations(measures: &mut [Measure]) {
+    let mut length = measure_length(&TimeContent::Measured {
+        signatures: vec![TimeSignature {
+            beats: "4".to_string(),
+            beat_type: "4".to_string(),
+        }],
+    });
+
+    for measure in measures {
+        for element in &mut measure.content {
+            match element {
+                MusicDataElement::Attributes(attributes) => {
+                    if let Some(time) = attributes.times.first() {
+                        length = measure_length(&time.content);
+                    }
+                }
+                MusicDataElement::Note(note) => {
+                    if let (
+                        NoteContent::Regular {
+                            full_note:
+                                FullNote {
+                                    content: PitchRestUnpitched::Rest(rest),
+                                    ..
+                                },
+                            duration,
+                            ..
+                        },
+                        Some(length),
+                    ) = (&mut note.content, length)
+                        && rest.measure == Some(YesNo::Yes)
+                    {
+                        *duration = length;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Auto-beam every measure, carrying the time signature over from earlier
+/// measures and starting from 4/4.
+fn beam_measures(measures: &mut [Measure]) {
+    let mut time = TimeContent::Measured {
+        signatures: vec![TimeSignature {
+            beats: "4".to_string(),
+            beat_type: "4".to_string(),
+        }],
+    };
+
+    for measure in measures {
+        // Only a time change at the start of the measure applies to all of it
+        if let Some(MusicDataElement::Attributes(attributes)) = measure.content.first()
+            && let Some(change) = attributes.times.first()
+        {
+            time = change.content.clone();
+        }
+        auto_beam(measure, &time);
+    }
+}
+
+/// Length of a measure in [`DEFAULT_DIVISIONS`], or `None` without a meter.
+///
+/// Additive numerators such as "3+2" are summed.
+fn measure_length(time: &TimeContent) -> Option<u64> {
+    let TimeContent::Measured { signatures } = time else {
+        return None;
+    };
+    let mut length = 0;
+    for signature in signatures {
+        let beats: u64 = signature
+            .beats
+            .split('+')
+            .map(|b| b.trim().parse::<u64>().ok())
+            .sum::<Option<u64>>()?;
+        let beat_type: u64 = signature.beat_type.trim().parse().ok().filter(|&t| t > 0)?;
+        length += beats * 4 * DEFAULT_DIVISIONS as u64 / beat_type;
+    }
+    Some(length)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ir::common::YesNo;
     use crate::lang::ast::{FermataDuration, FermataNote, FermataPitch, MeasureElement, PitchStep};
     use crate::sexpr::parse;
 
@@ -389,6 +627,37 @@ mod tests {
         assert_eq!(part.measures[1].number, Some(2));
     }
 
+    #[test]
+    fn test_compile_pickup_numbers_full_measures_from_one() {
+        let score = crate::lang::compile(
+            "(score (part :piano
+               (pickup (time 4 4) (note g4 :8))
+               (measure (note c5 :w))
+               (measure (note d5 :w))))",
+        )
+        .unwrap();
+
+        let measures = &score.parts[0].measures;
+        let numbers: Vec<&str> = measures.iter().map(|m| m.number.as_str()).collect();
+        assert_eq!(numbers, vec!["0", "1", "2"]);
+        assert_eq!(measures[0].implicit, Some(YesNo::Yes));
+        assert_eq!(measures[1].implicit, None);
+        assert!(crate::validate::validate_score(&score).is_empty());
+
+        let xml = crate::musicxml::emit(&score).unwrap();
+        assert!(xml.contains("<measure number=\"0\" implicit=\"yes\">"));
+        assert!(xml.contains("<measure number=\"1\">"));
+    }
+
+    #[test]
+    fn test_parse_part_from_sexpr_pickup_after_measure_fails() {
+        let sexpr = parse("(part :piano (measure (note c4 :w)) (pickup (note g4 :8)))").unwrap();
+        assert!(matches!(
+            parse_part_from_sexpr(&sexpr, 0),
+            Err(CompileError::Semantic(_))
+        ));
+    }
+
     #[test]
     fn test_parse_part_from_sexpr_instrument_shortcut() {
         let sexpr = parse("(part :piano)").unwrap();
@@ -526,6 +795,7 @@ mod tests {
             name: "Violin".to_string(),
             id: None,
             abbreviation: None,
+            transpose: None,
             measures: vec![],
         };
 
@@ -542,6 +812,7 @@ mod tests {
             name: "Violin".to_string(),
             id: Some("VLN1".to_string()),
             abbreviation: None,
+            transpose: None,
             measures: vec![],
         };
 
@@ -557,6 +828,7 @@ mod tests {
             name: "Violin I".to_string(),
             id: None,
             abbreviation: Some("Vln. I".to_string()),
+            transpose: None,
             measures: vec![],
         };
 
@@ -575,9 +847,11 @@ mod tests {
             name: "Piano".to_string(),
             id: None,
             abbreviation: None,
+            transpose: None,
             measures: vec![
                 crate::lang::ast::FermataMeasure {
                     number: Some(1),
+                    implicit: false,
                     content: vec![MeasureElement::Note(FermataNote {
                         pitch: FermataPitch {
                             step: PitchStep::C,
@@ -589,14 +863,18 @@ mod tests {
                         staff: None,
                         stem: None,
                         articulations: vec![],
+                        dynamic: None,
                         ornaments: vec![],
+                        technical: vec![],
                         tie: None,
                         slur: None,
-                        lyric: None,
+                        lyrics: vec![],
+                        invisible: false,
                     })],
                 },
                 crate::lang::ast::FermataMeasure {
                     number: Some(2),
+                    implicit: false,
                     content: vec![],
                 },
             ],
@@ -809,4 +1087,95 @@ mod tests {
             .count();
         assert_eq!(attr_count, 1);
     }
+
+    #[test]
+    fn test_compile_part_whole_measure_rest_uses_time_signature() {
+        let sexpr = parse(
+            "(part :name \"Viola\"
+               (measure (time 3 4) (rest :whole-measure))
+               (measure (mrest))
+               (measure (time 7 8) (mrest)))",
+        )
+        .unwrap();
+        let compiled = compile_part(&sexpr, 0).unwrap();
+
+        let rests: Vec<(u64, Option<YesNo>)> = compiled
+            .part
+            .measures
+            .iter()
+            .flat_map(|m| &m.content)
+            .filter_map(|e| match e {
+                MusicDataElement::Note(n) => match &n.content {
+                    NoteContent::Regular {
+                        full_note:
+                            FullNote {
+                                content: PitchRestUnpitched::Rest(rest),
+                                ..
+                            },
+                        duration,
+                        ..
+                    } => Some((*duration, rest.measure)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        let quarter = DEFAULT_DIVISIONS as u64;
+        assert_eq!(
+            rests,
+            vec![
+                (3 * quarter, Some(YesNo::Yes)),
+                (3 * quarter, Some(YesNo::Yes)),
+                (7 * quarter / 2, Some(YesNo::Yes)),
+            ]
+        );
+
+        let xml = crate::musicxml::emit(
+            &crate::lang::compile("(score (part :viola (measure (time 3 4) (mrest))))").unwrap(),
+        )
+        .unwrap();
+        assert!(xml.contains(r#"<rest measure="yes"/>"#));
+        assert!(xml.contains("<duration>2880</duration>"));
+    }
+
+    #[test]
+    fn test_compile_part_repeat_with_first_ending() {
+        let score = crate::lang::compile(
+            "(score (part :piano
+               (measure (barline :repeat-start) (note c4 :w))
+               (ending 1 (measure (note d4 :w) (barline :repeat-end :times 2)))
+               (ending 2 :discontinue (measure (note e4 :w)))))",
+        )
+        .unwrap();
+        let measures = &score.parts[0].measures;
+        assert_eq!(measures.len(), 3);
+        assert_eq!(measures[2].number, "3");
+
+        let xml = crate::musicxml::emit(&score).unwrap();
+        let measure = |n: usize| {
+            let start = xml.find(&format!(r#"<measure number="{}""#, n)).unwrap();
+            let end = start + xml[start..].find("</measure>").unwrap();
+            &xml[start..end]
+        };
+
+        // Repeat start on the left edge of measure 1
+        let first = measure(1);
+        assert!(first.contains(r#"<barline location="left">"#));
+        assert!(first.contains(r#"<repeat direction="forward"/>"#));
+
+        // The first ending opens and closes around measure 2, which ends
+        // with the repeat on its right barline
+        let second = measure(2);
+        let left = second.find(r#"<barline location="left">"#).unwrap();
+        assert!(left < second.find("<note>").unwrap());
+        assert!(second.contains(r#"<ending number="1" type="start"/>"#));
+        let right = second.find(r#"<barline location="right">"#).unwrap();
+        assert!(right > second.find("</note>").unwrap());
+        assert!(second[right..].contains(r#"<ending number="1" type="stop"/>"#));
+        assert!(second[right..].contains(r#"<repeat direction="backward" times="2"/>"#));
+
+        let third = measure(3);
+        assert!(third.contains(r#"<ending number="2" type="start"/>"#));
+        assert!(third.contains(r#"<ending number="2" type="discontinue"/>"#));
+    }
 }
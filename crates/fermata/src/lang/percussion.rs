@@ -0,0 +1,237 @@
+//! Unpitched (percussion) note compilation for Fermata syntax.
+//!
+//! Percussion notes have no pitch; their display step and octave only place
+//! them on the staff. They are written either with an explicit position,
+//! `(unpitched e5 :q)`, or by drum-kit name, `(hit snare :q)`.
+
+use crate::ir::common::Position;
+use crate::ir::note::{FullNote, Instrument, Note, NoteContent, PitchRestUnpitched};
+use crate::ir::pitch::Unpitched;
+use crate::lang::ast::{FermataDuration, FermataPitch, FermataUnpitched, PitchStep};
+use crate::lang::defaults::DEFAULT_DIVISIONS;
+use crate::lang::duration::{compile_dots, compile_duration_divisions_with, compile_duration_type};
+use crate::lang::error::{CompileError, CompileResult};
+use crate::lang::note::{is_duration_keyword, parse_u32};
+use crate::lang::pitch::{compile_pitch, parse_pitch_str};
+use crate::sexpr::Sexpr;
+
+/// Staff positions for common drum-kit instruments on a five-line staff.
+const DRUM_KIT: &[(&str, PitchStep, u8)] = &[
+    ("kick", PitchStep::F, 4),
+    ("bass-drum", PitchStep::F, 4),
+    ("floor-tom", PitchStep::A, 4),
+    ("snare", PitchStep::C, 5),
+    ("side-stick", PitchStep::C, 5),
+    ("mid-tom", PitchStep::D, 5),
+    ("high-tom", PitchStep::E, 5),
+    ("ride", PitchStep::F, 5),
+    ("hi-hat", PitchStep::G, 5),
+    ("crash", PitchStep::A, 5),
+    ("pedal-hi-hat", PitchStep::D, 4),
+];
+
+/// Parse `(unpitched position duration [keywords...])` arguments.
+///
+/// The position is a pitch without an accidental (e.g. `e5`).
+pub fn parse_unpitched_form(items: &[Sexpr]) -> CompileResult<FermataUnpitched> {
+    let position = items.first().and_then(|i| i.as_symbol()).ok_or_else(|| {
+        CompileError::InvalidNote("unpitched note requires a staff position".to_string())
+    })?;
+    let display = parse_pitch_str(position)?;
+    if display.alter.is_some() {
+        return Err(CompileError::InvalidNote(format!(
+            "unpitched position '{}' cannot have an accidental",
+            position
+        )));
+    }
+    parse_unpitched_args(display, &items[1..])
+}
+
+/// Parse `(hit instrument duration [keywords...])` arguments.
+///
+/// The instrument is a drum-kit name such as `snare`, `kick`, or `hi-hat`.
+pub fn parse_hit_form(items: &[Sexpr]) -> CompileResult<FermataUnpitched> {
+    let name = items
+        .first()
+        .and_then(|i| i.as_symbol())
+        .ok_or_else(|| CompileError::InvalidNote("hit requires an instrument name".to_string()))?;
+    let (_, step, octave) = DRUM_KIT
+        .iter()
+        .find(|(kit_name, _, _)| name.eq_ignore_ascii_case(kit_name))
+        .ok_or_else(|| {
+            let names: Vec<&str> = DRUM_KIT.iter().map(|(n, _, _)| *n).collect();
+            CompileError::InvalidNote(format!(
+                "unknown drum '{}', expected one of: {}",
+                name,
+                names.join(", ")
+            ))
+        })?;
+    let display = FermataPitch {
+        step: *step,
+        alter: None,
+        octave: *octave,
+    };
+    parse_unpitched_args(display, &items[1..])
+}
+
+/// Parse the duration and `:instrument`, `:voice`, `:staff` keywords.
+///
+/// An `:instrument` id is declared as a score-instrument of the part the
+/// note is compiled in.
+fn parse_unpitched_args(display: FermataPitch, items: &[Sexpr]) -> CompileResult<FermataUnpitched> {
+    let (duration, start) = match items.first().and_then(|i| i.as_keyword()) {
+        Some(dur_str) if is_duration_keyword(dur_str) => {
+            (crate::lang::duration::parse_duration(dur_str)?, 1)
+        }
+        _ => (FermataDuration::default(), 0),
+    };
+
+    let mut unpitched = FermataUnpitched {
+        display,
+        duration,
+        instrument: None,
+        voice: None,
+        staff: None,
+    };
+
+    let mut i = start;
+    while i < items.len() {
+        let kw = items[i].as_keyword().ok_or_else(|| {
+            CompileError::InvalidNote(format!("expected keyword, got {:?}", items[i]))
+        })?;
+        let value = items
+            .get(i + 1)
+            .ok_or_else(|| CompileError::InvalidNote(format!("missing :{} value", kw)))?;
+        match kw {
+            "instrument" => {
+                let id = value
+                    .as_string()
+                    .or_else(|| value.as_symbol())
+                    .ok_or_else(|| {
+                        CompileError::type_mismatch("instrument id", format!("{:?}", value))
+                    })?;
+                unpitched.instrument = Some(id.to_string());
+            }
+            "voice" => unpitched.voice = Some(parse_u32(value)?),
+            "staff" => unpitched.staff = Some(parse_u32(value)?),
+            _ => {
+                return Err(CompileError::InvalidNote(format!(
+                    "unknown unpitched keyword ':{}'",
+                    kw
+                )));
+            }
+        }
+        i += 2;
+    }
+
+    Ok(unpitched)
+}
+
+/// Compile a FermataUnpitched to an IR Note with Unpitched content.
+pub fn compile_fermata_unpitched(unpitched: &FermataUnpitched) -> CompileResult<Note> {
+    let display = compile_pitch(&unpitched.display)?;
+
+    Ok(Note {
+        position: Position::default(),
+        dynamics: None,
+        end_dynamics: None,
+        attack: None,
+        release: None,
+        pizzicato: None,
+        print_object: None,
+        content: NoteContent::Regular {
+            full_note: FullNote {
+                chord: false,
+                content: PitchRestUnpitched::Unpitched(Unpitched {
+                    display_step: Some(display.step),
+                    display_octave: Some(display.octave),
+                }),
+            },
+            duration: compile_duration_divisions_with(
+                &unpitched.duration,
+                DEFAULT_DIVISIONS as u64,
+            ),
+            ties: vec![],
+        },
+        instrument: unpitched
+            .instrument
+            .iter()
+            .map(|id| Instrument { id: id.clone() })
+            .collect(),
+        voice: unpitched.voice.map(|v| v.to_string()),
+        r#type: Some(compile_duration_type(&unpitched.duration.base)),
+        dots: compile_dots(unpitched.duration.dots),
+        accidental: None,
+        time_modification: None,
+        stem: None,
+        notehead: None,
+        staff: unpitched.staff.map(|s| s as u16),
+        beams: vec![],
+        notations: vec![],
+        lyrics: vec![],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::pitch::Step;
+
+    fn args(source: &str) -> Vec<Sexpr> {
+        crate::sexpr::parse(source).unwrap().as_list().unwrap()[1..].to_vec()
+    }
+
+    #[test]
+    fn test_parse_hit_form_snare() {
+        let hit = parse_hit_form(&args("(hit snare :8 :instrument \"P1-I39\")")).unwrap();
+        assert_eq!(hit.display.step, PitchStep::C);
+        assert_eq!(hit.display.octave, 5);
+        assert_eq!(hit.instrument.as_deref(), Some("P1-I39"));
+    }
+
+    #[test]
+    fn test_parse_hit_form_unknown_drum_lists_kit() {
+        let err = parse_hit_form(&args("(hit cowbell :q)")).unwrap_err();
+        assert!(err.to_string().contains("snare"));
+    }
+
+    #[test]
+    fn test_parse_unpitched_form_rejects_accidental() {
+        assert!(parse_unpitched_form(&args("(unpitched f#5 :q)")).is_err());
+    }
+
+    #[test]
+    fn test_compile_fermata_unpitched_sets_display_position() {
+        let unpitched = parse_unpitched_form(&args("(unpitched e5 :q :voice 1)")).unwrap();
+        let note = compile_fermata_unpitched(&unpitched).unwrap();
+        let NoteContent::Regular { full_note, .. } = &note.content else {
+            panic!("Expected regular note");
+        };
+        assert_eq!(
+            full_note.content,
+            PitchRestUnpitched::Unpitched(Unpitched {
+                display_step: Some(Step::E),
+                display_octave: Some(5),
+            })
+        );
+        assert_eq!(note.voice.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn test_snare_hit_emits_unpitched() {
+        let score = crate::lang::compile(
+            "(score (part :drums (measure (clef :percussion) (hit snare :q :instrument \"P1-I39\") (hit snare :q :instrument \"P1-I39\"))))",
+        )
+        .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        assert!(xml.contains("<unpitched>"));
+        assert!(xml.contains("<display-step>C</display-step>"));
+        assert!(xml.contains("<display-octave>5</display-octave>"));
+        assert!(xml.contains(r#"<instrument id="P1-I39"/>"#));
+        assert!(!xml.contains("<pitch>"));
+        // The reference is declared, so Fermata's own output reads back
+        assert_eq!(xml.matches(r#"<score-instrument id="P1-I39">"#).count(), 1);
+        assert!(crate::musicxml::parse(&xml).is_ok());
+    }
+}
@@ -3,6 +3,9 @@
 //! This module provides functions for parsing pitch strings (e.g., "c4", "f#5", "bb3")
 //! and compiling them to the IR representation.
 
+use std::fmt;
+use std::str::FromStr;
+
 use crate::ir::common::Semitones;
 use crate::ir::pitch::{Pitch as IrPitch, Step as IrStep};
 use crate::lang::ast::{FermataPitch, PitchAlter, PitchStep};
@@ -74,6 +77,13 @@ pub fn parse_pitch_str(s: &str) -> CompileResult<FermataPitch> {
     let alter_str = &remaining[..octave_pos];
     let octave_str = &remaining[octave_pos..];
 
+    if alter_str.ends_with('-') {
+        return Err(CompileError::InvalidPitch(format!(
+            "negative octave in pitch '{}' (octaves range 0-9)",
+            s
+        )));
+    }
+
     // Parse alteration
     let alter = if alter_str.is_empty() {
         None
@@ -101,6 +111,47 @@ pub fn parse_pitch_str(s: &str) -> CompileResult<FermataPitch> {
     })
 }
 
+impl FromStr for FermataPitch {
+    type Err = CompileError;
+
+    /// Parse scientific pitch notation such as `C#4`, `Bb3`, or `Dx5`.
+    ///
+    /// Accepts everything [`parse_pitch_str`] does, in either letter case.
+    fn from_str(s: &str) -> CompileResult<Self> {
+        parse_pitch_str(s)
+    }
+}
+
+impl fmt::Display for FermataPitch {
+    /// Format in scientific pitch notation: an uppercase letter, the
+    /// accidental in DSL spelling (`#`, `b`, `x`, `bb`, `n`, `+`, `d`, ...),
+    /// then the octave. A pitch without an alteration has no suffix.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let step = match self.step {
+            PitchStep::C => 'C',
+            PitchStep::D => 'D',
+            PitchStep::E => 'E',
+            PitchStep::F => 'F',
+            PitchStep::G => 'G',
+            PitchStep::A => 'A',
+            PitchStep::B => 'B',
+        };
+        let alter = match self.alter {
+            None => "",
+            Some(PitchAlter::Sharp) => "#",
+            Some(PitchAlter::Flat) => "b",
+            Some(PitchAlter::DoubleSharp) => "x",
+            Some(PitchAlter::DoubleFlat) => "bb",
+            Some(PitchAlter::Natural) => "n",
+            Some(PitchAlter::QuarterSharp) => "+",
+            Some(PitchAlter::QuarterFlat) => "d",
+            Some(PitchAlter::ThreeQuarterSharp) => "#+",
+            Some(PitchAlter::ThreeQuarterFlat) => "db",
+        };
+        write!(f, "{}{}{}", step, alter, self.octave)
+    }
+}
+
 /// Parse a single character to a PitchStep.
 pub fn parse_step(c: char) -> CompileResult<PitchStep> {
     match c.to_ascii_lowercase() {
@@ -150,6 +201,53 @@ pub fn compile_pitch(pitch: &FermataPitch) -> CompileResult<IrPitch> {
     })
 }
 
+impl TryFrom<&IrPitch> for FermataPitch {
+    type Error = CompileError;
+
+    /// Recover a Fermata pitch from an IR pitch.
+    ///
+    /// An alter of `0` becomes an explicit natural. Alterations other than
+    /// whole, quarter, and three-quarter tones are rejected.
+    fn try_from(pitch: &IrPitch) -> CompileResult<Self> {
+        let step = match pitch.step {
+            IrStep::C => PitchStep::C,
+            IrStep::D => PitchStep::D,
+            IrStep::E => PitchStep::E,
+            IrStep::F => PitchStep::F,
+            IrStep::G => PitchStep::G,
+            IrStep::A => PitchStep::A,
+            IrStep::B => PitchStep::B,
+        };
+        let alter = match pitch.alter {
+            None => None,
+            Some(semitones) => Some(
+                [
+                    PitchAlter::DoubleFlat,
+                    PitchAlter::ThreeQuarterFlat,
+                    PitchAlter::Flat,
+                    PitchAlter::QuarterFlat,
+                    PitchAlter::Natural,
+                    PitchAlter::QuarterSharp,
+                    PitchAlter::Sharp,
+                    PitchAlter::ThreeQuarterSharp,
+                    PitchAlter::DoubleSharp,
+                ]
+                .into_iter()
+                .find(|alter| alter.to_semitones() == semitones)
+                .ok_or_else(|| {
+                    CompileError::InvalidPitch(format!("no alteration for {} semitones", semitones))
+                })?,
+            ),
+        };
+
+        Ok(FermataPitch {
+            step,
+            alter,
+            octave: pitch.octave,
+        })
+    }
+}
+
 /// Compile a PitchStep to an IR Step.
 fn compile_step(step: &PitchStep) -> IrStep {
     match step {
@@ -173,7 +271,7 @@ fn compile_step(step: &PitchStep) -> IrStep {
 pub fn parse_pitch_sexpr(sexpr: &Sexpr) -> CompileResult<FermataPitch> {
     match sexpr {
         // Shorthand: symbol like "c4" or "f#5"
-        Sexpr::Symbol(s) => parse_pitch_str(s),
+        Sexpr::Symbol(s) => s.parse(),
 
         // Full form: (pitch :step C :octave 4 [:alter N])
         Sexpr::List(items) => {
@@ -798,4 +896,56 @@ mod tests {
             assert_eq!(ir_pitch.step, ir_step);
         }
     }
+
+    // === Display / FromStr tests ===
+
+    #[test]
+    fn test_pitch_display_scientific_notation() {
+        let pitch = |step, alter, octave| FermataPitch {
+            step,
+            alter,
+            octave,
+        };
+        assert_eq!(pitch(PitchStep::C, None, 4).to_string(), "C4");
+        assert_eq!(
+            pitch(PitchStep::C, Some(PitchAlter::Sharp), 4).to_string(),
+            "C#4"
+        );
+        assert_eq!(
+            pitch(PitchStep::B, Some(PitchAlter::Flat), 3).to_string(),
+            "Bb3"
+        );
+        assert_eq!(
+            pitch(PitchStep::D, Some(PitchAlter::DoubleSharp), 5).to_string(),
+            "Dx5"
+        );
+        assert_eq!(
+            pitch(PitchStep::E, Some(PitchAlter::DoubleFlat), 2).to_string(),
+            "Ebb2"
+        );
+    }
+
+    #[test]
+    fn test_pitch_from_str_round_trip() {
+        for text in [
+            "C4", "A0", "B9", "C#4", "Bb3", "Dx5", "Ebb2", "Fn4", "G+4", "Ad4", "C#+4", "Bdb3",
+        ] {
+            let pitch: FermataPitch = text.parse().unwrap();
+            assert_eq!(pitch.to_string(), text);
+        }
+        assert_eq!(
+            "f#5".parse::<FermataPitch>().unwrap(),
+            "F#5".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_pitch_from_str_rejects_garbage_and_negative_octaves() {
+        for text in ["", "H4", "C", "C#", "C?4", "C10"] {
+            assert!(text.parse::<FermataPitch>().is_err(), "{}", text);
+        }
+        // Octaves are 0-9 as in MusicXML, so negative octaves are an error
+        let err = "C-1".parse::<FermataPitch>().unwrap_err();
+        assert!(err.to_string().contains("negative octave"));
+    }
 }
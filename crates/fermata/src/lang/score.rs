@@ -3,12 +3,25 @@
 //! This module handles compiling score S-expressions into IR ScorePartwise types.
 //! It assembles parts, generates the part-list, and handles score metadata.
 
-use crate::ir::common::{Identification, LeftCenterRight, PrintStyle, TopMiddleBottom, TypedText};
-use crate::ir::part::PartList;
-use crate::ir::score::{Credit, CreditContent, CreditWords, ScorePartwise, Work};
-use crate::lang::ast::FermataScore;
+use std::collections::HashMap;
+
+use crate::ir::attributes::{Attributes, BarStyle, Barline};
+use crate::ir::common::{
+    Editorial, Identification, LeftCenterRight, PrintStyle, RightLeftMiddle, TopMiddleBottom,
+    TypedText,
+};
+use crate::ir::duration::divisions_for;
+use crate::ir::measure::{Measure, MusicDataElement, renumber_measures};
+use crate::ir::note::NoteContent;
+use crate::ir::part::{Part, PartList, PartListElement};
+use crate::ir::score::{Credit, CreditContent, CreditWords, Defaults, ScorePartwise, Work};
+use crate::lang::ast::{FermataMeasure, FermataScore, TempoMark};
+use crate::lang::defaults::DEFAULT_DIVISIONS;
+use crate::lang::direction::{compile_tempo_mark, parse_tempo_form};
+use crate::lang::duration::compute_measure_divisions;
 use crate::lang::error::{CompileError, CompileResult};
 use crate::lang::part::{compile_fermata_part, parse_part_from_sexpr, score_part_to_list_element};
+use crate::musicxml::MUSICXML_VERSION;
 use crate::sexpr::Sexpr;
 
 /// Compile a score S-expression into an IR ScorePartwise.
@@ -29,7 +42,10 @@ pub fn compile_score(sexpr: &Sexpr) -> CompileResult<ScorePartwise> {
 
 /// Parse a score S-expression into a FermataScore AST.
 ///
-/// Expected format: `(score [:title "Title"] [:composer "Composer"] parts...)`
+/// Expected format: `(score [:title "Title"] [:composer "Composer"] [:tempo (tempo ...)] parts...)`.
+/// The title, composer, and opening tempo may also be written as forms
+/// among the parts, e.g. `(score (title "Sonata") (composer "Bach")
+/// (tempo :q 120) (part ...))`.
 pub fn parse_score_from_sexpr(sexpr: &Sexpr) -> CompileResult<FermataScore> {
     let items = sexpr.as_list().ok_or_else(|| {
         CompileError::UnknownForm(format!("expected score list, got {:?}", sexpr))
@@ -57,6 +73,7 @@ pub fn parse_score_from_sexpr(sexpr: &Sexpr) -> CompileResult<FermataScore> {
     // Parse score attributes and content
     let mut title: Option<String> = None;
     let mut composer: Option<String> = None;
+    let mut tempo: Option<TempoMark> = None;
     let mut parts = Vec::new();
     let mut part_index = 0usize;
 
@@ -93,13 +110,29 @@ pub fn parse_score_from_sexpr(sexpr: &Sexpr) -> CompileResult<FermataScore> {
                     );
                     i += 2;
                 }
+                "tempo" => {
+                    if i + 1 >= items.len() {
+                        return Err(CompileError::MissingField("score tempo value"));
+                    }
+                    let form = items[i + 1]
+                        .as_list()
+                        .filter(|l| l.first().and_then(Sexpr::as_symbol) == Some("tempo"))
+                        .ok_or_else(|| {
+                            CompileError::type_mismatch(
+                                "(tempo ...)",
+                                format!("{:?}", items[i + 1]),
+                            )
+                        })?;
+                    tempo = Some(parse_tempo_form(&form[1..])?);
+                    i += 2;
+                }
                 _ => {
                     // Unknown keyword - skip
                     i += 1;
                 }
             }
         } else if let Some(list) = items[i].as_list() {
-            // Check if it's a part
+            // Check if it's a part or score metadata
             if !list.is_empty() {
                 if let Some(head) = list[0].as_symbol() {
                     if head == "part" {
@@ -109,9 +142,17 @@ pub fn parse_score_from_sexpr(sexpr: &Sexpr) -> CompileResult<FermataScore> {
                         i += 1;
                         continue;
                     }
+                    match head {
+                        "title" => title = Some(parse_metadata_text(list, "score title value")?),
+                        "composer" => {
+                            composer = Some(parse_metadata_text(list, "score composer value")?)
+                        }
+                        "tempo" => tempo = Some(parse_tempo_form(&list[1..])?),
+                        // Unknown list - skip
+                        _ => {}
+                    }
                 }
             }
-            // Not a part - skip unknown list
             i += 1;
         } else {
             // Skip unknown items
@@ -122,10 +163,20 @@ pub fn parse_score_from_sexpr(sexpr: &Sexpr) -> CompileResult<FermataScore> {
     Ok(FermataScore {
         title,
         composer,
+        tempo,
         parts,
     })
 }
 
+/// Read the text of a `(title "...")` or `(composer "...")` form.
+fn parse_metadata_text(list: &[Sexpr], field: &'static str) -> CompileResult<String> {
+    let value = list.get(1).ok_or(CompileError::MissingField(field))?;
+    let text = value
+        .as_string()
+        .ok_or_else(|| CompileError::type_mismatch("string", format!("{:?}", value)))?;
+    Ok(text.to_string())
+}
+
 /// Compile a FermataScore AST to an IR ScorePartwise.
 pub fn compile_fermata_score(score: &FermataScore) -> CompileResult<ScorePartwise> {
     // Compile all parts
@@ -138,6 +189,28 @@ pub fn compile_fermata_score(score: &FermataScore) -> CompileResult<ScorePartwis
         ir_parts.push(compiled.part);
     }
 
+    // The opening tempo sits above the top part, after its first attributes
+    if let (Some(mark), Some(measure)) = (
+        &score.tempo,
+        ir_parts.first_mut().and_then(|p| p.measures.first_mut()),
+    ) {
+        let direction = compile_tempo_mark(mark)?;
+        let at = measure
+            .content
+            .iter()
+            .take_while(|e| matches!(e, MusicDataElement::Attributes(_)))
+            .count();
+        measure
+            .content
+            .insert(at, MusicDataElement::Direction(Box::new(direction)));
+    }
+
+    // Raise divisions in the measures whose durations can't be expressed at
+    // the default
+    for (part, fermata_part) in ir_parts.iter_mut().zip(&score.parts) {
+        assign_divisions(part, &fermata_part.measures);
+    }
+
     // Build Work if we have a title
     let work = score.title.as_ref().map(|t| Work {
         work_number: None,
@@ -196,12 +269,12 @@ pub fn compile_fermata_score(score: &FermataScore) -> CompileResult<ScorePartwis
     }
 
     Ok(ScorePartwise {
-        version: Some("4.0".to_string()),
+        version: Some(MUSICXML_VERSION.to_string()),
         work,
         movement_number: None,
         movement_title: None,
         identification,
-        defaults: None,
+        defaults: concert_score_defaults(score),
         credits,
         part_list: PartList {
             content: part_list_elements,
@@ -210,6 +283,387 @@ pub fn compile_fermata_score(score: &FermataScore) -> CompileResult<ScorePartwis
     })
 }
 
+/// Mark the score as written at concert pitch when any part transposes.
+///
+/// Parts are compiled as written, at concert pitch, so a part's
+/// `<transpose>` would otherwise tell readers to play the notes at another
+/// pitch. [`to_transposing_parts`](crate::transform::to_transposing_parts)
+/// clears the mark when it writes the parts at written pitch.
+fn concert_score_defaults(score: &FermataScore) -> Option<Defaults> {
+    score
+        .parts
+        .iter()
+        .any(|part| part.transpose.is_some())
+        .then(|| Defaults {
+            concert_score: true,
+            ..Defaults::default()
+        })
+}
+
+/// Give each measure of a part compiled at [`DEFAULT_DIVISIONS`] a
+/// divisions value that expresses its durations exactly.
+///
+/// The divisions change only in a measure the current value can't
+/// express, such as the first measure of a septuplet passage, and the
+/// finer value then carries on for as long as it still works. This keeps
+/// a mostly plain piece at the default rather than raising every measure
+/// to the finest resolution any one of them needs. A change is written to
+/// the measure's attributes, which are added if the measure has none.
+fn assign_divisions(part: &mut Part, measures: &[FermataMeasure]) {
+    let mut current = DEFAULT_DIVISIONS;
+    for (measure, source) in part.measures.iter_mut().zip(measures) {
+        let needed = compute_measure_divisions(source);
+        if current % needed != 0 {
+            current = needed;
+            let existing = measure.content.iter_mut().find_map(|e| match e {
+                MusicDataElement::Attributes(a) => Some(a),
+                _ => None,
+            });
+            match existing {
+                Some(attributes) => attributes.divisions = Some(current as u64),
+                None => {
+                    let attributes = Attributes {
+                        divisions: Some(current as u64),
+                        ..Default::default()
+                    };
+                    measure
+                        .content
+                        .insert(0, MusicDataElement::Attributes(Box::new(attributes)));
+                }
+            }
+        }
+        if current != DEFAULT_DIVISIONS {
+            rescale_divisions(measure, current);
+        }
+    }
+}
+
+/// Rewrite a measure compiled at [`DEFAULT_DIVISIONS`] to use `divisions`.
+///
+/// `divisions` must be a multiple of the default. Notes with a type are
+/// recomputed exactly from their type, dots, and time modification; other
+/// durations are scaled. A backup that returns to an earlier note boundary
+/// (such as the start of a voice) is recomputed to land there exactly.
+fn rescale_divisions(measure: &mut Measure, divisions: u32) {
+    let factor = (divisions / DEFAULT_DIVISIONS) as u64;
+    // Note boundaries seen so far: old position -> new position
+    let mut boundaries = HashMap::from([(0u64, 0u64)]);
+    let (mut old_position, mut new_position) = (0u64, 0u64);
+
+    for element in &mut measure.content {
+        match element {
+            MusicDataElement::Attributes(attributes) if attributes.divisions.is_some() => {
+                attributes.divisions = Some(divisions as u64);
+            }
+            MusicDataElement::Note(note) => {
+                if let NoteContent::Regular {
+                    full_note,
+                    duration,
+                    ..
+                }
+                | NoteContent::Cue {
+                    full_note,
+                    duration,
+                } = &mut note.content
+                {
+                    let old = *duration;
+                    *duration = match &note.r#type {
+                        Some(note_type) => divisions_for(
+                            note_type.value,
+                            note.dots.len() as u32,
+                            note.time_modification.as_ref(),
+                            divisions,
+                        ),
+                        None => old * factor,
+                    };
+                    if !full_note.chord {
+                        old_position += old;
+                        new_position += *duration;
+                        boundaries.insert(old_position, new_position);
+                    }
+                }
+            }
+            MusicDataElement::Backup(backup) => {
+                let old_target = old_position.saturating_sub(backup.duration);
+                backup.duration = match boundaries.get(&old_target) {
+                    Some(&target) => new_position.saturating_sub(target),
+                    None => backup.duration * factor,
+                };
+                old_position = old_target;
+                new_position = new_position.saturating_sub(backup.duration);
+            }
+            MusicDataElement::Forward(forward) => {
+                old_position += forward.duration;
+                forward.duration *= factor;
+                new_position += forward.duration;
+                boundaries.insert(old_position, new_position);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Concatenate separately compiled movements into one score.
+///
+/// Every score must have the same part-list (the same part ids in the same
+/// order). Metadata comes from the first score; the measures of each later
+/// score are appended to the matching parts, all measures but implicit ones
+/// such as a pickup are renumbered from 1, and the last measure of every movement but the final one ends
+/// with a double barline.
+///
+/// # Errors
+///
+/// Returns [`CompileError::Semantic`] if `scores` is empty or the
+/// part-lists differ.
+pub fn concat_movements(scores: Vec<ScorePartwise>) -> CompileResult<ScorePartwise> {
+    let mut scores = scores.into_iter();
+    let mut result = scores
+        .next()
+        .ok_or_else(|| CompileError::Semantic("no movements to concatenate".to_string()))?;
+    let expected = part_ids(&result);
+
+    for (index, score) in scores.enumerate() {
+        let found = part_ids(&score);
+        if found != expected {
+            return Err(CompileError::Semantic(format!(
+                "movement {} has parts {:?}, expected {:?}",
+                index + 2,
+                found,
+                expected
+            )));
+        }
+        for (part, next) in result.parts.iter_mut().zip(score.parts) {
+            if next.id != part.id {
+                return Err(CompileError::Semantic(format!(
+                    "movement {} has part '{}' where '{}' was expected",
+                    index + 2,
+                    next.id,
+                    part.id
+                )));
+            }
+            if let Some(seam) = part.measures.last_mut() {
+                set_double_barline(seam);
+            }
+            part.measures.extend(next.measures);
+        }
+    }
+
+    for part in &mut result.parts {
+        renumber_measures(&mut part.measures);
+    }
+    Ok(result)
+}
+
+/// Part ids from a score's part-list, in order.
+fn part_ids(score: &ScorePartwise) -> Vec<String> {
+    score
+        .part_list
+        .content
+        .iter()
+        .filter_map(|element| match element {
+            PartListElement::ScorePart(score_part) => Some(score_part.id.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// End a measure with a double barline, keeping any repeat already there.
+fn set_double_barline(measure: &mut Measure) {
+    let right = measure
+        .content
+        .iter_mut()
+        .find_map(|element| match element {
+            MusicDataElement::Barline(barline)
+                if barline.location == Some(RightLeftMiddle::Right) =>
+            {
+                Some(barline)
+            }
+            _ => None,
+        });
+
+    match right {
+        Some(barline) if barline.repeat.is_some() => {}
+        Some(barline) => barline.bar_style = Some(BarStyle::LightLight),
+        None => measure
+            .content
+            .push(MusicDataElement::Barline(Box::new(Barline {
+                location: Some(RightLeftMiddle::Right),
+                bar_style: Some(BarStyle::LightLight),
+                editorial: Editorial::default(),
+                wavy_line: None,
+                segno: None,
+                coda: None,
+                fermatas: vec![],
+                ending: None,
+                repeat: None,
+            }))),
+    }
+}
+
+/// Keep only the part whose name or id is `name`, dropping every other
+/// part and all part groups.
+///
+/// Names are matched without regard to case; ids exactly. The kept part
+/// is unchanged, so all of its staves stay.
+///
+/// # Errors
+///
+/// Returns [`CompileError::Semantic`], listing the available parts, if no
+/// part matches.
+pub fn select_part(mut score: ScorePartwise, name: &str) -> CompileResult<ScorePartwise> {
+    let score_parts = || {
+        score
+            .part_list
+            .content
+            .iter()
+            .filter_map(|element| match element {
+                PartListElement::ScorePart(part) => Some(part),
+                PartListElement::PartGroup(_) => None,
+            })
+    };
+    let Some(id) = score_parts()
+        .find(|part| part.id == name || part.part_name.value.eq_ignore_ascii_case(name))
+        .map(|part| part.id.clone())
+    else {
+        let available: Vec<String> = score_parts()
+            .map(|part| format!("{} ({})", part.part_name.value, part.id))
+            .collect();
+        return Err(CompileError::Semantic(format!(
+            "no part named '{}'; available parts: {}",
+            name,
+            available.join(", ")
+        )));
+    };
+
+    score
+        .part_list
+        .content
+        .retain(|element| matches!(element, PartListElement::ScorePart(part) if part.id == id));
+    score.parts.retain(|part| part.id == id);
+    Ok(score)
+}
+
+/// Keep only the measures numbered `first..=last` of every part.
+///
+/// Measures are chosen by their number, not their position, so with a
+/// pickup numbered 0 the range 1-4 is the first four full measures and 0
+/// selects the pickup. A number that is not a plain integer, such as `12a`,
+/// counts as the integer it starts with, or else as the number of the
+/// measure before it. A `last` of `None` keeps everything from `first` to
+/// the end. The divisions, keys, times, clefs, staves, and transpositions
+/// in effect at `first` are gathered from the measures before it and
+/// written into an `<attributes>` at the start of the new first measure,
+/// merged with any attributes that measure already opens with. Measure
+/// numbers are kept.
+///
+/// # Errors
+///
+/// Returns [`CompileError::Semantic`] if the range is empty or a part has
+/// no measures numbered within it.
+pub fn select_measures(
+    mut score: ScorePartwise,
+    first: usize,
+    last: Option<usize>,
+) -> CompileResult<ScorePartwise> {
+    let range = || {
+        format!(
+            "{}-{}",
+            first,
+            last.map(|last| last.to_string()).unwrap_or_default()
+        )
+    };
+    if last.is_some_and(|last| last < first) {
+        return Err(CompileError::Semantic(format!(
+            "invalid measure range {}",
+            range()
+        )));
+    }
+    let in_range =
+        |number: &Option<usize>| number.is_some_and(|n| n >= first && last.is_none_or(|l| n <= l));
+
+    for part in &mut score.parts {
+        let mut current = None;
+        let numbers: Vec<Option<usize>> = part
+            .measures
+            .iter()
+            .map(|measure| {
+                let digits: String = measure
+                    .number
+                    .chars()
+                    .take_while(char::is_ascii_digit)
+                    .collect();
+                if let Ok(number) = digits.parse() {
+                    current = Some(number);
+                }
+                current
+            })
+            .collect();
+        let (Some(start), Some(end)) = (
+            numbers.iter().position(in_range),
+            numbers.iter().rposition(in_range),
+        ) else {
+            return Err(CompileError::Semantic(format!(
+                "part '{}' has no measures numbered {}",
+                part.id,
+                range()
+            )));
+        };
+
+        let mut state = Attributes::default();
+        for measure in &part.measures[..start] {
+            for element in &measure.content {
+                if let MusicDataElement::Attributes(attributes) = element {
+                    merge_attributes(&mut state, attributes);
+                }
+            }
+        }
+
+        part.measures.truncate(end + 1);
+        part.measures.drain(..start);
+
+        let opening = &mut part.measures[0].content;
+        match opening.first_mut() {
+            Some(MusicDataElement::Attributes(attributes)) => {
+                merge_attributes(&mut state, attributes);
+                **attributes = state;
+            }
+            _ if state != Attributes::default() => {
+                opening.insert(0, MusicDataElement::Attributes(Box::new(state)));
+            }
+            _ => {}
+        }
+    }
+    Ok(score)
+}
+
+/// Apply an attributes change on top of the running state.
+///
+/// Keys, clefs, and transpositions replace the entry for the same staff
+/// number; a new time signature replaces all earlier ones.
+fn merge_attributes(state: &mut Attributes, change: &Attributes) {
+    if change.divisions.is_some() {
+        state.divisions = change.divisions;
+    }
+    if change.staves.is_some() {
+        state.staves = change.staves;
+    }
+    if !change.times.is_empty() {
+        state.times = change.times.clone();
+    }
+    for key in &change.keys {
+        state.keys.retain(|k| k.number != key.number);
+        state.keys.push(key.clone());
+    }
+    for clef in &change.clefs {
+        state.clefs.retain(|c| c.number != clef.number);
+        state.clefs.push(clef.clone());
+    }
+    for transpose in &change.transpose {
+        state.transpose.retain(|t| t.number != transpose.number);
+        state.transpose.push(transpose.clone());
+    }
+}
+
 /// Parse score to AST (public helper).
 ///
 /// This is a convenience function for parsing just the score structure
@@ -223,8 +677,8 @@ mod tests {
     use super::*;
     use crate::ir::part::PartListElement;
     use crate::lang::ast::{
-        FermataDuration, FermataMeasure, FermataNote, FermataPart, FermataPitch, MeasureElement,
-        PitchStep,
+        DurationBase, FermataDuration, FermataMeasure, FermataNote, FermataPart, FermataPitch,
+        MeasureElement, PitchStep,
     };
     use crate::sexpr::parse;
 
@@ -392,6 +846,7 @@ mod tests {
         let fermata_score = FermataScore {
             title: None,
             composer: None,
+            tempo: None,
             parts: vec![],
         };
 
@@ -407,6 +862,7 @@ mod tests {
         let fermata_score = FermataScore {
             title: Some("Test Title".to_string()),
             composer: None,
+            tempo: None,
             parts: vec![],
         };
 
@@ -427,6 +883,7 @@ mod tests {
         let fermata_score = FermataScore {
             title: None,
             composer: Some("Test Composer".to_string()),
+            tempo: None,
             parts: vec![],
         };
 
@@ -443,6 +900,7 @@ mod tests {
         let fermata_score = FermataScore {
             title: Some("Title".to_string()),
             composer: Some("Composer".to_string()),
+            tempo: None,
             parts: vec![],
         };
 
@@ -457,17 +915,20 @@ mod tests {
         let fermata_score = FermataScore {
             title: None,
             composer: None,
+            tempo: None,
             parts: vec![
                 FermataPart {
                     name: "Violin".to_string(),
                     id: None,
                     abbreviation: None,
+                    transpose: None,
                     measures: vec![],
                 },
                 FermataPart {
                     name: "Cello".to_string(),
                     id: None,
                     abbreviation: None,
+                    transpose: None,
                     measures: vec![],
                 },
             ],
@@ -488,12 +949,15 @@ mod tests {
         let fermata_score = FermataScore {
             title: None,
             composer: None,
+            tempo: None,
             parts: vec![FermataPart {
                 name: "Piano".to_string(),
                 id: None,
                 abbreviation: None,
+                transpose: None,
                 measures: vec![FermataMeasure {
                     number: Some(1),
+                    implicit: false,
                     content: vec![MeasureElement::Note(FermataNote {
                         pitch: FermataPitch {
                             step: PitchStep::C,
@@ -505,10 +969,13 @@ mod tests {
                         staff: None,
                         stem: None,
                         articulations: vec![],
+                        dynamic: None,
                         ornaments: vec![],
+                        technical: vec![],
                         tie: None,
                         slur: None,
-                        lyric: None,
+                        lyrics: vec![],
+                        invisible: false,
                     })],
                 }],
             }],
@@ -565,4 +1032,334 @@ mod tests {
             panic!("Expected CreditWords");
         }
     }
+
+    #[test]
+    fn test_compile_score_raises_divisions_for_septuplets() {
+        let score = crate::lang::compile(
+            "(score (part :piano (measure
+               (tuplet 7 4 (note c4 :16) (note c4 :16) (note c4 :16) (note c4 :16)
+                           (note c4 :16) (note c4 :16) (note c4 :16))
+               (note c4 :q) (backup 2) (forward 1) (rest :h))))",
+        )
+        .unwrap();
+        let content = &score.parts[0].measures[0].content;
+
+        let divisions = content.iter().find_map(|e| match e {
+            MusicDataElement::Attributes(a) => a.divisions,
+            _ => None,
+        });
+        assert_eq!(divisions, Some(6720));
+
+        let durations: Vec<u64> = content
+            .iter()
+            .filter_map(|e| match e {
+                MusicDataElement::Note(n) => match n.content {
+                    NoteContent::Regular { duration, .. } => Some(duration),
+                    _ => None,
+                },
+                MusicDataElement::Backup(b) => Some(b.duration),
+                MusicDataElement::Forward(f) => Some(f.duration),
+                _ => None,
+            })
+            .collect();
+        // Seven exact sixteenth-septuplets fill one quarter
+        assert_eq!(durations[..7].iter().sum::<u64>(), 6720);
+        assert_eq!(durations[7..], [6720, 13440, 6720, 13440]);
+    }
+
+    #[test]
+    fn test_compile_score_rescale_keeps_voice_backup_exact() {
+        let score = crate::lang::compile(
+            "(score (part :piano (measure
+               (voice 1
+                 (tuplet 7 4 (note c5 :16) (note c5 :16) (note c5 :16) (note c5 :16)
+                             (note c5 :16) (note c5 :16) (note c5 :16))
+                 (note d5 :q))
+               (voice 2 (note c4 :h)))))",
+        )
+        .unwrap();
+        let backup = score.parts[0].measures[0]
+            .content
+            .iter()
+            .find_map(|e| match e {
+                MusicDataElement::Backup(b) => Some(b.duration),
+                _ => None,
+            });
+        // Two quarters at 6720 divisions, not 7 x the rounded 960-based sum
+        assert_eq!(backup, Some(13440));
+    }
+
+    #[test]
+    fn test_compile_score_changes_divisions_only_where_needed() {
+        let score = crate::lang::compile(
+            "(score (part :piano
+               (measure (time 2 4) (note c4 :q) (note d4 :q))
+               (measure (tuplet 7 4 (note e4 :16) (note e4 :16) (note e4 :16) (note e4 :16)
+                                    (note e4 :16) (note e4 :16) (note e4 :16))
+                        (note f4 :q))
+               (measure (note g4 :h))))",
+        )
+        .unwrap();
+        let measures = &score.parts[0].measures;
+
+        let divisions: Vec<Option<u64>> = measures
+            .iter()
+            .map(|m| {
+                m.content.iter().find_map(|e| match e {
+                    MusicDataElement::Attributes(a) => a.divisions,
+                    _ => None,
+                })
+            })
+            .collect();
+        // The coarse opening keeps the default; the finer value carries on
+        assert_eq!(divisions, [Some(960), Some(6720), None]);
+
+        let durations = |measure: &Measure| -> Vec<u64> {
+            measure
+                .content
+                .iter()
+                .filter_map(|e| match e {
+                    MusicDataElement::Note(n) => match n.content {
+                        NoteContent::Regular { duration, .. } => Some(duration),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(durations(&measures[0]), [960, 960]);
+        assert_eq!(durations(&measures[1])[..7].iter().sum::<u64>(), 6720);
+        assert_eq!(durations(&measures[1])[7], 6720);
+        assert_eq!(durations(&measures[2]), [13440]);
+
+        let xml = crate::musicxml::emit(&score).unwrap();
+        assert!(xml.contains("<divisions>960</divisions>"), "{}", xml);
+        assert!(xml.contains("<divisions>6720</divisions>"), "{}", xml);
+    }
+
+    // === concat_movements tests ===
+
+    fn four_bars(first: &str) -> ScorePartwise {
+        crate::lang::compile(&format!(
+            "(score (part :piano
+               (measure (note {first} :w)) (measure (note d4 :w))
+               (measure (note e4 :w)) (measure (note f4 :w) (barline :final))))"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_concat_movements_renumbers_and_marks_seam() {
+        let score = concat_movements(vec![four_bars("c4"), four_bars("g4")]).unwrap();
+        let measures = &score.parts[0].measures;
+
+        let numbers: Vec<&str> = measures.iter().map(|m| m.number.as_str()).collect();
+        assert_eq!(numbers, ["1", "2", "3", "4", "5", "6", "7", "8"]);
+
+        let right_barline = |measure: &Measure| {
+            measure.content.iter().find_map(|e| match e {
+                MusicDataElement::Barline(b) if b.location == Some(RightLeftMiddle::Right) => {
+                    b.bar_style
+                }
+                _ => None,
+            })
+        };
+        assert_eq!(right_barline(&measures[3]), Some(BarStyle::LightLight));
+        assert_eq!(right_barline(&measures[7]), Some(BarStyle::LightHeavy));
+    }
+
+    #[test]
+    fn test_concat_movements_keeps_pickup_numbered_zero() {
+        let with_pickup = crate::lang::compile(
+            "(score (part :piano (pickup (note g3 :q)) (measure (note c4 :w))))",
+        )
+        .unwrap();
+        let score = concat_movements(vec![with_pickup, four_bars("c4")]).unwrap();
+        let numbers: Vec<&str> = score.parts[0]
+            .measures
+            .iter()
+            .map(|m| m.number.as_str())
+            .collect();
+        assert_eq!(numbers, ["0", "1", "2", "3", "4", "5"]);
+    }
+
+    #[test]
+    fn test_concat_movements_mismatched_parts_is_semantic_error() {
+        let strings = crate::lang::compile(
+            "(score (part :violin (measure (note c4 :w))) (part :cello (measure (note c3 :w))))",
+        )
+        .unwrap();
+        let err = concat_movements(vec![four_bars("c4"), strings]).unwrap_err();
+        assert!(matches!(err, CompileError::Semantic(message) if message.contains("movement 2")));
+
+        assert!(concat_movements(vec![]).is_err());
+    }
+
+    #[test]
+    fn test_select_measures_carries_key_into_excerpt() {
+        let score = crate::lang::compile(
+            "(score (part :piano \
+               (measure (key d :major) (time 3 4) (clef :bass) (note d3 :h.)) \
+               (measure (note e3 :h.)) \
+               (measure (note f#3 :h.)) \
+               (measure (note g3 :h.)) \
+               (measure (note a3 :h.))))",
+        )
+        .unwrap();
+
+        let excerpt = select_measures(score, 2, Some(3)).unwrap();
+        let measures = &excerpt.parts[0].measures;
+        assert_eq!(measures.len(), 2);
+        assert_eq!(measures[0].number, "2");
+        assert_eq!(measures[1].number, "3");
+
+        let MusicDataElement::Attributes(attributes) = &measures[0].content[0] else {
+            panic!("expected synthesized attributes");
+        };
+        assert_eq!(attributes.divisions, Some(960));
+        assert!(matches!(
+            &attributes.keys[0].content,
+            crate::ir::attributes::KeyContent::Traditional(key) if key.fifths == 2
+        ));
+        assert_eq!(attributes.times.len(), 1);
+        assert_eq!(attributes.clefs[0].sign, crate::ir::attributes::ClefSign::F);
+    }
+
+    #[test]
+    fn test_select_measures_open_and_invalid_ranges() {
+        let open = select_measures(four_bars("c4"), 3, None).unwrap();
+        let numbers: Vec<&str> = open.parts[0]
+            .measures
+            .iter()
+            .map(|m| m.number.as_str())
+            .collect();
+        assert_eq!(numbers, ["3", "4"]);
+
+        assert!(select_measures(four_bars("c4"), 0, Some(0)).is_err());
+        assert!(select_measures(four_bars("c4"), 5, None).is_err());
+        assert!(select_measures(four_bars("c4"), 3, Some(2)).is_err());
+    }
+
+    #[test]
+    fn test_select_measures_by_number_after_pickup() {
+        let source = "(score (part :piano (pickup (note g3 :q))
+               (measure (note c4 :w)) (measure (note d4 :w)) (measure (note e4 :w))))";
+        let numbers = |first, last| {
+            let excerpt = select_measures(crate::lang::compile(source).unwrap(), first, last);
+            excerpt.unwrap().parts[0]
+                .measures
+                .iter()
+                .map(|m| m.number.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(1, Some(2)), ["1", "2"]);
+        assert_eq!(numbers(0, Some(1)), ["0", "1"]);
+        assert_eq!(numbers(3, None), ["3"]);
+    }
+
+    #[test]
+    fn test_select_part_by_name_or_id() {
+        let source = "(score (part :name \"Violin\" (measure (note g4 :w)))
+                             (part :name \"Cello\" (measure (note c3 :w))))";
+
+        let violin = select_part(crate::lang::compile(source).unwrap(), "violin").unwrap();
+        assert_eq!(violin.part_list.content.len(), 1);
+        assert!(matches!(
+            &violin.part_list.content[0],
+            PartListElement::ScorePart(part) if part.part_name.value == "Violin"
+        ));
+        assert_eq!(violin.parts.len(), 1);
+        assert_eq!(violin.parts[0].id, "P1");
+
+        let cello = select_part(crate::lang::compile(source).unwrap(), "P2").unwrap();
+        assert_eq!(cello.parts[0].id, "P2");
+
+        let err = select_part(crate::lang::compile(source).unwrap(), "Viola").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Semantic error: no part named 'Viola'; available parts: Violin (P1), Cello (P2)"
+        );
+    }
+
+    #[test]
+    fn test_compile_score_title_and_composer_forms() {
+        let score = crate::lang::compile(
+            r#"(score (title "Sonata") (composer "Bach") (part :piano (measure (note c4 :w))))"#,
+        )
+        .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        let work = xml.find("<work>").expect("work");
+        let title = xml
+            .find("<work-title>Sonata</work-title>")
+            .expect("work title");
+        assert!(
+            work < title && title < xml.find("</work>").unwrap(),
+            "{}",
+            xml
+        );
+        assert!(
+            xml.contains(r#"<creator type="composer">Bach</creator>"#),
+            "{}",
+            xml
+        );
+        let identification = xml.find("<identification>").expect("identification");
+        assert!(xml[identification..].contains("Bach"), "{}", xml);
+    }
+
+    #[test]
+    fn test_parse_score_title_form_requires_string() {
+        for source in ["(score (title))", "(score (composer bach))"] {
+            assert!(
+                parse_score_from_sexpr(&parse(source).unwrap()).is_err(),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_score_bare_tempo_form() {
+        let sexpr = parse("(score (tempo :h 60) (part :piano (measure)))").unwrap();
+        let score = parse_score_from_sexpr(&sexpr).unwrap();
+
+        let tempo = score.tempo.unwrap();
+        assert_eq!(tempo.beat_unit, Some(DurationBase::Half));
+        assert_eq!(tempo.beat_unit_dots, 0);
+        assert_eq!(tempo.per_minute, Some(60));
+        assert_eq!(score.parts.len(), 1);
+    }
+
+    #[test]
+    fn test_compile_score_bare_dotted_tempo() {
+        let score = crate::lang::compile(
+            "(score (tempo :q. 80) (part :piano (measure (time 6 8) (note c4 :h.))))",
+        )
+        .unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        assert!(xml.contains("<beat-unit>quarter</beat-unit>"), "{}", xml);
+        assert!(xml.contains("<beat-unit-dot/>"), "{}", xml);
+        assert!(xml.contains("<per-minute>80</per-minute>"), "{}", xml);
+    }
+
+    #[test]
+    fn test_compile_score_tempo_in_first_measure() {
+        let score = crate::lang::compile(
+            "(score :tempo (tempo :q 96)
+               (part :piano (measure (note c4 :w)) (measure (note d4 :w)))
+               (part :bass (measure (note c3 :w)) (measure (note d3 :w))))",
+        )
+        .unwrap();
+
+        let first = &score.parts[0].measures[0].content;
+        assert!(matches!(first[0], MusicDataElement::Attributes(_)));
+        assert!(matches!(first[1], MusicDataElement::Direction(_)));
+
+        let xml = crate::musicxml::emit(&score).unwrap();
+        assert_eq!(xml.matches("<metronome>").count(), 1);
+        assert!(xml.contains("<beat-unit>quarter</beat-unit>"));
+        assert!(xml.contains("<per-minute>96</per-minute>"));
+        assert!(xml.contains(r#"<sound tempo="96"/>"#));
+    }
 }
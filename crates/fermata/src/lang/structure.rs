@@ -0,0 +1,211 @@
+//! Structural checks on Fermata source.
+//!
+//! Before a score is interpreted, its forms are checked for mistakes in
+//! the overall shape of the source that would otherwise be skipped or
+//! surface as low-level failures far from their cause:
+//!
+//! - a `score` with no parts;
+//! - a `measure` written directly in a `score` instead of inside a `part`;
+//! - a `note` with no duration, such as `(note c4)`.
+//!
+//! Each error records the path to the offending form, so a caller holding
+//! the source can point at it. The bodies of `define` forms and
+//! quasiquote templates are not checked, since their forms are only
+//! complete once expanded, and a reference to a definition is taken to
+//! supply whatever it stands for.
+
+use std::collections::HashSet;
+
+use crate::lang::error::{CompileError, SourceSpan};
+use crate::lang::note::is_duration_keyword;
+use crate::sexpr::{Sexpr, SpanMap};
+
+/// A structural mistake, with the child-index path to the form at fault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructureError {
+    /// What is wrong, naming the offending form
+    pub message: String,
+    /// Child indices from the root to the offending form
+    pub path: Vec<usize>,
+}
+
+impl StructureError {
+    /// Convert to a [`CompileError::Semantic`] pointing at the offending
+    /// form, using the spans of the parsed `source`.
+    pub fn locate(self, spans: &SpanMap, source: &str) -> CompileError {
+        let span = spans.get(&self.path).map(|node| node.span.clone());
+        let error = CompileError::from(self);
+        match span {
+            Some(span) => {
+                error.with_span(SourceSpan::new(span.start, span.end).with_source(source))
+            }
+            None => error,
+        }
+    }
+}
+
+impl From<StructureError> for CompileError {
+    fn from(error: StructureError) -> Self {
+        CompileError::Semantic(error.message)
+    }
+}
+
+/// Check the structure of a Fermata score, returning the first mistake.
+///
+/// # Examples
+///
+/// ```
+/// use fermata::lang::structure::check_structure;
+/// use fermata::sexpr::parse;
+///
+/// let error = check_structure(&parse("(score)").unwrap()).unwrap_err();
+/// assert_eq!(error.message, "score has no parts");
+///
+/// let error = check_structure(&parse("(score (part :piano (measure (note c4))))").unwrap())
+///     .unwrap_err();
+/// assert_eq!(error.path, vec![1, 2, 1]);
+/// ```
+pub fn check_structure(sexpr: &Sexpr) -> Result<(), StructureError> {
+    let mut definitions = HashSet::new();
+    collect_definitions(sexpr, &mut definitions);
+
+    let checker = Checker { definitions };
+    let mut path = Vec::new();
+    checker.check(sexpr, None, &mut path)
+}
+
+/// Gather the name of every `define` in the tree.
+fn collect_definitions<'a>(sexpr: &'a Sexpr, names: &mut HashSet<&'a str>) {
+    let Some(list) = sexpr.as_list() else {
+        return;
+    };
+    if head(list) == Some("define") {
+        let name = list.get(1).and_then(|target| {
+            target
+                .as_symbol()
+                .or_else(|| target.as_list().and_then(|l| head(l)))
+        });
+        names.extend(name);
+        return;
+    }
+    for item in list {
+        collect_definitions(item, names);
+    }
+}
+
+fn head(list: &[Sexpr]) -> Option<&str> {
+    list.first().and_then(Sexpr::as_symbol)
+}
+
+struct Checker<'a> {
+    definitions: HashSet<&'a str>,
+}
+
+impl Checker<'_> {
+    /// Check `sexpr`, found inside a form headed `parent`, whose path from
+    /// the root is `path`.
+    fn check(
+        &self,
+        sexpr: &Sexpr,
+        parent: Option<&str>,
+        path: &mut Vec<usize>,
+    ) -> Result<(), StructureError> {
+        let Some(list) = sexpr.as_list() else {
+            return Ok(());
+        };
+        let form = head(list);
+        if matches!(form, Some("define" | "quasiquote")) {
+            return Ok(());
+        }
+
+        match form {
+            Some("measure") if parent == Some("score") => {
+                return Err(self.error("measure must be inside a part", path));
+            }
+            Some("note") if !self.has_duration(list) => {
+                let message = match list.get(1).and_then(Sexpr::as_symbol) {
+                    Some(pitch) => {
+                        format!("note {} has no duration, e.g. (note {} :q)", pitch, pitch)
+                    }
+                    None => "note has no duration".to_string(),
+                };
+                return Err(self.error(&message, path));
+            }
+            _ => {}
+        }
+
+        for (index, item) in list.iter().enumerate().skip(1) {
+            path.push(index);
+            self.check(item, form, path)?;
+            path.pop();
+        }
+
+        if form == Some("score") && !list[1..].iter().any(|item| self.may_be_part(item)) {
+            return Err(self.error("score has no parts", path));
+        }
+        Ok(())
+    }
+
+    /// Whether a note form gives a duration after its pitch.
+    fn has_duration(&self, list: &[Sexpr]) -> bool {
+        list.get(2)
+            .and_then(|item| item.as_keyword().or_else(|| item.as_symbol()))
+            .is_some_and(|name| is_duration_keyword(name) || self.definitions.contains(name))
+    }
+
+    /// Whether a score item is a part, or a definition that may hold one.
+    fn may_be_part(&self, item: &Sexpr) -> bool {
+        let name = item
+            .as_symbol()
+            .or_else(|| item.as_list().and_then(|l| head(l)));
+        name.is_some_and(|name| name == "part" || self.definitions.contains(name))
+    }
+
+    fn error(&self, message: &str, path: &[usize]) -> StructureError {
+        StructureError {
+            message: message.to_string(),
+            path: path.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sexpr::parse;
+
+    fn structure(source: &str) -> Result<(), StructureError> {
+        check_structure(&parse(source).unwrap())
+    }
+
+    #[test]
+    fn test_check_structure_reports_form_paths() {
+        let cases = [
+            ("(score :title \"Empty\")", "score has no parts", vec![]),
+            (
+                "(score (part :piano) (measure (note c4 :q)))",
+                "measure must be inside a part",
+                vec![2],
+            ),
+            (
+                "(score (part :piano (measure (note c4 :q) (note d4 :voice 2))))",
+                "note d4 has no duration, e.g. (note d4 :q)",
+                vec![1, 2, 2],
+            ),
+        ];
+        for (source, message, path) in cases {
+            let error = structure(source).unwrap_err();
+            assert_eq!(error.message, message, "{}", source);
+            assert_eq!(error.path, path, "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_check_structure_accepts_definitions() {
+        let source = "(score
+            (define (bass p) (note p :h) (note p))
+            (define violin (part :violin (measure (bass g3))))
+            violin)";
+        assert_eq!(structure(source), Ok(()));
+    }
+}
@@ -0,0 +1,232 @@
+//! Tremolo compilation for Fermata syntax.
+//!
+//! A single-note tremolo repeats one note rapidly and is drawn as slashes
+//! through its stem: `(tremolo 3 (note c4 :q))`. A two-note tremolo
+//! alternates between two notes and is drawn as beams between them:
+//! `(tremolo-pair 3 (note c4 :h) (note g4 :h))`. Both compile to
+//! `<tremolo>` ornaments on the notes involved.
+
+use crate::ir::duration::{TimeModification, divisions_for};
+use crate::ir::notation::TremoloType;
+use crate::ir::note::{Note, NoteContent};
+use crate::lang::ast::{FermataNote, FermataTremoloPair, Ornament};
+use crate::lang::defaults::DEFAULT_DIVISIONS;
+use crate::lang::error::{CompileError, CompileResult};
+use crate::lang::note::{compile_fermata_note, parse_note_form, parse_u32};
+use crate::sexpr::Sexpr;
+
+/// Parse a tremolo form into a FermataNote carrying a single-note tremolo.
+///
+/// Expected format: `marks (note ...)` after the `tremolo` head, where
+/// marks is the number of tremolo beams (1-8).
+pub fn parse_tremolo_form(items: &[Sexpr]) -> CompileResult<FermataNote> {
+    let [marks, note] = items else {
+        return Err(CompileError::InvalidNote(
+            "tremolo requires a number of marks and one note, e.g. (tremolo 3 (note c4 :q))"
+                .to_string(),
+        ));
+    };
+
+    let marks = parse_tremolo_marks(marks)?;
+    let mut note = parse_tremolo_note(note, "tremolo")?;
+    note.ornaments.push(Ornament::Tremolo {
+        marks,
+        r#type: TremoloType::Single,
+    });
+    Ok(note)
+}
+
+/// Parse a tremolo-pair form into a FermataTremoloPair AST.
+///
+/// Expected format: `marks (note ...) (note ...)` after the `tremolo-pair`
+/// head, where marks is the number of beams drawn between the notes (1-8).
+pub fn parse_tremolo_pair_form(items: &[Sexpr]) -> CompileResult<FermataTremoloPair> {
+    let [marks, first, second] = items else {
+        return Err(CompileError::InvalidNote(
+            "tremolo-pair requires a number of marks and two notes, \
+             e.g. (tremolo-pair 3 (note c4 :h) (note g4 :h))"
+                .to_string(),
+        ));
+    };
+
+    Ok(FermataTremoloPair {
+        marks: parse_tremolo_marks(marks)?,
+        first: parse_tremolo_note(first, "tremolo-pair")?,
+        second: parse_tremolo_note(second, "tremolo-pair")?,
+    })
+}
+
+/// Compile a FermataTremoloPair to a `Vec<Note>`.
+///
+/// Both notes keep their written value but share its time, as MusicXML
+/// expects: each gets a 2:1 time modification and half the duration, so a
+/// pair of half notes fills a half note. The first note carries a
+/// `<tremolo type="start">` and the second a `<tremolo type="stop">`.
+///
+/// # Errors
+///
+/// Returns [`CompileError::Semantic`] if the notes have different written
+/// durations.
+pub fn compile_fermata_tremolo_pair(pair: &FermataTremoloPair) -> CompileResult<Vec<Note>> {
+    if pair.first.duration != pair.second.duration {
+        return Err(CompileError::Semantic(format!(
+            "tremolo-pair notes must have the same duration, got {:?} and {:?}",
+            pair.first.duration, pair.second.duration
+        )));
+    }
+
+    [
+        (&pair.first, TremoloType::Start),
+        (&pair.second, TremoloType::Stop),
+    ]
+    .into_iter()
+    .map(|(fermata_note, r#type)| {
+        let mut fermata_note = fermata_note.clone();
+        fermata_note.ornaments.push(Ornament::Tremolo {
+            marks: pair.marks,
+            r#type,
+        });
+        let mut note = compile_fermata_note(&fermata_note)?;
+
+        let time_modification = TimeModification {
+            actual_notes: 2,
+            normal_notes: 1,
+            normal_type: None,
+            normal_dots: 0,
+        };
+        if let (NoteContent::Regular { duration, .. }, Some(note_type)) =
+            (&mut note.content, &note.r#type)
+        {
+            *duration = divisions_for(
+                note_type.value,
+                note.dots.len() as u32,
+                Some(&time_modification),
+                DEFAULT_DIVISIONS,
+            );
+        }
+        note.time_modification = Some(time_modification);
+        Ok(note)
+    })
+    .collect()
+}
+
+/// Parse the number of tremolo beams, which MusicXML limits to 1-8.
+fn parse_tremolo_marks(sexpr: &Sexpr) -> CompileResult<u8> {
+    let marks = parse_u32(sexpr)?;
+    if !(1..=8).contains(&marks) {
+        return Err(CompileError::InvalidNote(format!(
+            "tremolo marks must be between 1 and 8, got {}",
+            marks
+        )));
+    }
+    Ok(marks as u8)
+}
+
+/// Parse the note wrapped by a tremolo form.
+fn parse_tremolo_note(sexpr: &Sexpr, form: &str) -> CompileResult<FermataNote> {
+    match sexpr.as_list() {
+        Some(items) if items.first().is_some_and(|h| h.is_symbol("note")) => {
+            parse_note_form(&items[1..])
+        }
+        _ => Err(CompileError::InvalidNote(format!(
+            "expected note form in {}, got {:?}",
+            form, sexpr
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::duration::NoteTypeValue;
+    use crate::ir::notation::{NotationContent, OrnamentElement};
+    use crate::lang::compile;
+    use crate::sexpr::parse;
+
+    fn items(source: &str) -> Vec<Sexpr> {
+        parse(source).unwrap().as_list().unwrap()[1..].to_vec()
+    }
+
+    /// The (value, type) of each tremolo ornament on a note.
+    fn tremolos(note: &Note) -> Vec<(u8, Option<TremoloType>)> {
+        note.notations
+            .iter()
+            .flat_map(|n| &n.content)
+            .filter_map(|content| match content {
+                NotationContent::Ornaments(ornaments) => Some(&ornaments.content),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|o| match &o.ornament {
+                OrnamentElement::Tremolo(t) => Some((t.value, t.r#type)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_tremolo_form_adds_single_tremolo() {
+        let note = parse_tremolo_form(&items("(tremolo 3 (note c4 :q))")).unwrap();
+
+        assert_eq!(
+            note.ornaments,
+            vec![Ornament::Tremolo {
+                marks: 3,
+                r#type: TremoloType::Single
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_tremolo_form_marks_out_of_range_fails() {
+        assert!(parse_tremolo_form(&items("(tremolo 9 (note c4 :q))")).is_err());
+        assert!(parse_tremolo_form(&items("(tremolo 0 (note c4 :q))")).is_err());
+    }
+
+    #[test]
+    fn test_parse_tremolo_pair_form_requires_two_notes() {
+        let result = parse_tremolo_pair_form(&items("(tremolo-pair 3 (note c4 :h))"));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_compile_fermata_tremolo_pair_shares_written_duration() {
+        let pair =
+            parse_tremolo_pair_form(&items("(tremolo-pair 3 (note c4 :h) (note g4 :h))")).unwrap();
+
+        let notes = compile_fermata_tremolo_pair(&pair).unwrap();
+
+        assert_eq!(notes.len(), 2);
+        assert_eq!(tremolos(&notes[0]), vec![(3, Some(TremoloType::Start))]);
+        assert_eq!(tremolos(&notes[1]), vec![(3, Some(TremoloType::Stop))]);
+        for note in &notes {
+            assert_eq!(note.r#type.as_ref().unwrap().value, NoteTypeValue::Half);
+            assert_eq!(note.duration().unwrap(), DEFAULT_DIVISIONS as u64);
+        }
+    }
+
+    #[test]
+    fn test_compile_fermata_tremolo_pair_mismatched_durations_fails() {
+        let pair =
+            parse_tremolo_pair_form(&items("(tremolo-pair 3 (note c4 :h) (note g4 :q))")).unwrap();
+
+        let result = compile_fermata_tremolo_pair(&pair);
+
+        assert!(matches!(result, Err(CompileError::Semantic(_))));
+    }
+
+    #[test]
+    fn test_compile_single_tremolo_emits_tremolo_ornament() {
+        let score = compile("(score (part :violin (measure (time 1 4) (tremolo 3 (note c4 :q)))))")
+            .unwrap();
+
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        assert!(
+            xml.contains("<tremolo type=\"single\">3</tremolo>"),
+            "{}",
+            xml
+        );
+    }
+}
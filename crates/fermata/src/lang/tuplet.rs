@@ -4,12 +4,14 @@
 //! A tuplet modifies the time value of notes, such as triplets (3 in the time of 2).
 
 use crate::ir::common::{Position, StartStop, YesNo};
-use crate::ir::duration::TimeModification;
+use crate::ir::duration::{TimeModification, divisions_for};
 use crate::ir::notation::{
     NotationContent, Notations, ShowTuplet, Tuplet, TupletNumber, TupletPortion,
 };
-use crate::ir::note::Note;
+use crate::ir::note::{Note, NoteContent};
 use crate::lang::ast::{FermataTuplet, MeasureElement};
+use crate::lang::defaults::DEFAULT_DIVISIONS;
+use crate::lang::direction::{OpenSpans, compile_spanned_element};
 use crate::lang::error::{CompileError, CompileResult};
 use crate::lang::note::parse_u32;
 use crate::sexpr::Sexpr;
@@ -58,7 +60,8 @@ pub fn compile_tuplet(sexpr: &Sexpr) -> CompileResult<Vec<Note>> {
 ///
 /// Expected format: `ratio notes...`
 /// - ratio: "3:2" or "3/2" or separate "3" "2" or just "3" (implies 3:2)
-/// - notes: sequence of note, rest, or chord forms
+/// - notes: sequence of forms a spanning form accepts: note, rest, chord,
+///   nested tuplet, slur, tie, or tremolo
 pub fn parse_tuplet_form(items: &[Sexpr]) -> CompileResult<FermataTuplet> {
     if items.is_empty() {
         return Err(CompileError::InvalidTuplet {
@@ -69,51 +72,11 @@ pub fn parse_tuplet_form(items: &[Sexpr]) -> CompileResult<FermataTuplet> {
     // Parse the ratio (actual:normal)
     let (actual, normal, remaining_start) = parse_ratio(items)?;
 
-    // Parse the notes/rests/chords inside the tuplet
-    let mut notes: Vec<MeasureElement> = Vec::new();
-
-    for item in &items[remaining_start..] {
-        // Each item should be a list representing a note, rest, or chord
-        match item {
-            Sexpr::List(sub_items) if !sub_items.is_empty() => {
-                if let Some(head) = sub_items[0].as_symbol() {
-                    let element = match head {
-                        "note" => {
-                            let fermata_note = crate::lang::note::parse_note_form(&sub_items[1..])?;
-                            MeasureElement::Note(fermata_note)
-                        }
-                        "rest" => {
-                            let fermata_rest = crate::lang::note::parse_rest_form(&sub_items[1..])?;
-                            MeasureElement::Rest(fermata_rest)
-                        }
-                        "chord" => {
-                            let fermata_chord =
-                                crate::lang::chord::parse_chord_form(&sub_items[1..])?;
-                            MeasureElement::Chord(fermata_chord)
-                        }
-                        _ => {
-                            return Err(CompileError::InvalidTuplet {
-                                reason: format!(
-                                    "unexpected element '{}' in tuplet, expected note, rest, or chord",
-                                    head
-                                ),
-                            });
-                        }
-                    };
-                    notes.push(element);
-                } else {
-                    return Err(CompileError::InvalidTuplet {
-                        reason: format!("expected note/rest/chord form, got {:?}", item),
-                    });
-                }
-            }
-            _ => {
-                return Err(CompileError::InvalidTuplet {
-                    reason: format!("expected note/rest/chord list, got {:?}", item),
-                });
-            }
-        }
-    }
+    // Parse the notes, rests, chords, and groups inside the tuplet
+    let notes = items[remaining_start..]
+        .iter()
+        .map(|item| crate::lang::direction::parse_spanned_element(item, "tuplet"))
+        .collect::<CompileResult<Vec<MeasureElement>>>()?;
 
     if notes.is_empty() {
         return Err(CompileError::InvalidTuplet {
@@ -208,88 +171,127 @@ fn parse_ratio(items: &[Sexpr]) -> CompileResult<(u32, u32, usize)> {
 ///
 /// Each note in the tuplet gets:
 /// - A TimeModification specifying the actual:normal ratio
+/// - A duration scaled by normal/actual
 /// - First note gets Tuplet notation with type=Start
 /// - Last note gets Tuplet notation with type=Stop
+///
+/// Nested tuplets are compiled recursively: inner notes carry the product of
+/// the enclosing ratios, and each nesting level uses its own tuplet number.
 pub fn compile_fermata_tuplet(tuplet: &FermataTuplet) -> CompileResult<Vec<Note>> {
-    let time_modification = TimeModification {
-        actual_notes: tuplet.actual,
-        normal_notes: tuplet.normal,
-        normal_type: None,
-        normal_dots: 0,
+    compile_tuplet_level(tuplet, OpenSpans::default())
+}
+
+/// Compile a tuplet inside the slurs and tuplets already `open`, numbering
+/// it one past the enclosing tuplets.
+pub(crate) fn compile_tuplet_level(
+    tuplet: &FermataTuplet,
+    open: OpenSpans,
+) -> CompileResult<Vec<Note>> {
+    if tuplet.actual == 0 || tuplet.normal == 0 {
+        return Err(CompileError::InvalidTuplet {
+            reason: format!(
+                "tuplet ratio must be positive, got {}:{}",
+                tuplet.actual, tuplet.normal
+            ),
+        });
+    }
+
+    let level = open.tuplets + 1;
+    let inner = OpenSpans {
+        tuplets: level,
+        ..open
     };
 
     let mut all_notes: Vec<Note> = Vec::new();
+    for element in &tuplet.notes {
+        all_notes.extend(compile_spanned_element(element, "tuplet", inner)?);
+    }
+
+    // The tuplet starts on the first note and stops on the last one to
+    // take time, each along with its chord notes
+    let last = all_notes
+        .iter()
+        .rposition(|note| !note.is_chord_member())
+        .unwrap_or(0);
+    let onset_end = |start: usize| {
+        start
+            + 1
+            + all_notes[start + 1..]
+                .iter()
+                .take_while(|note| note.is_chord_member())
+                .count()
+    };
+    let first_onset = 0..onset_end(0);
+    let last_onset = last..onset_end(last);
+
+    for (idx, note) in all_notes.iter_mut().enumerate() {
+        let is_first = first_onset.contains(&idx);
+        let is_last = last_onset.contains(&idx) && !is_first;
+
+        // Apply (or compound, for nested tuplets) the time modification
+        note.time_modification = Some(match note.time_modification.take() {
+            Some(inner) => TimeModification {
+                actual_notes: inner.actual_notes * tuplet.actual,
+                normal_notes: inner.normal_notes * tuplet.normal,
+                ..inner
+            },
+            None => TimeModification {
+                actual_notes: tuplet.actual,
+                normal_notes: tuplet.normal,
+                normal_type: None,
+                normal_dots: 0,
+            },
+        });
 
-    for (idx, element) in tuplet.notes.iter().enumerate() {
-        let is_first = idx == 0;
-        let is_last = idx == tuplet.notes.len() - 1;
-
-        // Compile the element to notes
-        let mut notes = compile_measure_element(element)?;
-
-        // Apply time modification and tuplet notation to each note
-        for note in &mut notes {
-            // Apply time modification
-            note.time_modification = Some(time_modification.clone());
-
-            // Add tuplet notation for first and last
-            if is_first || is_last {
-                let tuplet_notation = create_tuplet_notation(
-                    if is_first {
-                        StartStop::Start
-                    } else {
-                        StartStop::Stop
-                    },
-                    tuplet.actual,
-                    tuplet.normal,
-                );
-
-                // Add to existing notations or create new
-                if note.notations.is_empty() {
-                    note.notations.push(Notations {
-                        print_object: None,
-                        content: vec![NotationContent::Tuplet(Box::new(tuplet_notation))],
-                        editorial: Default::default(),
-                    });
+        // Recompute the sounding duration under the (compound) ratio
+        if let NoteContent::Regular { duration, .. } = &mut note.content {
+            *duration = match &note.r#type {
+                Some(note_type) => divisions_for(
+                    note_type.value,
+                    note.dots.len() as u32,
+                    note.time_modification.as_ref(),
+                    DEFAULT_DIVISIONS,
+                ),
+                None => *duration * tuplet.normal as u64 / tuplet.actual as u64,
+            };
+        }
+
+        // Add tuplet notation for first and last
+        if is_first || is_last {
+            let tuplet_notation = create_tuplet_notation(
+                if is_first {
+                    StartStop::Start
                 } else {
-                    note.notations[0]
-                        .content
-                        .push(NotationContent::Tuplet(Box::new(tuplet_notation)));
-                }
+                    StartStop::Stop
+                },
+                tuplet.actual,
+                tuplet.normal,
+                level,
+            );
+
+            // Add to existing notations or create new
+            if note.notations.is_empty() {
+                note.notations.push(Notations {
+                    print_object: None,
+                    content: vec![NotationContent::Tuplet(Box::new(tuplet_notation))],
+                    editorial: Default::default(),
+                });
+            } else {
+                note.notations[0]
+                    .content
+                    .push(NotationContent::Tuplet(Box::new(tuplet_notation)));
             }
         }
-
-        all_notes.extend(notes);
     }
 
     Ok(all_notes)
 }
 
-/// Compile a MeasureElement to a Vec<Note>.
-fn compile_measure_element(element: &MeasureElement) -> CompileResult<Vec<Note>> {
-    match element {
-        MeasureElement::Note(fermata_note) => {
-            let note = crate::lang::note::compile_fermata_note(fermata_note)?;
-            Ok(vec![note])
-        }
-        MeasureElement::Rest(fermata_rest) => {
-            let note = crate::lang::note::compile_fermata_rest(fermata_rest)?;
-            Ok(vec![note])
-        }
-        MeasureElement::Chord(fermata_chord) => {
-            crate::lang::chord::compile_fermata_chord(fermata_chord)
-        }
-        _ => Err(CompileError::InvalidTuplet {
-            reason: format!("unsupported element type in tuplet: {:?}", element),
-        }),
-    }
-}
-
 /// Create a Tuplet notation element.
-fn create_tuplet_notation(r#type: StartStop, actual: u32, normal: u32) -> Tuplet {
+fn create_tuplet_notation(r#type: StartStop, actual: u32, normal: u32, number: u8) -> Tuplet {
     Tuplet {
         r#type,
-        number: Some(1), // Default tuplet number
+        number: Some(number),
         bracket: Some(YesNo::Yes),
         show_number: Some(ShowTuplet::Actual),
         show_type: None,
@@ -622,10 +624,13 @@ mod tests {
                     staff: None,
                     stem: None,
                     articulations: vec![],
+                    dynamic: None,
                     ornaments: vec![],
+                    technical: vec![],
                     tie: None,
                     slur: None,
-                    lyric: None,
+                    lyrics: vec![],
+                    invisible: false,
                 }),
                 MeasureElement::Note(FermataNote {
                     pitch: FermataPitch {
@@ -638,10 +643,13 @@ mod tests {
                     staff: None,
                     stem: None,
                     articulations: vec![],
+                    dynamic: None,
                     ornaments: vec![],
+                    technical: vec![],
                     tie: None,
                     slur: None,
-                    lyric: None,
+                    lyrics: vec![],
+                    invisible: false,
                 }),
             ],
         };
@@ -675,16 +683,20 @@ mod tests {
                     staff: None,
                     stem: None,
                     articulations: vec![],
+                    dynamic: None,
                     ornaments: vec![],
+                    technical: vec![],
                     tie: None,
                     slur: None,
-                    lyric: None,
+                    lyrics: vec![],
+                    invisible: false,
                 }),
                 MeasureElement::Rest(FermataRest {
                     duration: FermataDuration::default(),
                     voice: None,
                     staff: None,
                     measure_rest: false,
+                    invisible: false,
                 }),
                 MeasureElement::Note(FermataNote {
                     pitch: FermataPitch {
@@ -697,10 +709,13 @@ mod tests {
                     staff: None,
                     stem: None,
                     articulations: vec![],
+                    dynamic: None,
                     ornaments: vec![],
+                    technical: vec![],
                     tie: None,
                     slur: None,
-                    lyric: None,
+                    lyrics: vec![],
+                    invisible: false,
                 }),
             ],
         };
@@ -716,7 +731,7 @@ mod tests {
 
     #[test]
     fn test_create_tuplet_notation_start() {
-        let notation = create_tuplet_notation(StartStop::Start, 3, 2);
+        let notation = create_tuplet_notation(StartStop::Start, 3, 2, 1);
         assert_eq!(notation.r#type, StartStop::Start);
         assert_eq!(notation.bracket, Some(YesNo::Yes));
 
@@ -729,10 +744,103 @@ mod tests {
 
     #[test]
     fn test_create_tuplet_notation_stop() {
-        let notation = create_tuplet_notation(StartStop::Stop, 5, 4);
+        let notation = create_tuplet_notation(StartStop::Stop, 5, 4, 1);
         assert_eq!(notation.r#type, StartStop::Stop);
 
         let actual = notation.tuplet_actual.as_ref().unwrap();
         assert_eq!(actual.tuplet_number.as_ref().unwrap().value, 5);
     }
+
+    // === Nested tuplet and emission tests ===
+
+    fn tuplet_types(note: &Note) -> Vec<(StartStop, Option<u8>)> {
+        note.notations
+            .iter()
+            .flat_map(|n| &n.content)
+            .filter_map(|c| match c {
+                NotationContent::Tuplet(t) => Some((t.r#type, t.number)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn note_duration(note: &Note) -> u64 {
+        match &note.content {
+            NoteContent::Regular { duration, .. } => *duration,
+            _ => panic!("Expected regular note"),
+        }
+    }
+
+    #[test]
+    fn test_compile_tuplet_scales_duration() {
+        let sexpr =
+            crate::sexpr::parse("(tuplet 3 2 (note c4 :8) (note d4 :8) (note e4 :8))").unwrap();
+        let notes = compile_tuplet(&sexpr).unwrap();
+
+        let total: u64 = notes.iter().map(note_duration).sum();
+        assert_eq!(total, 960); // three triplet eighths fill one quarter
+    }
+
+    #[test]
+    fn test_compile_tuplet_nested() {
+        let sexpr = crate::sexpr::parse(
+            "(tuplet 3 2 (note c4 :8) (tuplet 3 2 (note d4 :16) (note e4 :16) (note f4 :16)) (note g4 :8))",
+        )
+        .unwrap();
+        let notes = compile_tuplet(&sexpr).unwrap();
+        assert_eq!(notes.len(), 5);
+
+        // Outer notes carry 3:2, inner notes the compounded 9:4
+        let ratios: Vec<(u32, u32)> = notes
+            .iter()
+            .map(|n| {
+                let tm = n.time_modification.as_ref().unwrap();
+                (tm.actual_notes, tm.normal_notes)
+            })
+            .collect();
+        assert_eq!(ratios, vec![(3, 2), (9, 4), (9, 4), (9, 4), (3, 2)]);
+
+        // Inner brackets use tuplet number 2
+        assert_eq!(tuplet_types(&notes[0]), vec![(StartStop::Start, Some(1))]);
+        assert_eq!(tuplet_types(&notes[1]), vec![(StartStop::Start, Some(2))]);
+        assert!(tuplet_types(&notes[2]).is_empty());
+        assert_eq!(tuplet_types(&notes[3]), vec![(StartStop::Stop, Some(2))]);
+        assert_eq!(tuplet_types(&notes[4]), vec![(StartStop::Stop, Some(1))]);
+
+        assert_eq!(note_duration(&notes[0]), 320);
+        assert_eq!(note_duration(&notes[4]), 320);
+    }
+
+    #[test]
+    fn test_compile_tuplet_with_slur() {
+        let sexpr =
+            crate::sexpr::parse("(tuplet 3 2 (slur (note c4 :8) (note d4 :8)) (note e4 :8))")
+                .unwrap();
+        let notes = compile_tuplet(&sexpr).unwrap();
+        assert_eq!(notes.len(), 3);
+        assert!(notes.iter().all(|n| note_duration(n) == 320));
+
+        // The bracket starts on the slur's first note only
+        assert_eq!(tuplet_types(&notes[0]), vec![(StartStop::Start, Some(1))]);
+        assert!(tuplet_types(&notes[1]).is_empty());
+        assert_eq!(tuplet_types(&notes[2]), vec![(StartStop::Stop, Some(1))]);
+    }
+
+    #[test]
+    fn test_compile_tuplet_triplet_emits_time_modification_and_brackets() {
+        let source =
+            "(score (part :piano (measure (tuplet 3 2 (note c4 :8) (note d4 :8) (note e4 :8)))))";
+        let score = crate::lang::compile(source).unwrap();
+        let xml = crate::musicxml::emit(&score).unwrap();
+
+        let note_xml: Vec<&str> = xml.split("<note>").skip(1).collect();
+        assert_eq!(note_xml.len(), 3);
+        for n in &note_xml {
+            assert!(n.contains("<actual-notes>3</actual-notes>"));
+            assert!(n.contains("<normal-notes>2</normal-notes>"));
+        }
+        assert!(note_xml[0].contains(r#"<tuplet type="start""#));
+        assert!(!note_xml[1].contains("<tuplet "));
+        assert!(note_xml[2].contains(r#"<tuplet type="stop""#));
+    }
 }
@@ -43,20 +43,34 @@
 //! - [`musicxml`] - MusicXML parsing and emission
 //! - [`sexpr`] - S-expression parsing and printing
 //! - [`ir`] - Intermediate representation (MusicXML-faithful)
+//! - [`theory`] - Intervals and pitch arithmetic
+//! - [`abc`] - ABC notation import
+//! - [`transform`] - Whole-score rewrites (enharmonic respelling)
+//! - [`validate`] - Score-level lints (unterminated ties, slurs, etc.)
+//! - [`diagnostic`] - Uniform diagnostics across parsing, compilation, and lints
+//! - [`analyze`] - Score statistics (note count, range, playing time)
+//! - [`report`] - Colored, plain, or captured output for command-line tools
 
 #![warn(missing_docs)]
 #![warn(rustdoc::missing_crate_level_docs)]
 
+pub mod abc;
+pub mod analyze;
+pub mod diagnostic;
 pub mod ir;
 pub mod lang;
 pub mod musicxml;
 pub mod repl;
+pub mod report;
 pub mod sexpr;
+pub mod theory;
+pub mod transform;
+pub mod validate;
 
 // Re-export AST types with cleaner names
 pub use lang::ast::{
-    ArpeggiateDirection, Articulation, BarlineSpec, ClefSpec, DurationBase, DynamicMark,
-    EndingAction, FermataChord as Chord, FermataDuration as Duration, FermataMark,
+    ArpeggiateDirection, Articulation, ArticulationMark, BarlineSpec, ClefSpec, DurationBase,
+    DynamicMark, EndingAction, FermataChord as Chord, FermataDuration as Duration, FermataMark,
     FermataMeasure as Measure, FermataNote as Note, FermataPart as Part, FermataPitch as Pitch,
     FermataRest as Rest, FermataScore as Score, FermataTuplet as Tuplet, KeySpec, LyricSpec,
     MeasureElement, Mode, Ornament, PitchAlter, PitchStep, SlurMark, StemDirection, Syllabic,
@@ -9,29 +9,58 @@
 //! # Compile to MusicXML
 //! fermata compile score.fm -o score.musicxml
 //!
+//! # Compile to compact MusicXML (or use --indent tabs, --indent 4)
+//! fermata compile score.fm --indent none
+//!
+//! # Reformat a file in place (or verify it with --check)
+//! fermata fmt score.fm
+//!
+//! # Summarize a score (notes, range, playing time)
+//! fermata analyze score.fm --format json
+//!
+//! # Import an ABC tune as Fermata Lisp
+//! fermata import-abc tune.abc -o tune.fm
+//!
+//! # Import MusicXML, .mxl, or ABC, detecting the format from the content
+//! cat tune.abc | fermata convert -o tune.fm
+//!
+//! # Report what an import would skip without writing anything
+//! fermata import score.xml --check-only
+//!
 //! # Show reference information
 //! fermata show durations
 //! fermata show targets --format json
+//! fermata show examples
 //!
 //! # Show version
 //! fermata --version
 //!
+//! # Show version, MusicXML version, targets, and build details
+//! fermata version --format json
+//!
 //! # Show help
 //! fermata --help
 //! ```
 
 use std::fs;
 use std::io::{self, Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
 use clap::{Parser, Subcommand, ValueEnum};
-use owo_colors::OwoColorize;
 
-use fermata::lang::{check, compile};
-use fermata::musicxml::{emit, parse};
+use fermata::analyze::{ScoreStats, analyze};
+use fermata::diagnostic::{self, Severity};
+use fermata::lang::{compile, parse_recover, select_measures, select_part};
+use fermata::musicxml::{
+    EmitOptions, Indent, MUSICXML_VERSION, ParseOptions, SkippedElement, emit_mxl,
+    emit_to_writer_with_options, note_type_value_to_string, parse_score_with_skipped, read_mxl,
+};
 use fermata::repl::Repl;
+use fermata::report::{Reporter, Style, reporter};
+use fermata::sexpr::format::format_source;
 use fermata::sexpr::{ToSexpr, print_sexpr};
+use fermata::transform::{auto_barline, to_transposing_parts};
 
 mod show;
 
@@ -56,7 +85,7 @@ struct Cli {
 enum Commands {
     /// Compile a Fermata file to MusicXML
     Compile {
-        /// Input file (use '-' for stdin)
+        /// Input file (use '-' for stdin), or a directory with --output-dir
         #[arg(value_name = "FILE")]
         file: Option<String>,
 
@@ -64,9 +93,33 @@ enum Commands {
         #[arg(short, long, value_name = "FILE")]
         output: Option<String>,
 
+        /// Compile every .fm file under the input directory into this directory, keeping its layout
+        #[arg(long, value_name = "DIR", conflicts_with = "output")]
+        output_dir: Option<String>,
+
         /// Output format
         #[arg(short, long, value_enum, default_value_t = OutputTarget::MusicXml)]
         target: OutputTarget,
+
+        /// MusicXML indentation: a number of spaces, 'tabs', or 'none' for compact output
+        #[arg(long, value_name = "INDENT", default_value = "2", value_parser = parse_indent)]
+        indent: Indent,
+
+        /// Only emit these measures: 'N', 'N-M', or 'N-' (by measure number, inclusive; a pickup is 0)
+        #[arg(long, value_name = "RANGE", value_parser = parse_measure_range)]
+        measures: Option<MeasureRange>,
+
+        /// Only emit the part with this name or id
+        #[arg(long, visible_alias = "select-part", value_name = "PART")]
+        part: Option<String>,
+
+        /// Write transposing instruments' parts at written pitch, a major second up for a B-flat clarinet
+        #[arg(long)]
+        transposing_parts: bool,
+
+        /// Split measures that run past their time signature, tying notes across the new barlines
+        #[arg(long)]
+        auto_barline: bool,
     },
 
     /// Check if a Fermata file is valid
@@ -74,11 +127,61 @@ enum Commands {
         /// Input file (use '-' for stdin)
         #[arg(value_name = "FILE")]
         file: Option<String>,
+
+        /// Treat validation warnings (overfull measures, unterminated ties and slurs) as errors
+        #[arg(long)]
+        strict: bool,
     },
 
-    /// Import MusicXML and convert to Fermata Lisp
+    /// Reformat a Fermata file in place, keeping its comments
+    Fmt {
+        /// Input file (use '-' for stdin, which prints the result)
+        #[arg(value_name = "FILE")]
+        file: Option<String>,
+
+        /// Only check formatting; fail if the file would change
+        #[arg(long, conflicts_with = "stdout")]
+        check: bool,
+
+        /// Print the formatted source instead of rewriting the file
+        #[arg(long)]
+        stdout: bool,
+    },
+
+    /// Summarize a Fermata file: notes, measures, range, and playing time
+    Analyze {
+        /// Input file (use '-' for stdin)
+        #[arg(value_name = "FILE")]
+        file: Option<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+
+    /// Import MusicXML, compressed MusicXML, or ABC and convert to Fermata Lisp
+    #[command(visible_alias = "convert")]
     Import {
-        /// Input MusicXML file (use '-' for stdin)
+        /// Input file (use '-' for stdin)
+        #[arg(value_name = "FILE")]
+        file: Option<String>,
+
+        /// Output file (omit for stdout)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<String>,
+
+        /// Input format (detected from the leading bytes if omitted)
+        #[arg(long, value_enum, value_name = "FORMAT")]
+        from: Option<InputFormat>,
+
+        /// Only report whether the import succeeds and which elements it skips
+        #[arg(long)]
+        check_only: bool,
+    },
+
+    /// Import an ABC tune and convert to Fermata Lisp
+    ImportAbc {
+        /// Input ABC file (use '-' for stdin)
         #[arg(value_name = "FILE")]
         file: Option<String>,
 
@@ -98,21 +201,48 @@ enum Commands {
         format: OutputFormat,
     },
 
+    /// Show the version with the supported MusicXML version, output
+    /// targets, and build details
+    Version {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+
     /// Start the interactive REPL
-    Repl,
+    Repl {
+        /// Don't load or save the line history file
+        #[arg(long)]
+        no_history: bool,
+    },
 }
 
 /// Output target format for compilation
-#[derive(Clone, ValueEnum)]
+#[derive(Clone, Copy, ValueEnum)]
 enum OutputTarget {
     /// MusicXML format
-    #[value(alias = "xml")]
+    #[value(name = "musicxml", aliases = ["xml", "music-xml"])]
     MusicXml,
     /// LilyPond format (not yet implemented)
-    #[value(alias = "ly")]
+    #[value(name = "lilypond", aliases = ["ly", "lily-pond"])]
     LilyPond,
 }
 
+/// Input format for import
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum InputFormat {
+    /// Uncompressed MusicXML
+    #[value(alias = "xml")]
+    MusicXml,
+    /// Compressed MusicXML (zip archive)
+    Mxl,
+    /// ABC notation
+    Abc,
+    /// Fermata Lisp (already Fermata; nothing to import)
+    #[value(alias = "fm")]
+    Fermata,
+}
+
 /// Output format for show commands
 #[derive(Clone, Copy, ValueEnum, Default)]
 pub enum OutputFormat {
@@ -123,6 +253,21 @@ pub enum OutputFormat {
     Json,
 }
 
+/// Naming convention for pitches in `show pitches`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Default)]
+pub enum PitchNotation {
+    /// C D E F G A B, with sharps and flats
+    #[default]
+    English,
+    /// C D E F G A H, with -is and -es suffixes (B is B-flat)
+    German,
+    /// Do Re Mi Fa Sol La Si
+    #[value(alias = "solfège")]
+    Solfege,
+    /// C D E F G A B, with -is and -es suffixes (Bes is B-flat)
+    Dutch,
+}
+
 /// Topics for the show command
 #[derive(Clone, Subcommand)]
 pub enum ShowTopic {
@@ -133,7 +278,11 @@ pub enum ShowTopic {
     /// Duration symbols (:w, :h, :q, :8, etc.)
     Durations,
     /// Pitch notation (C4, D#5, Bb3, etc.)
-    Pitches,
+    Pitches {
+        /// Display pitch names in this naming convention
+        #[arg(long, value_enum, default_value_t = PitchNotation::English)]
+        notation: PitchNotation,
+    },
     /// Available clefs
     Clefs,
     /// Key signatures and modes
@@ -154,6 +303,8 @@ pub enum ShowTopic {
     Noteheads,
     /// Fermata shapes
     Fermatas,
+    /// Complete example scores
+    Examples,
 }
 
 /// Entry point
@@ -179,27 +330,84 @@ fn main() -> ExitCode {
         }
     }
 
+    let mut reporter = reporter(use_colors);
+    let reporter = reporter.as_mut();
+
     match cli.command {
         Some(Commands::Compile {
             file,
             output,
+            output_dir,
             target,
-        }) => cmd_compile(file.as_deref(), output.as_deref(), target, use_colors),
-        Some(Commands::Check { file }) => cmd_check(file.as_deref(), use_colors),
-        Some(Commands::Import { file, output }) => {
-            cmd_import(file.as_deref(), output.as_deref(), use_colors)
+            indent,
+            measures,
+            part,
+            transposing_parts,
+            auto_barline,
+        }) => {
+            let edits = ScoreEdits {
+                measures,
+                part: part.as_deref(),
+                transposing_parts,
+                auto_barline,
+            };
+            match output_dir {
+                Some(dir) => cmd_compile_dir(
+                    file.as_deref(),
+                    &dir,
+                    target,
+                    EmitOptions { indent },
+                    edits,
+                    reporter,
+                ),
+                None => cmd_compile(
+                    file.as_deref(),
+                    output.as_deref(),
+                    target,
+                    EmitOptions { indent },
+                    edits,
+                    reporter,
+                ),
+            }
         }
-        Some(Commands::Show { topic, format }) => show::run(topic, format, use_colors),
-        Some(Commands::Repl) | None => {
-            // Launch the interactive REPL (default when no command given)
-            cmd_repl(use_colors)
+        Some(Commands::Check { file, strict }) => cmd_check(file.as_deref(), strict, reporter),
+        Some(Commands::Fmt {
+            file,
+            check,
+            stdout,
+        }) => cmd_fmt(file.as_deref(), check, stdout, reporter),
+        Some(Commands::Analyze { file, format }) => cmd_analyze(file.as_deref(), format, reporter),
+        Some(Commands::Import {
+            file,
+            output,
+            from,
+            check_only,
+        }) => cmd_import(
+            file.as_deref(),
+            output.as_deref(),
+            from,
+            check_only,
+            reporter,
+        ),
+        Some(Commands::ImportAbc { file, output }) => {
+            cmd_import_abc(file.as_deref(), output.as_deref(), reporter)
         }
+        Some(Commands::Show { topic, format }) => show::run(topic, format, use_colors),
+        Some(Commands::Version { format }) => cmd_version(format, reporter),
+        Some(Commands::Repl { no_history }) => cmd_repl(use_colors, no_history),
+        // Launch the interactive REPL (default when no command given)
+        None => cmd_repl(use_colors, false),
     }
 }
 
 /// REPL command - start interactive session
-fn cmd_repl(use_colors: bool) -> ExitCode {
-    match Repl::new(use_colors) {
+fn cmd_repl(use_colors: bool, no_history: bool) -> ExitCode {
+    let repl = if no_history {
+        Repl::with_history(use_colors, None)
+    } else {
+        Repl::new(use_colors)
+    };
+    match repl {
         Ok(mut repl) => match repl.run() {
             Ok(()) => ExitCode::SUCCESS,
             Err(e) => {
@@ -214,21 +422,14 @@ fn cmd_repl(use_colors: bool) -> ExitCode {
     }
 }
 
-/// Print an error message with optional coloring.
-fn print_error(label: &str, message: &str, use_colors: bool) {
-    if use_colors {
-        eprintln!("{}: {}", label.red(), message);
-    } else {
-        eprintln!("{}: {}", label, message);
-    }
-}
-
 /// Compile command
 fn cmd_compile(
     file: Option<&str>,
     output: Option<&str>,
     target: OutputTarget,
-    use_colors: bool,
+    options: EmitOptions,
+    edits: ScoreEdits,
+    reporter: &mut dyn Reporter,
 ) -> ExitCode {
     // Default to stdin if no file specified
     let input_path = file.unwrap_or("-");
@@ -237,7 +438,7 @@ fn cmd_compile(
     let source = match read_input(input_path) {
         Ok(s) => s,
         Err(e) => {
-            print_error("Error reading input", &e.to_string(), use_colors);
+            reporter.error("Error reading input", &e.to_string());
             return ExitCode::FAILURE;
         }
     };
@@ -246,42 +447,186 @@ fn cmd_compile(
     let score = match compile(&source) {
         Ok(s) => s,
         Err(e) => {
-            print_error("Compilation error", &e.to_string(), use_colors);
+            // Syntax errors are re-parsed for their positions
+            let (_, errors) = parse_recover(&source);
+            if errors.is_empty() {
+                reporter.error("Compilation error", &e.render(&source));
+            }
+            for error in &errors {
+                reporter.error("Compilation error", &error.render(&source));
+            }
             return ExitCode::FAILURE;
         }
     };
 
-    // Generate output based on target
-    let output_content = match target {
-        OutputTarget::MusicXml => match emit(&score) {
-            Ok(x) => x,
+    // Extract the requested part
+    let mut score = match edits.part {
+        Some(name) => match select_part(score, name) {
+            Ok(s) => s,
             Err(e) => {
-                print_error("MusicXML generation error", &e.to_string(), use_colors);
+                reporter.error("Part selection error", &e.to_string());
                 return ExitCode::FAILURE;
             }
         },
+        None => score,
+    };
+
+    if edits.auto_barline {
+        auto_barline(&mut score);
+    }
+    if edits.transposing_parts {
+        to_transposing_parts(&mut score);
+    }
+
+    // Excerpt the requested measures
+    let score = match edits.measures {
+        Some((first, last)) => match select_measures(score, first, last) {
+            Ok(s) => s,
+            Err(e) => {
+                reporter.error("Measure selection error", &e.to_string());
+                return ExitCode::FAILURE;
+            }
+        },
+        None => score,
+    };
+
+    // Compressed MusicXML is chosen by the output extension
+    if matches!(target, OutputTarget::MusicXml) && output.is_some_and(is_mxl_path) {
+        let bytes = match emit_mxl(&score) {
+            Ok(b) => b,
+            Err(e) => {
+                reporter.error("MusicXML generation error", &e.to_string());
+                return ExitCode::FAILURE;
+            }
+        };
+        return match write_output(output, &bytes) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                reporter.error("Error writing output", &e.to_string());
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    // Emit in full before writing, so a failure leaves no partial output
+    match target {
+        OutputTarget::MusicXml => {
+            let mut bytes = Vec::new();
+            if let Err(e) = emit_to_writer_with_options(&score, &mut bytes, &options) {
+                reporter.error("MusicXML generation error", &e.to_string());
+                return ExitCode::FAILURE;
+            }
+            match write_output(output, &bytes) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    reporter.error("Error writing output", &e.to_string());
+                    ExitCode::FAILURE
+                }
+            }
+        }
         OutputTarget::LilyPond => {
-            print_error(
-                "Error",
-                "LilyPond output is not yet implemented",
-                use_colors,
-            );
+            reporter.error("Error", "LilyPond output is not yet implemented");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Compile every `.fm` file under `input` into `output_dir`.
+///
+/// The directory layout is mirrored, so `src/act1/overture.fm` becomes
+/// `build/act1/overture.musicxml`. Each file is reported as it compiles;
+/// the command fails if any file does.
+fn cmd_compile_dir(
+    input: Option<&str>,
+    output_dir: &str,
+    target: OutputTarget,
+    options: EmitOptions,
+    edits: ScoreEdits,
+    reporter: &mut dyn Reporter,
+) -> ExitCode {
+    let extension = match target {
+        OutputTarget::MusicXml => "musicxml",
+        OutputTarget::LilyPond => {
+            reporter.error("Error", "LilyPond output is not yet implemented");
             return ExitCode::FAILURE;
         }
     };
+    let root = match input {
+        Some("-") | None => {
+            reporter.error("Error", "--output-dir needs an input directory, not stdin");
+            return ExitCode::FAILURE;
+        }
+        Some(path) => Path::new(path),
+    };
 
-    // Write output
-    match write_output(output, &output_content) {
-        Ok(()) => ExitCode::SUCCESS,
-        Err(e) => {
-            print_error("Error writing output", &e.to_string(), use_colors);
-            ExitCode::FAILURE
+    // A single file compiles into the directory under its own name
+    let (base, sources) = if root.is_file() {
+        let name = PathBuf::from(root.file_name().unwrap_or_default());
+        (root.parent().unwrap_or(Path::new("")), vec![name])
+    } else {
+        let mut sources = Vec::new();
+        if let Err(e) = collect_sources(root, Path::new(""), &mut sources) {
+            reporter.error("Error reading input", &format!("{}: {}", root.display(), e));
+            return ExitCode::FAILURE;
+        }
+        sources.sort();
+        (root, sources)
+    };
+
+    let mut failed = 0;
+    for relative in &sources {
+        let source = base.join(relative);
+        let output = Path::new(output_dir)
+            .join(relative)
+            .with_extension(extension);
+        let status = cmd_compile(
+            source.to_str(),
+            output.to_str(),
+            target,
+            options.clone(),
+            edits,
+            reporter,
+        );
+        if status == ExitCode::SUCCESS {
+            reporter.success(
+                "OK",
+                &format!("{} -> {}", source.display(), output.display()),
+            );
+        } else {
+            reporter.error("Failed", &source.display().to_string());
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        reporter.error(
+            "Error",
+            &format!("{} of {} file(s) failed to compile", failed, sources.len()),
+        );
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Append the `.fm` files under `dir`, as paths relative to the starting
+/// directory, to `sources`.
+fn collect_sources(dir: &Path, relative: &Path, sources: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = relative.join(entry.file_name());
+        if path.is_dir() {
+            collect_sources(&path, &name, sources)?;
+        } else if path.extension().is_some_and(|ext| ext == "fm") {
+            sources.push(name);
         }
     }
+    Ok(())
 }
 
 /// Check command
-fn cmd_check(file: Option<&str>, use_colors: bool) -> ExitCode {
+fn cmd_check(file: Option<&str>, strict: bool, reporter: &mut dyn Reporter) -> ExitCode {
     // Default to stdin if no file specified
     let input_path = file.unwrap_or("-");
 
@@ -289,55 +634,334 @@ fn cmd_check(file: Option<&str>, use_colors: bool) -> ExitCode {
     let source = match read_input(input_path) {
         Ok(s) => s,
         Err(e) => {
-            print_error("Error reading input", &e.to_string(), use_colors);
+            reporter.error("Error reading input", &e.to_string());
             return ExitCode::FAILURE;
         }
     };
 
-    // Check
-    match check(&source) {
-        Ok(()) => {
-            if use_colors {
-                println!("{}: {} is valid", "OK".green(), input_path);
-            } else {
-                println!("OK: {} is valid", input_path);
+    // Syntax errors, compile errors, and lints, rendered alike; lints are
+    // warnings unless --strict makes them errors
+    let diagnostics = diagnostic::check(&source);
+    for diagnostic in &diagnostics {
+        let location = match &diagnostic.span {
+            Some(span) => format!("{}:{}:{}", input_path, span.line, span.column),
+            None => input_path.to_string(),
+        };
+        let message = diagnostic.render(&source);
+        match diagnostic.severity {
+            Severity::Warning if !strict => {
+                reporter.warning(&format!("Warning in {}", location), &message)
             }
-            ExitCode::SUCCESS
+            _ => reporter.error(&format!("Error in {}", location), &message),
         }
+    }
+
+    let failed = diagnostics.iter().any(|d| d.is_error() || strict);
+    if failed {
+        return ExitCode::FAILURE;
+    }
+    reporter.success("OK", &format!("{} is valid", input_path));
+    ExitCode::SUCCESS
+}
+
+/// Format command - rewrite a file in canonical layout, like `rustfmt`
+///
+/// With `check`, nothing is written and the command fails if the file is
+/// not already formatted. Formatted source goes to stdout with `stdout` or
+/// when reading stdin; otherwise the file is rewritten only if it changes.
+fn cmd_fmt(file: Option<&str>, check: bool, stdout: bool, reporter: &mut dyn Reporter) -> ExitCode {
+    let input_path = file.unwrap_or("-");
+
+    let source = match read_input(input_path) {
+        Ok(s) => s,
         Err(e) => {
-            if use_colors {
-                eprintln!("{} in {}: {}", "Error".red(), input_path, e);
+            reporter.error("Error reading input", &e.to_string());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let formatted = match format_source(&source) {
+        Ok(f) => f,
+        Err(e) => {
+            reporter.error(&format!("Error in {}", input_path), &e.to_string());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if check {
+        if formatted != source {
+            reporter.error(
+                "Not formatted",
+                &format!("{} would be reformatted", input_path),
+            );
+            return ExitCode::FAILURE;
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let result = if stdout || input_path == "-" {
+        write_output(None, formatted.as_bytes())
+    } else if formatted != source {
+        write_output(Some(input_path), formatted.as_bytes())
+    } else {
+        Ok(())
+    };
+    if let Err(e) = result {
+        reporter.error("Error writing output", &e.to_string());
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+/// Analyze command - print summary statistics for a score
+fn cmd_analyze(file: Option<&str>, format: OutputFormat, reporter: &mut dyn Reporter) -> ExitCode {
+    let input_path = file.unwrap_or("-");
+    let source = match read_input(input_path) {
+        Ok(s) => s,
+        Err(e) => {
+            reporter.error("Error reading input", &e.to_string());
+            return ExitCode::FAILURE;
+        }
+    };
+    let score = match compile(&source) {
+        Ok(s) => s,
+        Err(e) => {
+            reporter.error("Compilation error", &e.render(&source));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let stats = analyze(&score);
+    match format {
+        OutputFormat::Text => print_stats(&stats, reporter),
+        OutputFormat::Json => reporter.out(&stats_json(&stats)),
+    }
+    ExitCode::SUCCESS
+}
+
+/// Name of a note value with its dots, e.g. "dotted quarter".
+fn duration_name(stats: &ScoreStats) -> Option<String> {
+    stats.common_duration.map(|(value, dots)| {
+        let prefix = match dots {
+            0 => "",
+            1 => "dotted ",
+            2 => "double-dotted ",
+            _ => "multiply-dotted ",
+        };
+        format!("{}{}", prefix, note_type_value_to_string(&value))
+    })
+}
+
+/// Print score statistics as aligned text.
+fn print_stats(stats: &ScoreStats, reporter: &mut dyn Reporter) {
+    let row = |reporter: &mut dyn Reporter, label: &str, value: String| {
+        let label = format!("{:<12}", format!("{}:", label));
+        let line = format!("{} {}", reporter.paint(&label, Style::Emphasis), value);
+        reporter.out(&line);
+    };
+    let none = || "-".to_string();
+
+    row(reporter, "Notes", stats.notes.to_string());
+    row(reporter, "Parts", stats.parts.len().to_string());
+    for part in &stats.parts {
+        reporter.out(&format!(
+            "  {} {}: {} measures",
+            part.id, part.name, part.measures
+        ));
+    }
+    row(
+        reporter,
+        "Range",
+        match (&stats.lowest, &stats.highest) {
+            (Some(low), Some(high)) => format!("{} - {}", low, high),
+            _ => none(),
+        },
+    );
+    row(
+        reporter,
+        "Most common",
+        duration_name(stats).unwrap_or_else(none),
+    );
+    row(
+        reporter,
+        "Time",
+        if stats.time_signatures.is_empty() {
+            none()
+        } else {
+            stats
+                .time_signatures
+                .iter()
+                .map(|t| format!("{} (m. {})", t.signature, t.measure))
+                .collect::<Vec<_>>()
+                .join(", ")
+        },
+    );
+    let seconds = stats.duration.as_secs_f64().round() as u64;
+    row(
+        reporter,
+        "Length",
+        format!("{}:{:02}", seconds / 60, seconds % 60),
+    );
+}
+
+/// Render score statistics as pretty-printed JSON.
+fn stats_json(stats: &ScoreStats) -> String {
+    let value = serde_json::json!({
+        "notes": stats.notes,
+        "parts": stats.parts.iter().map(|part| serde_json::json!({
+            "id": part.id,
+            "name": part.name,
+            "measures": part.measures,
+        })).collect::<Vec<_>>(),
+        "lowest": stats.lowest.as_ref().map(ToString::to_string),
+        "highest": stats.highest.as_ref().map(ToString::to_string),
+        "most_common_duration": duration_name(stats),
+        "time_signatures": stats.time_signatures.iter().map(|t| serde_json::json!({
+            "measure": t.measure,
+            "signature": t.signature,
+        })).collect::<Vec<_>>(),
+        "duration_seconds": stats.duration.as_secs_f64(),
+    });
+    serde_json::to_string_pretty(&value).expect("JSON serialization failed")
+}
+
+/// Version command - report capabilities and build details for bug reports
+fn cmd_version(format: OutputFormat, reporter: &mut dyn Reporter) -> ExitCode {
+    let targets: Vec<(String, bool)> = OutputTarget::value_variants()
+        .iter()
+        .filter_map(|target| {
+            let name = target.to_possible_value()?.get_name().to_string();
+            Some((name, matches!(target, OutputTarget::MusicXml)))
+        })
+        .collect();
+    let or_unknown = |value: &'static str| if value.is_empty() { "unknown" } else { value };
+    let commit = or_unknown(env!("FERMATA_GIT_COMMIT"));
+    let build_target = or_unknown(env!("FERMATA_BUILD_TARGET"));
+    let profile = or_unknown(env!("FERMATA_BUILD_PROFILE"));
+    let features: Vec<&str> = [("render", cfg!(feature = "render"))]
+        .into_iter()
+        .filter_map(|(name, enabled)| enabled.then_some(name))
+        .collect();
+
+    match format {
+        OutputFormat::Text => {
+            let row = |reporter: &mut dyn Reporter, label: &str, value: &str| {
+                let label = format!("{:<10}", format!("{}:", label));
+                let line = format!("{} {}", reporter.paint(&label, Style::Emphasis), value);
+                reporter.out(&line);
+            };
+            reporter.out(&format!("fermata {}", env!("CARGO_PKG_VERSION")));
+            row(reporter, "MusicXML", MUSICXML_VERSION);
+            let targets = targets
+                .iter()
+                .map(|(name, implemented)| match implemented {
+                    true => name.clone(),
+                    false => format!("{} (not yet implemented)", name),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            row(reporter, "Targets", &targets);
+            row(reporter, "Commit", commit);
+            row(
+                reporter,
+                "Build",
+                &format!("{} ({})", build_target, profile),
+            );
+            let features = if features.is_empty() {
+                "-".to_string()
             } else {
-                eprintln!("Error in {}: {}", input_path, e);
-            }
-            ExitCode::FAILURE
+                features.join(", ")
+            };
+            row(reporter, "Features", &features);
+        }
+        OutputFormat::Json => {
+            let value = serde_json::json!({
+                "version": env!("CARGO_PKG_VERSION"),
+                "musicxml_version": MUSICXML_VERSION,
+                "targets": targets.iter().map(|(name, implemented)| serde_json::json!({
+                    "name": name,
+                    "implemented": implemented,
+                })).collect::<Vec<_>>(),
+                "commit": commit,
+                "build_target": build_target,
+                "profile": profile,
+                "features": features,
+            });
+            reporter.out(&serde_json::to_string_pretty(&value).expect("JSON serialization failed"));
         }
     }
+    ExitCode::SUCCESS
 }
 
 /// Import command - convert MusicXML to Fermata Lisp
-fn cmd_import(file: Option<&str>, output: Option<&str>, use_colors: bool) -> ExitCode {
+fn cmd_import(
+    file: Option<&str>,
+    output: Option<&str>,
+    from: Option<InputFormat>,
+    check_only: bool,
+    reporter: &mut dyn Reporter,
+) -> ExitCode {
     // Default to stdin if no file specified
     let input_path = file.unwrap_or("-");
 
-    // Read input
-    let xml = match read_input(input_path) {
-        Ok(s) => s,
+    let bytes = match read_input_bytes(input_path) {
+        Ok(b) => b,
         Err(e) => {
-            print_error("Error reading input", &e.to_string(), use_colors);
+            reporter.error("Error reading input", &e.to_string());
             return ExitCode::FAILURE;
         }
     };
 
-    // Parse MusicXML
-    let score = match parse(&xml) {
-        Ok(s) => s,
-        Err(e) => {
-            print_error("MusicXML parse error", &e.to_string(), use_colors);
+    // An explicit --from wins; otherwise sniff the content, then the extension
+    let format = from.or_else(|| detect_format(&bytes)).unwrap_or_else(|| {
+        if is_mxl_path(input_path) {
+            InputFormat::Mxl
+        } else if is_abc_path(input_path) {
+            InputFormat::Abc
+        } else {
+            InputFormat::MusicXml
+        }
+    });
+
+    let options = ParseOptions {
+        record_skipped: check_only,
+        ..ParseOptions::default()
+    };
+    let parse_xml = |xml: String| {
+        parse_score_with_skipped(&xml, &options)
+            .map_err(|e| ("MusicXML parse error", e.to_string()))
+    };
+    let parsed = match format {
+        InputFormat::Mxl => read_mxl(&bytes)
+            .map_err(|e| ("MusicXML parse error", e.to_string()))
+            .and_then(parse_xml),
+        InputFormat::MusicXml => String::from_utf8(bytes)
+            .map_err(|e| ("Error reading input", e.to_string()))
+            .and_then(parse_xml),
+        InputFormat::Abc => String::from_utf8(bytes)
+            .map_err(|e| ("Error reading input", e.to_string()))
+            .and_then(|abc| {
+                fermata::abc::parse(&abc).map_err(|e| ("ABC parse error", e.to_string()))
+            })
+            .map(|score| (score, Vec::new())),
+        InputFormat::Fermata => Err((
+            "Nothing to import",
+            "input is already Fermata Lisp; use 'fermata compile' instead".to_string(),
+        )),
+    };
+    let (score, skipped) = match parsed {
+        Ok(parsed) => parsed,
+        Err((label, message)) => {
+            reporter.error(label, &message);
             return ExitCode::FAILURE;
         }
     };
 
+    if check_only {
+        report_skipped(input_path, &skipped, reporter);
+        return ExitCode::SUCCESS;
+    }
+
     // Convert to S-expression
     let sexpr = score.to_sexpr();
 
@@ -345,15 +969,136 @@ fn cmd_import(file: Option<&str>, output: Option<&str>, use_colors: bool) -> Exi
     let output_content = print_sexpr(&sexpr);
 
     // Write output
-    match write_output(output, &output_content) {
+    match write_output(output, output_content.as_bytes()) {
         Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
-            print_error("Error writing output", &e.to_string(), use_colors);
+            reporter.error("Error writing output", &e.to_string());
             ExitCode::FAILURE
         }
     }
 }
 
+/// Report a successful `import --check-only`, listing each skipped element
+/// once with the number of times it occurred.
+fn report_skipped(input_path: &str, skipped: &[SkippedElement], reporter: &mut dyn Reporter) {
+    if skipped.is_empty() {
+        reporter.success("OK", &format!("{} imports fully", input_path));
+        return;
+    }
+
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for element in skipped {
+        match counts.iter_mut().find(|(seen, _)| *seen == element.name) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((&element.name, 1)),
+        }
+    }
+
+    reporter.success(
+        "OK",
+        &format!(
+            "{} imports, skipping {} unsupported element(s):",
+            input_path,
+            skipped.len()
+        ),
+    );
+    for (name, count) in counts {
+        reporter.out(&format!("  {} ({})", name, count));
+    }
+}
+
+/// Import ABC command - convert an ABC tune to Fermata Lisp
+fn cmd_import_abc(
+    file: Option<&str>,
+    output: Option<&str>,
+    reporter: &mut dyn Reporter,
+) -> ExitCode {
+    // Default to stdin if no file specified
+    let input_path = file.unwrap_or("-");
+
+    // Read input
+    let abc = match read_input(input_path) {
+        Ok(s) => s,
+        Err(e) => {
+            reporter.error("Error reading input", &e.to_string());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Parse ABC
+    let score = match fermata::abc::parse(&abc) {
+        Ok(s) => s,
+        Err(e) => {
+            reporter.error("ABC parse error", &e.to_string());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Convert to S-expression and write output
+    let output_content = print_sexpr(&score.to_sexpr());
+    match write_output(output, output_content.as_bytes()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            reporter.error("Error writing output", &e.to_string());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Parse an `--indent` value: a number of spaces, `tabs`, or `none`.
+fn parse_indent(value: &str) -> Result<Indent, String> {
+    match value {
+        "none" | "compact" => Ok(Indent::None),
+        "tab" | "tabs" => Ok(Indent::Tabs),
+        _ => value.parse().map(Indent::Spaces).map_err(|_| {
+            format!(
+                "expected a number of spaces, 'tabs', or 'none', got '{}'",
+                value
+            )
+        }),
+    }
+}
+
+/// A measure range from `--measures`: first measure and optional last.
+type MeasureRange = (usize, Option<usize>);
+
+/// How `compile` narrows and rewrites the score before emitting it.
+#[derive(Clone, Copy)]
+struct ScoreEdits<'a> {
+    /// Measures to keep, if not all
+    measures: Option<MeasureRange>,
+    /// Name or id of the only part to keep
+    part: Option<&'a str>,
+    /// Whether to write transposing parts at written pitch
+    transposing_parts: bool,
+    /// Whether to split overfull measures
+    auto_barline: bool,
+}
+
+/// Parse a `--measures` value: `5`, `5-12`, or `5-`.
+fn parse_measure_range(value: &str) -> Result<MeasureRange, String> {
+    let number = |text: &str| {
+        text.trim()
+            .parse::<usize>()
+            .ok()
+            .ok_or_else(|| format!("expected a measure number, got '{}'", text))
+    };
+    match value.split_once('-') {
+        Some((first, "")) => Ok((number(first)?, None)),
+        Some((first, last)) => {
+            let (first, last) = (number(first)?, number(last)?);
+            if last < first {
+                return Err(format!("range {} ends before it starts", value));
+            }
+            Ok((first, Some(last)))
+        }
+        None => {
+            let n = number(value)?;
+            Ok((n, Some(n)))
+        }
+    }
+}
+
 /// Read input from file or stdin
 fn read_input(path: &str) -> io::Result<String> {
     if path == "-" {
@@ -365,22 +1110,125 @@ fn read_input(path: &str) -> io::Result<String> {
     }
 }
 
-/// Write output to file or stdout
-fn write_output(path: Option<&str>, content: &str) -> io::Result<()> {
-    match path {
+/// Read raw input bytes from file or stdin
+fn read_input_bytes(path: &str) -> io::Result<Vec<u8>> {
+    if path == "-" {
+        let mut buffer = Vec::new();
+        io::stdin().read_to_end(&mut buffer)?;
+        Ok(buffer)
+    } else {
+        fs::read(path)
+    }
+}
+
+/// Guess an input format from its leading bytes.
+///
+/// A zip header means compressed MusicXML, an XML declaration or `<score-`
+/// root means MusicXML, `(` means Fermata Lisp, and an `X:` or `T:` header
+/// field means ABC. A byte-order mark, leading whitespace, and ABC `%`
+/// comment lines such as `%abc-2.1` are skipped.
+fn detect_format(bytes: &[u8]) -> Option<InputFormat> {
+    if bytes.starts_with(b"PK\x03\x04") {
+        return Some(InputFormat::Mxl);
+    }
+    let text = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let mut text = text.trim_ascii_start();
+    while text.starts_with(b"%") {
+        let line_end = text.iter().position(|&b| b == b'\n').unwrap_or(text.len());
+        text = text[line_end..].trim_ascii_start();
+    }
+    if text.starts_with(b"<?xml") || text.starts_with(b"<score-") {
+        Some(InputFormat::MusicXml)
+    } else if text.starts_with(b"(") {
+        Some(InputFormat::Fermata)
+    } else if text.starts_with(b"X:") || text.starts_with(b"T:") {
+        Some(InputFormat::Abc)
+    } else {
+        None
+    }
+}
+
+/// Whether a path names a compressed MusicXML (`.mxl`) file
+fn is_mxl_path(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("mxl"))
+}
+
+/// Whether a path names an ABC notation (`.abc`) file
+fn is_abc_path(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("abc"))
+}
+
+/// Write output to file or stdout.
+///
+/// A file is written beside its destination under a temporary name and
+/// renamed into place once complete, creating parent directories as
+/// needed, so a failed write never leaves it empty or half-written.
+fn write_output(path: Option<&str>, content: &[u8]) -> io::Result<()> {
+    let path = match path {
         Some("-") | None => {
-            let mut stdout = io::stdout().lock();
-            stdout.write_all(content.as_bytes())?;
-            stdout.flush()
-        }
-        Some(p) => {
-            // Create parent directories if needed
-            if let Some(parent) = Path::new(p).parent() {
-                if !parent.as_os_str().is_empty() {
-                    fs::create_dir_all(parent)?;
-                }
-            }
-            fs::write(p, content)
+            let mut out = io::stdout().lock();
+            out.write_all(content)?;
+            return out.flush();
         }
+        Some(p) => Path::new(p),
+    };
+    let name = path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a file path", path.display()),
+        )
+    })?;
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(name);
+    temp_name.push(".tmp");
+    let temp = path.with_file_name(temp_name);
+    fs::write(&temp, content)
+        .and_then(|()| fs::rename(&temp, path))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&temp);
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_format_magic_prefixes() {
+        assert_eq!(detect_format(b"PK\x03\x04rest"), Some(InputFormat::Mxl));
+        assert_eq!(
+            detect_format(b"<?xml version=\"1.0\"?>\n<score-partwise>"),
+            Some(InputFormat::MusicXml)
+        );
+        assert_eq!(
+            detect_format(b"\xEF\xBB\xBF  <score-partwise version=\"4.0\">"),
+            Some(InputFormat::MusicXml)
+        );
+        assert_eq!(
+            detect_format(b"\n(score (part :piano))"),
+            Some(InputFormat::Fermata)
+        );
+        assert_eq!(detect_format(b"X:1\nT:Tune\nK:C\n"), Some(InputFormat::Abc));
+        assert_eq!(detect_format(b"T:Untitled\nK:G\n"), Some(InputFormat::Abc));
+        assert_eq!(
+            detect_format(b"%abc-2.1\n% a comment\n\nX:1\nK:C\n"),
+            Some(InputFormat::Abc)
+        );
+    }
+
+    #[test]
+    fn test_detect_format_unknown_content() {
+        assert_eq!(detect_format(b""), None);
+        assert_eq!(detect_format(b"hello"), None);
     }
 }
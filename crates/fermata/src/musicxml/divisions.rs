@@ -132,7 +132,9 @@ pub fn apply_time_modification(duration: u32, time_mod: &TimeModification) -> u3
 /// Calculate the complete duration for a note.
 ///
 /// This function combines note type, dots, and time modification to calculate
-/// the final duration in divisions.
+/// the final duration in divisions. It delegates to
+/// [`divisions_for`](crate::ir::duration::divisions_for), which documents the
+/// rounding rule for durations that don't divide evenly.
 ///
 /// # Arguments
 ///
@@ -181,12 +183,7 @@ pub fn calculate_duration(
     time_modification: Option<&TimeModification>,
     divisions: u32,
 ) -> u32 {
-    let base = note_type_to_divisions(note_type, divisions);
-    let dotted = apply_dots(base, dots);
-    match time_modification {
-        Some(tm) => apply_time_modification(dotted, tm),
-        None => dotted,
-    }
+    crate::ir::duration::divisions_for(*note_type, dots as u32, time_modification, divisions) as u32
 }
 
 #[cfg(test)]
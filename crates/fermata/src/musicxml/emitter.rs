@@ -34,4 +34,6 @@ mod voice;
 
 // Re-export the main public API
 pub use helpers::note_type_value_to_string;
-pub use score::emit_score;
+pub use score::{
+    emit_score, emit_score_to_writer, emit_score_to_writer_with_options, emit_score_with_options,
+};
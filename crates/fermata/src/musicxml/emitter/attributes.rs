@@ -1,17 +1,19 @@
 //! Attributes emission functions for MusicXML.
 //!
 //! This module handles the emission of attributes elements including key signatures,
-//! time signatures, clefs, and transpose.
+//! time signatures, clefs, transpose, and measure styles.
 
 use crate::ir::attributes::{
-    Attributes, Cancel, Clef, Key, KeyContent, Time, TimeContent, Transpose,
+    Attributes, Cancel, Clef, Key, KeyContent, MeasureStyle, MeasureStyleContent, PartSymbol, Time,
+    TimeContent, Transpose,
 };
 use crate::musicxml::EmitError;
 use crate::musicxml::writer::{ElementBuilder, XmlWriter};
 
 use super::helpers::{
-    accidental_value_to_string, cancel_location_to_string, clef_sign_to_string, mode_to_string,
-    step_to_string, time_symbol_to_string, yes_no_to_string,
+    accidental_value_to_string, cancel_location_to_string, clef_sign_to_string,
+    group_symbol_value_to_string, mode_to_string, start_stop_to_string, step_to_string,
+    time_symbol_to_string, yes_no_to_string,
 };
 
 /// Emit an attributes element.
@@ -55,7 +57,10 @@ pub(crate) fn emit_attributes(w: &mut XmlWriter, attrs: &Attributes) -> Result<(
             .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
     }
 
-    // part-symbol - skipped for now
+    // part-symbol
+    if let Some(ref part_symbol) = attrs.part_symbol {
+        emit_part_symbol(w, part_symbol)?;
+    }
 
     // instruments
     if let Some(instruments) = attrs.instruments {
@@ -75,13 +80,51 @@ pub(crate) fn emit_attributes(w: &mut XmlWriter, attrs: &Attributes) -> Result<(
         emit_transpose(w, transpose)?;
     }
 
-    // measure-style* - skipped for now
+    // measure-style*
+    for style in &attrs.measure_styles {
+        emit_measure_style(w, style)?;
+    }
 
     w.end_element("attributes")
         .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
     Ok(())
 }
 
+/// Emit a part-symbol element.
+fn emit_part_symbol(w: &mut XmlWriter, part_symbol: &PartSymbol) -> Result<(), EmitError> {
+    let mut elem = ElementBuilder::new("part-symbol");
+    if let Some(top) = part_symbol.top_staff {
+        elem = elem.attr("top-staff", &top.to_string());
+    }
+    if let Some(bottom) = part_symbol.bottom_staff {
+        elem = elem.attr("bottom-staff", &bottom.to_string());
+    }
+    let position = &part_symbol.position;
+    if let Some(dx) = position.default_x {
+        elem = elem.attr("default-x", &dx.to_string());
+    }
+    if let Some(dy) = position.default_y {
+        elem = elem.attr("default-y", &dy.to_string());
+    }
+    if let Some(rx) = position.relative_x {
+        elem = elem.attr("relative-x", &rx.to_string());
+    }
+    if let Some(ry) = position.relative_y {
+        elem = elem.attr("relative-y", &ry.to_string());
+    }
+    if let Some(ref color) = part_symbol.color {
+        elem = elem.attr("color", color);
+    }
+
+    w.write_start(elem)
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    w.write_text(group_symbol_value_to_string(&part_symbol.value))
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    w.end_element("part-symbol")
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    Ok(())
+}
+
 /// Emit a key element.
 pub(crate) fn emit_key(w: &mut XmlWriter, key: &Key) -> Result<(), EmitError> {
     let mut elem = ElementBuilder::new("key");
@@ -254,14 +297,65 @@ pub(crate) fn emit_transpose(w: &mut XmlWriter, transpose: &Transpose) -> Result
     Ok(())
 }
 
+/// Emit a measure-style element.
+pub(crate) fn emit_measure_style(w: &mut XmlWriter, style: &MeasureStyle) -> Result<(), EmitError> {
+    let mut elem = ElementBuilder::new("measure-style");
+    if let Some(num) = style.number {
+        elem = elem.attr("number", &num.to_string());
+    }
+    w.write_start(elem)
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+
+    match &style.content {
+        MeasureStyleContent::MultipleRest { count, use_symbols } => {
+            let mut elem = ElementBuilder::new("multiple-rest");
+            if let Some(use_symbols) = use_symbols {
+                elem = elem.attr("use-symbols", yes_no_to_string(use_symbols));
+            }
+            w.write_start(elem)
+                .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+            w.write_text(&count.to_string())
+                .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+            w.end_element("multiple-rest")
+                .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        }
+        MeasureStyleContent::MeasureRepeat { r#type, slashes } => {
+            let elem = ElementBuilder::new("measure-repeat")
+                .attr("type", start_stop_to_string(r#type))
+                .optional_attr("slashes", slashes);
+            w.empty_element_with_attrs(elem)
+                .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        }
+        MeasureStyleContent::BeatRepeat { r#type, slashes } => {
+            let elem = ElementBuilder::new("beat-repeat")
+                .attr("type", start_stop_to_string(r#type))
+                .optional_attr("slashes", slashes);
+            w.empty_element_with_attrs(elem)
+                .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        }
+        MeasureStyleContent::Slash { r#type, use_stems } => {
+            let mut elem = ElementBuilder::new("slash").attr("type", start_stop_to_string(r#type));
+            if let Some(use_stems) = use_stems {
+                elem = elem.attr("use-stems", yes_no_to_string(use_stems));
+            }
+            w.empty_element_with_attrs(elem)
+                .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        }
+    }
+
+    w.end_element("measure-style")
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::ir::attributes::{
-        CancelLocation, ClefSign, KeyContent, KeyStep, Mode, TimeContent, TimeSignature,
-        TimeSymbol, TraditionalKey,
+        CancelLocation, ClefSign, GroupSymbolValue, KeyContent, KeyStep, Mode, TimeContent,
+        TimeSignature, TimeSymbol, TraditionalKey,
     };
-    use crate::ir::common::{AccidentalValue, Editorial, YesNo};
+    use crate::ir::common::{AccidentalValue, Editorial, Position, StartStop, YesNo};
     use crate::ir::pitch::Step;
 
     // ==========================================================================
@@ -395,6 +489,31 @@ mod tests {
         assert!(xml.contains("<instruments>3</instruments>"));
     }
 
+    #[test]
+    fn test_emit_attributes_with_part_symbol() {
+        let mut w = XmlWriter::new();
+        let attrs = Attributes {
+            staves: Some(2),
+            part_symbol: Some(PartSymbol {
+                value: GroupSymbolValue::Brace,
+                top_staff: Some(1),
+                bottom_staff: Some(2),
+                position: Position::default(),
+                color: None,
+            }),
+            instruments: Some(1),
+            ..Attributes::default()
+        };
+
+        emit_attributes(&mut w, &attrs).unwrap();
+        let xml = w.into_string().unwrap();
+
+        let part_symbol = r#"<part-symbol top-staff="1" bottom-staff="2">brace</part-symbol>"#;
+        let symbol_at = xml.find(part_symbol).expect(&xml);
+        assert!(xml.find("<staves>").unwrap() < symbol_at, "{}", xml);
+        assert!(symbol_at < xml.find("<instruments>").unwrap(), "{}", xml);
+    }
+
     #[test]
     fn test_emit_attributes_with_transpose() {
         let mut w = XmlWriter::new();
@@ -426,6 +545,41 @@ mod tests {
         assert!(xml.contains("</transpose>"));
     }
 
+    #[test]
+    fn test_emit_attributes_with_measure_styles() {
+        let mut w = XmlWriter::new();
+        let attrs = Attributes {
+            measure_styles: vec![
+                MeasureStyle {
+                    number: None,
+                    content: MeasureStyleContent::MultipleRest {
+                        count: 4,
+                        use_symbols: Some(YesNo::No),
+                    },
+                },
+                MeasureStyle {
+                    number: Some(2),
+                    content: MeasureStyleContent::Slash {
+                        r#type: StartStop::Start,
+                        use_stems: None,
+                    },
+                },
+            ],
+            ..Default::default()
+        };
+
+        emit_attributes(&mut w, &attrs).unwrap();
+        let xml = w.into_string().unwrap();
+
+        assert!(
+            xml.contains(r#"<multiple-rest use-symbols="no">4</multiple-rest>"#),
+            "{}",
+            xml
+        );
+        assert!(xml.contains(r#"<measure-style number="2">"#), "{}", xml);
+        assert!(xml.contains(r#"<slash type="start"/>"#), "{}", xml);
+    }
+
     #[test]
     fn test_emit_attributes_with_multiple_keys() {
         let mut w = XmlWriter::new();
@@ -470,6 +624,42 @@ mod tests {
         assert_eq!(key_count, 2);
     }
 
+    #[test]
+    fn test_emit_attributes_polytonal_keys_per_staff() {
+        let key = |fifths, staff| Key {
+            content: KeyContent::Traditional(TraditionalKey {
+                cancel: None,
+                fifths,
+                mode: None,
+            }),
+            number: Some(staff),
+            print_object: None,
+        };
+        let mut w = XmlWriter::new();
+        let attrs = Attributes {
+            editorial: Editorial::default(),
+            divisions: None,
+            keys: vec![key(2, 1), key(-2, 2)],
+            times: vec![],
+            staves: Some(2),
+            part_symbol: None,
+            instruments: None,
+            clefs: vec![],
+            staff_details: vec![],
+            transpose: vec![],
+            measure_styles: vec![],
+        };
+
+        emit_attributes(&mut w, &attrs).unwrap();
+        let xml = w.into_string().unwrap();
+
+        let upper = xml.find(r#"<key number="1">"#).expect("staff 1 key");
+        let lower = xml.find(r#"<key number="2">"#).expect("staff 2 key");
+        assert!(upper < lower, "{}", xml);
+        assert!(xml[upper..lower].contains("<fifths>2</fifths>"), "{}", xml);
+        assert!(xml[lower..].contains("<fifths>-2</fifths>"), "{}", xml);
+    }
+
     #[test]
     fn test_emit_attributes_with_multiple_times() {
         let mut w = XmlWriter::new();
@@ -7,6 +7,7 @@ use crate::ir::direction::{
     Dashes, Direction, DirectionType, DirectionTypeContent, DynamicElement, Dynamics,
     MetronomeContent, OctaveShift, Offset, Pedal, Sound, Wedge, Words,
 };
+use crate::ir::part::MidiInstrument;
 use crate::musicxml::EmitError;
 use crate::musicxml::writer::{ElementBuilder, XmlWriter};
 
@@ -15,6 +16,7 @@ use super::helpers::{
     start_stop_continue_to_string, up_down_stop_continue_to_string, wedge_type_to_string,
     yes_no_to_string,
 };
+use super::score::font_attrs;
 
 /// Emit a direction element.
 ///
@@ -122,7 +124,57 @@ pub(crate) fn emit_sound(w: &mut XmlWriter, sound: &Sound) -> Result<(), EmitErr
         elem = elem.attr("pizzicato", yes_no_to_string(pizzicato));
     }
 
-    w.empty_element_with_attrs(elem)
+    if sound.midi_instruments.is_empty() && sound.offset.is_none() {
+        w.empty_element_with_attrs(elem)
+            .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        return Ok(());
+    }
+
+    w.write_start(elem)
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    for instrument in &sound.midi_instruments {
+        emit_midi_instrument(w, instrument)?;
+    }
+    if let Some(ref offset) = sound.offset {
+        emit_offset(w, offset)?;
+    }
+    w.end_element("sound")
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    Ok(())
+}
+
+/// Emit a midi-instrument element.
+fn emit_midi_instrument(w: &mut XmlWriter, instrument: &MidiInstrument) -> Result<(), EmitError> {
+    w.write_start(ElementBuilder::new("midi-instrument").attr("id", &instrument.id))
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+
+    let fields = [
+        (
+            "midi-channel",
+            instrument.midi_channel.map(|v| v.to_string()),
+        ),
+        ("midi-name", instrument.midi_name.clone()),
+        ("midi-bank", instrument.midi_bank.map(|v| v.to_string())),
+        (
+            "midi-program",
+            instrument.midi_program.map(|v| v.to_string()),
+        ),
+        (
+            "midi-unpitched",
+            instrument.midi_unpitched.map(|v| v.to_string()),
+        ),
+        ("volume", instrument.volume.map(|v| v.to_string())),
+        ("pan", instrument.pan.map(|v| v.to_string())),
+        ("elevation", instrument.elevation.map(|v| v.to_string())),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            w.text_element(name, &value)
+                .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        }
+    }
+
+    w.end_element("midi-instrument")
         .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
     Ok(())
 }
@@ -252,7 +304,7 @@ pub(crate) fn emit_coda(w: &mut XmlWriter) -> Result<(), EmitError> {
 
 /// Emit a words element.
 pub(crate) fn emit_words(w: &mut XmlWriter, words: &Words) -> Result<(), EmitError> {
-    let mut elem = ElementBuilder::new("words");
+    let mut elem = font_attrs(ElementBuilder::new("words"), &words.print_style.font);
     if let Some(ref lang) = words.lang {
         elem = elem.attr("xml:lang", lang);
     }
@@ -956,6 +1008,8 @@ mod tests {
             fine: Some("yes".to_string()),
             time_only: Some("2".to_string()),
             pizzicato: Some(YesNo::No),
+            midi_instruments: vec![],
+            offset: None,
         };
 
         emit_sound(&mut w, &sound).unwrap();
@@ -4,7 +4,9 @@
 //! to their MusicXML string representations.
 
 use crate::ir::NoteTypeValue;
-use crate::ir::attributes::{BarStyle, CancelLocation, ClefSign, Mode, TimeSymbol, Winged};
+use crate::ir::attributes::{
+    BarStyle, CancelLocation, ClefSign, GroupSymbolValue, Mode, TimeSymbol, Winged,
+};
 use crate::ir::beam::{BeamValue, Fan, NoteheadValue, StemValue};
 use crate::ir::common::{
     AboveBelow, AccidentalValue, BackwardForward, LineType, OverUnder, RightLeftMiddle, StartStop,
@@ -596,6 +598,17 @@ pub(crate) fn top_middle_bottom_to_string(
     }
 }
 
+/// Convert a GroupSymbolValue to its MusicXML string representation.
+pub(crate) fn group_symbol_value_to_string(value: &GroupSymbolValue) -> &'static str {
+    match value {
+        GroupSymbolValue::None => "none",
+        GroupSymbolValue::Brace => "brace",
+        GroupSymbolValue::Line => "line",
+        GroupSymbolValue::Bracket => "bracket",
+        GroupSymbolValue::Square => "square",
+    }
+}
+
 /// Convert a MarginType to its MusicXML string representation.
 pub(crate) fn margin_type_to_string(margin_type: &crate::ir::score::MarginType) -> &'static str {
     match margin_type {
@@ -3,14 +3,14 @@
 //! This module handles the emission of notation elements including tied, slur,
 //! tuplet, articulations, and other note-attached notations.
 
-use crate::ir::common::WavyLine;
+use crate::ir::common::{LineType, NumberLevel, StartStop, WavyLine};
 use crate::ir::notation::{
     AccidentalMark, Arpeggiate, Arrow, ArticulationElement, Articulations, Bend, BreathMark,
-    Caesura, EmptyLine, EmptyTrillSound, Fingering, Fret, HammerPull, Handbell, HarmonMute,
-    Harmonic, HeelToe, Hole, HoleClosed, Mordent, NonArpeggiate, NotationContent, Notations,
-    OrnamentElement, OrnamentWithAccidentals, Ornaments, OtherNotation, OtherOrnament,
-    OtherTechnical, Pluck, Slur, StringNumber, StrongAccent, Tap, Technical, TechnicalElement,
-    Tied, Tremolo, Tuplet, TupletPortion, Turn,
+    Caesura, EmptyLine, EmptyTrillSound, Fingering, Fret, Glissando, HammerPull, Handbell,
+    HarmonMute, Harmonic, HeelToe, Hole, HoleClosed, Mordent, NonArpeggiate, NotationContent,
+    Notations, OrnamentElement, OrnamentWithAccidentals, Ornaments, OtherNotation, OtherOrnament,
+    OtherTechnical, Pluck, Slide, Slur, StringNumber, StrongAccent, Tap, Technical,
+    TechnicalElement, Tied, Tremolo, Tuplet, TupletPortion, Turn,
 };
 use crate::musicxml::EmitError;
 use crate::musicxml::writer::{ElementBuilder, XmlWriter};
@@ -48,12 +48,8 @@ pub(crate) fn emit_notations(w: &mut XmlWriter, notations: &Notations) -> Result
             NotationContent::Tied(tied) => emit_tied(w, tied)?,
             NotationContent::Slur(slur) => emit_slur(w, slur)?,
             NotationContent::Tuplet(tuplet) => emit_tuplet(w, tuplet)?,
-            NotationContent::Glissando(_glissando) => {
-                // TODO: Implement glissando
-            }
-            NotationContent::Slide(_slide) => {
-                // TODO: Implement slide
-            }
+            NotationContent::Glissando(glissando) => emit_glissando(w, glissando)?,
+            NotationContent::Slide(slide) => emit_slide(w, slide)?,
             NotationContent::Ornaments(ornaments) => {
                 emit_ornaments(w, ornaments)?;
             }
@@ -148,6 +144,65 @@ pub(crate) fn emit_slur(w: &mut XmlWriter, slur: &Slur) -> Result<(), EmitError>
     Ok(())
 }
 
+/// Emit a glissando element.
+pub(crate) fn emit_glissando(w: &mut XmlWriter, glissando: &Glissando) -> Result<(), EmitError> {
+    emit_line_connector(
+        w,
+        "glissando",
+        &glissando.r#type,
+        glissando.number,
+        glissando.line_type.as_ref(),
+        glissando.text.as_deref(),
+    )
+}
+
+/// Emit a slide element.
+pub(crate) fn emit_slide(w: &mut XmlWriter, slide: &Slide) -> Result<(), EmitError> {
+    emit_line_connector(
+        w,
+        "slide",
+        &slide.r#type,
+        slide.number,
+        slide.line_type.as_ref(),
+        slide.text.as_deref(),
+    )
+}
+
+/// Emit a start/stop line between notes with optional text (glissando, slide).
+fn emit_line_connector(
+    w: &mut XmlWriter,
+    name: &str,
+    r#type: &StartStop,
+    number: Option<NumberLevel>,
+    line_type: Option<&LineType>,
+    text: Option<&str>,
+) -> Result<(), EmitError> {
+    let mut elem = ElementBuilder::new(name).attr("type", start_stop_to_string(r#type));
+
+    if let Some(number) = number {
+        elem = elem.attr("number", &number.to_string());
+    }
+    if let Some(line_type) = line_type {
+        elem = elem.attr("line-type", line_type_to_string(line_type));
+    }
+
+    match text {
+        Some(text) => {
+            w.write_start(elem)
+                .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+            w.write_text(text)
+                .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+            w.end_element(name)
+                .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        }
+        None => {
+            w.empty_element_with_attrs(elem)
+                .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        }
+    }
+    Ok(())
+}
+
 /// Emit a tuplet element.
 pub(crate) fn emit_tuplet(w: &mut XmlWriter, tuplet: &Tuplet) -> Result<(), EmitError> {
     let mut elem = ElementBuilder::new("tuplet").attr("type", start_stop_to_string(&tuplet.r#type));
@@ -939,10 +994,14 @@ fn emit_bend(w: &mut XmlWriter, bend: &Bend) -> Result<(), EmitError> {
 
     if let Some(ref release) = bend.release {
         let release_str = match release {
-            crate::ir::notation::BendRelease::Early => "early",
-            crate::ir::notation::BendRelease::Late => "late",
+            crate::ir::notation::BendRelease::Default => None,
+            crate::ir::notation::BendRelease::Early => Some("early"),
+            crate::ir::notation::BendRelease::Late => Some("late"),
         };
-        let elem = ElementBuilder::new("release").attr("offset", release_str);
+        let mut elem = ElementBuilder::new("release");
+        if let Some(release_str) = release_str {
+            elem = elem.attr("offset", release_str);
+        }
         w.empty_element_with_attrs(elem)
             .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
     }
@@ -1971,6 +2030,27 @@ mod tests {
         assert!(xml.contains("color=\"#00FF00\""));
     }
 
+    #[test]
+    fn test_emit_glissando_with_text() {
+        let mut w = XmlWriter::new();
+        let glissando = Glissando {
+            r#type: StartStop::Start,
+            number: Some(1),
+            text: Some("gliss.".to_string()),
+            line_type: Some(LineType::Wavy),
+            position: Position::default(),
+        };
+
+        emit_glissando(&mut w, &glissando).unwrap();
+        let xml = w.into_string().unwrap();
+
+        assert!(
+            xml.contains(
+                r#"<glissando type="start" number="1" line-type="wavy">gliss.</glissando>"#
+            )
+        );
+    }
+
     // === Arpeggiate Tests ===
 
     #[test]
@@ -3013,6 +3093,26 @@ mod tests {
         assert!(xml.contains("<pre-bend/>"));
     }
 
+    #[test]
+    fn test_emit_technical_bend_with_default_release() {
+        use crate::ir::notation::{BendRelease, TechnicalElement};
+
+        let mut w = XmlWriter::new();
+        let technical = Technical {
+            content: vec![TechnicalElement::Bend(Bend {
+                bend_alter: 2.0,
+                pre_bend: false,
+                release: Some(BendRelease::Default),
+                with_bar: None,
+            })],
+        };
+
+        emit_technical(&mut w, &technical).unwrap();
+        let xml = w.into_string().unwrap();
+
+        assert!(xml.contains("<release/>"), "{}", xml);
+    }
+
     #[test]
     fn test_emit_technical_bend_with_release_early() {
         use crate::ir::notation::{BendRelease, TechnicalElement};
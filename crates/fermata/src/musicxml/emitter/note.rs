@@ -40,7 +40,11 @@ use super::notation::emit_notations;
 /// 14. notations*
 /// 15. lyric*
 pub(crate) fn emit_note(w: &mut XmlWriter, note: &Note) -> Result<(), EmitError> {
-    w.start_element("note")
+    let mut elem = ElementBuilder::new("note");
+    if let Some(ref print_object) = note.print_object {
+        elem = elem.attr("print-object", yes_no_to_string(print_object));
+    }
+    w.write_start(elem)
         .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
 
     // Handle the three content variants
@@ -4,19 +4,23 @@
 //! emitting score headers, part lists, parts, and measures.
 
 use crate::ir::common::{Encoding, EncodingContent, Identification, Supports, TypedText};
-use crate::ir::part::{PartGroup, PartList, PartListElement, ScorePart};
+use crate::ir::part::{
+    MidiDevice, MidiInstrument, PartGroup, PartList, PartListElement, ScoreInstrument, ScorePart,
+    SoloOrEnsemble,
+};
 use crate::ir::score::{
     Appearance, Credit, CreditContent, CreditImage, CreditSymbol, CreditWords, Defaults, Distance,
     LineWidth, LyricFont, LyricLanguage, NoteSize, Opus, PageLayout, PageMargins, Scaling,
     StaffLayout, SystemDividers, SystemLayout, SystemMargins, Work,
 };
-use crate::ir::{Measure, MusicDataElement, Part, ScorePartwise};
+use crate::ir::{Listening, Measure, MusicDataElement, Part, Print, ScorePartwise};
 use crate::musicxml::EmitError;
+use crate::musicxml::options::EmitOptions;
 use crate::musicxml::writer::{ElementBuilder, XmlWriter};
 
 use super::attributes::emit_attributes;
 use super::barline::emit_barline;
-use super::direction::emit_direction;
+use super::direction::{emit_direction, emit_offset};
 use super::helpers::{
     font_size_to_string, left_center_right_to_string, margin_type_to_string,
     note_size_type_to_string, top_middle_bottom_to_string, yes_no_to_string,
@@ -37,8 +41,45 @@ use super::voice::{emit_backup, emit_forward};
 ///
 /// A `Result` containing the complete XML string or an `EmitError`
 pub fn emit_score(score: &ScorePartwise) -> Result<String, EmitError> {
-    let mut w = XmlWriter::new();
+    emit_score_with_options(score, &EmitOptions::default())
+}
+
+/// Emit a complete MusicXML document formatted according to `options`.
+pub fn emit_score_with_options(
+    score: &ScorePartwise,
+    options: &EmitOptions,
+) -> Result<String, EmitError> {
+    let mut w = XmlWriter::with_indent(options.indent);
+    write_score(&mut w, score)?;
+    w.into_string()
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))
+}
+
+/// Emit a complete MusicXML document directly to a writer.
+///
+/// Produces exactly the same bytes as [`emit_score`] without holding the
+/// whole document in memory.
+pub fn emit_score_to_writer<W: std::io::Write>(
+    score: &ScorePartwise,
+    out: &mut W,
+) -> Result<(), EmitError> {
+    emit_score_to_writer_with_options(score, out, &EmitOptions::default())
+}
 
+/// Emit a complete MusicXML document to a writer, formatted according to
+/// `options`.
+pub fn emit_score_to_writer_with_options<W: std::io::Write>(
+    score: &ScorePartwise,
+    out: &mut W,
+    options: &EmitOptions,
+) -> Result<(), EmitError> {
+    let mut w = XmlWriter::to_stream_with_indent(out, options.indent);
+    write_score(&mut w, score)?;
+    w.flush().map_err(|e| EmitError::XmlWrite(e.to_string()))
+}
+
+/// Write the document (header, root element, and contents) to `w`.
+fn write_score(w: &mut XmlWriter, score: &ScorePartwise) -> Result<(), EmitError> {
     w.write_header()
         .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
 
@@ -51,17 +92,14 @@ pub fn emit_score(score: &ScorePartwise) -> Result<String, EmitError> {
         .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
 
     // Score header elements (work, identification, defaults, credits, part-list)
-    emit_score_header(&mut w, score)?;
+    emit_score_header(w, score)?;
 
     // Parts
     for part in &score.parts {
-        emit_part(&mut w, part)?;
+        emit_part(w, part)?;
     }
 
     w.end_element("score-partwise")
-        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
-
-    w.into_string()
         .map_err(|e| EmitError::XmlWrite(e.to_string()))
 }
 
@@ -261,6 +299,12 @@ pub(crate) fn emit_defaults(w: &mut XmlWriter, defaults: &Defaults) -> Result<()
         emit_scaling(w, scaling)?;
     }
 
+    // concert-score
+    if defaults.concert_score {
+        w.empty_element("concert-score")
+            .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    }
+
     // page-layout
     if let Some(ref page_layout) = defaults.page_layout {
         emit_page_layout(w, page_layout)?;
@@ -521,8 +565,17 @@ fn emit_font_element(
     name: &str,
     font: &crate::ir::common::Font,
 ) -> Result<(), EmitError> {
-    let mut elem = ElementBuilder::new(name);
+    let elem = font_attrs(ElementBuilder::new(name), font);
+    w.empty_element_with_attrs(elem)
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    Ok(())
+}
 
+/// Add font attributes (family, style, size, weight) to an element.
+pub(crate) fn font_attrs(
+    mut elem: ElementBuilder,
+    font: &crate::ir::common::Font,
+) -> ElementBuilder {
     if let Some(ref family) = font.font_family {
         elem = elem.attr("font-family", family);
     }
@@ -543,10 +596,7 @@ fn emit_font_element(
         };
         elem = elem.attr("font-weight", weight_str);
     }
-
-    w.empty_element_with_attrs(elem)
-        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
-    Ok(())
+    elem
 }
 
 /// Emit a lyric-font element.
@@ -669,13 +719,7 @@ fn emit_credit_words(w: &mut XmlWriter, cw: &CreditWords) -> Result<(), EmitErro
     if let Some(ref valign) = cw.valign {
         elem = elem.attr("valign", top_middle_bottom_to_string(valign));
     }
-    // Font attributes from print_style.font
-    if let Some(ref family) = cw.print_style.font.font_family {
-        elem = elem.attr("font-family", family);
-    }
-    if let Some(ref size) = cw.print_style.font.font_size {
-        elem = elem.attr("font-size", &font_size_to_string(size));
-    }
+    elem = font_attrs(elem, &cw.print_style.font);
     if let Some(ref lang) = cw.lang {
         elem = elem.attr("xml:lang", lang);
     }
@@ -741,6 +785,10 @@ pub(crate) fn emit_score_part(w: &mut XmlWriter, sp: &ScorePart) -> Result<(), E
     w.write_start(elem)
         .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
 
+    if let Some(ref id) = sp.identification {
+        emit_identification(w, id)?;
+    }
+
     // part-name is required
     w.text_element("part-name", &sp.part_name.value)
         .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
@@ -749,15 +797,114 @@ pub(crate) fn emit_score_part(w: &mut XmlWriter, sp: &ScorePart) -> Result<(), E
     // TODO: part-abbreviation
     // TODO: part-abbreviation-display
     // TODO: group
-    // TODO: score-instrument
-    // TODO: midi-device
-    // TODO: midi-instrument
+
+    // Notes refer to these by id, so they must be written for the part to
+    // read back
+    for instrument in &sp.score_instruments {
+        emit_score_instrument(w, instrument)?;
+    }
+    for device in &sp.midi_devices {
+        emit_midi_device(w, device)?;
+    }
+    for instrument in &sp.midi_instruments {
+        emit_midi_instrument(w, instrument)?;
+    }
 
     w.end_element("score-part")
         .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
     Ok(())
 }
 
+/// Emit a score-instrument element.
+fn emit_score_instrument(w: &mut XmlWriter, si: &ScoreInstrument) -> Result<(), EmitError> {
+    let elem = ElementBuilder::new("score-instrument").attr("id", &si.id);
+    w.write_start(elem)
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+
+    w.text_element("instrument-name", &si.instrument_name)
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    if let Some(ref abbreviation) = si.instrument_abbreviation {
+        w.text_element("instrument-abbreviation", abbreviation)
+            .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    }
+    if let Some(ref sound) = si.instrument_sound {
+        w.text_element("instrument-sound", sound)
+            .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    }
+    match si.solo_or_ensemble {
+        Some(SoloOrEnsemble::Solo) => w.empty_element("solo"),
+        // An ensemble of unspecified size is written empty
+        Some(SoloOrEnsemble::Ensemble(0)) => w.empty_element("ensemble"),
+        Some(SoloOrEnsemble::Ensemble(size)) => w.text_element("ensemble", &size.to_string()),
+        None => Ok(()),
+    }
+    .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    if let Some(ref virtual_instrument) = si.virtual_instrument {
+        w.start_element("virtual-instrument")
+            .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        if let Some(ref library) = virtual_instrument.virtual_library {
+            w.text_element("virtual-library", library)
+                .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        }
+        if let Some(ref name) = virtual_instrument.virtual_name {
+            w.text_element("virtual-name", name)
+                .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        }
+        w.end_element("virtual-instrument")
+            .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    }
+
+    w.end_element("score-instrument")
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    Ok(())
+}
+
+/// Emit a midi-device element.
+fn emit_midi_device(w: &mut XmlWriter, device: &MidiDevice) -> Result<(), EmitError> {
+    let mut elem = ElementBuilder::new("midi-device");
+    if let Some(port) = device.port {
+        elem = elem.attr("port", &port.to_string());
+    }
+    if let Some(ref id) = device.id {
+        elem = elem.attr("id", id);
+    }
+    w.write_start(elem)
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    w.write_text(&device.value)
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    w.end_element("midi-device")
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    Ok(())
+}
+
+/// Emit a midi-instrument element.
+fn emit_midi_instrument(w: &mut XmlWriter, mi: &MidiInstrument) -> Result<(), EmitError> {
+    let elem = ElementBuilder::new("midi-instrument").attr("id", &mi.id);
+    w.write_start(elem)
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+
+    let fields = [
+        ("midi-channel", mi.midi_channel.map(|v| v.to_string())),
+        ("midi-name", mi.midi_name.clone()),
+        ("midi-bank", mi.midi_bank.map(|v| v.to_string())),
+        ("midi-program", mi.midi_program.map(|v| v.to_string())),
+        ("midi-unpitched", mi.midi_unpitched.map(|v| v.to_string())),
+        ("volume", mi.volume.map(|v| v.to_string())),
+        ("pan", mi.pan.map(|v| v.to_string())),
+        ("elevation", mi.elevation.map(|v| v.to_string())),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            w.text_element(name, &value)
+                .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        }
+    }
+
+    w.end_element("midi-instrument")
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    Ok(())
+}
+
 /// Emit a part-group element (stub).
 pub(crate) fn emit_part_group(w: &mut XmlWriter, _pg: &PartGroup) -> Result<(), EmitError> {
     // TODO: implement part-group emission
@@ -783,7 +930,13 @@ pub(crate) fn emit_part(w: &mut XmlWriter, part: &Part) -> Result<(), EmitError>
 
 /// Emit a measure element.
 pub(crate) fn emit_measure(w: &mut XmlWriter, measure: &Measure) -> Result<(), EmitError> {
-    let elem = ElementBuilder::new("measure").attr("number", &measure.number);
+    let mut elem = ElementBuilder::new("measure").attr("number", &measure.number);
+    if let Some(ref implicit) = measure.implicit {
+        elem = elem.attr("implicit", yes_no_to_string(implicit));
+    }
+    if let Some(ref non_controlling) = measure.non_controlling {
+        elem = elem.attr("non-controlling", yes_no_to_string(non_controlling));
+    }
     w.write_start(elem)
         .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
 
@@ -805,6 +958,8 @@ pub(crate) fn emit_measure(w: &mut XmlWriter, measure: &Measure) -> Result<(), E
 /// - Direction
 /// - Attributes
 /// - Barline
+/// - Print
+/// - Listening
 pub(crate) fn emit_music_data(
     w: &mut XmlWriter,
     element: &MusicDataElement,
@@ -816,7 +971,103 @@ pub(crate) fn emit_music_data(
         MusicDataElement::Direction(dir) => emit_direction(w, dir),
         MusicDataElement::Attributes(attrs) => emit_attributes(w, attrs),
         MusicDataElement::Barline(barline) => emit_barline(w, barline),
+        MusicDataElement::Print(print) => emit_print(w, print),
+        MusicDataElement::Listening(listening) => emit_listening(w, listening),
+    }
+}
+
+/// Emit a print element.
+fn emit_print(w: &mut XmlWriter, print: &Print) -> Result<(), EmitError> {
+    let mut elem = ElementBuilder::new("print");
+    if let Some(ref ns) = print.new_system {
+        elem = elem.attr("new-system", yes_no_to_string(ns));
+    }
+    if let Some(ref np) = print.new_page {
+        elem = elem.attr("new-page", yes_no_to_string(np));
+    }
+    if let Some(ref page_number) = print.page_number {
+        elem = elem.attr("page-number", page_number);
+    }
+
+    let has_content = print.page_layout.is_some()
+        || print.system_layout.is_some()
+        || !print.staff_layouts.is_empty()
+        || print.measure_layout.is_some();
+    if !has_content {
+        return w
+            .empty_element_with_attrs(elem)
+            .map_err(|e| EmitError::XmlWrite(e.to_string()));
+    }
+
+    w.write_start(elem)
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+
+    if let Some(ref pl) = print.page_layout {
+        emit_page_layout(w, pl)?;
+    }
+    if let Some(ref sl) = print.system_layout {
+        emit_system_layout(w, sl)?;
+    }
+    for sl in &print.staff_layouts {
+        emit_staff_layout(w, sl)?;
+    }
+    if let Some(ref ml) = print.measure_layout {
+        w.start_element("measure-layout")
+            .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        if let Some(dist) = ml.measure_distance {
+            w.text_element("measure-distance", &dist.to_string())
+                .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        }
+        w.end_element("measure-layout")
+            .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    }
+
+    w.end_element("print")
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    Ok(())
+}
+
+/// Emit a listening element.
+fn emit_listening(w: &mut XmlWriter, listening: &Listening) -> Result<(), EmitError> {
+    w.start_element("listening")
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+
+    for sync in &listening.syncs {
+        let mut elem = ElementBuilder::new("sync").attr("type", &sync.r#type);
+        if let Some(latency) = sync.latency {
+            elem = elem.attr("latency", &latency.to_string());
+        }
+        if let Some(ref player) = sync.player {
+            elem = elem.attr("player", player);
+        }
+        if let Some(ref time_only) = sync.time_only {
+            elem = elem.attr("time-only", time_only);
+        }
+        w.empty_element_with_attrs(elem)
+            .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    }
+    for other in &listening.other_listening {
+        let mut elem = ElementBuilder::new("other-listening").attr("type", &other.r#type);
+        if let Some(ref player) = other.player {
+            elem = elem.attr("player", player);
+        }
+        if let Some(ref time_only) = other.time_only {
+            elem = elem.attr("time-only", time_only);
+        }
+        w.write_start(elem)
+            .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        w.write_text(&other.value)
+            .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+        w.end_element("other-listening")
+            .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
     }
+    if let Some(ref offset) = listening.offset {
+        emit_offset(w, offset)?;
+    }
+
+    w.end_element("listening")
+        .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -827,7 +1078,7 @@ mod tests {
         Attributes, Clef, ClefSign, Key, KeyContent, Mode, Time, TimeContent, TimeSignature,
         TraditionalKey,
     };
-    use crate::ir::common::{Editorial, FontSize};
+    use crate::ir::common::{Editorial, FontSize, YesNo};
     use crate::ir::part::PartName;
 
     fn create_minimal_score() -> ScorePartwise {
@@ -887,6 +1138,36 @@ mod tests {
         assert!(xml.contains("</score-partwise>"));
     }
 
+    #[test]
+    fn test_emit_score_to_writer_matches_string() {
+        let score = crate::lang::compile(
+            r#"(score :title "Stream" (part :piano (measure (note c4 :q) (chord (e4 g4) :q) (rest :h))))"#,
+        )
+        .unwrap();
+        let expected = emit_score(&score).unwrap();
+
+        let mut out: Vec<u8> = Vec::new();
+        emit_score_to_writer(&score, &mut out).unwrap();
+
+        assert_eq!(out, expected.into_bytes());
+    }
+
+    #[test]
+    fn test_emit_score_to_writer_propagates_io_error() {
+        struct FailingWriter;
+        impl std::io::Write for FailingWriter {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk full"))
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let result = emit_score_to_writer(&create_minimal_score(), &mut FailingWriter);
+        assert!(matches!(result, Err(EmitError::XmlWrite(msg)) if msg.contains("disk full")));
+    }
+
     #[test]
     fn test_emit_score_part_list() {
         let score = create_minimal_score();
@@ -924,6 +1205,16 @@ mod tests {
         assert!(xml.contains("</measure>"));
     }
 
+    #[test]
+    fn test_emit_measure_implicit() {
+        let mut score = create_minimal_score();
+        score.parts[0].measures[0].number = "0".to_string();
+        score.parts[0].measures[0].implicit = Some(YesNo::Yes);
+        let xml = emit_score(&score).unwrap();
+
+        assert!(xml.contains("<measure number=\"0\" implicit=\"yes\">"));
+    }
+
     #[test]
     fn test_emit_score_without_version() {
         let mut score = create_minimal_score();
@@ -2040,6 +2331,7 @@ mod tests {
                 millimeters: 7.056,
                 tenths: 40.0,
             }),
+            concert_score: false,
             page_layout: Some(PageLayout {
                 page_height: Some(1683.36),
                 page_width: Some(1190.88),
@@ -2693,6 +2985,7 @@ mod tests {
         let mut score = create_minimal_score();
         score.defaults = Some(Defaults {
             scaling: None,
+            concert_score: false,
             page_layout: None,
             system_layout: Some(SystemLayout {
                 system_margins: None,
@@ -2735,6 +3028,7 @@ mod tests {
         let mut score = create_minimal_score();
         score.defaults = Some(Defaults {
             scaling: None,
+            concert_score: false,
             page_layout: None,
             system_layout: None,
             staff_layout: vec![],
@@ -2795,6 +3089,7 @@ mod tests {
         let mut score = create_minimal_score();
         score.defaults = Some(Defaults {
             scaling: None,
+            concert_score: false,
             page_layout: None,
             system_layout: None,
             staff_layout: vec![],
@@ -2830,6 +3125,7 @@ mod tests {
         let mut score = create_minimal_score();
         score.defaults = Some(Defaults {
             scaling: None,
+            concert_score: false,
             page_layout: None,
             system_layout: None,
             staff_layout: vec![
@@ -2862,6 +3158,7 @@ mod tests {
         let mut score = create_minimal_score();
         score.defaults = Some(Defaults {
             scaling: None,
+            concert_score: false,
             page_layout: None,
             system_layout: None,
             staff_layout: vec![],
@@ -2901,6 +3198,7 @@ mod tests {
         let mut score = create_minimal_score();
         score.defaults = Some(Defaults {
             scaling: None,
+            concert_score: false,
             page_layout: Some(PageLayout {
                 page_height: None,
                 page_width: None,
@@ -3012,6 +3310,7 @@ mod tests {
         let mut score = create_minimal_score();
         score.defaults = Some(Defaults {
             scaling: None,
+            concert_score: false,
             page_layout: None,
             system_layout: None,
             staff_layout: vec![],
@@ -3059,6 +3358,7 @@ mod tests {
         let mut score = create_minimal_score();
         score.defaults = Some(Defaults {
             scaling: None,
+            concert_score: false,
             page_layout: Some(PageLayout {
                 page_height: None,
                 page_width: None,
@@ -34,6 +34,8 @@
 
 mod divisions;
 mod emitter;
+mod mxl;
+mod options;
 mod parser;
 mod reader;
 mod values;
@@ -43,11 +45,20 @@ pub use divisions::{
     STANDARD_DIVISIONS, apply_dots, apply_time_modification, calculate_duration,
     note_type_to_divisions,
 };
-pub use emitter::{emit_score, note_type_value_to_string};
-pub use parser::parse_score;
+pub use emitter::{
+    emit_score, emit_score_to_writer, emit_score_to_writer_with_options, emit_score_with_options,
+    note_type_value_to_string,
+};
+pub use mxl::{emit_mxl, parse_mxl, read_mxl};
+pub use options::{EmitOptions, Indent, ParseOptions};
+pub use parser::{parse_score, parse_score_with_options, parse_score_with_skipped};
+pub use reader::SkippedElement;
 
 use crate::ir::ScorePartwise;
 
+/// The MusicXML version Fermata reads and writes.
+pub const MUSICXML_VERSION: &str = "4.0";
+
 /// Parse a MusicXML document from a string.
 ///
 /// This is the main entry point for parsing MusicXML. It accepts a complete
@@ -82,6 +93,19 @@ pub fn parse(xml: &str) -> Result<ScorePartwise, ParseError> {
     parser::parse_score(xml)
 }
 
+/// Parse a MusicXML document using `options`.
+///
+/// Use this for untrusted input that needs a tighter nesting limit than
+/// [`ParseOptions::default`].
+///
+/// # Errors
+///
+/// Returns `ParseError::Other` if elements nest deeper than
+/// [`ParseOptions::max_depth`], plus the errors described for [`parse`].
+pub fn parse_with_options(xml: &str, options: &ParseOptions) -> Result<ScorePartwise, ParseError> {
+    parser::parse_score_with_options(xml, options)
+}
+
 /// Emit a MusicXML document from a ScorePartwise IR.
 ///
 /// Returns the complete XML string including declaration and DOCTYPE.
@@ -102,6 +126,53 @@ pub fn emit(score: &ScorePartwise) -> Result<String, EmitError> {
     emitter::emit_score(score)
 }
 
+/// Emit a MusicXML document formatted according to `options`.
+///
+/// [`emit`] is equivalent to passing [`EmitOptions::default`] (two-space
+/// indentation); [`Indent::None`] produces compact output with no
+/// whitespace between elements.
+///
+/// # Errors
+///
+/// Returns `EmitError::XmlWrite` if there's an error writing XML elements.
+/// Returns `EmitError::InvalidData` if the IR contains invalid data.
+pub fn emit_with_options(
+    score: &ScorePartwise,
+    options: &EmitOptions,
+) -> Result<String, EmitError> {
+    emitter::emit_score_with_options(score, options)
+}
+
+/// Emit a MusicXML document from a ScorePartwise IR straight to a writer.
+///
+/// The output is byte-for-byte identical to [`emit`], but is streamed to
+/// `w` as it is generated instead of being collected into a `String`.
+///
+/// # Errors
+///
+/// Returns `EmitError::XmlWrite` if writing to `w` fails.
+/// Returns `EmitError::InvalidData` if the IR contains invalid data.
+pub fn emit_to_writer<W: std::io::Write>(
+    score: &ScorePartwise,
+    w: &mut W,
+) -> Result<(), EmitError> {
+    emitter::emit_score_to_writer(score, w)
+}
+
+/// Stream a MusicXML document to a writer, formatted according to `options`.
+///
+/// # Errors
+///
+/// Returns `EmitError::XmlWrite` if writing to `w` fails.
+/// Returns `EmitError::InvalidData` if the IR contains invalid data.
+pub fn emit_to_writer_with_options<W: std::io::Write>(
+    score: &ScorePartwise,
+    w: &mut W,
+    options: &EmitOptions,
+) -> Result<(), EmitError> {
+    emitter::emit_score_to_writer_with_options(score, w, options)
+}
+
 /// Errors that can occur during MusicXML parsing.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ParseError {
@@ -587,6 +658,89 @@ mod tests {
         assert!(xml.contains("</score-partwise>"));
     }
 
+    #[test]
+    fn test_emit_with_options_compact_round_trips() {
+        let score = crate::lang::compile(
+            r#"(score :title "Test" (part :piano (measure (note c4 :q :staccato) (rest :q))))"#,
+        )
+        .unwrap();
+        let pretty = emit(&score).unwrap();
+        let compact = emit_with_options(&score, &EmitOptions::compact()).unwrap();
+
+        assert!(pretty.contains(">\n  <"));
+        assert!(!compact.contains('\n'));
+        assert!(!compact.contains("> "));
+        assert!(compact.len() < pretty.len());
+        assert_eq!(parse(&compact).unwrap(), parse(&pretty).unwrap());
+
+        let tabs = EmitOptions {
+            indent: Indent::Tabs,
+        };
+        assert!(emit_with_options(&score, &tabs).unwrap().contains("\n\t<"));
+    }
+
+    #[test]
+    fn test_emit_score_instruments_round_trip() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<score-partwise version="4.0">
+  <part-list>
+    <score-part id="P1">
+      <part-name>Strings</part-name>
+      <score-instrument id="P1-I1">
+        <instrument-name>Violins</instrument-name>
+        <instrument-sound>strings.violin</instrument-sound>
+        <ensemble>16</ensemble>
+      </score-instrument>
+      <midi-instrument id="P1-I1">
+        <midi-channel>2</midi-channel>
+        <midi-program>41</midi-program>
+        <volume>78.5</volume>
+        <pan>-45</pan>
+      </midi-instrument>
+    </score-part>
+  </part-list>
+  <part id="P1">
+    <measure number="1">
+      <note><pitch><step>G</step><octave>3</octave></pitch><duration>4</duration><instrument id="P1-I1"/><type>whole</type></note>
+    </measure>
+  </part>
+</score-partwise>"#;
+        let score = parse(xml).unwrap();
+        let emitted = emit(&score).unwrap();
+
+        assert!(emitted.contains("<ensemble>16</ensemble>"), "{}", emitted);
+        assert!(emitted.contains("<volume>78.5</volume>"), "{}", emitted);
+        assert_eq!(parse(&emitted).unwrap(), score);
+    }
+
+    #[test]
+    fn test_emit_per_staff_keys_and_times_round_trip() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<score-partwise version="4.0">
+  <part-list>
+    <score-part id="P1"><part-name>Piano</part-name></score-part>
+  </part-list>
+  <part id="P1">
+    <measure number="1">
+      <attributes>
+        <divisions>1</divisions>
+        <key number="1"><fifths>2</fifths></key>
+        <key number="2"><fifths>-2</fifths></key>
+        <time number="1"><beats>3</beats><beat-type>4</beat-type></time>
+        <time number="2"><beats>6</beats><beat-type>8</beat-type></time>
+        <staves>2</staves>
+      </attributes>
+    </measure>
+  </part>
+</score-partwise>"#;
+        let score = parse(xml).unwrap();
+        let emitted = emit(&score).unwrap();
+
+        assert!(emitted.contains(r#"<key number="2">"#), "{}", emitted);
+        assert!(emitted.contains(r#"<time number="2">"#), "{}", emitted);
+        assert_eq!(parse(&emitted).unwrap(), score);
+    }
+
     #[test]
     fn test_emit_error_display() {
         let err = EmitError::XmlWrite("test error".to_string());
@@ -0,0 +1,190 @@
+//! Compressed MusicXML (`.mxl`) containers.
+//!
+//! An `.mxl` file is a zip archive holding the score plus a
+//! `META-INF/container.xml` manifest whose first `<rootfile>` names the
+//! score's path inside the archive. Writing produces the layout recommended
+//! by the MusicXML specification: an uncompressed `mimetype` entry first,
+//! then the manifest, then the deflated score.
+
+use std::io::{Cursor, Read, Write};
+
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use super::reader::local_name;
+use super::{EmitError, ParseError};
+use crate::ir::ScorePartwise;
+
+/// MIME type stored in the `mimetype` entry of an `.mxl` archive.
+const MXL_MIMETYPE: &str = "application/vnd.recordare.musicxml";
+
+/// Path of the container manifest inside the archive.
+const CONTAINER_PATH: &str = "META-INF/container.xml";
+
+/// Path the score is written to by [`emit_mxl`].
+const SCORE_PATH: &str = "score.musicxml";
+
+/// Parse a score from the bytes of an `.mxl` archive.
+///
+/// # Errors
+///
+/// Returns `ParseError::Other` if the archive cannot be read, has no
+/// container manifest, or the rootfile it names is missing or not UTF-8,
+/// plus any error from [`parse_score`](super::parse_score).
+pub fn parse_mxl(bytes: &[u8]) -> Result<ScorePartwise, ParseError> {
+    super::parse_score(&read_mxl(bytes)?)
+}
+
+/// Extract the score document from the bytes of an `.mxl` archive without
+/// parsing it.
+///
+/// # Errors
+///
+/// Returns `ParseError::Other` if the archive cannot be read, has no
+/// container manifest, or the rootfile it names is missing or not UTF-8.
+pub fn read_mxl(bytes: &[u8]) -> Result<String, ParseError> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| ParseError::other(format!("invalid .mxl archive: {}", e), None))?;
+
+    let container = read_entry(&mut archive, CONTAINER_PATH)?;
+    let rootfile = rootfile_path(&container)?;
+    read_entry(&mut archive, &rootfile)
+}
+
+/// Write a score as the bytes of an `.mxl` archive.
+///
+/// # Errors
+///
+/// Returns `EmitError::XmlWrite` if the score or the archive cannot be
+/// written.
+pub fn emit_mxl(score: &ScorePartwise) -> Result<Vec<u8>, EmitError> {
+    let xml = super::emit(score)?;
+    let container = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<container>
+  <rootfiles>
+    <rootfile full-path="{}" media-type="application/vnd.recordare.musicxml+xml"/>
+  </rootfiles>
+</container>
+"#,
+        SCORE_PATH
+    );
+
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let entries = [
+        ("mimetype", MXL_MIMETYPE, stored),
+        (CONTAINER_PATH, container.as_str(), deflated),
+        (SCORE_PATH, xml.as_str(), deflated),
+    ];
+    for (path, content, options) in entries {
+        zip.start_file(path, options).map_err(zip_error)?;
+        zip.write_all(content.as_bytes())
+            .map_err(|e| EmitError::XmlWrite(e.to_string()))?;
+    }
+    let cursor = zip.finish().map_err(zip_error)?;
+    Ok(cursor.into_inner())
+}
+
+/// Read a UTF-8 text entry from the archive.
+fn read_entry(archive: &mut ZipArchive<Cursor<&[u8]>>, path: &str) -> Result<String, ParseError> {
+    let mut entry = archive
+        .by_name(path)
+        .map_err(|_| ParseError::other(format!(".mxl archive has no {}", path), None))?;
+    let mut text = String::new();
+    entry
+        .read_to_string(&mut text)
+        .map_err(|e| ParseError::other(format!("cannot read {}: {}", path, e), None))?;
+    Ok(text)
+}
+
+/// Return the `full-path` of the first `<rootfile>` in a container manifest.
+fn rootfile_path(container: &str) -> Result<String, ParseError> {
+    let mut reader = Reader::from_str(container);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if local_name(e.name().as_ref()) == b"rootfile" => {
+                let path = e
+                    .try_get_attribute("full-path")
+                    .map_err(|e| ParseError::other(e.to_string(), None))?
+                    .ok_or_else(|| {
+                        ParseError::missing_attribute(
+                            "full-path",
+                            "rootfile",
+                            reader.buffer_position() as usize,
+                        )
+                    })?;
+                let value = path
+                    .unescape_value()
+                    .map_err(|e| ParseError::other(e.to_string(), None))?;
+                return Ok(value.into_owned());
+            }
+            Event::Eof => {
+                return Err(ParseError::other(
+                    format!("{} has no rootfile", CONTAINER_PATH),
+                    None,
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn zip_error(e: zip::result::ZipError) -> EmitError {
+    EmitError::XmlWrite(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mxl_round_trip() {
+        let score = crate::lang::compile(
+            r#"(score :title "Zipped" (part :piano (measure (note c4 :q) (note e4 :q) (note g4 :h))))"#,
+        )
+        .unwrap();
+
+        let bytes = emit_mxl(&score).unwrap();
+        assert!(bytes.starts_with(b"PK"));
+        // The uncompressed mimetype entry comes first
+        assert_eq!(&bytes[30..38], b"mimetype");
+
+        let parsed = parse_mxl(&bytes).unwrap();
+        assert_eq!(
+            parsed,
+            crate::musicxml::parse(&crate::musicxml::emit(&score).unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_mxl_follows_rootfile_path() {
+        let xml = crate::musicxml::emit(
+            &crate::lang::compile("(score (part :piano (measure (note c4 :w))))").unwrap(),
+        )
+        .unwrap();
+        let container = r#"<container><rootfiles><rootfile full-path="scores/etude.xml"/></rootfiles></container>"#;
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (path, content) in [
+            (CONTAINER_PATH, container),
+            ("scores/etude.xml", xml.as_str()),
+        ] {
+            zip.start_file(path, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let score = parse_mxl(&bytes).unwrap();
+        assert_eq!(score.parts.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_mxl_rejects_non_zip() {
+        assert!(parse_mxl(b"<score-partwise/>").is_err());
+    }
+}
@@ -0,0 +1,65 @@
+//! Options for MusicXML parsing and emission.
+
+/// Indentation style for emitted MusicXML.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    /// Compact output: no newlines or indentation between elements
+    None,
+    /// Indent nested elements by the given number of spaces
+    Spaces(usize),
+    /// Indent nested elements by one tab per level
+    Tabs,
+}
+
+impl Default for Indent {
+    fn default() -> Self {
+        Indent::Spaces(2)
+    }
+}
+
+/// Options controlling how a score is written as MusicXML.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmitOptions {
+    /// Indentation style (defaults to two spaces)
+    pub indent: Indent,
+}
+
+impl EmitOptions {
+    /// Options for compact output with no indentation.
+    pub fn compact() -> Self {
+        Self {
+            indent: Indent::None,
+        }
+    }
+}
+
+/// Options controlling how MusicXML is parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Deepest element nesting accepted before parsing fails (defaults to 256)
+    ///
+    /// Real scores nest well under 20 levels; the limit keeps pathological
+    /// or hostile input from exhausting the stack.
+    pub max_depth: usize,
+    /// Record every element skipped because Fermata does not read it
+    /// (defaults to false)
+    ///
+    /// The list is returned by
+    /// [`parse_score_with_skipped`](super::parse_score_with_skipped); when
+    /// this is off nothing is allocated for it.
+    pub record_skipped: bool,
+}
+
+impl ParseOptions {
+    /// Default maximum element nesting depth.
+    pub const DEFAULT_MAX_DEPTH: usize = 256;
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+            record_skipped: false,
+        }
+    }
+}
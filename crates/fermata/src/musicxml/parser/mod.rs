@@ -21,12 +21,13 @@
 
 use quick_xml::events::Event;
 
-use super::ParseError;
-use super::reader::{XmlReader, element_name};
+use super::reader::{SkippedElement, XmlReader, element_name};
 use super::values;
+use super::{ParseError, ParseOptions};
 use crate::ir::attributes::{
-    Attributes, Barline, Cancel, Clef, ClefSign, Ending, Key, KeyContent, Mode, Repeat, Time,
-    TimeContent, TimeSignature, TraditionalKey,
+    Attributes, Barline, Cancel, Clef, ClefSign, Ending, GroupSymbolValue, Key, KeyContent,
+    MeasureStyle, MeasureStyleContent, Mode, PartSymbol, Repeat, Time, TimeContent, TimeSignature,
+    TraditionalKey,
 };
 use crate::ir::beam::{Beam, Notehead, Stem};
 use crate::ir::common::{
@@ -36,12 +37,14 @@ use crate::ir::common::{
 use crate::ir::direction::{Coda, Segno};
 use crate::ir::duration::{Dot, NoteType, TimeModification};
 use crate::ir::lyric::{Elision, Extend, Lyric, LyricContent, TextElementData};
-use crate::ir::measure::Measure;
+use crate::ir::measure::{Listening, ListeningSync, Measure, MeasureLayout, OtherListening, Print};
 use crate::ir::notation::{Fermata, FermataShape};
 use crate::ir::note::{
-    Accidental, FullNote, Grace, Note, NoteContent, PitchRestUnpitched, Rest, Tie,
+    Accidental, FullNote, Grace, Instrument, Note, NoteContent, PitchRestUnpitched, Rest, Tie,
+};
+use crate::ir::part::{
+    MidiInstrument, PartList, PartListElement, PartName, ScoreInstrument, ScorePart, SoloOrEnsemble,
 };
-use crate::ir::part::{PartList, PartListElement, PartName, ScorePart};
 use crate::ir::pitch::{Pitch, Unpitched};
 use crate::ir::score::{
     Appearance, Credit, CreditContent, CreditImage, CreditWords, Defaults, Distance, Divider,
@@ -74,8 +77,46 @@ use crate::ir::{Part, PrintStyle};
 /// - The document uses `score-timewise` (not yet supported)
 /// - References are undefined (e.g., part ID not in part-list)
 pub fn parse_score(xml: &str) -> Result<ScorePartwise, ParseError> {
-    let mut reader = XmlReader::new(xml);
+    parse_score_with_options(xml, &ParseOptions::default())
+}
 
+/// Parse a MusicXML document from a string using `options`.
+///
+/// # Errors
+///
+/// In addition to the errors from [`parse_score`], returns
+/// `ParseError::Other` if elements nest deeper than
+/// [`ParseOptions::max_depth`].
+pub fn parse_score_with_options(
+    xml: &str,
+    options: &ParseOptions,
+) -> Result<ScorePartwise, ParseError> {
+    let mut reader = XmlReader::with_options(xml, options);
+    parse_document(&mut reader)
+}
+
+/// Parse a MusicXML document using `options`, also returning every element
+/// that was skipped because Fermata does not read it yet.
+///
+/// Skipped elements are only collected when
+/// [`ParseOptions::record_skipped`] is set; otherwise the list is empty.
+/// They are listed in document order, once per occurrence, and children of
+/// a skipped element are not listed separately.
+///
+/// # Errors
+///
+/// The same as [`parse_score_with_options`].
+pub fn parse_score_with_skipped(
+    xml: &str,
+    options: &ParseOptions,
+) -> Result<(ScorePartwise, Vec<SkippedElement>), ParseError> {
+    let mut reader = XmlReader::with_options(xml, options);
+    let score = parse_document(&mut reader)?;
+    Ok((score, reader.take_skipped()))
+}
+
+/// Parse a whole document, from the XML declaration to the score element.
+fn parse_document(reader: &mut XmlReader<'_>) -> Result<ScorePartwise, ParseError> {
     // Skip XML declaration and DOCTYPE
     loop {
         match reader.next_event()? {
@@ -86,8 +127,8 @@ pub fn parse_score(xml: &str) -> Result<ScorePartwise, ParseError> {
                     "score-partwise" => {
                         let version = reader
                             .get_optional_attr(e.attributes(), "version")?
-                            .or_else(|| Some("4.0".to_string()));
-                        return parse_score_partwise(&mut reader, version);
+                            .or_else(|| Some(super::MUSICXML_VERSION.to_string()));
+                        return parse_score_partwise(reader, version);
                     }
                     "score-timewise" => {
                         return Err(ParseError::other(
@@ -316,8 +357,7 @@ fn parse_score_part(
                 let name = element_name(&e);
                 match name.as_str() {
                     "identification" => {
-                        // TODO: Parse identification
-                        reader.skip_element("identification")?;
+                        score_part.identification = Some(parse_identification(reader)?);
                     }
                     "part-name" => {
                         score_part.part_name.value = reader.read_text("part-name")?;
@@ -340,16 +380,18 @@ fn parse_score_part(
                         score_part.group.push(group_name);
                     }
                     "score-instrument" => {
-                        // TODO: Parse score-instrument
-                        reader.skip_element("score-instrument")?;
+                        score_part
+                            .score_instruments
+                            .push(parse_score_instrument(reader, &e)?);
                     }
                     "midi-device" => {
                         // TODO: Parse midi-device
                         reader.skip_element("midi-device")?;
                     }
                     "midi-instrument" => {
-                        // TODO: Parse midi-instrument
-                        reader.skip_element("midi-instrument")?;
+                        score_part
+                            .midi_instruments
+                            .push(parse_midi_instrument(reader, &e)?);
                     }
                     _ => {
                         reader.skip_element(&name)?;
@@ -393,12 +435,146 @@ fn parse_score_part(
     Ok(score_part)
 }
 
+/// Parse a score-instrument element.
+fn parse_score_instrument(
+    reader: &mut XmlReader<'_>,
+    start: &quick_xml::events::BytesStart<'_>,
+) -> Result<ScoreInstrument, ParseError> {
+    let id = reader.get_attr(start.attributes(), "id", "score-instrument")?;
+
+    let mut instrument = ScoreInstrument {
+        id,
+        instrument_name: String::new(),
+        instrument_abbreviation: None,
+        instrument_sound: None,
+        solo_or_ensemble: None,
+        virtual_instrument: None,
+    };
+
+    loop {
+        let event = reader.next_event()?;
+        match event {
+            Event::Start(e) => {
+                let name = element_name(&e);
+                match name.as_str() {
+                    "instrument-name" => {
+                        instrument.instrument_name = reader.read_text("instrument-name")?;
+                    }
+                    "instrument-abbreviation" => {
+                        instrument.instrument_abbreviation =
+                            Some(reader.read_text("instrument-abbreviation")?);
+                    }
+                    "instrument-sound" => {
+                        instrument.instrument_sound = Some(reader.read_text("instrument-sound")?);
+                    }
+                    "ensemble" => {
+                        let size = reader.read_text("ensemble")?;
+                        let size = size.trim().parse().unwrap_or(0);
+                        instrument.solo_or_ensemble = Some(SoloOrEnsemble::Ensemble(size));
+                    }
+                    _ => {
+                        // TODO: Parse virtual-instrument
+                        reader.skip_element(&name)?;
+                    }
+                }
+            }
+            Event::Empty(e) => {
+                let name = element_name(&e);
+                match name.as_str() {
+                    "solo" => instrument.solo_or_ensemble = Some(SoloOrEnsemble::Solo),
+                    "ensemble" => {
+                        instrument.solo_or_ensemble = Some(SoloOrEnsemble::Ensemble(0));
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(_) => break,
+            Event::Eof => {
+                return Err(ParseError::xml(
+                    "unexpected EOF in score-instrument",
+                    reader.position(),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(instrument)
+}
+
+/// Parse a midi-instrument element.
+fn parse_midi_instrument(
+    reader: &mut XmlReader<'_>,
+    start: &quick_xml::events::BytesStart<'_>,
+) -> Result<MidiInstrument, ParseError> {
+    let id = reader.get_attr(start.attributes(), "id", "midi-instrument")?;
+
+    let mut instrument = MidiInstrument {
+        id,
+        midi_channel: None,
+        midi_name: None,
+        midi_bank: None,
+        midi_program: None,
+        midi_unpitched: None,
+        volume: None,
+        pan: None,
+        elevation: None,
+    };
+
+    loop {
+        let event = reader.next_event()?;
+        match event {
+            Event::Start(e) => {
+                let name = element_name(&e);
+                match name.as_str() {
+                    "midi-channel" => {
+                        instrument.midi_channel = Some(reader.read_text_as("midi-channel")?);
+                    }
+                    "midi-name" => {
+                        instrument.midi_name = Some(reader.read_text("midi-name")?);
+                    }
+                    "midi-bank" => {
+                        instrument.midi_bank = Some(reader.read_text_as("midi-bank")?);
+                    }
+                    "midi-program" => {
+                        instrument.midi_program = Some(reader.read_text_as("midi-program")?);
+                    }
+                    "midi-unpitched" => {
+                        instrument.midi_unpitched = Some(reader.read_text_as("midi-unpitched")?);
+                    }
+                    "volume" => {
+                        instrument.volume = Some(reader.read_text_as("volume")?);
+                    }
+                    "pan" => {
+                        instrument.pan = Some(reader.read_text_as("pan")?);
+                    }
+                    "elevation" => {
+                        instrument.elevation = Some(reader.read_text_as("elevation")?);
+                    }
+                    _ => {
+                        reader.skip_element(&name)?;
+                    }
+                }
+            }
+            Event::End(_) => break,
+            Event::Eof => {
+                return Err(ParseError::xml(
+                    "unexpected EOF in midi-instrument",
+                    reader.position(),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(instrument)
+}
+
 /// Parse a part-group element.
 fn parse_part_group(
     reader: &mut XmlReader<'_>,
     start: &quick_xml::events::BytesStart<'_>,
 ) -> Result<crate::ir::part::PartGroup, ParseError> {
-    use crate::ir::attributes::GroupSymbolValue;
     use crate::ir::part::{GroupBarlineValue, GroupName, GroupSymbol, PartGroup};
 
     let type_attr = reader.get_attr(start.attributes(), "type", "part-group")?;
@@ -446,20 +622,8 @@ fn parse_part_group(
                     }
                     "group-symbol" => {
                         let value = reader.read_text("group-symbol")?;
-                        let symbol_value = match value.as_str() {
-                            "none" => GroupSymbolValue::None,
-                            "brace" => GroupSymbolValue::Brace,
-                            "line" => GroupSymbolValue::Line,
-                            "bracket" => GroupSymbolValue::Bracket,
-                            "square" => GroupSymbolValue::Square,
-                            _ => {
-                                return Err(ParseError::invalid_value(
-                                    "group-symbol-value",
-                                    &value,
-                                    reader.position(),
-                                ));
-                            }
-                        };
+                        let symbol_value =
+                            values::parse_group_symbol_value(&value, reader.position())?;
                         part_group.group_symbol = Some(GroupSymbol {
                             value: symbol_value,
                             position: crate::ir::common::Position::default(),
@@ -580,6 +744,15 @@ fn parse_part(
         ));
     }
 
+    let score_instruments: &[ScoreInstrument] = part_list
+        .content
+        .iter()
+        .find_map(|elem| match elem {
+            PartListElement::ScorePart(sp) if sp.id == id => Some(sp.score_instruments.as_slice()),
+            _ => None,
+        })
+        .unwrap_or_default();
+
     let mut measures = Vec::new();
 
     loop {
@@ -590,6 +763,7 @@ fn parse_part(
                 match name.as_str() {
                     "measure" => {
                         let measure = parse_measure(reader, &e)?;
+                        validate_instrument_refs(&measure, score_instruments, reader.position())?;
                         measures.push(measure);
                     }
                     _ => {
@@ -624,6 +798,29 @@ fn parse_part(
     Ok(Part { id, measures })
 }
 
+/// Check that every note's instrument reference names a score-instrument
+/// declared for the part.
+fn validate_instrument_refs(
+    measure: &Measure,
+    score_instruments: &[ScoreInstrument],
+    position: usize,
+) -> Result<(), ParseError> {
+    for element in &measure.content {
+        if let crate::ir::measure::MusicDataElement::Note(note) = element {
+            for instrument in &note.instrument {
+                if !score_instruments.iter().any(|si| si.id == instrument.id) {
+                    return Err(ParseError::undefined_reference(
+                        "instrument",
+                        &instrument.id,
+                        position,
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Parse a measure element.
 fn parse_measure(
     reader: &mut XmlReader<'_>,
@@ -687,16 +884,18 @@ fn parse_measure(
                         reader.skip_element("figured-bass")?;
                     }
                     "print" => {
-                        // TODO: Parse print
-                        reader.skip_element("print")?;
+                        let print = parse_print(reader, &e, false)?;
+                        content.push(crate::ir::measure::MusicDataElement::Print(Box::new(print)));
                     }
                     "sound" => {
                         // TODO: Parse sound
                         reader.skip_element("sound")?;
                     }
                     "listening" => {
-                        // TODO: Parse listening
-                        reader.skip_element("listening")?;
+                        let listening = parse_listening(reader)?;
+                        content.push(crate::ir::measure::MusicDataElement::Listening(Box::new(
+                            listening,
+                        )));
                     }
                     _ => {
                         reader.skip_element(&name)?;
@@ -706,7 +905,16 @@ fn parse_measure(
             Event::Empty(e) => {
                 let name = element_name(&e);
                 match name.as_str() {
-                    "print" | "sound" | "listening" => {
+                    "print" => {
+                        let print = parse_print(reader, &e, true)?;
+                        content.push(crate::ir::measure::MusicDataElement::Print(Box::new(print)));
+                    }
+                    "listening" => {
+                        content.push(crate::ir::measure::MusicDataElement::Listening(
+                            Box::default(),
+                        ));
+                    }
+                    "sound" => {
                         // Empty versions - skip for now
                     }
                     _ => {}
@@ -734,6 +942,179 @@ fn parse_measure(
     })
 }
 
+/// Parse a print element (explicit breaks and layout changes).
+///
+/// `is_empty` is true for the self-closing form, which carries only
+/// attributes.
+fn parse_print(
+    reader: &mut XmlReader<'_>,
+    start: &quick_xml::events::BytesStart<'_>,
+    is_empty: bool,
+) -> Result<Print, ParseError> {
+    let yes_no = |value: Option<String>, position| {
+        value
+            .map(|s| values::parse_yes_no(&s, position))
+            .transpose()
+    };
+    let mut print = Print {
+        new_system: yes_no(
+            reader.get_optional_attr(start.attributes(), "new-system")?,
+            reader.position(),
+        )?,
+        new_page: yes_no(
+            reader.get_optional_attr(start.attributes(), "new-page")?,
+            reader.position(),
+        )?,
+        page_number: reader.get_optional_attr(start.attributes(), "page-number")?,
+        ..Print::default()
+    };
+
+    if is_empty {
+        return Ok(print);
+    }
+
+    loop {
+        let event = reader.next_event()?;
+        match event {
+            Event::Start(e) => {
+                let name = element_name(&e);
+                match name.as_str() {
+                    "page-layout" => print.page_layout = Some(parse_page_layout(reader)?),
+                    "system-layout" => print.system_layout = Some(parse_system_layout(reader)?),
+                    "staff-layout" => print.staff_layouts.push(parse_staff_layout(reader, &e)?),
+                    "measure-layout" => {
+                        print.measure_layout = Some(parse_measure_layout(reader)?);
+                    }
+                    _ => {
+                        // TODO: Parse measure-numbering and name displays
+                        reader.skip_element(&name)?;
+                    }
+                }
+            }
+            Event::Empty(e) => {
+                let name = element_name(&e);
+                match name.as_str() {
+                    "staff-layout" => print
+                        .staff_layouts
+                        .push(parse_staff_layout_from_empty(&e, reader)?),
+                    "measure-layout" => print.measure_layout = Some(MeasureLayout::default()),
+                    "system-layout" => print.system_layout = Some(SystemLayout::default()),
+                    _ => {}
+                }
+            }
+            Event::End(_) => break,
+            Event::Eof => {
+                return Err(ParseError::xml(
+                    "unexpected EOF in print",
+                    reader.position(),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(print)
+}
+
+/// Parse a listening element (hints for score-following applications).
+fn parse_listening(reader: &mut XmlReader<'_>) -> Result<Listening, ParseError> {
+    let mut listening = Listening::default();
+
+    loop {
+        let event = reader.next_event()?;
+        match event {
+            Event::Start(e) => {
+                let name = element_name(&e);
+                match name.as_str() {
+                    "other-listening" => {
+                        let r#type = reader.get_attr(e.attributes(), "type", "other-listening")?;
+                        let player = reader.get_optional_attr(e.attributes(), "player")?;
+                        let time_only = reader.get_optional_attr(e.attributes(), "time-only")?;
+                        let value = reader.read_text("other-listening")?;
+                        listening.other_listening.push(OtherListening {
+                            value,
+                            r#type,
+                            player,
+                            time_only,
+                        });
+                    }
+                    "offset" => listening.offset = Some(parse_offset(reader, &e)?),
+                    "sync" => {
+                        listening.syncs.push(parse_listening_sync(&e, reader)?);
+                        reader.skip_element("sync")?;
+                    }
+                    _ => {
+                        reader.skip_element(&name)?;
+                    }
+                }
+            }
+            Event::Empty(e) => {
+                let name = element_name(&e);
+                match name.as_str() {
+                    "sync" => listening.syncs.push(parse_listening_sync(&e, reader)?),
+                    "offset" => listening.offset = Some(parse_offset_from_empty(&e, reader)?),
+                    _ => {}
+                }
+            }
+            Event::End(_) => break,
+            Event::Eof => {
+                return Err(ParseError::xml(
+                    "unexpected EOF in listening",
+                    reader.position(),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(listening)
+}
+
+/// Parse the attributes of a sync element.
+fn parse_listening_sync(
+    start: &quick_xml::events::BytesStart<'_>,
+    reader: &XmlReader<'_>,
+) -> Result<ListeningSync, ParseError> {
+    Ok(ListeningSync {
+        r#type: reader.get_attr(start.attributes(), "type", "sync")?,
+        latency: reader.get_optional_attr_as(start.attributes(), "latency")?,
+        player: reader.get_optional_attr(start.attributes(), "player")?,
+        time_only: reader.get_optional_attr(start.attributes(), "time-only")?,
+    })
+}
+
+/// Parse a measure-layout element.
+fn parse_measure_layout(reader: &mut XmlReader<'_>) -> Result<MeasureLayout, ParseError> {
+    let mut layout = MeasureLayout::default();
+
+    loop {
+        let event = reader.next_event()?;
+        match event {
+            Event::Start(e) => {
+                let name = element_name(&e);
+                match name.as_str() {
+                    "measure-distance" => {
+                        layout.measure_distance = Some(reader.read_text_as("measure-distance")?);
+                    }
+                    _ => {
+                        reader.skip_element(&name)?;
+                    }
+                }
+            }
+            Event::End(_) => break,
+            Event::Eof => {
+                return Err(ParseError::xml(
+                    "unexpected EOF in measure-layout",
+                    reader.position(),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(layout)
+}
+
 // === Stub functions for elements that will be fully implemented in later milestones ===
 
 /// Parse a note element.
@@ -781,6 +1162,7 @@ fn parse_note(
     let mut beams: Vec<Beam> = Vec::new();
     let mut notations: Vec<crate::ir::notation::Notations> = Vec::new();
     let mut lyrics: Vec<Lyric> = Vec::new();
+    let mut instruments: Vec<Instrument> = Vec::new();
 
     loop {
         let event = reader.next_event()?;
@@ -848,7 +1230,7 @@ fn parse_note(
                         lyrics.push(parse_lyric(reader, &e)?);
                     }
                     "instrument" => {
-                        // TODO: Parse instrument references
+                        instruments.push(parse_instrument(reader, &e)?);
                         reader.skip_element("instrument")?;
                     }
                     _ => {
@@ -880,6 +1262,9 @@ fn parse_note(
                     "dot" => {
                         dots.push(parse_dot_from_empty(&e, reader)?);
                     }
+                    "instrument" => {
+                        instruments.push(parse_instrument(reader, &e)?);
+                    }
                     _ => {
                         // Skip unknown empty elements
                     }
@@ -941,7 +1326,7 @@ fn parse_note(
         pizzicato,
         print_object,
         content: note_content,
-        instrument: vec![],
+        instrument: instruments,
         voice,
         r#type: note_type,
         dots,
@@ -1376,6 +1761,18 @@ fn parse_notehead(
     })
 }
 
+/// Parse an instrument reference on a note.
+///
+/// Works for both `<instrument id="..."/>` and the start-tag form; the
+/// caller is responsible for consuming the end tag of the latter.
+fn parse_instrument(
+    reader: &mut XmlReader<'_>,
+    start: &quick_xml::events::BytesStart<'_>,
+) -> Result<Instrument, ParseError> {
+    let id = reader.get_attr(start.attributes(), "id", "instrument")?;
+    Ok(Instrument { id })
+}
+
 /// Parse a beam element.
 fn parse_beam(
     reader: &mut XmlReader<'_>,
@@ -1430,8 +1827,7 @@ fn parse_attributes(reader: &mut XmlReader<'_>) -> Result<Attributes, ParseError
                         attrs.staves = Some(reader.read_text_as("staves")?);
                     }
                     "part-symbol" => {
-                        // TODO: Parse part-symbol fully
-                        reader.skip_element("part-symbol")?;
+                        attrs.part_symbol = Some(parse_part_symbol(reader, &e)?);
                     }
                     "instruments" => {
                         attrs.instruments = Some(reader.read_text_as("instruments")?);
@@ -1449,8 +1845,7 @@ fn parse_attributes(reader: &mut XmlReader<'_>) -> Result<Attributes, ParseError
                         attrs.transpose.push(transpose);
                     }
                     "measure-style" => {
-                        // TODO: Parse measure-style fully
-                        reader.skip_element("measure-style")?;
+                        attrs.measure_styles.push(parse_measure_style(reader, &e)?);
                     }
                     "footnote" | "level" => {
                         // Skip editorial elements for now
@@ -1468,6 +1863,9 @@ fn parse_attributes(reader: &mut XmlReader<'_>) -> Result<Attributes, ParseError
                         let clef = parse_clef_from_empty(&e, reader)?;
                         attrs.clefs.push(clef);
                     }
+                    "part-symbol" => {
+                        attrs.part_symbol = Some(parse_part_symbol_from_empty(&e, reader)?);
+                    }
                     _ => {
                         // Skip unknown empty elements
                     }
@@ -1489,6 +1887,127 @@ fn parse_attributes(reader: &mut XmlReader<'_>) -> Result<Attributes, ParseError
     Ok(attrs)
 }
 
+/// Parse a part-symbol element.
+///
+/// The element text is the symbol (`brace`, `bracket`, `line`, `square`,
+/// `none`); `top-staff`/`bottom-staff` give the range of staves it spans.
+fn parse_part_symbol(
+    reader: &mut XmlReader<'_>,
+    start: &quick_xml::events::BytesStart<'_>,
+) -> Result<PartSymbol, ParseError> {
+    let mut part_symbol = parse_part_symbol_from_empty(start, reader)?;
+    let value = reader.read_text("part-symbol")?;
+    part_symbol.value = values::parse_group_symbol_value(value.trim(), reader.position())?;
+    Ok(part_symbol)
+}
+
+/// Parse a self-closing part-symbol element (no symbol means `none`).
+fn parse_part_symbol_from_empty(
+    start: &quick_xml::events::BytesStart<'_>,
+    reader: &XmlReader<'_>,
+) -> Result<PartSymbol, ParseError> {
+    Ok(PartSymbol {
+        value: GroupSymbolValue::None,
+        top_staff: reader.get_optional_attr_as::<u16>(start.attributes(), "top-staff")?,
+        bottom_staff: reader.get_optional_attr_as::<u16>(start.attributes(), "bottom-staff")?,
+        position: parse_position_attrs(start, reader)?,
+        color: reader.get_optional_attr(start.attributes(), "color")?,
+    })
+}
+
+/// Parse a measure-style element.
+///
+/// Reads the single `multiple-rest`, `measure-repeat`, `beat-repeat`, or
+/// `slash` child; the `number` attribute scopes the style to one staff.
+fn parse_measure_style(
+    reader: &mut XmlReader<'_>,
+    start: &quick_xml::events::BytesStart<'_>,
+) -> Result<MeasureStyle, ParseError> {
+    let number = reader.get_optional_attr_as::<u16>(start.attributes(), "number")?;
+    let mut content: Option<MeasureStyleContent> = None;
+
+    loop {
+        let event = reader.next_event()?;
+        match event {
+            Event::Start(e) => {
+                let name = element_name(&e);
+                match name.as_str() {
+                    "multiple-rest" => {
+                        let use_symbols = reader
+                            .get_optional_attr(e.attributes(), "use-symbols")?
+                            .map(|s| values::parse_yes_no(&s, reader.position()))
+                            .transpose()?;
+                        let text = reader.read_text("multiple-rest")?;
+                        let count = text.trim().parse().map_err(|_| {
+                            ParseError::invalid_value(
+                                "multiple-rest count",
+                                &text,
+                                reader.position(),
+                            )
+                        })?;
+                        content = Some(MeasureStyleContent::MultipleRest { count, use_symbols });
+                    }
+                    "measure-repeat" | "beat-repeat" | "slash" => {
+                        content = Some(parse_measure_style_content(&name, &e, reader)?);
+                        reader.skip_element(&name)?;
+                    }
+                    _ => {
+                        reader.skip_element(&name)?;
+                    }
+                }
+            }
+            Event::Empty(e) => {
+                let name = element_name(&e);
+                if matches!(name.as_str(), "measure-repeat" | "beat-repeat" | "slash") {
+                    content = Some(parse_measure_style_content(&name, &e, reader)?);
+                }
+            }
+            Event::End(_) => break,
+            Event::Eof => {
+                return Err(ParseError::xml(
+                    "unexpected EOF in measure-style",
+                    reader.position(),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    let content = content.ok_or_else(|| {
+        ParseError::missing_element("multiple-rest", "measure-style", reader.position())
+    })?;
+
+    Ok(MeasureStyle { number, content })
+}
+
+/// Parse the start/stop measure-style variants from their tag attributes.
+fn parse_measure_style_content(
+    name: &str,
+    start: &quick_xml::events::BytesStart<'_>,
+    reader: &XmlReader<'_>,
+) -> Result<MeasureStyleContent, ParseError> {
+    let type_attr = reader.get_attr(start.attributes(), "type", name)?;
+    let r#type = values::parse_start_stop(&type_attr, reader.position())?;
+
+    match name {
+        "slash" => {
+            let use_stems = reader
+                .get_optional_attr(start.attributes(), "use-stems")?
+                .map(|s| values::parse_yes_no(&s, reader.position()))
+                .transpose()?;
+            Ok(MeasureStyleContent::Slash { r#type, use_stems })
+        }
+        _ => {
+            let slashes = reader.get_optional_attr_as::<u32>(start.attributes(), "slashes")?;
+            if name == "measure-repeat" {
+                Ok(MeasureStyleContent::MeasureRepeat { r#type, slashes })
+            } else {
+                Ok(MeasureStyleContent::BeatRepeat { r#type, slashes })
+            }
+        }
+    }
+}
+
 /// Parse a key element.
 fn parse_key(
     reader: &mut XmlReader<'_>,
@@ -2481,12 +3000,49 @@ fn parse_offset_from_empty(
 }
 
 /// Parse a sound element.
+///
+/// Besides its attributes, a sound may carry MIDI instrument changes and an
+/// offset. Other children (instrument-change, midi-device, play, swing) are
+/// skipped.
 fn parse_sound(
     reader: &mut XmlReader<'_>,
     start: &quick_xml::events::BytesStart<'_>,
 ) -> Result<crate::ir::direction::Sound, ParseError> {
-    let sound = parse_sound_from_empty(start, reader)?;
-    reader.skip_element("sound")?;
+    let mut sound = parse_sound_from_empty(start, reader)?;
+
+    loop {
+        let event = reader.next_event()?;
+        match event {
+            Event::Start(e) => {
+                let name = element_name(&e);
+                match name.as_str() {
+                    "midi-instrument" => {
+                        sound
+                            .midi_instruments
+                            .push(parse_midi_instrument(reader, &e)?);
+                    }
+                    "offset" => {
+                        sound.offset = Some(parse_offset(reader, &e)?);
+                    }
+                    _ => {
+                        reader.skip_element(&name)?;
+                    }
+                }
+            }
+            Event::Empty(e) if element_name(&e) == "offset" => {
+                sound.offset = Some(parse_offset_from_empty(&e, reader)?);
+            }
+            Event::End(_) => break,
+            Event::Eof => {
+                return Err(ParseError::xml(
+                    "unexpected EOF in sound",
+                    reader.position(),
+                ));
+            }
+            _ => {}
+        }
+    }
+
     Ok(sound)
 }
 
@@ -2532,6 +3088,8 @@ fn parse_sound_from_empty(
         fine,
         time_only,
         pizzicato,
+        midi_instruments: vec![],
+        offset: None,
     })
 }
 
@@ -3550,13 +4108,13 @@ fn parse_technical(
                         content.push(TechnicalElement::Bend(parse_bend(reader, &e)?));
                     }
                     "hole" => {
-                        reader.skip_element("hole")?;
+                        content.push(TechnicalElement::Hole(parse_hole(reader)?));
                     }
                     "arrow" => {
-                        reader.skip_element("arrow")?;
+                        content.push(TechnicalElement::Arrow(parse_arrow(reader, &e)?));
                     }
                     "handbell" => {
-                        reader.skip_element("handbell")?;
+                        content.push(TechnicalElement::Handbell(parse_handbell(reader)?));
                     }
                     "brass-bend" => {
                         content.push(TechnicalElement::BrassBend(parse_empty_placement_element(
@@ -3679,6 +4237,12 @@ fn parse_technical(
                             &e, reader,
                         )?));
                     }
+                    "arrow" => {
+                        content.push(TechnicalElement::Arrow(crate::ir::notation::Arrow {
+                            smufl: reader.get_optional_attr(e.attributes(), "smufl")?,
+                            ..Default::default()
+                        }));
+                    }
                     _ => {}
                 }
             }
@@ -4795,7 +5359,7 @@ fn parse_bend(
                         reader.skip_element("pre-bend")?;
                     }
                     "release" => {
-                        release = Some(BendRelease::Early);
+                        release = Some(BendRelease::Default);
                         reader.skip_element("release")?;
                     }
                     "with-bar" => {
@@ -4810,7 +5374,7 @@ fn parse_bend(
                 let name = element_name(&e);
                 match name.as_str() {
                     "pre-bend" => pre_bend = true,
-                    "release" => release = Some(BendRelease::Early),
+                    "release" => release = Some(BendRelease::Default),
                     _ => {}
                 }
             }
@@ -4830,6 +5394,114 @@ fn parse_bend(
     })
 }
 
+/// Parse a hole element (woodwind and brass fingering holes).
+fn parse_hole(reader: &mut XmlReader<'_>) -> Result<crate::ir::notation::Hole, ParseError> {
+    use crate::ir::notation::{Hole, HoleClosed};
+
+    let mut hole_type: Option<String> = None;
+    let mut hole_closed: Option<HoleClosed> = None;
+    let mut hole_shape: Option<String> = None;
+
+    loop {
+        let event = reader.next_event()?;
+        match event {
+            Event::Start(e) => {
+                let name = element_name(&e);
+                match name.as_str() {
+                    "hole-type" => {
+                        hole_type = Some(reader.read_text("hole-type")?);
+                    }
+                    "hole-closed" => {
+                        let location = reader
+                            .get_optional_attr(e.attributes(), "location")?
+                            .map(|s| values::parse_hole_closed_location(&s, reader.position()))
+                            .transpose()?;
+                        let text = reader.read_text("hole-closed")?;
+                        let value = values::parse_hole_closed_value(&text, reader.position())?;
+                        hole_closed = Some(HoleClosed { value, location });
+                    }
+                    "hole-shape" => {
+                        hole_shape = Some(reader.read_text("hole-shape")?);
+                    }
+                    _ => {
+                        reader.skip_element(&name)?;
+                    }
+                }
+            }
+            Event::End(_) => break,
+            Event::Eof => {
+                return Err(ParseError::xml("unexpected EOF in hole", reader.position()));
+            }
+            _ => {}
+        }
+    }
+
+    let hole_closed = hole_closed
+        .ok_or_else(|| ParseError::missing_element("hole-closed", "hole", reader.position()))?;
+
+    Ok(Hole {
+        hole_type,
+        hole_closed,
+        hole_shape,
+    })
+}
+
+/// Parse an arrow element.
+///
+/// Only the linear form (arrow-direction and arrow-style) is kept;
+/// circular-arrow has no IR representation and is skipped.
+fn parse_arrow(
+    reader: &mut XmlReader<'_>,
+    start: &quick_xml::events::BytesStart<'_>,
+) -> Result<crate::ir::notation::Arrow, ParseError> {
+    use crate::ir::notation::Arrow;
+
+    let mut arrow = Arrow {
+        smufl: reader.get_optional_attr(start.attributes(), "smufl")?,
+        ..Default::default()
+    };
+
+    loop {
+        let event = reader.next_event()?;
+        match event {
+            Event::Start(e) => {
+                let name = element_name(&e);
+                match name.as_str() {
+                    "arrow-direction" => {
+                        let text = reader.read_text("arrow-direction")?;
+                        arrow.direction =
+                            Some(values::parse_arrow_direction(&text, reader.position())?);
+                    }
+                    "arrow-style" => {
+                        let text = reader.read_text("arrow-style")?;
+                        arrow.style = Some(values::parse_arrow_style(&text, reader.position())?);
+                    }
+                    _ => {
+                        reader.skip_element(&name)?;
+                    }
+                }
+            }
+            Event::End(_) => break,
+            Event::Eof => {
+                return Err(ParseError::xml(
+                    "unexpected EOF in arrow",
+                    reader.position(),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(arrow)
+}
+
+/// Parse a handbell element.
+fn parse_handbell(reader: &mut XmlReader<'_>) -> Result<crate::ir::notation::Handbell, ParseError> {
+    let text = reader.read_text("handbell")?;
+    let value = values::parse_handbell_value(&text, reader.position())?;
+    Ok(crate::ir::notation::Handbell { value })
+}
+
 /// Parse a barline element.
 ///
 /// Barline elements describe bar lines at the end or within measures.
@@ -6076,7 +6748,11 @@ fn parse_credit_words(
         .get_optional_attr(start.attributes(), "valign")?
         .map(|s| values::parse_top_middle_bottom(&s, reader.position()))
         .transpose()?;
-    let print_style = parse_print_style_attrs(start, reader)?;
+    // Each run keeps its own font (e.g. a large title followed by a smaller subtitle)
+    let print_style = PrintStyle {
+        font: parse_font_attrs(start, reader)?,
+        ..parse_print_style_attrs(start, reader)?
+    };
     let lang = reader.get_optional_attr(start.attributes(), "xml:lang")?;
 
     let value = reader.read_text("credit-words")?;
@@ -6108,7 +6784,10 @@ fn parse_credit_words_from_empty(
         .get_optional_attr(start.attributes(), "valign")?
         .map(|s| values::parse_top_middle_bottom(&s, reader.position()))
         .transpose()?;
-    let print_style = parse_print_style_attrs(start, reader)?;
+    let print_style = PrintStyle {
+        font: parse_font_attrs(start, reader)?,
+        ..parse_print_style_attrs(start, reader)?
+    };
     let lang = reader.get_optional_attr(start.attributes(), "xml:lang")?;
 
     Ok(CreditWords {
@@ -6158,6 +6837,7 @@ fn parse_credit_image_from_empty(
 /// Parse a defaults element containing page layout, system layout, etc.
 fn parse_defaults(reader: &mut XmlReader<'_>) -> Result<Defaults, ParseError> {
     let mut scaling: Option<Scaling> = None;
+    let mut concert_score = false;
     let mut page_layout: Option<PageLayout> = None;
     let mut system_layout: Option<SystemLayout> = None;
     let mut staff_layout: Vec<StaffLayout> = Vec::new();
@@ -6176,6 +6856,10 @@ fn parse_defaults(reader: &mut XmlReader<'_>) -> Result<Defaults, ParseError> {
                     "scaling" => {
                         scaling = Some(parse_scaling(reader)?);
                     }
+                    "concert-score" => {
+                        concert_score = true;
+                        reader.skip_element(&name)?;
+                    }
                     "page-layout" => {
                         page_layout = Some(parse_page_layout(reader)?);
                     }
@@ -6223,6 +6907,9 @@ fn parse_defaults(reader: &mut XmlReader<'_>) -> Result<Defaults, ParseError> {
                     "staff-layout" => {
                         staff_layout.push(parse_staff_layout_from_empty(&e, reader)?);
                     }
+                    "concert-score" => {
+                        concert_score = true;
+                    }
                     _ => {}
                 }
             }
@@ -6239,6 +6926,7 @@ fn parse_defaults(reader: &mut XmlReader<'_>) -> Result<Defaults, ParseError> {
 
     Ok(Defaults {
         scaling,
+        concert_score,
         page_layout,
         system_layout,
         staff_layout,
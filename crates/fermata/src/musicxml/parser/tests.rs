@@ -1,5 +1,7 @@
 use super::*;
 use crate::ir::attributes::TimeSymbol;
+use crate::ir::common::{FontSize, FontStyle, FontWeight};
+use crate::ir::score::CreditContent;
 
 // === parse_score Tests ===
 
@@ -498,6 +500,192 @@ fn test_parse_attributes_clef() {
     }
 }
 
+#[test]
+fn test_parse_attributes_part_symbol() {
+    let xml = r#"<?xml version="1.0"?>
+            <score-partwise>
+                <part-list>
+                    <score-part id="P1">
+                        <part-name>Piano</part-name>
+                    </score-part>
+                </part-list>
+                <part id="P1">
+                    <measure number="1">
+                        <attributes>
+                            <staves>2</staves>
+                            <part-symbol top-staff="1" bottom-staff="2">brace</part-symbol>
+                        </attributes>
+                    </measure>
+                </part>
+            </score-partwise>"#;
+
+    let score = parse_score(xml).unwrap();
+    if let crate::ir::measure::MusicDataElement::Attributes(attrs) =
+        &score.parts[0].measures[0].content[0]
+    {
+        let part_symbol = attrs.part_symbol.as_ref().unwrap();
+        assert_eq!(
+            part_symbol.value,
+            crate::ir::attributes::GroupSymbolValue::Brace
+        );
+        assert_eq!(part_symbol.top_staff, Some(1));
+        assert_eq!(part_symbol.bottom_staff, Some(2));
+        assert_eq!(attrs.staves, Some(2));
+    } else {
+        panic!("Expected Attributes");
+    }
+}
+
+#[test]
+fn test_parse_attributes_part_symbol_empty() {
+    let xml = r##"<?xml version="1.0"?>
+            <score-partwise>
+                <part-list>
+                    <score-part id="P1">
+                        <part-name>Harp</part-name>
+                    </score-part>
+                </part-list>
+                <part id="P1">
+                    <measure number="1">
+                        <attributes>
+                            <part-symbol top-staff="1" bottom-staff="2" color="#000000"/>
+                        </attributes>
+                    </measure>
+                </part>
+            </score-partwise>"##;
+
+    let score = parse_score(xml).unwrap();
+    if let crate::ir::measure::MusicDataElement::Attributes(attrs) =
+        &score.parts[0].measures[0].content[0]
+    {
+        let part_symbol = attrs.part_symbol.as_ref().unwrap();
+        assert_eq!(
+            part_symbol.value,
+            crate::ir::attributes::GroupSymbolValue::None
+        );
+        assert_eq!(part_symbol.top_staff, Some(1));
+        assert_eq!(part_symbol.bottom_staff, Some(2));
+        assert_eq!(part_symbol.color, Some("#000000".to_string()));
+    } else {
+        panic!("Expected Attributes");
+    }
+}
+
+#[test]
+fn test_parse_attributes_part_symbol_invalid() {
+    let xml = r#"<?xml version="1.0"?>
+            <score-partwise>
+                <part-list>
+                    <score-part id="P1">
+                        <part-name>Piano</part-name>
+                    </score-part>
+                </part-list>
+                <part id="P1">
+                    <measure number="1">
+                        <attributes>
+                            <part-symbol>curly</part-symbol>
+                        </attributes>
+                    </measure>
+                </part>
+            </score-partwise>"#;
+
+    assert!(parse_score(xml).is_err());
+}
+
+fn parse_single_attributes(attributes_xml: &str) -> Attributes {
+    let xml = format!(
+        r#"<?xml version="1.0"?>
+            <score-partwise>
+                <part-list>
+                    <score-part id="P1">
+                        <part-name>Horn</part-name>
+                    </score-part>
+                </part-list>
+                <part id="P1">
+                    <measure number="1">
+                        <attributes>{}</attributes>
+                    </measure>
+                </part>
+            </score-partwise>"#,
+        attributes_xml
+    );
+
+    let score = parse_score(&xml).unwrap();
+    match &score.parts[0].measures[0].content[0] {
+        crate::ir::measure::MusicDataElement::Attributes(attrs) => (**attrs).clone(),
+        _ => panic!("Expected Attributes"),
+    }
+}
+
+#[test]
+fn test_parse_attributes_measure_style_multiple_rest() {
+    use crate::ir::attributes::MeasureStyleContent;
+
+    let attrs =
+        parse_single_attributes("<measure-style><multiple-rest>8</multiple-rest></measure-style>");
+    assert_eq!(attrs.measure_styles.len(), 1);
+    assert_eq!(attrs.measure_styles[0].number, None);
+    assert_eq!(
+        attrs.measure_styles[0].content,
+        MeasureStyleContent::MultipleRest {
+            count: 8,
+            use_symbols: None
+        }
+    );
+}
+
+#[test]
+fn test_parse_attributes_measure_style_multiple_rest_use_symbols() {
+    use crate::ir::attributes::MeasureStyleContent;
+
+    let attrs = parse_single_attributes(
+        r#"<measure-style number="2"><multiple-rest use-symbols="yes">3</multiple-rest></measure-style>"#,
+    );
+    assert_eq!(attrs.measure_styles[0].number, Some(2));
+    assert_eq!(
+        attrs.measure_styles[0].content,
+        MeasureStyleContent::MultipleRest {
+            count: 3,
+            use_symbols: Some(YesNo::Yes)
+        }
+    );
+}
+
+#[test]
+fn test_parse_attributes_measure_style_repeats_and_slash() {
+    use crate::ir::attributes::MeasureStyleContent;
+    use crate::ir::common::StartStop;
+
+    let attrs = parse_single_attributes(
+        r#"<measure-style><measure-repeat type="start" slashes="1">1</measure-repeat></measure-style>
+           <measure-style><beat-repeat type="stop"><slash-type>eighth</slash-type></beat-repeat></measure-style>
+           <measure-style number="1"><slash type="start" use-stems="no"/></measure-style>"#,
+    );
+    assert_eq!(attrs.measure_styles.len(), 3);
+    assert_eq!(
+        attrs.measure_styles[0].content,
+        MeasureStyleContent::MeasureRepeat {
+            r#type: StartStop::Start,
+            slashes: Some(1)
+        }
+    );
+    assert_eq!(
+        attrs.measure_styles[1].content,
+        MeasureStyleContent::BeatRepeat {
+            r#type: StartStop::Stop,
+            slashes: None
+        }
+    );
+    assert_eq!(attrs.measure_styles[2].number, Some(1));
+    assert_eq!(
+        attrs.measure_styles[2].content,
+        MeasureStyleContent::Slash {
+            r#type: StartStop::Start,
+            use_stems: Some(YesNo::No)
+        }
+    );
+}
+
 #[test]
 fn test_parse_attributes_complete() {
     let xml = r#"<?xml version="1.0"?>
@@ -1454,6 +1642,41 @@ fn test_parse_score_part_with_empty_part_name() {
     }
 }
 
+#[test]
+fn test_parse_score_part_with_identification() {
+    let xml = r#"<?xml version="1.0"?>
+            <score-partwise>
+                <part-list>
+                    <score-part id="P1">
+                        <identification>
+                            <creator type="arranger">Jane Doe</creator>
+                        </identification>
+                        <part-name>Violin</part-name>
+                    </score-part>
+                </part-list>
+                <part id="P1">
+                    <measure number="1"/>
+                </part>
+            </score-partwise>"#;
+
+    let score = parse_score(xml).unwrap();
+    let PartListElement::ScorePart(sp) = &score.part_list.content[0] else {
+        panic!("Expected ScorePart");
+    };
+    let identification = sp.identification.as_ref().expect("part identification");
+    assert_eq!(identification.creators.len(), 1);
+    assert_eq!(
+        identification.creators[0].r#type.as_deref(),
+        Some("arranger")
+    );
+    assert_eq!(identification.creators[0].value, "Jane Doe");
+    assert_eq!(sp.part_name.value, "Violin");
+
+    // The creator survives a round trip through the emitter
+    let reparsed = parse_score(&crate::musicxml::emit(&score).unwrap()).unwrap();
+    assert_eq!(reparsed.part_list, score.part_list);
+}
+
 #[test]
 fn test_parse_score_part_with_group() {
     let xml = r#"<?xml version="1.0"?>
@@ -1838,6 +2061,43 @@ fn test_parse_forward_without_optional_elements() {
     }
 }
 
+#[test]
+fn test_parse_tab_clef_keeps_sign_and_line_on_emission() {
+    let xml = r#"<?xml version="1.0"?>
+        <score-partwise>
+            <part-list>
+                <score-part id="P1"><part-name>Guitar</part-name></score-part>
+            </part-list>
+            <part id="P1">
+                <measure number="1">
+                    <attributes>
+                        <clef number="1"><sign>G</sign><line>2</line><clef-octave-change>-1</clef-octave-change></clef>
+                        <clef number="2"><sign>TAB</sign><line>5</line></clef>
+                    </attributes>
+                </measure>
+            </part>
+        </score-partwise>"#;
+
+    let score = parse_score(xml).unwrap();
+    let crate::ir::measure::MusicDataElement::Attributes(attrs) =
+        &score.parts[0].measures[0].content[0]
+    else {
+        panic!("Expected Attributes");
+    };
+    assert_eq!(attrs.clefs[1].sign, ClefSign::Tab);
+    assert_eq!(attrs.clefs[1].line, Some(5));
+
+    let emitted = crate::musicxml::emit(&score).unwrap();
+    assert!(emitted.contains("<sign>TAB</sign>"), "{}", emitted);
+    let reparsed = parse_score(&emitted).unwrap();
+    let crate::ir::measure::MusicDataElement::Attributes(reparsed_attrs) =
+        &reparsed.parts[0].measures[0].content[0]
+    else {
+        panic!("Expected Attributes after round trip");
+    };
+    assert_eq!(reparsed_attrs.clefs, attrs.clefs);
+}
+
 #[test]
 fn test_parse_all_clef_signs() {
     let signs = ["G", "F", "C", "percussion", "TAB", "jianpu", "none"];
@@ -3556,6 +3816,27 @@ fn test_parse_identification_element() {
     assert_eq!(id.source, Some("Manuscript".to_string()));
 }
 
+#[test]
+fn test_parse_defaults_with_concert_score() {
+    let xml = r#"<?xml version="1.0"?>
+            <score-partwise>
+                <defaults>
+                    <concert-score/>
+                </defaults>
+                <part-list>
+                    <score-part id="P1">
+                        <part-name>Clarinet in Bb</part-name>
+                    </score-part>
+                </part-list>
+                <part id="P1">
+                    <measure number="1"/>
+                </part>
+            </score-partwise>"#;
+
+    let score = parse_score(xml).unwrap();
+    assert!(score.defaults.unwrap().concert_score);
+}
+
 #[test]
 fn test_parse_defaults_with_scaling() {
     let xml = r#"<?xml version="1.0"?>
@@ -5862,6 +6143,127 @@ fn test_parse_technical_pluck_and_tap() {
     }
 }
 
+#[test]
+fn test_parse_technical_handbell() {
+    let xml = r#"<?xml version="1.0"?>
+            <score-partwise>
+                <part-list>
+                    <score-part id="P1">
+                        <part-name>Handbells</part-name>
+                    </score-part>
+                </part-list>
+                <part id="P1">
+                    <measure number="1">
+                        <note>
+                            <pitch><step>C</step><octave>5</octave></pitch>
+                            <duration>4</duration>
+                            <type>quarter</type>
+                            <notations>
+                                <technical>
+                                    <handbell>martellato</handbell>
+                                </technical>
+                            </notations>
+                        </note>
+                    </measure>
+                </part>
+            </score-partwise>"#;
+
+    let score = parse_score(xml).unwrap();
+    let crate::ir::measure::MusicDataElement::Note(note) = &score.parts[0].measures[0].content[0]
+    else {
+        panic!("expected note");
+    };
+    let crate::ir::notation::NotationContent::Technical(t) = &note.notations[0].content[0] else {
+        panic!("expected technical");
+    };
+    assert_eq!(
+        t.content,
+        vec![crate::ir::notation::TechnicalElement::Handbell(
+            crate::ir::notation::Handbell {
+                value: crate::ir::notation::HandbellValue::Martellato,
+            }
+        )]
+    );
+}
+
+#[test]
+fn test_parse_technical_hole_and_arrow() {
+    use crate::ir::notation::{
+        Arrow, ArrowDirection, ArrowStyle, Hole, HoleClosed, HoleClosedLocation, HoleClosedValue,
+        TechnicalElement,
+    };
+
+    let xml = r#"<?xml version="1.0"?>
+            <score-partwise>
+                <part-list>
+                    <score-part id="P1">
+                        <part-name>Flute</part-name>
+                    </score-part>
+                </part-list>
+                <part id="P1">
+                    <measure number="1">
+                        <note>
+                            <pitch><step>D</step><octave>5</octave></pitch>
+                            <duration>4</duration>
+                            <type>quarter</type>
+                            <notations>
+                                <technical>
+                                    <hole>
+                                        <hole-type>thumb</hole-type>
+                                        <hole-closed location="bottom">half</hole-closed>
+                                        <hole-shape>circle</hole-shape>
+                                    </hole>
+                                    <arrow>
+                                        <arrow-direction>up</arrow-direction>
+                                        <arrow-style>filled</arrow-style>
+                                    </arrow>
+                                    <arrow/>
+                                </technical>
+                            </notations>
+                        </note>
+                    </measure>
+                </part>
+            </score-partwise>"#;
+
+    let score = parse_score(xml).unwrap();
+    let crate::ir::measure::MusicDataElement::Note(note) = &score.parts[0].measures[0].content[0]
+    else {
+        panic!("expected note");
+    };
+    let crate::ir::notation::NotationContent::Technical(t) = &note.notations[0].content[0] else {
+        panic!("expected technical");
+    };
+    assert_eq!(
+        t.content,
+        vec![
+            TechnicalElement::Hole(Hole {
+                hole_type: Some("thumb".to_string()),
+                hole_closed: HoleClosed {
+                    value: HoleClosedValue::Half,
+                    location: Some(HoleClosedLocation::Bottom),
+                },
+                hole_shape: Some("circle".to_string()),
+            }),
+            TechnicalElement::Arrow(Arrow {
+                direction: Some(ArrowDirection::Up),
+                style: Some(ArrowStyle::Filled),
+                smufl: None,
+            }),
+            TechnicalElement::Arrow(Arrow::default()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_technical_hole_without_closed_is_error() {
+    let xml = r#"<score-partwise><part-list><score-part id="P1"><part-name>Flute</part-name></score-part></part-list>
+        <part id="P1"><measure number="1"><note><pitch><step>D</step><octave>5</octave></pitch>
+        <duration>4</duration><notations><technical><hole><hole-type>thumb</hole-type></hole></technical></notations>
+        </note></measure></part></score-partwise>"#;
+
+    assert!(parse_score(xml).is_err());
+}
+
 // =======================================================================
 // Barline Parsing Tests
 // =======================================================================
@@ -7875,6 +8277,41 @@ fn test_parse_direction_with_sound() {
     }
 }
 
+#[test]
+fn test_parse_sound_midi_instrument_change_survives_round_trip() {
+    let xml = r#"<?xml version="1.0"?>
+            <score-partwise>
+                <part-list>
+                    <score-part id="P1">
+                        <part-name>Trumpet</part-name>
+                    </score-part>
+                </part-list>
+                <part id="P1">
+                    <measure number="1">
+                        <direction>
+                            <direction-type>
+                                <words>con sord.</words>
+                            </direction-type>
+                            <sound><midi-instrument id="P1-I1"><midi-program>57</midi-program></midi-instrument></sound>
+                        </direction>
+                    </measure>
+                </part>
+            </score-partwise>"#;
+
+    let score = parse_score(xml).unwrap();
+    let crate::ir::measure::MusicDataElement::Direction(d) = &score.parts[0].measures[0].content[0]
+    else {
+        panic!("expected direction");
+    };
+    let sound = d.sound.as_ref().unwrap();
+    assert_eq!(sound.midi_instruments.len(), 1);
+    assert_eq!(sound.midi_instruments[0].id, "P1-I1");
+    assert_eq!(sound.midi_instruments[0].midi_program, Some(57));
+
+    let reparsed = parse_score(&crate::musicxml::emit(&score).unwrap()).unwrap();
+    assert_eq!(reparsed.parts, score.parts);
+}
+
 // === Accidental-Mark Tests ===
 
 #[test]
@@ -9417,6 +9854,75 @@ fn test_parse_measure_with_print() {
     assert!(!score.parts.is_empty());
 }
 
+// === Listening Element Tests ===
+
+#[test]
+fn test_parse_measure_with_listening_retains_sync() {
+    let xml = r#"<?xml version="1.0"?>
+            <score-partwise>
+                <part-list>
+                    <score-part id="P1">
+                        <part-name>Test</part-name>
+                    </score-part>
+                </part-list>
+                <part id="P1">
+                    <measure number="1">
+                        <listening><sync type="mostly-linear"/></listening>
+                    </measure>
+                </part>
+            </score-partwise>"#;
+
+    let score = parse_score(xml).unwrap();
+    let crate::ir::measure::MusicDataElement::Listening(listening) =
+        &score.parts[0].measures[0].content[0]
+    else {
+        panic!("expected listening");
+    };
+    assert_eq!(listening.syncs.len(), 1);
+    assert_eq!(listening.syncs[0].r#type, "mostly-linear");
+
+    let emitted = crate::musicxml::emit(&score).unwrap();
+    assert!(
+        emitted.contains("<listening>\n") && emitted.contains("<sync type=\"mostly-linear\"/>"),
+        "{}",
+        emitted
+    );
+}
+
+#[test]
+fn test_parse_listening_other_listening_and_offset() {
+    let xml = r#"<?xml version="1.0"?>
+            <score-partwise>
+                <part-list>
+                    <score-part id="P1">
+                        <part-name>Test</part-name>
+                    </score-part>
+                </part-list>
+                <part id="P1">
+                    <measure number="1">
+                        <listening>
+                            <sync type="event" latency="50" player="P1-I1"/>
+                            <other-listening type="follow" time-only="2">strict</other-listening>
+                            <offset>-2</offset>
+                        </listening>
+                    </measure>
+                </part>
+            </score-partwise>"#;
+
+    let score = parse_score(xml).unwrap();
+    let crate::ir::measure::MusicDataElement::Listening(listening) =
+        &score.parts[0].measures[0].content[0]
+    else {
+        panic!("expected listening");
+    };
+    assert_eq!(listening.syncs[0].latency, Some(50));
+    assert_eq!(listening.syncs[0].player.as_deref(), Some("P1-I1"));
+    assert_eq!(listening.other_listening[0].r#type, "follow");
+    assert_eq!(listening.other_listening[0].value, "strict");
+    assert_eq!(listening.other_listening[0].time_only.as_deref(), Some("2"));
+    assert_eq!(listening.offset.as_ref().unwrap().value, -2);
+}
+
 // === Attributes with Instruments ===
 
 #[test]
@@ -10659,6 +11165,51 @@ fn test_parse_multiple_credits() {
     assert_eq!(score.credits.len(), 2);
 }
 
+#[test]
+fn test_parse_credit_keeps_each_words_run() {
+    let xml = r#"<?xml version="1.0"?>
+        <score-partwise>
+            <credit page="1">
+                <credit-type>title</credit-type>
+                <credit-words default-x="600" default-y="1500" font-size="24" font-weight="bold" justify="center">Sonata</credit-words>
+                <credit-words font-size="14" font-style="italic">No. 14</credit-words>
+            </credit>
+            <part-list>
+                <score-part id="P1"><part-name>Test</part-name></score-part>
+            </part-list>
+            <part id="P1"><measure number="1"/></part>
+        </score-partwise>"#;
+    let score = parse_score(xml).unwrap();
+    let runs: Vec<_> = score.credits[0]
+        .content
+        .iter()
+        .filter_map(|c| match c {
+            CreditContent::CreditWords(cw) => Some(cw),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(runs.len(), 2);
+
+    assert_eq!(runs[0].value, "Sonata");
+    assert_eq!(
+        runs[0].print_style.font.font_size,
+        Some(FontSize::Points(24.0))
+    );
+    assert_eq!(runs[0].print_style.font.font_weight, Some(FontWeight::Bold));
+    assert_eq!(runs[0].print_style.position.default_y, Some(1500.0));
+
+    assert_eq!(runs[1].value, "No. 14");
+    assert_eq!(
+        runs[1].print_style.font.font_size,
+        Some(FontSize::Points(14.0))
+    );
+    assert_eq!(runs[1].print_style.font.font_style, Some(FontStyle::Italic));
+
+    // Both runs survive a round trip through the emitter
+    let reparsed = parse_score(&crate::musicxml::emit(&score).unwrap()).unwrap();
+    assert_eq!(reparsed.credits, score.credits);
+}
+
 // === Dynamics Self-closing ===
 
 #[test]
@@ -10742,3 +11293,204 @@ fn test_parse_lyric_extend_self_closing() {
         panic!("Expected Note element");
     }
 }
+
+// === Instrument references ===
+
+const DRUM_KIT_XML: &str = r#"<?xml version="1.0"?>
+<score-partwise>
+    <part-list>
+        <score-part id="P1">
+            <part-name>Drum Set</part-name>
+            <score-instrument id="P1-I1">
+                <instrument-name>Snare Drum</instrument-name>
+            </score-instrument>
+            <score-instrument id="P1-I2">
+                <instrument-name>Bass Drum</instrument-name>
+                <solo/>
+            </score-instrument>
+        </score-part>
+    </part-list>
+    <part id="P1">
+        <measure number="1">
+            <note>
+                <unpitched><display-step>C</display-step><display-octave>5</display-octave></unpitched>
+                <duration>1</duration>
+                <instrument id="P1-I1"/>
+            </note>
+            <note>
+                <unpitched><display-step>F</display-step><display-octave>4</display-octave></unpitched>
+                <duration>1</duration>
+                <instrument id="P1-I2"></instrument>
+            </note>
+        </measure>
+    </part>
+</score-partwise>"#;
+
+#[test]
+fn test_parse_score_instruments() {
+    let score = parse_score(DRUM_KIT_XML).unwrap();
+    let PartListElement::ScorePart(sp) = &score.part_list.content[0] else {
+        panic!("Expected ScorePart");
+    };
+    assert_eq!(sp.score_instruments.len(), 2);
+    assert_eq!(sp.score_instruments[0].id, "P1-I1");
+    assert_eq!(sp.score_instruments[0].instrument_name, "Snare Drum");
+    assert_eq!(
+        sp.score_instruments[1].solo_or_ensemble,
+        Some(SoloOrEnsemble::Solo)
+    );
+}
+
+#[test]
+fn test_parse_note_instrument_references() {
+    let score = parse_score(DRUM_KIT_XML).unwrap();
+    let ids: Vec<&str> = score.parts[0].measures[0]
+        .content
+        .iter()
+        .filter_map(|element| match element {
+            crate::ir::measure::MusicDataElement::Note(note) => {
+                Some(note.instrument[0].id.as_str())
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(ids, vec!["P1-I1", "P1-I2"]);
+}
+
+#[test]
+fn test_parse_note_instrument_undefined_reference() {
+    let xml = DRUM_KIT_XML.replace(r#"<instrument id="P1-I2">"#, r#"<instrument id="P1-I9">"#);
+    let result = parse_score(&xml);
+    if let Err(ParseError::UndefinedReference {
+        reference_type, id, ..
+    }) = result
+    {
+        assert_eq!(reference_type, "instrument");
+        assert_eq!(id, "P1-I9");
+    } else {
+        panic!("Expected UndefinedReference error");
+    }
+}
+
+// === Print (system/page breaks) ===
+
+fn first_print(score: &ScorePartwise) -> &crate::ir::measure::Print {
+    match &score.parts[0].measures[0].content[0] {
+        crate::ir::measure::MusicDataElement::Print(print) => print,
+        other => panic!("Expected Print element, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_print_new_system_empty() {
+    let xml = minimal_xml(
+        r#"<print new-system="yes"/>
+        <note><rest/><duration>4</duration></note>"#,
+    );
+    let score = parse_score(&xml).unwrap();
+    let print = first_print(&score);
+    assert_eq!(print.new_system, Some(YesNo::Yes));
+    assert_eq!(print.new_page, None);
+    assert_eq!(score.parts[0].measures[0].content.len(), 2);
+}
+
+#[test]
+fn test_parse_print_with_layouts() {
+    let xml = minimal_xml(
+        r#"<print new-page="yes" page-number="3">
+            <system-layout>
+                <system-margins><left-margin>70</left-margin><right-margin>0</right-margin></system-margins>
+                <top-system-distance>211</top-system-distance>
+            </system-layout>
+            <staff-layout number="2"><staff-distance>65</staff-distance></staff-layout>
+            <measure-layout><measure-distance>20</measure-distance></measure-layout>
+        </print>"#,
+    );
+    let score = parse_score(&xml).unwrap();
+    let print = first_print(&score);
+    assert_eq!(print.new_page, Some(YesNo::Yes));
+    assert_eq!(print.page_number.as_deref(), Some("3"));
+    let system_layout = print.system_layout.as_ref().unwrap();
+    assert_eq!(system_layout.top_system_distance, Some(211.0));
+    assert_eq!(system_layout.system_margins.as_ref().unwrap().left, 70.0);
+    assert_eq!(print.staff_layouts.len(), 1);
+    assert_eq!(print.staff_layouts[0].number, Some(2));
+    assert_eq!(print.staff_layouts[0].staff_distance, Some(65.0));
+    assert_eq!(
+        print.measure_layout.as_ref().unwrap().measure_distance,
+        Some(20.0)
+    );
+}
+
+#[test]
+fn test_print_round_trip() {
+    let xml = minimal_xml(
+        r#"<print new-system="yes"><staff-layout number="1"><staff-distance>80</staff-distance></staff-layout></print>"#,
+    );
+    let score = parse_score(&xml).unwrap();
+    let emitted = crate::musicxml::emit(&score).unwrap();
+    assert!(emitted.contains(r#"<print new-system="yes">"#));
+    let reparsed = parse_score(&emitted).unwrap();
+    assert_eq!(first_print(&reparsed), first_print(&score));
+}
+
+#[test]
+fn test_parse_score_deep_nesting_returns_error() {
+    let xml = format!(
+        "<score-partwise><part-list>{}",
+        r#"<part-group type="start">"#.repeat(1000)
+    );
+    let err = parse_score(&xml).unwrap_err();
+    assert!(matches!(
+        &err,
+        ParseError::Other { message, .. } if message == "maximum nesting depth exceeded"
+    ));
+}
+
+#[test]
+fn test_parse_score_with_options_custom_depth_limit() {
+    let xml = r#"<score-partwise version="4.0">
+  <part-list>
+    <score-part id="P1"><part-name>Piano</part-name></score-part>
+  </part-list>
+  <part id="P1"><measure number="1"/></part>
+</score-partwise>"#;
+    assert!(parse_score_with_options(xml, &ParseOptions::default()).is_ok());
+
+    let shallow = ParseOptions {
+        max_depth: 2,
+        ..ParseOptions::default()
+    };
+    assert!(parse_score_with_options(xml, &shallow).is_err());
+}
+
+#[test]
+fn test_parse_score_with_skipped_reports_name_and_position() {
+    let xml = r#"<score-partwise version="4.0">
+  <part-list>
+    <score-part id="P1"><part-name>Piano</part-name></score-part>
+  </part-list>
+  <part id="P1">
+    <measure number="1">
+      <harmony><root><root-step>C</root-step></root><kind>major</kind></harmony>
+    </measure>
+  </part>
+</score-partwise>"#;
+    let options = ParseOptions {
+        record_skipped: true,
+        ..ParseOptions::default()
+    };
+
+    let (score, skipped) = parse_score_with_skipped(xml, &options).unwrap();
+
+    assert_eq!(score.parts.len(), 1);
+    assert_eq!(
+        skipped,
+        vec![SkippedElement {
+            name: "harmony".to_string(),
+            position: xml.find("<harmony>").unwrap(),
+        }]
+    );
+    let (_, skipped) = parse_score_with_skipped(xml, &ParseOptions::default()).unwrap();
+    assert!(skipped.is_empty());
+}
@@ -13,7 +13,7 @@ use quick_xml::events::attributes::Attributes;
 use quick_xml::events::{BytesStart, Event};
 use std::str::FromStr;
 
-use super::ParseError;
+use super::{ParseError, ParseOptions};
 
 /// A wrapper around quick-xml's Reader with helper methods for parsing MusicXML.
 ///
@@ -26,10 +26,30 @@ use super::ParseError;
 pub(crate) struct XmlReader<'a> {
     /// The underlying quick-xml reader
     reader: Reader<&'a [u8]>,
-    /// Buffer for storing the peeked event (if any)
-    peeked: Option<Event<'static>>,
+    /// Buffer for storing the peeked event (if any) and where it starts
+    peeked: Option<(Event<'static>, usize)>,
     /// Buffer for reading events
     buf: Vec<u8>,
+    /// Current element nesting depth of events read so far
+    depth: usize,
+    /// Deepest nesting allowed before reading fails
+    max_depth: usize,
+    /// Byte offset of the last event returned by `next_event`
+    event_start: usize,
+    /// Elements passed to `skip_element`, when recording
+    skipped: Option<Vec<SkippedElement>>,
+}
+
+/// An element the parser skipped because Fermata does not read it.
+///
+/// Collected when [`ParseOptions::record_skipped`] is set; see
+/// [`parse_score_with_skipped`](super::parse_score_with_skipped).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedElement {
+    /// Element name, e.g. `harmony`
+    pub name: String,
+    /// Byte offset of the element's start tag in the document
+    pub position: usize,
 }
 
 impl<'a> XmlReader<'a> {
@@ -43,15 +63,40 @@ impl<'a> XmlReader<'a> {
     ///
     /// A new XmlReader ready for parsing
     pub fn new(xml: &'a str) -> Self {
+        Self::with_max_depth(xml, ParseOptions::DEFAULT_MAX_DEPTH)
+    }
+
+    /// Create a new XmlReader configured by `options`.
+    pub fn with_options(xml: &'a str, options: &ParseOptions) -> Self {
+        let mut reader = Self::with_max_depth(xml, options.max_depth);
+        if options.record_skipped {
+            reader.skipped = Some(Vec::new());
+        }
+        reader
+    }
+
+    /// Create a new XmlReader that fails once elements nest deeper than
+    /// `max_depth`.
+    pub fn with_max_depth(xml: &'a str, max_depth: usize) -> Self {
         let mut reader = Reader::from_reader(xml.as_bytes());
         reader.config_mut().trim_text(true);
         XmlReader {
             reader,
             peeked: None,
             buf: Vec::new(),
+            depth: 0,
+            max_depth,
+            event_start: 0,
+            skipped: None,
         }
     }
 
+    /// The elements skipped so far, in document order, or an empty list if
+    /// the reader was not asked to record them.
+    pub fn take_skipped(&mut self) -> Vec<SkippedElement> {
+        self.skipped.take().unwrap_or_default()
+    }
+
     /// Get the current byte position in the input.
     ///
     /// This is useful for error reporting to indicate where in the
@@ -70,12 +115,12 @@ impl<'a> XmlReader<'a> {
     ///
     /// The next XML event, or an error if parsing fails.
     pub fn next_event(&mut self) -> Result<Event<'static>, ParseError> {
-        if let Some(event) = self.peeked.take() {
-            return Ok(event);
-        }
-        self.buf.clear();
-        let event = self.reader.read_event_into(&mut self.buf)?;
-        Ok(event.into_owned())
+        let (event, start) = match self.peeked.take() {
+            Some(peeked) => peeked,
+            None => self.read_event()?,
+        };
+        self.event_start = start;
+        Ok(event)
     }
 
     /// Peek at the next event without consuming it.
@@ -88,11 +133,36 @@ impl<'a> XmlReader<'a> {
     /// A reference to the next XML event, or an error if parsing fails.
     pub fn peek_event(&mut self) -> Result<&Event<'static>, ParseError> {
         if self.peeked.is_none() {
-            self.buf.clear();
-            let event = self.reader.read_event_into(&mut self.buf)?;
-            self.peeked = Some(event.into_owned());
+            let peeked = self.read_event()?;
+            self.peeked = Some(peeked);
         }
-        Ok(self.peeked.as_ref().unwrap())
+        Ok(&self.peeked.as_ref().unwrap().0)
+    }
+
+    /// Read an event from the underlying reader, tracking nesting depth.
+    ///
+    /// Returns the event with the byte offset of its start tag; other
+    /// events report the offset just past them.
+    fn read_event(&mut self) -> Result<(Event<'static>, usize), ParseError> {
+        self.buf.clear();
+        let event = self.reader.read_event_into(&mut self.buf)?.into_owned();
+        let mut start = self.position();
+        match &event {
+            Event::Start(e) => {
+                // The raw tag sits between `<` and `>`
+                start -= e.len() + 2;
+                self.depth += 1;
+                if self.depth > self.max_depth {
+                    return Err(ParseError::other(
+                        "maximum nesting depth exceeded",
+                        Some(self.position()),
+                    ));
+                }
+            }
+            Event::End(_) => self.depth = self.depth.saturating_sub(1),
+            _ => {}
+        }
+        Ok((event, start))
     }
 
     /// Read the text content of the current element.
@@ -190,6 +260,12 @@ impl<'a> XmlReader<'a> {
     ///
     /// Ok(()) on success, or an error if parsing fails.
     pub fn skip_element(&mut self, element_name: &str) -> Result<(), ParseError> {
+        if let Some(skipped) = &mut self.skipped {
+            skipped.push(SkippedElement {
+                name: element_name.to_string(),
+                position: self.event_start,
+            });
+        }
         let mut depth = 1usize;
         loop {
             let event = self.next_event()?;
@@ -12,7 +12,9 @@
 #![allow(dead_code)]
 
 use super::ParseError;
-use crate::ir::attributes::{BarStyle, CancelLocation, ClefSign, Mode, TimeSymbol, Winged};
+use crate::ir::attributes::{
+    BarStyle, CancelLocation, ClefSign, GroupSymbolValue, Mode, TimeSymbol, Winged,
+};
 use crate::ir::beam::{BeamValue, Fan, NoteheadValue, StemValue};
 use crate::ir::common::{
     AboveBelow, AccidentalValue, BackwardForward, CssFontSize, FontSize, LeftCenterRight, LineType,
@@ -114,6 +116,25 @@ pub(crate) fn parse_clef_sign(s: &str, position: usize) -> Result<ClefSign, Pars
     }
 }
 
+// === Group Symbol ===
+
+/// Parse a group-symbol-value (used by part-group and part-symbol).
+///
+/// Valid values: "none", "brace", "line", "bracket", "square"
+pub(crate) fn parse_group_symbol_value(
+    s: &str,
+    position: usize,
+) -> Result<GroupSymbolValue, ParseError> {
+    match s {
+        "none" => Ok(GroupSymbolValue::None),
+        "brace" => Ok(GroupSymbolValue::Brace),
+        "line" => Ok(GroupSymbolValue::Line),
+        "bracket" => Ok(GroupSymbolValue::Bracket),
+        "square" => Ok(GroupSymbolValue::Square),
+        _ => Err(ParseError::invalid_value("group-symbol-value", s, position)),
+    }
+}
+
 // === Time Symbol ===
 
 /// Parse a time-symbol value.
@@ -1037,6 +1058,33 @@ mod tests {
         assert_eq!(parse_clef_sign("none", 0).unwrap(), ClefSign::None);
     }
 
+    // === Group Symbol Tests ===
+
+    #[test]
+    fn test_parse_group_symbol_value_all_values() {
+        assert_eq!(
+            parse_group_symbol_value("none", 0).unwrap(),
+            GroupSymbolValue::None
+        );
+        assert_eq!(
+            parse_group_symbol_value("brace", 0).unwrap(),
+            GroupSymbolValue::Brace
+        );
+        assert_eq!(
+            parse_group_symbol_value("line", 0).unwrap(),
+            GroupSymbolValue::Line
+        );
+        assert_eq!(
+            parse_group_symbol_value("bracket", 0).unwrap(),
+            GroupSymbolValue::Bracket
+        );
+        assert_eq!(
+            parse_group_symbol_value("square", 0).unwrap(),
+            GroupSymbolValue::Square
+        );
+        assert!(parse_group_symbol_value("Brace", 0).is_err());
+    }
+
     // === Time Symbol Tests ===
 
     #[test]
@@ -5,19 +5,70 @@
 
 use quick_xml::Writer;
 use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
-use std::io::Cursor;
+use std::io::Write;
+
+use super::options::Indent;
+
+/// Destination for emitted bytes: an owned buffer or a borrowed stream.
+enum Sink<'a> {
+    Buffer(Vec<u8>),
+    Stream(&'a mut dyn Write),
+}
+
+impl Write for Sink<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Sink::Buffer(v) => v.write(buf),
+            Sink::Stream(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Sink::Buffer(_) => Ok(()),
+            Sink::Stream(w) => w.flush(),
+        }
+    }
+}
 
 /// A wrapper around `quick_xml::Writer` with helper methods for MusicXML emission.
-pub struct XmlWriter {
-    writer: Writer<Cursor<Vec<u8>>>,
+///
+/// Writes either into an in-memory buffer ([`XmlWriter::new`]) or straight
+/// to any [`std::io::Write`] ([`XmlWriter::to_stream`]).
+pub struct XmlWriter<'a> {
+    writer: Writer<Sink<'a>>,
+    indent: Indent,
 }
 
 #[allow(dead_code)]
-impl XmlWriter {
-    /// Create a new XmlWriter with 2-space indentation.
+impl<'a> XmlWriter<'a> {
+    /// Create a new buffering XmlWriter with 2-space indentation.
     pub fn new() -> Self {
-        let writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
-        Self { writer }
+        Self::with_indent(Indent::default())
+    }
+
+    /// Create a new buffering XmlWriter with the given indentation.
+    pub fn with_indent(indent: Indent) -> Self {
+        Self::from_sink(Sink::Buffer(Vec::new()), indent)
+    }
+
+    /// Create an XmlWriter that streams to `out` with 2-space indentation.
+    pub fn to_stream(out: &'a mut dyn Write) -> Self {
+        Self::to_stream_with_indent(out, Indent::default())
+    }
+
+    /// Create an XmlWriter that streams to `out` with the given indentation.
+    pub fn to_stream_with_indent(out: &'a mut dyn Write, indent: Indent) -> Self {
+        Self::from_sink(Sink::Stream(out), indent)
+    }
+
+    fn from_sink(sink: Sink<'a>, indent: Indent) -> Self {
+        let writer = match indent {
+            Indent::None => Writer::new(sink),
+            Indent::Spaces(n) => Writer::new_with_indent(sink, b' ', n),
+            Indent::Tabs => Writer::new_with_indent(sink, b'\t', 1),
+        };
+        Self { writer, indent }
     }
 
     /// Write XML declaration and DOCTYPE for MusicXML 4.0 partwise.
@@ -28,17 +79,23 @@ impl XmlWriter {
     /// <!DOCTYPE score-partwise PUBLIC "-//Recordare//DTD MusicXML 4.0 Partwise//EN" "http://www.musicxml.org/dtds/partwise.dtd">
     /// ```
     pub fn write_header(&mut self) -> Result<(), std::io::Error> {
-        use std::io::Write;
-
         // <?xml version="1.0" encoding="UTF-8"?>
         self.writer
             .write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
 
-        // DOCTYPE for MusicXML 4.0 partwise
+        // DOCTYPE for MusicXML 4.0 partwise, on its own line unless compact
         // Use Write trait to properly update cursor position
-        self.writer.get_mut().write_all(
-            b"\n<!DOCTYPE score-partwise PUBLIC \"-//Recordare//DTD MusicXML 4.0 Partwise//EN\" \"http://www.musicxml.org/dtds/partwise.dtd\">\n"
+        let newline: &[u8] = if self.indent == Indent::None {
+            b""
+        } else {
+            b"\n"
+        };
+        let out = self.writer.get_mut();
+        out.write_all(newline)?;
+        out.write_all(
+            b"<!DOCTYPE score-partwise PUBLIC \"-//Recordare//DTD MusicXML 4.0 Partwise//EN\" \"http://www.musicxml.org/dtds/partwise.dtd\">"
         )?;
+        out.write_all(newline)?;
         Ok(())
     }
 
@@ -96,13 +153,23 @@ impl XmlWriter {
         self.writer.write_event(Event::Text(BytesText::new(text)))
     }
 
+    /// Flush any buffered output to the underlying stream.
+    pub fn flush(&mut self) -> Result<(), std::io::Error> {
+        self.writer.get_mut().flush()
+    }
+
     /// Consume the writer and return the XML string.
+    ///
+    /// A streaming writer has no buffer, so this returns an empty string.
     pub fn into_string(self) -> Result<String, std::string::FromUtf8Error> {
-        String::from_utf8(self.writer.into_inner().into_inner())
+        match self.writer.into_inner() {
+            Sink::Buffer(bytes) => String::from_utf8(bytes),
+            Sink::Stream(_) => Ok(String::new()),
+        }
     }
 }
 
-impl Default for XmlWriter {
+impl Default for XmlWriter<'_> {
     fn default() -> Self {
         Self::new()
     }
@@ -175,6 +242,21 @@ mod tests {
         assert!(result.contains("MusicXML 4.0"));
     }
 
+    #[test]
+    fn test_xmlwriter_indent_styles() {
+        let nested = |indent| {
+            let mut writer = XmlWriter::with_indent(indent);
+            writer.start_element("note").unwrap();
+            writer.empty_element("rest").unwrap();
+            writer.end_element("note").unwrap();
+            writer.into_string().unwrap()
+        };
+
+        assert_eq!(nested(Indent::None), "<note><rest/></note>");
+        assert_eq!(nested(Indent::Spaces(4)), "<note>\n    <rest/>\n</note>");
+        assert_eq!(nested(Indent::Tabs), "<note>\n\t<rest/>\n</note>");
+    }
+
     #[test]
     fn test_xmlwriter_start_end_element() {
         let mut writer = XmlWriter::new();
@@ -2,9 +2,17 @@
 
 use owo_colors::OwoColorize;
 
+use crate::lang::{compile_sexpr, parse_score_to_ast};
+
+use super::display::{format_as_musicxml, format_compile_error, format_info};
 use super::error::ReplResult;
 use super::session::{DisplayMode, ReplSession};
 
+/// Names of all available commands, as listed for unknown commands.
+const COMMAND_NAMES: &[&str] = &[
+    "help", "quit", "clear", "banner", "set", "settings", "load", "ast", "emit", "reset",
+];
+
 /// Result of executing a command.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CommandResult {
@@ -41,14 +49,90 @@ pub fn dispatch(
         "banner" => Ok(CommandResult::ShowBanner),
         "set" => cmd_set(args, session),
         "settings" => Ok(cmd_settings(session)),
+        "load" | "l" => Ok(cmd_load(args, session, use_colors)),
+        "ast" => Ok(cmd_ast(session)),
+        "emit" => Ok(cmd_emit(args, session, use_colors)),
+        "reset" => Ok(cmd_reset(session, use_colors)),
         "" => Ok(CommandResult::Continue),
         other => Ok(CommandResult::Output(format!(
-            "Unknown command: :{}\nType :help for available commands.",
-            other
+            "Unknown command: :{}\nAvailable commands: {}\nType :help for details.",
+            other,
+            COMMAND_NAMES
+                .iter()
+                .map(|name| format!(":{}", name))
+                .collect::<Vec<_>>()
+                .join(", ")
         ))),
     }
 }
 
+/// Handle the :load command.
+///
+/// Reads a `.fm` file, compiles it, and stores the expression and result
+/// in the session history as if it had been typed at the prompt.
+fn cmd_load(path: &str, session: &mut ReplSession, use_colors: bool) -> CommandResult {
+    if path.is_empty() {
+        return CommandResult::Output("Usage: :load <file>".to_string());
+    }
+
+    let source = match std::fs::read_to_string(path) {
+        Ok(s) => s,
+        Err(e) => return CommandResult::Output(format!("Cannot read '{}': {}", path, e)),
+    };
+
+    let sexpr = match session.parse_cache().get_or_parse(&source) {
+        Ok(s) => s,
+        Err(e) => return CommandResult::Output(format_compile_error(&e.into(), use_colors)),
+    };
+
+    match compile_sexpr(&sexpr) {
+        Ok(score) => {
+            session.push_expression(sexpr.as_ref().clone());
+            session.push_result(score);
+            CommandResult::Output(format_info(&format!("Loaded {}", path), use_colors))
+        }
+        Err(e) => CommandResult::Output(format_compile_error(&e, use_colors)),
+    }
+}
+
+/// Handle the :ast command: pretty-print the AST of the last input.
+fn cmd_ast(session: &ReplSession) -> CommandResult {
+    let Some(expr) = session.get_expression("+") else {
+        return CommandResult::Output("No input yet. Evaluate or :load a score first.".to_string());
+    };
+
+    match parse_score_to_ast(expr) {
+        Ok(ast) => CommandResult::Output(format!("{:#?}", ast)),
+        Err(_) => CommandResult::Output(format!("{:#?}", expr)),
+    }
+}
+
+/// Handle the :emit command: show the compiled output of the last result.
+fn cmd_emit(target: &str, session: &ReplSession, use_colors: bool) -> CommandResult {
+    let Some(score) = session.get_result("*") else {
+        return CommandResult::Output(
+            "Nothing to emit. Evaluate or :load a score first.".to_string(),
+        );
+    };
+
+    match target.to_lowercase().as_str() {
+        "" | "xml" | "musicxml" => CommandResult::Output(format_as_musicxml(score, use_colors)),
+        "ly" | "lilypond" => {
+            CommandResult::Output("LilyPond output is not yet implemented".to_string())
+        }
+        other => CommandResult::Output(format!(
+            "Unknown emit target: '{}'\nValid targets: xml, ly",
+            other
+        )),
+    }
+}
+
+/// Handle the :reset command.
+fn cmd_reset(session: &mut ReplSession, use_colors: bool) -> CommandResult {
+    session.reset();
+    CommandResult::Output(format_info("Session history cleared", use_colors))
+}
+
 /// Handle the :set command.
 fn cmd_set(args: &str, session: &mut ReplSession) -> ReplResult<CommandResult> {
     let trimmed = args.trim();
@@ -209,6 +293,10 @@ fn general_help(use_colors: bool) -> String {
   {}          Clear the screen
   {}   Set display mode (sexpr, musicxml, png, silent)
   {}             Show current settings
+  {}         Load and evaluate a .fm file
+  {}                  Show the AST of the last input
+  {}         Show compiled output of the last result
+  {}                Clear session history
 
 {}
   {}      Last 1-3 evaluated results
@@ -236,6 +324,10 @@ fn general_help(use_colors: bool) -> String {
         cmd(":clear, :cls", use_colors),
         cmd(":set display <mode>", use_colors),
         cmd(":settings", use_colors),
+        cmd(":load <file>", use_colors),
+        cmd(":ast", use_colors),
+        cmd(":emit xml|ly", use_colors),
+        cmd(":reset", use_colors),
         header("HISTORY VARIABLES:", use_colors),
         cmd("*, **, ***", use_colors),
         cmd("+, ++, +++", use_colors),
@@ -260,6 +352,10 @@ Commands start with ':' and control the REPL itself.
   {}          Clear the screen
   {}   Set output display mode
   {}             Show current settings
+  {}         Load and evaluate a .fm file
+  {}                  Show the AST of the last input
+  {}         Show compiled output of the last result
+  {}                Clear session history
 
 {}
   {}     S-expression output (default, for debugging)
@@ -281,6 +377,10 @@ Commands start with ':' and control the REPL itself.
         cmd(":clear, :cls", use_colors),
         cmd(":set display <mode>", use_colors),
         cmd(":settings", use_colors),
+        cmd(":load <file>", use_colors),
+        cmd(":ast", use_colors),
+        cmd(":emit xml|ly", use_colors),
+        cmd(":reset", use_colors),
         header("DISPLAY MODES:", use_colors),
         cmd("sexpr", use_colors),
         cmd("musicxml", use_colors),
@@ -384,6 +484,8 @@ Chat messages start with '/' and are used for communication
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lang::compile;
+    use crate::sexpr::parser::parse as parse_sexpr;
 
     #[test]
     fn test_dispatch_help() {
@@ -684,4 +786,141 @@ mod tests {
         assert_eq!(a, b);
         assert_ne!(a, c);
     }
+
+    // === :load / :ast / :emit / :reset tests ===
+
+    const SCORE_SOURCE: &str = "(score :title \"Loaded\" (part :piano (measure (note c4 :w))))";
+
+    /// Write `source` to a unique temp file and return its path.
+    fn write_temp_source(name: &str, source: &str) -> std::path::PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("fermata-repl-{}-{}.fm", name, std::process::id()));
+        std::fs::write(&path, source).unwrap();
+        path
+    }
+
+    fn output_of(result: CommandResult) -> String {
+        match result {
+            CommandResult::Output(s) => s,
+            other => panic!("Expected Output, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_dispatch_load_stores_result_and_expression() {
+        let path = write_temp_source("load", SCORE_SOURCE);
+        let mut session = ReplSession::new();
+
+        let cmd = format!("load {}", path.display());
+        let out = output_of(dispatch(&cmd, &mut session, false).unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(out.contains("Loaded"));
+        assert!(session.get_result("*").is_some());
+        assert!(session.get_expression("+").is_some());
+        assert_eq!(session.parse_cache().len(), 1);
+    }
+
+    #[test]
+    fn test_dispatch_load_missing_file() {
+        let mut session = ReplSession::new();
+        let out = output_of(dispatch("load /no/such/file.fm", &mut session, false).unwrap());
+        assert!(out.contains("Cannot read"));
+        assert!(session.get_result("*").is_none());
+    }
+
+    #[test]
+    fn test_dispatch_load_no_args_shows_usage() {
+        let mut session = ReplSession::new();
+        let out = output_of(dispatch("load", &mut session, false).unwrap());
+        assert!(out.contains("Usage: :load"));
+    }
+
+    #[test]
+    fn test_dispatch_load_compile_error() {
+        let path = write_temp_source("load-error", "(score (part :piano (measure (note zz :q))))");
+        let mut session = ReplSession::new();
+
+        let cmd = format!("load {}", path.display());
+        let out = output_of(dispatch(&cmd, &mut session, false).unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(out.contains("Error"));
+        assert!(session.get_result("*").is_none());
+    }
+
+    #[test]
+    fn test_dispatch_ast_after_load() {
+        let path = write_temp_source("ast", SCORE_SOURCE);
+        let mut session = ReplSession::new();
+        dispatch(&format!("load {}", path.display()), &mut session, false).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let out = output_of(dispatch("ast", &mut session, false).unwrap());
+        assert!(out.contains("FermataScore"));
+        assert!(out.contains("Loaded"));
+    }
+
+    #[test]
+    fn test_dispatch_ast_without_input() {
+        let mut session = ReplSession::new();
+        let out = output_of(dispatch("ast", &mut session, false).unwrap());
+        assert!(out.contains("No input yet"));
+    }
+
+    #[test]
+    fn test_dispatch_emit_xml() {
+        let mut session = ReplSession::new();
+        session.push_result(compile(SCORE_SOURCE).unwrap());
+
+        let out = output_of(dispatch("emit xml", &mut session, false).unwrap());
+        assert!(out.contains("<score-partwise"));
+        assert!(out.contains("<step>C</step>"));
+    }
+
+    #[test]
+    fn test_dispatch_emit_ly_not_implemented() {
+        let mut session = ReplSession::new();
+        session.push_result(compile(SCORE_SOURCE).unwrap());
+
+        let out = output_of(dispatch("emit ly", &mut session, false).unwrap());
+        assert!(out.contains("not yet implemented"));
+    }
+
+    #[test]
+    fn test_dispatch_emit_unknown_target() {
+        let mut session = ReplSession::new();
+        session.push_result(compile(SCORE_SOURCE).unwrap());
+
+        let out = output_of(dispatch("emit pdf", &mut session, false).unwrap());
+        assert!(out.contains("Unknown emit target"));
+    }
+
+    #[test]
+    fn test_dispatch_emit_without_result() {
+        let mut session = ReplSession::new();
+        let out = output_of(dispatch("emit xml", &mut session, false).unwrap());
+        assert!(out.contains("Nothing to emit"));
+    }
+
+    #[test]
+    fn test_dispatch_reset_clears_history() {
+        let mut session = ReplSession::new();
+        session.push_result(compile(SCORE_SOURCE).unwrap());
+        session.push_expression(parse_sexpr(SCORE_SOURCE).unwrap());
+
+        dispatch("reset", &mut session, false).unwrap();
+
+        assert!(session.get_result("*").is_none());
+        assert!(session.get_expression("+").is_none());
+    }
+
+    #[test]
+    fn test_dispatch_unknown_lists_commands() {
+        let mut session = ReplSession::new();
+        let out = output_of(dispatch("lod", &mut session, false).unwrap());
+        assert!(out.contains(":load"));
+        assert!(out.contains(":emit"));
+        assert!(out.contains(":reset"));
+    }
 }
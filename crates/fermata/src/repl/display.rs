@@ -5,6 +5,7 @@ use owo_colors::OwoColorize;
 use crate::ir::score::ScorePartwise;
 use crate::lang::error::CompileError;
 use crate::musicxml;
+use crate::report::{Colored, Plain, Reporter, Style};
 use crate::sexpr::{ToSexpr, print_sexpr};
 
 use super::session::{DisplayMode, RenderOptions};
@@ -52,31 +53,18 @@ pub fn format_as_sexpr(score: &ScorePartwise, use_colors: bool) -> String {
     let sexpr = score.to_sexpr();
     let output = print_sexpr(&sexpr);
 
-    if use_colors {
-        format!("{}", output.green())
-    } else {
-        output
-    }
+    paint(&output, Style::Success, use_colors)
 }
 
 /// Format a score as MusicXML.
 pub fn format_as_musicxml(score: &ScorePartwise, use_colors: bool) -> String {
     match musicxml::emit(score) {
-        Ok(xml) => {
-            if use_colors {
-                format!("{}", xml.cyan())
-            } else {
-                xml
-            }
-        }
-        Err(e) => {
-            let msg = format!("Failed to emit MusicXML: {}", e);
-            if use_colors {
-                format!("{}: {}", "Error".red(), msg)
-            } else {
-                format!("Error: {}", msg)
-            }
-        }
+        Ok(xml) => paint(&xml, Style::Info, use_colors),
+        Err(e) => format!(
+            "{}: Failed to emit MusicXML: {}",
+            paint("Error", Style::Error, use_colors),
+            e
+        ),
     }
 }
 
@@ -87,11 +75,7 @@ fn format_render_placeholder(format: &str, use_colors: bool) -> String {
         "({} rendering requires 'render' feature - use :set display sexpr)",
         format
     );
-    if use_colors {
-        format!("{}", msg.yellow())
-    } else {
-        msg
-    }
+    paint(&msg, Style::Warning, use_colors)
 }
 
 /// Format a successful evaluation result (legacy, defaults to sexpr).
@@ -101,31 +85,21 @@ pub fn format_eval_result(score: &ScorePartwise, use_colors: bool) -> String {
 
 /// Format a compilation error.
 pub fn format_compile_error(error: &CompileError, use_colors: bool) -> String {
-    let msg = error.to_string();
-
-    if use_colors {
-        format!("{}: {}", "Error".red(), msg)
-    } else {
-        format!("Error: {}", msg)
-    }
+    format!("{}: {}", paint("Error", Style::Error, use_colors), error)
 }
 
 /// Format a general message (info level).
 pub fn format_info(message: &str, use_colors: bool) -> String {
-    if use_colors {
-        format!("{}", message.cyan())
-    } else {
-        message.to_string()
-    }
+    paint(message, Style::Info, use_colors)
 }
 
 /// Format a warning message.
 pub fn format_warning(message: &str, use_colors: bool) -> String {
-    if use_colors {
-        format!("{}: {}", "Warning".yellow(), message)
-    } else {
-        format!("Warning: {}", message)
-    }
+    format!(
+        "{}: {}",
+        paint("Warning", Style::Warning, use_colors),
+        message
+    )
 }
 
 /// Format the REPL banner shown at startup.
@@ -149,6 +123,15 @@ pub fn format_banner(banner_text: &str, use_colors: bool) -> String {
     }
 }
 
+/// Style `text` as the command-line reporters do.
+fn paint(text: &str, style: Style, use_colors: bool) -> String {
+    if use_colors {
+        Colored.paint(text, style)
+    } else {
+        Plain.paint(text, style)
+    }
+}
+
 /// Format a chat message (stub for Phase 6b).
 pub fn format_chat_stub(kind: &str, message: &str, use_colors: bool) -> String {
     let output = format!("[Chat {}]: {}", kind, message);
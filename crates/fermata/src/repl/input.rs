@@ -78,24 +78,32 @@ fn classify_chat(input: &str) -> InputKind {
 
 /// Check if input needs more lines (unbalanced parentheses).
 ///
-/// Respects double-quoted strings: parens inside `"..."` are not counted.
+/// Respects double-quoted strings and `;` line comments: parens inside
+/// `"..."` or after a `;` on the same line are not counted.
 /// Returns `true` if the input has unmatched open parentheses.
 pub fn needs_continuation(input: &str) -> bool {
     let mut depth: i32 = 0;
     let mut in_string = false;
+    let mut in_comment = false;
     let mut prev_backslash = false;
 
     for ch in input.chars() {
+        if in_comment {
+            in_comment = ch != '\n';
+            continue;
+        }
+
         if in_string {
             if ch == '"' && !prev_backslash {
                 in_string = false;
             }
-            prev_backslash = ch == '\\';
+            prev_backslash = ch == '\\' && !prev_backslash;
             continue;
         }
 
         match ch {
             '"' => in_string = true,
+            ';' => in_comment = true,
             '(' => depth += 1,
             ')' => depth -= 1,
             _ => {}
@@ -106,6 +114,57 @@ pub fn needs_continuation(input: &str) -> bool {
     depth > 0
 }
 
+/// Outcome of feeding one line to an [`InputBuffer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineOutcome {
+    /// The accumulated input is balanced and ready to classify.
+    Complete(String),
+    /// The expression is still open; keep reading lines.
+    Incomplete,
+    /// An empty line was entered mid-expression; pending input was dropped.
+    Cancelled,
+}
+
+/// Accumulates input lines until parentheses balance.
+///
+/// Lines are joined with newlines so that `;` comments end where the user
+/// typed them. An empty line while an expression is pending cancels it.
+#[derive(Debug, Clone, Default)]
+pub struct InputBuffer {
+    pending: String,
+}
+
+impl InputBuffer {
+    /// Create an empty input buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether an incomplete expression is waiting for more lines.
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Feed one line of input.
+    pub fn push_line(&mut self, line: &str) -> LineOutcome {
+        if self.is_pending() && line.trim().is_empty() {
+            self.pending.clear();
+            return LineOutcome::Cancelled;
+        }
+
+        if self.is_pending() && !self.pending.ends_with('\n') {
+            self.pending.push('\n');
+        }
+        self.pending.push_str(line);
+
+        if needs_continuation(&self.pending) {
+            LineOutcome::Incomplete
+        } else {
+            LineOutcome::Complete(std::mem::take(&mut self.pending))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,6 +325,65 @@ mod tests {
         assert!(!needs_continuation(":quit"));
     }
 
+    #[test]
+    fn test_needs_continuation_ignores_comments() {
+        assert!(!needs_continuation("(note c4 :q) ; (unclosed"));
+        assert!(needs_continuation("(note ; close later )\n c4"));
+        assert!(!needs_continuation("(note ; close later )\n c4 :q)"));
+    }
+
+    #[test]
+    fn test_needs_continuation_escaped_backslash() {
+        assert!(!needs_continuation(r#"(words "a\\")"#));
+    }
+
+    // ===== InputBuffer tests =====
+
+    #[test]
+    fn test_input_buffer_accumulates_until_balanced() {
+        let mut buffer = InputBuffer::new();
+        assert_eq!(buffer.push_line("(note\n"), LineOutcome::Incomplete);
+        assert!(buffer.is_pending());
+
+        let LineOutcome::Complete(source) = buffer.push_line("c4 :q)\n") else {
+            panic!("Expected complete input");
+        };
+        assert!(!buffer.is_pending());
+        assert!(crate::sexpr::parser::parse(&source).is_ok());
+        assert!(crate::lang::compile_note_str(&source).is_ok());
+    }
+
+    #[test]
+    fn test_input_buffer_single_line_complete() {
+        let mut buffer = InputBuffer::new();
+        assert_eq!(
+            buffer.push_line(":help"),
+            LineOutcome::Complete(":help".to_string())
+        );
+    }
+
+    #[test]
+    fn test_input_buffer_empty_line_cancels() {
+        let mut buffer = InputBuffer::new();
+        assert_eq!(buffer.push_line("(score"), LineOutcome::Incomplete);
+        assert_eq!(buffer.push_line("   "), LineOutcome::Cancelled);
+        assert!(!buffer.is_pending());
+        assert_eq!(
+            buffer.push_line("(rest :q)"),
+            LineOutcome::Complete("(rest :q)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_input_buffer_comment_does_not_close() {
+        let mut buffer = InputBuffer::new();
+        assert_eq!(buffer.push_line("(note c4 ; )"), LineOutcome::Incomplete);
+        assert_eq!(
+            buffer.push_line(":q)"),
+            LineOutcome::Complete("(note c4 ; )\n:q)".to_string())
+        );
+    }
+
     // ===== ChatKind tests =====
 
     #[test]
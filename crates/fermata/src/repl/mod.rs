@@ -33,7 +33,7 @@ pub mod validator;
 
 use std::path::PathBuf;
 
-use reedline::{FileBackedHistory, Reedline, Signal};
+use reedline::{FileBackedHistory, Reedline, SearchDirection, SearchQuery, Signal};
 
 use crate::lang::compile;
 use crate::sexpr::parser::parse as parse_sexpr;
@@ -46,7 +46,7 @@ pub use session::{DisplayMode, HistoryValue, RenderOptions, ReplSession};
 use commands::CommandResult;
 use config::ReplConfig;
 use display::{format_banner, format_chat_stub, format_compile_error, format_result_for_mode};
-use input::classify;
+use input::{InputBuffer, LineOutcome, classify};
 use prompt::FermataPrompt;
 use validator::FermataValidator;
 
@@ -60,22 +60,39 @@ pub struct Repl {
     session: ReplSession,
     /// Configuration
     config: ReplConfig,
+    /// Lines of an expression that is not yet balanced
+    pending: InputBuffer,
 }
 
 impl Repl {
     /// Create a new REPL instance.
     ///
+    /// Entered lines are saved to the history file in the platform data
+    /// directory (`$XDG_DATA_HOME/fermata/repl_history` when set) and
+    /// earlier sessions' lines are loaded from it.
+    ///
     /// # Errors
     ///
     /// Returns an error if the history file cannot be created.
     pub fn new(use_colors: bool) -> ReplResult<Self> {
-        let editor = Self::create_editor(use_colors)?;
+        Self::with_history(use_colors, Some(Self::history_path()?))
+    }
+
+    /// Create a new REPL that keeps its line history in `history_path`,
+    /// loading any lines already there, or only in memory when `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file cannot be created.
+    pub fn with_history(use_colors: bool, history_path: Option<PathBuf>) -> ReplResult<Self> {
+        let editor = Self::create_editor(use_colors, history_path)?;
         let config = ReplConfig::load()?;
         Ok(Self {
             editor,
             use_colors,
             session: ReplSession::new(),
             config,
+            pending: InputBuffer::new(),
         })
     }
 
@@ -89,32 +106,47 @@ impl Repl {
         &mut self.session
     }
 
-    /// Create the reedline editor with history, validation, and syntax highlighting.
-    fn create_editor(use_colors: bool) -> ReplResult<Reedline> {
-        // Set up history file
-        let history_path = Self::history_path()?;
+    /// Lines entered in this and (with a history file) earlier sessions,
+    /// oldest first.
+    pub fn history(&self) -> Vec<String> {
+        let query = SearchQuery::everything(SearchDirection::Forward, None);
+        self.editor
+            .history()
+            .search(query)
+            .map(|items| items.into_iter().map(|item| item.command_line).collect())
+            .unwrap_or_default()
+    }
 
-        // Ensure parent directory exists
-        if let Some(parent) = history_path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
+    /// Create the reedline editor with history, validation, and syntax highlighting.
+    fn create_editor(use_colors: bool, history_path: Option<PathBuf>) -> ReplResult<Reedline> {
+        let mut editor = Reedline::create()
+            .with_validator(Box::new(FermataValidator::new()))
+            .with_highlighter(Box::new(highlighter::FermataHighlighter::new(use_colors)));
 
-        let history = Box::new(
-            FileBackedHistory::with_file(1000, history_path)
-                .map_err(|e| ReplError::reedline(e.to_string()))?,
-        );
+        if let Some(history_path) = history_path {
+            // Ensure parent directory exists
+            if let Some(parent) = history_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
 
-        let editor = Reedline::create()
-            .with_validator(Box::new(FermataValidator::new()))
-            .with_highlighter(Box::new(highlighter::FermataHighlighter::new(use_colors)))
-            .with_history(history);
+            let history = Box::new(
+                FileBackedHistory::with_file(1000, history_path)
+                    .map_err(|e| ReplError::reedline(e.to_string()))?,
+            );
+            editor = editor.with_history(history);
+        }
 
         Ok(editor)
     }
 
-    /// Get the history file path.
+    /// Get the history file path, preferring `$XDG_DATA_HOME` over the
+    /// platform data directory.
     fn history_path() -> ReplResult<PathBuf> {
-        let data_dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from("."));
+        let data_dir = std::env::var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(dirs::data_dir)
+            .unwrap_or_else(|| PathBuf::from("."));
         Ok(data_dir.join("fermata").join("repl_history"))
     }
 
@@ -137,14 +169,17 @@ impl Repl {
         loop {
             match self.editor.read_line(&prompt) {
                 Ok(Signal::Success(line)) => {
-                    let should_exit = self.handle_input(&line)?;
-                    prompt.increment();
+                    let should_exit = self.handle_submission(&line)?;
+                    if !self.pending.is_pending() {
+                        prompt.increment();
+                    }
                     if should_exit {
                         break;
                     }
                 }
                 Ok(Signal::CtrlC) => {
                     // Clear current input, continue
+                    self.pending = InputBuffer::new();
                     println!("^C");
                 }
                 Ok(Signal::CtrlD) => {
@@ -161,6 +196,27 @@ impl Repl {
         Ok(())
     }
 
+    /// Feed submitted editor text through the pending-input buffer.
+    ///
+    /// Only balanced input is handed to [`handle_input`](Self::handle_input);
+    /// an empty line in the middle of an expression cancels it.
+    ///
+    /// Returns `true` if the REPL should exit.
+    fn handle_submission(&mut self, text: &str) -> ReplResult<bool> {
+        for line in text.split('\n') {
+            match self.pending.push_line(line) {
+                LineOutcome::Complete(input) => {
+                    if self.handle_input(&input)? {
+                        return Ok(true);
+                    }
+                }
+                LineOutcome::Incomplete => {}
+                LineOutcome::Cancelled => println!("(input cancelled)"),
+            }
+        }
+        Ok(false)
+    }
+
     /// Handle a complete input line.
     ///
     /// Returns `true` if the REPL should exit.
@@ -312,6 +368,35 @@ mod tests {
         assert!(path.to_string_lossy().contains("fermata"));
     }
 
+    #[test]
+    fn test_with_history_loads_lines_from_earlier_session() {
+        let path =
+            std::env::temp_dir().join(format!("fermata-repl-history-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        {
+            let mut first = Repl::with_history(false, Some(path.clone())).unwrap();
+            for line in ["(note c4 :q)", ":help"] {
+                first
+                    .editor
+                    .history_mut()
+                    .save(reedline::HistoryItem::from_command_line(line))
+                    .unwrap();
+            }
+        }
+        let second = Repl::with_history(false, Some(path.clone())).unwrap();
+
+        assert_eq!(second.history(), vec!["(note c4 :q)", ":help"]);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_with_history_none_keeps_history_in_memory() {
+        let repl = Repl::with_history(false, None).unwrap();
+
+        assert!(repl.history().is_empty());
+    }
+
     #[test]
     fn test_classify_reexport() {
         // Verify classify is accessible
@@ -7,7 +7,7 @@
 //! - Render options
 
 use crate::ir::score::ScorePartwise;
-use crate::sexpr::Sexpr;
+use crate::sexpr::{ParseCache, Sexpr};
 
 /// Display mode for REPL output.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -133,6 +133,8 @@ pub struct ReplSession {
     expressions: [Option<Sexpr>; 3],
     /// Whether we've warned about terminal image support
     warned_terminal_support: bool,
+    /// Parsed sources, so reloading an unchanged file skips the parser
+    parse_cache: ParseCache,
 }
 
 impl Default for ReplSession {
@@ -151,9 +153,15 @@ impl ReplSession {
             results: [None, None, None],
             expressions: [None, None, None],
             warned_terminal_support: false,
+            parse_cache: ParseCache::new(),
         }
     }
 
+    /// Get the session's parse cache.
+    pub fn parse_cache(&self) -> &ParseCache {
+        &self.parse_cache
+    }
+
     /// Get the current display mode.
     pub fn display_mode(&self) -> DisplayMode {
         self.display_mode
@@ -244,6 +252,14 @@ impl ReplSession {
         }
     }
 
+    /// Clear all evaluated results and input expressions.
+    ///
+    /// Display mode and render settings are kept.
+    pub fn reset(&mut self) {
+        self.results = [None, None, None];
+        self.expressions = [None, None, None];
+    }
+
     /// Check if a symbol is a history variable.
     pub fn is_history_symbol(symbol: &str) -> bool {
         matches!(symbol, "*" | "**" | "***" | "+" | "++" | "+++")
@@ -551,4 +567,20 @@ mod tests {
         assert!(session.get_history_value("+").is_none());
         assert!(session.get_history_value("foo").is_none());
     }
+
+    // === reset tests ===
+
+    #[test]
+    fn test_session_reset_clears_history_keeps_settings() {
+        let mut session = ReplSession::new();
+        session.set_display_mode(DisplayMode::MusicXml);
+        session.push_result(test_score("Test"));
+        session.push_expression(test_sexpr("Test"));
+
+        session.reset();
+
+        assert!(session.get_result("*").is_none());
+        assert!(session.get_expression("+").is_none());
+        assert_eq!(session.display_mode(), DisplayMode::MusicXml);
+    }
 }
@@ -7,7 +7,8 @@ use super::input::needs_continuation;
 /// Validator that detects incomplete S-expressions.
 ///
 /// When the input has unbalanced parentheses, it returns `Incomplete`
-/// to allow the user to continue typing on the next line.
+/// to allow the user to continue typing on the next line. An empty
+/// continuation line submits the buffer so the REPL can cancel it.
 #[derive(Debug, Default, Clone)]
 pub struct FermataValidator;
 
@@ -20,7 +21,8 @@ impl FermataValidator {
 
 impl Validator for FermataValidator {
     fn validate(&self, line: &str) -> ValidationResult {
-        if needs_continuation(line) {
+        // A trailing newline means the last continuation line was left empty
+        if needs_continuation(line) && !line.ends_with('\n') {
             ValidationResult::Incomplete
         } else {
             ValidationResult::Complete
@@ -89,6 +91,19 @@ mod tests {
         assert!(is_complete(validator.validate("(score :title \"(test)\")")));
     }
 
+    #[test]
+    fn test_validator_complete_on_empty_continuation_line() {
+        let validator = FermataValidator::new();
+        assert!(is_incomplete(validator.validate("(score\n  :title")));
+        assert!(is_complete(validator.validate("(score\n")));
+    }
+
+    #[test]
+    fn test_validator_ignores_parens_in_comments() {
+        let validator = FermataValidator::new();
+        assert!(is_incomplete(validator.validate("(score ; )")));
+    }
+
     #[test]
     fn test_validator_clone() {
         let validator = FermataValidator::new();
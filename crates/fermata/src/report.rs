@@ -0,0 +1,179 @@
+//! Colored or plain output for command-line tools.
+//!
+//! A [`Reporter`] decides how labels such as "Error" are styled and where
+//! lines end up. The CLI builds one from its `--no-color` flag with
+//! [`reporter`] and passes it to every command, so commands never check for
+//! color support themselves. [`Buffer`] captures the text instead of
+//! printing it, which makes output testable.
+
+use owo_colors::OwoColorize;
+
+/// What a piece of styled text means, which picks its color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Something failed (red)
+    Error,
+    /// Something looks wrong but did not fail (yellow)
+    Warning,
+    /// Something succeeded (green)
+    Success,
+    /// Neutral information (cyan)
+    Info,
+    /// A label or heading (bold)
+    Emphasis,
+}
+
+impl Style {
+    /// Wrap `text` in the ANSI codes for this style.
+    pub fn paint(self, text: &str) -> String {
+        match self {
+            Style::Error => text.red().to_string(),
+            Style::Warning => text.yellow().to_string(),
+            Style::Success => text.green().to_string(),
+            Style::Info => text.cyan().to_string(),
+            Style::Emphasis => text.bold().to_string(),
+        }
+    }
+}
+
+/// A destination for command output and diagnostics.
+///
+/// Implementors provide the two streams and, optionally, styling; the
+/// `error`, `warning`, and `success` methods format `label: message` lines
+/// on top of them.
+pub trait Reporter {
+    /// Style `text`. Plain reporters return it unchanged.
+    fn paint(&self, text: &str, style: Style) -> String {
+        let _ = style;
+        text.to_string()
+    }
+
+    /// Write a line of regular output (standard output).
+    fn out(&mut self, line: &str);
+
+    /// Write a line of diagnostic output (standard error).
+    fn err(&mut self, line: &str);
+
+    /// Report an error, e.g. `Compilation error: ...`.
+    fn error(&mut self, label: &str, message: &str) {
+        let line = format!("{}: {}", self.paint(label, Style::Error), message);
+        self.err(&line);
+    }
+
+    /// Report a warning, e.g. `Warning: ...`.
+    fn warning(&mut self, label: &str, message: &str) {
+        let line = format!("{}: {}", self.paint(label, Style::Warning), message);
+        self.err(&line);
+    }
+
+    /// Report success on regular output, e.g. `OK: score.fm is valid`.
+    fn success(&mut self, label: &str, message: &str) {
+        let line = format!("{}: {}", self.paint(label, Style::Success), message);
+        self.out(&line);
+    }
+}
+
+/// Prints to the terminal without colors.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Plain;
+
+impl Reporter for Plain {
+    fn out(&mut self, line: &str) {
+        println!("{}", line);
+    }
+
+    fn err(&mut self, line: &str) {
+        eprintln!("{}", line);
+    }
+}
+
+/// Prints to the terminal with ANSI colors.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Colored;
+
+impl Reporter for Colored {
+    fn paint(&self, text: &str, style: Style) -> String {
+        style.paint(text)
+    }
+
+    fn out(&mut self, line: &str) {
+        println!("{}", line);
+    }
+
+    fn err(&mut self, line: &str) {
+        eprintln!("{}", line);
+    }
+}
+
+/// Captures output in memory, without colors.
+#[derive(Debug, Clone, Default)]
+pub struct Buffer {
+    output: String,
+    errors: String,
+}
+
+impl Buffer {
+    /// Create an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything written as regular output, one line per write.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Everything written as diagnostic output, one line per write.
+    pub fn errors(&self) -> &str {
+        &self.errors
+    }
+}
+
+impl Reporter for Buffer {
+    fn out(&mut self, line: &str) {
+        self.output.push_str(line);
+        self.output.push('\n');
+    }
+
+    fn err(&mut self, line: &str) {
+        self.errors.push_str(line);
+        self.errors.push('\n');
+    }
+}
+
+/// The terminal reporter for the given color setting.
+pub fn reporter(colored: bool) -> Box<dyn Reporter> {
+    if colored {
+        Box::new(Colored)
+    } else {
+        Box::new(Plain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang::compile;
+
+    #[test]
+    fn test_buffer_captures_error_without_ansi_codes() {
+        let error = compile("(score (part :piano (measure (note c4 :q :stacato))))").unwrap_err();
+        let mut buffer = Buffer::new();
+
+        buffer.error("Compilation error", &error.to_string());
+
+        assert!(buffer.errors().starts_with("Compilation error: "));
+        assert!(buffer.errors().contains(":stacato"));
+        assert!(!buffer.errors().contains('\x1b'));
+        assert!(buffer.output().is_empty());
+    }
+
+    #[test]
+    fn test_colored_paint_adds_ansi_codes() {
+        assert_eq!(Plain.paint("Error", Style::Error), "Error");
+        assert_eq!(
+            Colored.paint("Error", Style::Error),
+            "\x1b[31mError\x1b[39m"
+        );
+    }
+}
@@ -1,7 +1,9 @@
 //! S-expression AST types.
 //!
 //! This module defines the core S-expression representation used for
-//! serializing and deserializing musical scores in a Lisp-like syntax.
+//! serializing and deserializing musical scores in a Lisp-like syntax,
+//! along with [`Visitor`] and [`Folder`] for passes that walk or rewrite a
+//! tree without reimplementing the traversal.
 
 /// An S-expression value.
 ///
@@ -597,6 +599,152 @@ impl ListBuilder {
     }
 }
 
+/// A read-only pass over an S-expression tree.
+///
+/// Every method has a default: [`visit`](Visitor::visit) dispatches on the
+/// node kind, [`visit_list`](Visitor::visit_list) visits each item in
+/// order, and the atom methods do nothing. Override only the nodes a pass
+/// cares about; an overridden `visit_list` can call [`walk_list`] to keep
+/// descending.
+///
+/// # Examples
+///
+/// ```
+/// use fermata::sexpr::{Visitor, parse};
+///
+/// #[derive(Default)]
+/// struct Keywords(Vec<String>);
+///
+/// impl Visitor for Keywords {
+///     fn visit_keyword(&mut self, name: &str) {
+///         self.0.push(name.to_string());
+///     }
+/// }
+///
+/// let mut keywords = Keywords::default();
+/// keywords.visit(&parse("(note c4 :q (lyric :text \"la\"))").unwrap());
+/// assert_eq!(keywords.0, vec!["q", "text"]);
+/// ```
+pub trait Visitor {
+    /// Visit any node. The default dispatches to the method for its kind.
+    fn visit(&mut self, sexpr: &Sexpr) {
+        walk(self, sexpr);
+    }
+
+    /// Visit a list. The default visits each item in order.
+    fn visit_list(&mut self, items: &[Sexpr]) {
+        walk_list(self, items);
+    }
+
+    /// Visit a symbol.
+    fn visit_symbol(&mut self, _name: &str) {}
+
+    /// Visit a keyword (without its leading colon).
+    fn visit_keyword(&mut self, _name: &str) {}
+
+    /// Visit a string literal.
+    fn visit_string(&mut self, _value: &str) {}
+
+    /// Visit an integer.
+    fn visit_integer(&mut self, _value: i64) {}
+
+    /// Visit a float.
+    fn visit_float(&mut self, _value: f64) {}
+
+    /// Visit a boolean.
+    fn visit_bool(&mut self, _value: bool) {}
+
+    /// Visit `nil`.
+    fn visit_nil(&mut self) {}
+}
+
+/// Call the [`Visitor`] method matching the kind of `sexpr`.
+pub fn walk<V: Visitor + ?Sized>(visitor: &mut V, sexpr: &Sexpr) {
+    match sexpr {
+        Sexpr::Symbol(name) => visitor.visit_symbol(name),
+        Sexpr::Keyword(name) => visitor.visit_keyword(name),
+        Sexpr::String(value) => visitor.visit_string(value),
+        Sexpr::Integer(value) => visitor.visit_integer(*value),
+        Sexpr::Float(value) => visitor.visit_float(*value),
+        Sexpr::Bool(value) => visitor.visit_bool(*value),
+        Sexpr::Nil => visitor.visit_nil(),
+        Sexpr::List(items) => visitor.visit_list(items),
+    }
+}
+
+/// Visit each item of a list in order.
+pub fn walk_list<V: Visitor + ?Sized>(visitor: &mut V, items: &[Sexpr]) {
+    for item in items {
+        visitor.visit(item);
+    }
+}
+
+/// A pass that rebuilds an S-expression tree, such as a macro expander.
+///
+/// Works like [`Visitor`] but takes nodes by value and returns their
+/// replacements, so a symbol may become a whole list. By default every
+/// node is rebuilt unchanged; numbers, booleans, and `nil` always are
+/// unless [`fold`](Folder::fold) itself is overridden.
+///
+/// # Examples
+///
+/// ```
+/// use fermata::sexpr::{Folder, Sexpr, parse};
+///
+/// struct Rename;
+///
+/// impl Folder for Rename {
+///     fn fold_symbol(&mut self, name: String) -> Sexpr {
+///         Sexpr::symbol(if name == "n" { "note".to_string() } else { name })
+///     }
+/// }
+///
+/// let folded = Rename.fold(parse("(measure (n c4 :q))").unwrap());
+/// assert_eq!(folded, parse("(measure (note c4 :q))").unwrap());
+/// ```
+pub trait Folder {
+    /// Fold any node. The default dispatches to the method for its kind.
+    fn fold(&mut self, sexpr: Sexpr) -> Sexpr {
+        fold_sexpr(self, sexpr)
+    }
+
+    /// Fold a list. The default folds each item in order.
+    fn fold_list(&mut self, items: Vec<Sexpr>) -> Sexpr {
+        fold_list_items(self, items)
+    }
+
+    /// Fold a symbol.
+    fn fold_symbol(&mut self, name: String) -> Sexpr {
+        Sexpr::Symbol(name)
+    }
+
+    /// Fold a keyword (without its leading colon).
+    fn fold_keyword(&mut self, name: String) -> Sexpr {
+        Sexpr::Keyword(name)
+    }
+
+    /// Fold a string literal.
+    fn fold_string(&mut self, value: String) -> Sexpr {
+        Sexpr::String(value)
+    }
+}
+
+/// Call the [`Folder`] method matching the kind of `sexpr`.
+pub fn fold_sexpr<F: Folder + ?Sized>(folder: &mut F, sexpr: Sexpr) -> Sexpr {
+    match sexpr {
+        Sexpr::Symbol(name) => folder.fold_symbol(name),
+        Sexpr::Keyword(name) => folder.fold_keyword(name),
+        Sexpr::String(value) => folder.fold_string(value),
+        Sexpr::List(items) => folder.fold_list(items),
+        atom => atom,
+    }
+}
+
+/// Fold each item of a list in order, keeping the results as a list.
+pub fn fold_list_items<F: Folder + ?Sized>(folder: &mut F, items: Vec<Sexpr>) -> Sexpr {
+    Sexpr::List(items.into_iter().map(|item| folder.fold(item)).collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1120,4 +1268,86 @@ mod tests {
         let cloned = builder.clone();
         assert_eq!(builder.build(), cloned.build());
     }
+
+    // === Visitor Tests ===
+
+    #[derive(Default)]
+    struct SymbolCounter {
+        symbols: usize,
+    }
+
+    impl Visitor for SymbolCounter {
+        fn visit_symbol(&mut self, _name: &str) {
+            self.symbols += 1;
+        }
+    }
+
+    #[test]
+    fn test_visitor_counts_symbols_in_nested_tree() {
+        let tree = crate::sexpr::parse(
+            "(score :title \"Etude\" (part :piano (measure (note c4 :q) (chord (e4 g4) :h))))",
+        )
+        .unwrap();
+        let mut counter = SymbolCounter::default();
+
+        counter.visit(&tree);
+
+        // score part measure note c4 chord e4 g4
+        assert_eq!(counter.symbols, 8);
+    }
+
+    #[test]
+    fn test_visitor_overridden_list_can_skip_subtrees() {
+        struct TopLevelOnly(SymbolCounter);
+
+        impl Visitor for TopLevelOnly {
+            fn visit_list(&mut self, items: &[Sexpr]) {
+                for item in items.iter().filter(|item| !item.is_list()) {
+                    self.visit(item);
+                }
+            }
+
+            fn visit_symbol(&mut self, name: &str) {
+                self.0.visit_symbol(name);
+            }
+        }
+
+        let tree = crate::sexpr::parse("(measure (note c4 :q) rest)").unwrap();
+        let mut visitor = TopLevelOnly(SymbolCounter::default());
+
+        visitor.visit(&tree);
+
+        assert_eq!(visitor.0.symbols, 2);
+    }
+
+    #[test]
+    fn test_folder_default_rebuilds_tree_unchanged() {
+        struct Identity;
+        impl Folder for Identity {}
+
+        let tree = crate::sexpr::parse("(note c4 :q :dynamics 0.5 #t nil \"x\" -3)").unwrap();
+
+        assert_eq!(Identity.fold(tree.clone()), tree);
+    }
+
+    #[test]
+    fn test_folder_symbol_can_expand_to_list() {
+        struct ExpandRest;
+        impl Folder for ExpandRest {
+            fn fold_symbol(&mut self, name: String) -> Sexpr {
+                if name == "r" {
+                    Sexpr::list(vec![Sexpr::symbol("rest"), Sexpr::keyword("q")])
+                } else {
+                    Sexpr::Symbol(name)
+                }
+            }
+        }
+
+        let tree = crate::sexpr::parse("(measure r (note c4 :q))").unwrap();
+
+        assert_eq!(
+            ExpandRest.fold(tree),
+            crate::sexpr::parse("(measure (rest :q) (note c4 :q))").unwrap()
+        );
+    }
 }
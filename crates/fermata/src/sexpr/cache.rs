@@ -0,0 +1,122 @@
+//! Memoized S-expression parsing.
+//!
+//! A [`ParseCache`] remembers the expression parsed from each source text,
+//! so re-reading an unchanged file (the REPL's `:load`, or a rebuild in a
+//! watch loop) skips the parser. Lookups are by a hash of the source, and
+//! the cached result is always the one [`parse`] would return.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use super::ast::Sexpr;
+use super::error::ParseResult;
+use super::parser::parse;
+
+/// A cache of parsed expressions keyed by source hash.
+///
+/// Parse errors are not cached; failing sources are re-parsed each time.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use fermata::sexpr::ParseCache;
+///
+/// let cache = ParseCache::new();
+/// let first = cache.get_or_parse("(note c4 :q)")?;
+/// let second = cache.get_or_parse("(note c4 :q)")?;
+/// assert!(Rc::ptr_eq(&first, &second));
+/// # Ok::<(), fermata::sexpr::ParseError>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct ParseCache {
+    /// Source text and its expression, by hash of the source
+    entries: RefCell<HashMap<u64, (String, Rc<Sexpr>)>>,
+}
+
+impl ParseCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the expression for `source`, parsing it only if this exact
+    /// text has not been parsed before.
+    pub fn get_or_parse(&self, source: &str) -> ParseResult<Rc<Sexpr>> {
+        let key = source_hash(source);
+        if let Some((cached, sexpr)) = self.entries.borrow().get(&key)
+            && cached == source
+        {
+            return Ok(Rc::clone(sexpr));
+        }
+
+        let sexpr = Rc::new(parse(source)?);
+        self.entries
+            .borrow_mut()
+            .insert(key, (source.to_string(), Rc::clone(&sexpr)));
+        Ok(sexpr)
+    }
+
+    /// Number of cached sources.
+    pub fn len(&self) -> usize {
+        self.entries.borrow().len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.borrow().is_empty()
+    }
+
+    /// Forget every cached expression.
+    pub fn clear(&self) {
+        self.entries.borrow_mut().clear();
+    }
+}
+
+fn source_hash(source: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_or_parse_same_source_returns_same_rc() {
+        let cache = ParseCache::new();
+        let source = "(score (part :piano (measure (note c4 :q))))";
+
+        let first = cache.get_or_parse(source).unwrap();
+        let second = cache.get_or_parse(source).unwrap();
+
+        assert!(Rc::ptr_eq(&first, &second));
+        assert_eq!(*first, parse(source).unwrap());
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_get_or_parse_changed_source_reparses() {
+        let cache = ParseCache::new();
+        let first = cache.get_or_parse("(note c4 :q)").unwrap();
+        let second = cache.get_or_parse("(note d4 :q)").unwrap();
+
+        assert!(!Rc::ptr_eq(&first, &second));
+        assert_eq!(*second, parse("(note d4 :q)").unwrap());
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_get_or_parse_errors_are_not_cached() {
+        let cache = ParseCache::new();
+        assert_eq!(
+            cache.get_or_parse("(note c4"),
+            Err(parse("(note c4").unwrap_err())
+        );
+        assert!(cache.is_empty());
+    }
+}
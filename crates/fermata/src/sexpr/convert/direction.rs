@@ -1142,6 +1142,8 @@ impl ToSexpr for Sound {
             .kwarg_opt("fine", &self.fine)
             .kwarg_opt("time-only", &self.time_only)
             .kwarg_opt("pizzicato", &self.pizzicato)
+            .kwarg_list("midi-instruments", &self.midi_instruments)
+            .kwarg_opt("offset", &self.offset)
             .build()
     }
 }
@@ -1167,6 +1169,8 @@ impl FromSexpr for Sound {
             fine: optional_kwarg(list, "fine")?,
             time_only: optional_kwarg(list, "time-only")?,
             pizzicato: optional_kwarg(list, "pizzicato")?,
+            midi_instruments: optional_kwarg(list, "midi-instruments")?.unwrap_or_default(),
+            offset: optional_kwarg(list, "offset")?,
         })
     }
 }
@@ -3235,6 +3239,21 @@ mod tests {
             fine: Some("fine".to_string()),
             time_only: Some("1".to_string()),
             pizzicato: Some(YesNo::Yes),
+            midi_instruments: vec![crate::ir::part::MidiInstrument {
+                id: "P1-I1".to_string(),
+                midi_channel: Some(2),
+                midi_name: None,
+                midi_bank: None,
+                midi_program: Some(57),
+                midi_unpitched: None,
+                volume: None,
+                pan: None,
+                elevation: None,
+            }],
+            offset: Some(Offset {
+                value: 2,
+                sound: None,
+            }),
         };
         let sexpr = sound.to_sexpr();
         let parsed = Sound::from_sexpr(&sexpr).unwrap();
@@ -3250,6 +3269,8 @@ mod tests {
         assert_eq!(sound.fine, parsed.fine);
         assert_eq!(sound.time_only, parsed.time_only);
         assert_eq!(sound.pizzicato, parsed.pizzicato);
+        assert_eq!(sound.midi_instruments, parsed.midi_instruments);
+        assert_eq!(sound.offset, parsed.offset);
     }
 
     #[test]
@@ -286,6 +286,7 @@ impl FromSexpr for TremoloType {
 impl ToSexpr for BendRelease {
     fn to_sexpr(&self) -> Sexpr {
         Sexpr::symbol(match self {
+            BendRelease::Default => "default",
             BendRelease::Early => "early",
             BendRelease::Late => "late",
         })
@@ -295,6 +296,7 @@ impl ToSexpr for BendRelease {
 impl FromSexpr for BendRelease {
     fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
         match sexpr.as_symbol() {
+            Some("default") => Ok(BendRelease::Default),
             Some("early") => Ok(BendRelease::Early),
             Some("late") => Ok(BendRelease::Late),
             _ => Err(ConvertError::type_mismatch("bend-release", sexpr)),
@@ -3113,8 +3115,11 @@ mod tests {
 
     #[test]
     fn test_bend_release_all_variants() {
-        for (variant, expected_str) in [(BendRelease::Early, "early"), (BendRelease::Late, "late")]
-        {
+        for (variant, expected_str) in [
+            (BendRelease::Default, "default"),
+            (BendRelease::Early, "early"),
+            (BendRelease::Late, "late"),
+        ] {
             let sexpr = variant.to_sexpr();
             assert_eq!(sexpr.as_symbol(), Some(expected_str));
             let parsed = BendRelease::from_sexpr(&sexpr).unwrap();
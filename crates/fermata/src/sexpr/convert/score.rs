@@ -1,20 +1,64 @@
 //! S-expression conversions for score structure types.
 //!
-//! This module implements [`ToSexpr`] for high-level score structure types:
+//! This module implements [`ToSexpr`] and [`FromSexpr`] for high-level score
+//! structure types:
 //! - [`ScorePartwise`] - The root score element
 //! - [`Part`] - A musical part
 //! - [`Measure`] - A measure within a part
 //! - [`MusicDataElement`] - Elements within a measure
+//! - [`Print`] - Explicit system and page breaks, with any layout overrides
+//! - [`Listening`] - Hints for score-following applications
 //! - Part-list types (`PartList`, `ScorePart`, etc.)
-
-use crate::ir::measure::{Measure, MusicDataElement};
+//!
+//! Reading is the inverse of the compact form written here, so the output of
+//! `fermata import` can be read back; fields the writer omits (score-wide
+//! layout defaults, name displays, credit positions) come back as their
+//! defaults.
+
+use crate::ir::common::{Editorial, Position, PrintStyle};
+use crate::ir::measure::{
+    Listening, ListeningSync, Measure, MeasureLayout, MusicDataElement, OtherListening, Print,
+};
 use crate::ir::part::{
     GroupBarline, GroupBarlineValue, GroupName, GroupSymbol, MidiDevice, MidiInstrument,
     NameDisplay, NameDisplayContent, Part, PartGroup, PartList, PartListElement, PartName,
     ScoreInstrument, ScorePart, SoloOrEnsemble, VirtualInstrument,
 };
-use crate::ir::score::{Credit, CreditContent, CreditWords, ScorePartwise, Work};
-use crate::sexpr::{ListBuilder, Sexpr, ToSexpr};
+use crate::ir::score::{
+    Credit, CreditContent, CreditWords, Divider, MarginType, PageLayout, PageMargins,
+    ScorePartwise, StaffLayout, SystemDividers, SystemLayout, SystemMargins, Work,
+};
+use crate::musicxml::MUSICXML_VERSION;
+use crate::sexpr::{ConvertError, ConvertResult, FromSexpr, ListBuilder, Sexpr, ToSexpr};
+
+use super::{expect_head, get_head, optional_kwarg, require_kwarg};
+
+/// Child lists of `list` whose head is `head`, in order.
+fn children<'a>(list: &'a [Sexpr], head: &'a str) -> impl Iterator<Item = &'a Sexpr> {
+    list.iter().skip(1).filter(move |item| {
+        item.as_list()
+            .is_some_and(|l| l.first().is_some_and(|h| h.is_symbol(head)))
+    })
+}
+
+/// Parse every child list with the given head.
+fn parse_children<T: FromSexpr>(list: &[Sexpr], head: &str) -> ConvertResult<Vec<T>> {
+    children(list, head).map(T::from_sexpr).collect()
+}
+
+/// Parse the first child list with the given head, if any.
+fn parse_child<T: FromSexpr>(list: &[Sexpr], head: &str) -> ConvertResult<Option<T>> {
+    children(list, head).next().map(T::from_sexpr).transpose()
+}
+
+/// Expect `sexpr` to be a list with the given head and return its items.
+fn expect_list<'a>(sexpr: &'a Sexpr, head: &'static str) -> ConvertResult<&'a [Sexpr]> {
+    let list = sexpr
+        .as_list()
+        .ok_or_else(|| ConvertError::type_mismatch(head, sexpr))?;
+    expect_head(list, head)?;
+    Ok(list)
+}
 
 // ============================================================================
 // ScorePartwise (root element)
@@ -59,6 +103,32 @@ impl ToSexpr for ScorePartwise {
     }
 }
 
+impl FromSexpr for ScorePartwise {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "score")?;
+
+        let work_title: Option<String> = optional_kwarg(list, "title")?;
+        let work_number: Option<String> = optional_kwarg(list, "work-number")?;
+        let work = (work_title.is_some() || work_number.is_some()).then_some(Work {
+            work_number,
+            work_title,
+            opus: None,
+        });
+
+        Ok(ScorePartwise {
+            version: Some(MUSICXML_VERSION.to_string()),
+            work,
+            movement_number: optional_kwarg(list, "movement-number")?,
+            movement_title: optional_kwarg(list, "movement-title")?,
+            identification: None,
+            defaults: None,
+            credits: parse_children(list, "credit")?,
+            part_list: parse_child(list, "part-list")?.unwrap_or(PartList { content: vec![] }),
+            parts: parse_children(list, "part")?,
+        })
+    }
+}
+
 // ============================================================================
 // Work
 // ============================================================================
@@ -100,6 +170,25 @@ impl ToSexpr for Credit {
     }
 }
 
+impl FromSexpr for Credit {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "credit")?;
+
+        let mut content = Vec::new();
+        if let Some(credit_type) = optional_kwarg(list, "type")? {
+            content.push(CreditContent::CreditType(credit_type));
+        }
+        for words in parse_children(list, "credit-words")? {
+            content.push(CreditContent::CreditWords(words));
+        }
+
+        Ok(Credit {
+            page: optional_kwarg(list, "page")?,
+            content,
+        })
+    }
+}
+
 impl ToSexpr for CreditWords {
     fn to_sexpr(&self) -> Sexpr {
         ListBuilder::new("credit-words")
@@ -108,6 +197,21 @@ impl ToSexpr for CreditWords {
     }
 }
 
+impl FromSexpr for CreditWords {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "credit-words")?;
+
+        Ok(CreditWords {
+            value: require_kwarg(list, "text")?,
+            print_style: PrintStyle::default(),
+            justify: None,
+            halign: None,
+            valign: None,
+            lang: None,
+        })
+    }
+}
+
 // ============================================================================
 // PartList
 // ============================================================================
@@ -124,6 +228,19 @@ impl ToSexpr for PartList {
     }
 }
 
+impl FromSexpr for PartList {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "part-list")?;
+
+        Ok(PartList {
+            content: list[1..]
+                .iter()
+                .map(PartListElement::from_sexpr)
+                .collect::<ConvertResult<_>>()?,
+        })
+    }
+}
+
 impl ToSexpr for PartListElement {
     fn to_sexpr(&self) -> Sexpr {
         match self {
@@ -133,6 +250,20 @@ impl ToSexpr for PartListElement {
     }
 }
 
+impl FromSexpr for PartListElement {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = sexpr
+            .as_list()
+            .ok_or_else(|| ConvertError::type_mismatch("part-list element", sexpr))?;
+
+        match get_head(list)? {
+            "score-part" => Ok(PartListElement::ScorePart(ScorePart::from_sexpr(sexpr)?)),
+            "part-group" => Ok(PartListElement::PartGroup(PartGroup::from_sexpr(sexpr)?)),
+            other => Err(ConvertError::InvalidVariant(other.to_string())),
+        }
+    }
+}
+
 impl ToSexpr for ScorePart {
     fn to_sexpr(&self) -> Sexpr {
         let mut builder = ListBuilder::new("score-part")
@@ -155,6 +286,32 @@ impl ToSexpr for ScorePart {
     }
 }
 
+impl FromSexpr for ScorePart {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "score-part")?;
+
+        let part_name = |value: String| PartName {
+            value,
+            print_style: PrintStyle::default(),
+            print_object: None,
+            justify: None,
+        };
+
+        Ok(ScorePart {
+            id: require_kwarg(list, "id")?,
+            identification: None,
+            part_name: part_name(require_kwarg(list, "name")?),
+            part_name_display: None,
+            part_abbreviation: optional_kwarg(list, "abbreviation")?.map(part_name),
+            part_abbreviation_display: None,
+            group: vec![],
+            score_instruments: parse_children(list, "score-instrument")?,
+            midi_devices: vec![],
+            midi_instruments: parse_children(list, "midi-instrument")?,
+        })
+    }
+}
+
 impl ToSexpr for PartName {
     fn to_sexpr(&self) -> Sexpr {
         ListBuilder::new("part-name")
@@ -214,6 +371,28 @@ impl ToSexpr for ScoreInstrument {
     }
 }
 
+impl FromSexpr for ScoreInstrument {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "score-instrument")?;
+
+        // `:solo` is written bare, so it is found by value rather than as a kwarg
+        let solo_or_ensemble = if list.iter().any(|item| item.is_keyword("solo")) {
+            Some(SoloOrEnsemble::Solo)
+        } else {
+            parse_child(list, "ensemble")?
+        };
+
+        Ok(ScoreInstrument {
+            id: require_kwarg(list, "id")?,
+            instrument_name: require_kwarg(list, "name")?,
+            instrument_abbreviation: optional_kwarg(list, "abbreviation")?,
+            instrument_sound: optional_kwarg(list, "sound")?,
+            solo_or_ensemble,
+            virtual_instrument: parse_child(list, "virtual-instrument")?,
+        })
+    }
+}
+
 impl ToSexpr for SoloOrEnsemble {
     fn to_sexpr(&self) -> Sexpr {
         match self {
@@ -225,6 +404,16 @@ impl ToSexpr for SoloOrEnsemble {
     }
 }
 
+impl FromSexpr for SoloOrEnsemble {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        if sexpr.is_keyword("solo") {
+            return Ok(SoloOrEnsemble::Solo);
+        }
+        let list = expect_list(sexpr, "ensemble")?;
+        Ok(SoloOrEnsemble::Ensemble(require_kwarg(list, "size")?))
+    }
+}
+
 impl ToSexpr for VirtualInstrument {
     fn to_sexpr(&self) -> Sexpr {
         ListBuilder::new("virtual-instrument")
@@ -234,6 +423,17 @@ impl ToSexpr for VirtualInstrument {
     }
 }
 
+impl FromSexpr for VirtualInstrument {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "virtual-instrument")?;
+
+        Ok(VirtualInstrument {
+            virtual_library: optional_kwarg(list, "library")?,
+            virtual_name: optional_kwarg(list, "name")?,
+        })
+    }
+}
+
 impl ToSexpr for MidiDevice {
     fn to_sexpr(&self) -> Sexpr {
         ListBuilder::new("midi-device")
@@ -260,6 +460,24 @@ impl ToSexpr for MidiInstrument {
     }
 }
 
+impl FromSexpr for MidiInstrument {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "midi-instrument")?;
+
+        Ok(MidiInstrument {
+            id: require_kwarg(list, "id")?,
+            midi_channel: optional_kwarg(list, "channel")?,
+            midi_name: optional_kwarg(list, "name")?,
+            midi_bank: optional_kwarg(list, "bank")?,
+            midi_program: optional_kwarg(list, "program")?,
+            midi_unpitched: optional_kwarg(list, "unpitched")?,
+            volume: optional_kwarg(list, "volume")?,
+            pan: optional_kwarg(list, "pan")?,
+            elevation: optional_kwarg(list, "elevation")?,
+        })
+    }
+}
+
 impl ToSexpr for PartGroup {
     fn to_sexpr(&self) -> Sexpr {
         let mut builder = ListBuilder::new("part-group")
@@ -282,6 +500,25 @@ impl ToSexpr for PartGroup {
     }
 }
 
+impl FromSexpr for PartGroup {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "part-group")?;
+
+        Ok(PartGroup {
+            r#type: require_kwarg(list, "type")?,
+            number: optional_kwarg(list, "number")?,
+            group_name: parse_child(list, "group-name")?,
+            group_name_display: None,
+            group_abbreviation: None,
+            group_abbreviation_display: None,
+            group_symbol: parse_child(list, "group-symbol")?,
+            group_barline: parse_child(list, "group-barline")?,
+            group_time: None,
+            editorial: Editorial::default(),
+        })
+    }
+}
+
 impl ToSexpr for GroupName {
     fn to_sexpr(&self) -> Sexpr {
         ListBuilder::new("group-name")
@@ -290,6 +527,18 @@ impl ToSexpr for GroupName {
     }
 }
 
+impl FromSexpr for GroupName {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "group-name")?;
+
+        Ok(GroupName {
+            value: require_kwarg(list, "value")?,
+            print_style: PrintStyle::default(),
+            justify: None,
+        })
+    }
+}
+
 impl ToSexpr for GroupSymbol {
     fn to_sexpr(&self) -> Sexpr {
         ListBuilder::new("group-symbol")
@@ -298,6 +547,18 @@ impl ToSexpr for GroupSymbol {
     }
 }
 
+impl FromSexpr for GroupSymbol {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "group-symbol")?;
+
+        Ok(GroupSymbol {
+            value: require_kwarg(list, "value")?,
+            position: Position::default(),
+            color: None,
+        })
+    }
+}
+
 impl ToSexpr for GroupBarline {
     fn to_sexpr(&self) -> Sexpr {
         ListBuilder::new("group-barline")
@@ -306,6 +567,17 @@ impl ToSexpr for GroupBarline {
     }
 }
 
+impl FromSexpr for GroupBarline {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "group-barline")?;
+
+        Ok(GroupBarline {
+            value: require_kwarg(list, "value")?,
+            color: None,
+        })
+    }
+}
+
 impl ToSexpr for GroupBarlineValue {
     fn to_sexpr(&self) -> Sexpr {
         Sexpr::keyword(match self {
@@ -316,6 +588,17 @@ impl ToSexpr for GroupBarlineValue {
     }
 }
 
+impl FromSexpr for GroupBarlineValue {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        match sexpr.as_keyword() {
+            Some("yes") => Ok(GroupBarlineValue::Yes),
+            Some("no") => Ok(GroupBarlineValue::No),
+            Some("mensurstrich") => Ok(GroupBarlineValue::Mensurstrich),
+            _ => Err(ConvertError::type_mismatch("group-barline value", sexpr)),
+        }
+    }
+}
+
 // ============================================================================
 // Part
 // ============================================================================
@@ -332,6 +615,17 @@ impl ToSexpr for Part {
     }
 }
 
+impl FromSexpr for Part {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "part")?;
+
+        Ok(Part {
+            id: require_kwarg(list, "id")?,
+            measures: parse_children(list, "measure")?,
+        })
+    }
+}
+
 // ============================================================================
 // Measure
 // ============================================================================
@@ -351,6 +645,27 @@ impl ToSexpr for Measure {
     }
 }
 
+impl FromSexpr for Measure {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "measure")?;
+
+        // Content is every list child; kwarg values are atoms
+        let content = list[1..]
+            .iter()
+            .filter(|item| item.as_list().is_some())
+            .map(MusicDataElement::from_sexpr)
+            .collect::<ConvertResult<_>>()?;
+
+        Ok(Measure {
+            number: require_kwarg(list, "number")?,
+            implicit: optional_kwarg(list, "implicit")?,
+            non_controlling: None,
+            width: optional_kwarg(list, "width")?,
+            content,
+        })
+    }
+}
+
 // ============================================================================
 // MusicDataElement
 // ============================================================================
@@ -364,7 +679,344 @@ impl ToSexpr for MusicDataElement {
             MusicDataElement::Direction(direction) => direction.to_sexpr(),
             MusicDataElement::Attributes(attributes) => attributes.to_sexpr(),
             MusicDataElement::Barline(barline) => barline.to_sexpr(),
+            MusicDataElement::Print(print) => print.to_sexpr(),
+            MusicDataElement::Listening(listening) => listening.to_sexpr(),
+        }
+    }
+}
+
+impl FromSexpr for MusicDataElement {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = sexpr
+            .as_list()
+            .ok_or_else(|| ConvertError::type_mismatch("measure element", sexpr))?;
+
+        Ok(match get_head(list)? {
+            "note" => MusicDataElement::Note(Box::new(FromSexpr::from_sexpr(sexpr)?)),
+            "backup" => MusicDataElement::Backup(FromSexpr::from_sexpr(sexpr)?),
+            "forward" => MusicDataElement::Forward(FromSexpr::from_sexpr(sexpr)?),
+            "direction" => MusicDataElement::Direction(Box::new(FromSexpr::from_sexpr(sexpr)?)),
+            "attributes" => MusicDataElement::Attributes(Box::new(FromSexpr::from_sexpr(sexpr)?)),
+            "barline" => MusicDataElement::Barline(Box::new(FromSexpr::from_sexpr(sexpr)?)),
+            "print" => MusicDataElement::Print(Box::new(FromSexpr::from_sexpr(sexpr)?)),
+            "listening" => MusicDataElement::Listening(Box::new(FromSexpr::from_sexpr(sexpr)?)),
+            other => return Err(ConvertError::InvalidVariant(other.to_string())),
+        })
+    }
+}
+
+impl ToSexpr for Print {
+    fn to_sexpr(&self) -> Sexpr {
+        let mut builder = ListBuilder::new("print")
+            .kwarg_opt("new-system", &self.new_system)
+            .kwarg_opt("new-page", &self.new_page)
+            .kwarg_opt("page-number", &self.page_number);
+        if let Some(ref page_layout) = self.page_layout {
+            builder = builder.arg(page_layout.to_sexpr());
+        }
+        if let Some(ref system_layout) = self.system_layout {
+            builder = builder.arg(system_layout.to_sexpr());
+        }
+        for staff_layout in &self.staff_layouts {
+            builder = builder.arg(staff_layout.to_sexpr());
+        }
+        if let Some(ref measure_layout) = self.measure_layout {
+            builder = builder.arg(measure_layout.to_sexpr());
+        }
+        builder.build()
+    }
+}
+
+impl FromSexpr for Print {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "print")?;
+
+        Ok(Print {
+            new_system: optional_kwarg(list, "new-system")?,
+            new_page: optional_kwarg(list, "new-page")?,
+            page_number: optional_kwarg(list, "page-number")?,
+            page_layout: parse_child(list, "page-layout")?,
+            system_layout: parse_child(list, "system-layout")?,
+            staff_layouts: parse_children(list, "staff-layout")?,
+            measure_layout: parse_child(list, "measure-layout")?,
+        })
+    }
+}
+
+impl ToSexpr for PageLayout {
+    fn to_sexpr(&self) -> Sexpr {
+        let mut builder = ListBuilder::new("page-layout")
+            .kwarg_opt("page-height", &self.page_height)
+            .kwarg_opt("page-width", &self.page_width);
+        for margins in &self.page_margins {
+            builder = builder.arg(margins.to_sexpr());
+        }
+        builder.build()
+    }
+}
+
+impl FromSexpr for PageLayout {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "page-layout")?;
+
+        Ok(PageLayout {
+            page_height: optional_kwarg(list, "page-height")?,
+            page_width: optional_kwarg(list, "page-width")?,
+            page_margins: parse_children(list, "page-margins")?,
+        })
+    }
+}
+
+impl ToSexpr for PageMargins {
+    fn to_sexpr(&self) -> Sexpr {
+        ListBuilder::new("page-margins")
+            .kwarg_opt("type", &self.r#type)
+            .kwarg("left", &self.left)
+            .kwarg("right", &self.right)
+            .kwarg("top", &self.top)
+            .kwarg("bottom", &self.bottom)
+            .build()
+    }
+}
+
+impl FromSexpr for PageMargins {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "page-margins")?;
+
+        Ok(PageMargins {
+            r#type: optional_kwarg(list, "type")?,
+            left: require_kwarg(list, "left")?,
+            right: require_kwarg(list, "right")?,
+            top: require_kwarg(list, "top")?,
+            bottom: require_kwarg(list, "bottom")?,
+        })
+    }
+}
+
+impl ToSexpr for MarginType {
+    fn to_sexpr(&self) -> Sexpr {
+        Sexpr::keyword(match self {
+            MarginType::Odd => "odd",
+            MarginType::Even => "even",
+            MarginType::Both => "both",
+        })
+    }
+}
+
+impl FromSexpr for MarginType {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        match sexpr.as_keyword() {
+            Some("odd") => Ok(MarginType::Odd),
+            Some("even") => Ok(MarginType::Even),
+            Some("both") => Ok(MarginType::Both),
+            _ => Err(ConvertError::type_mismatch("margin type", sexpr)),
+        }
+    }
+}
+
+impl ToSexpr for SystemLayout {
+    fn to_sexpr(&self) -> Sexpr {
+        let mut builder = ListBuilder::new("system-layout")
+            .kwarg_opt("system-distance", &self.system_distance)
+            .kwarg_opt("top-system-distance", &self.top_system_distance);
+        if let Some(ref margins) = self.system_margins {
+            builder = builder.arg(margins.to_sexpr());
+        }
+        if let Some(ref dividers) = self.system_dividers {
+            builder = builder.arg(dividers.to_sexpr());
+        }
+        builder.build()
+    }
+}
+
+impl FromSexpr for SystemLayout {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "system-layout")?;
+
+        Ok(SystemLayout {
+            system_margins: parse_child(list, "system-margins")?,
+            system_distance: optional_kwarg(list, "system-distance")?,
+            top_system_distance: optional_kwarg(list, "top-system-distance")?,
+            system_dividers: parse_child(list, "system-dividers")?,
+        })
+    }
+}
+
+impl ToSexpr for SystemMargins {
+    fn to_sexpr(&self) -> Sexpr {
+        ListBuilder::new("system-margins")
+            .kwarg("left", &self.left)
+            .kwarg("right", &self.right)
+            .build()
+    }
+}
+
+impl FromSexpr for SystemMargins {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "system-margins")?;
+
+        Ok(SystemMargins {
+            left: require_kwarg(list, "left")?,
+            right: require_kwarg(list, "right")?,
+        })
+    }
+}
+
+// Dividers keep only whether they print, which is all the emitter writes
+impl ToSexpr for SystemDividers {
+    fn to_sexpr(&self) -> Sexpr {
+        let divider = |head: &str, divider: &Divider| {
+            ListBuilder::new(head)
+                .kwarg_opt("print-object", &divider.print_object)
+                .build()
+        };
+        let mut builder = ListBuilder::new("system-dividers");
+        if let Some(ref left) = self.left_divider {
+            builder = builder.arg(divider("left-divider", left));
+        }
+        if let Some(ref right) = self.right_divider {
+            builder = builder.arg(divider("right-divider", right));
+        }
+        builder.build()
+    }
+}
+
+impl FromSexpr for SystemDividers {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "system-dividers")?;
+        let divider = |head: &str| -> ConvertResult<Option<Divider>> {
+            children(list, head)
+                .next()
+                .map(|item| {
+                    Ok(Divider {
+                        print_object: optional_kwarg(
+                            item.as_list().unwrap_or_default(),
+                            "print-object",
+                        )?,
+                        print_style: PrintStyle::default(),
+                    })
+                })
+                .transpose()
+        };
+
+        Ok(SystemDividers {
+            left_divider: divider("left-divider")?,
+            right_divider: divider("right-divider")?,
+        })
+    }
+}
+
+impl ToSexpr for StaffLayout {
+    fn to_sexpr(&self) -> Sexpr {
+        ListBuilder::new("staff-layout")
+            .kwarg_opt("number", &self.number)
+            .kwarg_opt("staff-distance", &self.staff_distance)
+            .build()
+    }
+}
+
+impl FromSexpr for StaffLayout {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "staff-layout")?;
+
+        Ok(StaffLayout {
+            number: optional_kwarg(list, "number")?,
+            staff_distance: optional_kwarg(list, "staff-distance")?,
+        })
+    }
+}
+
+impl ToSexpr for MeasureLayout {
+    fn to_sexpr(&self) -> Sexpr {
+        ListBuilder::new("measure-layout")
+            .kwarg_opt("measure-distance", &self.measure_distance)
+            .build()
+    }
+}
+
+impl FromSexpr for MeasureLayout {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "measure-layout")?;
+
+        Ok(MeasureLayout {
+            measure_distance: optional_kwarg(list, "measure-distance")?,
+        })
+    }
+}
+
+impl ToSexpr for Listening {
+    fn to_sexpr(&self) -> Sexpr {
+        let mut builder = ListBuilder::new("listening");
+        for sync in &self.syncs {
+            builder = builder.arg(sync.to_sexpr());
+        }
+        for other in &self.other_listening {
+            builder = builder.arg(other.to_sexpr());
         }
+        if let Some(ref offset) = self.offset {
+            builder = builder.arg(offset.to_sexpr());
+        }
+        builder.build()
+    }
+}
+
+impl FromSexpr for Listening {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "listening")?;
+
+        Ok(Listening {
+            syncs: parse_children(list, "sync")?,
+            other_listening: parse_children(list, "other-listening")?,
+            offset: parse_child(list, "offset")?,
+        })
+    }
+}
+
+impl ToSexpr for ListeningSync {
+    fn to_sexpr(&self) -> Sexpr {
+        ListBuilder::new("sync")
+            .kwarg("type", &self.r#type)
+            .kwarg_opt("latency", &self.latency)
+            .kwarg_opt("player", &self.player)
+            .kwarg_opt("time-only", &self.time_only)
+            .build()
+    }
+}
+
+impl FromSexpr for ListeningSync {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "sync")?;
+
+        Ok(ListeningSync {
+            r#type: require_kwarg(list, "type")?,
+            latency: optional_kwarg(list, "latency")?,
+            player: optional_kwarg(list, "player")?,
+            time_only: optional_kwarg(list, "time-only")?,
+        })
+    }
+}
+
+impl ToSexpr for OtherListening {
+    fn to_sexpr(&self) -> Sexpr {
+        ListBuilder::new("other-listening")
+            .kwarg("type", &self.r#type)
+            .kwarg("value", &self.value)
+            .kwarg_opt("player", &self.player)
+            .kwarg_opt("time-only", &self.time_only)
+            .build()
+    }
+}
+
+impl FromSexpr for OtherListening {
+    fn from_sexpr(sexpr: &Sexpr) -> ConvertResult<Self> {
+        let list = expect_list(sexpr, "other-listening")?;
+
+        Ok(OtherListening {
+            value: require_kwarg(list, "value")?,
+            r#type: require_kwarg(list, "type")?,
+            player: optional_kwarg(list, "player")?,
+            time_only: optional_kwarg(list, "time-only")?,
+        })
     }
 }
 
@@ -700,6 +1352,7 @@ mod tests {
         assert!(output.contains("Strings"));
         assert!(output.contains("group-symbol"));
         assert!(output.contains("group-barline"));
+        assert_eq!(PartGroup::from_sexpr(&sexpr).unwrap(), pg);
     }
 
     #[test]
@@ -1144,6 +1797,7 @@ mod tests {
         assert!(output.contains("keyboard.piano.grand"));
         assert!(output.contains("solo"));
         assert!(output.contains("virtual-instrument"));
+        assert_eq!(ScoreInstrument::from_sexpr(&sexpr).unwrap(), si);
     }
 
     // ============================================================================
@@ -1905,4 +2559,23 @@ mod tests {
         assert!(output.contains("part-group"));
         assert!(output.contains("Woodwinds"));
     }
+
+    #[test]
+    fn test_listening_round_trip() {
+        let elem = MusicDataElement::Listening(Box::new(Listening {
+            syncs: vec![ListeningSync {
+                r#type: "mostly-linear".to_string(),
+                latency: Some(20),
+                player: None,
+                time_only: None,
+            }],
+            other_listening: vec![],
+            offset: None,
+        }));
+
+        let sexpr = elem.to_sexpr();
+        let output = print_sexpr(&sexpr);
+        assert!(output.contains("(sync :type \"mostly-linear\" :latency 20)"));
+        assert_eq!(MusicDataElement::from_sexpr(&sexpr).unwrap(), elem);
+    }
 }
@@ -0,0 +1,463 @@
+//! Source formatter for Fermata S-expression text.
+//!
+//! Unlike [`parse`](super::parse), which discards comments, the formatter
+//! reads source into a lightweight tree that keeps `;` line comments as
+//! trivia. A comment on its own line is attached to the form that follows
+//! it and printed above that form; a comment after a form on the same line
+//! is attached to that form and stays at the end of its line. Atoms are
+//! re-emitted verbatim, so formatting never changes the meaning of the
+//! source.
+//!
+//! # Examples
+//!
+//! ```
+//! use fermata::sexpr::format::format_source;
+//!
+//! let formatted = format_source("; melody\n(note   c4\n :q) ; tonic").unwrap();
+//! assert_eq!(formatted, "; melody\n(note c4 :q) ; tonic\n");
+//! ```
+
+use super::PrintOptions;
+use super::error::{ParseError, ParseResult};
+
+/// A node in the formatter's concrete syntax tree.
+#[derive(Debug, Clone, PartialEq)]
+struct Node {
+    /// Comments on the lines preceding this node (without the newline).
+    leading: Vec<String>,
+    /// The node itself.
+    kind: NodeKind,
+    /// A comment later on the line where this node ends.
+    trailing: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum NodeKind {
+    /// An atom, kept exactly as written (symbol, keyword, number, string).
+    Atom(String),
+    /// A list, plus any comments between its last child and the `)`.
+    List(Vec<Node>, Vec<String>),
+    /// A form behind a `` ` `` or `,` prefix, written without a space.
+    Prefixed(char, Box<Node>),
+}
+
+impl Node {
+    fn is_list(&self) -> bool {
+        match &self.kind {
+            NodeKind::Atom(_) => false,
+            NodeKind::List(..) => true,
+            NodeKind::Prefixed(_, inner) => inner.is_list(),
+        }
+    }
+
+    fn is_keyword(&self) -> bool {
+        matches!(&self.kind, NodeKind::Atom(a) if a.starts_with(':'))
+    }
+
+    /// Whether this node or any descendant carries a comment.
+    fn has_comments(&self) -> bool {
+        !self.leading.is_empty()
+            || self.trailing.is_some()
+            || match &self.kind {
+                NodeKind::Atom(_) => false,
+                NodeKind::List(children, trailing) => {
+                    !trailing.is_empty() || children.iter().any(Node::has_comments)
+                }
+                NodeKind::Prefixed(_, inner) => inner.has_comments(),
+            }
+    }
+
+    /// Whether this node is printed with a comment closing its line, so
+    /// nothing else may follow it there.
+    fn ends_line(&self) -> bool {
+        self.trailing.is_some()
+            || matches!(&self.kind, NodeKind::Prefixed(_, inner) if inner.ends_line())
+    }
+}
+
+/// Format Fermata source text using the default [`PrintOptions`].
+///
+/// # Errors
+///
+/// Returns [`ParseError`] if the source contains unbalanced parentheses or
+/// an unclosed string literal.
+pub fn format_source(source: &str) -> ParseResult<String> {
+    format_source_with(source, &PrintOptions::default())
+}
+
+/// Format Fermata source text with custom options.
+///
+/// Lists that fit within `max_width` (and contain no comments) are printed
+/// on one line; others are broken with one child per line, indented by
+/// `indent`. Comments written on their own lines are printed above the
+/// form they precede, and comments after a form stay at the end of its
+/// line. Top-level forms are separated by a blank line.
+///
+/// # Errors
+///
+/// Returns [`ParseError`] if the source contains unbalanced parentheses or
+/// an unclosed string literal.
+pub fn format_source_with(source: &str, options: &PrintOptions) -> ParseResult<String> {
+    let (forms, trailing) = Reader::new(source).read_top_level()?;
+
+    let mut out = String::new();
+    for (i, form) in forms.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        write_node(&mut out, form, 0, options);
+        out.push('\n');
+    }
+    if !trailing.is_empty() {
+        if !forms.is_empty() {
+            out.push('\n');
+        }
+        for comment in &trailing {
+            out.push_str(comment);
+            out.push('\n');
+        }
+    }
+    Ok(out)
+}
+
+// === Reader ===
+
+/// A comment-preserving reader over source text.
+struct Reader<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(src: &'a str) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    /// Read a comment that follows on the current line, skipping the
+    /// spaces before it.
+    fn read_trailing(&mut self) -> Option<String> {
+        let rest = &self.src[self.pos..];
+        let offset = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        let rest = &rest[offset..];
+        if !rest.starts_with(';') {
+            return None;
+        }
+        let end = rest.find('\n').unwrap_or(rest.len());
+        self.pos += offset + end;
+        Some(rest[..end].trim_end().to_string())
+    }
+
+    /// Skip whitespace and collect any comments encountered.
+    fn read_trivia(&mut self) -> Vec<String> {
+        let mut comments = Vec::new();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.pos += c.len_utf8();
+            } else if c == ';' {
+                let rest = &self.src[self.pos..];
+                let end = rest.find('\n').unwrap_or(rest.len());
+                comments.push(rest[..end].trim_end().to_string());
+                self.pos += end;
+            } else {
+                break;
+            }
+        }
+        comments
+    }
+
+    fn read_top_level(&mut self) -> ParseResult<(Vec<Node>, Vec<String>)> {
+        let mut forms = Vec::new();
+        loop {
+            let leading = self.read_trivia();
+            match self.peek() {
+                None => return Ok((forms, leading)),
+                Some(')') => return Err(ParseError::UnexpectedChar(')')),
+                Some(_) => forms.push(self.read_node(leading)?),
+            }
+        }
+    }
+
+    fn read_node(&mut self, leading: Vec<String>) -> ParseResult<Node> {
+        let kind = match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let mut children = Vec::new();
+                loop {
+                    let trivia = self.read_trivia();
+                    match self.peek() {
+                        None => return Err(ParseError::UnclosedList),
+                        Some(')') => {
+                            self.pos += 1;
+                            break NodeKind::List(children, trivia);
+                        }
+                        Some(_) => children.push(self.read_node(trivia)?),
+                    }
+                }
+            }
+            Some('"') => NodeKind::Atom(self.read_string()?),
+            Some(c @ ('`' | ',')) => {
+                self.pos += 1;
+                let trivia = self.read_trivia();
+                match self.peek() {
+                    None => return Err(ParseError::UnexpectedEof),
+                    Some(')') => return Err(ParseError::UnexpectedChar(c)),
+                    Some(_) => NodeKind::Prefixed(c, Box::new(self.read_node(trivia)?)),
+                }
+            }
+            Some(_) => NodeKind::Atom(self.read_atom()),
+            None => return Err(ParseError::UnexpectedEof),
+        };
+        // A prefixed form's comment was already taken by the form inside it
+        let trailing = match kind {
+            NodeKind::Prefixed(..) => None,
+            _ => self.read_trailing(),
+        };
+        Ok(Node {
+            leading,
+            kind,
+            trailing,
+        })
+    }
+
+    fn read_string(&mut self) -> ParseResult<String> {
+        let start = self.pos;
+        let mut chars = self.src[self.pos + 1..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '"' => {
+                    self.pos += i + 2;
+                    return Ok(self.src[start..self.pos].to_string());
+                }
+                _ => {}
+            }
+        }
+        Err(ParseError::UnclosedString)
+    }
+
+    fn read_atom(&mut self) -> String {
+        let rest = &self.src[self.pos..];
+        let end = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | ';' | '"' | '`' | ','))
+            .unwrap_or(rest.len());
+        self.pos += end;
+        rest[..end].to_string()
+    }
+}
+
+// === Writer ===
+
+/// Render a node on a single line.
+fn flat(node: &Node) -> String {
+    match &node.kind {
+        NodeKind::Atom(a) => a.clone(),
+        NodeKind::List(children, _) => {
+            let inner: Vec<String> = children.iter().map(flat).collect();
+            format!("({})", inner.join(" "))
+        }
+        NodeKind::Prefixed(prefix, inner) => format!("{}{}", prefix, flat(inner)),
+    }
+}
+
+fn push_indent(out: &mut String, level: usize, options: &PrintOptions) {
+    out.push_str(&options.indent.repeat(level));
+}
+
+/// Write a node (with its comments) starting at the current line, which
+/// is assumed to already be indented to `level`.
+fn write_node(out: &mut String, node: &Node, level: usize, options: &PrintOptions) {
+    for comment in &node.leading {
+        out.push_str(comment);
+        out.push('\n');
+        push_indent(out, level, options);
+    }
+    write_kind(out, node, level, options);
+    if let Some(comment) = &node.trailing {
+        out.push(' ');
+        out.push_str(comment);
+    }
+}
+
+/// Write a node without its own comments.
+fn write_kind(out: &mut String, node: &Node, level: usize, options: &PrintOptions) {
+    let (children, trailing) = match &node.kind {
+        NodeKind::List(children, trailing) => (children, trailing),
+        NodeKind::Prefixed(prefix, inner) => {
+            out.push(*prefix);
+            write_node(out, inner, level, options);
+            return;
+        }
+        NodeKind::Atom(_) => {
+            out.push_str(&flat(node));
+            return;
+        }
+    };
+
+    let column = options.indent.len() * level;
+    let flat_form = flat(node);
+    let body_has_comments = !trailing.is_empty() || children.iter().any(Node::has_comments);
+    if !body_has_comments && column + flat_form.len() <= options.max_width {
+        out.push_str(&flat_form);
+        return;
+    }
+
+    // Head line: the head plus any leading comment-free atoms, up to the
+    // first one followed by a comment
+    out.push('(');
+    let mut i = 0;
+    while i < children.len() && !children[i].is_list() && children[i].leading.is_empty() {
+        if i > 0 {
+            out.push(' ');
+        }
+        write_node(out, &children[i], level + 1, options);
+        i += 1;
+        if children[i - 1].ends_line() {
+            break;
+        }
+    }
+
+    // Remaining children, one per line; a keyword keeps its atom value
+    while i < children.len() {
+        out.push('\n');
+        push_indent(out, level + 1, options);
+        write_node(out, &children[i], level + 1, options);
+        if children[i].is_keyword()
+            && !children[i].ends_line()
+            && children
+                .get(i + 1)
+                .is_some_and(|next| !next.is_list() && next.leading.is_empty())
+        {
+            out.push(' ');
+            write_node(out, &children[i + 1], level + 1, options);
+            i += 1;
+        }
+        i += 1;
+    }
+
+    if trailing.is_empty() {
+        // A comment ending the last child's line pushes the `)` below it
+        if children.last().is_some_and(Node::ends_line) {
+            out.push('\n');
+            push_indent(out, level, options);
+        }
+        out.push(')');
+    } else {
+        for comment in trailing {
+            out.push('\n');
+            push_indent(out, level + 1, options);
+            out.push_str(comment);
+        }
+        out.push('\n');
+        push_indent(out, level, options);
+        out.push(')');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_source_flat_form() {
+        let out = format_source("(note   c4\n   :q)").unwrap();
+        assert_eq!(out, "(note c4 :q)\n");
+    }
+
+    #[test]
+    fn test_format_source_breaks_long_form() {
+        let source = "(score :title \"A Long Title\" (part :piano (measure (note c4 :q) (note d4 :q) (note e4 :q) (note f4 :q) (note g4 :q))))";
+        let out = format_source(source).unwrap();
+        assert_eq!(
+            out,
+            "(score :title \"A Long Title\"\n  (part :piano\n    (measure (note c4 :q) (note d4 :q) (note e4 :q) (note f4 :q) (note g4 :q))))\n"
+        );
+    }
+
+    #[test]
+    fn test_format_source_preserves_comments_before_forms() {
+        let source = "; Opening\n(score :title \"T\"\n  ; the melody\n  (part :piano\n (measure (note c4 :q))))\n";
+        let out = format_source(source).unwrap();
+        assert_eq!(
+            out,
+            "; Opening\n(score :title \"T\"\n  ; the melody\n  (part :piano (measure (note c4 :q))))\n"
+        );
+    }
+
+    #[test]
+    fn test_format_source_comment_before_close_paren() {
+        let source = "(measure (note c4 :q)\n ; last\n)";
+        let out = format_source(source).unwrap();
+        assert_eq!(out, "(measure\n  (note c4 :q)\n  ; last\n)\n");
+    }
+
+    #[test]
+    fn test_format_source_keeps_section_comment_above_form() {
+        let source = "(score (part :piano\n;; section A\n(measure (note c4 :q))))";
+        let out = format_source(source).unwrap();
+        assert_eq!(
+            out,
+            "(score\n  (part :piano\n    ;; section A\n    (measure (note c4 :q))))\n"
+        );
+    }
+
+    #[test]
+    fn test_format_source_trailing_comments_stay_at_end_of_line() {
+        let source = "(measure :number 1 ; first\n  (note c4 :q)   ; tonic\n  (note g4 :q)) ; dominant\n(part ; empty\n)";
+        let out = format_source(source).unwrap();
+        assert_eq!(
+            out,
+            "(measure :number 1 ; first\n  (note c4 :q) ; tonic\n  (note g4 :q)) ; dominant\n\n(part ; empty\n)\n"
+        );
+        assert_eq!(format_source(&out).unwrap(), out);
+    }
+
+    #[test]
+    fn test_format_source_trailing_comment_on_last_child_moves_paren() {
+        let out = format_source("(part (measure (note c4 :q)) ; m1\n)").unwrap();
+        assert_eq!(out, "(part\n  (measure (note c4 :q)) ; m1\n)\n");
+
+        let out = format_source("(note c4 :q ; quarter\n)").unwrap();
+        assert_eq!(out, "(note c4 :q ; quarter\n)\n");
+    }
+
+    #[test]
+    fn test_format_source_is_idempotent() {
+        let source = ";; header\n(score\n ; a\n (part :piano (measure (note c4 :q) ; b\n)))\n; end";
+        let once = format_source(source).unwrap();
+        let twice = format_source(&once).unwrap();
+        assert_eq!(once, twice);
+        assert!(once.contains(";; header") && once.contains("; a") && once.contains("; end"));
+    }
+
+    #[test]
+    fn test_format_source_preserves_strings_verbatim() {
+        let out = format_source("(words \"a ; (b) \\\" c\")").unwrap();
+        assert_eq!(out, "(words \"a ; (b) \\\" c\")\n");
+    }
+
+    #[test]
+    fn test_format_source_multiple_forms() {
+        let out = format_source("(a)\n\n\n(b)").unwrap();
+        assert_eq!(out, "(a)\n\n(b)\n");
+    }
+
+    #[test]
+    fn test_format_source_keeps_quasiquote_prefixes_attached() {
+        let formatted = format_source("(define (arp root) ` ( ,root ,(up root :M3)))").unwrap();
+
+        assert_eq!(formatted, "(define (arp root) `(,root ,(up root :M3)))\n");
+    }
+
+    #[test]
+    fn test_format_source_unbalanced_is_error() {
+        assert_eq!(format_source("(a (b)"), Err(ParseError::UnclosedList));
+        assert_eq!(format_source("(a))"), Err(ParseError::UnexpectedChar(')')));
+        assert_eq!(format_source("(a \"b)"), Err(ParseError::UnclosedString));
+    }
+}
@@ -15,11 +15,14 @@
 //!
 //! # Modules
 //!
-//! - `ast` - Untyped S-expression AST and [`ListBuilder`]
+//! - `ast` - Untyped S-expression AST, [`ListBuilder`], and the [`Visitor`]
+//!   and [`Folder`] traversal traits
+//! - [`cache`] - [`ParseCache`] for memoized parsing
 //! - [`error`] - Error types for parsing and conversion
 //! - [`parser`] - nom-based parser (text -> AST)
 //! - [`traits`] - [`ToSexpr`] and [`FromSexpr`] conversion traits
 //! - [`convert`] - IR type conversions
+//! - [`format`] - Comment-preserving source formatter
 //!
 //! # Parsing S-expressions
 //!
@@ -90,16 +93,19 @@
 //! ```
 
 mod ast;
+pub mod cache;
 pub mod convert;
 pub mod error;
+pub mod format;
 pub mod parser;
 mod printer;
 pub mod traits;
 
 // Re-export core types
-pub use ast::{ListBuilder, Sexpr};
+pub use ast::{Folder, ListBuilder, Sexpr, Visitor, fold_list_items, fold_sexpr, walk, walk_list};
+pub use cache::ParseCache;
 pub use error::{ConvertError, ConvertResult, ParseError, ParseResult};
-pub use parser::{parse, parse_all};
+pub use parser::{SpanMap, parse, parse_all, parse_recover, parse_spanned};
 pub use traits::{FromSexpr, ToSexpr};
 
 // Note: print_sexpr and related functions are defined later in this file
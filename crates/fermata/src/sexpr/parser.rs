@@ -11,6 +11,8 @@
 //! - **Numbers**: Integers and floating-point: `42`, `-3.14`
 //! - **Booleans**: `#t`, `#f`, `true`, `false`, `nil`
 //! - **Lists**: Parenthesized sequences: `(note :pitch C4)`
+//! - **Quasiquote**: `` `x `` reads as `(quasiquote x)` and `,x` as
+//!   `(unquote x)`
 //! - **Comments**: Semicolon to end of line: `; this is a comment`
 //!
 //! # Examples
@@ -32,9 +34,56 @@ use nom::{
     sequence::{delimited, pair, preceded},
 };
 
+use std::ops::Range;
+
 use super::ast::Sexpr;
 use super::error::{ParseError, ParseResult};
 
+/// Byte-offset spans for a parsed S-expression, mirroring its shape.
+///
+/// The root `SpanMap` covers the whole expression; for a list, `children`
+/// holds one `SpanMap` per list element in the same order. Atoms have no
+/// children. Use [`SpanMap::get`] to look up a node by its child-index path
+/// and [`SpanMap::path_at`] to find the innermost node under a byte offset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanMap {
+    /// Byte range of this node in the source (end exclusive)
+    pub span: Range<usize>,
+    /// Spans of the list elements (empty for atoms)
+    pub children: Vec<SpanMap>,
+}
+
+impl SpanMap {
+    /// Look up the node reached by following `path` (child indices) from here.
+    ///
+    /// An empty path returns `self`.
+    pub fn get(&self, path: &[usize]) -> Option<&SpanMap> {
+        path.iter()
+            .try_fold(self, |node, &index| node.children.get(index))
+    }
+
+    /// Return the child-index path of the innermost node containing `offset`.
+    ///
+    /// Returns `None` if `offset` lies outside this node.
+    pub fn path_at(&self, offset: usize) -> Option<Vec<usize>> {
+        if !self.span.contains(&offset) {
+            return None;
+        }
+        let mut path = Vec::new();
+        let mut node = self;
+        while let Some((index, child)) = node
+            .children
+            .iter()
+            .enumerate()
+            .find(|(_, child)| child.span.contains(&offset))
+        {
+            path.push(index);
+            node = child;
+        }
+        Some(path)
+    }
+}
+
 /// Parse a complete S-expression from a string.
 ///
 /// This parses a single S-expression from the input. Any trailing content
@@ -116,17 +165,345 @@ pub fn parse_all(input: &str) -> ParseResult<Vec<Sexpr>> {
     Ok(sexprs)
 }
 
+/// Parse a single S-expression and record the source span of every node.
+///
+/// This accepts exactly the same input as [`parse`] and returns the same
+/// [`Sexpr`], along with a [`SpanMap`] giving the byte range of each node.
+/// Spans exclude surrounding whitespace and comments.
+///
+/// # Examples
+///
+/// ```
+/// use fermata::sexpr::parser::parse_spanned;
+///
+/// let (sexpr, spans) = parse_spanned("(note c4 :q)").unwrap();
+/// assert!(sexpr.is_list());
+/// assert_eq!(spans.get(&[1]).unwrap().span, 6..8);
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ParseError`] if the input contains invalid syntax.
+pub fn parse_spanned(input: &str) -> ParseResult<(Sexpr, SpanMap)> {
+    let total = input.len();
+    let (remaining, result) = spanned_sexpr(input, total).map_err(|e| match e {
+        nom::Err::Incomplete(_) => ParseError::UnexpectedEof,
+        nom::Err::Error(e) | nom::Err::Failure(e) => ParseError::Nom(format!("{:?}", e)),
+    })?;
+
+    // Check for trailing content (allow whitespace/comments)
+    let (remaining, _) = skip_ws_and_comments(remaining).map_err(|_| ParseError::UnexpectedEof)?;
+
+    if !remaining.is_empty() {
+        return Err(ParseError::TrailingContent(
+            remaining[..remaining.len().min(20)].to_string(),
+        ));
+    }
+
+    Ok(result)
+}
+
+/// Parse a single S-expression, recovering from syntax errors.
+///
+/// Instead of stopping at the first problem, the parser records it, skips
+/// ahead to the next newline or paren, and carries on; lists still open at
+/// end of input are closed there. The returned expression holds whatever
+/// could be parsed, and the errors come back in source order, each with the
+/// byte range of the offending text.
+///
+/// Input that [`parse`] accepts yields the same expression and no errors.
+///
+/// # Examples
+///
+/// ```
+/// use fermata::sexpr::parser::parse_recover;
+///
+/// let (sexpr, errors) = parse_recover("(a ] (b [ c))");
+/// assert!(sexpr.is_some());
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[0].1, 3..4);
+/// ```
+pub fn parse_recover(input: &str) -> (Option<Sexpr>, Vec<(ParseError, Range<usize>)>) {
+    let mut recovery = Recovery {
+        input,
+        pos: 0,
+        errors: Vec::new(),
+    };
+
+    recovery.skip_trivia();
+    if recovery.at_end() {
+        recovery
+            .errors
+            .push((ParseError::UnexpectedEof, input.len()..input.len()));
+        return (None, recovery.errors);
+    }
+
+    let mut result = None;
+    let mut parsed_first = false;
+    let mut reported_trailing = false;
+    while !recovery.at_end() {
+        let start = recovery.pos;
+        if recovery.rest().starts_with(')') {
+            recovery.pos += 1;
+            recovery
+                .errors
+                .push((ParseError::UnexpectedChar(')'), start..start + 1));
+        } else if !parsed_first {
+            result = recovery.expr();
+            parsed_first = true;
+        } else {
+            let snippet: String = recovery.rest().chars().take(20).collect();
+            let before = recovery.errors.len();
+            recovery.expr();
+            // An unclosed list already runs to the end; one error is enough
+            let unclosed = recovery.errors[before..]
+                .iter()
+                .any(|(error, _)| matches!(error, ParseError::UnclosedList));
+            if !reported_trailing && !unclosed {
+                recovery
+                    .errors
+                    .push((ParseError::TrailingContent(snippet), start..recovery.pos));
+                reported_trailing = true;
+            }
+        }
+        recovery.skip_trivia();
+    }
+
+    recovery.errors.sort_by_key(|(_, span)| span.start);
+    (result, recovery.errors)
+}
+
+/// State for [`parse_recover`].
+struct Recovery<'a> {
+    input: &'a str,
+    pos: usize,
+    errors: Vec<(ParseError, Range<usize>)>,
+}
+
+impl<'a> Recovery<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    fn skip_trivia(&mut self) {
+        if let Ok((rest, _)) = skip_ws_and_comments(self.rest()) {
+            self.pos = self.input.len() - rest.len();
+        }
+    }
+
+    /// Parse one expression at the current (non-trivia) position.
+    ///
+    /// Returns `None` if it was malformed; the error is recorded and the
+    /// position always advances.
+    fn expr(&mut self) -> Option<Sexpr> {
+        if self.rest().starts_with('(') {
+            return Some(self.list());
+        }
+        if let Some((c, head)) = self.rest().chars().next().and_then(prefix_symbol) {
+            let start = self.pos;
+            self.pos += 1;
+            self.skip_trivia();
+            if self.at_end() || self.rest().starts_with(')') {
+                self.errors
+                    .push((ParseError::UnexpectedChar(c), start..start + 1));
+                return None;
+            }
+            let inner = self.expr()?;
+            return Some(Sexpr::List(vec![Sexpr::symbol(head), inner]));
+        }
+
+        let atom = alt((boolean, nil, string_literal, number, keyword, symbol)).parse(self.rest());
+        match atom {
+            Ok((rest, atom)) => {
+                self.pos = self.input.len() - rest.len();
+                Some(atom)
+            }
+            Err(_) => {
+                self.bad_atom();
+                None
+            }
+        }
+    }
+
+    fn list(&mut self) -> Sexpr {
+        let start = self.pos;
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_trivia();
+            if self.at_end() {
+                self.errors
+                    .push((ParseError::UnclosedList, start..start + 1));
+                return Sexpr::List(items);
+            }
+            if self.rest().starts_with(')') {
+                self.pos += 1;
+                return Sexpr::List(items);
+            }
+            if let Some(item) = self.expr() {
+                items.push(item);
+            }
+        }
+    }
+
+    /// Record an error for the malformed atom at the current position and
+    /// skip past it.
+    fn bad_atom(&mut self) {
+        let start = self.pos;
+        let rest = &self.input[start..];
+        let Some(c) = rest.chars().next() else {
+            return;
+        };
+
+        if c != '"' {
+            self.errors
+                .push((ParseError::UnexpectedChar(c), start..start + c.len_utf8()));
+            self.pos += c.len_utf8();
+            // Resynchronize at the next line or list boundary
+            let skip = self
+                .rest()
+                .find(['\n', '(', ')'])
+                .unwrap_or(self.rest().len());
+            self.pos += skip;
+            return;
+        }
+
+        // Strings: report the first bad escape, or the missing close quote
+        let mut chars = rest.char_indices().skip(1);
+        let mut invalid = None;
+        while let Some((i, ch)) = chars.next() {
+            match ch {
+                '"' => {
+                    let error = invalid.unwrap_or_else(|| {
+                        (
+                            ParseError::Nom(format!("invalid string at byte {}", start)),
+                            0,
+                        )
+                    });
+                    self.errors.push((error.0, start + error.1..start + i + 1));
+                    self.pos = start + i + 1;
+                    return;
+                }
+                '\\' => {
+                    if let Some((_, escape)) = chars.next()
+                        && !matches!(escape, '"' | '\\' | 'n' | 'r' | 't')
+                        && invalid.is_none()
+                    {
+                        invalid = Some((ParseError::InvalidEscape(escape), i));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Unclosed: the rest of the line is lost
+        self.errors
+            .push((ParseError::UnclosedString, start..start + 1));
+        self.pos = start + rest.find('\n').unwrap_or(rest.len());
+    }
+}
+
 // === Internal Parsers ===
 
+/// Parse a single S-expression, tracking offsets relative to an input of
+/// `total` bytes.
+fn spanned_sexpr(input: &str, total: usize) -> IResult<&str, (Sexpr, SpanMap)> {
+    let (input, _) = skip_ws_and_comments(input)?;
+    let start = total - input.len();
+
+    if let Some((c, head)) = input.chars().next().and_then(prefix_symbol) {
+        let (rest, (inner, inner_span)) = spanned_sexpr(&input[c.len_utf8()..], total)?;
+        let head_span = SpanMap {
+            span: start..start + c.len_utf8(),
+            children: Vec::new(),
+        };
+        return Ok((
+            rest,
+            (
+                Sexpr::List(vec![Sexpr::symbol(head), inner]),
+                SpanMap {
+                    span: start..total - rest.len(),
+                    children: vec![head_span, inner_span],
+                },
+            ),
+        ));
+    }
+
+    if let Ok((mut rest, _)) = char::<&str, nom::error::Error<&str>>('(').parse(input) {
+        let mut items = Vec::new();
+        let mut children = Vec::new();
+        loop {
+            let (after_ws, _) = skip_ws_and_comments(rest)?;
+            if let Ok((after_close, _)) = char::<&str, nom::error::Error<&str>>(')').parse(after_ws)
+            {
+                let span = start..total - after_close.len();
+                return Ok((
+                    after_close,
+                    (Sexpr::List(items), SpanMap { span, children }),
+                ));
+            }
+            let (after_item, (item, item_span)) = spanned_sexpr(after_ws, total)?;
+            items.push(item);
+            children.push(item_span);
+            rest = after_item;
+        }
+    }
+
+    let (rest, atom) = alt((boolean, nil, string_literal, number, keyword, symbol)).parse(input)?;
+    let span = start..total - rest.len();
+    Ok((
+        rest,
+        (
+            atom,
+            SpanMap {
+                span,
+                children: Vec::new(),
+            },
+        ),
+    ))
+}
+
 /// Parse a single S-expression.
 fn sexpr(input: &str) -> IResult<&str, Sexpr> {
     preceded(
         skip_ws_and_comments,
-        alt((boolean, nil, string_literal, number, keyword, symbol, list)),
+        alt((
+            boolean,
+            nil,
+            string_literal,
+            number,
+            keyword,
+            symbol,
+            list,
+            prefixed,
+        )),
     )
     .parse(input)
 }
 
+/// The prefix character at the start of `` `x `` or `,x` and the symbol it
+/// abbreviates.
+fn prefix_symbol(c: char) -> Option<(char, &'static str)> {
+    match c {
+        '`' => Some((c, "quasiquote")),
+        ',' => Some((c, "unquote")),
+        _ => None,
+    }
+}
+
+/// Parse a prefixed expression: `` `x `` as `(quasiquote x)` or `,x` as
+/// `(unquote x)`.
+fn prefixed(input: &str) -> IResult<&str, Sexpr> {
+    let (rest, c) = one_of("`,").parse(input)?;
+    let (rest, inner) = sexpr(rest)?;
+    let (_, head) = prefix_symbol(c).expect("one_of only matches prefix characters");
+    Ok((rest, Sexpr::List(vec![Sexpr::symbol(head), inner])))
+}
+
 /// Skip whitespace and comments.
 fn skip_ws_and_comments(input: &str) -> IResult<&str, ()> {
     let mut remaining = input;
@@ -758,4 +1135,80 @@ mod tests {
         let list = parsed.as_list().unwrap();
         assert_eq!(list.len(), 3);
     }
+
+    // === Quasiquote Tests ===
+
+    #[test]
+    fn test_parse_quasiquote_and_unquote_expand_to_lists() {
+        let sexpr = parse("`(,root e4)").unwrap();
+
+        assert_eq!(
+            sexpr,
+            Sexpr::list(vec![
+                Sexpr::symbol("quasiquote"),
+                Sexpr::list(vec![
+                    Sexpr::list(vec![Sexpr::symbol("unquote"), Sexpr::symbol("root")]),
+                    Sexpr::symbol("e4"),
+                ]),
+            ])
+        );
+        assert_eq!(parse_spanned("`(,root e4)").unwrap().0, sexpr);
+        assert_eq!(parse_recover("`(,root e4)"), (Some(sexpr), vec![]));
+    }
+
+    #[test]
+    fn test_parse_recover_unclosed_trailing_list_is_one_error() {
+        let (sexpr, errors) = parse_recover("(score)\n(oops");
+
+        assert_eq!(sexpr, Some(Sexpr::list(vec![Sexpr::symbol("score")])));
+        assert_eq!(errors, vec![(ParseError::UnclosedList, 8..9)]);
+    }
+
+    #[test]
+    fn test_parse_recover_dangling_unquote_is_error() {
+        let (sexpr, errors) = parse_recover("(a ,)");
+
+        assert_eq!(sexpr, Some(Sexpr::list(vec![Sexpr::symbol("a")])));
+        assert_eq!(errors, vec![(ParseError::UnexpectedChar(','), 3..4)]);
+    }
+
+    #[test]
+    fn test_parse_spanned_symbol_span_is_exact() {
+        let (sexpr, spans) = parse_spanned("(note c4 :q)").unwrap();
+        assert_eq!(sexpr, parse("(note c4 :q)").unwrap());
+        assert_eq!(spans.span, 0..12);
+        assert_eq!(spans.get(&[1]).unwrap().span, 6..8);
+        assert_eq!(spans.get(&[2]).unwrap().span, 9..11);
+    }
+
+    #[test]
+    fn test_parse_spanned_nested_skips_comments() {
+        let source = "; melody\n(measure\n  (note c4 :q) ; first\n  (note \"a b\" :h))";
+        let (sexpr, spans) = parse_spanned(source).unwrap();
+        assert_eq!(sexpr, parse(source).unwrap());
+
+        let second = spans.get(&[2, 1]).unwrap();
+        assert_eq!(&source[second.span.clone()], "\"a b\"");
+        assert_eq!(&source[spans.span.clone()], &source[9..]);
+        assert!(spans.get(&[3]).is_none());
+    }
+
+    #[test]
+    fn test_parse_spanned_path_at_finds_innermost() {
+        let source = "(measure (note c4 :q))";
+        let (_, spans) = parse_spanned(source).unwrap();
+        assert_eq!(spans.path_at(16), Some(vec![1, 1]));
+        assert_eq!(spans.path_at(9), Some(vec![1]));
+        assert_eq!(spans.path_at(0), Some(vec![]));
+        assert_eq!(spans.path_at(40), None);
+    }
+
+    #[test]
+    fn test_parse_spanned_errors_match_parse() {
+        assert!(parse_spanned("(a (b)").is_err());
+        assert!(matches!(
+            parse_spanned("(a) (b)"),
+            Err(ParseError::TrailingContent(_))
+        ));
+    }
 }
@@ -4,7 +4,7 @@
 //! S-expression strings.
 
 use crate::ir::{
-    Barline, Measure,
+    Barline, Listening, Measure, Print,
     attributes::{
         Attributes, BarStyle, Cancel, Clef, ClefSign, Ending, GroupSymbolValue, Key, KeyContent,
         Mode, Repeat, StaffDetails, Time, TimeContent, TimeSymbol, Transpose,
@@ -35,7 +35,7 @@ use crate::ir::{
         VirtualInstrument,
     },
     pitch::{Pitch, Step, Unpitched},
-    score::{Credit, Defaults, ScorePartwise, Work},
+    score::{Credit, Defaults, MarginType, PageLayout, ScorePartwise, SystemLayout, Work},
     voice::{Backup, Forward},
 };
 
@@ -283,6 +283,9 @@ fn print_defaults(defaults: &Defaults, level: usize, options: &PrintOptions) ->
             format_float(scaling.tenths)
         ));
     }
+    if defaults.concert_score {
+        out.push_str(" :concert-score #t");
+    }
 
     out.push(')');
     out
@@ -594,6 +597,8 @@ fn print_music_data_element(
         MusicDataElement::Direction(direction) => print_direction(direction, level, options),
         MusicDataElement::Attributes(attrs) => print_attributes(attrs, level, options),
         MusicDataElement::Barline(barline) => print_barline(barline, level, options),
+        MusicDataElement::Print(print) => print_print(print, level, options),
+        MusicDataElement::Listening(listening) => print_listening(listening, level, options),
     }
 }
 
@@ -1799,6 +1804,162 @@ fn print_barline(barline: &Barline, level: usize, options: &PrintOptions) -> Str
     out
 }
 
+fn print_print(print: &Print, level: usize, options: &PrintOptions) -> String {
+    let ind = indent(level, options);
+    let mut out = format!("{}(print", ind);
+
+    if let Some(new_system) = print.new_system {
+        out.push_str(&format!(" :new-system {}", print_yes_no(new_system)));
+    }
+    if let Some(new_page) = print.new_page {
+        out.push_str(&format!(" :new-page {}", print_yes_no(new_page)));
+    }
+    if let Some(ref page_number) = print.page_number {
+        out.push_str(&format!(" :page-number \"{}\"", escape_string(page_number)));
+    }
+    if let Some(ref page_layout) = print.page_layout {
+        out.push(' ');
+        out.push_str(&print_page_layout(page_layout));
+    }
+    if let Some(ref system_layout) = print.system_layout {
+        out.push(' ');
+        out.push_str(&print_system_layout(system_layout));
+    }
+    for staff_layout in &print.staff_layouts {
+        out.push_str(" (staff-layout");
+        if let Some(number) = staff_layout.number {
+            out.push_str(&format!(" :number {}", number));
+        }
+        if let Some(distance) = staff_layout.staff_distance {
+            out.push_str(&format!(" :staff-distance {}", format_float(distance)));
+        }
+        out.push(')');
+    }
+    if let Some(ref measure_layout) = print.measure_layout {
+        out.push_str(" (measure-layout");
+        if let Some(distance) = measure_layout.measure_distance {
+            out.push_str(&format!(" :measure-distance {}", format_float(distance)));
+        }
+        out.push(')');
+    }
+
+    out.push(')');
+    out
+}
+
+fn print_page_layout(layout: &PageLayout) -> String {
+    let mut out = String::from("(page-layout");
+
+    if let Some(height) = layout.page_height {
+        out.push_str(&format!(" :page-height {}", format_float(height)));
+    }
+    if let Some(width) = layout.page_width {
+        out.push_str(&format!(" :page-width {}", format_float(width)));
+    }
+    for margins in &layout.page_margins {
+        out.push_str(" (page-margins");
+        if let Some(margin_type) = margins.r#type {
+            let margin_type = match margin_type {
+                MarginType::Odd => "odd",
+                MarginType::Even => "even",
+                MarginType::Both => "both",
+            };
+            out.push_str(&format!(" :type :{}", margin_type));
+        }
+        out.push_str(&format!(
+            " :left {} :right {} :top {} :bottom {})",
+            format_float(margins.left),
+            format_float(margins.right),
+            format_float(margins.top),
+            format_float(margins.bottom)
+        ));
+    }
+
+    out.push(')');
+    out
+}
+
+fn print_system_layout(layout: &SystemLayout) -> String {
+    let mut out = String::from("(system-layout");
+
+    if let Some(distance) = layout.system_distance {
+        out.push_str(&format!(" :system-distance {}", format_float(distance)));
+    }
+    if let Some(distance) = layout.top_system_distance {
+        out.push_str(&format!(" :top-system-distance {}", format_float(distance)));
+    }
+    if let Some(ref margins) = layout.system_margins {
+        out.push_str(&format!(
+            " (system-margins :left {} :right {})",
+            format_float(margins.left),
+            format_float(margins.right)
+        ));
+    }
+    if let Some(ref dividers) = layout.system_dividers {
+        out.push_str(" (system-dividers");
+        for (head, divider) in [
+            ("left-divider", &dividers.left_divider),
+            ("right-divider", &dividers.right_divider),
+        ] {
+            let Some(divider) = divider else {
+                continue;
+            };
+            out.push_str(&format!(" ({}", head));
+            if let Some(print_object) = divider.print_object {
+                out.push_str(&format!(" :print-object {}", print_yes_no(print_object)));
+            }
+            out.push(')');
+        }
+        out.push(')');
+    }
+
+    out.push(')');
+    out
+}
+
+fn print_listening(listening: &Listening, level: usize, options: &PrintOptions) -> String {
+    let ind = indent(level, options);
+    let mut out = format!("{}(listening", ind);
+
+    for sync in &listening.syncs {
+        out.push_str(&format!(" (sync :type \"{}\"", escape_string(&sync.r#type)));
+        if let Some(latency) = sync.latency {
+            out.push_str(&format!(" :latency {}", latency));
+        }
+        if let Some(ref player) = sync.player {
+            out.push_str(&format!(" :player \"{}\"", escape_string(player)));
+        }
+        if let Some(ref time_only) = sync.time_only {
+            out.push_str(&format!(" :time-only \"{}\"", escape_string(time_only)));
+        }
+        out.push(')');
+    }
+    for other in &listening.other_listening {
+        out.push_str(&format!(
+            " (other-listening :type \"{}\" :value \"{}\"",
+            escape_string(&other.r#type),
+            escape_string(&other.value)
+        ));
+        if let Some(ref player) = other.player {
+            out.push_str(&format!(" :player \"{}\"", escape_string(player)));
+        }
+        if let Some(ref time_only) = other.time_only {
+            out.push_str(&format!(" :time-only \"{}\"", escape_string(time_only)));
+        }
+        out.push(')');
+    }
+    if let Some(ref offset) = listening.offset {
+        out.push_str(&format!(" (offset :value {}", offset.value));
+        if let Some(sound) = offset.sound {
+            out.push_str(&format!(" :sound {}", print_yes_no(sound)));
+        }
+        out.push(')');
+    }
+
+    out.push(')');
+    out
+}
+
 fn print_repeat(repeat: &Repeat, level: usize, options: &PrintOptions) -> String {
     let ind = indent(level, options);
     let mut out = format!("{}(repeat", ind);
@@ -2590,6 +2751,33 @@ mod tests {
         assert_eq!(result, "(forward :duration 2 :voice \"1\" :staff 1)");
     }
 
+    #[test]
+    fn test_print_print_with_layouts() {
+        let print = Print {
+            new_system: Some(YesNo::Yes),
+            system_layout: Some(SystemLayout {
+                system_margins: Some(crate::ir::score::SystemMargins {
+                    left: 0.0,
+                    right: 0.0,
+                }),
+                system_distance: Some(120.0),
+                top_system_distance: None,
+                system_dividers: None,
+            }),
+            staff_layouts: vec![crate::ir::score::StaffLayout {
+                number: Some(2),
+                staff_distance: Some(65.5),
+            }],
+            ..Print::default()
+        };
+        let options = PrintOptions::default();
+        assert_eq!(
+            print_print(&print, 0, &options),
+            "(print :new-system #t (system-layout :system-distance 120 \
+             (system-margins :left 0 :right 0)) (staff-layout :number 2 :staff-distance 65.5))"
+        );
+    }
+
     // === Indent Tests ===
 
     #[test]
@@ -5,7 +5,7 @@ use std::process::ExitCode;
 use owo_colors::OwoColorize;
 use serde::Serialize;
 
-use crate::{OutputFormat, ShowTopic};
+use crate::{OutputFormat, PitchNotation, ShowTopic};
 
 /// Run a show command with the given topic and format.
 pub fn run(topic: ShowTopic, format: OutputFormat, use_colors: bool) -> ExitCode {
@@ -13,7 +13,7 @@ pub fn run(topic: ShowTopic, format: OutputFormat, use_colors: bool) -> ExitCode
         ShowTopic::Targets => show_targets(format, use_colors),
         ShowTopic::Syntax => show_syntax(format, use_colors),
         ShowTopic::Durations => show_durations(format, use_colors),
-        ShowTopic::Pitches => show_pitches(format, use_colors),
+        ShowTopic::Pitches { notation } => show_pitches(notation, format, use_colors),
         ShowTopic::Clefs => show_clefs(format, use_colors),
         ShowTopic::Keys => show_keys(format, use_colors),
         ShowTopic::Dynamics => show_dynamics(format, use_colors),
@@ -24,20 +24,20 @@ pub fn run(topic: ShowTopic, format: OutputFormat, use_colors: bool) -> ExitCode
         ShowTopic::Accidentals => show_accidentals(format, use_colors),
         ShowTopic::Noteheads => show_noteheads(format, use_colors),
         ShowTopic::Fermatas => show_fermatas(format, use_colors),
+        ShowTopic::Examples => show_examples(format, use_colors),
     }
 }
 
 /// A reference item with keyword, description, and optional example.
-#[derive(Debug, Serialize)]
+#[derive(Debug)]
 struct RefItem {
     keyword: &'static str,
     description: &'static str,
-    #[serde(skip_serializing_if = "Option::is_none")]
     example: Option<&'static str>,
 }
 
 /// A category of reference items.
-#[derive(Debug, Serialize)]
+#[derive(Debug)]
 struct RefCategory {
     name: &'static str,
     items: Vec<RefItem>,
@@ -80,14 +80,62 @@ fn print_text(title: &str, categories: &[RefCategory], use_colors: bool) {
     }
 }
 
-/// Print items in JSON format.
-fn print_json(categories: &[RefCategory]) {
-    let json = serde_json::to_string_pretty(categories).expect("JSON serialization failed");
-    println!("{}", json);
+/// One entry of a reference topic in JSON output.
+///
+/// Every topic, including examples, serializes to the same entry shape so
+/// that tools (editor autocomplete, docs generators) can consume any topic
+/// without special cases.
+#[derive(Debug, Serialize)]
+struct ReferenceEntry {
+    /// What to type, e.g. `:q` or `(tuplet 3 2 ...)`
+    symbol: &'static str,
+    /// Short human-readable name
+    name: &'static str,
+    /// Full description
+    description: &'static str,
+    /// Group within the topic, if the topic is grouped
+    #[serde(skip_serializing_if = "str::is_empty")]
+    category: &'static str,
+    /// Usage example, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    example: Option<&'static str>,
+    /// Complete source, for entries that are whole example scores
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<&'static str>,
+}
+
+/// The JSON envelope for a reference topic.
+#[derive(Debug, Serialize)]
+struct ReferenceTopic {
+    topic: &'static str,
+    entries: Vec<ReferenceEntry>,
+}
+
+impl ReferenceEntry {
+    /// Convert a text-mode item; the name is the description up to any
+    /// parenthetical, so "Quarter note (crotchet)" is named "Quarter note".
+    fn from_item(category: &'static str, item: RefItem) -> Self {
+        let name = item.description.split(" (").next().unwrap_or_default();
+        ReferenceEntry {
+            symbol: item.keyword,
+            name,
+            description: item.description,
+            category,
+            example: item.example,
+            source: None,
+        }
+    }
+}
+
+/// Serialize a topic as a pretty-printed JSON envelope.
+fn topic_json(topic: &'static str, entries: Vec<ReferenceEntry>) -> String {
+    serde_json::to_string_pretty(&ReferenceTopic { topic, entries })
+        .expect("JSON serialization failed")
 }
 
 /// Output helper that handles format selection.
 fn output(
+    topic: &'static str,
     title: &str,
     categories: Vec<RefCategory>,
     format: OutputFormat,
@@ -95,7 +143,19 @@ fn output(
 ) -> ExitCode {
     match format {
         OutputFormat::Text => print_text(title, &categories, use_colors),
-        OutputFormat::Json => print_json(&categories),
+        OutputFormat::Json => {
+            let entries = categories
+                .into_iter()
+                .flat_map(|category| {
+                    let name = category.name;
+                    category
+                        .items
+                        .into_iter()
+                        .map(move |item| ReferenceEntry::from_item(name, item))
+                })
+                .collect();
+            println!("{}", topic_json(topic, entries));
+        }
     }
     ExitCode::SUCCESS
 }
@@ -128,7 +188,7 @@ fn show_targets(format: OutputFormat, use_colors: bool) -> ExitCode {
             },
         ],
     }];
-    output("Output Targets", categories, format, use_colors)
+    output("targets", "Output Targets", categories, format, use_colors)
 }
 
 fn show_syntax(format: OutputFormat, use_colors: bool) -> ExitCode {
@@ -151,11 +211,26 @@ fn show_syntax(format: OutputFormat, use_colors: bool) -> ExitCode {
                     description: "A measure of music",
                     example: None,
                 },
+                RefItem {
+                    keyword: "(pickup ...)",
+                    description: "Pickup measure, numbered 0",
+                    example: Some("(pickup (note g4 :8))"),
+                },
                 RefItem {
                     keyword: "(voice ...)",
                     description: "Voice within a measure",
                     example: None,
                 },
+                RefItem {
+                    keyword: "(define name ...)",
+                    description: "Name a sequence of forms for reuse",
+                    example: Some("(define motif (note c4 :8) (note e4 :8))"),
+                },
+                RefItem {
+                    keyword: "(define (name params) `...)",
+                    description: "Template with , for parameters and (up p :M3)",
+                    example: Some("(define (arp r) `(,r ,(up r :M3) ,(up r :P5)))"),
+                },
             ],
         },
         RefCategory {
@@ -171,11 +246,26 @@ fn show_syntax(format: OutputFormat, use_colors: bool) -> ExitCode {
                     description: "A rest",
                     example: Some("(rest :q)"),
                 },
+                RefItem {
+                    keyword: ":invisible",
+                    description: "Hide a note or rest but keep its time",
+                    example: Some("(rest :q :invisible)"),
+                },
                 RefItem {
                     keyword: "(chord ...)",
                     description: "Multiple notes together",
                     example: Some("(chord :q (c4 e4 g4))"),
                 },
+                RefItem {
+                    keyword: ":arpeggiate",
+                    description: "Roll a chord (:arpeggiate-up, :arpeggiate-down)",
+                    example: Some("(chord (c4 e4 g4) :q :arpeggiate)"),
+                },
+                RefItem {
+                    keyword: ":non-arpeggiate",
+                    description: "Bracket a chord to be played unrolled",
+                    example: Some("(chord (c4 e4 g4) :q :non-arpeggiate)"),
+                },
             ],
         },
         RefCategory {
@@ -201,10 +291,21 @@ fn show_syntax(format: OutputFormat, use_colors: bool) -> ExitCode {
                     description: "Tempo marking",
                     example: Some("(tempo 120 :quarter)"),
                 },
+                RefItem {
+                    keyword: "(text ...)",
+                    description: "Expressive text (:above, :below, :italic, :bold)",
+                    example: Some("(text \"rit.\" :above :italic)"),
+                },
             ],
         },
     ];
-    output("Syntax Quick Reference", categories, format, use_colors)
+    output(
+        "syntax",
+        "Syntax Quick Reference",
+        categories,
+        format,
+        use_colors,
+    )
 }
 
 fn show_durations(format: OutputFormat, use_colors: bool) -> ExitCode {
@@ -292,13 +393,58 @@ fn show_durations(format: OutputFormat, use_colors: bool) -> ExitCode {
                     description: "Add multiple dots",
                     example: Some("(note c4 :h :dots 2)"),
                 },
+                RefItem {
+                    keyword: "N/M",
+                    description: "Fraction of a whole note, with any dots it implies",
+                    example: Some("(note c4 3/8) = dotted quarter"),
+                },
             ],
         },
     ];
-    output("Duration Symbols", categories, format, use_colors)
+    output(
+        "durations",
+        "Duration Symbols",
+        categories,
+        format,
+        use_colors,
+    )
 }
 
-fn show_pitches(format: OutputFormat, use_colors: bool) -> ExitCode {
+/// Fermata spellings of the chromatic pitch classes, in the order of the
+/// tables returned by [`pitch_names`].
+const PITCH_SPELLINGS: [&str; 17] = [
+    "c", "c#", "db", "d", "d#", "eb", "e", "f", "f#", "gb", "g", "g#", "ab", "a", "a#", "bb", "b",
+];
+
+/// How each of [`PITCH_SPELLINGS`] is named in a notation.
+fn pitch_names(notation: PitchNotation) -> [&'static str; 17] {
+    match notation {
+        PitchNotation::English => [
+            "C", "C#", "Db", "D", "D#", "Eb", "E", "F", "F#", "Gb", "G", "G#", "Ab", "A", "A#",
+            "Bb", "B",
+        ],
+        PitchNotation::German => [
+            "C", "Cis", "Des", "D", "Dis", "Es", "E", "F", "Fis", "Ges", "G", "Gis", "As", "A",
+            "Ais", "B", "H",
+        ],
+        PitchNotation::Solfege => [
+            "Do", "Do#", "Reb", "Re", "Re#", "Mib", "Mi", "Fa", "Fa#", "Solb", "Sol", "Sol#",
+            "Lab", "La", "La#", "Sib", "Si",
+        ],
+        PitchNotation::Dutch => [
+            "C", "Cis", "Des", "D", "Dis", "Es", "E", "F", "Fis", "Ges", "G", "Gis", "As", "A",
+            "Ais", "Bes", "B",
+        ],
+    }
+}
+
+fn show_pitches(notation: PitchNotation, format: OutputFormat, use_colors: bool) -> ExitCode {
+    let names_category = match notation {
+        PitchNotation::English => "Note Names (English)",
+        PitchNotation::German => "Note Names (German)",
+        PitchNotation::Solfege => "Note Names (Solfege)",
+        PitchNotation::Dutch => "Note Names (Dutch)",
+    };
     let categories = vec![
         RefCategory {
             name: "Pitch Names",
@@ -308,6 +454,18 @@ fn show_pitches(format: OutputFormat, use_colors: bool) -> ExitCode {
                 example: Some("c4, D5, e3"),
             }],
         },
+        RefCategory {
+            name: names_category,
+            items: PITCH_SPELLINGS
+                .iter()
+                .zip(pitch_names(notation))
+                .map(|(&keyword, description)| RefItem {
+                    keyword,
+                    description,
+                    example: None,
+                })
+                .collect(),
+        },
         RefCategory {
             name: "Octaves",
             items: vec![
@@ -359,7 +517,7 @@ fn show_pitches(format: OutputFormat, use_colors: bool) -> ExitCode {
             ],
         },
     ];
-    output("Pitch Notation", categories, format, use_colors)
+    output("pitches", "Pitch Notation", categories, format, use_colors)
 }
 
 fn show_clefs(format: OutputFormat, use_colors: bool) -> ExitCode {
@@ -429,8 +587,33 @@ fn show_clefs(format: OutputFormat, use_colors: bool) -> ExitCode {
                 },
             ],
         },
+        RefCategory {
+            name: "Other Lines",
+            items: vec![
+                RefItem {
+                    keyword: ":soprano",
+                    description: "C clef on line 1",
+                    example: Some("Early vocal music"),
+                },
+                RefItem {
+                    keyword: ":mezzo-soprano",
+                    description: "C clef on line 2",
+                    example: None,
+                },
+                RefItem {
+                    keyword: ":baritone",
+                    description: "C clef on line 5",
+                    example: None,
+                },
+                RefItem {
+                    keyword: ":<clef> <line>",
+                    description: "Any clef moved to another staff line",
+                    example: Some("(clef :c 2) (clef :percussion 2) (clef :tab 6)"),
+                },
+            ],
+        },
     ];
-    output("Clefs", categories, format, use_colors)
+    output("clefs", "Clefs", categories, format, use_colors)
 }
 
 fn show_keys(format: OutputFormat, use_colors: bool) -> ExitCode {
@@ -526,7 +709,7 @@ fn show_keys(format: OutputFormat, use_colors: bool) -> ExitCode {
             ],
         },
     ];
-    output("Key Signatures", categories, format, use_colors)
+    output("keys", "Key Signatures", categories, format, use_colors)
 }
 
 fn show_dynamics(format: OutputFormat, use_colors: bool) -> ExitCode {
@@ -672,7 +855,13 @@ fn show_dynamics(format: OutputFormat, use_colors: bool) -> ExitCode {
             ],
         },
     ];
-    output("Dynamic Markings", categories, format, use_colors)
+    output(
+        "dynamics",
+        "Dynamic Markings",
+        categories,
+        format,
+        use_colors,
+    )
 }
 
 fn show_articulations(format: OutputFormat, use_colors: bool) -> ExitCode {
@@ -712,6 +901,21 @@ fn show_articulations(format: OutputFormat, use_colors: bool) -> ExitCode {
                 },
             ],
         },
+        RefCategory {
+            name: "Placement",
+            items: vec![
+                RefItem {
+                    keyword: ":above / :below",
+                    description: "Place the preceding articulation relative to the staff",
+                    example: Some("(note c4 :q :accent :below)"),
+                },
+                RefItem {
+                    keyword: "(:art :below)",
+                    description: "Articulation and placement as a list",
+                    example: Some("(note c4 :q (:staccato :below))"),
+                },
+            ],
+        },
         RefCategory {
             name: "Jazz Articulations",
             items: vec![
@@ -768,7 +972,13 @@ fn show_articulations(format: OutputFormat, use_colors: bool) -> ExitCode {
             ],
         },
     ];
-    output("Articulations", categories, format, use_colors)
+    output(
+        "articulations",
+        "Articulations",
+        categories,
+        format,
+        use_colors,
+    )
 }
 
 fn show_ornaments(format: OutputFormat, use_colors: bool) -> ExitCode {
@@ -837,9 +1047,14 @@ fn show_ornaments(format: OutputFormat, use_colors: bool) -> ExitCode {
             name: "Other Ornaments",
             items: vec![
                 RefItem {
-                    keyword: ":tremolo",
-                    description: "Tremolo (rapid repetition)",
-                    example: Some("1-8 slashes"),
+                    keyword: "(tremolo N note)",
+                    description: "Single-note tremolo with 1-8 slashes",
+                    example: Some("(tremolo 3 (note c4 :q))"),
+                },
+                RefItem {
+                    keyword: "(tremolo-pair N note note)",
+                    description: "Two-note tremolo sharing one note's time",
+                    example: Some("(tremolo-pair 3 (note c4 :h) (note g4 :h))"),
                 },
                 RefItem {
                     keyword: ":schleifer",
@@ -849,7 +1064,7 @@ fn show_ornaments(format: OutputFormat, use_colors: bool) -> ExitCode {
             ],
         },
     ];
-    output("Ornaments", categories, format, use_colors)
+    output("ornaments", "Ornaments", categories, format, use_colors)
 }
 
 fn show_instruments(format: OutputFormat, use_colors: bool) -> ExitCode {
@@ -1015,7 +1230,7 @@ fn show_instruments(format: OutputFormat, use_colors: bool) -> ExitCode {
             ],
         },
     ];
-    output("Instruments", categories, format, use_colors)
+    output("instruments", "Instruments", categories, format, use_colors)
 }
 
 fn show_barlines(format: OutputFormat, use_colors: bool) -> ExitCode {
@@ -1070,6 +1285,21 @@ fn show_barlines(format: OutputFormat, use_colors: bool) -> ExitCode {
                 },
             ],
         },
+        RefCategory {
+            name: "Endings",
+            items: vec![
+                RefItem {
+                    keyword: "(ending 1 ...)",
+                    description: "Volta bracket over the enclosed measures",
+                    example: Some("(ending 1 (measure ...))"),
+                },
+                RefItem {
+                    keyword: ":discontinue",
+                    description: "Leave the bracket open at the end",
+                    example: Some("(ending 2 :discontinue (measure ...))"),
+                },
+            ],
+        },
         RefCategory {
             name: "Special Barlines",
             items: vec![
@@ -1101,7 +1331,7 @@ fn show_barlines(format: OutputFormat, use_colors: bool) -> ExitCode {
             ],
         },
     ];
-    output("Barline Types", categories, format, use_colors)
+    output("barlines", "Barline Types", categories, format, use_colors)
 }
 
 fn show_accidentals(format: OutputFormat, use_colors: bool) -> ExitCode {
@@ -1247,7 +1477,7 @@ fn show_accidentals(format: OutputFormat, use_colors: bool) -> ExitCode {
             ],
         },
     ];
-    output("Accidentals", categories, format, use_colors)
+    output("accidentals", "Accidentals", categories, format, use_colors)
 }
 
 fn show_noteheads(format: OutputFormat, use_colors: bool) -> ExitCode {
@@ -1408,7 +1638,13 @@ fn show_noteheads(format: OutputFormat, use_colors: bool) -> ExitCode {
             ],
         },
     ];
-    output("Notehead Shapes", categories, format, use_colors)
+    output(
+        "noteheads",
+        "Notehead Shapes",
+        categories,
+        format,
+        use_colors,
+    )
 }
 
 fn show_fermatas(format: OutputFormat, use_colors: bool) -> ExitCode {
@@ -1474,5 +1710,137 @@ fn show_fermatas(format: OutputFormat, use_colors: bool) -> ExitCode {
             ],
         },
     ];
-    output("Fermata Shapes", categories, format, use_colors)
+    output("fermatas", "Fermata Shapes", categories, format, use_colors)
+}
+
+/// A complete, copy-pasteable Fermata example.
+#[derive(Debug)]
+struct Example {
+    name: &'static str,
+    description: &'static str,
+    source: &'static str,
+}
+
+/// The example snippets shown by `fermata show examples`.
+fn examples() -> Vec<Example> {
+    vec![
+        Example {
+            name: "Single note",
+            description: "The smallest complete score: one part, one measure, one note",
+            source: r#"(score
+  (part :piano
+    (measure
+      (note c4 :w))))"#,
+        },
+        Example {
+            name: "C major scale",
+            description: "Two measures of quarter notes with a key, time, and clef",
+            source: r#"(score
+  (part :piano
+    (measure
+      (key c :major)
+      (time 4 4)
+      (clef :treble)
+      (note c4 :q) (note d4 :q) (note e4 :q) (note f4 :q))
+    (measure
+      (note g4 :q) (note a4 :q) (note b4 :q) (note c5 :q))))"#,
+        },
+        Example {
+            name: "Two voices",
+            description: "Voice 1 and voice 2 share a measure; backup rewinds four beats",
+            source: r#"(score
+  (part :piano
+    (measure
+      (note e5 :h :voice 1) (note d5 :h :voice 1)
+      (backup 4)
+      (note c4 :w :voice 2))))"#,
+        },
+        Example {
+            name: "Score with metadata",
+            description: "Title, composer, tempo, and dynamics",
+            source: r#"(score :title "Little Tune" :composer "Anonymous"
+  (part :violin
+    (measure
+      (tempo 96 :quarter)
+      (mf)
+      (note g4 :q) (note a4 :q) (note b4 :h))
+    (measure
+      (chord (g4 b4 d5) :w))))"#,
+        },
+    ]
+}
+
+/// Serialize the examples as a reference topic; each entry's symbol is
+/// the example name and its score is in `source`.
+fn examples_json() -> String {
+    let entries = examples()
+        .into_iter()
+        .map(|example| ReferenceEntry {
+            symbol: example.name,
+            name: example.name,
+            description: example.description,
+            category: "",
+            example: None,
+            source: Some(example.source),
+        })
+        .collect();
+    topic_json("examples", entries)
+}
+
+fn show_examples(format: OutputFormat, use_colors: bool) -> ExitCode {
+    match format {
+        OutputFormat::Text => {
+            let title = "Example Scores";
+            if use_colors {
+                println!("{}", title.bold().underline());
+            } else {
+                println!("{}", title);
+            }
+            println!();
+
+            for example in examples() {
+                if use_colors {
+                    println!("  {}", example.name.cyan().bold());
+                    println!("  {}", format!(";; {}", example.description).dimmed());
+                } else {
+                    println!("  {}", example.name);
+                    println!("  ;; {}", example.description);
+                }
+                for line in example.source.lines() {
+                    println!("  {}", line);
+                }
+                println!();
+            }
+        }
+        OutputFormat::Json => println!("{}", examples_json()),
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples_json_parses_with_four_examples() {
+        let json: serde_json::Value = serde_json::from_str(&examples_json()).unwrap();
+        assert_eq!(json["topic"], "examples");
+        let items = json["entries"]
+            .as_array()
+            .expect("examples should have an entries array");
+        assert!(items.len() >= 4);
+        for item in items {
+            assert_eq!(item["symbol"], item["name"]);
+            assert!(item["source"].as_str().unwrap().starts_with("(score"));
+        }
+    }
+
+    #[test]
+    fn test_examples_compile() {
+        for example in examples() {
+            if let Err(e) = fermata::lang::compile(example.source) {
+                panic!("example '{}' failed to compile: {}", example.name, e);
+            }
+        }
+    }
 }
@@ -0,0 +1,156 @@
+//! Triads and seventh chords.
+//!
+//! Chord tones are stacked as intervals above the root, so they are always
+//! spelled in thirds: a B-flat major triad is B-flat, D, F.
+
+use crate::ir::pitch::Pitch;
+
+use super::interval::Interval;
+
+/// The quality of a three-note chord.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TriadQuality {
+    /// Major third and perfect fifth
+    Major,
+    /// Minor third and perfect fifth
+    Minor,
+    /// Minor third and diminished fifth
+    Diminished,
+    /// Major third and augmented fifth
+    Augmented,
+}
+
+/// The quality of a four-note seventh chord.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SeventhQuality {
+    /// Major triad with a minor seventh
+    Dominant,
+    /// Major triad with a major seventh
+    Major,
+    /// Minor triad with a minor seventh
+    Minor,
+    /// Diminished triad with a minor seventh
+    HalfDiminished,
+    /// Diminished triad with a diminished seventh
+    Diminished,
+}
+
+/// A chord in root position, lowest note first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chord {
+    pitches: Vec<Pitch>,
+}
+
+impl Chord {
+    /// A triad on `root`, or `None` if a chord tone is above octave 9.
+    pub fn triad(root: Pitch, quality: TriadQuality) -> Option<Self> {
+        let (third, fifth) = match quality {
+            TriadQuality::Major => (Interval::MAJOR_THIRD, Interval::PERFECT_FIFTH),
+            TriadQuality::Minor => (Interval::MINOR_THIRD, Interval::PERFECT_FIFTH),
+            TriadQuality::Diminished => (Interval::MINOR_THIRD, Interval::DIMINISHED_FIFTH),
+            TriadQuality::Augmented => (Interval::MAJOR_THIRD, Interval::AUGMENTED_FIFTH),
+        };
+        Self::stack(root, &[third, fifth])
+    }
+
+    /// A seventh chord on `root`, or `None` if a chord tone is above
+    /// octave 9.
+    pub fn seventh(root: Pitch, quality: SeventhQuality) -> Option<Self> {
+        let intervals = match quality {
+            SeventhQuality::Dominant => [
+                Interval::MAJOR_THIRD,
+                Interval::PERFECT_FIFTH,
+                Interval::MINOR_SEVENTH,
+            ],
+            SeventhQuality::Major => [
+                Interval::MAJOR_THIRD,
+                Interval::PERFECT_FIFTH,
+                Interval::MAJOR_SEVENTH,
+            ],
+            SeventhQuality::Minor => [
+                Interval::MINOR_THIRD,
+                Interval::PERFECT_FIFTH,
+                Interval::MINOR_SEVENTH,
+            ],
+            SeventhQuality::HalfDiminished => [
+                Interval::MINOR_THIRD,
+                Interval::DIMINISHED_FIFTH,
+                Interval::MINOR_SEVENTH,
+            ],
+            SeventhQuality::Diminished => [
+                Interval::MINOR_THIRD,
+                Interval::DIMINISHED_FIFTH,
+                Interval::DIMINISHED_SEVENTH,
+            ],
+        };
+        Self::stack(root, &intervals)
+    }
+
+    fn stack(root: Pitch, intervals: &[Interval]) -> Option<Self> {
+        let mut pitches = Vec::with_capacity(intervals.len() + 1);
+        for &interval in intervals {
+            pitches.push(root.add_interval(interval)?);
+        }
+        pitches.insert(0, root);
+        Some(Self { pitches })
+    }
+
+    /// The chord tones, lowest first.
+    pub fn pitches(&self) -> &[Pitch] {
+        &self.pitches
+    }
+
+    /// Consume the chord and return its tones, lowest first.
+    pub fn into_pitches(self) -> Vec<Pitch> {
+        self.pitches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::pitch::Step;
+
+    fn pitch(step: Step, alter: Option<f64>, octave: u8) -> Pitch {
+        Pitch {
+            step,
+            alter,
+            octave,
+        }
+    }
+
+    #[test]
+    fn test_chord_triad_d_minor() {
+        let chord = Chord::triad(pitch(Step::D, None, 4), TriadQuality::Minor).unwrap();
+        assert_eq!(
+            chord.pitches(),
+            [
+                pitch(Step::D, None, 4),
+                pitch(Step::F, None, 4),
+                pitch(Step::A, None, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chord_seventh_spelled_in_thirds() {
+        let chord = Chord::seventh(pitch(Step::B, None, 3), SeventhQuality::Diminished).unwrap();
+        assert_eq!(
+            chord.into_pitches(),
+            vec![
+                pitch(Step::B, None, 3),
+                pitch(Step::D, None, 4),
+                pitch(Step::F, None, 4),
+                pitch(Step::A, Some(-1.0), 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chord_above_octave_nine_is_none() {
+        assert_eq!(
+            Chord::triad(pitch(Step::A, None, 9), TriadQuality::Major),
+            None
+        );
+    }
+}
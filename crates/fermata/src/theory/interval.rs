@@ -0,0 +1,534 @@
+//! Diatonic intervals and pitch arithmetic.
+//!
+//! An [`Interval`] pairs a diatonic number (1 = unison, 3 = third,
+//! 10 = compound third) with a [`IntervalQuality`]. Adding an interval to
+//! a [`Pitch`] moves the step by the diatonic number and then chooses the
+//! alteration that produces the right number of semitones, so spelling is
+//! always preserved: C + major third is E, never F-flat.
+
+use crate::ir::pitch::{Pitch, Step};
+
+/// Semitones above C for each natural step, indexed by diatonic position.
+pub(crate) const NATURAL_SEMITONES: [i32; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+/// The quality of an interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntervalQuality {
+    /// Doubly diminished
+    DoublyDiminished,
+    /// Diminished
+    Diminished,
+    /// Minor (seconds, thirds, sixths, sevenths)
+    Minor,
+    /// Perfect (unisons, fourths, fifths, octaves)
+    Perfect,
+    /// Major (seconds, thirds, sixths, sevenths)
+    Major,
+    /// Augmented
+    Augmented,
+    /// Doubly augmented
+    DoublyAugmented,
+}
+
+/// A diatonic interval such as a major third or a perfect twelfth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval {
+    quality: IntervalQuality,
+    number: u8,
+}
+
+impl Interval {
+    /// Perfect unison
+    pub const UNISON: Self = Self::new_unchecked(IntervalQuality::Perfect, 1);
+    /// Minor second
+    pub const MINOR_SECOND: Self = Self::new_unchecked(IntervalQuality::Minor, 2);
+    /// Major second
+    pub const MAJOR_SECOND: Self = Self::new_unchecked(IntervalQuality::Major, 2);
+    /// Minor third
+    pub const MINOR_THIRD: Self = Self::new_unchecked(IntervalQuality::Minor, 3);
+    /// Major third
+    pub const MAJOR_THIRD: Self = Self::new_unchecked(IntervalQuality::Major, 3);
+    /// Perfect fourth
+    pub const PERFECT_FOURTH: Self = Self::new_unchecked(IntervalQuality::Perfect, 4);
+    /// Augmented fourth (tritone)
+    pub const AUGMENTED_FOURTH: Self = Self::new_unchecked(IntervalQuality::Augmented, 4);
+    /// Diminished fifth (tritone)
+    pub const DIMINISHED_FIFTH: Self = Self::new_unchecked(IntervalQuality::Diminished, 5);
+    /// Perfect fifth
+    pub const PERFECT_FIFTH: Self = Self::new_unchecked(IntervalQuality::Perfect, 5);
+    /// Augmented fifth
+    pub const AUGMENTED_FIFTH: Self = Self::new_unchecked(IntervalQuality::Augmented, 5);
+    /// Minor sixth
+    pub const MINOR_SIXTH: Self = Self::new_unchecked(IntervalQuality::Minor, 6);
+    /// Major sixth
+    pub const MAJOR_SIXTH: Self = Self::new_unchecked(IntervalQuality::Major, 6);
+    /// Diminished seventh
+    pub const DIMINISHED_SEVENTH: Self = Self::new_unchecked(IntervalQuality::Diminished, 7);
+    /// Minor seventh
+    pub const MINOR_SEVENTH: Self = Self::new_unchecked(IntervalQuality::Minor, 7);
+    /// Major seventh
+    pub const MAJOR_SEVENTH: Self = Self::new_unchecked(IntervalQuality::Major, 7);
+    /// Perfect octave
+    pub const OCTAVE: Self = Self::new_unchecked(IntervalQuality::Perfect, 8);
+
+    const fn new_unchecked(quality: IntervalQuality, number: u8) -> Self {
+        Self { quality, number }
+    }
+
+    /// Create an interval from a quality and diatonic number.
+    ///
+    /// Returns `None` if the number is zero or the quality does not apply
+    /// to it (e.g. a "major fifth" or a "perfect third").
+    pub fn new(quality: IntervalQuality, number: u8) -> Option<Self> {
+        if number == 0 {
+            return None;
+        }
+        let perfect_type = is_perfect_type(number);
+        let valid = match quality {
+            IntervalQuality::Perfect => perfect_type,
+            IntervalQuality::Minor | IntervalQuality::Major => !perfect_type,
+            _ => true,
+        };
+        valid.then_some(Self { quality, number })
+    }
+
+    /// Parse an interval name such as `M3`, `m6`, `P5`, `A4`, `d7`, or
+    /// `M10`.
+    ///
+    /// The quality comes first: `P` perfect, `M` major, `m` minor, `A`
+    /// augmented, `d` diminished, `AA` and `dd` for the doubled forms.
+    /// Perfect and augmented may also be written in lowercase. Returns
+    /// `None` for anything else, including a quality that does not apply to
+    /// the number (`M5`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        let split = name.find(|c: char| c.is_ascii_digit())?;
+        let (quality, number) = name.split_at(split);
+        let quality = match quality {
+            "P" | "p" => IntervalQuality::Perfect,
+            "M" => IntervalQuality::Major,
+            "m" => IntervalQuality::Minor,
+            "A" | "a" => IntervalQuality::Augmented,
+            "d" => IntervalQuality::Diminished,
+            "AA" | "aa" => IntervalQuality::DoublyAugmented,
+            "dd" => IntervalQuality::DoublyDiminished,
+            _ => return None,
+        };
+        Self::new(quality, number.parse().ok()?)
+    }
+
+    /// The interval's quality.
+    pub fn quality(&self) -> IntervalQuality {
+        self.quality
+    }
+
+    /// The diatonic number (1 = unison, 8 = octave, 10 = compound third).
+    pub fn number(&self) -> u8 {
+        self.number
+    }
+
+    /// Whether the interval spans more than an octave.
+    pub fn is_compound(&self) -> bool {
+        self.number > 8
+    }
+
+    /// The interval reduced to within an octave (a tenth becomes a third).
+    ///
+    /// Octaves and unisons are left as they are.
+    pub fn simple(&self) -> Self {
+        if self.number <= 8 {
+            *self
+        } else {
+            Self {
+                quality: self.quality,
+                number: (self.number - 2) % 7 + 2,
+            }
+        }
+    }
+
+    /// The number of semitones the interval spans.
+    pub fn semitones(&self) -> i32 {
+        let steps = i32::from(self.number) - 1;
+        let base = (steps / 7) * 12 + NATURAL_SEMITONES[(steps % 7) as usize];
+        base + quality_offset(self.quality, is_perfect_type(self.number))
+    }
+}
+
+/// Unisons, fourths, fifths and their compounds take perfect quality.
+fn is_perfect_type(number: u8) -> bool {
+    matches!((number - 1) % 7, 0 | 3 | 4)
+}
+
+/// Semitone adjustment from the perfect/major size for a quality.
+fn quality_offset(quality: IntervalQuality, perfect_type: bool) -> i32 {
+    match (quality, perfect_type) {
+        (IntervalQuality::DoublyDiminished, true) => -2,
+        (IntervalQuality::DoublyDiminished, false) => -3,
+        (IntervalQuality::Diminished, true) => -1,
+        (IntervalQuality::Diminished, false) => -2,
+        (IntervalQuality::Minor, _) => -1,
+        (IntervalQuality::Perfect, _) | (IntervalQuality::Major, _) => 0,
+        (IntervalQuality::Augmented, _) => 1,
+        (IntervalQuality::DoublyAugmented, _) => 2,
+    }
+}
+
+/// Inverse of [`quality_offset`].
+fn quality_from_offset(offset: i32, perfect_type: bool) -> Option<IntervalQuality> {
+    let quality = match (offset, perfect_type) {
+        (-2, true) | (-3, false) => IntervalQuality::DoublyDiminished,
+        (-1, true) | (-2, false) => IntervalQuality::Diminished,
+        (-1, false) => IntervalQuality::Minor,
+        (0, true) => IntervalQuality::Perfect,
+        (0, false) => IntervalQuality::Major,
+        (1, _) => IntervalQuality::Augmented,
+        (2, _) => IntervalQuality::DoublyAugmented,
+        _ => return None,
+    };
+    Some(quality)
+}
+
+/// Diatonic position of a step above C, from 0 (C) to 6 (B).
+pub(crate) fn step_index(step: Step) -> i32 {
+    match step {
+        Step::C => 0,
+        Step::D => 1,
+        Step::E => 2,
+        Step::F => 3,
+        Step::G => 4,
+        Step::A => 5,
+        Step::B => 6,
+    }
+}
+
+/// The step at a diatonic position above C, wrapping every octave.
+pub(crate) fn step_from_index(index: i32) -> Step {
+    match index.rem_euclid(7) {
+        0 => Step::C,
+        1 => Step::D,
+        2 => Step::E,
+        3 => Step::F,
+        4 => Step::G,
+        5 => Step::A,
+        _ => Step::B,
+    }
+}
+
+impl Pitch {
+    /// Position on the staff counted in diatonic steps from C0.
+    fn diatonic_position(&self) -> i32 {
+        i32::from(self.octave) * 7 + step_index(self.step)
+    }
+
+    /// Pitch height in semitones from C0, including the alteration.
+    pub(crate) fn semitone_position(&self) -> f64 {
+        let natural =
+            i32::from(self.octave) * 12 + NATURAL_SEMITONES[step_index(self.step) as usize];
+        f64::from(natural) + self.alter.unwrap_or(0.0)
+    }
+
+    /// Return the pitch an interval above this one, spelled diatonically.
+    ///
+    /// The step moves by the interval's number and the alteration is chosen
+    /// to match its size, so B4 + minor second is C5 and A4 + major third
+    /// is C-sharp 5. Returns `None` if the result is above octave 9.
+    pub fn add_interval(&self, interval: Interval) -> Option<Pitch> {
+        self.shift(
+            i32::from(interval.number) - 1,
+            f64::from(interval.semitones()),
+        )
+    }
+
+    /// Return the pitch an interval below this one, spelled diatonically.
+    ///
+    /// The inverse of [`add_interval`](Self::add_interval): E4 minus a major
+    /// third is C4, and C5 minus a minor second is B4. Returns `None` if the
+    /// result is below octave 0.
+    pub fn subtract_interval(&self, interval: Interval) -> Option<Pitch> {
+        self.shift(
+            1 - i32::from(interval.number),
+            -f64::from(interval.semitones()),
+        )
+    }
+
+    /// Move by `steps` diatonic steps, choosing the alteration that makes
+    /// the pitch `semitones` higher, or `None` if it leaves octaves 0-9.
+    pub(crate) fn shift(&self, steps: i32, semitones: f64) -> Option<Pitch> {
+        let position = self.diatonic_position() + steps;
+        let step = step_from_index(position);
+        let octave = position.div_euclid(7);
+
+        let natural = octave * 12 + NATURAL_SEMITONES[step_index(step) as usize];
+        let target = self.semitone_position() + semitones;
+        let alter = target - f64::from(natural);
+
+        Some(Pitch {
+            step,
+            alter: (alter != 0.0).then_some(alter),
+            octave: u8::try_from(octave).ok().filter(|&o| o <= 9)?,
+        })
+    }
+
+    /// The interval between this pitch and `other`, measured from the lower
+    /// of the two.
+    ///
+    /// Returns `None` when the distance cannot be named with the supported
+    /// qualities (e.g. a triply augmented interval or a quarter-tone offset).
+    pub fn interval_to(&self, other: &Pitch) -> Option<Interval> {
+        let (low, high) = if (self.diatonic_position(), self.semitone_position())
+            <= (other.diatonic_position(), other.semitone_position())
+        {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        let steps = high.diatonic_position() - low.diatonic_position();
+        let number = u8::try_from(steps + 1).ok()?;
+        let semitones = high.semitone_position() - low.semitone_position();
+        if semitones.fract() != 0.0 {
+            return None;
+        }
+
+        let perfect_type = is_perfect_type(number);
+        let expected = Interval::new_unchecked(
+            if perfect_type {
+                IntervalQuality::Perfect
+            } else {
+                IntervalQuality::Major
+            },
+            number,
+        )
+        .semitones();
+        let quality = quality_from_offset(semitones as i32 - expected, perfect_type)?;
+        Some(Interval { quality, number })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pitch(step: Step, alter: Option<f64>, octave: u8) -> Pitch {
+        Pitch {
+            step,
+            alter,
+            octave,
+        }
+    }
+
+    fn c4() -> Pitch {
+        pitch(Step::C, None, 4)
+    }
+
+    #[test]
+    fn test_interval_new_rejects_mismatched_quality() {
+        assert!(Interval::new(IntervalQuality::Major, 5).is_none());
+        assert!(Interval::new(IntervalQuality::Perfect, 3).is_none());
+        assert!(Interval::new(IntervalQuality::Perfect, 0).is_none());
+        assert_eq!(
+            Interval::new(IntervalQuality::Major, 3),
+            Some(Interval::MAJOR_THIRD)
+        );
+    }
+
+    #[test]
+    fn test_interval_semitones_within_octave() {
+        let expected = [
+            (Interval::UNISON, 0),
+            (Interval::MINOR_SECOND, 1),
+            (Interval::MAJOR_SECOND, 2),
+            (Interval::MINOR_THIRD, 3),
+            (Interval::MAJOR_THIRD, 4),
+            (Interval::PERFECT_FOURTH, 5),
+            (Interval::AUGMENTED_FOURTH, 6),
+            (Interval::DIMINISHED_FIFTH, 6),
+            (Interval::PERFECT_FIFTH, 7),
+            (Interval::MINOR_SIXTH, 8),
+            (Interval::MAJOR_SIXTH, 9),
+            (Interval::MINOR_SEVENTH, 10),
+            (Interval::MAJOR_SEVENTH, 11),
+            (Interval::OCTAVE, 12),
+        ];
+        for (interval, semitones) in expected {
+            assert_eq!(interval.semitones(), semitones, "{:?}", interval);
+        }
+    }
+
+    #[test]
+    fn test_add_interval_all_qualities_from_c4() {
+        let expected = [
+            (Interval::UNISON, Step::C, None, 4),
+            (Interval::MINOR_SECOND, Step::D, Some(-1.0), 4),
+            (Interval::MAJOR_SECOND, Step::D, None, 4),
+            (Interval::MINOR_THIRD, Step::E, Some(-1.0), 4),
+            (Interval::MAJOR_THIRD, Step::E, None, 4),
+            (Interval::PERFECT_FOURTH, Step::F, None, 4),
+            (Interval::AUGMENTED_FOURTH, Step::F, Some(1.0), 4),
+            (Interval::DIMINISHED_FIFTH, Step::G, Some(-1.0), 4),
+            (Interval::PERFECT_FIFTH, Step::G, None, 4),
+            (Interval::MINOR_SIXTH, Step::A, Some(-1.0), 4),
+            (Interval::MAJOR_SIXTH, Step::A, None, 4),
+            (Interval::MINOR_SEVENTH, Step::B, Some(-1.0), 4),
+            (Interval::MAJOR_SEVENTH, Step::B, None, 4),
+            (Interval::OCTAVE, Step::C, None, 5),
+        ];
+        for (interval, step, alter, octave) in expected {
+            let result = c4().add_interval(interval).unwrap();
+            assert_eq!(result, pitch(step, alter, octave), "{:?}", interval);
+            assert_eq!(c4().interval_to(&result), Some(interval));
+        }
+    }
+
+    #[test]
+    fn test_add_interval_diminished_and_augmented() {
+        let dim_seventh = Interval::new(IntervalQuality::Diminished, 7).unwrap();
+        assert_eq!(
+            pitch(Step::C, Some(1.0), 4)
+                .add_interval(dim_seventh)
+                .unwrap(),
+            pitch(Step::B, Some(-1.0), 4)
+        );
+
+        let aug_second = Interval::new(IntervalQuality::Augmented, 2).unwrap();
+        assert_eq!(
+            c4().add_interval(aug_second).unwrap(),
+            pitch(Step::D, Some(1.0), 4)
+        );
+        assert_eq!(aug_second.semitones(), 3);
+    }
+
+    #[test]
+    fn test_add_interval_respects_spelling() {
+        // C + major third is E, not F-flat
+        assert_eq!(
+            c4().add_interval(Interval::MAJOR_THIRD).unwrap(),
+            pitch(Step::E, None, 4)
+        );
+        // A + major third is C-sharp
+        assert_eq!(
+            pitch(Step::A, None, 4)
+                .add_interval(Interval::MAJOR_THIRD)
+                .unwrap(),
+            pitch(Step::C, Some(1.0), 5)
+        );
+        // E-flat + perfect fifth is B-flat
+        assert_eq!(
+            pitch(Step::E, Some(-1.0), 4)
+                .add_interval(Interval::PERFECT_FIFTH)
+                .unwrap(),
+            pitch(Step::B, Some(-1.0), 4)
+        );
+    }
+
+    #[test]
+    fn test_add_interval_octave_wrap() {
+        assert_eq!(
+            pitch(Step::B, None, 4)
+                .add_interval(Interval::MINOR_SECOND)
+                .unwrap(),
+            pitch(Step::C, None, 5)
+        );
+        assert_eq!(
+            pitch(Step::G, None, 3)
+                .add_interval(Interval::PERFECT_FOURTH)
+                .unwrap(),
+            pitch(Step::C, None, 4)
+        );
+    }
+
+    #[test]
+    fn test_add_interval_out_of_range_is_none() {
+        assert_eq!(
+            pitch(Step::B, None, 9).add_interval(Interval::MINOR_SECOND),
+            None
+        );
+        assert_eq!(
+            pitch(Step::C, None, 0).subtract_interval(Interval::MINOR_SECOND),
+            None
+        );
+        assert_eq!(
+            pitch(Step::C, None, 0).subtract_interval(Interval::UNISON),
+            Some(pitch(Step::C, None, 0))
+        );
+    }
+
+    #[test]
+    fn test_subtract_interval_inverts_add_interval() {
+        let e4 = pitch(Step::E, None, 4);
+        assert_eq!(e4.subtract_interval(Interval::MAJOR_THIRD).unwrap(), c4());
+        assert_eq!(
+            c4().subtract_interval(Interval::MINOR_SECOND).unwrap(),
+            pitch(Step::B, None, 3)
+        );
+        assert_eq!(
+            c4().subtract_interval(Interval::MAJOR_THIRD).unwrap(),
+            pitch(Step::A, Some(-1.0), 3)
+        );
+    }
+
+    #[test]
+    fn test_interval_from_name() {
+        assert_eq!(Interval::from_name("M3"), Some(Interval::MAJOR_THIRD));
+        assert_eq!(Interval::from_name("m3"), Some(Interval::MINOR_THIRD));
+        assert_eq!(Interval::from_name("p5"), Some(Interval::PERFECT_FIFTH));
+        assert_eq!(Interval::from_name("A4"), Some(Interval::AUGMENTED_FOURTH));
+        assert_eq!(
+            Interval::from_name("M10"),
+            Interval::new(IntervalQuality::Major, 10)
+        );
+        assert_eq!(Interval::from_name("M5"), None);
+        assert_eq!(Interval::from_name("x3"), None);
+        assert_eq!(Interval::from_name("M"), None);
+    }
+
+    #[test]
+    fn test_compound_intervals() {
+        let major_tenth = Interval::new(IntervalQuality::Major, 10).unwrap();
+        assert!(major_tenth.is_compound());
+        assert_eq!(major_tenth.simple(), Interval::MAJOR_THIRD);
+        assert_eq!(major_tenth.semitones(), 16);
+        assert_eq!(
+            c4().add_interval(major_tenth).unwrap(),
+            pitch(Step::E, None, 5)
+        );
+        assert_eq!(
+            c4().interval_to(&pitch(Step::E, None, 5)),
+            Some(major_tenth)
+        );
+
+        let perfect_twelfth = Interval::new(IntervalQuality::Perfect, 12).unwrap();
+        assert_eq!(perfect_twelfth.simple(), Interval::PERFECT_FIFTH);
+        assert_eq!(
+            c4().add_interval(perfect_twelfth).unwrap(),
+            pitch(Step::G, None, 5)
+        );
+
+        let double_octave = Interval::new(IntervalQuality::Perfect, 15).unwrap();
+        assert_eq!(double_octave.simple(), Interval::OCTAVE);
+        assert_eq!(double_octave.semitones(), 24);
+    }
+
+    #[test]
+    fn test_interval_to_is_order_independent() {
+        let e4 = pitch(Step::E, None, 4);
+        assert_eq!(e4.interval_to(&c4()), Some(Interval::MAJOR_THIRD));
+    }
+
+    #[test]
+    fn test_interval_to_distinguishes_enharmonics() {
+        assert_eq!(
+            c4().interval_to(&pitch(Step::F, Some(1.0), 4)),
+            Some(Interval::AUGMENTED_FOURTH)
+        );
+        assert_eq!(
+            c4().interval_to(&pitch(Step::G, Some(-1.0), 4)),
+            Some(Interval::DIMINISHED_FIFTH)
+        );
+    }
+
+    #[test]
+    fn test_interval_to_unnameable_returns_none() {
+        assert_eq!(c4().interval_to(&pitch(Step::D, Some(3.0), 4)), None);
+        assert_eq!(c4().interval_to(&pitch(Step::D, Some(0.5), 4)), None);
+    }
+}
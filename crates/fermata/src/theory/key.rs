@@ -0,0 +1,121 @@
+//! Key signatures for any root and mode.
+//!
+//! A key signature is counted in fifths: positive for sharps, negative for
+//! flats. Every mode borrows the signature of the major scale it is a
+//! rotation of, so D Dorian and E Phrygian share C major's empty
+//! signature.
+
+use crate::ir::attributes::Mode;
+use crate::ir::pitch::Step;
+
+/// The most sharps or flats a key signature can hold.
+pub const MAX_FIFTHS: i8 = 7;
+
+/// The key signature, in fifths, for `root` raised or lowered by `alter`
+/// semitones, in `mode`.
+///
+/// A mode's signature is the root's major signature moved a fixed number
+/// of fifths: Lydian adds a sharp (F Lydian has C major's empty signature),
+/// Mixolydian adds a flat, Dorian two, Aeolian and minor three, Phrygian
+/// four, and Locrian five. [`Mode::None`] is treated as major.
+///
+/// Each semitone of `alter` moves the root seven fifths. Results beyond
+/// seven sharps or flats are respelled as the enharmonic key twelve fifths
+/// away, so G-sharp major (eight sharps) becomes A-flat major (four
+/// flats) and A-sharp Dorian becomes B-flat Dorian.
+///
+/// # Examples
+///
+/// ```
+/// use fermata::ir::Step;
+/// use fermata::ir::attributes::Mode;
+/// use fermata::theory::fifths_for;
+///
+/// assert_eq!(fifths_for(Step::D, 0, Mode::Major), 2);
+/// assert_eq!(fifths_for(Step::D, 0, Mode::Dorian), 0);
+/// assert_eq!(fifths_for(Step::B, -1, Mode::Major), -2);
+/// ```
+pub fn fifths_for(root: Step, alter: i8, mode: Mode) -> i8 {
+    let natural: i32 = match root {
+        Step::F => -1,
+        Step::C => 0,
+        Step::G => 1,
+        Step::D => 2,
+        Step::A => 3,
+        Step::E => 4,
+        Step::B => 5,
+    };
+    let mode_offset = match mode {
+        Mode::Lydian => 1,
+        Mode::Major | Mode::Ionian | Mode::None => 0,
+        Mode::Mixolydian => -1,
+        Mode::Dorian => -2,
+        Mode::Minor | Mode::Aeolian => -3,
+        Mode::Phrygian => -4,
+        Mode::Locrian => -5,
+    };
+
+    let mut fifths = natural + 7 * i32::from(alter) + mode_offset;
+    while fifths > i32::from(MAX_FIFTHS) {
+        fifths -= 12;
+    }
+    while fifths < -i32::from(MAX_FIFTHS) {
+        fifths += 12;
+    }
+    fifths as i8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODES: [Mode; 9] = [
+        Mode::Major,
+        Mode::Ionian,
+        Mode::Dorian,
+        Mode::Phrygian,
+        Mode::Lydian,
+        Mode::Mixolydian,
+        Mode::Aeolian,
+        Mode::Minor,
+        Mode::Locrian,
+    ];
+
+    #[test]
+    fn test_fifths_for_white_note_modes_have_no_signature() {
+        let roots = [
+            (Step::C, Mode::Ionian),
+            (Step::D, Mode::Dorian),
+            (Step::E, Mode::Phrygian),
+            (Step::F, Mode::Lydian),
+            (Step::G, Mode::Mixolydian),
+            (Step::A, Mode::Aeolian),
+            (Step::B, Mode::Locrian),
+        ];
+        for (root, mode) in roots {
+            assert_eq!(fifths_for(root, 0, mode), 0, "{:?} {:?}", root, mode);
+        }
+    }
+
+    #[test]
+    fn test_fifths_for_every_mode_on_d_and_b_flat() {
+        let on_d: Vec<i8> = MODES.iter().map(|&m| fifths_for(Step::D, 0, m)).collect();
+        assert_eq!(on_d, vec![2, 2, 0, -2, 3, 1, -1, -1, -3]);
+
+        let on_b_flat: Vec<i8> = MODES.iter().map(|&m| fifths_for(Step::B, -1, m)).collect();
+        assert_eq!(on_b_flat, vec![-2, -2, -4, -6, -1, -3, -5, -5, -7]);
+    }
+
+    #[test]
+    fn test_fifths_for_out_of_range_respells_enharmonically() {
+        // G# major (8 sharps) is written as Ab major
+        assert_eq!(fifths_for(Step::G, 1, Mode::Major), -4);
+        // Fb major (8 flats) is written as E major
+        assert_eq!(fifths_for(Step::F, -1, Mode::Major), 4);
+        // C# major and Cb major stay in range
+        assert_eq!(fifths_for(Step::C, 1, Mode::Major), 7);
+        assert_eq!(fifths_for(Step::C, -1, Mode::Major), -7);
+        // Double sharps are respelled too
+        assert_eq!(fifths_for(Step::B, 2, Mode::Major), 7);
+    }
+}
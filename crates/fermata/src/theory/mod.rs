@@ -0,0 +1,30 @@
+//! Music theory helpers built on the IR types.
+//!
+//! # Module Organization
+//!
+//! - [`interval`] - Diatonic intervals and pitch arithmetic
+//! - [`key`] - Key signatures in any mode
+//! - [`scale`] - Scales in any mode
+//! - [`chord`] - Triads and seventh chords
+//!
+//! # Example
+//!
+//! ```
+//! use fermata::ir::{Pitch, Step};
+//! use fermata::theory::Interval;
+//!
+//! let c4 = Pitch { step: Step::C, alter: None, octave: 4 };
+//! let e4 = c4.add_interval(Interval::MAJOR_THIRD).unwrap();
+//! assert_eq!(e4.step, Step::E);
+//! assert_eq!(c4.interval_to(&e4), Some(Interval::MAJOR_THIRD));
+//! ```
+
+pub mod chord;
+pub mod interval;
+pub mod key;
+pub mod scale;
+
+pub use chord::{Chord, SeventhQuality, TriadQuality};
+pub use interval::{Interval, IntervalQuality};
+pub use key::{MAX_FIFTHS, fifths_for};
+pub use scale::Scale;
@@ -0,0 +1,174 @@
+//! Scales built from a root and a mode.
+//!
+//! Each degree is reached by adding a diatonic interval to the root, so
+//! every scale uses each letter name once per octave: F major has B-flat,
+//! not A-sharp.
+
+use crate::ir::attributes::Mode;
+use crate::ir::pitch::Pitch;
+
+use super::interval::Interval;
+
+/// A seven-note scale on a given root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scale {
+    root: Pitch,
+    mode: Mode,
+}
+
+impl Scale {
+    /// Create a scale. [`Mode::None`] is treated as major.
+    pub fn new(root: Pitch, mode: Mode) -> Self {
+        Self { root, mode }
+    }
+
+    /// The scale's starting pitch.
+    pub fn root(&self) -> &Pitch {
+        &self.root
+    }
+
+    /// The scale's mode.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Intervals above the root of the seven degrees, starting with the unison.
+    pub fn intervals(&self) -> [Interval; 7] {
+        use Interval as I;
+        match self.mode {
+            Mode::Major | Mode::Ionian | Mode::None => [
+                I::UNISON,
+                I::MAJOR_SECOND,
+                I::MAJOR_THIRD,
+                I::PERFECT_FOURTH,
+                I::PERFECT_FIFTH,
+                I::MAJOR_SIXTH,
+                I::MAJOR_SEVENTH,
+            ],
+            Mode::Dorian => [
+                I::UNISON,
+                I::MAJOR_SECOND,
+                I::MINOR_THIRD,
+                I::PERFECT_FOURTH,
+                I::PERFECT_FIFTH,
+                I::MAJOR_SIXTH,
+                I::MINOR_SEVENTH,
+            ],
+            Mode::Phrygian => [
+                I::UNISON,
+                I::MINOR_SECOND,
+                I::MINOR_THIRD,
+                I::PERFECT_FOURTH,
+                I::PERFECT_FIFTH,
+                I::MINOR_SIXTH,
+                I::MINOR_SEVENTH,
+            ],
+            Mode::Lydian => [
+                I::UNISON,
+                I::MAJOR_SECOND,
+                I::MAJOR_THIRD,
+                I::AUGMENTED_FOURTH,
+                I::PERFECT_FIFTH,
+                I::MAJOR_SIXTH,
+                I::MAJOR_SEVENTH,
+            ],
+            Mode::Mixolydian => [
+                I::UNISON,
+                I::MAJOR_SECOND,
+                I::MAJOR_THIRD,
+                I::PERFECT_FOURTH,
+                I::PERFECT_FIFTH,
+                I::MAJOR_SIXTH,
+                I::MINOR_SEVENTH,
+            ],
+            Mode::Minor | Mode::Aeolian => [
+                I::UNISON,
+                I::MAJOR_SECOND,
+                I::MINOR_THIRD,
+                I::PERFECT_FOURTH,
+                I::PERFECT_FIFTH,
+                I::MINOR_SIXTH,
+                I::MINOR_SEVENTH,
+            ],
+            Mode::Locrian => [
+                I::UNISON,
+                I::MINOR_SECOND,
+                I::MINOR_THIRD,
+                I::PERFECT_FOURTH,
+                I::DIMINISHED_FIFTH,
+                I::MINOR_SIXTH,
+                I::MINOR_SEVENTH,
+            ],
+        }
+    }
+
+    /// The ascending pitches of the scale over the given number of octaves,
+    /// ending on the root at the top: one octave of C major is C D E F G A
+    /// B C. Returns `None` if the scale would climb above octave 9.
+    pub fn pitches(&self, octaves: u8) -> Option<Vec<Pitch>> {
+        let intervals = self.intervals();
+        let mut pitches = Vec::with_capacity(usize::from(octaves) * 7 + 1);
+        let mut tonic = self.root.clone();
+        for _ in 0..octaves {
+            for &interval in &intervals {
+                pitches.push(tonic.add_interval(interval)?);
+            }
+            tonic = tonic.add_interval(Interval::OCTAVE)?;
+        }
+        pitches.push(tonic);
+        Some(pitches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::pitch::Step;
+
+    fn names(pitches: &[Pitch]) -> Vec<(Step, Option<f64>, u8)> {
+        pitches
+            .iter()
+            .map(|p| (p.step, p.alter, p.octave))
+            .collect()
+    }
+
+    #[test]
+    fn test_scale_pitches_c_major() {
+        let c4 = Pitch {
+            step: Step::C,
+            alter: None,
+            octave: 4,
+        };
+        let scale = Scale::new(c4, Mode::Major);
+
+        assert_eq!(
+            names(&scale.pitches(1).unwrap()),
+            vec![
+                (Step::C, None, 4),
+                (Step::D, None, 4),
+                (Step::E, None, 4),
+                (Step::F, None, 4),
+                (Step::G, None, 4),
+                (Step::A, None, 4),
+                (Step::B, None, 4),
+                (Step::C, None, 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scale_pitches_spelling_and_octaves() {
+        let d4 = Pitch {
+            step: Step::D,
+            alter: None,
+            octave: 4,
+        };
+        let pitches = Scale::new(d4, Mode::Minor).pitches(2).unwrap();
+
+        assert_eq!(pitches.len(), 15);
+        // B-flat, never A-sharp
+        assert_eq!((pitches[5].step, pitches[5].alter), (Step::B, Some(-1.0)));
+        assert_eq!((pitches[12].step, pitches[12].octave), (Step::B, 5));
+        assert_eq!((pitches[14].step, pitches[14].octave), (Step::D, 6));
+    }
+}
@@ -0,0 +1,1420 @@
+//! Whole-score and part rewrites.
+//!
+//! These passes edit a compiled or imported [`ScorePartwise`], or one of
+//! its parts, in place, changing how the music is written without changing
+//! how it sounds.
+
+use crate::ir::attributes::{
+    Attributes, BarStyle, Barline, Key, KeyContent, MeasureStyle, MeasureStyleContent, TimeContent,
+    Transpose,
+};
+use crate::ir::common::{
+    AccidentalValue, Position, RightLeftMiddle, StartStop, StartStopContinue, YesNo,
+};
+use crate::ir::direction::{Direction, DirectionTypeContent};
+use crate::ir::duration::{Dot, NoteType, NoteTypeValue};
+use crate::ir::measure::{Measure, MusicDataElement};
+use crate::ir::notation::{NotationContent, Notations, Tied};
+use crate::ir::note::{FullNote, Note, NoteContent, PitchRestUnpitched, Rest, Tie};
+use crate::ir::part::Part;
+use crate::ir::pitch::Pitch;
+use crate::ir::score::{Defaults, ScorePartwise};
+use crate::theory::MAX_FIFTHS;
+use crate::theory::interval::{NATURAL_SEMITONES, step_from_index, step_index};
+use crate::validate::measure_length;
+
+/// How [`respell`] chooses between enharmonic spellings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpellingPolicy {
+    /// Spell black keys as sharps and white keys as naturals (A#, not Bb)
+    PreferSharps,
+    /// Spell black keys as flats and white keys as naturals (Bb, not A#)
+    PreferFlats,
+    /// Use the spelling of the active key signature where the pitch belongs
+    /// to the key; otherwise sharps in sharp keys and flats in flat keys
+    KeyContext,
+}
+
+/// Spelling of each pitch class (0 = C) as a step index and alteration.
+const SHARP_SPELLINGS: [(usize, i32); 12] = [
+    (0, 0),
+    (0, 1),
+    (1, 0),
+    (1, 1),
+    (2, 0),
+    (3, 0),
+    (3, 1),
+    (4, 0),
+    (4, 1),
+    (5, 0),
+    (5, 1),
+    (6, 0),
+];
+const FLAT_SPELLINGS: [(usize, i32); 12] = [
+    (0, 0),
+    (1, -1),
+    (1, 0),
+    (2, -1),
+    (2, 0),
+    (3, 0),
+    (4, -1),
+    (4, 0),
+    (5, -1),
+    (5, 0),
+    (6, -1),
+    (6, 0),
+];
+
+/// Steps in the order sharps are added to a key signature (F C G D A E B);
+/// flats are added in the reverse order.
+const SHARP_ORDER: [usize; 7] = [3, 0, 4, 1, 5, 2, 6];
+
+/// Note types from longest to shortest, with their length as a power of two
+/// of a whole note.
+const NOTE_TYPES: [(NoteTypeValue, i32); 14] = [
+    (NoteTypeValue::Maxima, 3),
+    (NoteTypeValue::Long, 2),
+    (NoteTypeValue::Breve, 1),
+    (NoteTypeValue::Whole, 0),
+    (NoteTypeValue::Half, -1),
+    (NoteTypeValue::Quarter, -2),
+    (NoteTypeValue::Eighth, -3),
+    (NoteTypeValue::N16th, -4),
+    (NoteTypeValue::N32nd, -5),
+    (NoteTypeValue::N64th, -6),
+    (NoteTypeValue::N128th, -7),
+    (NoteTypeValue::N256th, -8),
+    (NoteTypeValue::N512th, -9),
+    (NoteTypeValue::N1024th, -10),
+];
+
+/// Rewrite every pitch in the score to the spelling chosen by `policy`.
+///
+/// The sounding pitch is unchanged: Cb4 respelled with sharps becomes B3.
+/// Displayed accidentals are updated to match the new spelling. Pitches
+/// with microtonal alterations are left alone. With
+/// [`SpellingPolicy::KeyContext`], the key comes from the first key of
+/// the most recent attributes in each part, defaulting to C major.
+pub fn respell(score: &mut ScorePartwise, policy: SpellingPolicy) {
+    for part in &mut score.parts {
+        let mut fifths: i8 = 0;
+        for measure in &mut part.measures {
+            for element in &mut measure.content {
+                match element {
+                    MusicDataElement::Attributes(attributes) => {
+                        if let Some(KeyContent::Traditional(key)) =
+                            attributes.keys.first().map(|k| &k.content)
+                        {
+                            fifths = key.fifths;
+                        }
+                    }
+                    MusicDataElement::Note(note) => respell_note(note, policy, fifths),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn respell_note(note: &mut Note, policy: SpellingPolicy, fifths: i8) {
+    let Some(pitch) = pitch_mut(note) else {
+        return;
+    };
+    let Some(respelled) = respell_pitch(pitch, policy, fifths) else {
+        return;
+    };
+    *pitch = respelled;
+    sync_accidental(note);
+}
+
+/// Rewrite concert-pitch parts as transposing parts.
+///
+/// Each part's `<transpose>` says how its written pitch sounds, e.g. a
+/// major second lower for a B-flat clarinet. This pass assumes the notes
+/// are at concert pitch and moves them, and the key signatures, by the
+/// opposite interval so the part reads correctly for the player: a
+/// concert C for a B-flat clarinet is written as D. The `<transpose>`
+/// elements are kept, so the score still sounds the same. Parts without
+/// a transposition are left alone, and a transposition change takes
+/// effect from the attributes that contain it. The score's
+/// `<concert-score/>` mark is removed, as the notes are no longer at
+/// concert pitch.
+///
+/// Keys pushed past seven sharps or flats are written as the enharmonic
+/// key, with the notes respelled to match. Every staff's key is moved, not
+/// just the first.
+pub fn to_transposing_parts(score: &mut ScorePartwise) {
+    if let Some(defaults) = &mut score.defaults {
+        defaults.concert_score = false;
+    }
+    if score.defaults == Some(Defaults::default()) {
+        score.defaults = None;
+    }
+
+    for part in &mut score.parts {
+        let mut transpose: Option<Transpose> = None;
+        // The written key, if it was respelled enharmonically
+        let mut respelled_key: Option<i8> = None;
+        for measure in &mut part.measures {
+            for element in &mut measure.content {
+                match element {
+                    MusicDataElement::Attributes(attributes) => {
+                        if let Some(t) = attributes.transpose.first() {
+                            transpose = Some(t.clone());
+                        }
+                        let Some(t) = &transpose else {
+                            continue;
+                        };
+                        let mut keys = attributes.keys.iter_mut();
+                        if let Some(key) = keys.next() {
+                            respelled_key = transpose_key(key, t);
+                        }
+                        // Per-staff keys move with the first
+                        for key in keys {
+                            transpose_key(key, t);
+                        }
+                    }
+                    MusicDataElement::Note(note) => {
+                        let Some(t) = &transpose else {
+                            continue;
+                        };
+                        let Some(pitch) = pitch_mut(note) else {
+                            continue;
+                        };
+                        let (steps, semitones) = written_interval(t);
+                        let Some(mut written) = pitch.shift(steps, f64::from(semitones)) else {
+                            continue;
+                        };
+                        if let Some(fifths) = respelled_key
+                            && let Some(respelled) =
+                                respell_pitch(&written, SpellingPolicy::KeyContext, fifths)
+                        {
+                            written = respelled;
+                        }
+                        *pitch = written;
+                        sync_accidental(note);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Collapse runs of empty measures into multi-measure rests.
+///
+/// Meant for extracted instrumental parts. Each run of two or more
+/// consecutive measures holding nothing but rests is replaced by its first
+/// measure, which gets a `<multiple-rest>` measure style counting the whole
+/// run and the closing barline of the run's last measure. The part then no
+/// longer lines up measure for measure with the rest of the score.
+///
+/// A run starts afresh at a measure with a rehearsal mark, a tempo, or a
+/// key or time signature, all of which the player must still see, and ends
+/// after a double barline, a repeat, or a volta ending. A measure with any
+/// other direction, such as a dynamic, is not empty.
+pub fn collapse_rests(part: &mut Part) {
+    let mut collapsed = Vec::with_capacity(part.measures.len());
+    let mut run: Vec<Measure> = Vec::new();
+    for measure in std::mem::take(&mut part.measures) {
+        let empty = is_rest_only(&measure);
+        let extends_run = empty
+            && !starts_section(&measure)
+            && run.last().is_some_and(|last| !ends_section(last));
+        if !extends_run {
+            collapsed.extend(collapse_run(std::mem::take(&mut run)));
+        }
+        if empty {
+            run.push(measure);
+        } else {
+            collapsed.push(measure);
+        }
+    }
+    collapsed.extend(collapse_run(run));
+    part.measures = collapsed;
+}
+
+/// Turn a run of empty measures into the single measure that stands for it.
+fn collapse_run(mut run: Vec<Measure>) -> Option<Measure> {
+    let count = run.len() as u32;
+    let last = if count > 1 { run.pop() } else { None };
+    let mut first = run.into_iter().next()?;
+    let Some(last) = last else {
+        return Some(first);
+    };
+
+    // The run ends with the last measure's closing barline
+    first.content.retain(|element| !is_right_barline(element));
+    first
+        .content
+        .extend(last.content.into_iter().filter(is_right_barline));
+
+    let style = MeasureStyle {
+        number: None,
+        content: MeasureStyleContent::MultipleRest {
+            count,
+            use_symbols: None,
+        },
+    };
+    let attributes = first.content.iter_mut().find_map(|element| match element {
+        MusicDataElement::Attributes(attributes) => Some(attributes),
+        _ => None,
+    });
+    match attributes {
+        Some(attributes) => attributes.measure_styles.push(style),
+        None => {
+            let attributes = Attributes {
+                measure_styles: vec![style],
+                ..Default::default()
+            };
+            first
+                .content
+                .insert(0, MusicDataElement::Attributes(Box::new(attributes)));
+        }
+    }
+    Some(first)
+}
+
+/// Whether a measure holds only rests, and no directions other than the
+/// rehearsal marks and tempos that [`starts_section`] looks for.
+fn is_rest_only(measure: &Measure) -> bool {
+    measure.content.iter().all(|element| match element {
+        MusicDataElement::Note(note) => matches!(
+            &note.content,
+            NoteContent::Regular { full_note, .. } if matches!(full_note.content, PitchRestUnpitched::Rest(_))
+        ),
+        MusicDataElement::Direction(direction) => is_section_direction(direction),
+        _ => true,
+    })
+}
+
+/// Whether a measure opens a new section: a rehearsal mark, a tempo, or a
+/// key or time signature.
+fn starts_section(measure: &Measure) -> bool {
+    measure.content.iter().any(|element| match element {
+        MusicDataElement::Direction(direction) => is_section_direction(direction),
+        MusicDataElement::Attributes(attributes) => {
+            !attributes.keys.is_empty() || !attributes.times.is_empty()
+        }
+        MusicDataElement::Barline(barline) => {
+            barline.location == Some(RightLeftMiddle::Left) && is_section_barline(barline)
+        }
+        _ => false,
+    })
+}
+
+/// Whether a measure closes a section with a double barline, a repeat, or
+/// an ending.
+fn ends_section(measure: &Measure) -> bool {
+    measure.content.iter().any(|element| match element {
+        MusicDataElement::Barline(barline) => {
+            barline.location != Some(RightLeftMiddle::Left) && is_section_barline(barline)
+        }
+        _ => false,
+    })
+}
+
+/// Whether a direction is a rehearsal mark or a tempo.
+fn is_section_direction(direction: &Direction) -> bool {
+    direction.sound.as_ref().is_some_and(|s| s.tempo.is_some())
+        || direction.direction_types.iter().any(|dt| {
+            matches!(
+                dt.content,
+                DirectionTypeContent::Rehearsal(_) | DirectionTypeContent::Metronome(_)
+            )
+        })
+}
+
+/// Whether a barline is a double barline, a repeat, or an ending.
+fn is_section_barline(barline: &Barline) -> bool {
+    barline.repeat.is_some()
+        || barline.ending.is_some()
+        || matches!(
+            barline.bar_style,
+            Some(
+                BarStyle::LightLight
+                    | BarStyle::LightHeavy
+                    | BarStyle::HeavyLight
+                    | BarStyle::HeavyHeavy
+            )
+        )
+}
+
+/// Whether an element is a barline at the right end of its measure, where
+/// barlines go when no location is given.
+fn is_right_barline(element: &MusicDataElement) -> bool {
+    matches!(
+        element,
+        MusicDataElement::Barline(barline)
+            if matches!(barline.location, None | Some(RightLeftMiddle::Right))
+    )
+}
+
+/// Split measures that run past their time signature, tying notes across
+/// the new barlines.
+///
+/// Music written in phrases rather than bars can be compiled and then
+/// barred with this pass. A measure longer than its time signature is cut
+/// into as many measures as it fills, the last of which may be short, and
+/// the later measures of the part are renumbered to follow. A note or rest
+/// crossing a new barline is split into tied notes (or plain rests) that
+/// are written with ordinary note values, so a half note starting on the
+/// last beat of 4/4 becomes two tied quarters. The continuations keep
+/// nothing but their ties: articulations, lyrics, and accidentals stay on
+/// the first note.
+///
+/// Divisions and time signatures carry over from earlier measures. Pickup
+/// measures, measures with several voices (backups or forwards), and
+/// measures whose crossing notes are tuplets are left as they are.
+///
+/// The parts stay aligned: a measure is split in every part at once. A
+/// part whose measure is exactly full while another part's overflows is
+/// given rests for the overflow. If the parts cannot be lined up, because
+/// two parts overflow by different amounts or one of them cannot be split,
+/// the measure is left unsplit in every part and a warning is logged.
+pub fn auto_barline(score: &mut ScorePartwise) {
+    let parts: Vec<Vec<Measure>> = score
+        .parts
+        .iter_mut()
+        .map(|part| std::mem::take(&mut part.measures))
+        .collect();
+    let mut states = vec![BarringState::default(); parts.len()];
+    let mut barred: Vec<Vec<Measure>> = parts.iter().map(|m| Vec::with_capacity(m.len())).collect();
+    let mut parts: Vec<_> = parts.into_iter().map(Vec::into_iter).collect();
+
+    loop {
+        // The measure at this position in each part that still has one
+        let mut column = Vec::new();
+        for (part, measures) in parts.iter_mut().enumerate() {
+            if let Some(mut measure) = measures.next() {
+                let length = states[part].enter(&mut measure);
+                column.push(ColumnMeasure {
+                    part,
+                    measure,
+                    length,
+                });
+            }
+        }
+        if column.is_empty() {
+            break;
+        }
+
+        for (part, measures) in bar_column(&column) {
+            states[part].added += measures.len() as u32 - 1;
+            barred[part].extend(measures);
+        }
+    }
+
+    for (part, measures) in score.parts.iter_mut().zip(barred) {
+        part.measures = measures;
+    }
+}
+
+/// What [`auto_barline`] tracks through a part.
+#[derive(Debug, Clone, Default)]
+struct BarringState {
+    divisions: Option<u64>,
+    time: Option<TimeContent>,
+    /// Measures added so far, by which later numbers shift
+    added: u32,
+}
+
+impl BarringState {
+    /// Take in a measure's attributes and renumber it, returning its
+    /// expected length and the divisions it is written in, if known.
+    fn enter(&mut self, measure: &mut Measure) -> Option<(u64, u64)> {
+        if let Ok(number) = measure.number.parse::<u32>() {
+            measure.number = (number + self.added).to_string();
+        }
+        for element in &measure.content {
+            if let MusicDataElement::Attributes(attributes) = element {
+                if let Some(d) = attributes.divisions {
+                    self.divisions = Some(d);
+                }
+                if let Some(t) = attributes.times.first() {
+                    self.time = Some(t.content.clone());
+                }
+            }
+        }
+        self.divisions
+            .zip(self.time.as_ref())
+            .and_then(|(d, t)| Some((measure_length(t, d)?, d)))
+            .filter(|&(length, _)| length > 0 && measure.implicit != Some(YesNo::Yes))
+    }
+}
+
+/// A part's measure at one position in the score.
+struct ColumnMeasure {
+    /// Index of the part
+    part: usize,
+    measure: Measure,
+    /// Expected length and divisions, when known and the measure is not a
+    /// pickup
+    length: Option<(u64, u64)>,
+}
+
+/// Split the measures at one position across the parts so that every part
+/// gets the same number of measures, returning them by part index.
+fn bar_column(column: &[ColumnMeasure]) -> Vec<(usize, Vec<Measure>)> {
+    let splits: Vec<Option<Vec<Measure>>> = column
+        .iter()
+        .map(|c| {
+            c.length
+                .and_then(|(length, divisions)| split_measure(&c.measure, length, divisions))
+        })
+        .collect();
+
+    // The part that overflows the most sets where the barlines fall
+    let widest = splits
+        .iter()
+        .enumerate()
+        .filter_map(|(i, split)| split.as_ref().map(|s| (i, s.len())))
+        .max_by_key(|&(i, count)| (count, std::cmp::Reverse(i)));
+    let unsplit = || {
+        column
+            .iter()
+            .map(|c| (c.part, vec![c.measure.clone()]))
+            .collect()
+    };
+    let Some((widest, count)) = widest else {
+        return unsplit();
+    };
+    let reference = &column[widest];
+    let (length, divisions) = reference.length.expect("split measures have a length");
+    let total = measure_total(&reference.measure);
+
+    let mut barred = Vec::with_capacity(column.len());
+    for (c, split) in column.iter().zip(&splits) {
+        let measures = match split {
+            Some(split) if split.len() == count => Some(split.clone()),
+            Some(_) => None,
+            None => c.length.and_then(|part_length| {
+                pad_measure(&c.measure, part_length, (length, divisions, total), count)
+            }),
+        };
+        match measures {
+            Some(measures) => barred.push((c.part, measures)),
+            None => {
+                log::warn!(
+                    "auto_barline: parts overflow measure {} differently; leaving it unsplit",
+                    reference.measure.number
+                );
+                return unsplit();
+            }
+        }
+    }
+    barred
+}
+
+/// Follow a full measure with rest measures that line up with `count`
+/// pieces of a reference measure `total` divisions long, cut every
+/// `length` divisions of `divisions` per quarter.
+///
+/// Returns `None` if the measure is not exactly full, its measures are a
+/// different length, or the rests cannot be written in its divisions.
+fn pad_measure(
+    measure: &Measure,
+    (part_length, part_divisions): (u64, u64),
+    (length, divisions, total): (u64, u64, u64),
+    count: usize,
+) -> Option<Vec<Measure>> {
+    if measure_total(measure) != part_length || part_length * divisions != length * part_divisions {
+        return None;
+    }
+    let mut measures = vec![measure.clone()];
+    for index in 1..count {
+        let rest = (total - index as u64 * length).min(length) * part_divisions;
+        if rest % divisions != 0 {
+            return None;
+        }
+        let rest = rest / divisions;
+        let content = if rest == part_length {
+            vec![rest_note(rest, None, 0, true)]
+        } else {
+            note_values(rest, part_divisions)?
+                .into_iter()
+                .map(|(value, dots, piece)| rest_note(piece, Some(value), dots, false))
+                .collect()
+        };
+        measures.push(Measure {
+            number: piece_number(measure, index),
+            implicit: None,
+            non_controlling: None,
+            width: None,
+            content,
+        });
+    }
+    Some(measures)
+}
+
+/// The number of the `index`th measure a measure is cut into.
+fn piece_number(measure: &Measure, index: usize) -> String {
+    match measure.number.parse::<u32>() {
+        Ok(number) => (number + index as u32).to_string(),
+        Err(_) => format!("{}-{}", measure.number, index + 1),
+    }
+}
+
+/// The furthest point, in divisions, that a measure's notes reach.
+fn measure_total(measure: &Measure) -> u64 {
+    let mut position: u64 = 0;
+    let mut furthest = 0;
+    for element in &measure.content {
+        match element {
+            MusicDataElement::Note(note) if !note.is_chord_member() => {
+                position += note.duration().unwrap_or(0);
+            }
+            MusicDataElement::Backup(backup) => {
+                position = position.saturating_sub(backup.duration);
+            }
+            MusicDataElement::Forward(forward) => position += forward.duration,
+            _ => {}
+        }
+        furthest = furthest.max(position);
+    }
+    furthest
+}
+
+/// A rest of `duration` divisions, or a whole-measure rest.
+fn rest_note(
+    duration: u64,
+    value: Option<NoteTypeValue>,
+    dots: u32,
+    measure_rest: bool,
+) -> MusicDataElement {
+    MusicDataElement::Note(Box::new(Note {
+        position: Position::default(),
+        dynamics: None,
+        end_dynamics: None,
+        attack: None,
+        release: None,
+        pizzicato: None,
+        print_object: None,
+        content: NoteContent::Regular {
+            full_note: FullNote {
+                chord: false,
+                content: PitchRestUnpitched::Rest(Rest {
+                    measure: measure_rest.then_some(YesNo::Yes),
+                    ..Default::default()
+                }),
+            },
+            duration,
+            ties: vec![],
+        },
+        instrument: vec![],
+        voice: None,
+        r#type: value.map(|value| NoteType { value, size: None }),
+        dots: vec![Dot::default(); dots as usize],
+        accidental: None,
+        time_modification: None,
+        stem: None,
+        notehead: None,
+        staff: None,
+        beams: vec![],
+        notations: vec![],
+        lyrics: vec![],
+    }))
+}
+
+/// Cut a measure longer than `length` divisions into measures of that
+/// length, or `None` if it fits or cannot be split.
+fn split_measure(measure: &Measure, length: u64, divisions: u64) -> Option<Vec<Measure>> {
+    if measure.content.iter().any(|element| {
+        matches!(
+            element,
+            MusicDataElement::Backup(_) | MusicDataElement::Forward(_)
+        )
+    }) {
+        return None;
+    }
+    let total: u64 = measure
+        .content
+        .iter()
+        .filter_map(|element| match element {
+            MusicDataElement::Note(note) => note.duration().filter(|_| !note.is_chord_member()),
+            _ => None,
+        })
+        .sum();
+    if total <= length {
+        return None;
+    }
+
+    let count = total.div_ceil(length) as usize;
+    let mut contents: Vec<Vec<MusicDataElement>> = vec![Vec::new(); count];
+    let mut position = 0;
+    // Start of the last note that took time, which its chord notes share
+    let mut chord_start = 0;
+
+    for element in &measure.content {
+        let index = match element {
+            MusicDataElement::Note(note) => {
+                let Some(duration) = note.duration() else {
+                    // Grace notes go with the note they lead into
+                    contents[(position / length) as usize].push(element.clone());
+                    continue;
+                };
+                let start = if note.is_chord_member() {
+                    chord_start
+                } else {
+                    chord_start = position;
+                    position += duration;
+                    chord_start
+                };
+                let pieces = split_note(note, start, duration, length, divisions)?;
+                for (at, piece) in pieces {
+                    contents[(at / length) as usize].push(MusicDataElement::Note(Box::new(piece)));
+                }
+                continue;
+            }
+            // Closing barlines move to the end of the last new measure
+            MusicDataElement::Barline(barline) => match barline.location {
+                Some(RightLeftMiddle::Left) => 0,
+                Some(RightLeftMiddle::Middle) => (position / length) as usize,
+                None | Some(RightLeftMiddle::Right) => count - 1,
+            },
+            _ => (position / length) as usize,
+        };
+        contents[index.min(count - 1)].push(element.clone());
+    }
+
+    Some(
+        contents
+            .into_iter()
+            .enumerate()
+            .map(|(index, content)| {
+                let mut piece = measure.clone();
+                if index > 0 {
+                    piece.number = piece_number(measure, index);
+                    piece.width = None;
+                }
+                piece.content = content;
+                piece
+            })
+            .collect(),
+    )
+}
+
+/// Split a note starting at `start` at every multiple of `length` it
+/// crosses, returning the pieces with their start positions.
+///
+/// Pitched pieces are tied together. Returns `None` if a piece cannot be
+/// written as plain note values, or the note is a tuplet that would be cut.
+fn split_note(
+    note: &Note,
+    start: u64,
+    duration: u64,
+    length: u64,
+    divisions: u64,
+) -> Option<Vec<(u64, Note)>> {
+    let end = start + duration;
+    if start / length == (end - 1) / length {
+        return Some(vec![(start, note.clone())]);
+    }
+    if note.time_modification.is_some() {
+        return None;
+    }
+
+    // The note values of each piece, measure by measure
+    let mut values = Vec::new();
+    let mut at = start;
+    while at < end {
+        let bar_end = (at / length + 1) * length;
+        let span = bar_end.min(end) - at;
+        let mut offset = at;
+        for (value, dots, piece) in note_values(span, divisions)? {
+            values.push((offset, value, dots, piece));
+            offset += piece;
+        }
+        at += span;
+    }
+
+    let is_rest = note.is_rest();
+    let tied_on = has_tie(note, StartStop::Start);
+    let last = values.len() - 1;
+
+    Some(
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(index, (offset, value, dots, piece))| {
+                let mut split = note.clone();
+                if index > 0 {
+                    split.notations.clear();
+                    split.lyrics.clear();
+                    split.accidental = None;
+                }
+                split.beams.clear();
+                split.r#type = Some(NoteType { value, size: None });
+                split.dots = vec![Dot::default(); dots as usize];
+                match &mut split.content {
+                    NoteContent::Regular { duration, ties, .. } => {
+                        *duration = piece;
+                        if index > 0 {
+                            ties.clear();
+                        }
+                    }
+                    NoteContent::Cue { duration, .. } => *duration = piece,
+                    NoteContent::Grace { .. } => {}
+                }
+                if !is_rest {
+                    // The original tie onwards now leaves from the last piece
+                    if index < last {
+                        remove_tie(&mut split, StartStop::Start);
+                    }
+                    if index > 0 {
+                        add_tie(&mut split, StartStop::Stop);
+                    }
+                    if index < last || tied_on {
+                        add_tie(&mut split, StartStop::Start);
+                    }
+                }
+                (offset, split)
+            })
+            .collect(),
+    )
+}
+
+/// Write `duration` divisions as plain note values, longest first, each
+/// with up to two dots. Returns `None` if it cannot be written exactly.
+fn note_values(mut duration: u64, divisions: u64) -> Option<Vec<(NoteTypeValue, u32, u64)>> {
+    let whole = divisions * 4;
+    let mut values = Vec::new();
+    while duration > 0 {
+        let (value, dots, piece) = NOTE_TYPES.iter().find_map(|&(value, power)| {
+            let base = if power >= 0 {
+                whole << power
+            } else {
+                let den = 1u64 << -power;
+                if whole % den != 0 {
+                    return None;
+                }
+                whole / den
+            };
+            if base > duration {
+                return None;
+            }
+            let mut dots = 0;
+            let mut piece = base;
+            while dots < 2 && base % (2 << dots) == 0 {
+                let dotted = piece + base / (2 << dots);
+                if dotted > duration {
+                    break;
+                }
+                piece = dotted;
+                dots += 1;
+            }
+            Some((value, dots, piece))
+        })?;
+        values.push((value, dots, piece));
+        duration -= piece;
+    }
+    Some(values)
+}
+
+/// Whether a note has a tie of the given type, sounding or drawn.
+fn has_tie(note: &Note, r#type: StartStop) -> bool {
+    let sounding = matches!(
+        &note.content,
+        NoteContent::Regular { ties, .. } if ties.iter().any(|t| t.r#type == r#type)
+    );
+    sounding
+        || note
+            .notations
+            .iter()
+            .flat_map(|n| &n.content)
+            .any(|c| matches!(c, NotationContent::Tied(t) if t.r#type == tied_type(r#type)))
+}
+
+fn remove_tie(note: &mut Note, r#type: StartStop) {
+    if let NoteContent::Regular { ties, .. } = &mut note.content {
+        ties.retain(|t| t.r#type != r#type);
+    }
+    for notations in &mut note.notations {
+        notations
+            .content
+            .retain(|c| !matches!(c, NotationContent::Tied(t) if t.r#type == tied_type(r#type)));
+    }
+    note.notations.retain(|n| !n.content.is_empty());
+}
+
+/// Add a tie, both the sounding `<tie>` and the drawn `<tied>`.
+fn add_tie(note: &mut Note, r#type: StartStop) {
+    if let NoteContent::Regular { ties, .. } = &mut note.content {
+        ties.push(Tie {
+            r#type,
+            time_only: None,
+        });
+    }
+    let tied = NotationContent::Tied(Tied {
+        r#type: tied_type(r#type),
+        number: None,
+        line_type: None,
+        position: Position::default(),
+        placement: None,
+        orientation: None,
+        color: None,
+    });
+    match note.notations.first_mut() {
+        Some(notations) => notations.content.push(tied),
+        None => note.notations.push(Notations {
+            print_object: None,
+            content: vec![tied],
+            editorial: Default::default(),
+        }),
+    }
+}
+
+fn tied_type(r#type: StartStop) -> StartStopContinue {
+    match r#type {
+        StartStop::Start => StartStopContinue::Start,
+        StartStop::Stop => StartStopContinue::Stop,
+    }
+}
+
+/// The diatonic steps and semitones from sounding to written pitch under
+/// `transpose`, the reverse of the transposition it describes.
+fn written_interval(transpose: &Transpose) -> (i32, i32) {
+    let octaves = transpose.octave_change.unwrap_or(0);
+    let diatonic = transpose
+        .diatonic
+        .unwrap_or_else(|| (f64::from(transpose.chromatic) * 7.0 / 12.0).round() as i32);
+    (
+        -(diatonic + 7 * octaves),
+        -(transpose.chromatic + 12 * octaves),
+    )
+}
+
+/// Move a concert key to its written key, returning the written fifths if
+/// the key was respelled enharmonically.
+fn transpose_key(key: &mut Key, transpose: &Transpose) -> Option<i8> {
+    let KeyContent::Traditional(key) = &mut key.content else {
+        return None;
+    };
+    let (fifths, wrapped) = transpose_fifths(key.fifths, transpose);
+    key.fifths = fifths;
+    wrapped.then_some(fifths)
+}
+
+/// The written key signature for a concert key of `fifths`, and whether
+/// it had to be respelled to stay within seven sharps or flats.
+fn transpose_fifths(fifths: i8, transpose: &Transpose) -> (i8, bool) {
+    let (steps, semitones) = written_interval(transpose);
+    // Each semitone is seven fifths up; each diatonic step twelve back down
+    let mut written = i32::from(fifths) + 7 * semitones - 12 * steps;
+    let mut wrapped = false;
+    while written > i32::from(MAX_FIFTHS) {
+        written -= 12;
+        wrapped = true;
+    }
+    while written < -i32::from(MAX_FIFTHS) {
+        written += 12;
+        wrapped = true;
+    }
+    (written as i8, wrapped)
+}
+
+/// The pitch of a pitched note, if it has one.
+fn pitch_mut(note: &mut Note) -> Option<&mut Pitch> {
+    let full_note = match &mut note.content {
+        NoteContent::Regular { full_note, .. }
+        | NoteContent::Grace { full_note, .. }
+        | NoteContent::Cue { full_note, .. } => full_note,
+    };
+    match &mut full_note.content {
+        PitchRestUnpitched::Pitch(pitch) => Some(pitch),
+        _ => None,
+    }
+}
+
+/// Update a displayed accidental to match the note's current spelling.
+fn sync_accidental(note: &mut Note) {
+    let alter = pitch_mut(note).and_then(|p| p.alter).unwrap_or(0.0);
+    if let Some(accidental) = &mut note.accidental {
+        accidental.value = match alter as i32 {
+            2 => AccidentalValue::DoubleSharp,
+            1 => AccidentalValue::Sharp,
+            -1 => AccidentalValue::Flat,
+            -2 => AccidentalValue::FlatFlat,
+            _ => AccidentalValue::Natural,
+        };
+    }
+}
+
+/// The enharmonic spelling of `pitch` chosen by `policy` in a key of
+/// `fifths`, or `None` for microtonal pitches.
+fn respell_pitch(pitch: &Pitch, policy: SpellingPolicy, fifths: i8) -> Option<Pitch> {
+    let alter = pitch.alter.unwrap_or(0.0);
+    if alter.fract() != 0.0 {
+        return None;
+    }
+    let step = step_index(pitch.step) as usize;
+    let semitones = i32::from(pitch.octave) * 12 + NATURAL_SEMITONES[step] + alter as i32;
+    let pitch_class = semitones.rem_euclid(12) as usize;
+
+    let (new_step, new_alter) = match policy {
+        SpellingPolicy::PreferSharps => SHARP_SPELLINGS[pitch_class],
+        SpellingPolicy::PreferFlats => FLAT_SPELLINGS[pitch_class],
+        SpellingPolicy::KeyContext => key_spelling(fifths, pitch_class).unwrap_or(if fifths < 0 {
+            FLAT_SPELLINGS[pitch_class]
+        } else {
+            SHARP_SPELLINGS[pitch_class]
+        }),
+    };
+    let octave = (semitones - NATURAL_SEMITONES[new_step] - new_alter).div_euclid(12);
+
+    Some(Pitch {
+        step: step_from_index(new_step as i32),
+        alter: (new_alter != 0).then_some(f64::from(new_alter)),
+        octave: u8::try_from(octave).ok().filter(|&o| o <= 9)?,
+    })
+}
+
+/// The spelling of a pitch class within the scale of a key, if it is one
+/// of the key's seven notes.
+fn key_spelling(fifths: i8, pitch_class: usize) -> Option<(usize, i32)> {
+    (0..7).find_map(|step| {
+        let position = SHARP_ORDER.iter().position(|&s| s == step).unwrap() as i32;
+        let alter = if fifths > 0 && position < i32::from(fifths) {
+            1
+        } else if fifths < 0 && 6 - position < -i32::from(fifths) {
+            -1
+        } else {
+            0
+        };
+        ((NATURAL_SEMITONES[step] + alter).rem_euclid(12) as usize == pitch_class)
+            .then_some((step, alter))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::pitch::Step;
+    use crate::lang::compile;
+
+    /// (step, alter, octave) of each note in the first part.
+    fn spellings(score: &ScorePartwise) -> Vec<(Step, Option<f64>, u8)> {
+        score.parts[0]
+            .measures
+            .iter()
+            .flat_map(|m| &m.content)
+            .filter_map(|element| match element {
+                MusicDataElement::Note(note) => match &note.content {
+                    NoteContent::Regular { full_note, .. } => match &full_note.content {
+                        PitchRestUnpitched::Pitch(p) => Some((p.step, p.alter, p.octave)),
+                        _ => None,
+                    },
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_respell_prefer_sharps_moves_octave() {
+        let mut score =
+            compile("(score (part :piano (measure (note cb4 :q) (note bb3 :q) (note d4 :h))))")
+                .unwrap();
+
+        respell(&mut score, SpellingPolicy::PreferSharps);
+
+        assert_eq!(
+            spellings(&score),
+            vec![
+                (Step::B, None, 3),
+                (Step::A, Some(1.0), 3),
+                (Step::D, None, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_respell_prefer_flats_naturalizes_white_keys() {
+        let mut score =
+            compile("(score (part :piano (measure (note e#4 :q) (note b#4 :q) (note f#4 :h))))")
+                .unwrap();
+
+        respell(&mut score, SpellingPolicy::PreferFlats);
+        assert_eq!(
+            spellings(&score),
+            vec![
+                (Step::F, None, 4),
+                (Step::C, None, 5),
+                (Step::G, Some(-1.0), 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_respell_key_context_uses_key_signature() {
+        let mut score = compile(
+            "(score (part :piano
+               (measure (key f# :major) (note f4 :q) (note a#4 :q) (note bb4 :q) (note d4 :q))
+               (measure (key eb :major) (note d#4 :q) (note f#4 :q) (note g4 :h))))",
+        )
+        .unwrap();
+
+        respell(&mut score, SpellingPolicy::KeyContext);
+
+        assert_eq!(
+            spellings(&score),
+            vec![
+                // F# major spells F as E#, and A#/Bb as A#
+                (Step::E, Some(1.0), 4),
+                (Step::A, Some(1.0), 4),
+                (Step::A, Some(1.0), 4),
+                (Step::D, None, 4),
+                // E-flat major spells D# as Eb; F# is outside the key
+                (Step::E, Some(-1.0), 4),
+                (Step::G, Some(-1.0), 4),
+                (Step::G, None, 4),
+            ]
+        );
+    }
+
+    fn first_attributes(score: &ScorePartwise) -> &crate::ir::attributes::Attributes {
+        score.parts[0].measures[0]
+            .content
+            .iter()
+            .find_map(|element| match element {
+                MusicDataElement::Attributes(attributes) => Some(attributes.as_ref()),
+                _ => None,
+            })
+            .expect("attributes in the first measure")
+    }
+
+    fn first_fifths(score: &ScorePartwise) -> i8 {
+        match &first_attributes(score).keys[0].content {
+            KeyContent::Traditional(key) => key.fifths,
+            other => panic!("expected a traditional key, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_to_transposing_parts_b_flat_instrument_writes_a_second_higher() {
+        let mut score = compile(
+            "(score (part :name \"Clarinet in Bb\" :transpose -2
+               (measure (key c :major) (note c4 :q) (note e4 :q) (note f#4 :h))))",
+        )
+        .unwrap();
+        assert!(score.defaults.as_ref().is_some_and(|d| d.concert_score));
+
+        to_transposing_parts(&mut score);
+
+        assert_eq!(score.defaults, None);
+        assert_eq!(
+            spellings(&score),
+            vec![
+                (Step::D, None, 4),
+                (Step::F, Some(1.0), 4),
+                (Step::G, Some(1.0), 4),
+            ]
+        );
+        assert_eq!(first_fifths(&score), 2);
+        let transpose = &first_attributes(&score).transpose[0];
+        assert_eq!(transpose.chromatic, -2);
+        assert_eq!(transpose.diatonic, Some(-1));
+    }
+
+    #[test]
+    fn test_to_transposing_parts_horn_in_f_and_untransposed_parts() {
+        let mut score = compile(
+            "(score
+               (part :name \"Horn in F\" :transpose -7 (measure (key eb :major) (note bb3 :w)))
+               (part :name \"Flute\" (measure (key eb :major) (note bb4 :w))))",
+        )
+        .unwrap();
+
+        to_transposing_parts(&mut score);
+
+        assert_eq!(first_fifths(&score), -2);
+        assert_eq!(spellings(&score), vec![(Step::F, None, 4)]);
+        score.parts.remove(0);
+        assert_eq!(first_fifths(&score), -3);
+        assert_eq!(spellings(&score), vec![(Step::B, Some(-1.0), 4)]);
+    }
+
+    #[test]
+    fn test_to_transposing_parts_moves_every_staff_key() {
+        let mut score = compile(
+            "(score (part :name \"Clarinet in Bb\" :transpose -2
+               (measure (key c :major) (note c4 :w))))",
+        )
+        .unwrap();
+        let Some(MusicDataElement::Attributes(attributes)) = score.parts[0].measures[0]
+            .content
+            .iter_mut()
+            .find(|element| matches!(element, MusicDataElement::Attributes(_)))
+        else {
+            panic!("expected attributes in the first measure");
+        };
+        attributes.keys[0].number = Some(1);
+        let mut second = attributes.keys[0].clone();
+        second.number = Some(2);
+        attributes.keys.push(second);
+
+        to_transposing_parts(&mut score);
+
+        let fifths: Vec<i8> = first_attributes(&score)
+            .keys
+            .iter()
+            .map(|key| match &key.content {
+                KeyContent::Traditional(key) => key.fifths,
+                other => panic!("expected a traditional key, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(fifths, vec![2, 2]);
+    }
+
+    #[test]
+    fn test_to_transposing_parts_respells_keys_beyond_seven_sharps() {
+        // Concert F# major for a B-flat instrument would need eight sharps
+        let mut score = compile(
+            "(score (part :name \"Trumpet in Bb\" :transpose -2
+               (measure (key f# :major) (note f#4 :h) (note c#5 :h))))",
+        )
+        .unwrap();
+
+        to_transposing_parts(&mut score);
+
+        assert_eq!(first_fifths(&score), -4);
+        assert_eq!(
+            spellings(&score),
+            vec![(Step::A, Some(-1.0), 4), (Step::E, Some(-1.0), 5)]
+        );
+    }
+
+    /// The multiple-rest count of each measure in the first part, or 0.
+    fn multi_rests(score: &ScorePartwise) -> Vec<u32> {
+        score.parts[0]
+            .measures
+            .iter()
+            .map(|measure| {
+                measure
+                    .content
+                    .iter()
+                    .filter_map(|element| match element {
+                        MusicDataElement::Attributes(attributes) => Some(attributes),
+                        _ => None,
+                    })
+                    .flat_map(|attributes| &attributes.measure_styles)
+                    .find_map(|style| match style.content {
+                        MeasureStyleContent::MultipleRest { count, .. } => Some(count),
+                        _ => None,
+                    })
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_collapse_rests_five_empty_measures() {
+        let mut score = compile(
+            "(score (part :violin
+               (measure (note c4 :w))
+               (measure (rest :w)) (measure (rest :w)) (measure (rest :w))
+               (measure (rest :w)) (measure (rest :w))
+               (measure (note d4 :w))))",
+        )
+        .unwrap();
+
+        collapse_rests(&mut score.parts[0]);
+
+        assert_eq!(multi_rests(&score), vec![0, 5, 0]);
+        let numbers: Vec<&str> = score.parts[0]
+            .measures
+            .iter()
+            .map(|m| m.number.as_str())
+            .collect();
+        assert_eq!(numbers, ["1", "2", "7"]);
+
+        let xml = crate::musicxml::emit(&score).unwrap();
+        assert!(xml.contains("<multiple-rest>5</multiple-rest>"), "{}", xml);
+    }
+
+    #[test]
+    fn test_collapse_rests_stops_at_rehearsal_tempo_and_double_barlines() {
+        let mut score = compile(
+            "(score (part :violin
+               (measure (time 4 4) (rest :w)) (measure (rest :w))
+               (measure (rehearsal \"A\") (rest :w)) (measure (rest :w) (barline :double))
+               (measure (rest :w)) (measure (rest :w))
+               (measure (tempo 90 :quarter) (rest :w))
+               (measure (time 3 4) (rest :h.)) (measure (rest :h.))
+               (measure (rest :w))))",
+        )
+        .unwrap();
+
+        collapse_rests(&mut score.parts[0]);
+
+        // A lone empty measure stays as it is
+        assert_eq!(multi_rests(&score), vec![2, 2, 2, 0, 3]);
+        // The double barline stays at the end of the run it closes
+        let barlines = |measure: &Measure| {
+            measure
+                .content
+                .iter()
+                .filter(|e| matches!(e, MusicDataElement::Barline(_)))
+                .count()
+        };
+        assert_eq!(barlines(&score.parts[0].measures[1]), 1);
+    }
+
+    #[test]
+    fn test_collapse_rests_keeps_measures_with_dynamics() {
+        let mut score = compile(
+            "(score (part :violin
+               (measure (rest :w)) (measure (rest :w)) (measure (fp) (rest :w))))",
+        )
+        .unwrap();
+
+        collapse_rests(&mut score.parts[0]);
+
+        assert_eq!(multi_rests(&score), vec![2, 0]);
+    }
+
+    /// (type, tie types) of each note, measure by measure.
+    fn barred(score: &ScorePartwise) -> Vec<Vec<(NoteTypeValue, Vec<StartStop>)>> {
+        score.parts[0]
+            .measures
+            .iter()
+            .map(|m| {
+                m.content
+                    .iter()
+                    .filter_map(|element| match element {
+                        MusicDataElement::Note(note) => match &note.content {
+                            NoteContent::Regular { ties, .. } => Some((
+                                note.r#type.as_ref().unwrap().value,
+                                ties.iter().map(|t| t.r#type).collect(),
+                            )),
+                            _ => None,
+                        },
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_auto_barline_six_quarters_in_four_four() {
+        let mut score = compile(
+            "(score (part :piano (measure (time 4 4)
+               (note c4 :q) (note d4 :q) (note e4 :q) (note f4 :q) (note g4 :q) (note a4 :q))))",
+        )
+        .unwrap();
+
+        auto_barline(&mut score);
+
+        let measures = &score.parts[0].measures;
+        assert_eq!(measures.len(), 2);
+        assert_eq!(measures[1].number, "2");
+        let quarters = barred(&score);
+        assert_eq!(quarters[0].len(), 4);
+        assert_eq!(quarters[1].len(), 2);
+        // The attributes stay with the first measure
+        assert!(matches!(
+            measures[0].content[0],
+            MusicDataElement::Attributes(_)
+        ));
+    }
+
+    #[test]
+    fn test_auto_barline_ties_note_across_barline() {
+        let mut score = compile(
+            "(score (part :piano
+               (measure (time 4 4) (note c4 :q) (note d4 :q) (note e4 :q) (note f4 :h) (note g4 :q))
+               (measure (note c5 :w))))",
+        )
+        .unwrap();
+
+        auto_barline(&mut score);
+
+        use NoteTypeValue::*;
+        assert_eq!(
+            barred(&score),
+            vec![
+                vec![
+                    (Quarter, vec![]),
+                    (Quarter, vec![]),
+                    (Quarter, vec![]),
+                    (Quarter, vec![StartStop::Start]),
+                ],
+                vec![(Quarter, vec![StartStop::Stop]), (Quarter, vec![])],
+                vec![(Whole, vec![])],
+            ]
+        );
+        let numbers: Vec<&str> = score.parts[0]
+            .measures
+            .iter()
+            .map(|m| m.number.as_str())
+            .collect();
+        assert_eq!(numbers, vec!["1", "2", "3"]);
+
+        let xml = crate::musicxml::emit(&score).unwrap();
+        assert!(xml.contains(r#"<tied type="start"/>"#), "{}", xml);
+        assert!(xml.contains(r#"<tied type="stop"/>"#), "{}", xml);
+    }
+
+    #[test]
+    fn test_auto_barline_leaves_full_measures_alone() {
+        let source =
+            "(score (part :piano (measure (time 3 4) (note c4 :h.)) (measure (note d4 :h))))";
+        let mut score = compile(source).unwrap();
+
+        auto_barline(&mut score);
+
+        assert_eq!(score, compile(source).unwrap());
+    }
+
+    #[test]
+    fn test_auto_barline_pads_other_parts_with_rests() {
+        let mut score = compile(
+            "(score
+               (part :piano
+                 (measure (time 4 4)
+                   (note c4 :q) (note d4 :q) (note e4 :q) (note f4 :q) (note g4 :q) (note a4 :q))
+                 (measure (note c5 :w)))
+               (part :violin
+                 (measure (time 4 4) (note g4 :w))
+                 (measure (note a4 :w))))",
+        )
+        .unwrap();
+
+        auto_barline(&mut score);
+
+        for part in &score.parts {
+            let numbers: Vec<&str> = part.measures.iter().map(|m| m.number.as_str()).collect();
+            assert_eq!(numbers, vec!["1", "2", "3"], "{}", part.id);
+        }
+        // The violin rests while the piano finishes its overflow
+        let padding: Vec<_> = score.parts[1].measures[1]
+            .content
+            .iter()
+            .filter_map(|element| match element {
+                MusicDataElement::Note(note) => {
+                    Some((note.is_rest(), note.r#type.as_ref().map(|t| t.value)))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(padding, vec![(true, Some(NoteTypeValue::Half))]);
+        assert!(crate::musicxml::emit(&score).is_ok());
+    }
+
+    #[test]
+    fn test_auto_barline_leaves_misaligned_parts_alone() {
+        // The violin's first measure is short, so it cannot be padded
+        let source = "(score
+               (part :piano
+                 (measure (time 4 4)
+                   (note c4 :q) (note d4 :q) (note e4 :q) (note f4 :q) (note g4 :q) (note a4 :q)))
+               (part :violin (measure (time 4 4) (note g4 :h))))";
+        let mut score = compile(source).unwrap();
+
+        auto_barline(&mut score);
+
+        assert_eq!(score, compile(source).unwrap());
+    }
+}
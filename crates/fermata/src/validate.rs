@@ -0,0 +1,348 @@
+//! Score-level lints.
+//!
+//! These checks run on a compiled [`ScorePartwise`] and report problems that
+//! are syntactically valid but produce broken notation, such as a slur that
+//! is started and never stopped. They return warnings rather than errors so
+//! callers can decide how strict to be.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::ir::attributes::TimeContent;
+use crate::ir::common::StartStopContinue;
+use crate::ir::direction::{DirectionTypeContent, UpDownStopContinue, WedgeType};
+use crate::ir::measure::MusicDataElement;
+use crate::ir::notation::NotationContent;
+use crate::ir::part::Part;
+use crate::ir::score::ScorePartwise;
+use crate::ir::voice::MeasureCursor;
+
+/// The kind of spanner checked by [`validate_score`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpanKind {
+    /// A tie between notes of the same pitch
+    Tie,
+    /// A slur
+    Slur,
+    /// A crescendo or diminuendo hairpin
+    Wedge,
+    /// An 8va/8vb line
+    OctaveShift,
+}
+
+impl fmt::Display for SpanKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SpanKind::Tie => "tie",
+            SpanKind::Slur => "slur",
+            SpanKind::Wedge => "wedge",
+            SpanKind::OctaveShift => "octave shift",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A problem found by [`validate_score`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationWarning {
+    /// A spanner start with no matching stop, or a stop with no start.
+    UnterminatedSpan {
+        /// The kind of spanner
+        kind: SpanKind,
+        /// Id of the part containing it
+        part: String,
+        /// Number of the measure holding the unmatched start or stop
+        measure: String,
+    },
+
+    /// A measure whose notes run past the length of its time signature.
+    OverfullMeasure {
+        /// Id of the part containing it
+        part: String,
+        /// Number of the measure
+        measure: String,
+        /// Length allowed by the time signature, in divisions
+        expected: u64,
+        /// Length of the longest voice, in divisions
+        found: u64,
+    },
+}
+
+impl fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationWarning::UnterminatedSpan {
+                kind,
+                part,
+                measure,
+            } => write!(
+                f,
+                "unterminated {} in part {}, measure {}",
+                kind, part, measure
+            ),
+            ValidationWarning::OverfullMeasure {
+                part,
+                measure,
+                expected,
+                found,
+            } => write!(
+                f,
+                "overfull measure in part {}, measure {}: {} divisions where the time signature allows {}",
+                part, measure, found, expected
+            ),
+        }
+    }
+}
+
+/// Run every score-level lint and collect the warnings, in part order.
+pub fn validate_score(score: &ScorePartwise) -> Vec<ValidationWarning> {
+    score
+        .parts
+        .iter()
+        .flat_map(|part| {
+            let mut warnings = check_measure_lengths(part);
+            warnings.extend(check_spans(part));
+            warnings
+        })
+        .collect()
+}
+
+/// Report measures longer than their time signature allows.
+///
+/// Divisions and time signatures carry over from earlier measures.
+/// Measures are only checked once both are known and the meter is
+/// measured; short measures (pickups, final bars) are not reported.
+fn check_measure_lengths(part: &Part) -> Vec<ValidationWarning> {
+    let mut warnings = Vec::new();
+    let mut divisions: Option<u64> = None;
+    let mut time: Option<&TimeContent> = None;
+
+    for measure in &part.measures {
+        let mut cursor = MeasureCursor::new();
+        for element in &measure.content {
+            if let MusicDataElement::Attributes(attributes) = element {
+                if let Some(d) = attributes.divisions {
+                    divisions = Some(d);
+                }
+                if let Some(t) = attributes.times.first() {
+                    time = Some(&t.content);
+                }
+            }
+            cursor.advance(element);
+        }
+        let found = cursor.end();
+
+        let expected = divisions.zip(time).and_then(|(d, t)| measure_length(t, d));
+        if let Some(expected) = expected
+            && found > expected
+        {
+            warnings.push(ValidationWarning::OverfullMeasure {
+                part: part.id.clone(),
+                measure: measure.number.clone(),
+                expected,
+                found,
+            });
+        }
+    }
+    warnings
+}
+
+/// Length of a measure in the given divisions, or `None` without a meter.
+pub(crate) fn measure_length(time: &TimeContent, divisions: u64) -> Option<u64> {
+    let TimeContent::Measured { signatures } = time else {
+        return None;
+    };
+    let mut length = 0;
+    for signature in signatures {
+        let beats: u64 = signature
+            .beats
+            .split('+')
+            .map(|b| b.trim().parse::<u64>().ok())
+            .sum::<Option<u64>>()?;
+        let beat_type: u64 = signature.beat_type.trim().parse().ok().filter(|&t| t > 0)?;
+        length += beats * 4 * divisions / beat_type;
+    }
+    Some(length)
+}
+
+/// Whether a spanner event opens or closes a span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpanEvent {
+    Start,
+    Stop,
+}
+
+/// Pair spanner starts and stops by kind and number across a whole part.
+///
+/// Spans may cross barlines; only starts still open at the end of the part
+/// and stops with nothing open are reported.
+fn check_spans(part: &Part) -> Vec<ValidationWarning> {
+    let mut warnings = Vec::new();
+    // Measure numbers of the open starts, per kind and number
+    let mut open: HashMap<(SpanKind, u8), Vec<&str>> = HashMap::new();
+
+    for measure in &part.measures {
+        for (kind, number, event) in measure.content.iter().flat_map(span_events) {
+            let starts = open.entry((kind, number)).or_default();
+            match event {
+                SpanEvent::Start => starts.push(&measure.number),
+                SpanEvent::Stop => {
+                    if starts.pop().is_none() {
+                        warnings.push(ValidationWarning::UnterminatedSpan {
+                            kind,
+                            part: part.id.clone(),
+                            measure: measure.number.clone(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    let mut unclosed: Vec<(SpanKind, &str)> = open
+        .into_iter()
+        .flat_map(|((kind, _), starts)| starts.into_iter().map(move |m| (kind, m)))
+        .collect();
+    // HashMap order is arbitrary; report in a stable order
+    unclosed.sort_by_key(|&(kind, measure)| (measure.parse::<u32>().ok(), measure, kind as u8));
+    warnings.extend(unclosed.into_iter().map(|(kind, measure)| {
+        ValidationWarning::UnterminatedSpan {
+            kind,
+            part: part.id.clone(),
+            measure: measure.to_string(),
+        }
+    }));
+    warnings
+}
+
+/// Spanner starts and stops carried by one measure element.
+fn span_events(element: &MusicDataElement) -> Vec<(SpanKind, u8, SpanEvent)> {
+    let mut events = Vec::new();
+    match element {
+        MusicDataElement::Note(note) => {
+            for content in note.notations.iter().flat_map(|n| &n.content) {
+                match content {
+                    NotationContent::Tied(tied) => {
+                        if let Some(event) = start_stop_event(tied.r#type) {
+                            events.push((SpanKind::Tie, tied.number.unwrap_or(1), event));
+                        }
+                    }
+                    NotationContent::Slur(slur) => {
+                        if let Some(event) = start_stop_event(slur.r#type) {
+                            events.push((SpanKind::Slur, slur.number, event));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        MusicDataElement::Direction(direction) => {
+            for direction_type in &direction.direction_types {
+                match &direction_type.content {
+                    DirectionTypeContent::Wedge(wedge) => {
+                        let event = match wedge.r#type {
+                            WedgeType::Crescendo | WedgeType::Diminuendo => Some(SpanEvent::Start),
+                            WedgeType::Stop => Some(SpanEvent::Stop),
+                            WedgeType::Continue => None,
+                        };
+                        if let Some(event) = event {
+                            events.push((SpanKind::Wedge, wedge.number.unwrap_or(1), event));
+                        }
+                    }
+                    DirectionTypeContent::OctaveShift(shift) => {
+                        let event = match shift.r#type {
+                            UpDownStopContinue::Up | UpDownStopContinue::Down => {
+                                Some(SpanEvent::Start)
+                            }
+                            UpDownStopContinue::Stop => Some(SpanEvent::Stop),
+                            UpDownStopContinue::Continue => None,
+                        };
+                        if let Some(event) = event {
+                            events.push((SpanKind::OctaveShift, shift.number.unwrap_or(1), event));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+    events
+}
+
+fn start_stop_event(value: StartStopContinue) -> Option<SpanEvent> {
+    match value {
+        StartStopContinue::Start => Some(SpanEvent::Start),
+        StartStopContinue::Stop => Some(SpanEvent::Stop),
+        StartStopContinue::Continue => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang::compile;
+
+    #[test]
+    fn test_validate_score_unstopped_slur_warns_once() {
+        let score = compile(
+            "(score (part :piano
+               (measure (note c4 :q :slur :start) (note d4 :q) (note e4 :h))
+               (measure (note f4 :w))))",
+        )
+        .unwrap();
+
+        assert_eq!(
+            validate_score(&score),
+            vec![ValidationWarning::UnterminatedSpan {
+                kind: SpanKind::Slur,
+                part: "P1".to_string(),
+                measure: "1".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validate_score_cross_measure_spans_are_clean() {
+        let score = compile(
+            "(score (part :piano
+               (measure (note c4 :h :slur :start) (note d4 :h :tie :start))
+               (measure (note d4 :h :tie :stop) (note e4 :h :slur :stop))))",
+        )
+        .unwrap();
+
+        assert!(validate_score(&score).is_empty());
+    }
+
+    #[test]
+    fn test_validate_score_stop_without_start_warns() {
+        let score = compile("(score (part :piano (measure (note c4 :w :tie :stop))))").unwrap();
+
+        let warnings = validate_score(&score);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].to_string(),
+            "unterminated tie in part P1, measure 1"
+        );
+    }
+
+    #[test]
+    fn test_validate_score_overfull_measure() {
+        let score = compile(
+            "(score (part :piano
+               (measure (time 2 4) (note c4 :h))
+               (measure (note d4 :h) (note e4 :q))))",
+        )
+        .unwrap();
+
+        assert_eq!(
+            validate_score(&score),
+            vec![ValidationWarning::OverfullMeasure {
+                part: "P1".to_string(),
+                measure: "2".to_string(),
+                expected: 1920,
+                found: 2880,
+            }]
+        );
+    }
+}
@@ -0,0 +1,440 @@
+//! Integration tests for the `fermata` command-line tool.
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Write `source` to a fresh file in the system temp directory.
+fn write_source(name: &str, source: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("fermata-cli-{}-{}", std::process::id(), name));
+    fs::write(&path, source).unwrap();
+    path
+}
+
+fn fermata(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_fermata"))
+        .arg("--no-color")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Run `fermata` with `input` piped to stdin.
+fn fermata_stdin(args: &[&str], input: &[u8]) -> std::process::Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_fermata"))
+        .arg("--no-color")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+const OVERFULL: &str = "(score (part :piano (measure (time 2 4) (note c4 :h) (note d4 :q))))";
+
+#[test]
+fn test_check_strict_overfull_measure_fails() {
+    let path = write_source("overfull-strict.fm", OVERFULL);
+    let output = fermata(&["check", "--strict", path.to_str().unwrap()]);
+    fs::remove_file(&path).unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("overfull measure in part P1, measure 1"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_check_overfull_measure_warns_without_strict() {
+    let path = write_source("overfull-lenient.fm", OVERFULL);
+    let output = fermata(&["check", path.to_str().unwrap()]);
+    fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Warning"));
+}
+
+#[test]
+fn test_convert_detects_abc_on_stdin() {
+    let output = fermata_stdin(&["convert"], b"X:1\nT:Scale\nM:4/4\nL:1/4\nK:C\nCDEF|\n");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Scale"), "{}", stdout);
+}
+
+#[test]
+fn test_import_falls_back_to_abc_extension() {
+    // Neither an X: nor a T: header, so only the extension says ABC
+    let path = write_source("headerless.abc", "M:4/4\nL:1/4\nK:C\nCDEF|\n");
+    let output = fermata(&["import", path.to_str().unwrap()]);
+    fs::remove_file(&path).unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("(score"));
+}
+
+#[test]
+fn test_import_rejects_fermata_input() {
+    let output = fermata_stdin(&["import"], b"(score (part :piano (measure (note c4 :w))))");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already Fermata"));
+}
+
+#[test]
+fn test_import_from_overrides_detection() {
+    let output = fermata_stdin(&["import", "--from", "xml"], b"X:1\nK:C\nC|\n");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("MusicXML parse error"));
+}
+
+#[test]
+fn test_import_check_only_lists_skipped_harmony() {
+    let path = write_source(
+        "harmony.musicxml",
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<score-partwise version="4.0">
+  <part-list><score-part id="P1"><part-name>Piano</part-name></score-part></part-list>
+  <part id="P1">
+    <measure number="1">
+      <harmony><root><root-step>C</root-step></root><kind>major</kind></harmony>
+      <note><pitch><step>C</step><octave>4</octave></pitch><duration>4</duration><type>whole</type></note>
+    </measure>
+  </part>
+</score-partwise>
+"#,
+    );
+
+    let output = fermata(&["import", path.to_str().unwrap(), "--check-only"]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("OK"), "{}", stdout);
+    assert!(stdout.contains("  harmony (1)"), "{}", stdout);
+    assert!(!stdout.contains("(score"), "{}", stdout);
+}
+
+#[test]
+fn test_import_compile_import_keeps_instruments() {
+    let xml = write_source(
+        "instruments.musicxml",
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<score-partwise version="4.0">
+  <part-list>
+    <score-part id="P1">
+      <part-name>Piano</part-name>
+      <score-instrument id="P1-I1"><instrument-name>Piano</instrument-name></score-instrument>
+      <midi-instrument id="P1-I1"><midi-channel>1</midi-channel><midi-program>1</midi-program><volume>80</volume></midi-instrument>
+    </score-part>
+  </part-list>
+  <part id="P1">
+    <measure number="1">
+      <note><pitch><step>C</step><octave>4</octave></pitch><duration>4</duration><instrument id="P1-I1"/><type>whole</type></note>
+    </measure>
+  </part>
+</score-partwise>
+"#,
+    );
+    let fm = xml.with_extension("fm");
+    let compiled = xml.with_extension("out.musicxml");
+
+    let imported = fermata(&["import", xml.to_str().unwrap(), "-o", fm.to_str().unwrap()]);
+    let built = fermata(&[
+        "compile",
+        fm.to_str().unwrap(),
+        "-o",
+        compiled.to_str().unwrap(),
+    ]);
+    let reimported = fermata(&["import", compiled.to_str().unwrap()]);
+    let written = fs::read_to_string(&compiled).unwrap_or_default();
+    for path in [&xml, &fm, &compiled] {
+        let _ = fs::remove_file(path);
+    }
+
+    assert!(imported.status.success() && built.status.success());
+    assert!(
+        reimported.status.success(),
+        "{}",
+        String::from_utf8_lossy(&reimported.stderr)
+    );
+    assert!(
+        written.contains(r#"<score-instrument id="P1-I1">"#),
+        "{}",
+        written
+    );
+    assert!(
+        written.contains("<midi-program>1</midi-program>"),
+        "{}",
+        written
+    );
+}
+
+#[test]
+fn test_version_lists_musicxml_version_and_targets() {
+    let output = fermata(&["version"]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("MusicXML:  4.0"), "{}", stdout);
+    assert!(stdout.contains("musicxml"), "{}", stdout);
+
+    let output = fermata(&["version", "--format", "json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["musicxml_version"], "4.0");
+    assert!(
+        json["targets"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|target| target["name"] == "musicxml" && target["implemented"] == true)
+    );
+}
+
+#[test]
+fn test_show_json_uses_common_envelope() {
+    for topic in ["durations", "pitches"] {
+        let output = fermata(&["show", topic, "--format", "json"]);
+        assert!(output.status.success());
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["topic"], topic);
+        let entries = json["entries"].as_array().unwrap();
+        assert!(!entries.is_empty());
+        for entry in entries {
+            for field in ["symbol", "name", "description"] {
+                assert!(
+                    entry[field].is_string(),
+                    "{} entry missing {}",
+                    topic,
+                    field
+                );
+            }
+        }
+    }
+
+    let output = fermata(&["show", "durations", "--format", "json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let quarter = json["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["symbol"] == ":q")
+        .unwrap();
+    assert_eq!(quarter["name"], "Quarter note");
+}
+
+#[test]
+fn test_compile_part_keeps_only_selected_part() {
+    let source = r#"(score (part :name "Violin" (measure (note g4 :w)))
+                           (part :name "Cello" (measure (note c3 :w))))"#;
+    let path = write_source("select-part.fm", source);
+    let output = fermata(&["compile", path.to_str().unwrap(), "--part", "Cello"]);
+    let missing = fermata(&["compile", path.to_str().unwrap(), "--part", "Flute"]);
+    fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    let xml = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(xml.matches("<score-part ").count(), 1);
+    assert!(xml.contains("<part-name>Cello</part-name>"));
+    assert_eq!(xml.matches("<part id=").count(), 1);
+
+    assert!(!missing.status.success());
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("Violin (P1), Cello (P2)"));
+}
+
+#[test]
+fn test_compile_transposing_parts_writes_clarinet_a_second_higher() {
+    let source = r#"(score (part :name "Clarinet in Bb" :transpose -2 (measure (note c4 :w))))"#;
+    let path = write_source("transposing-parts.fm", source);
+    let concert = fermata(&["compile", path.to_str().unwrap()]);
+    let written = fermata(&["compile", path.to_str().unwrap(), "--transposing-parts"]);
+    fs::remove_file(&path).unwrap();
+
+    assert!(concert.status.success() && written.status.success());
+    let concert = String::from_utf8_lossy(&concert.stdout);
+    assert!(concert.contains("<step>C</step>"), "{}", concert);
+    assert!(concert.contains("<concert-score/>"), "{}", concert);
+    let written = String::from_utf8_lossy(&written.stdout);
+    assert!(written.contains("<step>D</step>"), "{}", written);
+    assert!(!written.contains("<concert-score/>"), "{}", written);
+    assert!(written.contains("<chromatic>-2</chromatic>"), "{}", written);
+}
+
+#[test]
+fn test_compile_auto_barline_splits_overfull_measure() {
+    let source = "(score (part :piano (measure (time 3 4) (note c4 :h) (note d4 :h))))";
+    let path = write_source("auto-barline.fm", source);
+    let plain = fermata(&["compile", path.to_str().unwrap()]);
+    let barred = fermata(&["compile", path.to_str().unwrap(), "--auto-barline"]);
+    fs::remove_file(&path).unwrap();
+
+    assert!(plain.status.success() && barred.status.success());
+    let plain = String::from_utf8_lossy(&plain.stdout);
+    assert_eq!(plain.matches("<measure ").count(), 1, "{}", plain);
+    let barred = String::from_utf8_lossy(&barred.stdout);
+    assert_eq!(barred.matches("<measure ").count(), 2, "{}", barred);
+    assert!(barred.contains(r#"<tie type="start"/>"#), "{}", barred);
+    assert!(barred.contains(r#"<tie type="stop"/>"#), "{}", barred);
+}
+
+#[test]
+fn test_fmt_rewrites_messy_file_and_is_idempotent() {
+    let messy = "; a tune\n(score   (part :piano\n\n  ; first bar\n (measure (note c4 :q)   (note d4 :q))))";
+    let path = write_source("fmt-messy.fm", messy);
+    let check_before = fermata(&["fmt", "--check", path.to_str().unwrap()]);
+    let first = fermata(&["fmt", path.to_str().unwrap()]);
+    let once = fs::read_to_string(&path).unwrap();
+    let second = fermata(&["fmt", path.to_str().unwrap()]);
+    let twice = fs::read_to_string(&path).unwrap();
+    let check_after = fermata(&["fmt", "--check", path.to_str().unwrap()]);
+    let printed = fermata(&["fmt", "--stdout", path.to_str().unwrap()]);
+    fs::remove_file(&path).unwrap();
+
+    assert!(!check_before.status.success());
+    assert!(first.status.success() && second.status.success());
+    assert_ne!(once, messy);
+    assert!(
+        once.contains("; a tune") && once.contains("; first bar"),
+        "{}",
+        once
+    );
+    assert_eq!(once, twice);
+    assert!(check_after.status.success());
+    assert_eq!(String::from_utf8_lossy(&printed.stdout), once);
+}
+
+#[test]
+fn test_compile_output_dir_mirrors_source_tree() {
+    let root = std::env::temp_dir().join(format!("fermata-cli-{}-batch", std::process::id()));
+    let (src, build) = (root.join("src"), root.join("build"));
+    fs::create_dir_all(src.join("act1")).unwrap();
+    fs::write(
+        src.join("prelude.fm"),
+        "(score (part :piano (measure (note c4 :w))))",
+    )
+    .unwrap();
+    fs::write(
+        src.join("act1").join("aria.fm"),
+        "(score (part :voice (measure (note g4 :w))))",
+    )
+    .unwrap();
+    fs::write(src.join("notes.txt"), "not a score").unwrap();
+
+    let output = fermata(&[
+        "compile",
+        src.to_str().unwrap(),
+        "--output-dir",
+        build.to_str().unwrap(),
+        "-t",
+        "xml",
+    ]);
+    let prelude = fs::read_to_string(build.join("prelude.musicxml"));
+    let aria = fs::read_to_string(build.join("act1").join("aria.musicxml"));
+    let stray = build.join("notes.musicxml").exists();
+
+    // A broken file is reported and fails the batch without stopping it
+    fs::write(
+        src.join("broken.fm"),
+        "(score (part :piano (measure (note c4 :q",
+    )
+    .unwrap();
+    let broken = fermata(&[
+        "compile",
+        src.to_str().unwrap(),
+        "--output-dir",
+        build.to_str().unwrap(),
+    ]);
+    fs::remove_dir_all(&root).unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    assert!(prelude.unwrap().contains("<step>C</step>"));
+    assert!(aria.unwrap().contains("<step>G</step>"));
+    assert!(!stray);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout)
+            .matches("OK")
+            .count(),
+        2
+    );
+
+    assert!(!broken.status.success());
+    let stderr = String::from_utf8_lossy(&broken.stderr);
+    assert!(stderr.contains("broken.fm"), "{}", stderr);
+    assert!(stderr.contains("1 of 3 file(s) failed"), "{}", stderr);
+}
+
+#[test]
+fn test_analyze_json_reports_notes_and_range() {
+    let path = write_source(
+        "analyze.fm",
+        "(score (part :piano (measure (note c4 :h) (note g5 :q) (rest :q))))",
+    );
+    let output = fermata(&["analyze", path.to_str().unwrap(), "--format", "json"]);
+    fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["notes"], 2);
+    assert_eq!(json["lowest"], "C4");
+    assert_eq!(json["highest"], "G5");
+    assert_eq!(json["parts"][0]["measures"], 1);
+}
+
+#[test]
+fn test_show_pitches_german_notation() {
+    let output = fermata(&["show", "pitches", "--notation", "german"]);
+    assert!(output.status.success());
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let name_of = |spelling: &str| {
+        text.lines()
+            .map(str::split_whitespace)
+            .find_map(|mut words| (words.next() == Some(spelling)).then(|| words.next()))
+            .flatten()
+            .map(str::to_string)
+    };
+    assert_eq!(name_of("b").as_deref(), Some("H"));
+    assert_eq!(name_of("bb").as_deref(), Some("B"));
+    assert_eq!(name_of("a#").as_deref(), Some("Ais"));
+}
+
+#[test]
+fn test_compile_output_replaces_file_without_leftovers() {
+    let source = write_source("replace.fm", "(score (part :piano (measure (note c4 :w))))");
+    let dir = std::env::temp_dir().join(format!("fermata-cli-{}-out", std::process::id()));
+    let output_path = dir.join("replace.musicxml");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(&output_path, "stale").unwrap();
+
+    let output = fermata(&[
+        "compile",
+        source.to_str().unwrap(),
+        "-o",
+        output_path.to_str().unwrap(),
+    ]);
+    let written = fs::read_to_string(&output_path).unwrap();
+    let entries: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    fs::remove_file(&source).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    assert!(written.contains("<score-partwise"), "{}", written);
+    // The temporary file was renamed into place
+    assert_eq!(entries, ["replace.musicxml"]);
+}